@@ -257,6 +257,7 @@ static E2E_LITERAL_REDACTIONS: &[(&str, &str)] = &[
     ("[UPLOADING]", "   Uploading"),
     ("[UPLOADED]", "    Uploaded"),
     ("[VERIFYING]", "   Verifying"),
+    ("[VERIFIED]", "    Verified"),
     ("[ARCHIVING]", "   Archiving"),
     ("[INSTALLING]", "  Installing"),
     ("[REPLACING]", "   Replacing"),
@@ -279,6 +280,7 @@ static E2E_LITERAL_REDACTIONS: &[(&str, &str)] = &[
     ("[PUBLISHED]", "   Published"),
     ("[BLOCKING]", "    Blocking"),
     ("[GENERATED]", "   Generated"),
+    ("[RESTORED]", "    Restored"),
 ];
 
 /// Normalizes the output so that it can be compared against the expected value.
@@ -106,6 +106,10 @@ pub struct RegistryBuilder {
     delayed_index_update: usize,
     /// Credential provider in configuration
     credential_provider: Option<String>,
+    /// Capabilities listed in config.json
+    capabilities: Vec<String>,
+    /// If set, config.json includes 'device-authorization'
+    device_authorization: bool,
 }
 
 pub struct TestRegistry {
@@ -175,6 +179,8 @@ impl RegistryBuilder {
             not_found_handler: Box::new(not_found),
             delayed_index_update: 0,
             credential_provider: None,
+            capabilities: Vec::new(),
+            device_authorization: false,
         }
     }
 
@@ -248,6 +254,21 @@ impl RegistryBuilder {
         self
     }
 
+    /// Lists the given capabilities in `config.json`.
+    #[must_use]
+    pub fn capabilities(mut self, capabilities: &[&str]) -> Self {
+        self.capabilities = capabilities.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Advertises the OAuth 2.0 device authorization flow in `config.json`,
+    /// with the `/oauth/device` and `/oauth/token` endpoints of the API.
+    #[must_use]
+    pub fn device_authorization(mut self) -> Self {
+        self.device_authorization = true;
+        self
+    }
+
     /// Operate the index over http
     #[must_use]
     pub fn http_index(mut self) -> Self {
@@ -446,11 +467,31 @@ impl RegistryBuilder {
         } else {
             String::new()
         };
+        let capabilities = if self.capabilities.is_empty() {
+            String::new()
+        } else {
+            format!(
+                r#","capabilities":{}"#,
+                serde_json::to_string(&self.capabilities).unwrap()
+            )
+        };
+        let device_authorization = if self.device_authorization {
+            format!(
+                r#","device-authorization":{{"url":"{}","token-url":"{}"}}"#,
+                registry.api_url.join("oauth/device").unwrap(),
+                registry.api_url.join("oauth/token").unwrap()
+            )
+        } else {
+            String::new()
+        };
         // Initialize a new registry.
         repo(&registry.path)
             .file(
                 "config.json",
-                &format!(r#"{{"dl":"{}"{api}{auth}}}"#, registry.dl_url),
+                &format!(
+                    r#"{{"dl":"{}"{api}{auth}{capabilities}{device_authorization}}}"#,
+                    registry.dl_url
+                ),
             )
             .build();
         fs::create_dir_all(api_path.join("api/v1/crates")).unwrap();
@@ -538,6 +579,8 @@ pub struct Package {
     proc_macro: bool,
     links: Option<String>,
     rust_version: Option<String>,
+    pubtime: Option<String>,
+    signing_key: Option<String>,
     cargo_features: Vec<String>,
     v: Option<u32>,
 }
@@ -637,6 +680,8 @@ pub struct Request {
     pub authorization: Option<String>,
     pub if_modified_since: Option<String>,
     pub if_none_match: Option<String>,
+    pub a_im: Option<String>,
+    pub range: Option<String>,
 }
 
 impl fmt::Debug for Request {
@@ -648,6 +693,8 @@ impl fmt::Debug for Request {
             .field("authorization", &self.authorization)
             .field("if_modified_since", &self.if_modified_since)
             .field("if_none_match", &self.if_none_match)
+            .field("a_im", &self.a_im)
+            .field("range", &self.range)
             .finish()
     }
 }
@@ -738,6 +785,8 @@ impl HttpServer {
             // Grab headers we care about.
             let mut if_modified_since = None;
             let mut if_none_match = None;
+            let mut a_im = None;
+            let mut range = None;
             let mut authorization = None;
             let mut content_len = None;
             loop {
@@ -756,6 +805,8 @@ impl HttpServer {
                 match name.as_str() {
                     "if-modified-since" => if_modified_since = Some(value),
                     "if-none-match" => if_none_match = Some(value),
+                    "a-im" => a_im = Some(value),
+                    "range" => range = Some(value),
                     "authorization" => authorization = Some(value),
                     "content-length" => content_len = Some(value),
                     _ => {}
@@ -774,6 +825,8 @@ impl HttpServer {
                 authorization,
                 if_modified_since,
                 if_none_match,
+                a_im,
+                range,
                 method,
                 url,
                 body,
@@ -782,7 +835,15 @@ impl HttpServer {
             let response = self.route(&req);
             let buf = buf.get_mut();
             write!(buf, "HTTP/1.1 {}\r\n", response.code).unwrap();
-            write!(buf, "Content-Length: {}\r\n", response.body.len()).unwrap();
+            // Responses may claim a different length to simulate a transfer
+            // that breaks off.
+            if !response
+                .headers
+                .iter()
+                .any(|h| h.to_ascii_lowercase().starts_with("content-length:"))
+            {
+                write!(buf, "Content-Length: {}\r\n", response.body.len()).unwrap();
+            }
             write!(buf, "Connection: close\r\n").unwrap();
             for header in response.headers {
                 write!(buf, "{}\r\n", header).unwrap();
@@ -1022,8 +1083,33 @@ impl HttpServer {
         if !file.exists() {
             return self.not_found(req);
         }
+        let body = fs::read(&file).unwrap();
+        // Only the `bytes=<start>-` form is used to resume downloads.
+        if let Some(range) = &req.range {
+            let start = range
+                .strip_prefix("bytes=")
+                .and_then(|r| r.strip_suffix('-'))
+                .and_then(|r| r.parse::<usize>().ok())
+                .unwrap_or_else(|| panic!("unsupported range `{range}`"));
+            if start >= body.len() {
+                return Response {
+                    body: vec![],
+                    code: 416,
+                    headers: vec![format!("Content-Range: bytes */{}", body.len())],
+                };
+            }
+            return Response {
+                body: body[start..].to_vec(),
+                code: 206,
+                headers: vec![format!(
+                    "Content-Range: bytes {start}-{}/{}",
+                    body.len() - 1,
+                    body.len()
+                )],
+            };
+        }
         return Response {
-            body: fs::read(&file).unwrap(),
+            body,
             code: 200,
             headers: vec![],
         };
@@ -1186,6 +1272,7 @@ fn save_new_crate(
         new_crate.links,
         new_crate.rust_version.as_deref(),
         None,
+        None,
     );
 
     write_to_index(registry_path, &new_crate.name, line, false);
@@ -1212,6 +1299,8 @@ impl Package {
             proc_macro: false,
             links: None,
             rust_version: None,
+            pubtime: None,
+            signing_key: None,
             cargo_features: Vec::new(),
             v: None,
         }
@@ -1361,6 +1450,18 @@ impl Package {
         self
     }
 
+    /// Sets the publish time reported in the index, as an RFC 3339 timestamp.
+    pub fn pubtime(&mut self, pubtime: &str) -> &mut Package {
+        self.pubtime = Some(pubtime.into());
+        self
+    }
+
+    /// Signs the entry in the index with the given PASERK `k3.secret` key.
+    pub fn signing_key(&mut self, key: &str) -> &mut Package {
+        self.signing_key = Some(key.into());
+        self
+    }
+
     /// Causes the JSON line emitted in the index to be invalid, presumably
     /// causing Cargo to skip over this version.
     pub fn invalid_json(&mut self, invalid: bool) -> &mut Package {
@@ -1442,7 +1543,7 @@ impl Package {
         } else {
             serde_json::json!(self.name)
         };
-        let line = create_index_line(
+        let mut line = create_index_line(
             name,
             &self.vers,
             deps,
@@ -1451,8 +1552,26 @@ impl Package {
             self.yanked,
             self.links.clone(),
             self.rust_version.as_deref(),
+            self.pubtime.as_deref(),
             self.v,
         );
+        if let Some(key) = &self.signing_key {
+            // The signature covers the whole entry, except for `yanked`.
+            let secret: AsymmetricSecretKey<pasetors::version3::V3> =
+                key.as_str().try_into().unwrap();
+            let mut entry: serde_json::Value = serde_json::from_str(&line).unwrap();
+            let mut payload = entry.clone();
+            payload.as_object_mut().unwrap().remove("yanked");
+            let sig = pasetors::version3::PublicToken::sign(
+                &secret,
+                payload.to_string().as_bytes(),
+                None,
+                None,
+            )
+            .unwrap();
+            entry["sig"] = serde_json::json!(sig);
+            line = entry.to_string();
+        }
 
         let registry_path = if self.alternative {
             alt_registry_path()
@@ -0,0 +1,57 @@
+//! Schema of the `artifacts.json` file written by `cargo build --artifact-dir`
+//!
+//! After copying the final artifacts of a build to the artifact directory,
+//! Cargo writes an [`ArtifactManifest`] to [`ARTIFACT_MANIFEST_FILE`] in that
+//! directory, so that packaging pipelines can find each file without guessing
+//! the platform-specific file names.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::PackageIdSpec;
+
+/// Name of the file the [`ArtifactManifest`] is written to, in the artifact
+/// directory.
+pub const ARTIFACT_MANIFEST_FILE: &str = "artifacts.json";
+
+/// The value of [`ArtifactManifest::version`] emitted by this version of
+/// Cargo.
+///
+/// This is bumped on any incompatible change to the schema; new fields may be
+/// added without bumping it.
+pub const ARTIFACT_MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    /// Version of the schema, see [`ARTIFACT_MANIFEST_VERSION`].
+    pub version: u32,
+    /// Every target with files copied to the artifact directory by the last
+    /// build, in no particular order.
+    pub artifacts: Vec<Artifact>,
+}
+
+/// The files of a single target, like a binary, an example or a `cdylib`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub package_id: PackageIdSpec,
+    pub target_name: String,
+    /// Kinds of the target, like in the build plan.
+    pub target_kind: Vec<String>,
+    /// Target triple the target was built for, or `None` for the host.
+    pub kind: Option<String>,
+    /// The outputs of the target, like an executable or a shared library and
+    /// its import library.
+    pub files: Vec<ArtifactFile>,
+    /// Separate debug info, like `.pdb` files or `.dSYM` directories.
+    pub debuginfo: Vec<ArtifactFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactFile {
+    /// Path of the file, relative to the artifact directory.
+    pub path: PathBuf,
+    /// SHA-256 checksum of the content of the file, as lowercase hex, or
+    /// `None` for directories.
+    pub sha256: Option<String>,
+}
@@ -0,0 +1,29 @@
+//! Schema of the `hashes` field of `compiler-artifact` JSON messages
+//!
+//! With `-Z artifact-hashes`, every `compiler-artifact` message emitted by
+//! `--message-format=json` carries [`ArtifactHashes`], so that tools caching
+//! or deploying the artifacts can check them without hashing them again.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactHashes {
+    /// Hash of the fingerprint of the unit, as written in the `.fingerprint`
+    /// directory of the target directory. It covers what the unit is built
+    /// with, like the compiler, profile, features and dependencies, but not
+    /// the content of its source files, and is only meaningful to the same
+    /// version of Cargo.
+    pub fingerprint: String,
+    /// The files of the `filenames` of the message, in the same order.
+    pub files: Vec<FileHash>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHash {
+    pub path: PathBuf,
+    /// SHA-256 checksum of the content of the file, as lowercase hex, or
+    /// `None` for directories.
+    pub sha256: Option<String>,
+}
@@ -0,0 +1,101 @@
+//! Schema of the `cargo build --build-plan` output
+//!
+//! The build plan lists every process Cargo would run for a build, in an
+//! order where each invocation comes after the invocations it depends on, so
+//! that an external executor can run the build without Cargo.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::PackageIdSpec;
+
+/// The value of [`BuildPlan::version`] emitted by this version of Cargo.
+///
+/// This is bumped on any incompatible change to the schema; new fields may be
+/// added without bumping it.
+pub const BUILD_PLAN_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildPlan {
+    /// Version of the schema, see [`BUILD_PLAN_VERSION`].
+    pub version: u32,
+    /// Every process to run, dependencies first.
+    pub invocations: Vec<Invocation>,
+    /// Files whose changes invalidate the whole plan: the manifests of all
+    /// packages involved, the lockfile, the configuration files, and the
+    /// files the reused build script outputs depend on.
+    pub inputs: Vec<PathBuf>,
+}
+
+/// A single process, either a `rustc` or `rustdoc` invocation, or the run of
+/// a build script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Invocation {
+    pub package_id: PackageIdSpec,
+    pub package_name: String,
+    pub package_version: semver::Version,
+    pub target_name: String,
+    /// Kinds of the target, like `["lib"]`, `["bin"]` or `["custom-build"]`,
+    /// with library targets listing their crate types instead.
+    pub target_kind: Vec<String>,
+    /// Root source file of the target, or `None` for targets generated by
+    /// Cargo, like the build script of a `metabuild` package.
+    pub target_src_path: Option<PathBuf>,
+    /// Target triple the invocation compiles for, or `None` for the host.
+    pub kind: Option<String>,
+    /// What the invocation does, like `"build"`, `"check"`, `"test"`,
+    /// `"doc"` or `"run-custom-build"`.
+    pub compile_mode: String,
+    /// Indices into [`BuildPlan::invocations`] of the invocations that must
+    /// finish before this one starts.
+    pub deps: Vec<usize>,
+    /// Files produced by the invocation.
+    pub outputs: Vec<PathBuf>,
+    /// Links to create after the invocation finishes, from the link path to
+    /// one of [`Invocation::outputs`].
+    pub links: BTreeMap<PathBuf, PathBuf>,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Environment variables to set, in addition to the environment Cargo
+    /// itself was run with.
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<PathBuf>,
+    /// For a `run-custom-build` invocation, what the build script printed
+    /// when it last ran, if that run is still up to date. Cargo already
+    /// applied it to the invocations depending on the build script; when it
+    /// is missing, the executor has to run the build script and apply its
+    /// output itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_script_output: Option<BuildScriptOutput>,
+}
+
+/// The `cargo::rustc-*` and `cargo::metadata` instructions of a build script.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildScriptOutput {
+    /// From `cargo::rustc-link-search`, passed to rustc with `-L`.
+    pub library_paths: Vec<PathBuf>,
+    /// From `cargo::rustc-link-lib`, passed to rustc with `-l`.
+    pub library_links: Vec<String>,
+    /// From `cargo::rustc-link-arg` and its variants.
+    pub linker_args: Vec<LinkArg>,
+    /// From `cargo::rustc-cfg`, passed to rustc with `--cfg`.
+    pub cfgs: Vec<String>,
+    /// From `cargo::rustc-check-cfg`, passed to rustc with `--check-cfg`.
+    pub check_cfgs: Vec<String>,
+    /// From `cargo::rustc-env`, set when running rustc.
+    pub env: BTreeMap<String, String>,
+    /// From `cargo::metadata`, set as `DEP_<links>_<key>` for the build
+    /// scripts of the packages depending on this one.
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// A linker argument from a build script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkArg {
+    /// The targets it applies to: `"all"`, `"cdylib"`, `"bins"`,
+    /// `"bin=<name>"`, `"tests"`, `"benches"` or `"examples"`.
+    pub targets: String,
+    pub arg: String,
+}
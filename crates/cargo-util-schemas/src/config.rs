@@ -0,0 +1,258 @@
+//! Schema of Cargo's `config.toml` files
+//!
+//! This only describes which keys exist, so that the ones Cargo doesn't know
+//! about can be reported. The values themselves are deserialized by Cargo
+//! when they are used.
+
+/// The keys a part of a config file may have.
+#[derive(Debug)]
+pub enum ConfigSchema {
+    /// A table with a fixed set of keys.
+    Table(&'static [(&'static str, &'static ConfigSchema)]),
+    /// A table whose keys are chosen by the user, like the names of
+    /// registries, with the same schema for every value.
+    Map(&'static ConfigSchema),
+    /// A value whose contents aren't checked, like a string, an array, or a
+    /// table with arbitrary keys.
+    Any,
+}
+
+impl ConfigSchema {
+    /// Returns the schema of `key` inside this one, or `None` if this is a
+    /// [`ConfigSchema::Table`] without that key.
+    pub fn get(&self, key: &str) -> Option<&'static ConfigSchema> {
+        match self {
+            ConfigSchema::Table(keys) => keys.iter().find(|(k, _)| *k == key).map(|(_, s)| *s),
+            ConfigSchema::Map(value) => Some(value),
+            ConfigSchema::Any => Some(&ConfigSchema::Any),
+        }
+    }
+
+    /// The keys of a [`ConfigSchema::Table`], empty for other schemas.
+    pub fn keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+        let keys: &[_] = match self {
+            ConfigSchema::Table(keys) => keys,
+            _ => &[],
+        };
+        keys.iter().map(|(k, _)| *k)
+    }
+}
+
+const ANY: &ConfigSchema = &ConfigSchema::Any;
+
+/// The schema of a whole config file.
+pub static CONFIG: ConfigSchema = ConfigSchema::Table(&[
+    ("advisories", &ADVISORIES),
+    ("alias", &ConfigSchema::Map(ANY)),
+    ("build", &BUILD),
+    ("cargo-new", &CARGO_NEW),
+    ("credential-alias", &ConfigSchema::Map(ANY)),
+    ("doc", &DOC),
+    ("env", &ConfigSchema::Map(ANY)),
+    ("future-incompat-report", &FUTURE_INCOMPAT_REPORT),
+    ("gc", &GC),
+    ("host", ANY),
+    ("http", &HTTP),
+    ("include", ANY),
+    ("install", &INSTALL),
+    ("license-report", &LICENSE_REPORT),
+    ("net", &NET),
+    ("patch", &ConfigSchema::Map(&ConfigSchema::Map(ANY))),
+    ("paths", ANY),
+    ("profile", &ConfigSchema::Map(&PROFILE)),
+    ("publish", &PUBLISH),
+    ("registries", &ConfigSchema::Map(&REGISTRIES)),
+    ("registry", &REGISTRY),
+    ("registry-remap", &ConfigSchema::Map(ANY)),
+    ("resolver", &RESOLVER),
+    ("runners", &ConfigSchema::Map(ANY)),
+    ("source", &ConfigSchema::Map(&SOURCE)),
+    ("source-policy", &SOURCE_POLICY),
+    // Besides the known keys, targets have a table for each overridden
+    // `links` library.
+    ("target", &ConfigSchema::Map(ANY)),
+    ("target-alias", &ConfigSchema::Map(&TARGET_ALIAS)),
+    ("target-applies-to-host", ANY),
+    ("term", &TERM),
+    ("unstable", ANY),
+]);
+
+static ADVISORIES: ConfigSchema =
+    ConfigSchema::Table(&[("database", ANY), ("ignore", ANY), ("policy", ANY)]);
+
+static BUILD: ConfigSchema = ConfigSchema::Table(&[
+    ("artifact-dir", ANY),
+    ("dep-info-basedir", ANY),
+    ("incremental", ANY),
+    ("jobs", ANY),
+    ("out-dir", ANY),
+    ("pipelining", ANY),
+    ("rustc", ANY),
+    ("rustc-wrapper", ANY),
+    ("rustc-workspace-wrapper", ANY),
+    ("rustdoc", ANY),
+    ("rustdocflags", ANY),
+    ("rustflags", ANY),
+    ("sandbox", ANY),
+    ("sandbox-trust", ANY),
+    ("target", ANY),
+    ("target-dir", ANY),
+]);
+
+static CARGO_NEW: ConfigSchema =
+    ConfigSchema::Table(&[("email", ANY), ("name", ANY), ("vcs", ANY)]);
+
+static DOC: ConfigSchema = ConfigSchema::Table(&[
+    ("browser", ANY),
+    (
+        "extern-map",
+        &ConfigSchema::Table(&[("registries", &ConfigSchema::Map(ANY)), ("std", ANY)]),
+    ),
+]);
+
+static FUTURE_INCOMPAT_REPORT: ConfigSchema = ConfigSchema::Table(&[("frequency", ANY)]);
+
+static GC: ConfigSchema = ConfigSchema::Table(&[(
+    "auto",
+    &ConfigSchema::Table(&[
+        ("frequency", ANY),
+        ("max-crate-age", ANY),
+        ("max-crate-size", ANY),
+        ("max-download-size", ANY),
+        ("max-git-co-age", ANY),
+        ("max-git-db-age", ANY),
+        ("max-git-size", ANY),
+        ("max-index-age", ANY),
+        ("max-src-age", ANY),
+        ("max-src-size", ANY),
+    ]),
+)]);
+
+static HTTP: ConfigSchema = ConfigSchema::Table(&[
+    ("cainfo", ANY),
+    ("check-revoke", ANY),
+    ("debug", ANY),
+    ("low-speed-limit", ANY),
+    ("multiplexing", ANY),
+    ("no-proxy", ANY),
+    ("proxy", ANY),
+    (
+        "ssl-version",
+        &ConfigSchema::Table(&[("max", ANY), ("min", ANY)]),
+    ),
+    ("timeout", ANY),
+    ("user-agent", ANY),
+]);
+
+static INSTALL: ConfigSchema = ConfigSchema::Table(&[
+    ("float", ANY),
+    ("locked", ANY),
+    ("prebuilt", ANY),
+    ("root", ANY),
+    ("target-cache", ANY),
+]);
+
+static LICENSE_REPORT: ConfigSchema = ConfigSchema::Table(&[("deny", ANY)]);
+
+static NET: ConfigSchema = ConfigSchema::Table(&[
+    ("fallback-to-cache", ANY),
+    ("git-fetch-jobs", ANY),
+    ("git-fetch-with-cli", ANY),
+    ("offline", ANY),
+    ("retry", ANY),
+    ("ssh", &ConfigSchema::Table(&[("known-hosts", ANY)])),
+]);
+
+static PROFILE: ConfigSchema = ConfigSchema::Table(&[
+    ("build-override", &PROFILE),
+    ("codegen-backend", ANY),
+    ("codegen-units", ANY),
+    ("debug", ANY),
+    ("debug-assertions", ANY),
+    ("dir-name", ANY),
+    ("incremental", ANY),
+    ("inherits", ANY),
+    ("lto", ANY),
+    ("opt-level", ANY),
+    ("overflow-checks", ANY),
+    ("package", &ConfigSchema::Map(&PROFILE)),
+    ("panic", ANY),
+    ("rpath", ANY),
+    ("rustflags", ANY),
+    ("split-debuginfo", ANY),
+    ("strip", ANY),
+    ("trim-paths", ANY),
+]);
+
+static PUBLISH: ConfigSchema = ConfigSchema::Table(&[
+    ("poll-interval", ANY),
+    ("rate-limit-timeout", ANY),
+    ("timeout", ANY),
+    ("verify-download", ANY),
+]);
+
+static REGISTRIES: ConfigSchema = ConfigSchema::Table(&[
+    ("credential-provider", ANY),
+    ("index", ANY),
+    ("mirrors", ANY),
+    ("protocol", ANY),
+    ("proxy", ANY),
+    ("require-signatures", ANY),
+    ("secret-key", ANY),
+    ("secret-key-subject", ANY),
+    ("token", ANY),
+    ("trusted-keys", ANY),
+]);
+
+static REGISTRY: ConfigSchema = ConfigSchema::Table(&[
+    ("credential-provider", ANY),
+    ("default", ANY),
+    ("global-credential-providers", ANY),
+    ("index", ANY),
+    ("index-snapshot", ANY),
+    ("require-signatures", ANY),
+    ("secret-key", ANY),
+    ("secret-key-subject", ANY),
+    ("token", ANY),
+    ("trusted-keys", ANY),
+]);
+
+static RESOLVER: ConfigSchema = ConfigSchema::Table(&[
+    ("feature-unification", ANY),
+    ("something-like-precedence", ANY),
+    ("yanked", ANY),
+]);
+
+static SOURCE: ConfigSchema = ConfigSchema::Table(&[
+    ("branch", ANY),
+    ("directory", ANY),
+    ("git", ANY),
+    ("local-registry", ANY),
+    ("registry", ANY),
+    ("replace-with", ANY),
+    ("rev", ANY),
+    ("tag", ANY),
+]);
+
+static SOURCE_POLICY: ConfigSchema =
+    ConfigSchema::Table(&[("forbid", ANY), ("package", ANY), ("profiles", ANY)]);
+
+static TARGET_ALIAS: ConfigSchema = ConfigSchema::Table(&[
+    ("env", &ConfigSchema::Map(ANY)),
+    ("linker", ANY),
+    ("runner", ANY),
+    ("rustflags", ANY),
+    ("triple", ANY),
+]);
+
+static TERM: ConfigSchema = ConfigSchema::Table(&[
+    ("color", ANY),
+    ("hyperlinks", ANY),
+    (
+        "progress",
+        &ConfigSchema::Table(&[("when", ANY), ("width", ANY)]),
+    ),
+    ("quiet", ANY),
+    ("unicode", ANY),
+    ("verbose", ANY),
+]);
@@ -0,0 +1,37 @@
+//! Schema of the requests Cargo sends to a `cargo build --executor` program
+//!
+//! For every `rustc` invocation, Cargo runs the executor program and writes a
+//! single [`ExecutorRequest`] to its stdin. The executor runs the command,
+//! possibly somewhere else, and reports back through its own stdout, stderr
+//! and exit status, which Cargo treats as those of the `rustc` invocation.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::PackageIdSpec;
+
+/// The value of [`ExecutorRequest::version`] sent by this version of Cargo.
+///
+/// This is bumped on any incompatible change to the protocol; new fields may
+/// be added without bumping it.
+pub const EXECUTOR_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutorRequest {
+    /// Version of the protocol, see [`EXECUTOR_PROTOCOL_VERSION`].
+    pub version: u32,
+    pub package_id: PackageIdSpec,
+    pub target_name: String,
+    /// Kinds of the target, like in the build plan.
+    pub target_kind: Vec<String>,
+    /// What the invocation does, like in the build plan.
+    pub compile_mode: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Environment variables to set, in addition to the environment of the
+    /// executor itself.
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<PathBuf>,
+}
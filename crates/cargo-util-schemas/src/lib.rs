@@ -5,7 +5,15 @@
 //! Any logic for getting final semantics from these will likely need other tools to process, like
 //! `cargo metadata`.
 
+pub mod artifact_dir;
+pub mod artifact_hashes;
+pub mod build_plan;
+pub mod config;
 pub mod core;
+pub mod executor;
 pub mod manifest;
+pub mod metadata;
+pub mod test_event;
+pub mod test_manifest;
 
 mod restricted_names;
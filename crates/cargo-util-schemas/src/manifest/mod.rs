@@ -5,6 +5,7 @@
 //! - Fields duplicated for an alias will have an accessor with the primary field's name
 //! - Keys that exist for bookkeeping but don't correspond to the schema have a `_` prefix
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::fmt::{self, Display, Write};
@@ -48,8 +49,8 @@ pub struct TomlManifest {
     pub build_dependencies2: Option<BTreeMap<PackageName, InheritableDependency>>,
     pub features: Option<BTreeMap<FeatureName, Vec<String>>>,
     pub target: Option<BTreeMap<String, TomlPlatform>>,
-    pub replace: Option<BTreeMap<String, TomlDependency>>,
-    pub patch: Option<BTreeMap<String, BTreeMap<PackageName, TomlDependency>>>,
+    pub replace: Option<InheritableReplace>,
+    pub patch: Option<InheritablePatch>,
     pub workspace: Option<TomlWorkspace>,
     pub badges: Option<BTreeMap<String, BTreeMap<String, String>>>,
     pub lints: Option<InheritableLints>,
@@ -109,6 +110,16 @@ impl TomlManifest {
     pub fn resolved_lints(&self) -> Result<Option<&TomlLints>, UnresolvedError> {
         self.lints.as_ref().map(|l| l.resolved()).transpose()
     }
+
+    pub fn resolved_lints_with_profile(
+        &self,
+        profile: Option<&str>,
+    ) -> Result<Option<Cow<'_, TomlLints>>, UnresolvedError> {
+        self.lints
+            .as_ref()
+            .map(|l| l.resolved_with_profile(profile))
+            .transpose()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -119,11 +130,13 @@ pub struct TomlWorkspace {
     pub default_members: Option<Vec<String>>,
     pub resolver: Option<String>,
     pub metadata: Option<toml::Value>,
+    pub features: Option<BTreeMap<String, Vec<String>>>,
+    pub split_target_dirs: Option<bool>,
 
     // Properties that can be inherited by members.
     pub package: Option<InheritablePackage>,
     pub dependencies: Option<BTreeMap<PackageName, TomlDependency>>,
-    pub lints: Option<TomlLints>,
+    pub lints: Option<TomlWorkspaceLints>,
 }
 
 /// A group of fields that are inheritable by members of the workspace
@@ -165,6 +178,7 @@ pub struct TomlPackage {
     pub authors: Option<InheritableVecString>,
     pub build: Option<StringOrBool>,
     pub metabuild: Option<StringOrVec>,
+    pub post_build: Option<String>,
     pub default_target: Option<String>,
     pub forced_target: Option<String>,
     pub links: Option<String>,
@@ -191,6 +205,8 @@ pub struct TomlPackage {
     pub repository: Option<InheritableString>,
     pub resolver: Option<String>,
 
+    pub build_sandbox: Option<TomlBuildSandbox>,
+    pub links_libs: Option<BTreeMap<String, TomlLinksLib>>,
     pub metadata: Option<toml::Value>,
 
     /// Provide a helpful error message for a common user error.
@@ -733,6 +749,10 @@ pub struct TomlDetailedDependency<P: Clone = String> {
     pub branch: Option<String>,
     pub tag: Option<String>,
     pub rev: Option<String>,
+    /// Fetch only the commit being checked out of the `git` repository.
+    pub shallow: Option<bool>,
+    /// Check out only these directories of the `git` repository.
+    pub paths: Option<Vec<String>>,
     pub features: Option<Vec<String>>,
     pub optional: Option<bool>,
     pub default_features: Option<bool>,
@@ -772,6 +792,8 @@ impl<P: Clone> Default for TomlDetailedDependency<P> {
             branch: Default::default(),
             tag: Default::default(),
             rev: Default::default(),
+            shallow: Default::default(),
+            paths: Default::default(),
             features: Default::default(),
             optional: Default::default(),
             default_features: Default::default(),
@@ -1377,6 +1399,7 @@ pub struct TomlPlatform {
     pub dev_dependencies: Option<BTreeMap<PackageName, InheritableDependency>>,
     #[serde(rename = "dev_dependencies")]
     pub dev_dependencies2: Option<BTreeMap<PackageName, InheritableDependency>>,
+    pub link_inputs: Option<TomlLinkInputs>,
 }
 
 impl TomlPlatform {
@@ -1393,12 +1416,60 @@ impl TomlPlatform {
     }
 }
 
+/// Corresponds to a `[package.build-sandbox]` table.
+///
+/// Declares what a build script needs beyond the default sandbox when
+/// `build.sandbox` is enabled.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlBuildSandbox {
+    /// Whether the build script may access the network.
+    pub network: Option<bool>,
+    /// Extra paths the build script may read, relative to the package root.
+    pub read: Option<Vec<String>>,
+    /// Extra paths the build script may write, relative to the package root.
+    pub write: Option<Vec<String>>,
+    /// Extra environment variables passed through to the build script.
+    pub env: Option<Vec<String>>,
+}
+
+/// Corresponds to an entry of the `[package.links-libs]` table.
+///
+/// Declares a system library the package links to, which Cargo probes for
+/// before building.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlLinksLib {
+    /// The minimum version of the library.
+    pub version: Option<String>,
+    /// The name of the `pkg-config` module, defaults to the name of the entry.
+    pub pkg_config: Option<String>,
+    /// The name of the `vcpkg` port providing the library.
+    pub vcpkg: Option<String>,
+    /// Packages providing the library, keyed by the package manager installing
+    /// them, for example `apt = "libssl-dev"`.
+    pub install: Option<BTreeMap<String, String>>,
+}
+
+/// Corresponds to a `[target.<platform>.link-inputs]` table.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlLinkInputs {
+    /// Linker scripts, passed to the linker with `-T`.
+    pub linker_scripts: Option<Vec<String>>,
+    /// Object files and archives to link in.
+    pub objects: Option<Vec<String>>,
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct InheritableLints {
     #[serde(skip_serializing_if = "is_false")]
     pub workspace: bool,
     #[serde(flatten)]
     pub lints: TomlLints,
+    /// Named sets of lints overriding `lints`, like `[lints.profile.ci.rust]`.
+    #[serde(rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: TomlLintProfiles,
 }
 
 impl InheritableLints {
@@ -1409,6 +1480,28 @@ impl InheritableLints {
             Ok(&self.lints)
         }
     }
+
+    /// The lints with those of the `profile` merged over them.
+    ///
+    /// A package that doesn't define the profile keeps its default lints, as
+    /// the profile may only be meant for some of the packages being built.
+    pub fn resolved_with_profile(
+        &self,
+        profile: Option<&str>,
+    ) -> Result<Cow<'_, TomlLints>, UnresolvedError> {
+        let lints = self.resolved()?;
+        let Some(profile) = profile.and_then(|name| self.profiles.get(name)) else {
+            return Ok(Cow::Borrowed(lints));
+        };
+        let mut lints = lints.clone();
+        for (tool, tool_lints) in profile {
+            lints
+                .entry(tool.clone())
+                .or_default()
+                .extend(tool_lints.clone());
+        }
+        Ok(Cow::Owned(lints))
+    }
 }
 
 fn is_false(b: &bool) -> bool {
@@ -1439,23 +1532,27 @@ impl<'de> Deserialize<'de> for InheritableLints {
                 M: de::MapAccess<'de>,
             {
                 let mut lints = TomlLints::new();
+                let mut profiles = TomlLintProfiles::new();
                 let mut workspace = false;
 
                 // While there are entries remaining in the input, add them
                 // into our map.
-                while let Some(key) = access.next_key()? {
+                while let Some(key) = access.next_key::<String>()? {
                     if key == "workspace" {
                         workspace = match access.next_value()? {
                             Some(WorkspaceValue) => true,
                             None => false,
                         };
                     } else {
-                        let value = access.next_value()?;
-                        lints.insert(key, value);
+                        next_lints_entry(&mut access, key, &mut lints, &mut profiles)?;
                     }
                 }
 
-                Ok(InheritableLints { workspace, lints })
+                Ok(InheritableLints {
+                    workspace,
+                    lints,
+                    profiles,
+                })
             }
         }
 
@@ -1463,11 +1560,163 @@ impl<'de> Deserialize<'de> for InheritableLints {
     }
 }
 
+/// The `[workspace.lints]` table.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TomlWorkspaceLints {
+    #[serde(flatten)]
+    pub lints: TomlLints,
+    /// Named sets of lints overriding `lints`, like
+    /// `[workspace.lints.profile.ci.rust]`.
+    #[serde(rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: TomlLintProfiles,
+}
+
+impl<'de> Deserialize<'de> for TomlWorkspaceLints {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct TomlWorkspaceLintsVisitor;
+
+        impl<'de> de::Visitor<'de> for TomlWorkspaceLintsVisitor {
+            type Value = TomlWorkspaceLints;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a lints table")
+            }
+
+            fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let mut table = TomlWorkspaceLints::default();
+                while let Some(key) = access.next_key::<String>()? {
+                    next_lints_entry(&mut access, key, &mut table.lints, &mut table.profiles)?;
+                }
+                Ok(table)
+            }
+        }
+
+        deserializer.deserialize_map(TomlWorkspaceLintsVisitor)
+    }
+}
+
+/// Reads the value of the `key` of a lints table, either the lints of a tool
+/// or the `profile` table of named lint sets.
+fn next_lints_entry<'de, M>(
+    access: &mut M,
+    key: String,
+    lints: &mut TomlLints,
+    profiles: &mut TomlLintProfiles,
+) -> Result<(), M::Error>
+where
+    M: de::MapAccess<'de>,
+{
+    if key == "profile" {
+        *profiles = access.next_value()?;
+    } else {
+        lints.insert(key, access.next_value()?);
+    }
+    Ok(())
+}
+
+/// A `[patch]` table, keyed by registry name or source URL.
+pub type InheritablePatch = InheritableTable<BTreeMap<PackageName, TomlDependency>>;
+
+/// A `[replace]` table, keyed by package ID spec.
+pub type InheritableReplace = InheritableTable<TomlDependency>;
+
+/// A table that a workspace member can declare as coming from the workspace
+/// root with `workspace = true` as its only key.
+///
+/// A `workspace` key with any other value is a regular entry, like a
+/// `[patch.workspace]` registry.
+#[derive(Serialize, Debug, Clone)]
+pub struct InheritableTable<T> {
+    #[serde(skip_serializing_if = "is_false")]
+    pub workspace: bool,
+    #[serde(flatten)]
+    pub entries: BTreeMap<String, T>,
+}
+
+impl<T> Default for InheritableTable<T> {
+    fn default() -> Self {
+        InheritableTable {
+            workspace: false,
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'de, T: de::Deserialize<'de>> Deserialize<'de> for InheritableTable<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct InheritableTableVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: de::Deserialize<'de>> de::Visitor<'de> for InheritableTableVisitor<T> {
+            type Value = InheritableTable<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a table")
+            }
+
+            fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                /// The value of a `workspace` key, which is only the marker
+                /// when it is a boolean, and an entry like any other otherwise.
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum WorkspaceKey<T> {
+                    Marker(bool),
+                    Entry(T),
+                }
+
+                let mut table = InheritableTable::default();
+                let mut marker = None;
+                while let Some(key) = access.next_key::<String>()? {
+                    if key == "workspace" {
+                        match access.next_value()? {
+                            WorkspaceKey::Marker(marker_) => marker = Some(marker_),
+                            WorkspaceKey::Entry(value) => {
+                                table.entries.insert(key, value);
+                            }
+                        }
+                    } else {
+                        let value = access.next_value()?;
+                        table.entries.insert(key, value);
+                    }
+                }
+                match marker {
+                    Some(_) if !table.entries.is_empty() => {
+                        return Err(de::Error::custom(
+                            "`workspace = true` must be the only key of the table, \
+                             remove the other entries as the ones of the workspace root apply",
+                        ));
+                    }
+                    Some(false) => return Err(de::Error::custom("`workspace` cannot be false")),
+                    Some(true) => table.workspace = true,
+                    None => {}
+                }
+                Ok(table)
+            }
+        }
+
+        deserializer.deserialize_map(InheritableTableVisitor(std::marker::PhantomData))
+    }
+}
+
 pub type TomlLints = BTreeMap<String, TomlToolLints>;
 
+/// Named sets of lints, selected with `--lints-profile`.
+pub type TomlLintProfiles = BTreeMap<String, TomlLints>;
+
 pub type TomlToolLints = BTreeMap<String, TomlLint>;
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum TomlLint {
     Level(TomlLintLevel),
@@ -1511,7 +1760,7 @@ impl TomlLint {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlLintConfig {
     pub level: TomlLintLevel,
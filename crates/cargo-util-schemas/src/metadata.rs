@@ -0,0 +1,239 @@
+//! Schemas for `cargo metadata` output
+//!
+//! The output is versioned with `--format-version`. Within a format version,
+//! changes are additive: fields may be added, but never removed or changed in
+//! meaning, so tools should ignore fields they don't know about, as `serde`
+//! does by default. Fields only output by later format versions are `Option`s
+//! that are `None` for earlier ones.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::PackageIdSpec;
+use crate::manifest::{RustVersion, TomlLints};
+
+/// The format versions `cargo metadata --format-version` supports.
+pub const FORMAT_VERSIONS: &[u32] = &[1, 2];
+
+/// The output of `cargo metadata`.
+///
+/// Sections left out by `--only` deserialize to their defaults.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default)]
+    pub packages: Vec<Package>,
+    #[serde(default)]
+    pub workspace_members: Vec<PackageIdSpec>,
+    #[serde(default)]
+    pub workspace_default_members: Vec<PackageIdSpec>,
+    /// `None` with `--no-deps` or `--no-feature-resolution`.
+    #[serde(default)]
+    pub resolve: Option<Resolve>,
+    /// Always output by format version 2 when dependencies are resolved, and
+    /// by format version 1 with `--include-feature-graph`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature_graph: Option<FeatureGraph>,
+    #[serde(default)]
+    pub target_directory: PathBuf,
+    pub version: u32,
+    #[serde(default)]
+    pub workspace_root: PathBuf,
+    /// The `[workspace.metadata]` table.
+    #[serde(default)]
+    pub metadata: Option<toml::Value>,
+}
+
+/// A package of the workspace or of its dependency graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: semver::Version,
+    pub id: PackageIdSpec,
+    pub license: Option<String>,
+    pub license_file: Option<String>,
+    pub description: Option<String>,
+    /// The source of the package, `None` for path packages.
+    pub source: Option<String>,
+    pub dependencies: Vec<Dependency>,
+    pub targets: Vec<Target>,
+    pub features: BTreeMap<String, Vec<String>>,
+    pub manifest_path: PathBuf,
+    /// The `[package.metadata]` table.
+    pub metadata: Option<toml::Value>,
+    /// The registries the package may be published to, `None` for all of
+    /// them.
+    pub publish: Option<Vec<String>>,
+    pub authors: Vec<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    pub readme: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub edition: String,
+    pub links: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metabuild: Option<Vec<String>>,
+    pub default_run: Option<String>,
+    pub rust_version: Option<RustVersion>,
+    /// The `[lints]` table, with workspace inheritance applied.
+    ///
+    /// Output by format version 2, where it is empty for packages without
+    /// lints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lints: Option<TomlLints>,
+}
+
+/// A dependency as declared in the manifest of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// The name of the package depended on.
+    pub name: String,
+    pub source: Option<String>,
+    pub req: String,
+    pub kind: Option<DependencyKind>,
+    /// The name the package is renamed to, with `package = "..."`.
+    pub rename: Option<String>,
+    pub optional: bool,
+    pub uses_default_features: bool,
+    pub features: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<ArtifactDependency>,
+    /// The platforms the dependency is for, `None` for all of them.
+    pub target: Option<String>,
+    /// The registry URL this dependency is from, `None` for crates.io.
+    pub registry: Option<String>,
+    /// The file system path for a local path dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// The kind of a dependency, where `None` is a normal dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DependencyKind {
+    #[serde(rename = "dev")]
+    Development,
+    #[serde(rename = "build")]
+    Build,
+}
+
+/// The `artifact` of a `-Z bindeps` dependency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactDependency {
+    pub kinds: Vec<String>,
+    pub lib: bool,
+    pub target: Option<String>,
+}
+
+/// A target of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Target {
+    /// Is this a `--bin bin`, `--lib`, `--example ex`?
+    /// Serialized as a list of strings for historical reasons.
+    pub kind: Vec<String>,
+    /// Corresponds to `--crate-type` compiler attribute.
+    /// See <https://doc.rust-lang.org/reference/linkage.html>
+    pub crate_types: Vec<String>,
+    pub name: String,
+    pub src_path: Option<PathBuf>,
+    pub edition: String,
+    #[serde(
+        rename = "required-features",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub required_features: Option<Vec<String>>,
+    /// Whether docs should be built for the target via `cargo doc`
+    /// See <https://doc.rust-lang.org/cargo/commands/cargo-doc.html#target-selection>
+    pub doc: bool,
+    pub doctest: bool,
+    /// Whether tests should be run for the target (`test` field in `Cargo.toml`)
+    pub test: bool,
+}
+
+/// The `resolve` section, the dependency graph of the workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Resolve {
+    pub nodes: Vec<Node>,
+    /// The package in the current directory, if any.
+    pub root: Option<PackageIdSpec>,
+}
+
+/// A package of the dependency graph, with its resolved dependencies and
+/// features.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Node {
+    pub id: PackageIdSpec,
+    pub dependencies: Vec<PackageIdSpec>,
+    pub deps: Vec<NodeDep>,
+    pub features: Vec<String>,
+    /// The features enabled for each `--filter-platform` target, when
+    /// features are unified separately for each target with
+    /// `-Zper-target-features`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_features: Option<BTreeMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeDep {
+    /// The name of the dependency's library target, as `extern crate`.
+    pub name: String,
+    pub pkg: PackageIdSpec,
+    pub dep_kinds: Vec<DepKindInfo>,
+}
+
+/// One of the ways a package depends on another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepKindInfo {
+    pub kind: Option<DependencyKind>,
+    pub target: Option<String>,
+
+    // vvvvv The fields below are introduced for `-Z bindeps`.
+    /// What the manifest calls the crate.
+    ///
+    /// A renamed dependency will show the rename instead of original name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extern_name: Option<String>,
+    /// Artifact's crate type, e.g. staticlib, cdylib, bin...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<String>,
+    /// Equivalent to `{ target = "…" }` in an artifact dependency requirement.
+    ///
+    /// * If the target points to a custom target JSON file, the path will be absolute.
+    /// * If the target is a build assumed target `{ target = "target" }`, it will show as `<target>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_target: Option<String>,
+    /// Executable name for an artifact binary dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin_name: Option<String>,
+    // ^^^^^ The fields above are introduced for `-Z bindeps`.
+}
+
+/// The `feature_graph` section of `cargo metadata --include-feature-graph`.
+///
+/// Each edge records one reason a feature, or an optional dependency, was
+/// activated by the resolver.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureGraph {
+    pub edges: Vec<FeatureEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureEdge {
+    /// What caused the activation, or `None` if it was requested on the
+    /// command line (including the default features of workspace members).
+    pub from: Option<FeatureNode>,
+    pub to: FeatureNode,
+}
+
+/// A package, or one of its features.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureNode {
+    pub pkg: PackageIdSpec,
+    /// `None` refers to the package itself: as the source of an edge, the
+    /// package's dependency declarations; as the target of an edge, the
+    /// activation of the package as an optional dependency.
+    pub feature: Option<String>,
+}
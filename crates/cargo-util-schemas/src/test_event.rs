@@ -0,0 +1,94 @@
+//! Schema of the events `cargo test --no-capture-json` reports
+//!
+//! The test harness (libtest) is asked to write its results as JSON, one
+//! event per line. Cargo parses them into a [`TestEvent`] and reports each
+//! as a `test-event` message, along with the package and target the test
+//! binary was built from, so the events of all test binaries make up a
+//! single stream with Cargo's own messages.
+//!
+//! Only the events and fields listed here are forwarded. Lines that are not
+//! an event, like the output of a test run with `--nocapture`, are written
+//! to stderr.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TestEvent {
+    /// Progress of a whole test binary.
+    Suite(SuiteEvent),
+    /// Progress of a single test.
+    Test(TestCaseEvent),
+    /// Result of a single benchmark.
+    Bench(BenchEvent),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum SuiteEvent {
+    Started {
+        /// Number of tests that are going to run, after filtering.
+        test_count: usize,
+    },
+    Ok(SuiteSummary),
+    Failed(SuiteSummary),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuiteSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    pub measured: usize,
+    pub filtered_out: usize,
+    /// Time it took to run all tests, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_time: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum TestCaseEvent {
+    Started {
+        name: String,
+    },
+    Ok(TestCaseResult),
+    Failed(TestCaseResult),
+    Ignored {
+        name: String,
+        /// The reason given to `#[ignore]`, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+    /// The test has been running for longer than the harness warns about.
+    Timeout {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestCaseResult {
+    pub name: String,
+    /// Time it took to run the test, in seconds, only reported when the
+    /// harness is passed `--report-time`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_time: Option<f64>,
+    /// Output captured from the test.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout: Option<String>,
+    /// Why the test failed, when it is not from a panic, like a
+    /// `#[should_panic]` test that did not panic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchEvent {
+    pub name: String,
+    /// Median time of an iteration, in nanoseconds.
+    pub median: f64,
+    /// Deviation of the time of an iteration, in nanoseconds.
+    pub deviation: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mib_per_second: Option<f64>,
+}
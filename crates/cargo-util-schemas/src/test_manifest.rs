@@ -0,0 +1,57 @@
+//! Schema of the `cargo test --test-manifest` output
+//!
+//! The test manifest lists the test executables built by `cargo test`, along
+//! with the exact command Cargo runs each of them with, so that another test
+//! runner can run them without Cargo, or on another machine with the same
+//! layout.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::PackageIdSpec;
+
+/// The value of [`TestManifest::version`] written by this version of Cargo.
+///
+/// This is bumped on any incompatible change to the schema; new fields may be
+/// added without bumping it.
+pub const TEST_MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestManifest {
+    /// Version of the schema, see [`TEST_MANIFEST_VERSION`].
+    pub version: u32,
+    /// Every test executable, in the order `cargo test` runs them.
+    pub executables: Vec<TestExecutable>,
+}
+
+/// A test executable, built from a single target.
+///
+/// Doctests are not listed, as they are compiled and run by `rustdoc`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestExecutable {
+    pub package_id: PackageIdSpec,
+    pub package_name: String,
+    pub package_version: semver::Version,
+    pub manifest_path: PathBuf,
+    pub target_name: String,
+    /// Kinds of the target, like in the build plan.
+    pub target_kind: Vec<String>,
+    pub target_src_path: Option<PathBuf>,
+    /// Target triple the executable was built for, or `None` for the host.
+    pub kind: Option<String>,
+    /// Path to the executable.
+    pub path: PathBuf,
+    /// Whether the executable uses the standard test harness (libtest), and
+    /// so accepts its command-line options.
+    pub harness: bool,
+    /// The command that runs the tests. This is the executable itself, or the
+    /// runner configured with `target.<triple>.runner`.
+    pub program: String,
+    pub args: Vec<String>,
+    /// Environment variables to set, in addition to the environment Cargo
+    /// itself was run with, like the search path for dynamic libraries.
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<PathBuf>,
+}
@@ -344,19 +344,31 @@ impl ProcessBuilder {
 
         let spawn = |mut cmd| {
             if !debug_force_argfile(self.retry_with_argfile) {
-                match piped(&mut cmd, false).spawn() {
+                match piped(&mut cmd, self.stdin.is_some()).spawn() {
                     Err(ref e) if self.should_retry_with_argfile(e) => {}
                     Err(e) => return Err(e),
                     Ok(child) => return Ok((child, None)),
                 }
             }
             let (mut cmd, argfile) = self.build_command_with_argfile()?;
-            Ok((piped(&mut cmd, false).spawn()?, Some(argfile)))
+            Ok((
+                piped(&mut cmd, self.stdin.is_some()).spawn()?,
+                Some(argfile),
+            ))
         };
 
         let status = (|| {
             let cmd = self.build_command();
             let (mut child, argfile) = spawn(cmd)?;
+            // Write stdin from another thread, as the process may block on a
+            // full stdout or stderr pipe before it has read all of its input.
+            let stdin_writer = match (&self.stdin, child.stdin.take()) {
+                (Some(data), Some(mut pipe)) => {
+                    let data = data.clone();
+                    Some(std::thread::spawn(move || pipe.write_all(&data)))
+                }
+                _ => None,
+            };
             let out = child.stdout.take().unwrap();
             let err = child.stderr.take().unwrap();
             read2(out, err, &mut |is_out, data, eof| {
@@ -402,6 +414,13 @@ impl ProcessBuilder {
                 data.drain(..idx);
                 *pos = 0;
             })?;
+            if let Some(stdin_writer) = stdin_writer {
+                match stdin_writer.join().expect("stdin writer panicked") {
+                    // The process is free to exit without reading all of it.
+                    Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+                    _ => {}
+                }
+            }
             let status = child.wait();
             if let Some(argfile) = argfile {
                 close_tempfile_and_log_error(argfile);
@@ -652,6 +671,26 @@ mod tests {
         assert_eq!(buf, "foo\nbar\n");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn streaming_large_stdin_does_not_deadlock() {
+        // `cat` fills its stdout pipe long before it reads all of its input.
+        let input = "line\n".repeat(1 << 16);
+        let mut cmd = ProcessBuilder::new("cat");
+        cmd.stdin(input.clone());
+        let mut lines = 0;
+        cmd.exec_with_streaming(
+            &mut |_| {
+                lines += 1;
+                Ok(())
+            },
+            &mut |_| Ok(()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(lines, 1 << 16);
+    }
+
     #[test]
     fn argfile_build_fails_if_arg_contains_newline() {
         let mut cmd = ProcessBuilder::new("echo");
@@ -11,6 +11,9 @@ use url::Url;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The capability of registries supporting the `role` of owners.
+pub const OWNER_ROLES: &str = "owner-roles";
+
 pub struct Registry {
     /// The base URL for issuing API requests.
     host: String,
@@ -21,6 +24,8 @@ pub struct Registry {
     handle: Easy,
     /// Whether to include the authorization token with all requests.
     auth_required: bool,
+    /// Optional API features the registry supports, like [`OWNER_ROLES`].
+    capabilities: Vec<String>,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -94,6 +99,11 @@ pub struct User {
     pub avatar: Option<String>,
     pub email: Option<String>,
     pub name: Option<String>,
+    /// Whether the owner is a `user` or a `team`, if the registry reports it.
+    pub kind: Option<String>,
+    /// The role of the owner on the crate, for registries with the
+    /// [`OWNER_ROLES`] capability.
+    pub role: Option<String>,
 }
 
 pub struct Warnings {
@@ -122,6 +132,8 @@ struct ApiError {
 #[derive(Serialize)]
 struct OwnersReq<'a> {
     users: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'a str>,
 }
 #[derive(Deserialize)]
 struct Users {
@@ -220,9 +232,19 @@ impl Registry {
             token,
             handle,
             auth_required,
+            capabilities: Vec::new(),
         }
     }
 
+    /// Sets the capabilities the registry advertises in its `config.json`.
+    pub fn set_capabilities(&mut self, capabilities: Vec<String>) {
+        self.capabilities = capabilities;
+    }
+
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
     pub fn set_token(&mut self, token: Option<String>) {
         self.token = token;
     }
@@ -244,14 +266,31 @@ impl Registry {
     }
 
     pub fn add_owners(&mut self, krate: &str, owners: &[&str]) -> Result<String> {
-        let body = serde_json::to_string(&OwnersReq { users: owners })?;
+        self.add_owners_with_role(krate, owners, None)
+    }
+
+    /// Invites `owners` to `krate` with the given `role`, which requires the
+    /// [`OWNER_ROLES`] capability.
+    pub fn add_owners_with_role(
+        &mut self,
+        krate: &str,
+        owners: &[&str],
+        role: Option<&str>,
+    ) -> Result<String> {
+        let body = serde_json::to_string(&OwnersReq {
+            users: owners,
+            role,
+        })?;
         let body = self.put(&format!("/crates/{}/owners", krate), body.as_bytes())?;
         assert!(serde_json::from_str::<OwnerResponse>(&body)?.ok);
         Ok(serde_json::from_str::<OwnerResponse>(&body)?.msg)
     }
 
     pub fn remove_owners(&mut self, krate: &str, owners: &[&str]) -> Result<()> {
-        let body = serde_json::to_string(&OwnersReq { users: owners })?;
+        let body = serde_json::to_string(&OwnersReq {
+            users: owners,
+            role: None,
+        })?;
         let body = self.delete(&format!("/crates/{}/owners", krate), Some(body.as_bytes()))?;
         assert!(serde_json::from_str::<OwnerResponse>(&body)?.ok);
         Ok(())
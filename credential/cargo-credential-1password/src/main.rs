@@ -276,6 +276,8 @@ impl Credential for OnePasswordCredential {
                             token,
                             cache: CacheControl::Session,
                             operation_independent: true,
+                            scope: None,
+                            message: None,
                         })
                 } else {
                     Err(Error::NotFound)
@@ -165,6 +165,8 @@ mod linux {
                             token,
                             cache: CacheControl::Session,
                             operation_independent: true,
+                            scope: None,
+                            message: None,
                         })
                     }
                 }
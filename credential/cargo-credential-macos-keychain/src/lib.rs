@@ -39,6 +39,8 @@ mod macos {
                             token: token.into(),
                             cache: CacheControl::Session,
                             operation_independent: true,
+                            scope: None,
+                            message: None,
                         })
                     }
                 },
@@ -71,6 +71,8 @@ mod win {
                         token: token?.into(),
                         cache: CacheControl::Session,
                         operation_independent: true,
+                        scope: None,
+                        message: None,
                     })
                 }
                 Action::Login(options) => {
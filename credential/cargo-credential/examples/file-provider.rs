@@ -35,6 +35,8 @@ impl Credential for FileCredential {
                         token: token.clone(),
                         cache: CacheControl::Session,
                         operation_independent: true,
+                        scope: None,
+                        message: None,
                     })
                 } else {
                     // Credential providers should respond with `NotFound` when a credential can not be
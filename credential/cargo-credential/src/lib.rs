@@ -184,6 +184,14 @@ pub enum CredentialResponse {
         #[serde(flatten)]
         cache: CacheControl,
         operation_independent: bool,
+        /// Operations the token may be used for. Takes precedence over
+        /// `operation_independent` (protocol version 2).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scope: Option<TokenScope>,
+        /// Message for Cargo to show to the user, such as a reminder that the
+        /// token is about to expire (protocol version 2).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
     },
     Login,
     Logout,
@@ -204,6 +212,23 @@ pub enum CacheControl {
     },
     /// Cache this result and use it for all subsequent requests in the current Cargo invocation.
     Session,
+    /// Cache this result and use it for subsequent requests in the current Cargo invocation for
+    /// the given number of seconds (protocol version 2).
+    ExpiresIn { expires_in: u64 },
+    #[serde(other)]
+    Unknown,
+}
+
+/// The operations a token returned by [`CredentialResponse::Get`] may be used for.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum TokenScope {
+    /// The token may only be used for fetching crates ([`Operation::Read`]).
+    Read,
+    /// The token may be used for all operations, including publishing, yanking
+    /// and changing the owners of crates.
+    Publish,
     #[serde(other)]
     Unknown,
 }
@@ -214,6 +239,11 @@ pub enum CacheControl {
 /// in the `CredentialHello` message. Cargo will then choose which protocol to use,
 /// or it will error if there are no common protocol versions available.
 pub const PROTOCOL_VERSION_1: u32 = 1;
+/// Credential process JSON protocol version 2. Extends version 1 with the `scope` and
+/// `message` fields of [`CredentialResponse::Get`], and [`CacheControl::ExpiresIn`].
+pub const PROTOCOL_VERSION_2: u32 = 2;
+/// Protocol versions supported by this library.
+pub const PROTOCOL_VERSIONS: &[u32] = &[PROTOCOL_VERSION_1, PROTOCOL_VERSION_2];
 pub trait Credential {
     /// Retrieves a token for the given registry.
     fn perform(
@@ -238,7 +268,7 @@ fn doit(
     credential: impl Credential,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     let hello = CredentialHello {
-        v: vec![PROTOCOL_VERSION_1],
+        v: PROTOCOL_VERSIONS.to_vec(),
     };
     serde_json::to_writer(std::io::stdout(), &hello)?;
     println!();
@@ -264,7 +294,7 @@ fn deserialize_request(
     value: &str,
 ) -> Result<CredentialRequest<'_>, Box<dyn std::error::Error + Send + Sync>> {
     let request: CredentialRequest<'_> = serde_json::from_str(&value)?;
-    if request.v != PROTOCOL_VERSION_1 {
+    if !PROTOCOL_VERSIONS.contains(&request.v) {
         return Err(format!("unsupported protocol version {}", request.v).into());
     }
     Ok(request)
@@ -341,6 +371,8 @@ mod tests {
             cache: CacheControl::Never,
             operation_independent: true,
             token: Secret::from("value".to_string()),
+            scope: None,
+            message: None,
         };
         let json = serde_json::to_string(&cr).unwrap();
         assert_eq!(
@@ -366,7 +398,40 @@ mod tests {
             CredentialResponse::Get {
                 cache: CacheControl::Never,
                 operation_independent: true,
-                token: Secret::from("value".to_string())
+                token: Secret::from("value".to_string()),
+                scope: None,
+                message: None,
+            }
+        );
+    }
+
+    #[test]
+    fn credential_response_v2() {
+        let cr = CredentialResponse::Get {
+            cache: CacheControl::ExpiresIn { expires_in: 300 },
+            operation_independent: false,
+            token: Secret::from("value".to_string()),
+            scope: Some(TokenScope::Read),
+            message: Some("approve the request".to_string()),
+        };
+        let json = serde_json::to_string(&cr).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"get","token":"value","cache":"expires-in","expires_in":300,"operation_independent":false,"scope":"read","message":"approve the request"}"#
+        );
+
+        let cr: CredentialResponse = serde_json::from_str(
+            r#"{"kind":"get","token":"value","cache":"session","operation_independent":true,"scope":"unknown-scope"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cr,
+            CredentialResponse::Get {
+                cache: CacheControl::Session,
+                operation_independent: true,
+                token: Secret::from("value".to_string()),
+                scope: Some(TokenScope::Unknown),
+                message: None,
             }
         );
     }
@@ -48,7 +48,8 @@ pub fn main(gctx: &mut GlobalContext) -> CliResult {
         gctx.reload_cwd()?;
     }
 
-    let (expanded_args, global_args) = expand_aliases(gctx, args, vec![])?;
+    let alias_placeholders = super::alias_placeholders_enabled(gctx, &args);
+    let (expanded_args, global_args) = expand_aliases(gctx, args, vec![], alias_placeholders)?;
 
     let is_verbose = expanded_args.verbose() > 0;
 
@@ -88,11 +89,16 @@ pub fn main(gctx: &mut GlobalContext) -> CliResult {
             gctx,
             &expanded_args,
             Some(subcommand_args),
-            global_args,
+            global_args.clone(),
             Some(&exec),
         )?;
         super::init_git(gctx);
 
+        if matches!(&exec, Exec::External(cmd) if cmd == "clippy")
+            && gctx.cli_unstable().native_clippy
+        {
+            return exec_native_clippy(gctx, &expanded_args, subcommand_args, global_args);
+        }
         exec.exec(gctx, subcommand_args)?;
     }
     Ok(())
@@ -278,6 +284,7 @@ fn expand_aliases(
     gctx: &mut GlobalContext,
     args: ArgMatches,
     mut already_expanded: Vec<String>,
+    alias_placeholders: bool,
 ) -> Result<(ArgMatches, GlobalArgs), CliError> {
     if let Some((cmd, sub_args)) = args.subcommand() {
         let exec = commands::builtin_exec(cmd);
@@ -339,16 +346,22 @@ For more information, see issue #12207 <https://github.com/rust-lang/cargo/issue
                     }
                 }
 
-                let mut alias = alias
-                    .into_iter()
-                    .map(|s| OsString::from(s))
+                let alias_args = sub_args
+                    .get_many::<OsString>("")
+                    .unwrap_or_default()
+                    .cloned()
                     .collect::<Vec<_>>();
-                alias.extend(
-                    sub_args
-                        .get_many::<OsString>("")
-                        .unwrap_or_default()
-                        .cloned(),
-                );
+                let alias = if alias_placeholders {
+                    let alias = super::parse_alias_placeholders(cmd, &alias)?;
+                    super::expand_alias_placeholders(cmd, alias, alias_args)?
+                } else {
+                    let mut alias = alias
+                        .into_iter()
+                        .map(|s| OsString::from(s))
+                        .collect::<Vec<_>>();
+                    alias.extend(alias_args);
+                    alias
+                };
                 // new_args strips out everything before the subcommand, so
                 // capture those global options now.
                 // Note that an alias to an external command will not receive
@@ -375,7 +388,8 @@ For more information, see issue #12207 <https://github.com/rust-lang/cargo/issue
                     .into());
                 }
 
-                let (expanded_args, _) = expand_aliases(gctx, new_args, already_expanded)?;
+                let (expanded_args, _) =
+                    expand_aliases(gctx, new_args, already_expanded, alias_placeholders)?;
                 return Ok((expanded_args, global_args));
             }
             (None, Err(e)) => return Err(e.into()),
@@ -440,9 +454,63 @@ fn configure_gctx(
         &unstable_flags,
         &config_args,
     )?;
+    let lints_profile = subcommand_args.and_then(|a| a._value_of("lints-profile"));
+    if lints_profile.is_some() {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--lints-profile", None)?;
+    }
+    gctx.set_lints_profile(lints_profile.map(str::to_owned));
+    let index_snapshot = subcommand_args.and_then(|a| a._value_of("index-snapshot"));
+    if index_snapshot.is_some() {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--index-snapshot", None)?;
+    }
+    gctx.set_index_snapshot(index_snapshot.map(str::to_owned));
+    if index_snapshot.is_some() || gctx.cli_unstable().unstable_options {
+        // Report a bad snapshot up front. Otherwise the config is only read
+        // when the index is queried, so commands like `cargo version` keep
+        // working with a broken config.
+        gctx.index_snapshot()?;
+    }
     Ok(())
 }
 
+/// Runs the built-in `cargo clippy` for `-Znative-clippy`.
+///
+/// The arguments of `cargo clippy` were collected like those of an external
+/// subcommand, so they are parsed again with the built-in command, and the
+/// global options among them are applied.
+fn exec_native_clippy(
+    gctx: &mut GlobalContext,
+    args: &ArgMatches,
+    subcommand_args: &ArgMatches,
+    global_args: GlobalArgs,
+) -> CliResult {
+    let mut clippy_args = vec![OsString::from("cargo"), OsString::from("clippy")];
+    clippy_args.extend(
+        subcommand_args
+            .get_many::<OsString>("")
+            .unwrap_or_default()
+            .cloned(),
+    );
+    let clippy_matches = cli(gctx)
+        .subcommand(commands::clippy::cli())
+        .try_get_matches_from(clippy_args)?;
+    let Some(("clippy", clippy_subcommand_args)) = clippy_matches.subcommand() else {
+        unreachable!("`clippy` is the only subcommand")
+    };
+    // The options given before `clippy`, or before the alias it came from.
+    let global_args = global_args.merge(GlobalArgs::new(args));
+    configure_gctx(
+        gctx,
+        &clippy_matches,
+        Some(clippy_subcommand_args),
+        global_args,
+        None,
+    )?;
+    commands::clippy::exec(gctx, clippy_subcommand_args)
+}
+
 enum Exec {
     Builtin(commands::Exec),
     Manifest(String),
@@ -505,7 +573,7 @@ For more information, see issue #12207 <https://github.com/rust-lang/cargo/issue
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct GlobalArgs {
     verbose: u32,
     quiet: bool,
@@ -538,6 +606,19 @@ impl GlobalArgs {
                 .collect(),
         }
     }
+
+    /// Combines these options with `other`, like when both are given.
+    fn merge(mut self, other: GlobalArgs) -> GlobalArgs {
+        self.verbose += other.verbose;
+        self.quiet |= other.quiet;
+        self.color = other.color.or(self.color);
+        self.frozen |= other.frozen;
+        self.locked |= other.locked;
+        self.offline |= other.offline;
+        self.unstable_flags.extend(other.unstable_flags);
+        self.config_args.extend(other.config_args);
+        self
+    }
 }
 
 #[tracing::instrument(skip_all)]
@@ -6,7 +6,9 @@ use indexmap::IndexSet;
 use cargo::core::dependency::DepKind;
 use cargo::core::FeatureValue;
 use cargo::ops::cargo_add::add;
+use cargo::ops::cargo_add::add_workspace_dependencies;
 use cargo::ops::cargo_add::AddOptions;
+use cargo::ops::cargo_add::AddWorkspaceOptions;
 use cargo::ops::cargo_add::DepOp;
 use cargo::ops::resolve_ws;
 use cargo::util::command_prelude::*;
@@ -160,7 +162,17 @@ Build-dependencies are the only dependencies available for use by build scripts
                 .action(ArgAction::Set)
                 .value_name("TARGET")
                 .value_parser(clap::builder::NonEmptyStringValueParser::new())
-                .help("Add as dependency to the given target platform")
+                .help("Add as dependency to the given target platform"),
+            flag("workspace-dependency",
+                "Add to `[workspace.dependencies]` in the workspace root (unstable)")
+                .long_help("Add to `[workspace.dependencies]` in the workspace root (unstable)
+
+Members can then depend on it with `workspace = true`.")
+                .group("section")
+                .conflicts_with_all(["target", "package"]),
+            flag("rewrite-members",
+                "Switch members that depend on it to `workspace = true` (unstable)")
+                .requires("workspace-dependency"),
         ])
 }
 
@@ -170,6 +182,24 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
 
     let ws = args.workspace(gctx)?;
 
+    if args.flag("workspace-dependency") {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--workspace-dependency", None)?;
+        let options = AddWorkspaceOptions {
+            gctx,
+            dependencies: parse_dependencies(gctx, args)?,
+            rewrite_members: args.flag("rewrite-members"),
+            dry_run,
+            honor_rust_version: args.honor_rust_version(),
+        };
+        add_workspace_dependencies(&ws, &options)?;
+
+        // Reload the workspace since we've changed dependencies
+        let ws = args.workspace(gctx)?;
+        resolve_ws(&ws, dry_run)?;
+        return Ok(());
+    }
+
     if args.is_present_with_zero_values("package") {
         print_available_packages(&ws)?;
     }
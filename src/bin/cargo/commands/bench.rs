@@ -22,6 +22,20 @@ pub fn cli() -> Command {
             "no-fail-fast",
             "Run all benchmarks regardless of failure",
         ))
+        .arg(
+            opt(
+                "save-baseline",
+                "Save the results of the benchmarks under NAME (unstable)",
+            )
+            .value_name("NAME"),
+        )
+        .arg(
+            opt(
+                "baseline",
+                "Compare the benchmarks to the results saved under NAME (unstable)",
+            )
+            .value_name("NAME"),
+        )
         .arg_message_format()
         .arg_silent_suggestion()
         .arg_package_spec(
@@ -42,14 +56,17 @@ pub fn cli() -> Command {
             "Benchmark all targets",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_jobs()
         .arg_unsupported_keep_going()
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help bench</>` for more detailed information.\n"
@@ -65,9 +82,25 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     compile_opts.build_config.requested_profile =
         args.get_profile_name("bench", ProfileChecking::Custom)?;
 
+    let save_baseline = args.get_one::<String>("save-baseline").cloned();
+    let baseline = args.get_one::<String>("baseline").cloned();
+    for (flag, value) in [
+        ("--save-baseline", &save_baseline),
+        ("--baseline", &baseline),
+    ] {
+        if value.is_some() {
+            gctx.cli_unstable().fail_if_stable_opt(flag, None)?;
+        }
+    }
+
     let ops = TestOptions {
         no_run: args.flag("no-run"),
         no_fail_fast: args.flag("no-fail-fast"),
+        json_events: false,
+        test_manifest: None,
+        partition: None,
+        save_baseline,
+        baseline,
         compile_opts,
     };
 
@@ -0,0 +1,61 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, BisectDepOptions};
+
+pub fn cli() -> Command {
+    subcommand("bisect-dep")
+        .about("Find the dependency updates that make a command fail (unstable)")
+        .arg(
+            Arg::new("args")
+                .value_name("ARGS")
+                .help("The cargo command to run at every step, `build` by default")
+                .num_args(0..)
+                .last(true),
+        )
+        .arg(
+            opt("good", "Lock file the command succeeds with")
+                .value_name("PATH")
+                .required(true),
+        )
+        .arg(
+            opt(
+                "bad",
+                "Lock file the command fails with, `Cargo.lock` by default",
+            )
+            .value_name("PATH"),
+        )
+        .arg(flag(
+            "reset",
+            "Forget the results recorded by a previous bisection",
+        ))
+        .arg_silent_suggestion()
+        .arg_manifest_path()
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "bisect-dep",
+        None,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    let ws = args.workspace(gctx)?;
+    let mut cmd_args: Vec<String> = args
+        .get_many::<String>("args")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    if cmd_args.is_empty() {
+        cmd_args.push("build".to_string());
+    }
+    let opts = BisectDepOptions {
+        gctx,
+        good: args.value_of_path("good", gctx).unwrap(),
+        bad: args.value_of_path("bad", gctx),
+        args: cmd_args,
+        reset: args.flag("reset"),
+    };
+    ops::bisect_dep(&ws, &opts)?;
+    Ok(())
+}
@@ -1,5 +1,11 @@
 use crate::command_prelude::*;
 
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use cargo::core::compiler::{Executor, ExternalExecutor};
 use cargo::ops;
 
 pub fn cli() -> Command {
@@ -8,6 +14,7 @@ pub fn cli() -> Command {
         // .alias("b")
         .about("Compile a local package and all of its dependencies")
         .arg_future_incompat_report()
+        .arg_watch()
         .arg_message_format()
         .arg_silent_suggestion()
         .arg_package_spec(
@@ -15,6 +22,7 @@ pub fn cli() -> Command {
             "Build all packages in the workspace",
             "Exclude packages from the build",
         )
+        .arg_only_deps()
         .arg_targets_all(
             "Build only this package's library",
             "Build only the specified binary",
@@ -28,17 +36,29 @@ pub fn cli() -> Command {
             "Build all targets",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_release("Build artifacts in release mode, with optimizations")
         .arg_redundant_default_mode("debug", "build", "release")
         .arg_profile("Build artifacts with the specified profile")
         .arg_parallel()
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_artifact_dir()
         .arg_build_plan()
+        .arg_print_env()
+        .arg(
+            opt(
+                "executor",
+                "Hand every rustc invocation to CMD, as JSON on stdin (unstable)",
+            )
+            .value_name("CMD")
+            .help_heading(heading::COMPILATION_OPTIONS),
+        )
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help build</>` for more detailed information.\n"
@@ -46,15 +66,26 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    let gctx = &*gctx;
+    args.exec_watching(gctx, |watched_files| build(gctx, args, watched_files))
+}
+
+fn build(
+    gctx: &GlobalContext,
+    args: &ArgMatches,
+    watched_files: Option<Rc<RefCell<Vec<PathBuf>>>>,
+) -> CliResult {
     let ws = args.workspace(gctx)?;
     let mut compile_opts =
         args.compile_options(gctx, CompileMode::Build, Some(&ws), ProfileChecking::Custom)?;
+    compile_opts.build_config.watched_files = watched_files;
 
     if let Some(artifact_dir) = args.value_of_path("artifact-dir", gctx) {
         // If the user specifies `--artifact-dir`, use that
         compile_opts.build_config.export_dir = Some(artifact_dir);
     } else if let Some(artifact_dir) = args.value_of_path("out-dir", gctx) {
         // `--out-dir` is deprecated, but still supported for now
+        gctx.cli_unstable().fail_if_stable_opt("--out-dir", 6790)?;
         gctx.shell()
             .warn("the --out-dir flag has been changed to --artifact-dir")?;
         compile_opts.build_config.export_dir = Some(artifact_dir);
@@ -65,17 +96,25 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         compile_opts.build_config.export_dir = Some(artifact_dir);
     } else if let Some(artifact_dir) = gctx.build_config()?.out_dir.as_ref() {
         // As a last priority, check `out-dir` in the build config
-        gctx.shell()
-            .warn("the out-dir config option has been changed to artifact-dir")?;
-        let artifact_dir = artifact_dir.resolve_path(gctx);
-        compile_opts.build_config.export_dir = Some(artifact_dir);
+        if gctx.cli_unstable().unstable_options {
+            gctx.shell()
+                .warn("the out-dir config option has been changed to artifact-dir")?;
+            let artifact_dir = artifact_dir.resolve_path(gctx);
+            compile_opts.build_config.export_dir = Some(artifact_dir);
+        } else {
+            gctx.shell().warn(
+                "ignoring `build.out-dir` config, pass `-Zunstable-options` to enable it \
+                 or use `build.artifact-dir` instead",
+            )?;
+        }
     }
 
-    if compile_opts.build_config.export_dir.is_some() {
-        gctx.cli_unstable()
-            .fail_if_stable_opt("--artifact-dir", 6790)?;
+    if let Some(executor) = args.get_one::<String>("executor") {
+        gctx.cli_unstable().fail_if_stable_opt("--executor", None)?;
+        let exec: Arc<dyn Executor> = Arc::new(ExternalExecutor::new(gctx, executor)?);
+        ops::compile_with_exec(&ws, &compile_opts, &exec)?;
+    } else {
+        ops::compile(&ws, &compile_opts)?;
     }
-
-    ops::compile(&ws, &compile_opts)?;
     Ok(())
 }
@@ -1,6 +1,9 @@
 use crate::command_prelude::*;
 
 use cargo::ops;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 pub fn cli() -> Command {
     subcommand("check")
@@ -8,6 +11,7 @@ pub fn cli() -> Command {
         // .alias("c")
         .about("Check a local package and all of its dependencies for errors")
         .arg_future_incompat_report()
+        .arg_watch()
         .arg_message_format()
         .arg_silent_suggestion()
         .arg_package_spec(
@@ -15,6 +19,7 @@ pub fn cli() -> Command {
             "Check all packages in the workspace",
             "Exclude packages from the check",
         )
+        .arg_only_deps()
         .arg_targets_all(
             "Check only this package's library",
             "Check only the specified binary",
@@ -28,14 +33,17 @@ pub fn cli() -> Command {
             "Check all targets",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_parallel()
         .arg_release("Check artifacts in release mode, with optimizations")
         .arg_profile("Check artifacts with the specified profile")
         .arg_target_triple("Check for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help check</>` for more detailed information.\n"
@@ -43,6 +51,15 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    let gctx = &*gctx;
+    args.exec_watching(gctx, |watched_files| check(gctx, args, watched_files))
+}
+
+fn check(
+    gctx: &GlobalContext,
+    args: &ArgMatches,
+    watched_files: Option<Rc<RefCell<Vec<PathBuf>>>>,
+) -> CliResult {
     let ws = args.workspace(gctx)?;
     // This is a legacy behavior that causes `cargo check` to pass `--test`.
     let test = matches!(
@@ -50,8 +67,9 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         Some("test")
     );
     let mode = CompileMode::Check { test };
-    let compile_opts =
+    let mut compile_opts =
         args.compile_options(gctx, mode, Some(&ws), ProfileChecking::LegacyTestOnly)?;
+    compile_opts.build_config.watched_files = watched_files;
 
     ops::compile(&ws, &compile_opts)?;
     Ok(())
@@ -20,6 +20,42 @@ pub fn cli() -> Command {
         .arg_target_dir()
         .arg_manifest_path()
         .arg_dry_run("Display what would be deleted without deleting anything")
+        .arg(
+            opt(
+                "older-than",
+                "Deletes build artifacts that have not been used \
+                since the given age (unstable)",
+            )
+            .value_name("DURATION")
+            .value_parser(parse_time_span)
+            .conflicts_with_all(["package", "doc", "target"]),
+        )
+        .arg(
+            opt(
+                "max-size",
+                "Deletes the least recently used build artifacts until the \
+                target directory is under the given size (unstable)",
+            )
+            .value_name("SIZE")
+            .value_parser(parse_human_size)
+            .conflicts_with_all(["package", "doc", "target"]),
+        )
+        .arg(
+            flag(
+                "clean-cache",
+                "Deletes the cached builds of single-file packages that are \
+                out of date (unstable)",
+            )
+            .conflicts_with_all([
+                "package",
+                "doc",
+                "release",
+                "profile",
+                "target",
+                "older-than",
+                "max-size",
+            ]),
+        )
         .args_conflicts_with_subcommands(true)
         .subcommand(
             subcommand("gc")
@@ -136,12 +172,42 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         None => {}
     }
 
+    if args.flag("clean-cache") {
+        gctx.cli_unstable().fail_if_stable_opt_custom_z(
+            "--clean-cache",
+            12207,
+            "script",
+            gctx.cli_unstable().script,
+        )?;
+        ops::clean_script_cache(gctx, args.dry_run())?;
+        return Ok(());
+    }
+
     let ws = args.workspace(gctx)?;
 
     if args.is_present_with_zero_values("package") {
         print_available_packages(&ws)?;
     }
 
+    let older_than = args.get_one::<Duration>("older-than").copied();
+    if older_than.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt_custom_z(
+            "--older-than",
+            12633,
+            "gc",
+            gctx.cli_unstable().gc,
+        )?;
+    }
+    let max_size = args.get_one::<u64>("max-size").copied();
+    if max_size.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt_custom_z(
+            "--max-size",
+            12633,
+            "gc",
+            gctx.cli_unstable().gc,
+        )?;
+    }
+
     let opts = CleanOptions {
         gctx,
         spec: values(args, "package"),
@@ -150,6 +216,8 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         profile_specified: args.contains_id("profile") || args.flag("release"),
         doc: args.flag("doc"),
         dry_run: args.dry_run(),
+        older_than,
+        max_size,
     };
     ops::clean(&ws, &opts)?;
     Ok(())
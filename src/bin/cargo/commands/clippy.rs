@@ -0,0 +1,125 @@
+use crate::command_prelude::*;
+
+use cargo::core::compiler::ClippyConfig;
+use cargo::core::Workspace;
+use cargo::ops;
+
+/// The built-in `cargo clippy`, used instead of the `cargo-clippy` external
+/// subcommand with `-Znative-clippy`.
+///
+/// It isn't part of [`super::builtin`], as `cargo clippy` is dispatched to
+/// `cargo-clippy` unless the built-in command is enabled.
+pub fn cli() -> Command {
+    subcommand("clippy")
+        .about("Check a package to catch common mistakes and improve your Rust code")
+        .arg(
+            Arg::new("args")
+                .value_name("ARGS")
+                .num_args(0..)
+                .help("Extra arguments for clippy, like `-D warnings`")
+                .last(true),
+        )
+        .arg(flag(
+            "no-deps",
+            "Lint only the selected packages, not the other workspace members",
+        ))
+        .arg(flag("fix", "Automatically apply lint suggestions"))
+        .arg(
+            flag(
+                "broken-code",
+                "Fix code even if it already has compiler errors",
+            )
+            .requires("fix"),
+        )
+        .arg(flag("allow-no-vcs", "Fix code even if a VCS was not detected").requires("fix"))
+        .arg(
+            flag(
+                "allow-dirty",
+                "Fix code even if the working directory is dirty",
+            )
+            .requires("fix"),
+        )
+        .arg(
+            flag(
+                "allow-staged",
+                "Fix code even if the working directory has staged changes",
+            )
+            .requires("fix"),
+        )
+        .arg_message_format()
+        .arg_silent_suggestion()
+        .arg_package_spec(
+            "Package(s) to check",
+            "Check all packages in the workspace",
+            "Exclude packages from the check",
+        )
+        .arg_targets_all(
+            "Check only this package's library",
+            "Check only the specified binary",
+            "Check all binaries",
+            "Check only the specified example",
+            "Check all examples",
+            "Check only the specified test target",
+            "Check all test targets",
+            "Check only the specified bench target",
+            "Check all bench targets",
+            "Check all targets",
+        )
+        .arg_features()
+        .arg_all_required_features()
+        .arg_parallel()
+        .arg_release("Check artifacts in release mode, with optimizations")
+        .arg_profile("Check artifacts with the specified profile")
+        .arg_target_triple("Check for the target triple")
+        .arg_target_dir()
+        .arg_lints_profile()
+        .arg_timings()
+        .arg_manifest_path()
+        .arg_index_snapshot()
+        .arg_ignore_rust_version()
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    // This is a legacy behavior that causes `cargo check` to pass `--test`.
+    let test = matches!(
+        args.get_one::<String>("profile").map(String::as_str),
+        Some("test")
+    );
+    let mode = CompileMode::Check { test };
+    let root_manifest = args.root_manifest(gctx)?;
+    let mut ws = Workspace::new(&root_manifest, gctx)?;
+    ws.set_resolve_honors_rust_version(args.honor_rust_version());
+    let mut compile_opts =
+        args.compile_options(gctx, mode, Some(&ws), ProfileChecking::LegacyTestOnly)?;
+    compile_opts.build_config.clippy = Some(ClippyConfig {
+        driver: gctx.clippy_driver()?,
+        args: values(args, "args"),
+        no_deps: args.flag("no-deps"),
+    });
+
+    if !args.flag("fix") {
+        ops::compile(&ws, &compile_opts)?;
+        return Ok(());
+    }
+
+    if !compile_opts.filter.is_specific() {
+        // Like `cargo fix`, fix all targets unless some are selected.
+        compile_opts.filter = ops::CompileFilter::new_all_targets();
+    }
+    ops::fix(
+        gctx,
+        &ws,
+        &root_manifest,
+        &mut ops::FixOptions {
+            edition: false,
+            idioms: false,
+            compile_opts,
+            allow_dirty: args.flag("allow-dirty"),
+            allow_no_vcs: args.flag("allow-no-vcs"),
+            allow_staged: args.flag("allow-staged"),
+            broken_code: args.flag("broken-code"),
+            from_json: None,
+        },
+    )?;
+    Ok(())
+}
@@ -18,6 +18,10 @@ pub fn cli() -> Command {
                         .value_parser(cargo_config::ConfigFormat::POSSIBLE_VALUES)
                         .default_value("toml"),
                 )
+                .arg(flag(
+                    "json",
+                    "Display in JSON format, same as `--format json`",
+                ))
                 .arg(flag(
                     "show-origin",
                     "Display where the config value is defined",
@@ -28,6 +32,53 @@ pub fn cli() -> Command {
                         .default_value("yes"),
                 ),
         )
+        .subcommand(
+            subcommand("set")
+                .about("Set a config value in a config file")
+                .arg(
+                    Arg::new("key")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The config key to set"),
+                )
+                .arg(
+                    Arg::new("value")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The value to set, as TOML or else as a string"),
+                )
+                .args(target_args()),
+        )
+        .subcommand(
+            subcommand("unset")
+                .about("Remove a config value from a config file")
+                .arg(
+                    Arg::new("key")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The config key to remove"),
+                )
+                .args(target_args()),
+        )
+}
+
+fn target_args() -> [Arg; 2] {
+    [
+        flag("global", "Edit the config file in the Cargo home directory")
+            .conflicts_with("workspace"),
+        flag(
+            "workspace",
+            "Edit the config file in the workspace root (default)",
+        ),
+    ]
+}
+
+fn target(args: &ArgMatches) -> cargo_config::ConfigTarget {
+    if args.flag("global") {
+        cargo_config::ConfigTarget::Global
+    } else {
+        cargo_config::ConfigTarget::Workspace
+    }
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
@@ -40,14 +91,34 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     )?;
     match args.subcommand() {
         Some(("get", args)) => {
+            let format = if args.flag("json") {
+                cargo_config::ConfigFormat::Json
+            } else {
+                args.get_one::<String>("format").unwrap().parse()?
+            };
             let opts = cargo_config::GetOptions {
                 key: args.get_one::<String>("key").map(String::as_str),
-                format: args.get_one::<String>("format").unwrap().parse()?,
+                format,
                 show_origin: args.flag("show-origin"),
                 merged: args.get_one::<String>("merged").map(String::as_str) == Some("yes"),
             };
             cargo_config::get(gctx, &opts)?;
         }
+        Some(("set", args)) => {
+            let opts = cargo_config::SetOptions {
+                key: args.get_one::<String>("key").unwrap(),
+                value: args.get_one::<String>("value").unwrap(),
+                target: target(args),
+            };
+            cargo_config::set(gctx, &opts)?;
+        }
+        Some(("unset", args)) => {
+            let opts = cargo_config::UnsetOptions {
+                key: args.get_one::<String>("key").unwrap(),
+                target: target(args),
+            };
+            cargo_config::unset(gctx, &opts)?;
+        }
         Some((cmd, _)) => {
             unreachable!("unexpected command {}", cmd)
         }
@@ -11,6 +11,15 @@ pub fn cli() -> Command {
             "open",
             "Opens the docs in a browser after the operation",
         ))
+        .arg(
+            opt(
+                "serve",
+                "Serve the docs over HTTP and rebuild them when the sources change (unstable)",
+            )
+            .value_name("ADDR")
+            .num_args(0..=1)
+            .default_missing_value("127.0.0.1:0"),
+        )
         .arg(flag(
             "no-deps",
             "Don't build documentation for dependencies",
@@ -24,6 +33,7 @@ pub fn cli() -> Command {
             "Exclude packages from the build",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_targets_lib_bin_example(
             "Document only this package's library",
             "Document only the specified binary",
@@ -36,9 +46,11 @@ pub fn cli() -> Command {
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help doc</>` for more detailed information.\n"
@@ -54,9 +66,21 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let mut compile_opts = args.compile_options(gctx, mode, Some(&ws), ProfileChecking::Custom)?;
     compile_opts.rustdoc_document_private_items = args.flag("document-private-items");
 
+    let serve = args.get_one::<String>("serve");
+    if serve.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt("--serve", None)?;
+    }
+    let serve = serve
+        .map(|addr| {
+            addr.parse()
+                .map_err(|_| anyhow::format_err!("invalid address `{addr}` for `--serve`"))
+        })
+        .transpose()?;
+
     let doc_opts = DocOptions {
         open_result: args.flag("open"),
         output_format: ops::OutputFormat::Html,
+        serve,
         compile_opts,
     };
     ops::doc(&ws, &doc_opts)?;
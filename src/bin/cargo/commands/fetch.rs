@@ -3,12 +3,27 @@ use crate::command_prelude::*;
 use cargo::ops;
 use cargo::ops::FetchOptions;
 
+use std::path::PathBuf;
+
 pub fn cli() -> Command {
     subcommand("fetch")
         .about("Fetch dependencies of a package from the network")
+        .arg(flag(
+            "lock-only",
+            "Only resolve the dependencies and list what would be downloaded (unstable)",
+        ))
+        .arg(
+            opt(
+                "output",
+                "Write the list of downloads as JSON to PATH (unstable)",
+            )
+            .value_name("PATH")
+            .value_parser(value_parser!(PathBuf)),
+        )
         .arg_silent_suggestion()
         .arg_target_triple("Fetch dependencies for the target triple")
         .arg_manifest_path()
+        .arg_index_snapshot()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help fetch</>` for more detailed information.\n"
         ))
@@ -17,9 +32,21 @@ pub fn cli() -> Command {
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let ws = args.workspace(gctx)?;
 
+    let lock_only = args.flag("lock-only");
+    let output = args.get_one::<PathBuf>("output").cloned();
+    if lock_only {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--lock-only", None)?;
+    }
+    if output.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt("--output", None)?;
+    }
+
     let opts = FetchOptions {
         gctx,
         targets: args.targets()?,
+        lock_only,
+        output,
     };
     let _ = ops::fetch(&ws, &opts)?;
     Ok(())
@@ -3,6 +3,8 @@ use crate::command_prelude::*;
 use cargo::core::Workspace;
 use cargo::ops;
 
+use std::path::PathBuf;
+
 pub fn cli() -> Command {
     subcommand("fix")
         .about("Automatically fix lint warnings reported by rustc")
@@ -27,6 +29,14 @@ pub fn cli() -> Command {
             "allow-staged",
             "Fix code even if the working directory has staged changes",
         ))
+        .arg(
+            opt(
+                "from-json",
+                "Apply the suggestions of the JSON messages of a previous build (unstable)",
+            )
+            .value_name("PATH")
+            .value_parser(value_parser!(PathBuf)),
+        )
         .arg_message_format()
         .arg_silent_suggestion()
         .arg_package_spec(
@@ -47,13 +57,16 @@ pub fn cli() -> Command {
             "Fix all targets (default)",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_parallel()
         .arg_release("Fix artifacts in release mode, with optimizations")
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Fix for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help fix</>` for more detailed information.\n"
@@ -80,6 +93,12 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         opts.filter = ops::CompileFilter::new_all_targets();
     }
 
+    let from_json = args.get_one::<PathBuf>("from-json").cloned();
+    if from_json.is_some() {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--from-json", None)?;
+    }
+
     ops::fix(
         gctx,
         &ws,
@@ -92,6 +111,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             allow_no_vcs: args.flag("allow-no-vcs"),
             allow_staged: args.flag("allow-staged"),
             broken_code: args.flag("broken-code"),
+            from_json,
         },
     )?;
     Ok(())
@@ -5,8 +5,10 @@ use cargo::ops;
 pub fn cli() -> Command {
     subcommand("generate-lockfile")
         .about("Generate the lockfile for a package")
+        .arg_minimal_versions()
         .arg_silent_suggestion()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version_with_help(
             "Ignore `rust-version` specification in packages (unstable)",
         )
@@ -24,7 +26,9 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             gctx.cli_unstable().msrv_policy,
         )?;
     }
-    let ws = args.workspace(gctx)?;
+    let mut ws = args.workspace(gctx)?;
+    ws.set_resolve_minimal_versions(args.flag("minimal-versions"));
+    ws.set_resolve_direct_minimal_versions(args.flag("direct-minimal-versions"));
     ops::generate_lockfile(&ws)?;
     Ok(())
 }
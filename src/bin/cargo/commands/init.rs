@@ -12,6 +12,13 @@ pub fn cli() -> Command {
                 .default_value("."),
         )
         .arg_new_opts()
+        .arg(
+            flag(
+                "workspace",
+                "Create a workspace root, moving an existing package into it (unstable)",
+            )
+            .conflicts_with_all(["bin", "lib", "template", "name"]),
+        )
         .arg_registry("Registry to use")
         .arg_silent_suggestion()
         .after_help(color_print::cstr!(
@@ -21,6 +28,12 @@ pub fn cli() -> Command {
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let opts = args.new_options(gctx)?;
+    if args.flag("workspace") {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--workspace", None)?;
+        ops::init_workspace(&opts, gctx)?;
+        return Ok(());
+    }
     ops::init(&opts, gctx)?;
     Ok(())
 }
@@ -74,6 +74,21 @@ pub fn cli() -> Command {
             "list",
             "List all installed packages and their versions",
         ))
+        .arg(
+            flag(
+                "outdated",
+                "Reinstall the installed packages that have a newer version, \
+                 or list them with --list (unstable)",
+            )
+            .conflicts_with_all(&["crate", "git", "path", "version"]),
+        )
+        .arg(
+            opt(
+                "prebuilt-checksum",
+                "SHA-256 checksum of the prebuilt binaries to install (unstable)",
+            )
+            .value_name("SHA256"),
+        )
         .arg_ignore_rust_version()
         .arg_message_format()
         .arg_silent_suggestion()
@@ -201,8 +216,25 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     compile_opts.build_config.requested_profile =
         args.get_profile_name("release", ProfileChecking::Custom)?;
 
+    let outdated = args.flag("outdated");
+    if outdated {
+        gctx.cli_unstable().fail_if_stable_opt("--outdated", None)?;
+    }
+
+    let prebuilt_checksum = args.get_one::<String>("prebuilt-checksum");
+    if prebuilt_checksum.is_some() {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--prebuilt-checksum", None)?;
+    }
+
     if args.flag("list") {
-        ops::install_list(root, gctx)?;
+        if outdated {
+            ops::install_list_outdated(root, gctx)?;
+        } else {
+            ops::install_list(root, gctx)?;
+        }
+    } else if outdated {
+        ops::install_outdated(gctx, root, &compile_opts)?;
     } else {
         ops::install(
             gctx,
@@ -213,6 +245,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             &compile_opts,
             args.flag("force"),
             args.flag("no-track"),
+            prebuilt_checksum.map(String::as_str),
         )?;
     }
     Ok(())
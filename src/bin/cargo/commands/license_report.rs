@@ -0,0 +1,44 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, LicenseReportFormat, LicenseReportOptions};
+
+pub fn cli() -> Command {
+    subcommand("license-report")
+        .about("Report the licenses of the dependencies of the workspace (unstable)")
+        .arg(
+            opt("format", "Output format of the report")
+                .value_name("FMT")
+                .value_parser(["markdown", "json", "csv"])
+                .default_value("markdown"),
+        )
+        .arg(multi_opt(
+            "deny",
+            "LICENSE",
+            "Fail if a dependency is only allowed under this license",
+        ))
+        .arg_silent_suggestion()
+        .arg_manifest_path()
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "license-report",
+        None,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    let ws = args.workspace(gctx)?;
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("json") => LicenseReportFormat::Json,
+        Some("csv") => LicenseReportFormat::Csv,
+        _ => LicenseReportFormat::Markdown,
+    };
+    let opts = LicenseReportOptions {
+        gctx,
+        format,
+        deny: args._values_of("deny"),
+    };
+    ops::license_report(&ws, &opts)?;
+    Ok(())
+}
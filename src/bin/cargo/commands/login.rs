@@ -8,6 +8,13 @@ pub fn cli() -> Command {
         .about("Log in to a registry.")
         .arg(Arg::new("token").value_name("TOKEN").action(ArgAction::Set))
         .arg_registry("Registry to use")
+        .arg(
+            flag(
+                "generate-keypair",
+                "Generate a new keypair for asymmetric tokens (unstable)",
+            )
+            .conflicts_with("token"),
+        )
         .arg(
             Arg::new("args")
                 .help("Additional arguments for the credential provider")
@@ -27,6 +34,16 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         "must not be index URL"
     );
 
+    let generate_keypair = args.flag("generate-keypair");
+    if generate_keypair {
+        gctx.cli_unstable().fail_if_stable_opt_custom_z(
+            "--generate-keypair",
+            10519,
+            "asymmetric-token",
+            gctx.cli_unstable().asymmetric_token,
+        )?;
+    }
+
     let extra_args = args
         .get_many::<String>("args")
         .unwrap_or_default()
@@ -37,6 +54,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         args.get_one::<String>("token").map(|s| s.as_str().into()),
         reg.as_ref(),
         &extra_args,
+        generate_keypair,
     )?;
     Ok(())
 }
@@ -18,20 +18,59 @@ pub fn cli() -> Command {
             "Output information only about the workspace members \
              and don't fetch dependencies",
         ))
+        .arg(
+            flag(
+                "include-feature-graph",
+                "Output which dependency edges and features activated each feature (unstable)",
+            )
+            .conflicts_with("no-deps"),
+        )
+        .arg(
+            opt("only", "Only output these top-level sections (unstable)")
+                .value_name("SECTIONS")
+                .value_delimiter(',')
+                .value_parser(clap::builder::PossibleValuesParser::new(
+                    ops::METADATA_SECTIONS,
+                ))
+                .action(ArgAction::Append),
+        )
+        .arg(
+            flag(
+                "no-feature-resolution",
+                "List the packages of the dependency graph without resolving \
+                 features, leaving `resolve` null (unstable)",
+            )
+            .conflicts_with_all(["filter-platform", "include-feature-graph"]),
+        )
         .arg(
             opt("format-version", "Format version")
                 .value_name("VERSION")
-                .value_parser(["1"]),
+                .value_parser(["1", "2"]),
         )
         .arg_silent_suggestion()
         .arg_features()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help metadata</>` for more detailed information.\n"
         ))
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    let include_feature_graph = args.flag("include-feature-graph");
+    if include_feature_graph {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--include-feature-graph", None)?;
+    }
+    let only = args._values_of("only");
+    if !only.is_empty() {
+        gctx.cli_unstable().fail_if_stable_opt("--only", None)?;
+    }
+    let no_feature_resolution = args.flag("no-feature-resolution");
+    if no_feature_resolution {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--no-feature-resolution", None)?;
+    }
     let ws = args.workspace(gctx)?;
 
     let version = match args.get_one::<String>("format-version") {
@@ -44,15 +83,26 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         }
         Some(version) => version.parse().unwrap(),
     };
+    if version >= 2 {
+        gctx.cli_unstable()
+            .fail_if_stable_opt(&format!("--format-version {version}"), None)?;
+    }
 
     let options = OutputMetadataOptions {
         cli_features: args.cli_features()?,
         no_deps: args.flag("no-deps"),
         filter_platforms: args._values_of("filter-platform"),
+        include_feature_graph,
         version,
+        only,
+        no_feature_resolution,
     };
 
     let result = ops::output_metadata(&ws, &options)?;
-    gctx.shell().print_json(&result)?;
+    if gctx.cli_unstable().unstable_options {
+        gctx.shell().stream_json(&result)?;
+    } else {
+        gctx.shell().print_json(&result)?;
+    }
     Ok(())
 }
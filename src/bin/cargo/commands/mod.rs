@@ -4,6 +4,7 @@ pub fn builtin() -> Vec<Command> {
     vec![
         add::cli(),
         bench::cli(),
+        bisect_dep::cli(),
         build::cli(),
         check::cli(),
         clean::cli(),
@@ -16,11 +17,13 @@ pub fn builtin() -> Vec<Command> {
         help::cli(),
         init::cli(),
         install::cli(),
+        license_report::cli(),
         locate_project::cli(),
         login::cli(),
         logout::cli(),
         metadata::cli(),
         new::cli(),
+        outdated::cli(),
         owner::cli(),
         package::cli(),
         pkgid::cli(),
@@ -28,6 +31,7 @@ pub fn builtin() -> Vec<Command> {
         read_manifest::cli(),
         remove::cli(),
         report::cli(),
+        resolve::cli(),
         run::cli(),
         rustc::cli(),
         rustdoc::cli(),
@@ -37,8 +41,10 @@ pub fn builtin() -> Vec<Command> {
         uninstall::cli(),
         update::cli(),
         vendor::cli(),
+        verify_lockfile::cli(),
         verify_project::cli(),
         version::cli(),
+        workspace::cli(),
         yank::cli(),
     ]
 }
@@ -49,6 +55,7 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
     let f = match cmd {
         "add" => add::exec,
         "bench" => bench::exec,
+        "bisect-dep" => bisect_dep::exec,
         "build" => build::exec,
         "check" => check::exec,
         "clean" => clean::exec,
@@ -61,11 +68,13 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
         "help" => help::exec,
         "init" => init::exec,
         "install" => install::exec,
+        "license-report" => license_report::exec,
         "locate-project" => locate_project::exec,
         "login" => login::exec,
         "logout" => logout::exec,
         "metadata" => metadata::exec,
         "new" => new::exec,
+        "outdated" => outdated::exec,
         "owner" => owner::exec,
         "package" => package::exec,
         "pkgid" => pkgid::exec,
@@ -73,6 +82,7 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
         "read-manifest" => read_manifest::exec,
         "remove" => remove::exec,
         "report" => report::exec,
+        "resolve" => resolve::exec,
         "run" => run::exec,
         "rustc" => rustc::exec,
         "rustdoc" => rustdoc::exec,
@@ -82,8 +92,10 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
         "uninstall" => uninstall::exec,
         "update" => update::exec,
         "vendor" => vendor::exec,
+        "verify-lockfile" => verify_lockfile::exec,
         "verify-project" => verify_project::exec,
         "version" => version::exec,
+        "workspace" => workspace::exec,
         "yank" => yank::exec,
         _ => return None,
     };
@@ -92,9 +104,11 @@ pub fn builtin_exec(cmd: &str) -> Option<Exec> {
 
 pub mod add;
 pub mod bench;
+pub mod bisect_dep;
 pub mod build;
 pub mod check;
 pub mod clean;
+pub mod clippy;
 pub mod config;
 pub mod doc;
 pub mod fetch;
@@ -104,11 +118,13 @@ pub mod git_checkout;
 pub mod help;
 pub mod init;
 pub mod install;
+pub mod license_report;
 pub mod locate_project;
 pub mod login;
 pub mod logout;
 pub mod metadata;
 pub mod new;
+pub mod outdated;
 pub mod owner;
 pub mod package;
 pub mod pkgid;
@@ -116,6 +132,7 @@ pub mod publish;
 pub mod read_manifest;
 pub mod remove;
 pub mod report;
+pub mod resolve;
 pub mod run;
 pub mod rustc;
 pub mod rustdoc;
@@ -125,6 +142,8 @@ pub mod tree;
 pub mod uninstall;
 pub mod update;
 pub mod vendor;
+pub mod verify_lockfile;
 pub mod verify_project;
 pub mod version;
+pub mod workspace;
 pub mod yank;
@@ -0,0 +1,48 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, OutdatedFormat, OutdatedOptions};
+
+pub fn cli() -> Command {
+    subcommand("outdated")
+        .about("Show dependencies with newer versions in the registry (unstable)")
+        .arg(
+            opt("format", "Output format of the report")
+                .value_name("FMT")
+                .value_parser(["human", "json"])
+                .default_value("human"),
+        )
+        .arg(flag(
+            "ignore-rust-version",
+            "Also suggest versions needing a newer Rust than the `rust-version` of the package",
+        ))
+        .arg_silent_suggestion()
+        .arg_package_spec_no_all(
+            "Package to show the outdated dependencies of",
+            "Show the outdated dependencies of all packages in the workspace",
+            "Exclude specific workspace members",
+        )
+        .arg_manifest_path()
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "outdated",
+        None,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    let ws = args.workspace(gctx)?;
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("json") => OutdatedFormat::Json,
+        _ => OutdatedFormat::Human,
+    };
+    let opts = OutdatedOptions {
+        gctx,
+        packages: args.packages_from_flags()?,
+        format,
+        ignore_rust_version: args.flag("ignore-rust-version"),
+    };
+    ops::outdated(&ws, &opts)?;
+    Ok(())
+}
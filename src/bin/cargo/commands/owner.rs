@@ -1,6 +1,6 @@
 use crate::command_prelude::*;
 
-use cargo::ops::{self, OwnersOptions};
+use cargo::ops::{self, OwnersOptions, RegistryMessageFormat};
 use cargo_credential::Secret;
 
 pub fn cli() -> Command {
@@ -23,7 +23,21 @@ pub fn cli() -> Command {
             )
             .short('r'),
         )
+        .arg(
+            opt(
+                "role",
+                "Role to invite the owners with, if the registry supports it (unstable)",
+            )
+            .value_name("ROLE")
+            .requires("add"),
+        )
         .arg(flag("list", "List owners of a crate").short('l'))
+        .arg(
+            opt("message-format", "Output representation (unstable)")
+                .value_name("FMT")
+                .value_parser(["human", "json"])
+                .default_value("human"),
+        )
         .arg_index("Registry index URL to modify owners for")
         .arg_registry("Registry to modify owners for")
         .arg(opt("token", "API token to use when authenticating").value_name("TOKEN"))
@@ -34,6 +48,18 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    let role = args.get_one::<String>("role").cloned();
+    let format = match args.get_one::<String>("message-format").map(String::as_str) {
+        Some("json") => RegistryMessageFormat::Json,
+        _ => RegistryMessageFormat::Human,
+    };
+    if role.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt("--role", None)?;
+    }
+    if format == RegistryMessageFormat::Json {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--message-format=json", None)?;
+    }
     let opts = OwnersOptions {
         krate: args.get_one::<String>("crate").cloned(),
         token: args.get_one::<String>("token").cloned().map(Secret::from),
@@ -41,10 +67,12 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         to_add: args
             .get_many::<String>("add")
             .map(|xs| xs.cloned().collect()),
+        role,
         to_remove: args
             .get_many::<String>("remove")
             .map(|xs| xs.cloned().collect()),
         list: args.flag("list"),
+        format,
     };
     ops::modify_owners(gctx, &opts)?;
     Ok(())
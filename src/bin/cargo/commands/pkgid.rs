@@ -1,12 +1,35 @@
 use crate::command_prelude::*;
 
-use cargo::ops;
+use cargo::ops::{self, PkgidFormat, PkgidOptions};
 use cargo::util::print_available_packages;
 
 pub fn cli() -> Command {
     subcommand("pkgid")
         .about("Print a fully qualified package specification")
-        .arg(Arg::new("spec").value_name("SPEC").action(ArgAction::Set))
+        .arg(
+            Arg::new("spec")
+                .value_name("SPEC")
+                .num_args(0..)
+                .action(ArgAction::Append),
+        )
+        .arg(flag(
+            "all",
+            "Print the specification of every package of the lock file (unstable)",
+        ))
+        .arg(
+            multi_opt(
+                "for-path",
+                "PATH",
+                "Print the specification of the package owning a path (unstable)",
+            )
+            .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            opt("format", "Output format (unstable)")
+                .value_name("FMT")
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
         .arg_silent_suggestion()
         .arg_package("Argument to get the package ID specifier for")
         .arg_manifest_path()
@@ -27,8 +50,44 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     if args.is_present_with_zero_values("package") {
         print_available_packages(&ws)?
     }
-    let spec = args
-        .get_one::<String>("spec")
+
+    let specs = args._values_of("spec");
+    let paths: Vec<_> = args
+        .get_many::<std::path::PathBuf>("for-path")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let all = args.flag("all");
+    let json = args.get_one::<String>("format").map(String::as_str) == Some("json");
+    let unstable_flag = if specs.len() > 1 {
+        Some("<SPEC>...")
+    } else if all {
+        Some("--all")
+    } else if !paths.is_empty() {
+        Some("--for-path")
+    } else if json {
+        Some("--format json")
+    } else {
+        None
+    };
+    if let Some(flag) = unstable_flag {
+        gctx.cli_unstable().fail_if_stable_opt(flag, None)?;
+        let opts = PkgidOptions {
+            specs,
+            all,
+            paths,
+            format: if json {
+                PkgidFormat::Json
+            } else {
+                PkgidFormat::Text
+            },
+        };
+        ops::pkgids(&ws, &opts)?;
+        return Ok(());
+    }
+
+    let spec = specs
+        .first()
         .or_else(|| args.get_one::<String>("package"))
         .map(String::as_str);
     let spec = ops::pkgid(&ws, spec)?;
@@ -1,6 +1,6 @@
 use crate::command_prelude::*;
 
-use cargo::ops::{self, PublishOpts};
+use cargo::ops::{self, PublishOpts, RegistryMessageFormat};
 
 pub fn cli() -> Command {
     subcommand("publish")
@@ -17,6 +17,20 @@ pub fn cli() -> Command {
             "allow-dirty",
             "Allow dirty working directories to be packaged",
         ))
+        .arg(
+            opt(
+                "wait-timeout",
+                "Seconds to wait for the package to be available, 0 to not wait (unstable)",
+            )
+            .value_name("SECONDS")
+            .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            opt("message-format", "Output representation (unstable)")
+                .value_name("FMT")
+                .value_parser(["human", "json"])
+                .default_value("human"),
+        )
         .arg_silent_suggestion()
         .arg_package("Package to publish")
         .arg_features()
@@ -40,6 +54,24 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         .into());
     }
 
+    let wait_timeout = args.get_one::<u64>("wait-timeout").copied();
+    if wait_timeout.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt_custom_z(
+            "--wait-timeout",
+            None,
+            "publish-timeout",
+            gctx.cli_unstable().publish_timeout,
+        )?;
+    }
+    let format = match args.get_one::<String>("message-format").map(String::as_str) {
+        Some("json") => RegistryMessageFormat::Json,
+        _ => RegistryMessageFormat::Human,
+    };
+    if format == RegistryMessageFormat::Json {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--message-format=json", None)?;
+    }
+
     ops::publish(
         &ws,
         &PublishOpts {
@@ -56,6 +88,8 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             keep_going: args.keep_going(),
             dry_run: args.dry_run(),
             cli_features: args.cli_features()?,
+            wait_timeout,
+            format,
         },
     )?;
     Ok(())
@@ -1,10 +1,13 @@
 use cargo::core::dependency::DepKind;
+use cargo::core::Package;
 use cargo::core::PackageIdSpec;
 use cargo::core::PackageIdSpecQuery;
 use cargo::core::Resolve;
 use cargo::core::Workspace;
 use cargo::ops::cargo_remove::remove;
+use cargo::ops::cargo_remove::remove_unused;
 use cargo::ops::cargo_remove::RemoveOptions;
+use cargo::ops::cargo_remove::RemoveUnusedOptions;
 use cargo::ops::resolve_ws;
 use cargo::util::command_prelude::*;
 use cargo::util::print_available_packages;
@@ -20,12 +23,25 @@ pub fn cli() -> clap::Command {
         // Subcommand aliases are handled in `aliased_command()`.
         // .alias("rm")
         .about("Remove dependencies from a Cargo.toml manifest file")
-        .args([clap::Arg::new("dependencies")
-            .action(clap::ArgAction::Append)
-            .required(true)
-            .num_args(1..)
-            .value_name("DEP_ID")
-            .help("Dependencies to be removed")])
+        .args([
+            clap::Arg::new("dependencies")
+                .action(clap::ArgAction::Append)
+                .required_unless_present("unused")
+                .num_args(1..)
+                .value_name("DEP_ID")
+                .help("Dependencies to be removed"),
+            flag(
+                "unused",
+                "Remove dependencies that no target uses (unstable)",
+            )
+            .long_help(
+                "Remove dependencies that no target uses (unstable)
+
+Checks all targets of the selected packages and removes the dependencies \
+that rustc reports as unused by every one of them.",
+            )
+            .conflicts_with_all(["dependencies", "section", "target"]),
+        ])
         .arg_dry_run("Don't actually write the manifest")
         .arg_silent_suggestion()
         .next_help_heading("Section")
@@ -67,6 +83,52 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
 
     let packages = args.packages_from_flags()?;
     let packages = packages.get_packages(&workspace)?;
+
+    if args.flag("unused") {
+        gctx.cli_unstable().fail_if_stable_opt("--unused", None)?;
+        let options = RemoveUnusedOptions {
+            gctx,
+            packages,
+            dry_run,
+        };
+        remove_unused(&workspace, &options)?;
+    } else {
+        remove_dependencies(gctx, args, packages, dry_run)?;
+    }
+
+    if !dry_run {
+        // Clean up the workspace
+        gc_workspace(&workspace)?;
+
+        // Reload the workspace since we've changed dependencies
+        let ws = args.workspace(gctx)?;
+        let resolve = {
+            // HACK: Avoid unused patch warnings by temporarily changing the verbosity.
+            // In rare cases, this might cause index update messages to not show up
+            let verbosity = ws.gctx().shell().verbosity();
+            ws.gctx()
+                .shell()
+                .set_verbosity(cargo::core::Verbosity::Quiet);
+            let resolve = resolve_ws(&ws, dry_run);
+            ws.gctx().shell().set_verbosity(verbosity);
+            resolve?.1
+        };
+
+        // Attempt to gc unused patches and re-resolve if anything is removed
+        if gc_unused_patches(&workspace, &resolve)? {
+            let ws = args.workspace(gctx)?;
+            resolve_ws(&ws, dry_run)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_dependencies(
+    gctx: &GlobalContext,
+    args: &ArgMatches,
+    packages: Vec<&Package>,
+    dry_run: bool,
+) -> CliResult {
     let spec = match packages.len() {
         0 => {
             return Err(CliError::new(
@@ -93,7 +155,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
 
     let dependencies = args
         .get_many::<String>("dependencies")
-        .expect("required(true)")
+        .expect("required unless `--unused`")
         .cloned()
         .collect::<Vec<_>>();
 
@@ -107,31 +169,6 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         dry_run,
     };
     remove(&options)?;
-
-    if !dry_run {
-        // Clean up the workspace
-        gc_workspace(&workspace)?;
-
-        // Reload the workspace since we've changed dependencies
-        let ws = args.workspace(gctx)?;
-        let resolve = {
-            // HACK: Avoid unused patch warnings by temporarily changing the verbosity.
-            // In rare cases, this might cause index update messages to not show up
-            let verbosity = ws.gctx().shell().verbosity();
-            ws.gctx()
-                .shell()
-                .set_verbosity(cargo::core::Verbosity::Quiet);
-            let resolve = resolve_ws(&ws, dry_run);
-            ws.gctx().shell().set_verbosity(verbosity);
-            resolve?.1
-        };
-
-        // Attempt to gc unused patches and re-resolve if anything is removed
-        if gc_unused_patches(&workspace, &resolve)? {
-            let ws = args.workspace(gctx)?;
-            resolve_ws(&ws, dry_run)?;
-        }
-    }
     Ok(())
 }
 
@@ -1,6 +1,7 @@
 use crate::command_prelude::*;
 use cargo::core::compiler::future_incompat::{OnDiskReports, REPORT_PREAMBLE};
 use cargo::drop_println;
+use cargo::ops;
 
 pub fn cli() -> Command {
     subcommand("report")
@@ -23,11 +24,19 @@ pub fn cli() -> Command {
                 )
                 .arg_package("Package to display a report for"),
         )
+        .subcommand(
+            subcommand("advisories")
+                .about(
+                    "Reports packages of the lock file affected by security advisories (unstable)",
+                )
+                .arg_manifest_path(),
+        )
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     match args.subcommand() {
         Some(("future-incompatibilities", args)) => report_future_incompatibilities(gctx, args),
+        Some(("advisories", args)) => report_advisories(gctx, args),
         Some((cmd, _)) => {
             unreachable!("unexpected command {}", cmd)
         }
@@ -49,3 +58,16 @@ fn report_future_incompatibilities(gctx: &GlobalContext, args: &ArgMatches) -> C
     drop(gctx.shell().print_ansi_stdout(report.as_bytes()));
     Ok(())
 }
+
+fn report_advisories(gctx: &GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "report advisories",
+        None,
+        "advisories",
+        gctx.cli_unstable().advisories,
+    )?;
+    let ws = args.workspace(gctx)?;
+    ops::advisories::report_advisories(&ws)?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, ResolveLockfileFormat, ResolveLockfileOptions};
+
+pub fn cli() -> Command {
+    subcommand("resolve")
+        .about("Resolve dependencies and write the lockfile, without building (unstable)")
+        .arg_dry_run("Print the lockfile to stdout instead of writing it")
+        .arg(
+            opt("format", "Output format for the changes to the lockfile")
+                .value_name("FMT")
+                .value_parser(["human", "json"])
+                .default_value("human"),
+        )
+        .arg_silent_suggestion()
+        .arg_manifest_path()
+        .arg_index_snapshot()
+        .arg_ignore_rust_version_with_help(
+            "Ignore `rust-version` specification in packages (unstable)",
+        )
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "resolve",
+        None,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    if args.honor_rust_version().is_some() {
+        gctx.cli_unstable().fail_if_stable_opt_custom_z(
+            "--ignore-rust-version",
+            9930,
+            "msrv-policy",
+            gctx.cli_unstable().msrv_policy,
+        )?;
+    }
+    let ws = args.workspace(gctx)?;
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("json") => ResolveLockfileFormat::Json,
+        _ => ResolveLockfileFormat::Human,
+    };
+    let opts = ResolveLockfileOptions {
+        gctx,
+        dry_run: args.dry_run(),
+        format,
+    };
+    ops::resolve_lockfile(&ws, &opts)?;
+    Ok(())
+}
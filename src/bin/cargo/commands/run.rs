@@ -1,6 +1,9 @@
+use std::cell::RefCell;
 use std::ffi::OsStr;
 use std::ffi::OsString;
 use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 use crate::command_prelude::*;
 use crate::util::restricted_names::is_glob_pattern;
@@ -24,6 +27,11 @@ pub fn cli() -> Command {
                 .num_args(0..)
                 .trailing_var_arg(true),
         )
+        .arg(flag(
+            "interactive",
+            "Choose the target to run when several match or none does (unstable)",
+        ))
+        .arg_watch()
         .arg_message_format()
         .arg_silent_suggestion()
         .arg_package("Package with the target to run")
@@ -32,12 +40,15 @@ pub fn cli() -> Command {
             "Name of the example target to run",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_parallel()
         .arg_release("Build artifacts in release mode, with optimizations")
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .arg_unit_graph()
         .arg_timings()
@@ -47,10 +58,20 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    let gctx = &*gctx;
+    args.exec_watching(gctx, |watched_files| run(gctx, args, watched_files))
+}
+
+fn run(
+    gctx: &GlobalContext,
+    args: &ArgMatches,
+    watched_files: Option<Rc<RefCell<Vec<PathBuf>>>>,
+) -> CliResult {
     let ws = args.workspace(gctx)?;
 
     let mut compile_opts =
         args.compile_options(gctx, CompileMode::Build, Some(&ws), ProfileChecking::Custom)?;
+    compile_opts.build_config.watched_files = watched_files;
 
     // Disallow `spec` to be an glob pattern
     if let Packages::Packages(opt_in) = &compile_opts.spec {
@@ -83,7 +104,14 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         }
     };
 
-    ops::run(&ws, &compile_opts, &values_os(args, "args")).map_err(|err| to_run_error(gctx, err))
+    let interactive = args.flag("interactive");
+    if interactive {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--interactive", None)?;
+    }
+
+    ops::run(&ws, &compile_opts, &values_os(args, "args"), interactive)
+        .map_err(|err| to_run_error(gctx, err))
 }
 
 /// See also `util/toml/mod.rs`s `is_embedded`
@@ -188,7 +216,7 @@ pub fn exec_manifest_command(gctx: &mut GlobalContext, cmd: &str, args: &[OsStri
         cargo::ops::CompileOptions::new(gctx, cargo::core::compiler::CompileMode::Build)?;
     compile_opts.spec = cargo::ops::Packages::Default;
 
-    cargo::ops::run(&ws, &compile_opts, args).map_err(|err| to_run_error(gctx, err))
+    cargo::ops::run(&ws, &compile_opts, args, false).map_err(|err| to_run_error(gctx, err))
 }
 
 fn suggested_script(cmd: &str) -> Option<String> {
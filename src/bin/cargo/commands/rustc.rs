@@ -44,14 +44,17 @@ pub fn cli() -> Command {
             "Build all targets",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_parallel()
         .arg_release("Build artifacts in release mode, with optimizations")
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Target triple which compiles will be for")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help rustc</>` for more detailed information.\n"
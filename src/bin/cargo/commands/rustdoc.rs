@@ -32,11 +32,13 @@ pub fn cli() -> Command {
             "Build all targets",
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_parallel()
         .arg_release("Build artifacts in release mode, with optimizations")
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg(
             opt("output-format", "The output type to write (unstable)")
                 .value_name("FMT")
@@ -45,6 +47,7 @@ pub fn cli() -> Command {
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help rustdoc</>` for more detailed information.\n"
@@ -81,6 +84,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let doc_opts = DocOptions {
         open_result: args.flag("open"),
         output_format,
+        serve: None,
         compile_opts,
     };
     ops::doc(&ws, &doc_opts)?;
@@ -1,5 +1,8 @@
 use crate::command_prelude::*;
 use cargo::ops;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 pub fn cli() -> Command {
     subcommand("test")
@@ -20,7 +23,26 @@ pub fn cli() -> Command {
         )
         .arg(flag("no-run", "Compile, but don't run tests"))
         .arg(flag("no-fail-fast", "Run all tests regardless of failure"))
+        .arg(flag(
+            "no-capture-json",
+            "Report the results of each test as JSON messages (unstable)",
+        ))
+        .arg(
+            opt(
+                "test-manifest",
+                "Write the test executables and how to run them to PATH, as JSON (unstable)",
+            )
+            .value_name("PATH"),
+        )
+        .arg(
+            opt(
+                "partition",
+                "Only run the test executables of shard M out of N (unstable)",
+            )
+            .value_name("M/N"),
+        )
         .arg_future_incompat_report()
+        .arg_watch()
         .arg_message_format()
         .arg(
             flag(
@@ -51,15 +73,18 @@ pub fn cli() -> Command {
                 .help_heading(heading::TARGET_SELECTION),
         )
         .arg_features()
+        .arg_all_required_features()
         .arg_jobs()
         .arg_unsupported_keep_going()
         .arg_release("Build artifacts in release mode, with optimizations")
         .arg_profile("Build artifacts with the specified profile")
         .arg_target_triple("Build for the target triple")
         .arg_target_dir()
+        .arg_lints_profile()
         .arg_unit_graph()
         .arg_timings()
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help test</>` for more detailed information.\n\
@@ -68,10 +93,20 @@ pub fn cli() -> Command {
 }
 
 pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    let gctx = &*gctx;
+    args.exec_watching(gctx, |watched_files| test(gctx, args, watched_files))
+}
+
+fn test(
+    gctx: &GlobalContext,
+    args: &ArgMatches,
+    watched_files: Option<Rc<RefCell<Vec<PathBuf>>>>,
+) -> CliResult {
     let ws = args.workspace(gctx)?;
 
     let mut compile_opts =
         args.compile_options(gctx, CompileMode::Test, Some(&ws), ProfileChecking::Custom)?;
+    compile_opts.build_config.watched_files = watched_files;
 
     compile_opts.build_config.requested_profile =
         args.get_profile_name("test", ProfileChecking::Custom)?;
@@ -104,9 +139,41 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         compile_opts.filter = ops::CompileFilter::all_test_targets();
     }
 
+    let json_events = args.flag("no-capture-json");
+    if json_events {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--no-capture-json", None)?;
+        if !compile_opts.build_config.emit_json() {
+            return Err(anyhow::format_err!(
+                "`--no-capture-json` requires `--message-format=json`"
+            )
+            .into());
+        }
+    }
+
+    let test_manifest = args.value_of_path("test-manifest", gctx);
+    if test_manifest.is_some() {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--test-manifest", None)?;
+    }
+
+    let partition = args.get_one::<String>("partition");
+    if partition.is_some() {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--partition", None)?;
+    }
+    let partition = partition
+        .map(|partition| partition.parse::<ops::Partition>())
+        .transpose()?;
+
     let ops = ops::TestOptions {
         no_run,
         no_fail_fast: args.flag("no-fail-fast"),
+        json_events,
+        test_manifest,
+        partition,
+        save_baseline: None,
+        baseline: None,
         compile_opts,
     };
 
@@ -37,6 +37,14 @@ pub fn cli() -> Command {
             )
             .short('i'),
         )
+        .arg(
+            multi_opt(
+                "why",
+                "SPEC",
+                "Show only the shortest path(s) from the workspace to the given package (unstable)",
+            )
+            .conflicts_with_all(["invert", "duplicates"]),
+        )
         .arg(multi_opt(
             "prune",
             "SPEC",
@@ -66,6 +74,13 @@ pub fn cli() -> Command {
             .short('d')
             .alias("duplicate"),
         )
+        .arg(
+            flag(
+                "duplicates-features",
+                "Show only packages whose dependents requested different features (unstable)",
+            )
+            .conflicts_with_all(["invert", "why", "duplicates"]),
+        )
         .arg(
             opt("charset", "Character set to use in output")
                 .value_name("CHARSET")
@@ -77,6 +92,24 @@ pub fn cli() -> Command {
                 .short('f')
                 .default_value("{p}"),
         )
+        .arg(
+            opt(
+                "output-format",
+                "Output the tree as text, or the graph as JSON (unstable)",
+            )
+            .value_name("FMT")
+            .value_parser(["text", "json"])
+            .default_value("text"),
+        )
+        .arg(
+            opt(
+                "graph",
+                "Output the graph for a renderer, with nodes labeled by --format (unstable)",
+            )
+            .value_name("FMT")
+            .value_parser(["dot", "mermaid"])
+            .conflicts_with("output-format"),
+        )
         .arg(
             // Backwards compatibility with old cargo-tree.
             flag("version", "Print version info and exit")
@@ -95,6 +128,7 @@ pub fn cli() -> Command {
             Pass `all` to include all targets.",
         )
         .arg_manifest_path()
+        .arg_index_snapshot()
         .after_help(color_print::cstr!(
             "Run `<cyan,bold>cargo help tree</>` for more detailed information.\n"
         ))
@@ -157,9 +191,18 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     let target = tree::Target::from_cli(targets);
 
     let (edge_kinds, no_proc_macro) = parse_edge_kinds(gctx, args)?;
-    let graph_features = edge_kinds.contains(&EdgeKind::Feature);
+    let duplicates_features = args.flag("duplicates-features");
+    if duplicates_features {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--duplicates-features", None)?;
+    }
+    let graph_features = edge_kinds.contains(&EdgeKind::Feature) || duplicates_features;
 
     let pkgs_to_prune = args._values_of("prune");
+    let why = args._values_of("why");
+    if !why.is_empty() {
+        gctx.cli_unstable().fail_if_stable_opt("--why", None)?;
+    }
 
     let packages = args.packages_from_flags()?;
     let mut invert = args
@@ -209,20 +252,48 @@ subtree of the package given to -p.\n\
             Charset::Ascii => gctx.shell().set_unicode(false)?,
         }
     }
+    let output_format = match args.get_one::<String>("output-format").unwrap().as_str() {
+        "json" => {
+            gctx.cli_unstable()
+                .fail_if_stable_opt("--output-format json", None)?;
+            tree::OutputFormat::Json
+        }
+        _ => tree::OutputFormat::Text,
+    };
+    let output_format = match args.get_one::<String>("graph").map(String::as_str) {
+        Some(graph) => {
+            gctx.cli_unstable().fail_if_stable_opt("--graph", None)?;
+            match graph {
+                "dot" => tree::OutputFormat::Dot,
+                _ => tree::OutputFormat::Mermaid,
+            }
+        }
+        None => output_format,
+    };
+    if output_format == tree::OutputFormat::Json && duplicates_features {
+        return Err(format_err!(
+            "the `--output-format json` flag does not support `--duplicates-features`"
+        )
+        .into());
+    }
+
     let opts = tree::TreeOptions {
         cli_features: args.cli_features()?,
         packages,
         target,
         edge_kinds,
         invert,
+        why,
         pkgs_to_prune,
         prefix,
         no_dedupe,
         duplicates: args.flag("duplicates"),
+        duplicates_features,
         format: args.get_one::<String>("format").cloned().unwrap(),
         graph_features,
         max_display_depth: args.value_of_u32("depth")?.unwrap_or(u32::MAX),
         no_proc_macro,
+        output_format,
     };
 
     if opts.graph_features && opts.duplicates {
@@ -7,6 +7,11 @@ pub fn cli() -> Command {
         .about("Remove a Rust binary")
         .arg(Arg::new("spec").value_name("SPEC").num_args(0..))
         .arg(opt("root", "Directory to uninstall packages from").value_name("DIR"))
+        .arg(flag(
+            "unused-deps",
+            "Also remove the tracking entries and cached builds \
+             that no installed package uses anymore (unstable)",
+        ))
         .arg_silent_suggestion()
         .arg_package_spec_simple("Package to uninstall")
         .arg(
@@ -29,11 +34,24 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         .into());
     }
 
-    let specs = args
+    let unused_deps = args.flag("unused-deps");
+    if unused_deps {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--unused-deps", None)?;
+    }
+
+    let specs: Vec<_> = args
         .get_many::<String>("spec")
         .unwrap_or_else(|| args.get_many::<String>("package").unwrap_or_default())
         .map(String::as_str)
         .collect();
-    ops::uninstall(root, specs, &values(args, "bin"), gctx)?;
+    // With `--unused-deps` alone, only the unused entries are removed rather
+    // than the package in the current directory.
+    if !unused_deps || !specs.is_empty() {
+        ops::uninstall(root, specs, &values(args, "bin"), gctx)?;
+    }
+    if unused_deps {
+        ops::uninstall_unused(root, gctx)?;
+    }
     Ok(())
 }
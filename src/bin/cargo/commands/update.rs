@@ -33,6 +33,7 @@ pub fn cli() -> Command {
         .arg(
             opt("precise", "Update [SPEC] to exactly PRECISE")
                 .value_name("PRECISE")
+                .action(ArgAction::Append)
                 .requires("package-group"),
         )
         .arg(
@@ -42,6 +43,15 @@ pub fn cli() -> Command {
             )
             .short('b'),
         )
+        .arg(
+            opt(
+                "lockfile-version",
+                "Rewrite the lock file using format VERSION (unstable)",
+            )
+            .value_name("VERSION")
+            .value_parser(clap::value_parser!(u32)),
+        )
+        .arg_minimal_versions()
         .arg_silent_suggestion()
         .arg(
             flag("workspace", "Only update the workspace packages")
@@ -49,6 +59,7 @@ pub fn cli() -> Command {
                 .help_heading(heading::PACKAGE_SELECTION),
         )
         .arg_manifest_path()
+        .arg_index_snapshot()
         .arg_ignore_rust_version_with_help(
             "Ignore `rust-version` specification in packages (unstable)",
         )
@@ -67,7 +78,14 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         )?;
     }
 
+    if args.contains_id("lockfile-version") {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--lockfile-version", None)?;
+    }
+
     let mut ws = args.workspace(gctx)?;
+    ws.set_resolve_minimal_versions(args.flag("minimal-versions"));
+    ws.set_resolve_direct_minimal_versions(args.flag("direct-minimal-versions"));
 
     if args.is_present_with_zero_values("package") {
         print_available_packages(&ws)?;
@@ -89,12 +107,23 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         }
     }
 
+    let precise: Vec<_> = args
+        .get_many::<String>("precise")
+        .unwrap_or_default()
+        .map(String::as_str)
+        .collect();
+    if precise.len() > 1 {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--precise <PRECISE>...", None)?;
+    }
+
     let update_opts = UpdateOptions {
         recursive: args.flag("recursive"),
-        precise: args.get_one::<String>("precise").map(String::as_str),
+        precise,
         to_update,
         dry_run: args.dry_run(),
         workspace: args.flag("workspace"),
+        lockfile_version: args.get_one::<u32>("lockfile-version").copied(),
         gctx,
     };
 
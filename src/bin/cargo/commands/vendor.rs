@@ -32,6 +32,14 @@ pub fn cli() -> Command {
             "versioned-dirs",
             "Always include version in subdir name",
         ))
+        .arg(
+            opt(
+                "summary",
+                "Write a JSON summary of the vendored changes to PATH (unstable)",
+            )
+            .value_name("PATH")
+            .value_parser(value_parser!(PathBuf)),
+        )
         .arg(unsupported("no-merge-sources"))
         .arg(unsupported("relative-path"))
         .arg(unsupported("only-git-deps"))
@@ -63,6 +71,16 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         gctx.values_mut()?.remove("source");
     }
 
+    let summary = args.get_one::<PathBuf>("summary").cloned();
+    if summary.is_some() {
+        gctx.cli_unstable().fail_if_stable_opt("--summary", None)?;
+        if !args.flag("versioned-dirs") {
+            return Err(
+                anyhow::format_err!("the `--summary` flag requires `--versioned-dirs`").into(),
+            );
+        }
+    }
+
     let ws = args.workspace(gctx)?;
     let path = args
         .get_one::<PathBuf>("path")
@@ -74,6 +92,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
             no_delete: args.flag("no-delete"),
             destination: &path,
             versioned_dirs: args.flag("versioned-dirs"),
+            summary,
             extra: args
                 .get_many::<PathBuf>("tomls")
                 .unwrap_or_default()
@@ -0,0 +1,36 @@
+use crate::command_prelude::*;
+
+use cargo::ops::{self, VerifyLockfileFormat, VerifyLockfileOptions};
+
+pub fn cli() -> Command {
+    subcommand("verify-lockfile")
+        .about("Check that Cargo.lock is consistent with the manifests and registries (unstable)")
+        .arg(
+            opt("format", "Output format for the verification report")
+                .value_name("FMT")
+                .value_parser(["human", "json"])
+                .default_value("human"),
+        )
+        .arg_silent_suggestion()
+        .arg_manifest_path()
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "verify-lockfile",
+        None,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    let ws = args.workspace(gctx)?;
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("json") => VerifyLockfileFormat::Json,
+        _ => VerifyLockfileFormat::Human,
+    };
+    let opts = VerifyLockfileOptions { gctx, format };
+    if !ops::verify_lockfile(&ws, &opts)? {
+        return Err(CliError::code(101));
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::command_prelude::*;
+
+use cargo::ops;
+use cargo::ops::cargo_workspace::{self, AddMemberOptions, RemoveMemberOptions};
+
+pub fn cli() -> Command {
+    subcommand("workspace")
+        .about("Manage the members of a workspace (unstable)")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            subcommand("add")
+                .about("Add a package to the workspace members, creating it if needed")
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Directory of the package to add"),
+                )
+                .arg(flag(
+                    "default-member",
+                    "Also add the package to `workspace.default-members`",
+                ))
+                .arg(flag(
+                    "inherit-dependencies",
+                    "Switch dependencies listed in `[workspace.dependencies]` to `workspace = true`",
+                ))
+                .arg_new_opts()
+                .arg_registry("Registry to use when creating the package")
+                .arg_silent_suggestion()
+                .arg_manifest_path(),
+        )
+        .subcommand(
+            subcommand("remove")
+                .about("Remove a package from the workspace members")
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Directory of the package to remove"),
+                )
+                .arg_silent_suggestion()
+                .arg_manifest_path(),
+        )
+}
+
+pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
+    gctx.cli_unstable().fail_if_stable_command(
+        gctx,
+        "workspace",
+        None,
+        "unstable-options",
+        gctx.cli_unstable().unstable_options,
+    )?;
+    match args.subcommand() {
+        Some(("add", args)) => {
+            let ws = args.workspace(gctx)?;
+            let new_opts = args.new_options(gctx)?;
+            if !new_opts.path.join("Cargo.toml").exists() {
+                if new_opts.path.exists() {
+                    ops::init(&new_opts, gctx)?;
+                } else {
+                    ops::new(&new_opts, gctx)?;
+                }
+            }
+            let opts = AddMemberOptions {
+                gctx,
+                path: new_opts.path,
+                default_member: args.flag("default-member"),
+                inherit_dependencies: args.flag("inherit-dependencies"),
+            };
+            cargo_workspace::add_member(&ws, &opts)?;
+        }
+        Some(("remove", args)) => {
+            let ws = args.workspace(gctx)?;
+            let opts = RemoveMemberOptions {
+                gctx,
+                path: args.value_of_path("path", gctx).unwrap(),
+            };
+            cargo_workspace::remove_member(&ws, &opts)?;
+        }
+        Some((cmd, _)) => {
+            unreachable!("unexpected command {}", cmd)
+        }
+        None => {
+            unreachable!("unexpected command")
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::command_prelude::*;
 
-use cargo::ops;
+use cargo::ops::{self, RegistryMessageFormat};
 use cargo_credential::Secret;
 
 pub fn cli() -> Command {
@@ -16,6 +16,12 @@ pub fn cli() -> Command {
             "undo",
             "Undo a yank, putting a version back into the index",
         ))
+        .arg(
+            opt("message-format", "Output representation (unstable)")
+                .value_name("FMT")
+                .value_parser(["human", "json"])
+                .default_value("human"),
+        )
         .arg_index("Registry index URL to yank from")
         .arg_registry("Registry to yank from")
         .arg(opt("token", "API token to use when authenticating").value_name("TOKEN"))
@@ -33,6 +39,14 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
     if version.is_none() {
         return Err(anyhow::format_err!("`--version` is required").into());
     }
+    let format = match args.get_one::<String>("message-format").map(String::as_str) {
+        Some("json") => RegistryMessageFormat::Json,
+        _ => RegistryMessageFormat::Human,
+    };
+    if format == RegistryMessageFormat::Json {
+        gctx.cli_unstable()
+            .fail_if_stable_opt("--message-format=json", None)?;
+    }
 
     ops::yank(
         gctx,
@@ -41,6 +55,7 @@ pub fn exec(gctx: &mut GlobalContext, args: &ArgMatches) -> CliResult {
         args.get_one::<String>("token").cloned().map(Secret::from),
         args.registry_or_index(gctx)?,
         args.flag("undo"),
+        format,
     )?;
     Ok(())
 }
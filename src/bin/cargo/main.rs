@@ -8,7 +8,7 @@ use cargo_util::{ProcessBuilder, ProcessError};
 use cargo_util_schemas::manifest::StringOrVec;
 use std::collections::BTreeMap;
 use std::env;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -158,6 +158,195 @@ fn aliased_command(gctx: &GlobalContext, command: &str) -> CargoResult<Option<Ve
     Ok(result)
 }
 
+/// Whether `-Zalias-placeholders` is set.
+///
+/// Aliases are expanded before [`GlobalContext::configure`] reads the `-Z`
+/// flags, so this looks at the command line and the `[unstable]` table itself.
+fn alias_placeholders_enabled(gctx: &GlobalContext, args: &ArgMatches) -> bool {
+    gctx.nightly_features_allowed
+        && (args
+            .get_many::<String>("unstable-features")
+            .is_some_and(|mut z| z.any(|z| z == "alias-placeholders"))
+            || matches!(
+                gctx.get::<Option<bool>>("unstable.alias-placeholders"),
+                Ok(Some(true))
+            ))
+}
+
+/// A piece of a word of an alias with `-Zalias-placeholders`.
+#[derive(Debug, PartialEq)]
+enum AliasPart {
+    Literal(String),
+    /// `{N}` or `{N:-default}`, the `N`th of the leading arguments passed to
+    /// the alias that don't start with `-`.
+    Positional(usize, Option<String>),
+    /// `{name}` or `{name:-default}`, the value of the `--name` flag passed
+    /// to the alias.
+    Flag(String, Option<String>),
+}
+
+/// Splits every word of `alias` into its literal text and placeholders.
+fn parse_alias_placeholders(
+    alias_name: &str,
+    alias: &[String],
+) -> CargoResult<Vec<Vec<AliasPart>>> {
+    alias
+        .iter()
+        .map(|word| {
+            parse_alias_word(word).map_err(|e| {
+                anyhow::format_err!("invalid placeholder in `alias.{alias_name}`: {e}")
+            })
+        })
+        .collect()
+}
+
+fn parse_alias_word(word: &str) -> CargoResult<Vec<AliasPart>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = word;
+    while let Some(i) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..i]);
+        let brace = &rest[i..i + 1];
+        rest = &rest[i + 1..];
+        // `{{` and `}}` stand for a literal brace.
+        if let Some(after) = rest.strip_prefix(brace) {
+            literal.push_str(brace);
+            rest = after;
+            continue;
+        }
+        if brace == "}" {
+            anyhow::bail!("unmatched `}}` in `{word}`, use `}}}}` for a literal `}}`");
+        }
+        let Some(end) = rest.find('}') else {
+            anyhow::bail!("unclosed `{{` in `{word}`, use `{{{{` for a literal `{{`");
+        };
+        let placeholder = &rest[..end];
+        rest = &rest[end + 1..];
+        let (key, default) = match placeholder.split_once(":-") {
+            Some((key, default)) => (key, Some(default.to_owned())),
+            None => (placeholder, None),
+        };
+        let part = if !key.is_empty() && key.chars().all(|c| c.is_ascii_digit()) {
+            match key.parse() {
+                Ok(0) | Err(_) => anyhow::bail!(
+                    "`{{{key}}}` in `{word}` is not an argument, they are numbered from 1"
+                ),
+                Ok(index) => AliasPart::Positional(index, default),
+            }
+        } else if key.starts_with(|c: char| c.is_ascii_alphabetic())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            AliasPart::Flag(key.to_owned(), default)
+        } else {
+            anyhow::bail!(
+                "`{{{placeholder}}}` in `{word}` is neither an argument number nor a flag name"
+            );
+        };
+        if !literal.is_empty() {
+            parts.push(AliasPart::Literal(std::mem::take(&mut literal)));
+        }
+        parts.push(part);
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() || parts.is_empty() {
+        parts.push(AliasPart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// Fills in the placeholders of `alias` with the `args` passed to it.
+///
+/// The arguments and flags used by a placeholder are removed, and the others
+/// are appended to the expanded alias.
+fn expand_alias_placeholders(
+    alias_name: &str,
+    alias: Vec<Vec<AliasPart>>,
+    args: Vec<OsString>,
+) -> CargoResult<Vec<OsString>> {
+    let flags: Vec<&str> = alias
+        .iter()
+        .flatten()
+        .filter_map(|part| match part {
+            AliasPart::Flag(name, _) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut flag_values = BTreeMap::new();
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rest.push(arg);
+            rest.extend(args);
+            break;
+        }
+        let Some(flag) = arg.to_str().and_then(|arg| arg.strip_prefix("--")) else {
+            rest.push(arg);
+            continue;
+        };
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(OsString::from(value))),
+            None => (flag, None),
+        };
+        if !flags.contains(&name) {
+            rest.push(arg);
+            continue;
+        }
+        let value = match value {
+            Some(value) => value,
+            None => args.next().ok_or_else(|| {
+                anyhow::format_err!("a value is required for `--{name}` of alias `{alias_name}`")
+            })?,
+        };
+        flag_values.insert(name.to_owned(), value);
+    }
+
+    let leading = rest
+        .iter()
+        .take_while(|arg| !arg.to_string_lossy().starts_with('-'))
+        .count();
+    let mut used = vec![false; leading];
+    let mut expanded = Vec::new();
+    for word in alias {
+        let mut value = OsString::new();
+        for part in word {
+            match part {
+                AliasPart::Literal(literal) => value.push(literal),
+                AliasPart::Positional(index, default) => {
+                    match (rest[..leading].get(index - 1), default) {
+                        (Some(arg), _) => {
+                            used[index - 1] = true;
+                            value.push(arg);
+                        }
+                        (None, Some(default)) => value.push(default),
+                        (None, None) => anyhow::bail!(
+                            "alias `{alias_name}` requires an argument for `{{{index}}}`"
+                        ),
+                    }
+                }
+                AliasPart::Flag(name, default) => match (flag_values.get(&name), default) {
+                    (Some(arg), _) => value.push(arg),
+                    (None, Some(default)) => value.push(default),
+                    (None, None) => anyhow::bail!(
+                        "alias `{alias_name}` requires `--{name} <VALUE>` for `{{{name}}}`"
+                    ),
+                },
+            }
+        }
+        expanded.push(value);
+    }
+    expanded.extend(
+        rest.into_iter()
+            .enumerate()
+            .filter(|(i, _)| !used.get(*i).copied().unwrap_or_default())
+            .map(|(_, arg)| arg),
+    );
+    Ok(expanded)
+}
+
 /// List all runnable commands
 fn list_commands(gctx: &GlobalContext) -> BTreeMap<String, CommandInfo> {
     let prefix = "cargo-";
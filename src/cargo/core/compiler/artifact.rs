@@ -21,7 +21,7 @@ pub fn get_env(
             .iter()
             .filter_map(|f| (f.flavor == FileFlavor::Normal).then(|| &f.path))
         {
-            let artifact_type_upper = unit_artifact_type_name_upper(&unit_dep.unit);
+            let artifact_type_upper = unit_artifact_type_name(&unit_dep.unit).to_uppercase();
             let dep_name = unit_dep.dep_name.unwrap_or(unit_dep.unit.pkg.name());
             let dep_name_upper = dep_name.to_uppercase().replace("-", "_");
 
@@ -70,14 +70,16 @@ pub fn get_env(
     Ok(env)
 }
 
-fn unit_artifact_type_name_upper(unit: &Unit) -> &'static str {
+/// Returns the artifact type of an artifact unit, like `bin` or `cdylib`, as
+/// used in its environment variable names.
+pub fn unit_artifact_type_name(unit: &Unit) -> &'static str {
     match unit.target.kind() {
         TargetKind::Lib(kinds) => match kinds.as_slice() {
-            &[CrateType::Cdylib] => "CDYLIB",
-            &[CrateType::Staticlib] => "STATICLIB",
+            &[CrateType::Cdylib] => "cdylib",
+            &[CrateType::Staticlib] => "staticlib",
             invalid => unreachable!("BUG: artifacts cannot be of type {:?}", invalid),
         },
-        TargetKind::Bin => "BIN",
+        TargetKind::Bin => "bin",
         invalid => unreachable!("BUG: artifacts cannot be of type {:?}", invalid),
     }
 }
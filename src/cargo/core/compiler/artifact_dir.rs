@@ -0,0 +1,80 @@
+//! The manifest of the files copied to the directory given to
+//! `cargo build --artifact-dir`.
+//!
+//! The files themselves are copied by each unit as it finishes, see
+//! `link_targets`. Once the whole build is done, the manifest listing them is
+//! written next to them.
+//!
+//! The serialized format is defined in [`cargo_util_schemas::artifact_dir`], and is versioned
+//! with [`ARTIFACT_MANIFEST_VERSION`].
+
+use std::path::Path;
+
+use cargo_util::{paths, Sha256};
+use cargo_util_schemas::artifact_dir::{
+    Artifact, ArtifactFile, ArtifactManifest, ARTIFACT_MANIFEST_FILE, ARTIFACT_MANIFEST_VERSION,
+};
+
+use super::build_plan::target_kind;
+use super::{BuildRunner, CompileKind, FileFlavor};
+use crate::util::CargoResult;
+
+/// Writes the [`ArtifactManifest`] of the files the build copied to
+/// `export_dir`.
+pub fn write_manifest(build_runner: &BuildRunner<'_, '_>, export_dir: &Path) -> CargoResult<()> {
+    let mut artifacts = Vec::new();
+    for unit in &build_runner.bcx.roots {
+        let mut files = Vec::new();
+        let mut debuginfo = Vec::new();
+        for output in build_runner.outputs(unit)?.iter() {
+            let Some(export_path) = &output.export_path else {
+                continue;
+            };
+            // Like when copying, skip outputs that weren't produced.
+            if !output.path.exists() {
+                continue;
+            }
+            let sha256 = if export_path.is_dir() {
+                None
+            } else {
+                Some(Sha256::new().update_path(export_path)?.finish_hex())
+            };
+            let file = ArtifactFile {
+                path: export_path
+                    .strip_prefix(export_dir)
+                    .unwrap_or(export_path)
+                    .to_path_buf(),
+                sha256,
+            };
+            if output.flavor == FileFlavor::DebugInfo {
+                debuginfo.push(file);
+            } else {
+                files.push(file);
+            }
+        }
+        if files.is_empty() && debuginfo.is_empty() {
+            continue;
+        }
+        artifacts.push(Artifact {
+            package_id: unit.pkg.package_id().to_spec(),
+            target_name: unit.target.name().to_string(),
+            target_kind: target_kind(unit.target.kind()),
+            kind: match unit.kind {
+                CompileKind::Host => None,
+                CompileKind::Target(target) => Some(target.rustc_target().to_string()),
+            },
+            files,
+            debuginfo,
+        });
+    }
+
+    let manifest = ArtifactManifest {
+        version: ARTIFACT_MANIFEST_VERSION,
+        artifacts,
+    };
+    paths::create_dir_all(export_dir)?;
+    paths::write(
+        export_dir.join(ARTIFACT_MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )
+}
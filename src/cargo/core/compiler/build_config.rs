@@ -1,4 +1,5 @@
 use crate::core::compiler::CompileKind;
+use crate::core::{PackageId, PackageIdSpec, PackageIdSpecQuery};
 use crate::util::context::JobsConfig;
 use crate::util::interning::InternedString;
 use crate::util::{CargoResult, GlobalContext, RustfixDiagnosticServer};
@@ -31,6 +32,12 @@ pub struct BuildConfig {
     pub build_plan: bool,
     /// Output the unit graph to stdout instead of actually compiling.
     pub unit_graph: bool,
+    /// Output the environment of every process the build would run to
+    /// stdout instead of actually compiling. Requires `build_plan` as well.
+    pub print_env: Option<PrintEnvFormat>,
+    /// The units `print_env` is limited to, from `--print-env-for`. Empty
+    /// for all of them.
+    pub print_env_for: Vec<PrintEnvSelection>,
     /// An optional override of the rustc process for primary units
     pub primary_unit_rustc: Option<ProcessBuilder>,
     /// A thread used by `cargo fix` to receive messages on a socket regarding
@@ -46,6 +53,37 @@ pub struct BuildConfig {
     pub future_incompat_report: bool,
     /// Which kinds of build timings to output (empty if none).
     pub timing_outputs: Vec<TimingOutput>,
+    /// `true` to check root units with the `unused_crate_dependencies` lint
+    /// and collect the results into [`Compilation::unused_dependencies`].
+    ///
+    /// [`Compilation::unused_dependencies`]: crate::core::compiler::Compilation::unused_dependencies
+    pub unused_dependencies: bool,
+    /// `true` to run build scripts in a sandbox, see `build.sandbox`.
+    pub sandbox: bool,
+    /// Packages besides the workspace members whose `[package.build-sandbox]`
+    /// is honored, see `build.sandbox-trust`.
+    pub sandbox_trust: Vec<PackageIdSpec>,
+    /// Where to collect the files that local units were built from, for
+    /// `--watch`. They are collected even if the build fails, so that fixing
+    /// the error causes a rebuild.
+    pub watched_files: Option<Rc<RefCell<Vec<PathBuf>>>>,
+    /// Lints the workspace members with `clippy-driver`, for `cargo clippy`.
+    pub clippy: Option<ClippyConfig>,
+    /// `true` to only compile the dependencies of the selected packages, not
+    /// the packages themselves.
+    pub only_deps: bool,
+}
+
+/// How `cargo clippy` runs `clippy-driver`.
+#[derive(Debug, Clone)]
+pub struct ClippyConfig {
+    /// The `clippy-driver` executable, used as the workspace wrapper of rustc.
+    pub driver: PathBuf,
+    /// Extra arguments for the linted units, like `-D warnings`.
+    pub args: Vec<String>,
+    /// Only lint the selected packages, compiling the other workspace members
+    /// with rustc.
+    pub no_deps: bool,
 }
 
 fn default_parallelism() -> CargoResult<u32> {
@@ -59,6 +97,8 @@ impl BuildConfig {
     /// configured options are:
     ///
     /// * `build.jobs`
+    /// * `build.sandbox`
+    /// * `build.sandbox-trust`
     /// * `build.target`
     /// * `target.$target.ar`
     /// * `target.$target.linker`
@@ -102,6 +142,25 @@ impl BuildConfig {
             anyhow::bail!("-Zbuild-std requires --target");
         }
 
+        let sandbox = match cfg.sandbox {
+            Some(true) if !gctx.cli_unstable().build_sandbox => {
+                gctx.shell()
+                    .warn("ignoring `build.sandbox` config, pass `-Zbuild-sandbox` to enable it")?;
+                false
+            }
+            sandbox => sandbox.unwrap_or(false),
+        };
+        let sandbox_trust = cfg
+            .sandbox_trust
+            .iter()
+            .flat_map(|specs| specs.as_slice())
+            .map(|spec| {
+                PackageIdSpec::parse(spec).with_context(|| {
+                    format!("invalid package ID spec `{spec}` in `build.sandbox-trust`")
+                })
+            })
+            .collect::<CargoResult<Vec<_>>>()?;
+
         Ok(BuildConfig {
             requested_kinds,
             jobs,
@@ -112,11 +171,19 @@ impl BuildConfig {
             force_rebuild: false,
             build_plan: false,
             unit_graph: false,
+            print_env: None,
+            print_env_for: Vec::new(),
             primary_unit_rustc: None,
             rustfix_diagnostic_server: Rc::new(RefCell::new(None)),
             export_dir: None,
             future_incompat_report: false,
             timing_outputs: Vec::new(),
+            unused_dependencies: false,
+            sandbox,
+            sandbox_trust,
+            watched_files: None,
+            clippy: None,
+            only_deps: false,
         })
     }
 
@@ -153,6 +220,9 @@ pub enum MessageFormat {
         ansi: bool,
     },
     Short,
+    /// Diagnostics are rendered like `Human`, and collected into a SARIF log
+    /// printed to stdout at the end of the build.
+    Sarif,
 }
 
 /// The general "mode" for what to do.
@@ -186,6 +256,9 @@ pub enum CompileMode {
     Docscrape,
     /// A marker for Units that represent the execution of a `build.rs` script.
     RunCustomBuild,
+    /// A marker for Units that represent the execution of a post-build
+    /// script, after the binaries of its package are built.
+    RunPostBuild,
 }
 
 impl ser::Serialize for CompileMode {
@@ -203,6 +276,7 @@ impl ser::Serialize for CompileMode {
             Doctest => "doctest".serialize(s),
             Docscrape => "docscrape".serialize(s),
             RunCustomBuild => "run-custom-build".serialize(s),
+            RunPostBuild => "run-post-build".serialize(s),
         }
     }
 }
@@ -253,6 +327,11 @@ impl CompileMode {
         self == CompileMode::RunCustomBuild
     }
 
+    /// Returns `true` if this is the *execution* of a post-build script.
+    pub fn is_run_post_build(self) -> bool {
+        self == CompileMode::RunPostBuild
+    }
+
     /// Returns `true` if this mode may generate an executable.
     ///
     /// Note that this also returns `true` for building libraries, so you also
@@ -265,6 +344,43 @@ impl CompileMode {
     }
 }
 
+/// Formats of the `--print-env` output.
+#[derive(Clone, Copy, PartialEq, Debug, Eq)]
+pub enum PrintEnvFormat {
+    /// Human-readable `NAME=value` lines
+    Human,
+    /// Machine-readable JSON, one line per process
+    Json,
+}
+
+/// A selection of the units to print the environment of, in the format
+/// `<SPEC>[:<TARGET>]` of `--print-env-for`.
+#[derive(Clone, Debug)]
+pub struct PrintEnvSelection {
+    spec: PackageIdSpec,
+    target: Option<String>,
+}
+
+impl PrintEnvSelection {
+    pub fn parse(selection: &str) -> CargoResult<PrintEnvSelection> {
+        // Target names never contain a `/`, unlike the URLs of specs.
+        let (spec, target) = match selection.rsplit_once(':') {
+            Some((spec, target)) if !target.is_empty() && !target.contains('/') => {
+                (spec, Some(target.to_string()))
+            }
+            _ => (selection, None),
+        };
+        let spec = PackageIdSpec::parse(spec)
+            .with_context(|| format!("invalid `--print-env-for` value `{selection}`"))?;
+        Ok(PrintEnvSelection { spec, target })
+    }
+
+    /// Whether the target `target_name` of the package `pkg` is selected.
+    pub fn matches(&self, pkg: PackageId, target_name: &str) -> bool {
+        self.spec.matches(pkg) && self.target.as_deref().map_or(true, |t| t == target_name)
+    }
+}
+
 /// Kinds of build timings we can output.
 #[derive(Clone, Copy, PartialEq, Debug, Eq, Hash, PartialOrd, Ord)]
 pub enum TimingOutput {
@@ -147,6 +147,17 @@ impl<'a, 'gctx> BuildContext<'a, 'gctx> {
         &self.target_data.info(unit.kind).rustflags
     }
 
+    /// Extra compiler flags from `target.*.profile.<name>.rustflags` to pass
+    /// to `rustc` for a given unit.
+    ///
+    /// These are passed after `profile.<name>.rustflags` but before the flags
+    /// from [`BuildContext::extra_args_for`] and [`BuildContext::rustflags_args`].
+    pub fn target_profile_rustflags_args(&self, unit: &Unit) -> &[String] {
+        self.target_data
+            .info(unit.kind)
+            .profile_rustflags(&unit.profile.name)
+    }
+
     /// Extra compiler flags to pass to `rustdoc` for a given unit.
     ///
     /// Although it depends on the caller, in the current Cargo implementation,
@@ -167,4 +178,36 @@ impl<'a, 'gctx> BuildContext<'a, 'gctx> {
     pub fn extra_args_for(&self, unit: &Unit) -> Option<&Vec<String>> {
         self.extra_compiler_args.get(unit)
     }
+
+    /// Whether `unit` is compiled with the workspace wrapper of rustc, which
+    /// is `clippy-driver` for `cargo clippy`.
+    ///
+    /// With `cargo clippy --no-deps`, only the selected packages are linted.
+    pub fn uses_workspace_wrapper(&self, unit: &Unit) -> bool {
+        if !self.ws.is_member(&unit.pkg) {
+            return false;
+        }
+        match &self.build_config.clippy {
+            Some(clippy) if clippy.no_deps => self.roots.iter().any(|root| root.pkg == unit.pkg),
+            _ => true,
+        }
+    }
+
+    /// Extra arguments of `cargo clippy` for `unit`, if it's linted.
+    pub fn clippy_args_for(&self, unit: &Unit) -> &[String] {
+        match &self.build_config.clippy {
+            Some(clippy) if self.uses_workspace_wrapper(unit) => &clippy.args,
+            _ => &[],
+        }
+    }
+
+    /// Whether `rustc` is asked to report unused dependencies of `unit`.
+    ///
+    /// See [`BuildConfig::unused_dependencies`].
+    pub fn checks_unused_dependencies(&self, unit: &Unit) -> bool {
+        self.build_config.unused_dependencies
+            && !unit.mode.is_doc()
+            && !unit.mode.is_doc_test()
+            && self.roots.contains(unit)
+    }
 }
@@ -12,7 +12,7 @@ use crate::core::compiler::{
     BuildOutput, BuildRunner, CompileKind, CompileMode, CompileTarget, CrateType,
 };
 use crate::core::{Dependency, Package, Target, TargetKind, Workspace};
-use crate::util::context::{GlobalContext, StringList, TargetConfig};
+use crate::util::context::{GlobalContext, StringList, TargetConfig, TargetProfileConfig};
 use crate::util::interning::InternedString;
 use crate::util::{CargoResult, Rustc};
 use anyhow::Context as _;
@@ -21,6 +21,7 @@ use cargo_util::{paths, ProcessBuilder};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::str::{self, FromStr};
 
@@ -55,6 +56,9 @@ pub struct TargetInfo {
     pub rustflags: Vec<String>,
     /// Extra flags to pass to `rustdoc`, see [`extra_args`].
     pub rustdocflags: Vec<String>,
+    /// Extra flags to pass to `rustc` keyed by profile name, see
+    /// [`profile_rustflags_from_target`].
+    pub profile_rustflags: BTreeMap<String, Vec<String>>,
     /// Whether or not rustc (stably) supports the `--check-cfg` flag.
     ///
     /// Can be removed once the minimum supported rustc version of Cargo is
@@ -321,6 +325,13 @@ impl TargetInfo {
                     kind,
                     Flags::Rustdoc,
                 )?,
+                profile_rustflags: profile_rustflags_from_target(
+                    gctx,
+                    requested_kinds,
+                    &rustc.host,
+                    &cfg,
+                    kind,
+                )?,
                 cfg,
                 support_split_debuginfo,
                 support_check_cfg,
@@ -340,6 +351,15 @@ impl TargetInfo {
         true
     }
 
+    /// Extra flags from `target.*.profile.<name>.rustflags` for the profile
+    /// with the given name.
+    pub fn profile_rustflags(&self, profile: &str) -> &[String] {
+        self.profile_rustflags
+            .get(profile)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
     /// All the target [`Cfg`] settings.
     pub fn cfg(&self) -> &[Cfg] {
         &self.cfg
@@ -545,7 +565,8 @@ impl TargetInfo {
             CompileMode::Doc { .. }
             | CompileMode::Doctest
             | CompileMode::Docscrape
-            | CompileMode::RunCustomBuild => {
+            | CompileMode::RunCustomBuild
+            | CompileMode::RunPostBuild => {
                 panic!("asked for rustc output for non-rustc mode")
             }
         }
@@ -795,8 +816,18 @@ fn rustflags_from_target(
         CompileKind::Host => host_triple,
         CompileKind::Target(target) => target.short_name(),
     };
+    // The rustflags of a `[target-alias]` used for the target take precedence.
+    let alias_rustflags = match (kind, flag) {
+        (CompileKind::Target(_), Flags::Rust) => gctx
+            .target_aliases()
+            .get(target)
+            .and_then(|(_, alias)| alias.rustflags.as_ref().map(|v| v.val.clone())),
+        _ => None,
+    };
     let key = format!("target.{}.{}", target, flag.as_key());
-    if let Some(args) = gctx.get::<Option<StringList>>(&key)? {
+    if let Some(args) = alias_rustflags {
+        rustflags.extend(args.as_slice().iter().cloned());
+    } else if let Some(args) = gctx.get::<Option<StringList>>(&key)? {
         rustflags.extend(args.as_slice().iter().cloned());
     }
     // ...including target.'cfg(...)'.rustflags
@@ -826,6 +857,75 @@ fn rustflags_from_target(
     }
 }
 
+/// Gets per-profile compiler flags from `[target]` sections in the config.
+///
+/// The locations are, in this order:
+///
+///  - `target.*.profile.<name>.rustflags`
+///  - `target.cfg(..).profile.<name>.rustflags`
+///
+/// Unlike [`extra_args`], these are layered on top of the other sources
+/// rather than replacing them: `RUSTFLAGS` or `build.rustflags` being set
+/// doesn't cause them to be ignored. They are passed right after
+/// `profile.<name>.rustflags`, so later flags take precedence.
+///
+/// Host artifacts follow the same rules as in [`extra_args`], except there is
+/// no `[host]` equivalent, so they get nothing unless `target-applies-to-host`
+/// allows it.
+///
+/// Requires `-Zprofile-rustflags`.
+fn profile_rustflags_from_target(
+    gctx: &GlobalContext,
+    requested_kinds: &[CompileKind],
+    host_triple: &str,
+    target_cfg: &[Cfg],
+    kind: CompileKind,
+) -> CargoResult<BTreeMap<String, Vec<String>>> {
+    let mut result: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    if kind.is_host() && !(gctx.target_applies_to_host()? && requested_kinds == [CompileKind::Host])
+    {
+        return Ok(result);
+    }
+
+    let target = match &kind {
+        CompileKind::Host => host_triple,
+        CompileKind::Target(target) => target.short_name(),
+    };
+    let target_profiles = gctx.target_cfg_triple(target)?.profile;
+    if !gctx.cli_unstable().profile_rustflags {
+        if target_profiles.is_some() {
+            gctx.shell().warn(format!(
+                "unused key `profile` in [target] config table `{}`\n\
+                 note: `profile` tables in [target] require `-Zprofile-rustflags`",
+                target
+            ))?;
+        }
+        return Ok(result);
+    }
+
+    let mut add = |profiles: &BTreeMap<String, TargetProfileConfig>| {
+        for (name, profile) in profiles {
+            if let Some(rustflags) = &profile.rustflags {
+                result
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(rustflags.val.as_slice().iter().cloned());
+            }
+        }
+    };
+
+    if let Some(profiles) = &target_profiles {
+        add(profiles);
+    }
+    gctx.target_cfgs()?
+        .iter()
+        .filter(|(key, _cfg)| CfgExpr::matches_key(key, target_cfg))
+        .filter_map(|(_key, cfg)| cfg.profile.as_ref())
+        .for_each(|profiles| add(profiles));
+
+    Ok(result)
+}
+
 /// Gets compiler flags from `[host]` section in the config.
 /// See [`extra_args`] for more.
 fn rustflags_from_host(
@@ -5,105 +5,188 @@
 //! shared with an external build system. Each Invocation in the BuildPlan comprises a single
 //! subprocess and defines the build environment, the outputs produced by the subprocess, and the
 //! dependencies on other Invocations.
+//!
+//! The serialized format is defined in [`cargo_util_schemas::build_plan`], and is versioned with
+//! [`BUILD_PLAN_VERSION`].
+//!
+//! `--print-env` reuses the same dry run, printing only the environment of each invocation along
+//! with that of the runners of the executables the build produces. The variables build scripts
+//! set with `cargo::rustc-env` are only known for the build scripts whose previous run is reused,
+//! the environment of the other processes they apply to is marked as partial.
 
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use cargo_util_schemas::build_plan::{self, Invocation, LinkArg, BUILD_PLAN_VERSION};
+use cargo_util_schemas::core::PackageIdSpec;
 use serde::Serialize;
 
 use super::build_runner::OutputFile;
-use super::{BuildRunner, CompileKind, CompileMode, Unit};
-use crate::core::TargetKind;
+use super::custom_build::{BuildOutput, BuildScriptOutputs, LinkArgTarget};
+use super::{
+    BuildRunner, CompileKind, CompileMode, Metadata, PrintEnvFormat, PrintEnvSelection, Unit,
+};
+use crate::core::{PackageId, TargetKind};
 use crate::util::{internal, CargoResult, GlobalContext};
 use cargo_util::ProcessBuilder;
 
-#[derive(Debug, Serialize)]
-struct Invocation {
-    package_name: String,
-    package_version: semver::Version,
-    target_kind: TargetKind,
-    kind: CompileKind,
-    compile_mode: CompileMode,
-    deps: Vec<usize>,
-    outputs: Vec<PathBuf>,
-    links: BTreeMap<PathBuf, PathBuf>,
-    program: String,
-    args: Vec<String>,
-    env: BTreeMap<String, String>,
-    cwd: Option<PathBuf>,
-}
-
 #[derive(Debug)]
 pub struct BuildPlan {
     invocation_map: BTreeMap<String, usize>,
-    plan: SerializedBuildPlan,
+    plan: build_plan::BuildPlan,
+    /// The invocations running build scripts, with the metadata of their
+    /// output and the root of their package.
+    build_scripts: BTreeMap<usize, (Metadata, PathBuf)>,
+    /// The package of each invocation.
+    package_ids: Vec<PackageId>,
+    /// Processes running the executables of the build, for `--print-env`,
+    /// and whether their environment is partial.
+    runners: Vec<(PackageId, Invocation, bool)>,
 }
 
-#[derive(Debug, Serialize)]
-struct SerializedBuildPlan {
-    invocations: Vec<Invocation>,
-    inputs: Vec<PathBuf>,
+/// A line of the `--print-env=json` output.
+#[derive(Serialize)]
+struct SerializedEnv<'a> {
+    /// What the process is, like `"rustc"`, `"build-script"` or `"runner"`.
+    process: &'static str,
+    package_id: &'a PackageIdSpec,
+    target_name: &'a str,
+    target_kind: &'a [String],
+    kind: &'a Option<String>,
+    compile_mode: &'a str,
+    program: &'a str,
+    env: &'a BTreeMap<String, String>,
+    /// Whether variables set by build scripts that haven't run yet are
+    /// missing from `env`.
+    partial: bool,
 }
 
-impl Invocation {
-    pub fn new(unit: &Unit, deps: Vec<usize>) -> Invocation {
-        let id = unit.pkg.package_id();
-        Invocation {
-            package_name: id.name().to_string(),
-            package_version: id.version().clone(),
-            kind: unit.kind,
-            target_kind: unit.target.kind().clone(),
-            compile_mode: unit.mode,
-            deps,
-            outputs: Vec::new(),
-            links: BTreeMap::new(),
-            program: String::new(),
-            args: Vec::new(),
-            env: BTreeMap::new(),
-            cwd: None,
-        }
+fn new_invocation(unit: &Unit, deps: Vec<usize>) -> Invocation {
+    let id = unit.pkg.package_id();
+    Invocation {
+        package_id: id.to_spec(),
+        package_name: id.name().to_string(),
+        package_version: id.version().clone(),
+        target_name: unit.target.name().to_string(),
+        target_kind: target_kind(unit.target.kind()),
+        target_src_path: unit.target.src_path().path().map(Path::to_path_buf),
+        kind: match unit.kind {
+            CompileKind::Host => None,
+            CompileKind::Target(target) => Some(target.rustc_target().to_string()),
+        },
+        compile_mode: compile_mode(unit.mode).to_string(),
+        deps,
+        outputs: Vec::new(),
+        links: BTreeMap::new(),
+        program: String::new(),
+        args: Vec::new(),
+        env: BTreeMap::new(),
+        cwd: None,
+        build_script_output: None,
     }
+}
 
-    pub fn add_output(&mut self, path: &Path, link: &Option<PathBuf>) {
-        self.outputs.push(path.to_path_buf());
-        if let Some(ref link) = *link {
-            self.links.insert(link.clone(), path.to_path_buf());
-        }
+/// The names of a [`TargetKind`] in the build plan, matching those in other
+/// JSON output like `cargo metadata`.
+pub(crate) fn target_kind(kind: &TargetKind) -> Vec<String> {
+    match kind {
+        TargetKind::Lib(kinds) => kinds.iter().map(|kind| kind.to_string()).collect(),
+        TargetKind::Bin => vec!["bin".to_string()],
+        TargetKind::ExampleBin | TargetKind::ExampleLib(_) => vec!["example".to_string()],
+        TargetKind::Test => vec!["test".to_string()],
+        TargetKind::CustomBuild => vec!["custom-build".to_string()],
+        TargetKind::PostBuild => vec!["post-build".to_string()],
+        TargetKind::Bench => vec!["bench".to_string()],
     }
+}
 
-    pub fn update_cmd(&mut self, cmd: &ProcessBuilder) -> CargoResult<()> {
-        self.program = cmd
-            .get_program()
-            .to_str()
-            .ok_or_else(|| anyhow::format_err!("unicode program string required"))?
-            .to_string();
-        self.cwd = Some(cmd.get_cwd().unwrap().to_path_buf());
-        for arg in cmd.get_args() {
-            self.args.push(
-                arg.to_str()
-                    .ok_or_else(|| anyhow::format_err!("unicode argument string required"))?
-                    .to_string(),
-            );
-        }
-        for (var, value) in cmd.get_envs() {
-            let Some(value) = value else { continue };
-            self.env.insert(
-                var.clone(),
-                value
-                    .to_str()
-                    .ok_or_else(|| anyhow::format_err!("unicode environment value required"))?
-                    .to_string(),
-            );
-        }
-        Ok(())
+pub(super) fn compile_mode(mode: CompileMode) -> &'static str {
+    match mode {
+        CompileMode::Test => "test",
+        CompileMode::Build => "build",
+        CompileMode::Check { .. } => "check",
+        CompileMode::Bench => "bench",
+        CompileMode::Doc { .. } => "doc",
+        CompileMode::Doctest => "doctest",
+        CompileMode::Docscrape => "docscrape",
+        CompileMode::RunCustomBuild => "run-custom-build",
+        CompileMode::RunPostBuild => "run-post-build",
+    }
+}
+
+fn build_script_output(output: &BuildOutput) -> build_plan::BuildScriptOutput {
+    build_plan::BuildScriptOutput {
+        library_paths: output.library_paths.clone(),
+        library_links: output.library_links.clone(),
+        linker_args: output
+            .linker_args
+            .iter()
+            .map(|(targets, arg)| LinkArg {
+                targets: match targets {
+                    LinkArgTarget::All => "all".to_string(),
+                    LinkArgTarget::Cdylib => "cdylib".to_string(),
+                    LinkArgTarget::Bin => "bins".to_string(),
+                    LinkArgTarget::SingleBin(name) => format!("bin={name}"),
+                    LinkArgTarget::Test => "tests".to_string(),
+                    LinkArgTarget::Bench => "benches".to_string(),
+                    LinkArgTarget::Example => "examples".to_string(),
+                },
+                arg: arg.clone(),
+            })
+            .collect(),
+        cfgs: output.cfgs.clone(),
+        check_cfgs: output.check_cfgs.clone(),
+        env: output.env.iter().cloned().collect(),
+        metadata: output.metadata.iter().cloned().collect(),
+    }
+}
+
+fn add_output(invocation: &mut Invocation, path: &Path, link: &Option<PathBuf>) {
+    invocation.outputs.push(path.to_path_buf());
+    if let Some(ref link) = *link {
+        invocation.links.insert(link.clone(), path.to_path_buf());
+    }
+}
+
+fn update_cmd(invocation: &mut Invocation, cmd: &ProcessBuilder) -> CargoResult<()> {
+    invocation.program = cmd
+        .get_program()
+        .to_str()
+        .ok_or_else(|| anyhow::format_err!("unicode program string required"))?
+        .to_string();
+    invocation.cwd = Some(cmd.get_cwd().unwrap().to_path_buf());
+    for arg in cmd.get_args() {
+        invocation.args.push(
+            arg.to_str()
+                .ok_or_else(|| anyhow::format_err!("unicode argument string required"))?
+                .to_string(),
+        );
     }
+    for (var, value) in cmd.get_envs() {
+        let Some(value) = value else { continue };
+        invocation.env.insert(
+            var.clone(),
+            value
+                .to_str()
+                .ok_or_else(|| anyhow::format_err!("unicode environment value required"))?
+                .to_string(),
+        );
+    }
+    Ok(())
 }
 
 impl BuildPlan {
     pub fn new() -> BuildPlan {
         BuildPlan {
             invocation_map: BTreeMap::new(),
-            plan: SerializedBuildPlan::new(),
+            plan: build_plan::BuildPlan {
+                version: BUILD_PLAN_VERSION,
+                invocations: Vec::new(),
+                inputs: Vec::new(),
+            },
+            build_scripts: BTreeMap::new(),
+            package_ids: Vec::new(),
+            runners: Vec::new(),
         }
     }
 
@@ -115,8 +198,14 @@ impl BuildPlan {
             .iter()
             .map(|dep| self.invocation_map[&dep.unit.buildkey()])
             .collect();
-        let invocation = Invocation::new(unit, deps);
+        let invocation = new_invocation(unit, deps);
         self.plan.invocations.push(invocation);
+        self.package_ids.push(unit.pkg.package_id());
+        if unit.mode.is_run_custom_build() {
+            let metadata = build_runner.get_run_build_script_metadata(unit);
+            self.build_scripts
+                .insert(id, (metadata, unit.pkg.root().to_path_buf()));
+        }
         Ok(())
     }
 
@@ -132,9 +221,9 @@ impl BuildPlan {
                 internal(format!("couldn't find invocation for {}", invocation_name))
             })?;
 
-        invocation.update_cmd(cmd)?;
+        update_cmd(invocation, cmd)?;
         for output in outputs.iter() {
-            invocation.add_output(&output.path, &output.hardlink);
+            add_output(invocation, &output.path, &output.hardlink);
         }
 
         Ok(())
@@ -144,17 +233,150 @@ impl BuildPlan {
         self.plan.inputs = inputs;
     }
 
-    pub fn output_plan(self, gctx: &GlobalContext) {
+    /// Records the outputs of the build scripts whose previous run is reused,
+    /// along with the files they depend on as inputs. A build script without
+    /// `cargo::rerun-if-changed` depends on its whole package.
+    pub fn set_build_script_outputs(&mut self, outputs: &BuildScriptOutputs) {
+        for (&id, (metadata, pkg_root)) in &self.build_scripts {
+            let Some(output) = outputs.get(*metadata) else {
+                continue;
+            };
+            if output.rerun_if_changed.is_empty() {
+                self.plan.inputs.push(pkg_root.clone());
+            } else {
+                self.plan.inputs.extend(
+                    output
+                        .rerun_if_changed
+                        .iter()
+                        .map(|path| pkg_root.join(path)),
+                );
+            }
+            self.plan.invocations[id].build_script_output = Some(build_script_output(output));
+        }
+        self.plan.inputs.sort();
+        self.plan.inputs.dedup();
+    }
+
+    pub fn output_plan(&self, gctx: &GlobalContext) {
         let encoded = serde_json::to_string(&self.plan).unwrap();
         crate::drop_println!(gctx, "{}", encoded);
     }
-}
 
-impl SerializedBuildPlan {
-    pub fn new() -> SerializedBuildPlan {
-        SerializedBuildPlan {
-            invocations: Vec::new(),
-            inputs: Vec::new(),
+    /// Records `cmd` running the executable built by `unit`. Its environment
+    /// is `partial` if the build script of `unit` hasn't run yet.
+    pub fn add_runner(
+        &mut self,
+        unit: &Unit,
+        cmd: &ProcessBuilder,
+        partial: bool,
+    ) -> CargoResult<()> {
+        let mut invocation = new_invocation(unit, Vec::new());
+        update_cmd(&mut invocation, cmd)?;
+        self.runners
+            .push((unit.pkg.package_id(), invocation, partial));
+        Ok(())
+    }
+
+    /// Gets the environment of the invocation `id`, with the variables set
+    /// by the build script of its package, and whether some of those are
+    /// missing because the build script hasn't run yet.
+    fn invocation_env(&self, id: usize) -> (BTreeMap<String, String>, bool) {
+        let invocation = &self.plan.invocations[id];
+        let mut env = invocation.env.clone();
+        let mut partial = false;
+        if matches!(process_name(&invocation.compile_mode), "rustc" | "rustdoc") {
+            for &dep in &invocation.deps {
+                let dep = &self.plan.invocations[dep];
+                if dep.compile_mode != "run-custom-build" || dep.package_id != invocation.package_id
+                {
+                    continue;
+                }
+                match &dep.build_script_output {
+                    Some(output) => env.extend(output.env.clone()),
+                    None => partial = true,
+                }
+            }
+        }
+        (env, partial)
+    }
+
+    /// Outputs the environment variables Cargo sets for each process selected
+    /// by `selection`, or all of them if it is empty, for `--print-env`.
+    pub fn output_env(
+        self,
+        gctx: &GlobalContext,
+        format: PrintEnvFormat,
+        selection: &[PrintEnvSelection],
+    ) -> CargoResult<()> {
+        let invocations = self
+            .plan
+            .invocations
+            .iter()
+            .enumerate()
+            .map(|(id, invocation)| {
+                let (env, partial) = self.invocation_env(id);
+                let process = process_name(&invocation.compile_mode);
+                (process, self.package_ids[id], invocation, env, partial)
+            });
+        let runners = self.runners.iter().map(|(pkg, invocation, partial)| {
+            ("runner", *pkg, invocation, invocation.env.clone(), *partial)
+        });
+        for (process, pkg, invocation, env, partial) in invocations.chain(runners) {
+            if !selection.is_empty()
+                && !selection
+                    .iter()
+                    .any(|selection| selection.matches(pkg, &invocation.target_name))
+            {
+                continue;
+            }
+            match format {
+                PrintEnvFormat::Human => {
+                    crate::drop_println!(
+                        gctx,
+                        "# {process}: {} v{}, {} target `{}`",
+                        invocation.package_name,
+                        invocation.package_version,
+                        invocation.target_kind.join(", "),
+                        invocation.target_name,
+                    );
+                    if partial {
+                        crate::drop_println!(
+                            gctx,
+                            "# partial: the build script hasn't run yet, \
+                             the variables it sets are missing"
+                        );
+                    }
+                    for (name, value) in &env {
+                        crate::drop_println!(gctx, "{name}={value}");
+                    }
+                    crate::drop_println!(gctx);
+                }
+                PrintEnvFormat::Json => {
+                    let line = SerializedEnv {
+                        process,
+                        package_id: &invocation.package_id,
+                        target_name: &invocation.target_name,
+                        target_kind: &invocation.target_kind,
+                        kind: &invocation.kind,
+                        compile_mode: &invocation.compile_mode,
+                        program: &invocation.program,
+                        env: &env,
+                        partial,
+                    };
+                    crate::drop_println!(gctx, "{}", serde_json::to_string(&line)?);
+                }
+            }
         }
+        Ok(())
+    }
+}
+
+/// The process an invocation runs, named after its [`compile_mode`].
+fn process_name(compile_mode: &str) -> &'static str {
+    match compile_mode {
+        "run-custom-build" => "build-script",
+        "run-post-build" => "post-build-script",
+        "doc" | "doctest" | "docscrape" => "rustdoc",
+        _ => "rustc",
     }
 }
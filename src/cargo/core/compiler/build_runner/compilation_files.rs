@@ -11,7 +11,7 @@ use tracing::debug;
 
 use super::{BuildContext, BuildRunner, CompileKind, FileFlavor, Layout};
 use crate::core::compiler::{CompileMode, CompileTarget, CrateType, FileType, Unit};
-use crate::core::{Target, TargetKind, Workspace};
+use crate::core::{PackageId, Target, TargetKind, Workspace};
 use crate::util::{self, CargoResult, StableHasher};
 
 /// This is a generic version number that can be changed to make
@@ -157,7 +157,13 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
             host,
             target,
             export_dir: build_runner.bcx.build_config.export_dir.clone(),
-            roots: build_runner.bcx.roots.clone(),
+            // With `--only-deps` the roots are dependencies, which are only
+            // uplifted if the user asked for them.
+            roots: if build_runner.bcx.build_config.only_deps {
+                Vec::new()
+            } else {
+                build_runner.bcx.roots.clone()
+            },
             metas,
             outputs,
         }
@@ -202,7 +208,7 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
             self.layout(unit.kind).doc().to_path_buf()
         } else if unit.mode.is_doc_test() {
             panic!("doc tests do not have an out dir");
-        } else if unit.target.is_custom_build() {
+        } else if unit.target.is_custom_build() || unit.target.is_post_build() {
             self.build_script_dir(unit)
         } else if unit.target.is_example() {
             self.layout(unit.kind).examples().to_path_buf()
@@ -270,7 +276,7 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
             "test-"
         } else if unit.mode.is_doc() {
             "doc-"
-        } else if unit.mode.is_run_custom_build() {
+        } else if unit.mode.is_run_custom_build() || unit.mode.is_run_post_build() {
             "run-"
         } else {
             ""
@@ -284,11 +290,12 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
         self.fingerprint_file_path(unit, "output-")
     }
 
-    /// Returns the directory where a compiled build script is stored.
+    /// Returns the directory where a compiled build script or post-build
+    /// script is stored.
     /// `/path/to/target/{debug,release}/build/PKG-HASH`
     pub fn build_script_dir(&self, unit: &Unit) -> PathBuf {
-        assert!(unit.target.is_custom_build());
-        assert!(!unit.mode.is_run_custom_build());
+        assert!(unit.target.is_custom_build() || unit.target.is_post_build());
+        assert!(!unit.mode.is_run_custom_build() && !unit.mode.is_run_post_build());
         assert!(self.metas.contains_key(unit));
         let dir = self.pkg_dir(unit);
         self.layout(CompileKind::Host).build().join(dir)
@@ -334,17 +341,28 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
         self.build_script_run_dir(unit).join("out")
     }
 
+    /// Returns the directory where information about running a post-build
+    /// script is stored.
+    /// `/path/to/target/{debug,release}/build/PKG-HASH`
+    pub fn post_build_run_dir(&self, unit: &Unit) -> PathBuf {
+        assert!(unit.target.is_post_build());
+        assert!(unit.mode.is_run_post_build());
+        let dir = self.pkg_dir(unit);
+        self.layout(unit.kind).build().join(dir)
+    }
+
     /// Returns the path to the executable binary for the given bin target.
     ///
     /// This should only to be used when a `Unit` is not available.
     pub fn bin_link_for_target(
         &self,
+        pkg_id: PackageId,
         target: &Target,
         kind: CompileKind,
         bcx: &BuildContext<'_, '_>,
     ) -> CargoResult<PathBuf> {
         assert!(target.is_bin());
-        let dest = self.layout(kind).dest();
+        let dest = self.uplift_dir(pkg_id, kind);
         let info = bcx.target_data.info(kind);
         let (file_types, _) = info
             .rustc_outputs(
@@ -375,6 +393,19 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
             .map(Arc::clone)
     }
 
+    /// Returns the directory the artifacts of `pkg_id` are uplifted to, which
+    /// is the member's own directory with `workspace.split-target-dirs`.
+    fn uplift_dir(&self, pkg_id: PackageId, kind: CompileKind) -> PathBuf {
+        let dest = self.layout(kind).dest();
+        let Some(member_dir) = self.ws.member_target_dir(pkg_id) else {
+            return dest.to_path_buf();
+        };
+        match dest.strip_prefix(self.ws.target_dir().as_path_unlocked()) {
+            Ok(rel) => member_dir.into_path_unlocked().join(rel),
+            Err(_) => dest.to_path_buf(),
+        }
+    }
+
     /// Returns the path where the output for the given unit and FileType
     /// should be uplifted to.
     ///
@@ -402,6 +433,7 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
         //   give the user access to staticlibs and cdylibs.
         if !unit.target.is_bin()
             && !unit.target.is_custom_build()
+            && !unit.target.is_post_build()
             && file_type.crate_type != Some(CrateType::Dylib)
             && !self.roots.contains(unit)
         {
@@ -411,11 +443,14 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
         let filename = file_type.uplift_filename(&unit.target);
         let uplift_path = if unit.target.is_example() {
             // Examples live in their own little world.
-            self.layout(unit.kind).examples().join(filename)
-        } else if unit.target.is_custom_build() {
+            self.uplift_dir(unit.pkg.package_id(), unit.kind)
+                .join("examples")
+                .join(filename)
+        } else if unit.target.is_custom_build() || unit.target.is_post_build() {
             self.build_script_dir(unit).join(filename)
         } else {
-            self.layout(unit.kind).dest().join(filename)
+            self.uplift_dir(unit.pkg.package_id(), unit.kind)
+                .join(filename)
         };
         if from_path == uplift_path {
             // This can happen with things like examples that reside in the
@@ -457,6 +492,16 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
                 // outputs.
                 vec![]
             }
+            CompileMode::RunPostBuild => {
+                // The output of the post-build script, used for tracking
+                // whether it needs to run again.
+                vec![OutputFile {
+                    path: self.post_build_run_dir(unit).join("output"),
+                    hardlink: None,
+                    export_path: None,
+                    flavor: FileFlavor::Normal,
+                }]
+            }
             CompileMode::Doctest => {
                 // Doctests are built in a temporary directory and then
                 // deleted. There is the `--persist-doctests` unstable flag,
@@ -530,7 +575,7 @@ impl<'a, 'gctx: 'a> CompilationFiles<'a, 'gctx> {
             // If, the `different_binary_name` feature is enabled, the name of the hardlink will
             // be the name of the binary provided by the user in `Cargo.toml`.
             let hardlink = self.uplift_to(unit, &file_type, &path);
-            let export_path = if unit.target.is_custom_build() {
+            let export_path = if unit.target.is_custom_build() || unit.target.is_post_build() {
                 None
             } else {
                 self.export_dir.as_ref().and_then(|export_dir| {
@@ -622,7 +667,7 @@ fn compute_metadata(
 
     hash_rustc_version(bcx, &mut hasher, unit);
 
-    if build_runner.bcx.ws.is_member(&unit.pkg) {
+    if build_runner.bcx.uses_workspace_wrapper(unit) {
         // This is primarily here for clippy. This ensures that the clippy
         // artifacts are separate from the `check` ones.
         if let Some(path) = &build_runner.bcx.rustc().workspace_wrapper {
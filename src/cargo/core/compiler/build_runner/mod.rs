@@ -8,7 +8,9 @@ use crate::core::compiler::compilation::{self, UnitOutput};
 use crate::core::compiler::{self, artifact, Unit};
 use crate::core::PackageId;
 use crate::util::cache_lock::CacheLockMode;
+use crate::util::context::Definition;
 use crate::util::errors::CargoResult;
+use crate::util::ConfigValue;
 use anyhow::{bail, Context as _};
 use filetime::FileTime;
 use itertools::Itertools;
@@ -16,9 +18,10 @@ use jobserver::Client;
 
 use super::build_plan::BuildPlan;
 use super::custom_build::{self, BuildDeps, BuildScriptOutputs, BuildScripts};
-use super::fingerprint::Fingerprint;
+use super::fingerprint::{self, Fingerprint};
 use super::job_queue::JobQueue;
 use super::layout::Layout;
+use super::links_libs::SystemLib;
 use super::lto::Lto;
 use super::unit_graph::UnitDep;
 use super::{
@@ -57,6 +60,9 @@ pub struct BuildRunner<'a, 'gctx> {
     /// Linking information for each `Unit`.
     /// See `build_map` for details.
     pub build_scripts: HashMap<Unit, Arc<BuildScripts>>,
+    /// System libraries found for packages with `[package.links-libs]`.
+    /// See `build_map` for details.
+    pub links_libs: HashMap<(PackageId, CompileKind), Arc<Vec<SystemLib>>>,
     /// Job server client to manage concurrency with other processes.
     pub jobserver: Client,
     /// "Primary" packages are the ones the user selected on the command-line
@@ -115,6 +121,7 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
             mtime_cache: HashMap::new(),
             compiled: HashSet::new(),
             build_scripts: HashMap::new(),
+            links_libs: HashMap::new(),
             build_explicit_deps: HashMap::new(),
             jobserver,
             primary_packages: HashSet::new(),
@@ -179,11 +186,20 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
         }
 
         // Now that we've figured out everything that we're going to do, do it!
-        queue.execute(&mut self, &mut plan)?;
+        let result = queue.execute(&mut self, &mut plan);
+        if let Some(watched_files) = self.bcx.build_config.watched_files.clone() {
+            *watched_files.borrow_mut() = self.watched_files()?;
+        }
+        result?;
 
         if build_plan {
             plan.set_inputs(self.build_plan_inputs()?);
-            plan.output_plan(self.bcx.gctx);
+            plan.set_build_script_outputs(&self.build_script_outputs.lock().unwrap());
+            if self.bcx.build_config.print_env.is_none() {
+                plan.output_plan(self.bcx.gctx);
+            }
+        } else if let Some(export_dir) = self.files().export_dir() {
+            super::artifact_dir::write_manifest(&self, &export_dir)?;
         }
 
         // Add `OUT_DIR` to env vars if unit has a build script.
@@ -274,7 +290,7 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
                 use super::MessageFormat;
                 let format = match self.bcx.build_config.message_format {
                     MessageFormat::Short => "short",
-                    MessageFormat::Human => "human",
+                    MessageFormat::Human | MessageFormat::Sarif => "human",
                     MessageFormat::Json { .. } => "json",
                 };
                 args.push("--error-format".into());
@@ -304,6 +320,24 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
                 self.compilation.native_dirs.insert(dir.clone());
             }
         }
+
+        if let Some(format) = self.bcx.build_config.print_env {
+            let compilation = &self.compilation;
+            let build_script_outputs = self.build_script_outputs.lock().unwrap();
+            for output in compilation.tests.iter().chain(&compilation.binaries) {
+                let cmd = compilation.target_process(
+                    &output.path,
+                    output.unit.kind,
+                    &output.unit.pkg,
+                    output.script_meta,
+                )?;
+                let partial = output
+                    .script_meta
+                    .is_some_and(|meta| build_script_outputs.get(meta).is_none());
+                plan.add_runner(&output.unit, &cmd, partial)?;
+            }
+            plan.output_env(self.bcx.gctx, format, &self.bcx.build_config.print_env_for)?;
+        }
         Ok(self.compilation)
     }
 
@@ -413,6 +447,32 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
         Some(self.get_run_build_script_metadata(&script_unit))
     }
 
+    /// Collects the files that the local units of the build were built from,
+    /// for [`BuildConfig::watched_files`].
+    ///
+    /// [`BuildConfig::watched_files`]: crate::core::compiler::BuildConfig::watched_files
+    fn watched_files(&mut self) -> CargoResult<Vec<PathBuf>> {
+        let units: Vec<_> = self
+            .bcx
+            .unit_graph
+            .keys()
+            .filter(|unit| unit.is_local())
+            .cloned()
+            .collect();
+        let mut files = BTreeSet::new();
+        for unit in &units {
+            files.insert(unit.pkg.manifest_path().to_path_buf());
+            files.extend(fingerprint::watched_files(self, unit)?);
+        }
+        // Files generated by the build, like in `OUT_DIR`, change on every
+        // build, so watching them would cause endless rebuilds.
+        let target_dir = self.bcx.ws.target_dir().into_path_unlocked();
+        Ok(files
+            .into_iter()
+            .filter(|file| !file.starts_with(&target_dir))
+            .collect())
+    }
+
     /// Returns the metadata hash for a RunCustomBuild unit.
     pub fn get_run_build_script_metadata(&self, unit: &Unit) -> Metadata {
         assert!(unit.mode.is_run_custom_build());
@@ -424,7 +484,7 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
     }
 
     /// Returns the list of filenames read by cargo to generate the [`BuildContext`]
-    /// (all `Cargo.toml`, etc.).
+    /// (all `Cargo.toml`, `Cargo.lock`, configuration files, etc.).
     pub fn build_plan_inputs(&self) -> CargoResult<Vec<PathBuf>> {
         // Keep sorted for consistency.
         let mut inputs = BTreeSet::new();
@@ -432,6 +492,35 @@ impl<'a, 'gctx> BuildRunner<'a, 'gctx> {
         for unit in self.bcx.unit_graph.keys() {
             inputs.insert(unit.pkg.manifest_path().to_path_buf());
         }
+        let lockfile = crate::ops::lockfile::lock_root(self.bcx.ws)
+            .as_path_unlocked()
+            .join("Cargo.lock");
+        if lockfile.exists() {
+            inputs.insert(lockfile);
+        }
+        fn config_files(value: &ConfigValue, inputs: &mut BTreeSet<PathBuf>) {
+            if let Definition::Path(path) = value.definition() {
+                inputs.insert(path.clone());
+            }
+            match value {
+                ConfigValue::Table(table, _) => {
+                    for value in table.values() {
+                        config_files(value, inputs);
+                    }
+                }
+                ConfigValue::List(list, _) => {
+                    for (_, def) in list {
+                        if let Definition::Path(path) = def {
+                            inputs.insert(path.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for value in self.bcx.gctx.values()?.values() {
+            config_files(value, &mut inputs);
+        }
         Ok(inputs.into_iter().collect())
     }
 
@@ -0,0 +1,236 @@
+//! Support for running build scripts in a sandbox.
+//!
+//! When `build.sandbox = true` is set (requires `-Zbuild-sandbox`), the
+//! process running a build script is wrapped with a sandboxing program
+//! provided by the platform:
+//!
+//! * On Linux, [bubblewrap] (`bwrap`) runs the script in new namespaces
+//!   without network access, with only a few paths of the filesystem
+//!   visible, and with everything except `OUT_DIR` mounted read-only.
+//! * On macOS, `sandbox-exec` denies network access and writes outside of
+//!   `OUT_DIR`. Reads are not restricted, as the set of paths a process
+//!   needs to start there is not stable across releases.
+//!
+//! On both, the script starts from an empty environment, only the variables
+//! Cargo sets for it and the ones in [`ENV_ALLOWLIST`] are passed through.
+//!
+//! Any other platform is an error. A build script trying to do something
+//! outside of what it was allowed to will fail, and that failure gets reported
+//! like any other build script failure.
+//!
+//! Packages can ask for more access for their build script with the
+//! `[package.build-sandbox]` table, see [`SandboxPolicy`]. It is only honored
+//! for workspace members and for the packages listed in the
+//! `build.sandbox-trust` config, so that a dependency can't lift the sandbox
+//! for itself.
+//!
+//! [bubblewrap]: https://github.com/containers/bubblewrap
+
+use std::path::{Component, Path, PathBuf};
+
+use cargo_util::ProcessBuilder;
+use cargo_util_schemas::manifest::TomlBuildSandbox;
+
+use crate::util::errors::CargoResult;
+use crate::GlobalContext;
+
+/// What a sandboxed build script is allowed to access.
+#[derive(Debug, Default)]
+pub struct SandboxPolicy {
+    /// Whether the network is reachable.
+    pub network: bool,
+    /// Paths that are visible to the script, but cannot be modified.
+    pub read: Vec<PathBuf>,
+    /// Paths the script can modify.
+    pub write: Vec<PathBuf>,
+    /// Variables of Cargo's environment passed through on top of
+    /// [`ENV_ALLOWLIST`].
+    pub env: Vec<String>,
+}
+
+impl SandboxPolicy {
+    /// Creates the policy for a build script of the package at `pkg_root`.
+    ///
+    /// The package and the build output directories are readable and
+    /// `out_dir` is writable. Anything declared in `[package.build-sandbox]`
+    /// is added on top, relative to the package root. The caller decides
+    /// whether the declaration is honored at all, a package must not be able
+    /// to grant itself more access.
+    ///
+    /// Declared paths must stay inside of the package root, so absolute paths
+    /// and `..` are rejected, and so are paths leading out of it through a
+    /// symlink.
+    pub fn new(
+        pkg_root: &Path,
+        out_dir: &Path,
+        read: impl IntoIterator<Item = PathBuf>,
+        declared: Option<&TomlBuildSandbox>,
+    ) -> CargoResult<SandboxPolicy> {
+        let mut policy = SandboxPolicy {
+            network: false,
+            read: vec![pkg_root.to_path_buf()],
+            write: vec![out_dir.to_path_buf()],
+            env: Vec::new(),
+        };
+        for path in read {
+            if !policy.read.contains(&path) {
+                policy.read.push(path);
+            }
+        }
+        if let Some(declared) = declared {
+            policy.network = declared.network.unwrap_or(false);
+            for path in declared.read.iter().flatten() {
+                policy.read.push(package_path(pkg_root, "read", path)?);
+            }
+            for path in declared.write.iter().flatten() {
+                policy.write.push(package_path(pkg_root, "write", path)?);
+            }
+            policy.env.extend(declared.env.iter().flatten().cloned());
+        }
+        Ok(policy)
+    }
+}
+
+/// Resolves a path declared in the `key` list of `[package.build-sandbox]`
+/// against `pkg_root`, failing if it points outside of it.
+fn package_path(pkg_root: &Path, key: &str, path: &str) -> CargoResult<PathBuf> {
+    let outside = || {
+        anyhow::format_err!(
+            "`build-sandbox.{key}` path `{path}` must be relative to the package root and stay inside of it"
+        )
+    };
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+    let joined = pkg_root.join(relative);
+    // The path may not exist yet, but any of its parents that does could be
+    // a symlink leading elsewhere.
+    let existing = joined
+        .ancestors()
+        .find(|p| p.symlink_metadata().is_ok())
+        .unwrap_or(pkg_root);
+    let root = pkg_root.canonicalize()?;
+    match existing.canonicalize() {
+        Ok(existing) if existing.starts_with(&root) => Ok(joined),
+        _ => Err(outside()),
+    }
+}
+
+/// Variables of Cargo's environment that are always passed through to a
+/// sandboxed build script.
+pub const ENV_ALLOWLIST: &[&str] = &[
+    "PATH", "HOME", "USER", "LANG", "LC_ALL", "LC_CTYPE", "TERM", "TZ", "TMPDIR",
+];
+
+/// Paths from the system that are always readable on Linux, if they exist.
+#[cfg(target_os = "linux")]
+const SYSTEM_READ_PATHS: &[&str] = &["/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc"];
+
+/// Returns a process that runs `cmd` inside a sandbox following `policy`.
+#[cfg(target_os = "linux")]
+pub fn wrap(
+    gctx: &GlobalContext,
+    cmd: &ProcessBuilder,
+    policy: &SandboxPolicy,
+) -> CargoResult<ProcessBuilder> {
+    let mut sandbox = ProcessBuilder::new("bwrap");
+    // `--new-session` keeps the script from injecting input into the
+    // terminal Cargo runs in.
+    sandbox.args(&["--die-with-parent", "--new-session", "--unshare-all"]);
+    if policy.network {
+        sandbox.arg("--share-net");
+    }
+    sandbox.args(&["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+    let read = SYSTEM_READ_PATHS
+        .iter()
+        .map(Path::new)
+        .chain(policy.read.iter().map(PathBuf::as_path));
+    for path in read {
+        sandbox.arg("--ro-bind-try").arg(path).arg(path);
+    }
+    // Binds are applied in order, so this makes `OUT_DIR` writable even
+    // though the build directory is mounted read-only above.
+    for path in &policy.write {
+        sandbox.arg("--bind-try").arg(path).arg(path);
+    }
+    sandbox.arg("--");
+    Ok(finish(gctx, sandbox, cmd, policy))
+}
+
+/// Returns a process that runs `cmd` inside a sandbox following `policy`.
+#[cfg(target_os = "macos")]
+pub fn wrap(
+    gctx: &GlobalContext,
+    cmd: &ProcessBuilder,
+    policy: &SandboxPolicy,
+) -> CargoResult<ProcessBuilder> {
+    let quote = |path: &Path| -> CargoResult<String> {
+        let path = path.to_str().ok_or_else(|| {
+            anyhow::format_err!("sandbox path `{}` is not valid UTF-8", path.display())
+        })?;
+        Ok(format!(
+            "\"{}\"",
+            path.replace('\\', "\\\\").replace('"', "\\\"")
+        ))
+    };
+    let mut profile = String::from("(version 1)\n(allow default)\n");
+    if !policy.network {
+        profile.push_str("(deny network*)\n");
+    }
+    profile.push_str("(deny file-write*)\n(allow file-write*\n");
+    profile.push_str("    (subpath \"/dev\")\n    (subpath \"/private/tmp\")\n");
+    profile.push_str("    (subpath \"/private/var/folders\")\n");
+    for path in &policy.write {
+        profile.push_str(&format!("    (subpath {})\n", quote(path)?));
+    }
+    profile.push_str(")\n");
+    let mut sandbox = ProcessBuilder::new("sandbox-exec");
+    sandbox.arg("-p").arg(profile);
+    Ok(finish(gctx, sandbox, cmd, policy))
+}
+
+/// Returns a process that runs `cmd` inside a sandbox following `policy`.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn wrap(
+    _gctx: &GlobalContext,
+    _cmd: &ProcessBuilder,
+    _policy: &SandboxPolicy,
+) -> CargoResult<ProcessBuilder> {
+    anyhow::bail!("`build.sandbox` is not supported on this platform")
+}
+
+/// Appends `cmd` to the sandboxing program, and carries over its environment
+/// and working directory.
+///
+/// Anything else from Cargo's own environment is removed, unless allowed by
+/// [`ENV_ALLOWLIST`] or `policy`.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn finish(
+    gctx: &GlobalContext,
+    mut sandbox: ProcessBuilder,
+    cmd: &ProcessBuilder,
+    policy: &SandboxPolicy,
+) -> ProcessBuilder {
+    sandbox
+        .arg(cmd.get_program())
+        .args(&cmd.get_args().collect::<Vec<_>>());
+    if let Some(cwd) = cmd.get_cwd() {
+        sandbox.cwd(cwd);
+    }
+    for key in gctx.env_keys() {
+        if !ENV_ALLOWLIST.contains(&key) && !policy.env.iter().any(|k| k == key) {
+            sandbox.env_remove(key);
+        }
+    }
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => sandbox.env(key, value),
+            None => sandbox.env_remove(key),
+        };
+    }
+    sandbox
+}
@@ -0,0 +1,261 @@
+//! A cache of build script results shared between workspaces.
+//!
+//! With `-Zbuild-script-cache`, after a build script runs successfully and
+//! declares what it depends on with `cargo::rerun-if-changed` or
+//! `cargo::rerun-if-env-changed`, Cargo stores its stdout and the contents of
+//! its `OUT_DIR` in `$CARGO_HOME/build-script-cache`. The next time the same
+//! build script needs to run, in this or another workspace, Cargo restores
+//! them instead of running it again, as long as the declared inputs are the
+//! same as when the entry was stored.
+//!
+//! An entry is keyed by:
+//!
+//! * the [`Metadata`] of the unit running the script, which covers the
+//!   package, its features, the profile, the target and the version of rustc,
+//!   as well as the dependencies of the script,
+//! * a hash of the compiled build script executable,
+//! * the `RUSTFLAGS` passed to the script,
+//! * whether the script runs in a sandbox, and what the sandbox allows.
+//!
+//! and records the contents of each `rerun-if-changed` path and the value of
+//! each `rerun-if-env-changed` variable, which are checked when restoring.
+//!
+//! Paths to `OUT_DIR` in the stdout of the script are rewritten when an entry
+//! is restored into a different `OUT_DIR`. Paths inside the files of `OUT_DIR`
+//! are not.
+//!
+//! The cache is best-effort: any error reading or writing it is logged and
+//! otherwise ignored, and the build script just runs as usual.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use cargo_util::{paths, Sha256};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::core::compiler::build_runner::Metadata;
+use crate::core::compiler::BuildOutput;
+use crate::util::errors::CargoResult;
+
+/// Bumped whenever the format of an entry changes.
+const CACHE_VERSION: u32 = 1;
+
+/// Stands in for the `OUT_DIR` of the build script in the stored stdout.
+const OUT_DIR_PLACEHOLDER: &str = "[CARGO_BUILD_SCRIPT_OUT_DIR]";
+
+/// The file holding an [`Entry`] in the directory of a cache entry.
+const ENTRY_FILE: &str = "entry.json";
+
+/// The directory holding the contents of `OUT_DIR` in a cache entry.
+const OUT_DIR: &str = "out";
+
+/// The cache entry of one build script run.
+pub struct BuildScriptCache {
+    /// `$CARGO_HOME/build-script-cache`
+    root: PathBuf,
+    /// The name of the directory of the entry in `root`.
+    key: String,
+}
+
+/// What is stored in [`ENTRY_FILE`].
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    version: u32,
+    /// The stdout of the build script, with [`OUT_DIR_PLACEHOLDER`] in place
+    /// of its `OUT_DIR`.
+    stdout: String,
+    /// `rerun-if-changed` paths as printed by the script, with a hash of
+    /// their contents, or `None` if they didn't exist.
+    files: Vec<(PathBuf, Option<String>)>,
+    /// `rerun-if-env-changed` variables with their values.
+    env: Vec<(String, Option<String>)>,
+}
+
+impl BuildScriptCache {
+    /// Locates the entry for running the build script `script`.
+    pub fn new(
+        root: PathBuf,
+        metadata: Metadata,
+        script: &Path,
+        rustflags: &[String],
+        sandbox: Option<&str>,
+    ) -> CargoResult<BuildScriptCache> {
+        let script_hash = Sha256::new().update_path(script)?.finish_hex();
+        let sandbox = sandbox
+            .map(|sandbox| format!("sandbox:{sandbox}"))
+            .unwrap_or_default();
+        let key = Sha256::new()
+            .update(CACHE_VERSION.to_string().as_bytes())
+            .update(metadata.to_string().as_bytes())
+            .update(script_hash.as_bytes())
+            .update(rustflags.join("\x1f").as_bytes())
+            .update(sandbox.as_bytes())
+            .finish_hex();
+        Ok(BuildScriptCache { root, key })
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.root.join(&self.key)
+    }
+
+    /// Restores the entry into `out_dir` if it exists and its inputs are
+    /// unchanged, returning the stdout of the build script.
+    pub fn restore(&self, pkg_root: &Path, out_dir: &Path) -> Option<Vec<u8>> {
+        match self.try_restore(pkg_root, out_dir) {
+            Ok(stdout) => stdout,
+            Err(e) => {
+                debug!("failed to restore build script cache {}: {e:?}", self.key);
+                None
+            }
+        }
+    }
+
+    fn try_restore(&self, pkg_root: &Path, out_dir: &Path) -> CargoResult<Option<Vec<u8>>> {
+        let dir = self.dir();
+        let Ok(contents) = paths::read(&dir.join(ENTRY_FILE)) else {
+            return Ok(None);
+        };
+        let entry: Entry = serde_json::from_str(&contents)?;
+        if entry.version != CACHE_VERSION {
+            return Ok(None);
+        }
+        let paths = entry.files.iter().map(|(path, _)| path.clone()).collect();
+        let vars = entry.env.iter().map(|(var, _)| var.clone()).collect();
+        if inputs(pkg_root, paths, vars)? != (entry.files.clone(), entry.env.clone()) {
+            debug!("build script cache {} has different inputs", self.key);
+            return Ok(None);
+        }
+        copy_dir(&dir.join(OUT_DIR), out_dir)?;
+        let out_dir = out_dir.to_str().context("`OUT_DIR` is not valid UTF-8")?;
+        Ok(Some(
+            entry
+                .stdout
+                .replace(OUT_DIR_PLACEHOLDER, out_dir)
+                .into_bytes(),
+        ))
+    }
+
+    /// Stores the result of a successful run of the build script, if it
+    /// declared its inputs.
+    pub fn store(&self, pkg_root: &Path, out_dir: &Path, stdout: &[u8], output: &BuildOutput) {
+        if output.rerun_if_changed.is_empty() && output.rerun_if_env_changed.is_empty() {
+            debug!("not caching build script without declared inputs");
+            return;
+        }
+        if let Err(e) = self.try_store(pkg_root, out_dir, stdout, output) {
+            debug!("failed to store build script cache {}: {e:?}", self.key);
+        }
+    }
+
+    fn try_store(
+        &self,
+        pkg_root: &Path,
+        out_dir: &Path,
+        stdout: &[u8],
+        output: &BuildOutput,
+    ) -> CargoResult<()> {
+        let dir = self.dir();
+        let out_dir_str = out_dir.to_str().context("`OUT_DIR` is not valid UTF-8")?;
+        let stdout = std::str::from_utf8(stdout)?.replace(out_dir_str, OUT_DIR_PLACEHOLDER);
+        let (files, env) = inputs(
+            pkg_root,
+            output.rerun_if_changed.clone(),
+            output.rerun_if_env_changed.clone(),
+        )?;
+        let entry = Entry {
+            version: CACHE_VERSION,
+            stdout,
+            files,
+            env,
+        };
+
+        // Fill in a temporary directory first, so that a concurrent build
+        // never sees an incomplete entry.
+        paths::create_dir_all(&self.root)?;
+        let tmp = tempfile::Builder::new()
+            .prefix(".tmp")
+            .tempdir_in(&self.root)?;
+        copy_dir(out_dir, &tmp.path().join(OUT_DIR))?;
+        paths::write(&tmp.path().join(ENTRY_FILE), serde_json::to_string(&entry)?)?;
+        // Replace the entry if there is one, it was stored with different
+        // inputs since the build script had to run.
+        if dir.exists() {
+            paths::remove_dir_all(&dir)?;
+        }
+        let tmp = tmp.into_path();
+        if let Err(e) = std::fs::rename(&tmp, &dir) {
+            // Most likely another build stored the same entry first.
+            debug!("failed to move build script cache into place: {e}");
+            paths::remove_dir_all(&tmp)?;
+        }
+        Ok(())
+    }
+}
+
+/// Hashes the current contents of `paths` and reads the values of `vars`.
+///
+/// Relative paths are relative to `pkg_root`, like for `rerun-if-changed`.
+fn inputs(
+    pkg_root: &Path,
+    paths: Vec<PathBuf>,
+    vars: Vec<String>,
+) -> CargoResult<(
+    Vec<(PathBuf, Option<String>)>,
+    Vec<(String, Option<String>)>,
+)> {
+    let files = paths
+        .into_iter()
+        .map(|path| {
+            let hash = hash_path(&pkg_root.join(&path))?;
+            Ok((path, hash))
+        })
+        .collect::<CargoResult<_>>()?;
+    // Read the same way as `LocalFingerprint::RerunIfEnvChanged`.
+    #[allow(clippy::disallowed_methods)]
+    let env = vars
+        .into_iter()
+        .map(|var| {
+            let val = std::env::var(&var).ok();
+            (var, val)
+        })
+        .collect();
+    Ok((files, env))
+}
+
+/// Hashes the contents of a file, or of all files in a directory along with
+/// their names. Returns `None` if `path` doesn't exist.
+fn hash_path(path: &Path) -> CargoResult<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut hasher = Sha256::new();
+    for entry in walkdir::WalkDir::new(path).sort_by_file_name() {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let rel = entry.path().strip_prefix(path)?;
+            hasher.update(paths::path2bytes(rel)?);
+            hasher.update_path(entry.path())?;
+        }
+    }
+    Ok(Some(hasher.finish_hex()))
+}
+
+/// Copies the files in `src` into `dst`, creating `dst` if needed.
+///
+/// Fails on anything that isn't a regular file or directory, such as
+/// symlinks, which are not cached.
+fn copy_dir(src: &Path, dst: &Path) -> CargoResult<()> {
+    for entry in walkdir::WalkDir::new(src) {
+        let entry = entry?;
+        let target = dst.join(entry.path().strip_prefix(src)?);
+        if entry.file_type().is_dir() {
+            paths::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            paths::copy(entry.path(), &target)?;
+        } else {
+            anyhow::bail!("cannot cache `{}`", entry.path().display());
+        }
+    }
+    Ok(())
+}
@@ -10,7 +10,7 @@ use cargo_util::{paths, ProcessBuilder};
 use crate::core::compiler::apply_env_config;
 use crate::core::compiler::BuildContext;
 use crate::core::compiler::{CompileKind, Metadata, Unit};
-use crate::core::Package;
+use crate::core::{Package, PackageId};
 use crate::util::{context, CargoResult, GlobalContext};
 
 /// Represents the kind of process we are creating.
@@ -109,6 +109,12 @@ pub struct Compilation<'gctx> {
     /// The target host triple.
     pub host: String,
 
+    /// Dependencies that `rustc` reported as unused, for each root unit
+    /// checked with [`BuildConfig::unused_dependencies`].
+    ///
+    /// [`BuildConfig::unused_dependencies`]: crate::core::compiler::BuildConfig::unused_dependencies
+    pub unused_dependencies: HashMap<Unit, BTreeSet<PackageId>>,
+
     gctx: &'gctx GlobalContext,
 
     /// Rustc process to be used by default
@@ -152,6 +158,12 @@ impl<'gctx> Compilation<'gctx> {
             to_doc_test: Vec::new(),
             gctx: bcx.gctx,
             host: bcx.host_triple().to_string(),
+            unused_dependencies: bcx
+                .roots
+                .iter()
+                .filter(|unit| bcx.checks_unused_dependencies(unit))
+                .map(|unit| (unit.clone(), BTreeSet::new()))
+                .collect(),
             rustc_process: rustc,
             rustc_workspace_wrapper_process,
             primary_rustc_process,
@@ -261,8 +273,19 @@ impl<'gctx> Compilation<'gctx> {
     ) -> CargoResult<ProcessBuilder> {
         let builder = if let Some((runner, args)) = self.target_runner(kind) {
             let mut builder = ProcessBuilder::new(runner);
-            builder.args(args);
-            builder.arg(cmd);
+            if self.gctx.cli_unstable().runner_composition {
+                let artifact = cmd.as_ref();
+                for arg in args {
+                    builder.arg(expand_artifact(arg, artifact));
+                }
+                if !args.iter().any(|arg| arg.contains(ARTIFACT_PLACEHOLDER)) {
+                    builder.arg(artifact);
+                }
+                builder.env("CARGO_RUNNER_ARTIFACT", artifact);
+            } else {
+                builder.args(args);
+                builder.arg(cmd);
+            }
             builder
         } else {
             ProcessBuilder::new(cmd)
@@ -449,12 +472,20 @@ fn target_runner(
 ) -> CargoResult<Option<(PathBuf, Vec<String>)>> {
     let target = bcx.target_data.short_name(&kind);
 
+    // try target-alias.{}.runner, for the alias `--target` expanded to
+    if !kind.is_host() {
+        if let Some((_, alias)) = bcx.gctx.target_aliases().get(target) {
+            if let Some(v) = &alias.runner {
+                return resolve_runner(bcx.gctx, &v.val).map(Some);
+            }
+        }
+    }
+
     // try target.{}.runner
     let key = format!("target.{}.runner", target);
 
     if let Some(v) = bcx.gctx.get::<Option<context::PathAndArgs>>(&key)? {
-        let path = v.path.resolve_program(bcx.gctx);
-        return Ok(Some((path, v.args)));
+        return resolve_runner(bcx.gctx, &v).map(Some);
     }
 
     // try target.'cfg(...)'.runner
@@ -477,12 +508,99 @@ fn target_runner(
             runner.definition
         );
     }
-    Ok(matching_runner.map(|(_k, runner)| {
-        (
-            runner.val.path.clone().resolve_program(bcx.gctx),
-            runner.val.args.clone(),
-        )
-    }))
+    matching_runner
+        .map(|(_k, runner)| resolve_runner(bcx.gctx, &runner.val))
+        .transpose()
+}
+
+/// Placeholder for the path of the artifact in the arguments of a runner,
+/// with `-Zrunner-composition`.
+pub(crate) const ARTIFACT_PLACEHOLDER: &str = "{artifact}";
+
+/// Replaces [`ARTIFACT_PLACEHOLDER`] in `arg` with `artifact`.
+fn expand_artifact(arg: &str, artifact: &OsStr) -> OsString {
+    let mut parts = arg.split(ARTIFACT_PLACEHOLDER);
+    let mut expanded = OsString::from(parts.next().unwrap_or_default());
+    for part in parts {
+        expanded.push(artifact);
+        expanded.push(part);
+    }
+    expanded
+}
+
+/// Resolves the program and the arguments of a `runner` config value.
+///
+/// With `-Zrunner-composition`, items of the form `@name` are replaced with
+/// the items of the named runner `runners.<name>`, so that runners can be
+/// chained, like a test harness adapter running `qemu`. `@@` escapes a
+/// leading `@`.
+fn resolve_runner(
+    gctx: &GlobalContext,
+    runner: &context::PathAndArgs,
+) -> CargoResult<(PathBuf, Vec<String>)> {
+    if !gctx.cli_unstable().runner_composition {
+        return Ok((
+            runner.path.clone().resolve_program(gctx),
+            runner.args.clone(),
+        ));
+    }
+    let mut items = Vec::new();
+    expand_runner(gctx, runner, &mut Vec::new(), &mut items)?;
+    let mut items = items.into_iter();
+    // Every runner has a program, so the chain does too.
+    let program = items.next().expect("runner has a program");
+    Ok((PathBuf::from(program), items.collect()))
+}
+
+/// Appends the items of `runner` to `items`, expanding references to named
+/// runners. `chain` holds the named runners being expanded, to detect cycles.
+fn expand_runner(
+    gctx: &GlobalContext,
+    runner: &context::PathAndArgs,
+    chain: &mut Vec<String>,
+    items: &mut Vec<String>,
+) -> CargoResult<()> {
+    let definition = &runner.path.value().definition;
+    let program = runner.path.raw_value();
+    for (i, item) in std::iter::once(program)
+        .chain(runner.args.iter().map(String::as_str))
+        .enumerate()
+    {
+        if let Some(escaped) = item.strip_prefix("@@") {
+            items.push(format!("@{escaped}"));
+        } else if let Some(name) = item.strip_prefix('@') {
+            if chain.iter().any(|n| n == name) {
+                let cycle: Vec<_> = chain
+                    .iter()
+                    .chain(Some(&name.to_string()))
+                    .map(|n| format!("`@{n}`"))
+                    .collect();
+                anyhow::bail!("runner `@{name}` refers to itself: {}", cycle.join(" -> "));
+            }
+            let key = format!("runners.{name}");
+            let Some(named) = gctx.get::<Option<context::PathAndArgs>>(&key)? else {
+                anyhow::bail!(
+                    "runner `@{name}` in the runner defined in {definition} was not found, \
+                     expected a `{key}` config value"
+                );
+            };
+            chain.push(name.to_string());
+            expand_runner(gctx, &named, chain, items)?;
+            chain.pop();
+        } else if i == 0 {
+            let program = runner.path.clone().resolve_program(gctx);
+            let program = program.into_os_string().into_string().map_err(|program| {
+                anyhow::format_err!(
+                    "runner program `{}` defined in {definition} is not valid UTF-8",
+                    program.to_string_lossy()
+                )
+            })?;
+            items.push(program);
+        } else {
+            items.push(item.to_string());
+        }
+    }
+    Ok(())
 }
 
 /// Gets the user-specified linker for a particular host or target from the configuration.
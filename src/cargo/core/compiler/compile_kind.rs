@@ -57,7 +57,10 @@ impl CompileKind {
         let dedup = |targets: &[String]| {
             Ok(targets
                 .iter()
-                .map(|value| Ok(CompileKind::Target(CompileTarget::new(value)?)))
+                .map(|value| {
+                    let value = gctx.expand_target_alias(value)?;
+                    Ok(CompileKind::Target(CompileTarget::new(&value)?))
+                })
                 // First collect into a set to deduplicate any `--target` passed
                 // more than once...
                 .collect::<CargoResult<BTreeSet<_>>>()?
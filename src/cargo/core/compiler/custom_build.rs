@@ -31,12 +31,15 @@
 //! [`CompileMode::RunCustomBuild`]: super::CompileMode
 //! [instructions]: https://doc.rust-lang.org/cargo/reference/build-scripts.html#outputs-of-the-build-script
 
-use super::{fingerprint, BuildRunner, Job, Unit, Work};
+use super::{fingerprint, BuildRunner, CompileKind, Job, Unit, Work};
 use crate::core::compiler::artifact;
 use crate::core::compiler::build_runner::Metadata;
+use crate::core::compiler::build_sandbox::{self, SandboxPolicy};
+use crate::core::compiler::build_script_cache::BuildScriptCache;
 use crate::core::compiler::fingerprint::DirtyReason;
 use crate::core::compiler::job_queue::JobState;
-use crate::core::{profiles::ProfileRoot, PackageId, Target};
+use crate::core::compiler::links_libs;
+use crate::core::{profiles::ProfileRoot, PackageId, PackageIdSpecQuery, Target};
 use crate::util::errors::CargoResult;
 use crate::util::internal;
 use crate::util::machine_message::{self, Message};
@@ -47,6 +50,7 @@ use cargo_util_schemas::manifest::RustVersion;
 use std::collections::hash_map::{Entry, HashMap};
 use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
 use std::str::{self, FromStr};
 use std::sync::{Arc, Mutex};
 
@@ -137,6 +141,23 @@ pub struct BuildScripts {
     /// added to LD_LIBRARY_PATH so that the compiler can find any dynamic
     /// libraries a build script may have generated.
     pub plugins: BTreeSet<(PackageId, Metadata)>,
+    /// Packages with `[package.links-libs]` this Unit needs to include for
+    /// linking, in the same order as `to_link`. Each element is a key into
+    /// `BuildRunner::links_libs`.
+    pub links_libs: Vec<(PackageId, CompileKind)>,
+    /// This is only used while constructing `links_libs` to avoid duplicates.
+    seen_links_libs: HashSet<(PackageId, CompileKind)>,
+}
+
+impl BuildScripts {
+    /// Whether the outputs of all these build scripts are known, which with
+    /// `--build-plan` is only the case when none of them has to run again.
+    pub fn outputs_known(&self, outputs: &BuildScriptOutputs) -> bool {
+        self.to_link
+            .iter()
+            .chain(self.plugins.iter())
+            .all(|(_, metadata)| outputs.get(*metadata).is_some())
+    }
 }
 
 /// Dependency information as declared by a build script that might trigger
@@ -312,6 +333,10 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
         cmd.env("CARGO_TRIM_PATHS", trim_paths.to_string());
     }
 
+    for (var, value) in links_libs::build_script_env(build_runner, unit)? {
+        cmd.env(&var, value);
+    }
+
     // Be sure to pass along all enabled features for this package, this is the
     // last piece of statically known information that we have.
     for feat in &unit.features {
@@ -358,6 +383,49 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
     );
     cmd.env_remove("RUSTFLAGS");
 
+    let sandboxed = bcx.build_config.sandbox;
+    let mut ignored_sandbox_declaration = false;
+    // What the script can do in the sandbox is part of the key in the build
+    // script cache, `None` when it doesn't run in a sandbox at all.
+    let mut sandbox_mode = None;
+    if sandboxed {
+        let files = build_runner.files();
+        let mut read = vec![
+            files.host_dest().to_path_buf(),
+            files.layout(unit.kind).dest().to_path_buf(),
+            bcx.target_data.info(CompileKind::Host).sysroot.clone(),
+        ];
+        read.extend(bcx.rustc().path.parent().map(Path::to_path_buf));
+        let declared = unit
+            .pkg
+            .manifest()
+            .resolved_toml()
+            .package()
+            .and_then(|p| p.build_sandbox.as_ref());
+        // A dependency must not be able to lift the sandbox for itself, so
+        // only the consumer can grant more access.
+        let trusted = bcx.ws.is_member(&unit.pkg)
+            || bcx
+                .build_config
+                .sandbox_trust
+                .iter()
+                .any(|spec| spec.matches(unit.pkg.package_id()));
+        if declared.is_some() && !trusted {
+            ignored_sandbox_declaration = true;
+        }
+        let declared = declared.filter(|_| trusted);
+        sandbox_mode = Some(serde_json::to_string(&declared)?);
+        let policy = SandboxPolicy::new(unit.pkg.root(), &script_out_dir, read, declared)
+            .with_context(|| {
+                format!(
+                    "failed to set up the build script sandbox for `{}`",
+                    unit.pkg
+                )
+            })?;
+        cmd = build_sandbox::wrap(bcx.gctx, &cmd, &policy)?;
+        cmd.inherit_jobserver(&build_runner.jobserver);
+    }
+
     if build_runner.bcx.ws.gctx().extra_verbose() {
         cmd.display_env_vars();
     }
@@ -419,6 +487,23 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
     let targets_fresh = targets.clone();
     let msrv_fresh = msrv.clone();
 
+    let cache_root = build_runner
+        .bcx
+        .gctx
+        .cli_unstable()
+        .build_script_cache
+        .then(|| {
+            build_runner
+                .bcx
+                .gctx
+                .home()
+                .join("build-script-cache")
+                .into_path_unlocked()
+        });
+    let script_path = script_dir.join(unit.target.name());
+    let cache_rustflags = build_runner.bcx.rustflags_args(unit).to_vec();
+    let pkg_root = unit.pkg.root().to_path_buf();
+
     let env_profile_name = unit.profile.name.to_uppercase();
     let built_with_debuginfo = build_runner
         .bcx
@@ -445,101 +530,150 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
         // along to this custom build command. We're also careful to augment our
         // dynamic library search path in case the build script depended on any
         // native dynamic libraries.
-        if !build_plan {
-            let build_script_outputs = build_script_outputs.lock().unwrap();
-            for (name, dep_id, dep_metadata) in lib_deps {
-                let script_output = build_script_outputs.get(dep_metadata).ok_or_else(|| {
-                    internal(format!(
-                        "failed to locate build state for env vars: {}/{}",
-                        dep_id, dep_metadata
-                    ))
-                })?;
-                let data = &script_output.metadata;
-                for (key, value) in data.iter() {
-                    cmd.env(
-                        &format!("DEP_{}_{}", super::envify(&name), super::envify(key)),
-                        value,
-                    );
+        //
+        // With `--build-plan`, only the outputs of build scripts that don't
+        // have to run again are known.
+        let script_outputs = build_script_outputs.lock().unwrap();
+        for (name, dep_id, dep_metadata) in lib_deps {
+            let Some(script_output) = script_outputs.get(dep_metadata) else {
+                if build_plan {
+                    continue;
                 }
+                return Err(internal(format!(
+                    "failed to locate build state for env vars: {}/{}",
+                    dep_id, dep_metadata
+                )));
+            };
+            let data = &script_output.metadata;
+            for (key, value) in data.iter() {
+                cmd.env(
+                    &format!("DEP_{}_{}", super::envify(&name), super::envify(key)),
+                    value,
+                );
             }
-            if let Some(build_scripts) = build_scripts {
+        }
+        if let Some(build_scripts) = build_scripts {
+            if !build_plan || build_scripts.outputs_known(&script_outputs) {
                 super::add_plugin_deps(
                     &mut cmd,
-                    &build_script_outputs,
+                    &script_outputs,
                     &build_scripts,
                     &host_target_root,
                 )?;
             }
         }
 
+        drop(script_outputs);
+
         if build_plan {
             state.build_plan(invocation_name, cmd.clone(), Arc::new(Vec::new()));
             return Ok(());
         }
 
-        // And now finally, run the build command itself!
-        state.running(&cmd);
         let timestamp = paths::set_invocation_time(&script_run_dir)?;
-        let prefix = format!("[{} {}] ", id.name(), id.version());
-        let mut warnings_in_case_of_panic = Vec::new();
-        let output = cmd
-            .exec_with_streaming(
-                &mut |stdout| {
-                    if let Some(warning) = stdout
-                        .strip_prefix(OLD_CARGO_WARNING_SYNTAX)
-                        .or(stdout.strip_prefix(NEW_CARGO_WARNING_SYNTAX))
-                    {
-                        warnings_in_case_of_panic.push(warning.to_owned());
-                    }
-                    if extra_verbose {
-                        state.stdout(format!("{}{}", prefix, stdout))?;
-                    }
-                    Ok(())
-                },
-                &mut |stderr| {
-                    if extra_verbose {
-                        state.stderr(format!("{}{}", prefix, stderr))?;
+        let cache = match cache_root {
+            Some(root) => Some(BuildScriptCache::new(
+                root,
+                metadata_hash,
+                &script_path,
+                &cache_rustflags,
+                sandbox_mode.as_deref(),
+            )?),
+            None => None,
+        };
+        let restored = cache
+            .as_ref()
+            .and_then(|cache| cache.restore(&pkg_root, &script_out_dir));
+        let cached = restored.is_some();
+
+        let output = if let Some(stdout) = restored {
+            state.restored(format!("build script output of {pkg_descr}"));
+            Output {
+                status: ExitStatus::default(),
+                stdout,
+                stderr: Vec::new(),
+            }
+        } else {
+            // And now finally, run the build command itself!
+            state.running(&cmd);
+            let prefix = format!("[{} {}] ", id.name(), id.version());
+            let mut warnings_in_case_of_panic = Vec::new();
+            let output = cmd
+                .exec_with_streaming(
+                    &mut |stdout| {
+                        if let Some(warning) = stdout
+                            .strip_prefix(OLD_CARGO_WARNING_SYNTAX)
+                            .or(stdout.strip_prefix(NEW_CARGO_WARNING_SYNTAX))
+                        {
+                            warnings_in_case_of_panic.push(warning.to_owned());
+                        }
+                        if extra_verbose {
+                            state.stdout(format!("{}{}", prefix, stdout))?;
+                        }
+                        Ok(())
+                    },
+                    &mut |stderr| {
+                        if extra_verbose {
+                            state.stderr(format!("{}{}", prefix, stderr))?;
+                        }
+                        Ok(())
+                    },
+                    true,
+                )
+                .with_context(|| {
+                    let mut build_error_context =
+                        format!("failed to run custom build command for `{}`", pkg_descr);
+
+                    // If we're opting into backtraces, mention that build dependencies' backtraces can
+                    // be improved by requesting debuginfo to be built, if we're not building with
+                    // debuginfo already.
+                    //
+                    // ALLOWED: Other tools like `rustc` might read it directly
+                    // through `std::env`. We should make their behavior consistent.
+                    #[allow(clippy::disallowed_methods)]
+                    if let Ok(show_backtraces) = std::env::var("RUST_BACKTRACE") {
+                        if !built_with_debuginfo && show_backtraces != "0" {
+                            build_error_context.push_str(&format!(
+                                "\n\
+                                note: To improve backtraces for build dependencies, set the \
+                                CARGO_PROFILE_{env_profile_name}_BUILD_OVERRIDE_DEBUG=true environment \
+                                variable to enable debug information generation.",
+                            ));
+                        }
                     }
-                    Ok(())
-                },
-                true,
-            )
-            .with_context(|| {
-                let mut build_error_context =
-                    format!("failed to run custom build command for `{}`", pkg_descr);
-
-                // If we're opting into backtraces, mention that build dependencies' backtraces can
-                // be improved by requesting debuginfo to be built, if we're not building with
-                // debuginfo already.
-                //
-                // ALLOWED: Other tools like `rustc` might read it directly
-                // through `std::env`. We should make their behavior consistent.
-                #[allow(clippy::disallowed_methods)]
-                if let Ok(show_backtraces) = std::env::var("RUST_BACKTRACE") {
-                    if !built_with_debuginfo && show_backtraces != "0" {
-                        build_error_context.push_str(&format!(
+
+                    if ignored_sandbox_declaration {
+                        build_error_context.push_str(
                             "\n\
-                            note: To improve backtraces for build dependencies, set the \
-                            CARGO_PROFILE_{env_profile_name}_BUILD_OVERRIDE_DEBUG=true environment \
-                            variable to enable debug information generation.",
-                        ));
+                            note: the build script was run in a sandbox because `build.sandbox` \
+                            is set, its `[package.build-sandbox]` table was ignored because the \
+                            package is not a workspace member, list it in `build.sandbox-trust` \
+                            to honor it",
+                        );
+                    } else if sandboxed {
+                        build_error_context.push_str(
+                            "\n\
+                            note: the build script was run in a sandbox because `build.sandbox` \
+                            is set, declare anything else it needs to access in the \
+                            `[package.build-sandbox]` table of its manifest",
+                        );
                     }
-                }
 
-                build_error_context
-            });
-
-        if let Err(error) = output {
-            insert_warnings_in_build_outputs(
-                build_script_outputs,
-                id,
-                metadata_hash,
-                warnings_in_case_of_panic,
-            );
-            return Err(error);
-        }
+                    build_error_context
+                });
+
+            if let Err(error) = output {
+                insert_warnings_in_build_outputs(
+                    build_script_outputs,
+                    id,
+                    metadata_hash,
+                    warnings_in_case_of_panic,
+                );
+                return Err(error);
+            }
 
-        let output = output.unwrap();
+            output.unwrap()
+        };
 
         // After the build command has finished running, we need to be sure to
         // remember all of its output so we can later discover precisely what it
@@ -566,6 +700,10 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
             &msrv,
         )?;
 
+        if let (Some(cache), false) = (&cache, cached) {
+            cache.store(&pkg_root, &script_out_dir, &output.stdout, &parsed_output);
+        }
+
         if json_messages {
             emit_build_output(state, &parsed_output, script_out_dir.as_path(), id)?;
         }
@@ -596,7 +734,7 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
             )?,
         };
 
-        if json_messages {
+        if json_messages && !build_plan {
             emit_build_output(state, &output, script_out_dir.as_path(), id)?;
         }
 
@@ -607,11 +745,19 @@ fn build_work(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
         Ok(())
     });
 
-    let mut job = if build_runner.bcx.build_config.build_plan {
-        Job::new_dirty(Work::noop(), DirtyReason::FreshBuild)
-    } else {
-        fingerprint::prepare_target(build_runner, unit, false)?
-    };
+    if build_plan {
+        // The command is always part of the plan. The output of a previous
+        // run that is still up to date is reused, so that the plan includes
+        // it and applies it to the invocations depending on it.
+        let mut job = Job::new_dirty(Work::noop(), DirtyReason::FreshBuild);
+        if fingerprint::is_fresh(build_runner, unit)? {
+            job.before(fresh);
+        }
+        job.before(dirty);
+        return Ok(job);
+    }
+
+    let mut job = fingerprint::prepare_target(build_runner, unit, false)?;
     if job.freshness().is_dirty() {
         job.before(dirty);
     } else {
@@ -1196,6 +1342,19 @@ pub fn build_map(build_runner: &mut BuildRunner<'_, '_>) -> CargoResult<()> {
             add_to_link(&mut ret, unit.pkg.package_id(), script_meta);
         }
 
+        // Probe the system libraries of the package before anything gets
+        // built, and add them for linking too.
+        if !unit.target.is_custom_build() && links_libs::declared(&unit.pkg).is_some() {
+            let key = (unit.pkg.package_id(), unit.kind);
+            if !build_runner.links_libs.contains_key(&key) {
+                let cache_dir = build_runner.files().layout(unit.kind).fingerprint();
+                let libs =
+                    links_libs::probe(build_runner.bcx.gctx, &unit.pkg, unit.kind, cache_dir)?;
+                build_runner.links_libs.insert(key, Arc::new(libs));
+            }
+            add_links_libs(&mut ret, key);
+        }
+
         if unit.mode.is_run_custom_build() {
             parse_previous_explicit_deps(build_runner, unit);
         }
@@ -1220,6 +1379,9 @@ pub fn build_map(build_runner: &mut BuildRunner<'_, '_>) -> CargoResult<()> {
                 for &(pkg, metadata) in dep_scripts.to_link.iter() {
                     add_to_link(&mut ret, pkg, metadata);
                 }
+                for &key in dep_scripts.links_libs.iter() {
+                    add_links_libs(&mut ret, key);
+                }
             }
         }
 
@@ -1237,6 +1399,12 @@ pub fn build_map(build_runner: &mut BuildRunner<'_, '_>) -> CargoResult<()> {
         }
     }
 
+    fn add_links_libs(scripts: &mut BuildScripts, key: (PackageId, CompileKind)) {
+        if scripts.seen_links_libs.insert(key) {
+            scripts.links_libs.push(key);
+        }
+    }
+
     /// Load any dependency declarations from a previous build script run.
     fn parse_previous_explicit_deps(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) {
         let script_run_dir = build_runner.files().build_script_run_dir(unit);
@@ -0,0 +1,84 @@
+//! An [`Executor`] handing every `rustc` invocation to an external program,
+//! for `cargo build --executor`.
+//!
+//! The protocol is defined in [`cargo_util_schemas::executor`]: the program
+//! receives an [`ExecutorRequest`] as JSON on stdin, and its stdout, stderr
+//! and exit status stand in for those of `rustc`. This lets remote-execution
+//! wrappers hook into a build without being linked into Cargo.
+
+use std::collections::BTreeMap;
+
+use cargo_util::ProcessBuilder;
+use cargo_util_schemas::executor::{ExecutorRequest, EXECUTOR_PROTOCOL_VERSION};
+
+use super::build_plan;
+use super::{CompileMode, Executor};
+use crate::core::{PackageId, Target};
+use crate::util::context::{Definition, PathAndArgs, Value};
+use crate::util::{CargoResult, GlobalContext};
+
+pub struct ExternalExecutor {
+    executor: ProcessBuilder,
+}
+
+impl ExternalExecutor {
+    /// Creates an executor running `command`, a path or name of a program
+    /// optionally followed by arguments, separated by whitespace.
+    pub fn new(gctx: &GlobalContext, command: &str) -> CargoResult<ExternalExecutor> {
+        let command = PathAndArgs::from_whitespace_separated_string(&Value {
+            val: command.to_string(),
+            definition: Definition::Cli(None),
+        });
+        if command.path.raw_value().is_empty() {
+            anyhow::bail!("the `--executor` command must not be empty");
+        }
+        let mut executor = ProcessBuilder::new(command.path.resolve_program(gctx));
+        executor.args(&command.args);
+        Ok(ExternalExecutor { executor })
+    }
+}
+
+impl Executor for ExternalExecutor {
+    fn exec(
+        &self,
+        cmd: &ProcessBuilder,
+        id: PackageId,
+        target: &Target,
+        mode: CompileMode,
+        on_stdout_line: &mut dyn FnMut(&str) -> CargoResult<()>,
+        on_stderr_line: &mut dyn FnMut(&str) -> CargoResult<()>,
+    ) -> CargoResult<()> {
+        let request = ExecutorRequest {
+            version: EXECUTOR_PROTOCOL_VERSION,
+            package_id: id.to_spec(),
+            target_name: target.name().to_string(),
+            target_kind: build_plan::target_kind(target.kind()),
+            compile_mode: build_plan::compile_mode(mode).to_string(),
+            program: to_string(cmd.get_program(), "program")?,
+            args: cmd
+                .get_args()
+                .map(|arg| to_string(arg, "argument"))
+                .collect::<CargoResult<_>>()?,
+            env: cmd
+                .get_envs()
+                .iter()
+                .filter_map(|(var, value)| Some((var, value.as_ref()?)))
+                .map(|(var, value)| Ok((var.clone(), to_string(value, "environment value")?)))
+                .collect::<CargoResult<BTreeMap<_, _>>>()?,
+            cwd: cmd.get_cwd().map(|cwd| cwd.to_path_buf()),
+        };
+
+        let mut executor = self.executor.clone();
+        executor.stdin(serde_json::to_vec(&request)?);
+        executor
+            .exec_with_streaming(on_stdout_line, on_stderr_line, false)
+            .map(drop)
+    }
+}
+
+fn to_string(value: &std::ffi::OsStr, what: &str) -> CargoResult<String> {
+    value
+        .to_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::format_err!("unicode {what} string required"))
+}
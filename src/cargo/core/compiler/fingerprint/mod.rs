@@ -386,6 +386,16 @@ use super::{BuildContext, BuildRunner, FileFlavor, Job, Unit, Work};
 
 pub use dirty_reason::DirtyReason;
 
+/// Whether `unit` is up to date, without preparing anything to rebuild it.
+///
+/// `--build-plan` uses this to reuse the output of build scripts that don't
+/// have to run again.
+pub fn is_fresh(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResult<bool> {
+    let loc = build_runner.files().fingerprint_file_path(unit, "");
+    let fingerprint = calculate(build_runner, unit)?;
+    Ok(compare_old_fingerprint(unit, &loc, &*fingerprint, false, false).is_none())
+}
+
 /// Determines if a [`Unit`] is up-to-date, and if not prepares necessary work to
 /// update the persisted fingerprint.
 ///
@@ -418,7 +428,10 @@ pub fn prepare_target(
     // compare it to an old version, if any, and attempt to print diagnostic
     // information about failed comparisons to aid in debugging.
     let fingerprint = calculate(build_runner, unit)?;
-    let mtime_on_use = build_runner.bcx.gctx.cli_unstable().mtime_on_use;
+    // With `-Zgc`, this records when the unit was last used for
+    // `cargo clean --older-than` and `--max-size`.
+    let mtime_on_use = build_runner.bcx.gctx.cli_unstable().mtime_on_use
+        || build_runner.bcx.gctx.cli_unstable().gc;
     let dirty_reason = compare_old_fingerprint(unit, &loc, &*fingerprint, mtime_on_use, force);
 
     let Some(dirty_reason) = dirty_reason else {
@@ -734,6 +747,9 @@ enum LocalFingerprint {
     /// `rerun-if-changed` are listed in `paths`. The `paths` field is relative
     /// to `pkg.root()`
     ///
+    /// This is also used for the `[target.<platform>.link-inputs]` of a unit
+    /// being compiled, with the dep-info file of the unit as `output`.
+    ///
     /// This is considered up-to-date if all of the `paths` are older than
     /// `output`, otherwise we need to recompile.
     RerunIfChanged {
@@ -895,7 +911,7 @@ impl Fingerprint {
         *self.memoized_hash.lock().unwrap() = None;
     }
 
-    fn hash_u64(&self) -> u64 {
+    pub(crate) fn hash_u64(&self) -> u64 {
         if let Some(s) = *self.memoized_hash.lock().unwrap() {
             return s;
         }
@@ -1371,14 +1387,19 @@ fn calculate_normal(
         //
         // Skip fingerprints of binaries because they don't actually induce a
         // recompile, they're just dependencies in the sense that they need to be
-        // built. The only exception here are artifact dependencies,
-        // which is an actual dependency that needs a recompile.
+        // built. The only exceptions here are artifact dependencies,
+        // which is an actual dependency that needs a recompile, and the
+        // binaries a post-build script runs after.
         //
         // Create Vec since mutable build_runner is needed in closure.
         let deps = Vec::from(build_runner.unit_deps(unit));
         let mut deps = deps
             .into_iter()
-            .filter(|dep| !dep.unit.target.is_bin() || dep.unit.artifact.is_true())
+            .filter(|dep| {
+                !dep.unit.target.is_bin()
+                    || dep.unit.artifact.is_true()
+                    || unit.mode.is_run_post_build()
+            })
             .map(|dep| DepFingerprint::new(build_runner, unit, &dep))
             .collect::<CargoResult<Vec<_>>>()?;
         deps.sort_by(|a, b| a.pkg_id.cmp(&b.pkg_id));
@@ -1387,6 +1408,7 @@ fn calculate_normal(
 
     // Afterwards calculate our own fingerprint information.
     let target_root = target_root(build_runner);
+    let link_inputs = super::link_inputs(build_runner.bcx, unit);
     let local = if unit.mode.is_doc() || unit.mode.is_doc_scrape() {
         // rustdoc does not have dep-info files.
         let fingerprint = pkg_fingerprint(build_runner.bcx, &unit.pkg).with_context(|| {
@@ -1399,7 +1421,23 @@ fn calculate_normal(
     } else {
         let dep_info = dep_info_loc(build_runner, unit);
         let dep_info = dep_info.strip_prefix(&target_root).unwrap().to_path_buf();
-        vec![LocalFingerprint::CheckDepInfo { dep_info }]
+        // Linker scripts and objects from `[target.<platform>.link-inputs]`
+        // aren't known to rustc, so check them against its last invocation.
+        let link_input_paths = link_inputs
+            .iter()
+            .flat_map(|inputs| inputs.linker_scripts.iter().chain(&inputs.objects))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut local = vec![LocalFingerprint::CheckDepInfo {
+            dep_info: dep_info.clone(),
+        }];
+        if !link_input_paths.is_empty() {
+            local.push(LocalFingerprint::RerunIfChanged {
+                output: dep_info,
+                paths: link_input_paths,
+            });
+        }
+        local
     };
 
     // Figure out what the outputs of our unit is, and we'll be storing them
@@ -1426,7 +1464,12 @@ fn calculate_normal(
     // HACK(#13975): duplicating the lookup logic here until `--check-cfg` is supported
     // on Cargo's MSRV and we can centralize the logic in `lints_to_rustflags`
     let mut lint_check_cfg = Vec::new();
-    if let Ok(Some(lints)) = unit.pkg.manifest().resolved_toml().resolved_lints() {
+    if let Ok(Some(lints)) = unit
+        .pkg
+        .manifest()
+        .resolved_toml()
+        .resolved_lints_with_profile(build_runner.bcx.gctx.lints_profile())
+    {
         if let Some(rust_lints) = lints.get("rust") {
             if let Some(unexpected_cfgs) = rust_lints.get("unexpected_cfgs") {
                 if let Some(config) = unexpected_cfgs.config() {
@@ -1446,9 +1489,13 @@ fn calculate_normal(
         &unit.profile,
         unit.mode,
         build_runner.bcx.extra_args_for(unit),
+        build_runner.bcx.target_profile_rustflags_args(unit),
         build_runner.lto[unit],
         unit.pkg.manifest().lint_rustflags(),
+        build_runner.bcx.clippy_args_for(unit),
         lint_check_cfg,
+        &link_inputs,
+        super::links_libs::rustc_args(build_runner, unit),
     ));
     // Include metadata since it is exposed as environment variables.
     let m = unit.pkg.manifest().metadata();
@@ -1462,6 +1509,9 @@ fn calculate_normal(
             map.hash(&mut config);
         }
     }
+    if let Some(index_page) = super::rustdoc::index_page(build_runner.bcx, unit) {
+        index_page.hash(&mut config);
+    }
     if let Some(allow_features) = &build_runner.bcx.gctx.cli_unstable().allow_features {
         allow_features.hash(&mut config);
     }
@@ -1875,6 +1925,39 @@ pub fn parse_dep_info(
     Ok(Some(ret))
 }
 
+/// Returns the files that `unit` was built from, for `--watch`.
+///
+/// These are the files of its dep-info, or of `rerun-if-changed` for build
+/// script executions. Like the fingerprint, this falls back to all the files
+/// of the package when there is no such list, like for rustdoc or a unit that
+/// failed to build.
+pub fn watched_files(
+    build_runner: &mut BuildRunner<'_, '_>,
+    unit: &Unit,
+) -> CargoResult<Vec<PathBuf>> {
+    let pkg_root = unit.pkg.root();
+    if unit.mode.is_run_custom_build() {
+        let metadata = build_runner.get_run_build_script_metadata(unit);
+        let outputs = build_runner.build_script_outputs.lock().unwrap();
+        if let Some(output) = outputs.get(metadata) {
+            if !output.rerun_if_changed.is_empty() {
+                return Ok(output
+                    .rerun_if_changed
+                    .iter()
+                    .map(|path| pkg_root.join(path))
+                    .collect());
+            }
+        }
+    } else if !unit.mode.is_doc() && !unit.mode.is_doc_scrape() && !unit.mode.is_doc_test() {
+        let target_root = target_root(build_runner);
+        let dep_info = dep_info_loc(build_runner, unit);
+        if let Some(info) = parse_dep_info(pkg_root, &target_root, &dep_info)? {
+            return Ok(info.files);
+        }
+    }
+    crate::sources::path::list_files(&unit.pkg, build_runner.bcx.gctx)
+}
+
 /// Calculates the fingerprint of a unit thats contains no dep-info files.
 fn pkg_fingerprint(bcx: &BuildContext<'_, '_>, pkg: &Package) -> CargoResult<String> {
     let source_id = pkg.package_id().source_id();
@@ -2011,12 +2094,7 @@ pub fn translate_dep_info(
     rustc_cmd: &ProcessBuilder,
     allow_package: bool,
 ) -> CargoResult<()> {
-    let depinfo = parse_rustc_dep_info(rustc_dep_info)?;
-
-    let target_root = try_canonicalize(target_root)?;
-    let pkg_root = try_canonicalize(pkg_root)?;
-    let mut on_disk_info = EncodedDepInfo::default();
-    on_disk_info.env = depinfo.env;
+    let mut depinfo = parse_rustc_dep_info(rustc_dep_info)?;
 
     // This is a bit of a tricky statement, but here we're *removing* the
     // dependency on environment variables that were defined specifically for
@@ -2046,14 +2124,44 @@ pub fn translate_dep_info(
     // This also includes `CARGO` since if the code is explicitly wanting to
     // know that path, it should be rebuilt if it changes. The CARGO path is
     // not tracked elsewhere in the fingerprint.
-    on_disk_info
+    depinfo
         .env
         .retain(|(key, _)| !rustc_cmd.get_envs().contains_key(key) || key == CARGO_ENV);
 
+    write_dep_info(
+        depinfo,
+        cargo_dep_info,
+        rustc_cwd,
+        pkg_root,
+        target_root,
+        allow_package,
+    )
+}
+
+/// Writes the dependencies in `depinfo` to `cargo_dep_info` in Cargo's own
+/// format, as read back by [`parse_dep_info`].
+///
+/// This is used for units not compiled by `rustc` too, like running a
+/// post-build script, with the files and environment variables they reported
+/// depending on. Relative paths in `depinfo` are relative to `cwd`. See
+/// [`translate_dep_info`] for the other arguments.
+pub fn write_dep_info(
+    depinfo: RustcDepInfo,
+    cargo_dep_info: &Path,
+    cwd: &Path,
+    pkg_root: &Path,
+    target_root: &Path,
+    allow_package: bool,
+) -> CargoResult<()> {
+    let target_root = try_canonicalize(target_root)?;
+    let pkg_root = try_canonicalize(pkg_root)?;
+    let mut on_disk_info = EncodedDepInfo::default();
+    on_disk_info.env = depinfo.env;
+
     for file in depinfo.files {
         // The path may be absolute or relative, canonical or not. Make sure
         // it is canonicalized so we are comparing the same kinds of paths.
-        let abs_file = rustc_cwd.join(file);
+        let abs_file = cwd.join(file);
         // If canonicalization fails, just use the abs path. There is currently
         // a bug where --remap-path-prefix is affecting .d files, causing them
         // to point to non-existent paths.
@@ -2068,7 +2176,7 @@ pub fn translate_dep_info(
             (DepInfoPathType::PackageRootRelative, stripped)
         } else {
             // It's definitely not target root relative, but this is an absolute path (since it was
-            // joined to rustc_cwd) and as such re-joining it later to the target root will have no
+            // joined to cwd) and as such re-joining it later to the target root will have no
             // effect.
             (DepInfoPathType::TargetRootRelative, &*abs_file)
         };
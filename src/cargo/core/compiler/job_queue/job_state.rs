@@ -72,6 +72,11 @@ impl<'a, 'gctx> JobState<'a, 'gctx> {
         self.messages.push(Message::Run(self.id, cmd.to_string()));
     }
 
+    /// See [`Message::Restored`].
+    pub fn restored(&self, descr: String) {
+        self.messages.push(Message::Restored(self.id, descr));
+    }
+
     pub fn build_plan(
         &self,
         module_name: String,
@@ -104,7 +109,7 @@ impl<'a, 'gctx> JobState<'a, 'gctx> {
 
     /// See [`Message::Diagnostic`] and [`Message::WarningCount`].
     pub fn emit_diag(&self, level: String, diag: String, fixable: bool) -> CargoResult<()> {
-        if let Some(dedupe) = self.output {
+        if let Some(dedupe) = self.output.filter(|dedupe| !dedupe.is_grouping()) {
             let emitted = dedupe.emit_diag(&diag)?;
             if level == "warning" {
                 self.messages.push(Message::WarningCount {
@@ -114,12 +119,20 @@ impl<'a, 'gctx> JobState<'a, 'gctx> {
                 });
             }
         } else {
-            self.messages.push_bounded(Message::Diagnostic {
+            let msg = Message::Diagnostic {
                 id: self.id,
                 level,
                 diag,
                 fixable,
-            });
+            };
+            if self.output.is_some() {
+                // Grouped diagnostics of fresh jobs go through the queue too,
+                // to know which unit emitted them. This is the thread that
+                // drains it, so it must not block.
+                self.messages.push(msg);
+            } else {
+                self.messages.push_bounded(msg);
+            }
         }
         Ok(())
     }
@@ -197,4 +210,15 @@ impl<'a, 'gctx> JobState<'a, 'gctx> {
         self.messages
             .push(Message::FutureIncompatReport(self.id, report));
     }
+
+    /// Records that `rustc` reported the extern crate `name` as unused.
+    pub fn unused_dependency(&self, name: String) {
+        self.messages.push(Message::UnusedDependency(self.id, name));
+    }
+
+    /// See [`Message::CompilerDiagnostic`].
+    pub fn compiler_diagnostic(&self, diagnostic: String) {
+        self.messages
+            .push(Message::CompilerDiagnostic(self.id, diagnostic));
+    }
 }
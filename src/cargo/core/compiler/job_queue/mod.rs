@@ -132,8 +132,9 @@ pub use self::job::Freshness::{self, Dirty, Fresh};
 pub use self::job::{Job, Work};
 pub use self::job_state::JobState;
 use super::build_runner::OutputFile;
+use super::sarif::SarifReport;
 use super::timings::Timings;
-use super::{BuildContext, BuildPlan, BuildRunner, CompileMode, Unit};
+use super::{BuildContext, BuildPlan, BuildRunner, CompileKind, CompileMode, MessageFormat, Unit};
 use crate::core::compiler::descriptive_pkg_name;
 use crate::core::compiler::future_incompat::{
     self, FutureBreakageItem, FutureIncompatReportPackage,
@@ -200,6 +201,8 @@ struct DrainState<'gctx> {
     /// How many jobs we've finished
     finished: usize,
     per_package_future_incompat_reports: Vec<FutureIncompatReportPackage>,
+    /// The diagnostics collected for `--message-format=sarif`.
+    sarif_report: Option<SarifReport>,
 }
 
 /// Count of warnings, used to print a summary after the job succeeds
@@ -294,6 +297,19 @@ impl std::fmt::Display for JobId {
 struct DiagDedupe<'gctx> {
     seen: RefCell<HashSet<u64>>,
     gctx: &'gctx GlobalContext,
+    /// With `-Zgroup-diagnostics`, the diagnostics of each package are held
+    /// here until all of its units finished, to print each of them once along
+    /// with the units that emitted it.
+    groups: Option<RefCell<HashMap<PackageId, DiagGroup>>>,
+}
+
+/// The diagnostics of a package held back with `-Zgroup-diagnostics`.
+#[derive(Default)]
+struct DiagGroup {
+    /// Each distinct diagnostic, with the units that emitted it.
+    diags: Vec<(String, Vec<Unit>)>,
+    /// The warning counts of the units, printed after the diagnostics.
+    summaries: Vec<String>,
 }
 
 impl<'gctx> DiagDedupe<'gctx> {
@@ -301,9 +317,19 @@ impl<'gctx> DiagDedupe<'gctx> {
         DiagDedupe {
             seen: RefCell::new(HashSet::new()),
             gctx,
+            groups: gctx
+                .cli_unstable()
+                .group_diagnostics
+                .then(|| RefCell::new(HashMap::new())),
         }
     }
 
+    /// Whether diagnostics are held back to be grouped, instead of being
+    /// printed by [`DiagDedupe::emit_diag`].
+    fn is_grouping(&self) -> bool {
+        self.groups.is_some()
+    }
+
     /// Emits a diagnostic message.
     ///
     /// Returns `true` if the message was emitted, or `false` if it was
@@ -318,6 +344,109 @@ impl<'gctx> DiagDedupe<'gctx> {
         shell.err().write_all(b"\n")?;
         Ok(true)
     }
+
+    /// Holds back a diagnostic of `unit` until its package is flushed.
+    ///
+    /// Returns `true` if this is the first time the package emits it, or
+    /// `false` if it is a duplicate.
+    fn group_diag(&self, unit: &Unit, diag: String) -> bool {
+        let mut groups = self.groups.as_ref().unwrap().borrow_mut();
+        let group = groups.entry(unit.pkg.package_id()).or_default();
+        match group.diags.iter_mut().find(|(d, _)| *d == diag) {
+            Some((_, units)) => {
+                if !units.contains(unit) {
+                    units.push(unit.clone());
+                }
+                false
+            }
+            None => {
+                group.diags.push((diag, vec![unit.clone()]));
+                true
+            }
+        }
+    }
+
+    /// Holds back the warning count of a unit until its package is flushed.
+    fn group_summary(&self, pkg_id: PackageId, summary: String) {
+        let mut groups = self.groups.as_ref().unwrap().borrow_mut();
+        groups.entry(pkg_id).or_default().summaries.push(summary);
+    }
+
+    /// Prints the diagnostics held back for `pkg_id`, if any.
+    fn flush(&self, pkg_id: PackageId) -> CargoResult<()> {
+        let Some(groups) = &self.groups else {
+            return Ok(());
+        };
+        let Some(group) = groups.borrow_mut().remove(&pkg_id) else {
+            return Ok(());
+        };
+        let mut shell = self.gctx.shell();
+        for (diag, mut units) in group.diags {
+            // Units finish in any order.
+            units.sort();
+            shell.print_ansi_stderr(diag.trim_end().as_bytes())?;
+            shell.err().write_all(b"\n")?;
+            if units.len() > 1 {
+                shell.note(format!(
+                    "emitted by {} units: {}",
+                    units.len(),
+                    describe_units(&units).join(", ")
+                ))?;
+            }
+            shell.err().write_all(b"\n")?;
+        }
+        for summary in group.summaries {
+            shell.warn(summary)?;
+        }
+        Ok(())
+    }
+
+    /// Prints all the diagnostics that are still held back.
+    fn flush_all(&self) -> CargoResult<()> {
+        let Some(groups) = &self.groups else {
+            return Ok(());
+        };
+        let mut pkg_ids = groups.borrow().keys().copied().collect::<Vec<_>>();
+        pkg_ids.sort();
+        for pkg_id in pkg_ids {
+            self.flush(pkg_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Describes the units that emitted the same diagnostic, adding the platform
+/// and the features of each unit when that's needed to tell them apart.
+fn describe_units(units: &[Unit]) -> Vec<String> {
+    let names = units
+        .iter()
+        .map(|unit| descriptive_pkg_name(&unit.pkg.name(), &unit.target, &unit.mode))
+        .collect::<Vec<_>>();
+    if names.iter().collect::<HashSet<_>>().len() == names.len() {
+        return names;
+    }
+    names
+        .into_iter()
+        .zip(units)
+        .map(|(name, unit)| {
+            let platform = match &unit.kind {
+                CompileKind::Host => "the host",
+                CompileKind::Target(target) => target.short_name(),
+            };
+            let features = match unit.features.as_slice() {
+                [] => "no features".to_owned(),
+                features => format!(
+                    "features {}",
+                    features
+                        .iter()
+                        .map(|f| format!("`{f}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+            format!("{name} for {platform} with {features}")
+        })
+        .collect()
 }
 
 /// Possible artifacts that can be produced by compilations, used as edge values
@@ -342,6 +471,9 @@ enum Artifact {
 
 enum Message {
     Run(JobId, String),
+    /// A build script's results were restored from the build script cache
+    /// instead of running it.
+    Restored(JobId, String),
     BuildPlanMsg(String, ProcessBuilder, Arc<Vec<OutputFile>>),
     Stdout(String),
     Stderr(String),
@@ -372,6 +504,9 @@ enum Message {
     Token(io::Result<Acquired>),
     Finish(JobId, Artifact, CargoResult<()>),
     FutureIncompatReport(JobId, Vec<FutureBreakageItem>),
+    UnusedDependency(JobId, String),
+    /// A diagnostic emitted by rustc as JSON, for `--message-format=sarif`.
+    CompilerDiagnostic(JobId, String),
 }
 
 impl<'gctx> JobQueue<'gctx> {
@@ -395,10 +530,12 @@ impl<'gctx> JobQueue<'gctx> {
             .filter(|dep| {
                 // Binaries aren't actually needed to *compile* tests, just to run
                 // them, so we don't include this dependency edge in the job graph.
-                // But we shouldn't filter out dependencies being scraped for Rustdoc.
+                // But we shouldn't filter out dependencies being scraped for Rustdoc,
+                // nor the binaries a post-build script runs on.
                 (!dep.unit.target.is_test() && !dep.unit.target.is_bin())
                     || dep.unit.artifact.is_true()
                     || dep.unit.mode.is_doc_scrape()
+                    || unit.mode.is_run_post_build()
             })
             .map(|dep| {
                 // Handle the case here where our `unit -> dep` dependency may
@@ -503,6 +640,8 @@ impl<'gctx> JobQueue<'gctx> {
             ),
             finished: 0,
             per_package_future_incompat_reports: Vec::new(),
+            sarif_report: (build_runner.bcx.build_config.message_format == MessageFormat::Sarif)
+                .then(|| SarifReport::new(build_runner.bcx.ws.root())),
         };
 
         // Create a helper thread for acquiring jobserver tokens
@@ -584,6 +723,17 @@ impl<'gctx> DrainState<'gctx> {
                     &unit,
                     job.freshness(),
                 )?;
+                machine_message::BuildStatus::Compiling {
+                    // The id that `run` gives to the job.
+                    id: self.next_id,
+                    package_id: unit.pkg.package_id().to_spec(),
+                    target: &unit.target,
+                    mode: unit.mode,
+                    fresh: job.freshness().is_fresh(),
+                    current: self.next_id as usize + 1,
+                    total: self.total_units,
+                }
+                .emit(&mut build_runner.bcx.gctx.shell())?;
             }
             self.run(&unit, job, build_runner, scope);
         }
@@ -610,6 +760,14 @@ impl<'gctx> DrainState<'gctx> {
                     .verbose(|c| c.status("Running", &cmd))?;
                 self.timings.unit_start(id, self.active[&id].clone());
             }
+            Message::Restored(id, descr) => {
+                build_runner
+                    .bcx
+                    .gctx
+                    .shell()
+                    .verbose(|c| c.status("Restored", &descr))?;
+                self.timings.unit_start(id, self.active[&id].clone());
+            }
             Message::BuildPlanMsg(module_name, cmd, filenames) => {
                 plan.update(&module_name, &cmd, &filenames)?;
             }
@@ -627,7 +785,11 @@ impl<'gctx> DrainState<'gctx> {
                 diag,
                 fixable,
             } => {
-                let emitted = self.diag_dedupe.emit_diag(&diag)?;
+                let emitted = if self.diag_dedupe.is_grouping() {
+                    self.diag_dedupe.group_diag(&self.active[&id], diag)
+                } else {
+                    self.diag_dedupe.emit_diag(&diag)?
+                };
                 if level == "warning" {
                     self.bump_warning_count(id, emitted, fixable);
                 }
@@ -674,7 +836,10 @@ impl<'gctx> DrainState<'gctx> {
                 };
                 debug!("end ({:?}): {:?}", unit, result);
                 match result {
-                    Ok(()) => self.finish(id, &unit, artifact, build_runner)?,
+                    Ok(()) => {
+                        self.finish(id, &unit, artifact, build_runner)?;
+                        self.flush_diags(&unit)?;
+                    }
                     Err(_) if build_runner.bcx.unit_can_fail_for_docscraping(&unit) => {
                         build_runner
                             .failed_scrape_units
@@ -682,8 +847,13 @@ impl<'gctx> DrainState<'gctx> {
                             .unwrap()
                             .insert(build_runner.files().metadata(&unit));
                         self.queue.finish(&unit, &artifact);
+                        self.flush_diags(&unit)?;
                     }
                     Err(error) => {
+                        // The diagnostics explaining the error must come
+                        // before it, even if other units of the package are
+                        // still running.
+                        self.diag_dedupe.flush(unit.pkg.package_id())?;
                         let msg = "The following warnings were emitted during compilation:";
                         self.emit_warnings(Some(msg), &unit, build_runner)?;
                         self.back_compat_notice(build_runner, &unit)?;
@@ -699,6 +869,27 @@ impl<'gctx> DrainState<'gctx> {
                 self.per_package_future_incompat_reports
                     .push(FutureIncompatReportPackage { package_id, items });
             }
+            Message::UnusedDependency(id, name) => {
+                let unit = &self.active[&id];
+                let dep = build_runner
+                    .unit_deps(unit)
+                    .iter()
+                    .find(|dep| dep.extern_crate_name.as_str() == name)
+                    .map(|dep| dep.unit.pkg.package_id());
+                if let Some(dep) = dep {
+                    build_runner
+                        .compilation
+                        .unused_dependencies
+                        .entry(unit.clone())
+                        .or_default()
+                        .insert(dep);
+                }
+            }
+            Message::CompilerDiagnostic(_id, diagnostic) => {
+                if let Some(report) = &mut self.sarif_report {
+                    report.add(&diagnostic);
+                }
+            }
             Message::Token(acquired_token) => {
                 let token = acquired_token.with_context(|| "failed to acquire jobserver token")?;
                 self.tokens.push(token);
@@ -787,6 +978,9 @@ impl<'gctx> DrainState<'gctx> {
             }
         }
         self.progress.clear();
+        if let Err(e) = self.diag_dedupe.flush_all() {
+            self.handle_error(&mut build_runner.bcx.gctx.shell(), &mut errors, e);
+        }
 
         let profile_name = build_runner.bcx.build_config.requested_profile;
         // NOTE: this may be a bit inaccurate, since this may not display the
@@ -820,6 +1014,21 @@ impl<'gctx> DrainState<'gctx> {
                 self.handle_error(&mut shell, &mut errors, e);
             }
         }
+        if let Some(report) = &self.sarif_report {
+            let mut shell = build_runner.bcx.gctx.shell();
+            if let Err(e) = writeln!(shell.out(), "{}", report.to_json_string()) {
+                self.handle_error(&mut shell, &mut errors, e);
+            }
+        }
+        if !build_runner.bcx.build_config.build_plan {
+            let mut shell = build_runner.bcx.gctx.shell();
+            let status = machine_message::BuildStatus::Finished {
+                success: errors.count == 0,
+            };
+            if let Err(e) = status.emit(&mut shell) {
+                self.handle_error(&mut shell, &mut errors, e);
+            }
+        }
 
         if let Some(error) = errors.to_error() {
             // Any errors up to this point have already been printed via the
@@ -898,9 +1107,12 @@ impl<'gctx> DrainState<'gctx> {
         match unit.mode {
             CompileMode::Doc { .. } => format!("{}(doc)", pkg_name),
             CompileMode::RunCustomBuild => format!("{}(build)", pkg_name),
+            CompileMode::RunPostBuild => format!("{}(post-build)", pkg_name),
             CompileMode::Test | CompileMode::Check { test: true } => match unit.target.kind() {
                 TargetKind::Lib(_) => format!("{}(test)", target_name),
-                TargetKind::CustomBuild => panic!("cannot test build script"),
+                TargetKind::CustomBuild | TargetKind::PostBuild => {
+                    panic!("cannot test build script")
+                }
                 TargetKind::Bin => format!("{}(bin test)", target_name),
                 TargetKind::Test => format!("{}(test)", target_name),
                 TargetKind::Bench => format!("{}(bench)", target_name),
@@ -911,6 +1123,7 @@ impl<'gctx> DrainState<'gctx> {
             _ => match unit.target.kind() {
                 TargetKind::Lib(_) => pkg_name.to_string(),
                 TargetKind::CustomBuild => format!("{}(build.rs)", pkg_name),
+                TargetKind::PostBuild => format!("{}(post-build.rs)", pkg_name),
                 TargetKind::Bin => format!("{}(bin)", target_name),
                 TargetKind::Test => format!("{}(test)", target_name),
                 TargetKind::Bench => format!("{}(bench)", target_name),
@@ -1051,7 +1264,7 @@ impl<'gctx> DrainState<'gctx> {
             // Do not show this if there are any errors or no fixable warnings
             if let FixableWarnings::Positive(fixable) = count.fixable {
                 // `cargo fix` doesn't have an option for custom builds
-                if !unit.target.is_custom_build() {
+                if !unit.target.is_custom_build() && !unit.target.is_post_build() {
                     // To make sure the correct command is shown for `clippy` we
                     // check if `RUSTC_WORKSPACE_WRAPPER` is set and pointing towards
                     // `clippy-driver`.
@@ -1086,11 +1299,30 @@ impl<'gctx> DrainState<'gctx> {
                 }
             }
         }
+        if self.diag_dedupe.is_grouping() {
+            self.diag_dedupe
+                .group_summary(unit.pkg.package_id(), message);
+            return;
+        }
         // Errors are ignored here because it is tricky to handle them
         // correctly, and they aren't important.
         let _ = gctx.shell().warn(message);
     }
 
+    /// Prints the diagnostics held back for the package of `unit` with
+    /// `-Zgroup-diagnostics`, once none of its units are left to run.
+    fn flush_diags(&self, unit: &Unit) -> CargoResult<()> {
+        let pkg_id = unit.pkg.package_id();
+        let running = self
+            .active
+            .values()
+            .any(|active| active.pkg.package_id() == pkg_id);
+        if self.counts[&pkg_id] == 0 && !running {
+            self.diag_dedupe.flush(pkg_id)?;
+        }
+        Ok(())
+    }
+
     fn finish(
         &mut self,
         id: JobId,
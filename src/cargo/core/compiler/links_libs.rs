@@ -0,0 +1,410 @@
+//! Probing for the system libraries declared in `[package.links-libs]`.
+//!
+//! A package can declare the system libraries it links to instead of
+//! finding them in a build script (requires the `links-libs` Cargo feature):
+//!
+//! ```toml
+//! [package.links-libs.ssl]
+//! pkg-config = "openssl"
+//! version = "1.1"
+//! install = { apt = "libssl-dev", brew = "openssl@3" }
+//! ```
+//!
+//! Before anything is built, [`build_map`] probes each of them with
+//! `pkg-config`, so a missing library is reported up front along with how to
+//! install it, rather than as a linker error. Only `pkg-config` is used for
+//! probing, `vcpkg` is only suggested when a library is missing. The results
+//! are saved in the build directory and reused until the `.pc` file of a
+//! library, the declarations or the `pkg-config` environment change.
+//!
+//! What was found is then used:
+//!
+//! * to pass `-l` flags to the targets of the package, and `-L` flags to it
+//!   and everything depending on it, like `cargo::rustc-link-lib` and
+//!   `cargo::rustc-link-search` do, see [`rustc_args`],
+//! * to tell the build script of the package and of the packages depending on
+//!   it where the headers are, see [`build_script_env`].
+//!
+//! [`build_map`]: super::custom_build::build_map
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use cargo_util::{paths, ProcessBuilder};
+use cargo_util_schemas::manifest::TomlLinksLib;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::core::compiler::{BuildRunner, CompileKind, Unit};
+use crate::core::{Package, PackageId};
+use crate::util::errors::CargoResult;
+use crate::util::hex::short_hash;
+use crate::GlobalContext;
+
+/// Environment variables changing what `pkg-config` finds.
+const PKG_CONFIG_ENV: &[&str] = &[
+    "PKG_CONFIG_PATH",
+    "PKG_CONFIG_LIBDIR",
+    "PKG_CONFIG_SYSROOT_DIR",
+    "PKG_CONFIG_ALLOW_SYSTEM_CFLAGS",
+    "PKG_CONFIG_ALLOW_SYSTEM_LIBS",
+];
+
+/// A system library found by `pkg-config`.
+#[derive(Debug, Clone, Hash, PartialEq, Serialize, Deserialize)]
+pub struct SystemLib {
+    /// The name of the entry in `[package.links-libs]`.
+    pub name: String,
+    /// The version reported by `pkg-config`.
+    pub version: String,
+    /// Libraries to link, from `-l` flags.
+    pub libs: Vec<String>,
+    /// Frameworks to link, from `-framework` flags.
+    pub frameworks: Vec<String>,
+    /// Directories to search for them, from `-L` flags.
+    pub link_paths: Vec<PathBuf>,
+    /// Directories with the headers of the library, from `-I` and `-isystem`
+    /// flags.
+    pub include_paths: Vec<PathBuf>,
+}
+
+/// The results of probing the libraries of a package, as saved by [`probe`].
+#[derive(Serialize, Deserialize)]
+struct ProbeCache {
+    /// A hash of the declarations, the `pkg-config` program and its
+    /// environment.
+    key: String,
+    /// The `.pc` file of each library with its modification time.
+    pc_files: Vec<(PathBuf, i64, u32)>,
+    libs: Vec<SystemLib>,
+}
+
+/// Returns the `[package.links-libs]` table of `pkg`.
+pub fn declared(pkg: &Package) -> Option<&BTreeMap<String, TomlLinksLib>> {
+    pkg.manifest()
+        .resolved_toml()
+        .package()
+        .and_then(|p| p.links_libs.as_ref())
+}
+
+/// Probes all the system libraries `pkg` declares, for building it for
+/// `kind`.
+///
+/// The results are saved in `cache_dir`, and returned from there by later
+/// calls as long as they are still up to date.
+pub fn probe(
+    gctx: &GlobalContext,
+    pkg: &Package,
+    kind: CompileKind,
+    cache_dir: &Path,
+) -> CargoResult<Vec<SystemLib>> {
+    let Some(declared) = declared(pkg) else {
+        return Ok(Vec::new());
+    };
+    let program = match kind {
+        CompileKind::Target(target) => {
+            gctx.get_env_os(format!("PKG_CONFIG_{}", super::envify(target.short_name())))
+        }
+        CompileKind::Host => None,
+    }
+    .or_else(|| gctx.get_env_os("PKG_CONFIG"))
+    .unwrap_or_else(|| OsString::from("pkg-config"));
+    let env: Vec<_> = PKG_CONFIG_ENV
+        .iter()
+        .map(|var| (var, gctx.get_env_os(var)))
+        .collect();
+    let key = short_hash(&(&program, serde_json::to_string(declared)?, env));
+    let cache_path = cache_dir.join(format!(
+        "links-libs-{}-{}.json",
+        pkg.name(),
+        short_hash(&(pkg.package_id(), kind))
+    ));
+    if let Some(libs) = load_cache(&cache_path, &key) {
+        return Ok(libs);
+    }
+
+    let mut libs = Vec::new();
+    let mut pc_files = Vec::new();
+    for (name, lib) in declared {
+        let (found, pc_file) = probe_one(&program, name, lib).with_context(|| {
+            let mut msg =
+                format!("failed to find system library `{name}` required by package `{pkg}`");
+            install_help(&mut msg, lib);
+            msg
+        })?;
+        libs.push(found);
+        pc_files.extend(pc_file);
+    }
+    // Without knowing where a library came from, there is no telling when
+    // the results are out of date, so they are not saved.
+    if pc_files.len() == libs.len() {
+        if let Err(e) = store_cache(&cache_path, key, &pc_files, &libs) {
+            debug!("failed to save probed system libraries: {e:?}");
+        }
+    }
+    Ok(libs)
+}
+
+/// Returns the saved results at `path`, if they exist and are up to date.
+fn load_cache(path: &Path, key: &str) -> Option<Vec<SystemLib>> {
+    let cache: ProbeCache = serde_json::from_slice(&paths::read_bytes(path).ok()?).ok()?;
+    if cache.key != key {
+        return None;
+    }
+    for (pc_file, seconds, nanos) in &cache.pc_files {
+        let mtime = paths::mtime(pc_file).ok()?;
+        if (mtime.unix_seconds(), mtime.nanoseconds()) != (*seconds, *nanos) {
+            debug!(
+                "`{}` changed, probing system libraries again",
+                pc_file.display()
+            );
+            return None;
+        }
+    }
+    Some(cache.libs)
+}
+
+fn store_cache(
+    path: &Path,
+    key: String,
+    pc_files: &[PathBuf],
+    libs: &[SystemLib],
+) -> CargoResult<()> {
+    let pc_files = pc_files
+        .iter()
+        .map(|pc_file| {
+            let mtime = paths::mtime(pc_file)?;
+            Ok((pc_file.clone(), mtime.unix_seconds(), mtime.nanoseconds()))
+        })
+        .collect::<CargoResult<_>>()?;
+    let cache = ProbeCache {
+        key,
+        pc_files,
+        libs: libs.to_vec(),
+    };
+    paths::write(path, serde_json::to_vec(&cache)?)
+}
+
+/// Probes a single library, returning what was found along with the `.pc`
+/// file it came from, if `pkg-config` tells.
+fn probe_one(
+    program: &OsString,
+    name: &str,
+    lib: &TomlLinksLib,
+) -> CargoResult<(SystemLib, Option<PathBuf>)> {
+    let module = lib.pkg_config.as_deref().unwrap_or(name);
+    let run = |args: &[&str]| -> CargoResult<String> {
+        let output = ProcessBuilder::new(program)
+            .args(args)
+            .arg(module)
+            .exec_with_output()?;
+        Ok(String::from_utf8(output.stdout)?)
+    };
+    let version = run(&["--modversion"])?.trim().to_string();
+    if let Some(required) = &lib.version {
+        if run(&[&format!("--atleast-version={required}")]).is_err() {
+            anyhow::bail!(
+                "found version {version} of `{module}`, but at least {required} is required"
+            );
+        }
+    }
+    let mut found = SystemLib {
+        name: name.to_string(),
+        version,
+        libs: Vec::new(),
+        frameworks: Vec::new(),
+        link_paths: Vec::new(),
+        include_paths: Vec::new(),
+    };
+    parse_flags(&mut found, &run(&["--libs"])?);
+    parse_flags(&mut found, &run(&["--cflags"])?);
+    let pc_file = run(&["--variable=pcfiledir"])
+        .ok()
+        .map(|dir| Path::new(dir.trim()).join(format!("{module}.pc")))
+        .filter(|pc_file| pc_file.is_file());
+    Ok((found, pc_file))
+}
+
+/// Adds the flags from the output of `pkg-config --libs` or `--cflags` to
+/// `found`, ignoring the ones Cargo has no use for.
+fn parse_flags(found: &mut SystemLib, output: &str) {
+    let mut flags = split_flags(output).into_iter();
+    while let Some(flag) = flags.next() {
+        // The value of a flag may also be passed separately, as in
+        // `-L /usr/lib`.
+        let mut value = |prefix: &str| -> Option<String> {
+            let rest = flag.strip_prefix(prefix)?;
+            if rest.is_empty() {
+                flags.next()
+            } else {
+                Some(rest.to_string())
+            }
+        };
+        if flag == "-framework" {
+            found.frameworks.extend(value("-framework"));
+        } else if flag == "-isystem" {
+            found
+                .include_paths
+                .extend(value("-isystem").map(PathBuf::from));
+        } else if let Some(lib) = value("-l") {
+            found.libs.push(lib);
+        } else if let Some(path) = value("-L") {
+            found.link_paths.push(PathBuf::from(path));
+        } else if let Some(path) = value("-I") {
+            found.include_paths.push(PathBuf::from(path));
+        }
+    }
+}
+
+/// Splits the output of `pkg-config` into flags, undoing the quoting and
+/// escaping it applies to values with spaces.
+fn split_flags(output: &str) -> Vec<String> {
+    let mut flags = Vec::new();
+    let mut flag = None::<String>;
+    let mut quote = None;
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', None | Some('"')) => {
+                let flag = flag.get_or_insert_with(String::new);
+                flag.extend(chars.next());
+            }
+            ('"' | '\'', None) => {
+                flag.get_or_insert_with(String::new);
+                quote = Some(c);
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => flags.extend(flag.take()),
+            (c, _) => flag.get_or_insert_with(String::new).push(c),
+        }
+    }
+    flags.extend(flag);
+    flags
+}
+
+/// Appends how to install `lib` to an error message, if the manifest says.
+fn install_help(msg: &mut String, lib: &TomlLinksLib) {
+    let mut commands: Vec<_> = lib
+        .install
+        .iter()
+        .flatten()
+        .map(|(manager, package)| format!("{manager} install {package}"))
+        .collect();
+    if let Some(port) = &lib.vcpkg {
+        commands.push(format!("vcpkg install {port}"));
+    }
+    if !commands.is_empty() {
+        msg.push_str("\nhelp: install it with one of");
+        for command in commands {
+            msg.push_str(&format!("\n  {command}"));
+        }
+    }
+}
+
+/// Returns the flags to link `unit` with the system libraries of its package
+/// and its dependencies.
+///
+/// Like with build scripts, `-l` flags are only passed to the library of a
+/// package, or to all of its targets if it has none.
+pub fn rustc_args(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> Vec<OsString> {
+    let mut result = Vec::new();
+    let Some(scripts) = build_runner.build_scripts.get(unit) else {
+        return result;
+    };
+    let pass_l_flag = unit.target.is_lib() || !unit.pkg.targets().iter().any(|t| t.is_lib());
+    for key in &scripts.links_libs {
+        for lib in build_runner.links_libs[key].iter() {
+            for path in &lib.link_paths {
+                let mut arg = OsString::from("native=");
+                arg.push(path);
+                result.push(OsString::from("-L"));
+                result.push(arg);
+            }
+            if key.0 == unit.pkg.package_id() && pass_l_flag {
+                for name in &lib.libs {
+                    result.push(OsString::from("-l"));
+                    result.push(OsString::from(name));
+                }
+                for name in &lib.frameworks {
+                    result.push(OsString::from("-l"));
+                    result.push(OsString::from(format!("framework={name}")));
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Returns the environment variables describing the system libraries
+/// available to the build script run by `unit`.
+///
+/// The build script gets `CARGO_LINKS_LIB_<NAME>_VERSION` and
+/// `CARGO_LINKS_LIB_<NAME>_INCLUDE` for the libraries declared by its package
+/// and by everything the package links to. It is an error for two of them to
+/// declare the same name, but find different libraries.
+pub fn build_script_env(
+    build_runner: &BuildRunner<'_, '_>,
+    unit: &Unit,
+) -> CargoResult<Vec<(String, OsString)>> {
+    assert!(unit.mode.is_run_custom_build());
+    let mut result = Vec::new();
+    if build_runner.links_libs.is_empty() {
+        return Ok(result);
+    }
+    // The libraries a package links to are collected by `build_map` for
+    // each of its units. Tests may link to more through dev-dependencies,
+    // which the build script doesn't get to see.
+    let own = (unit.pkg.package_id(), unit.kind);
+    let mut keys: Vec<_> = build_runner
+        .build_scripts
+        .iter()
+        .filter(|(u, _)| {
+            u.pkg == unit.pkg
+                && u.kind == unit.kind
+                && !u.target.is_custom_build()
+                && !u.mode.is_any_test()
+        })
+        .flat_map(|(_, scripts)| scripts.links_libs.iter().copied())
+        .filter(|key| *key != own)
+        .collect();
+    keys.sort();
+    keys.dedup();
+    if build_runner.links_libs.contains_key(&own) {
+        keys.insert(0, own);
+    }
+    let mut seen: HashMap<&str, (PackageId, &SystemLib)> = HashMap::new();
+    for key in keys {
+        for lib in build_runner.links_libs[&key].iter() {
+            match seen.entry(&lib.name) {
+                Entry::Occupied(e) => {
+                    let (other_pkg, other) = e.get();
+                    if *other != lib {
+                        anyhow::bail!(
+                            "system library `{}` is declared by both `{}` and `{}`, \
+                             but they found different libraries",
+                            lib.name,
+                            other_pkg,
+                            key.0
+                        );
+                    }
+                    continue;
+                }
+                Entry::Vacant(e) => {
+                    e.insert((key.0, lib));
+                }
+            }
+            let name = super::envify(&lib.name);
+            result.push((
+                format!("CARGO_LINKS_LIB_{name}_VERSION"),
+                OsString::from(&lib.version),
+            ));
+            let include = std::env::join_paths(&lib.include_paths)
+                .context("failed to join include paths of a system library")?;
+            result.push((format!("CARGO_LINKS_LIB_{name}_INCLUDE"), include));
+        }
+    }
+    Ok(result)
+}
@@ -26,35 +26,42 @@
 //! * [`Unit`] contains sufficient information to build something, usually
 //!   turning into a compiler invocation in a later phase.
 //!
-//! [^1]: Maybe [`-Zbuild-plan`](https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#build-plan)
-//!   was designed to serve that purpose but still [in flux](https://github.com/rust-lang/cargo/issues/7614).
+//! [^1]: [`--build-plan`](https://doc.rust-lang.org/nightly/cargo/reference/external-tools.html#build-plan)
+//!   serves that purpose for external executors, but Cargo itself doesn't build from it.
 //!
 //! [`ops::cargo_compile::compile`]: crate::ops::compile
 
 pub mod artifact;
+mod artifact_dir;
 mod build_config;
 pub(crate) mod build_context;
-mod build_plan;
+pub(crate) mod build_plan;
 pub(crate) mod build_runner;
+mod build_sandbox;
+mod build_script_cache;
 mod compilation;
 mod compile_kind;
 mod crate_type;
 mod custom_build;
+mod external_executor;
 pub(crate) mod fingerprint;
 pub mod future_incompat;
 pub(crate) mod job_queue;
 pub(crate) mod layout;
 mod links;
+mod links_libs;
 mod lto;
 mod output_depinfo;
+mod post_build;
 pub mod rustdoc;
+mod sarif;
 pub mod standard_lib;
 mod timings;
 mod unit;
 pub mod unit_dependencies;
 pub mod unit_graph;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::fmt::Display;
@@ -67,17 +74,22 @@ use anyhow::{bail, Context as _, Error};
 use lazycell::LazyCell;
 use tracing::{debug, trace};
 
-pub use self::build_config::{BuildConfig, CompileMode, MessageFormat, TimingOutput};
+pub use self::build_config::{
+    BuildConfig, ClippyConfig, CompileMode, MessageFormat, PrintEnvFormat, PrintEnvSelection,
+    TimingOutput,
+};
 pub use self::build_context::{
     BuildContext, FileFlavor, FileType, RustDocFingerprint, RustcTargetData, TargetInfo,
 };
 use self::build_plan::BuildPlan;
 pub use self::build_runner::{BuildRunner, Metadata};
+pub(crate) use self::compilation::ARTIFACT_PLACEHOLDER;
 pub use self::compilation::{Compilation, Doctest, UnitOutput};
 pub use self::compile_kind::{CompileKind, CompileTarget};
 pub use self::crate_type::CrateType;
 pub use self::custom_build::LinkArgTarget;
 pub use self::custom_build::{BuildOutput, BuildScriptOutputs, BuildScripts};
+pub use self::external_executor::ExternalExecutor;
 pub(crate) use self::fingerprint::DirtyReason;
 pub use self::job_queue::Freshness;
 use self::job_queue::{Job, JobQueue, JobState, Work};
@@ -87,14 +99,15 @@ use self::output_depinfo::output_depinfo;
 use self::unit_graph::UnitDep;
 use crate::core::compiler::future_incompat::FutureIncompatReport;
 pub use crate::core::compiler::unit::{Unit, UnitInterner};
-use crate::core::manifest::TargetSourcePath;
+use crate::core::manifest::{LinkInputs, TargetSourcePath};
 use crate::core::profiles::{PanicStrategy, Profile, StripInner};
 use crate::core::{Feature, PackageId, Target, Verbosity};
 use crate::util::errors::{CargoResult, VerboseError};
 use crate::util::interning::InternedString;
 use crate::util::machine_message::{self, Message};
-use crate::util::{add_path_args, internal};
-use cargo_util::{paths, ProcessBuilder, ProcessError};
+use crate::util::{self, add_path_args, internal};
+use cargo_util::{paths, ProcessBuilder, ProcessError, Sha256};
+use cargo_util_schemas::artifact_hashes::{ArtifactHashes, FileHash};
 use cargo_util_schemas::manifest::TomlDebugInfo;
 use cargo_util_schemas::manifest::TomlTrimPaths;
 use rustfix::diagnostics::Applicability;
@@ -179,6 +192,8 @@ fn compile<'gctx>(
 
     let job = if unit.mode.is_run_custom_build() {
         custom_build::prepare(build_runner, unit)?
+    } else if unit.mode.is_run_post_build() {
+        post_build::prepare(build_runner, unit)?
     } else if unit.mode.is_doc_test() {
         // We run these targets later, so this is just a no-op for now.
         Job::new_fresh()
@@ -207,6 +222,8 @@ fn compile<'gctx>(
                 build_runner.files().message_cache_path(unit),
                 build_runner.bcx.build_config.message_format,
                 unit.show_warnings(bcx.gctx),
+                bcx.checks_unused_dependencies(unit),
+                source_hashes_root(bcx),
             );
             // Need to link targets on both the dirty and fresh.
             work.then(link_targets(build_runner, unit, true)?)
@@ -346,7 +363,7 @@ fn rustc(
         // previous build scripts, we include them in the rustc invocation.
         if let Some(build_scripts) = build_scripts {
             let script_outputs = build_script_outputs.lock().unwrap();
-            if !build_plan {
+            if !build_plan || build_scripts.outputs_known(&script_outputs) {
                 add_native_deps(
                     &mut rustc,
                     &script_outputs,
@@ -534,8 +551,15 @@ fn link_targets(
     let profile = unit.profile.clone();
     let unit_mode = unit.mode;
     let features = unit.features.iter().map(|s| s.to_string()).collect();
+    let platform = unit.kind;
     let json_messages = bcx.build_config.emit_json();
     let executable = build_runner.get_executable(unit)?;
+    let fingerprint = bcx
+        .gctx
+        .cli_unstable()
+        .artifact_hashes
+        .then(|| build_runner.fingerprints.get(unit).cloned())
+        .flatten();
     let mut target = Target::clone(&unit.target);
     if let TargetSourcePath::Metabuild = target.src_path() {
         // Give it something to serialize.
@@ -564,6 +588,9 @@ fn link_targets(
                 continue;
             };
             destinations.push(dst.clone());
+            // A member's own directory with `workspace.split-target-dirs`
+            // isn't created by the layout.
+            paths::create_dir_all(dst.parent().unwrap())?;
             paths::link_or_copy(src, dst)?;
             if let Some(ref path) = output.export_path {
                 let export_dir = export_dir.as_ref().unwrap();
@@ -593,15 +620,41 @@ fn link_targets(
                 test: unit_mode.is_any_test(),
             };
 
+            // Hashed once the unit is built, like the fingerprint written
+            // after it.
+            let hashes = fingerprint
+                .map(|fingerprint| -> CargoResult<_> {
+                    let files = destinations
+                        .iter()
+                        .map(|path| {
+                            let sha256 = if path.is_dir() {
+                                None
+                            } else {
+                                Some(Sha256::new().update_path(path)?.finish_hex())
+                            };
+                            Ok(FileHash {
+                                path: path.clone(),
+                                sha256,
+                            })
+                        })
+                        .collect::<CargoResult<_>>()?;
+                    Ok(ArtifactHashes {
+                        fingerprint: util::to_hex(fingerprint.hash_u64()),
+                        files,
+                    })
+                })
+                .transpose()?;
             let msg = machine_message::Artifact {
                 package_id: package_id.to_spec(),
                 manifest_path,
                 target: &target,
+                platform,
                 profile: art_profile,
                 features,
                 filenames: destinations,
                 executable,
                 fresh,
+                hashes,
             }
             .to_json_string();
             state.stdout(msg)?;
@@ -673,7 +726,7 @@ where
 /// from build scripts.
 fn prepare_rustc(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> CargoResult<ProcessBuilder> {
     let is_primary = build_runner.is_primary_package(unit);
-    let is_workspace = build_runner.bcx.ws.is_member(&unit.pkg);
+    let is_workspace = build_runner.bcx.uses_workspace_wrapper(unit);
 
     let mut base = build_runner
         .compilation
@@ -684,6 +737,8 @@ fn prepare_rustc(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> CargoResult
     build_deps_args(&mut base, build_runner, unit)?;
     add_cap_lints(build_runner.bcx, unit, &mut base);
     base.args(build_runner.bcx.rustflags_args(unit));
+    // Like with `cargo-clippy`, these come last to override any other flag.
+    base.args(build_runner.bcx.clippy_args_for(unit));
     if build_runner.bcx.gctx.cli_unstable().binary_dep_depinfo {
         base.arg("-Z").arg("binary-dep-depinfo");
     }
@@ -779,6 +834,7 @@ fn prepare_rustdoc(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> CargoResu
     rustdoc::add_root_urls(build_runner, unit, &mut rustdoc)?;
 
     rustdoc::add_output_format(build_runner, unit, &mut rustdoc)?;
+    rustdoc::add_index_page(build_runner, unit, &mut rustdoc)?;
 
     rustdoc.args(bcx.rustdocflags_args(unit));
 
@@ -1030,8 +1086,9 @@ fn build_base_args(
         cmd.arg("--emit=dep-info,link");
     }
 
-    let prefer_dynamic = (unit.target.for_host() && !unit.target.is_custom_build())
-        || (contains_dy_lib && !build_runner.is_primary_package(unit));
+    let prefer_dynamic =
+        (unit.target.for_host() && !unit.target.is_custom_build() && !unit.target.is_post_build())
+            || (contains_dy_lib && !build_runner.is_primary_package(unit));
     if prefer_dynamic {
         cmd.arg("-C").arg("prefer-dynamic");
     }
@@ -1080,11 +1137,18 @@ fn build_base_args(
         trim_paths_args(cmd, build_runner, unit, &trim_paths)?;
     }
 
+    if let Some(remap) = embedded_remap_arg(unit) {
+        cmd.arg(remap);
+    }
+
     cmd.args(unit.pkg.manifest().lint_rustflags());
     cmd.args(&profile_rustflags);
+    cmd.args(build_runner.bcx.target_profile_rustflags_args(unit));
     if let Some(args) = build_runner.bcx.extra_args_for(unit) {
         cmd.args(args);
     }
+    cmd.args(&link_inputs_args(build_runner, unit));
+    cmd.args(&links_libs::rustc_args(build_runner, unit));
 
     // `-C overflow-checks` is implied by the setting of `-C debug-assertions`,
     // so we only need to provide `-C overflow-checks` if it differs from
@@ -1197,6 +1261,7 @@ fn build_base_args(
             .filter(|target| target.is_bin())
         {
             let exe_path = build_runner.files().bin_link_for_target(
+                unit.pkg.package_id(),
                 bin_target,
                 unit.kind,
                 build_runner.bcx,
@@ -1309,6 +1374,23 @@ fn trim_paths_args(
     Ok(())
 }
 
+/// Generates the `--remap-path-prefix` pointing the paths of a single-file
+/// package back at the script.
+///
+/// These packages are built from a copy of the script without its embedded
+/// manifest, so this makes diagnostics, `file!()` and debuginfo use the path
+/// of the script, relative to its directory like for other packages.
+fn embedded_remap_arg(unit: &Unit) -> Option<OsString> {
+    if !unit.pkg.manifest().is_embedded() {
+        return None;
+    }
+    let copy_dir = unit.target.src_path().path()?.parent()?;
+    let mut remap = OsString::from("--remap-path-prefix=");
+    remap.push(copy_dir);
+    remap.push("=");
+    Some(remap)
+}
+
 /// Generates the `--check-cfg` arguments for the `unit`.
 fn check_cfg_args(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> CargoResult<Vec<OsString>> {
     if build_runner
@@ -1361,7 +1443,12 @@ fn check_cfg_args(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> CargoResul
         ];
 
         // Also include the custom arguments specified in `[lints.rust.unexpected_cfgs.check_cfg]`
-        if let Ok(Some(lints)) = unit.pkg.manifest().resolved_toml().resolved_lints() {
+        if let Ok(Some(lints)) = unit
+            .pkg
+            .manifest()
+            .resolved_toml()
+            .resolved_lints_with_profile(build_runner.bcx.gctx.lints_profile())
+        {
             if let Some(rust_lints) = lints.get("rust") {
                 if let Some(unexpected_cfgs) = rust_lints.get("unexpected_cfgs") {
                     if let Some(config) = unexpected_cfgs.config() {
@@ -1410,6 +1497,56 @@ fn lto_args(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> Vec<OsString> {
     result
 }
 
+/// Returns the `[target.<platform>.link-inputs]` of the package of `unit`
+/// that apply to it, that is, those for its platform if `unit` is linked.
+///
+/// Host units like build scripts are never linked with them, as those tables
+/// are meant for the platform the package is built for.
+pub(crate) fn link_inputs<'a>(bcx: &BuildContext<'_, '_>, unit: &'a Unit) -> Vec<&'a LinkInputs> {
+    let links = !unit.target.for_host()
+        && match unit.mode {
+            CompileMode::Test | CompileMode::Bench => true,
+            CompileMode::Build => unit.target.rustc_crate_types().iter().any(|crate_type| {
+                matches!(
+                    crate_type,
+                    CrateType::Bin | CrateType::Cdylib | CrateType::Dylib
+                )
+            }),
+            _ => false,
+        };
+    if !links {
+        return Vec::new();
+    }
+    let name = bcx.target_data.short_name(&unit.kind);
+    let cfg = bcx.target_data.cfg(unit.kind);
+    unit.pkg
+        .manifest()
+        .link_inputs()
+        .iter()
+        .filter(|inputs| inputs.platform.matches(name, cfg))
+        .collect()
+}
+
+/// Adds the `-C link-arg` flags for the [`link_inputs`] of `unit`.
+fn link_inputs_args(build_runner: &BuildRunner<'_, '_>, unit: &Unit) -> Vec<OsString> {
+    let mut result = Vec::new();
+    let root = unit.pkg.root();
+    for inputs in link_inputs(build_runner.bcx, unit) {
+        let scripts = inputs
+            .linker_scripts
+            .iter()
+            .map(|path| ("link-arg=-T", path));
+        let objects = inputs.objects.iter().map(|path| ("link-arg=", path));
+        for (prefix, path) in scripts.chain(objects) {
+            let mut arg = OsString::from(prefix);
+            arg.push(root.join(path));
+            result.push(OsString::from("-C"));
+            result.push(arg);
+        }
+    }
+    result
+}
+
 /// Adds dependency-relevant rustc flags and environment variables
 /// to the command to execute, such as [`-L`] and [`--extern`].
 ///
@@ -1620,6 +1757,13 @@ struct OutputOptions {
     warnings_seen: usize,
     /// Tracks the number of errors we've seen so far.
     errors_seen: usize,
+    /// If `true`, `unused_crate_dependencies` lints are collected instead of
+    /// displayed. See [`BuildConfig::unused_dependencies`].
+    collect_unused_dependencies: bool,
+    /// The directory the file names in diagnostics are relative to, if the
+    /// hashes of the files are to be recorded in JSON messages.
+    /// See [`source_hashes_root`].
+    source_hashes_root: Option<PathBuf>,
 }
 
 impl OutputOptions {
@@ -1634,8 +1778,60 @@ impl OutputOptions {
             show_diagnostics: true,
             warnings_seen: 0,
             errors_seen: 0,
+            collect_unused_dependencies: build_runner.bcx.checks_unused_dependencies(unit),
+            source_hashes_root: source_hashes_root(build_runner.bcx),
+        }
+    }
+}
+
+/// Returns the directory `rustc` runs in if the hashes of the files
+/// diagnostics point to are recorded in JSON messages, so that
+/// `cargo fix --from-json` can tell whether they changed since.
+///
+/// This is only done with `-Zunstable-options`.
+fn source_hashes_root(bcx: &BuildContext<'_, '_>) -> Option<PathBuf> {
+    let json = matches!(bcx.build_config.message_format, MessageFormat::Json { .. });
+    (json && bcx.gctx.cli_unstable().unstable_options).then(|| bcx.ws.root().to_path_buf())
+}
+
+/// Hashes the files the spans of a diagnostic from `rustc` point to.
+fn source_hashes(root: &Path, compiler_message: &str) -> BTreeMap<String, String> {
+    // Partial diagnostics, as in `on_stderr_line_inner`. Suggestions are in
+    // the spans of the children.
+    #[derive(serde::Deserialize)]
+    struct PartialDiagnostic {
+        spans: Vec<PartialDiagnosticSpan>,
+        children: Vec<PartialChild>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct PartialChild {
+        spans: Vec<PartialDiagnosticSpan>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct PartialDiagnosticSpan {
+        file_name: String,
+    }
+
+    let mut hashes = BTreeMap::new();
+    let Ok(diag) = serde_json::from_str::<PartialDiagnostic>(compiler_message) else {
+        return hashes;
+    };
+    let spans = diag
+        .spans
+        .into_iter()
+        .chain(diag.children.into_iter().flat_map(|child| child.spans));
+    for span in spans {
+        if hashes.contains_key(&span.file_name) {
+            continue;
+        }
+        if let Ok(hash) = cargo_util::Sha256::new().update_path(root.join(&span.file_name)) {
+            let hash = hash.finish_hex();
+            hashes.insert(span.file_name, hash);
         }
     }
+    hashes
 }
 
 fn on_stdout_line(
@@ -1717,6 +1913,30 @@ fn on_stderr_line_inner(
         return Ok(true);
     }
 
+    if options.collect_unused_dependencies {
+        #[derive(serde::Deserialize)]
+        struct CompilerMessage {
+            message: String,
+            code: Option<DiagnosticCode>,
+        }
+        #[derive(serde::Deserialize)]
+        struct DiagnosticCode {
+            code: String,
+        }
+        if let Ok(msg) = serde_json::from_str::<CompilerMessage>(compiler_message.get()) {
+            if msg
+                .code
+                .is_some_and(|code| code.code == "unused_crate_dependencies")
+            {
+                // The message looks like "extern crate `foo` is unused in crate `bar`".
+                if let Some(name) = msg.message.split('`').nth(1) {
+                    state.unused_dependency(name.to_string());
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
     // Depending on what we're emitting from Cargo itself, we figure out what to
     // do with this JSON message.
     match options.format {
@@ -1726,6 +1946,7 @@ fn on_stderr_line_inner(
         // it ourselves.
         MessageFormat::Human
         | MessageFormat::Short
+        | MessageFormat::Sarif
         | MessageFormat::Json {
             render_diagnostics: true,
             ..
@@ -1784,6 +2005,9 @@ fn on_stderr_line_inner(
                         .any(|b| b);
                     count_diagnostic(&msg.level, options);
                     state.emit_diag(msg.level, rendered, machine_applicable)?;
+                    if options.format == MessageFormat::Sarif {
+                        state.compiler_diagnostic(compiler_message.get().to_owned());
+                    }
                 }
                 return Ok(true);
             }
@@ -1848,11 +2072,16 @@ fn on_stderr_line_inner(
         count_diagnostic(&message.level, options);
     }
 
+    let source_hashes = match &options.source_hashes_root {
+        Some(root) => source_hashes(root, compiler_message.get()),
+        None => BTreeMap::new(),
+    };
     let msg = machine_message::FromCompiler {
         package_id: package_id.to_spec(),
         manifest_path,
         target,
         message: compiler_message,
+        source_hashes,
     }
     .to_json_string();
 
@@ -1873,6 +2102,8 @@ fn replay_output_cache(
     path: PathBuf,
     format: MessageFormat,
     show_diagnostics: bool,
+    collect_unused_dependencies: bool,
+    source_hashes_root: Option<PathBuf>,
 ) -> Work {
     let target = target.clone();
     let mut options = OutputOptions {
@@ -1881,6 +2112,8 @@ fn replay_output_cache(
         show_diagnostics,
         warnings_seen: 0,
         errors_seen: 0,
+        collect_unused_dependencies,
+        source_hashes_root,
     };
     Work::new(move |state| {
         if !path.exists() {
@@ -1934,7 +2167,10 @@ pub(crate) fn apply_env_config(
     gctx: &crate::GlobalContext,
     cmd: &mut ProcessBuilder,
 ) -> CargoResult<()> {
-    for (key, value) in gctx.env_config()?.iter() {
+    // The `env` of target aliases comes first so that it wins over `[env]`.
+    let aliases = gctx.target_aliases();
+    let alias_env = aliases.values().filter_map(|(_, alias)| alias.env.as_ref());
+    for (key, value) in alias_env.flatten().chain(gctx.env_config()?.iter()) {
         // never override a value that has already been set by cargo
         if cmd.get_envs().contains_key(key) {
             continue;
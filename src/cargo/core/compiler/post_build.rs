@@ -0,0 +1,158 @@
+//! How to execute a post-build script and track its changes.
+//!
+//! A post-build script is declared with `package.post-build` in the manifest,
+//! under the unstable `post-build-scripts` feature. It's a host program like a
+//! build script, but runs *after* the binaries of its package are built, for
+//! steps like stripping, converting or signing them.
+//!
+//! For each package and compile kind with binaries to build, Cargo inserts a
+//! [`Unit`] with [`CompileMode::RunPostBuild`], which depends on compiling the
+//! script and on the binaries themselves. See
+//! `UnitGenerator::generate_post_build_units`.
+//!
+//! The script learns about the binaries through `CARGO_BIN_FILE_<NAME>`
+//! environment variables. It's run again whenever one of them is rebuilt,
+//! and when any file or environment variable it reports with
+//! `cargo::rerun-if-changed` or `cargo::rerun-if-env-changed` changes. These
+//! are recorded in the dep-info file of the unit, checked like the one of a
+//! `rustc` invocation.
+//!
+//! [`CompileMode::RunPostBuild`]: super::CompileMode::RunPostBuild
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use cargo_util::paths;
+
+use super::fingerprint::{self, DirtyReason, RustcDepInfo};
+use super::{BuildRunner, FileFlavor, Job, Unit, Work};
+use crate::core::profiles::ProfileRoot;
+use crate::util::errors::CargoResult;
+
+/// Prepares a `Job` running the post-build script of `unit`.
+pub fn prepare(build_runner: &mut BuildRunner<'_, '_>, unit: &Unit) -> CargoResult<Job> {
+    assert!(unit.mode.is_run_post_build());
+    let bcx = build_runner.bcx;
+    let dependencies = build_runner.unit_deps(unit).to_vec();
+    let script_unit = dependencies
+        .iter()
+        .find(|d| d.unit.target.is_post_build())
+        .map(|d| &d.unit)
+        .expect("running a post-build script not depending on an actual script");
+    let script_dir = build_runner.files().build_script_dir(script_unit);
+    let run_dir = build_runner.files().post_build_run_dir(unit);
+    let out_dir = run_dir.join("out");
+    let build_plan = bcx.build_config.build_plan;
+    let invocation_name = unit.buildkey();
+
+    let to_exec = script_dir.join(unit.target.name()).into_os_string();
+    let mut cmd = build_runner.compilation.host_process(to_exec, &unit.pkg)?;
+    let debug = unit.profile.debuginfo.is_turned_on();
+    cmd.cwd(unit.pkg.root())
+        .env("OUT_DIR", &out_dir)
+        .env("NUM_JOBS", &bcx.jobs().to_string())
+        .env("TARGET", bcx.target_data.short_name(&unit.kind))
+        .env("DEBUG", debug.to_string())
+        .env("OPT_LEVEL", &unit.profile.opt_level)
+        .env(
+            "PROFILE",
+            match unit.profile.root {
+                ProfileRoot::Release => "release",
+                ProfileRoot::Debug => "debug",
+            },
+        )
+        .env("HOST", &bcx.host_triple())
+        .env("RUSTC", &bcx.rustc().path);
+    for feat in &unit.features {
+        cmd.env(&format!("CARGO_FEATURE_{}", super::envify(feat)), "1");
+    }
+    for dep in dependencies.iter().filter(|d| d.unit.target.is_bin()) {
+        for output in build_runner.outputs(&dep.unit)?.iter() {
+            if output.flavor != FileFlavor::Normal {
+                continue;
+            }
+            let var = format!("CARGO_BIN_FILE_{}", dep.unit.target.name());
+            cmd.env(&var, output.bin_dst());
+        }
+    }
+    if bcx.gctx.extra_verbose() {
+        cmd.display_env_vars();
+    }
+
+    let pkg_descr = unit.pkg.to_string();
+    let pkg_root = unit.pkg.root().to_path_buf();
+    let target_root = bcx.ws.target_dir().into_path_unlocked();
+    let dep_info_loc = fingerprint::dep_info_loc(build_runner, unit);
+    let output_file = run_dir.join("output");
+    let allow_package = unit.pkg.package_id().source_id().is_path();
+    // Snapshot of the environment, to record the values of the variables the
+    // script reports depending on.
+    let current_env = bcx
+        .gctx
+        .env()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+
+    paths::create_dir_all(&script_dir)?;
+
+    let work = Work::new(move |state| {
+        if build_plan {
+            state.build_plan(invocation_name, cmd.clone(), Default::default());
+            return Ok(());
+        }
+
+        paths::create_dir_all(&out_dir)?;
+        state.running(&cmd);
+        let timestamp = paths::set_invocation_time(&run_dir)?;
+        let output = cmd
+            .exec_with_output()
+            .with_context(|| format!("failed to run post-build script for `{}`", pkg_descr))?;
+
+        let mut depinfo = RustcDepInfo::default();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some((key, value)) = line
+                .strip_prefix("cargo::")
+                .or_else(|| line.strip_prefix("cargo:"))
+                .and_then(|directive| directive.split_once('='))
+            else {
+                continue;
+            };
+            match key {
+                "rerun-if-changed" => depinfo.files.push(PathBuf::from(value)),
+                "rerun-if-env-changed" => {
+                    depinfo
+                        .env
+                        .push((value.to_string(), current_env.get(value).cloned()));
+                }
+                "warning" => state.warning(value.to_string())?,
+                _ => {}
+            }
+        }
+
+        paths::write(&output_file, &output.stdout)?;
+        fingerprint::write_dep_info(
+            depinfo,
+            &dep_info_loc,
+            &pkg_root,
+            &pkg_root,
+            &target_root,
+            allow_package,
+        )?;
+        // Files modified while the script runs are newer than these, and
+        // make it run again next time.
+        paths::set_file_time_no_err(&output_file, timestamp);
+        paths::set_file_time_no_err(&dep_info_loc, timestamp);
+        Ok(())
+    });
+
+    let mut job = if build_plan {
+        Job::new_dirty(Work::noop(), DirtyReason::FreshBuild)
+    } else {
+        fingerprint::prepare_target(build_runner, unit, false)?
+    };
+    if job.freshness().is_dirty() {
+        job.before(work);
+    }
+    Ok(job)
+}
@@ -5,7 +5,8 @@ use crate::core::compiler::unit::Unit;
 use crate::core::compiler::{BuildContext, CompileKind};
 use crate::sources::CRATES_IO_REGISTRY;
 use crate::util::errors::{internal, CargoResult};
-use cargo_util::ProcessBuilder;
+use cargo_util::{paths, ProcessBuilder};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
@@ -267,6 +268,71 @@ pub fn add_output_format(
     Ok(())
 }
 
+/// Adds unstable flags [`--enable-index-page`][1] and [`--index-page`][2] to
+/// the given `rustdoc` invocation, so that the documentation directory gets an
+/// index page listing the crates of the workspace members being documented.
+/// This is for unstable feature [`-Zdoc-merged-index`][3].
+///
+/// [1]: https://doc.rust-lang.org/nightly/rustdoc/unstable-features.html#--enable-index-page-generate-a-default-index-page-for-docs
+/// [2]: https://doc.rust-lang.org/nightly/rustdoc/unstable-features.html#--index-page-provide-a-top-level-landing-page-for-docs
+/// [3]: https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#doc-merged-index
+pub fn add_index_page(
+    build_runner: &BuildRunner<'_, '_>,
+    unit: &Unit,
+    rustdoc: &mut ProcessBuilder,
+) -> CargoResult<()> {
+    let Some(index_page) = index_page(build_runner.bcx, unit) else {
+        return Ok(());
+    };
+    // Every invocation writing to the same documentation directory shares the
+    // page, so that whichever runs last leaves an index of all of them.
+    // rustdoc names the page after the file, so it has to be `index.md`.
+    let dir = build_runner
+        .files()
+        .layout(unit.kind)
+        .prepare_tmp()?
+        .join("doc-index");
+    paths::create_dir_all(&dir)?;
+    let path = dir.join("index.md");
+    paths::write_if_changed(&path, index_page)?;
+    rustdoc
+        .arg("-Zunstable-options")
+        .arg("--enable-index-page")
+        .arg("--index-page")
+        .arg(path);
+    Ok(())
+}
+
+/// Generates the Markdown of the index page that [`add_index_page`] passes to
+/// the rustdoc invocation of `unit`, or `None` if the unit doesn't get one.
+///
+/// The page links to the crates of the root units documented for the same
+/// kind, which all end up in the same documentation directory.
+pub fn index_page(bcx: &BuildContext<'_, '_>, unit: &Unit) -> Option<String> {
+    if !bcx.gctx.cli_unstable().doc_merged_index
+        || !matches!(unit.mode, CompileMode::Doc { json: false, .. })
+        || !bcx.ws.is_member(&unit.pkg)
+    {
+        return None;
+    }
+    let crates: BTreeMap<_, _> = bcx
+        .roots
+        .iter()
+        .filter(|root| root.kind == unit.kind && root.mode == unit.mode)
+        .map(|root| (root.target.crate_name(), root.pkg.manifest().metadata()))
+        .collect();
+    let mut page = String::from("# Crates\n\n");
+    for (name, metadata) in crates {
+        page.push_str(&format!("* [{name}]({name}/index.html)"));
+        if let Some(description) = &metadata.description {
+            let description = description.split_whitespace().collect::<Vec<_>>();
+            page.push_str(&format!(": {}", description.join(" ")));
+        }
+        page.push('\n');
+    }
+    Some(page)
+}
+
 /// Indicates whether a target should have examples scraped from it by rustdoc.
 /// Configured within Cargo.toml and only for unstable feature
 /// [`-Zrustdoc-scrape-examples`][1].
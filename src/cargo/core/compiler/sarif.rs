@@ -0,0 +1,225 @@
+//! Conversion of compiler diagnostics to [SARIF], for `--message-format=sarif`.
+//!
+//! The diagnostics that rustc emits as JSON are forwarded to the main thread
+//! as `Message::CompilerDiagnostic`, and collected into a [`SarifReport`].
+//! Once the build finished, the report is printed to stdout as a single SARIF
+//! log, which code scanning services like GitHub's can ingest.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The diagnostics of a build, to print as a SARIF log.
+pub struct SarifReport {
+    /// The root of the workspace, which rustc paths of local packages are
+    /// relative to.
+    ws_root: PathBuf,
+    /// The rules of the results, by id.
+    rules: BTreeMap<String, Rule>,
+    /// The distinct results, in the order rustc emitted them.
+    results: Vec<SarifResult>,
+}
+
+/// A diagnostic as emitted by rustc, with only the fields converted to SARIF.
+#[derive(Deserialize)]
+struct Diagnostic {
+    message: String,
+    code: Option<DiagnosticCode>,
+    level: String,
+    spans: Vec<DiagnosticSpan>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    level: &'static str,
+    message: Text,
+    locations: Vec<Location>,
+}
+
+#[derive(Serialize, PartialEq)]
+struct Text {
+    text: String,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    /// `%SRCROOT%` for the paths relative to the workspace root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl SarifReport {
+    pub fn new(ws_root: &Path) -> SarifReport {
+        SarifReport {
+            ws_root: ws_root.to_path_buf(),
+            rules: BTreeMap::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Adds a diagnostic emitted by rustc as JSON. A diagnostic emitted by
+    /// several units is only added once, and notes about the compilation
+    /// itself are skipped.
+    pub fn add(&mut self, diagnostic: &str) {
+        let Ok(diagnostic) = serde_json::from_str::<Diagnostic>(diagnostic) else {
+            return;
+        };
+        // Like "For more information about this error, try `rustc --explain`".
+        if diagnostic.level == "failure-note" {
+            return;
+        }
+        let rule_id = diagnostic.code.map(|code| code.code);
+        if let Some(id) = &rule_id {
+            self.rules.entry(id.clone()).or_insert_with(|| Rule {
+                id: id.clone(),
+                help_uri: help_uri(id),
+            });
+        }
+        let level = match diagnostic.level.as_str() {
+            "warning" => "warning",
+            level if level.starts_with("error") => "error",
+            _ => "note",
+        };
+        let locations = diagnostic
+            .spans
+            .iter()
+            .filter(|span| span.is_primary)
+            .map(|span| Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: self.artifact_location(&span.file_name),
+                    region: Region {
+                        start_line: span.line_start,
+                        start_column: span.column_start,
+                        end_line: span.line_end,
+                        end_column: span.column_end,
+                    },
+                },
+            })
+            .collect();
+        let result = SarifResult {
+            rule_id,
+            level,
+            message: Text {
+                text: diagnostic.message,
+            },
+            locations,
+        };
+        if !self.results.contains(&result) {
+            self.results.push(result);
+        }
+    }
+
+    /// Converts a path from rustc to a URI, relative to `%SRCROOT%` when it
+    /// is in the workspace.
+    fn artifact_location(&self, file_name: &str) -> ArtifactLocation {
+        let path = self.ws_root.join(file_name);
+        match path.strip_prefix(&self.ws_root) {
+            Ok(relative) => ArtifactLocation {
+                uri: relative
+                    .iter()
+                    .map(|component| component.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                uri_base_id: Some("%SRCROOT%"),
+            },
+            Err(_) => ArtifactLocation {
+                uri: Url::from_file_path(&path)
+                    .map(String::from)
+                    .unwrap_or_else(|_| file_name.to_owned()),
+                uri_base_id: None,
+            },
+        }
+    }
+
+    /// Serializes the report as a SARIF log.
+    pub fn to_json_string(&self) -> String {
+        let root = Url::from_directory_path(&self.ws_root)
+            .map(String::from)
+            .unwrap_or_default();
+        let log = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "rustc",
+                        "informationUri": "https://www.rust-lang.org/",
+                        "rules": self.rules.values().collect::<Vec<_>>(),
+                    }
+                },
+                "originalUriBaseIds": {
+                    "%SRCROOT%": { "uri": root }
+                },
+                "results": self.results,
+            }]
+        });
+        serde_json::to_string(&log).unwrap()
+    }
+}
+
+/// Gets the documentation of the error codes of rustc, and of clippy lints.
+fn help_uri(id: &str) -> Option<String> {
+    if let Some(lint) = id.strip_prefix("clippy::") {
+        Some(format!(
+            "https://rust-lang.github.io/rust-clippy/master/index.html#{lint}"
+        ))
+    } else if id.starts_with('E') && id[1..].chars().all(|c| c.is_ascii_digit()) {
+        Some(format!("https://doc.rust-lang.org/error_codes/{id}.html"))
+    } else {
+        None
+    }
+}
@@ -102,6 +102,8 @@ pub fn resolve_std<'gctx>(
         /*exclude*/ &None,
         /*inheritable*/ &None,
         /*custom_metadata*/ &None,
+        /*feature_presets*/ &None,
+        /*split_target_dirs*/ &None,
     ));
     let virtual_manifest = crate::core::VirtualManifest::new(
         Rc::default(),
@@ -172,7 +172,7 @@ impl<'gctx> Timings<'gctx> {
             CompileMode::Doc { .. } => target.push_str(" (doc)"),
             CompileMode::Doctest => target.push_str(" (doc test)"),
             CompileMode::Docscrape => target.push_str(" (doc scrape)"),
-            CompileMode::RunCustomBuild => target.push_str(" (run)"),
+            CompileMode::RunCustomBuild | CompileMode::RunPostBuild => target.push_str(" (run)"),
         }
         let unit_time = UnitTime {
             unit,
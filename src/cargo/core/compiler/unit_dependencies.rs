@@ -135,6 +135,7 @@ pub fn build_unit_dependencies<'a, 'gctx>(
     }
 
     connect_run_custom_build_deps(&mut state);
+    connect_run_post_build_deps(&mut state, roots);
 
     // Dependencies are used in tons of places throughout the backend, many of
     // which affect the determinism of the build itself. As a result be sure
@@ -217,9 +218,9 @@ fn deps_of_roots(roots: &[Unit], state: &mut State<'_, '_>) -> CargoResult<()> {
             } else {
                 UnitFor::new_test(state.gctx, root_compile_kind)
             }
-        } else if unit.target.is_custom_build() {
-            // This normally doesn't happen, except `clean` aggressively
-            // generates all units.
+        } else if unit.target.is_custom_build() || unit.target.is_post_build() {
+            // This normally doesn't happen for build scripts, except `clean`
+            // aggressively generates all units.
             UnitFor::new_host(false, root_compile_kind)
         } else if unit.target.proc_macro() {
             UnitFor::new_host(true, root_compile_kind)
@@ -266,6 +267,8 @@ fn compute_deps(
 ) -> CargoResult<Vec<UnitDep>> {
     if unit.mode.is_run_custom_build() {
         return compute_deps_custom_build(unit, unit_for, state);
+    } else if unit.mode.is_run_post_build() {
+        return compute_deps_post_build(unit, unit_for, state);
     } else if unit.mode.is_doc() {
         // Note: this does not include doc test.
         return compute_deps_doc(unit, state, unit_for);
@@ -338,7 +341,7 @@ fn compute_deps(
     // If this target is a build script, then what we've collected so far is
     // all we need. If this isn't a build script, then it depends on the
     // build script if there is one.
-    if unit.target.is_custom_build() {
+    if unit.target.is_custom_build() || unit.target.is_post_build() {
         return Ok(ret);
     }
     ret.extend(dep_build_script(unit, unit_for, state)?);
@@ -372,6 +375,7 @@ fn compute_deps(
                                 state.features(),
                                 state.package_set,
                                 id,
+                                unit.kind,
                             );
                             rf.iter().all(|f| features.contains(f))
                         }
@@ -534,6 +538,29 @@ fn compute_deps_custom_build(
     Ok(result)
 }
 
+/// Returns the dependencies needed to run a post-build script.
+///
+/// This is only compiling the post-build script itself. The binaries it runs
+/// after are attached later in [`connect_run_post_build_deps`].
+fn compute_deps_post_build(
+    unit: &Unit,
+    unit_for: UnitFor,
+    state: &State<'_, '_>,
+) -> CargoResult<Vec<UnitDep>> {
+    let compile_script_unit = new_unit_dep(
+        state,
+        unit,
+        &unit.pkg,
+        &unit.target,
+        unit_for.for_custom_build(),
+        // Post-build scripts are always compiled for the host.
+        CompileKind::Host,
+        CompileMode::Build,
+        IS_NO_ARTIFACT_DEP,
+    )?;
+    Ok(vec![compile_script_unit])
+}
+
 /// Given a `parent` unit containing a dependency `dep` whose package is `artifact_pkg`,
 /// find all targets in `artifact_pkg` which refer to the `dep`s artifact declaration
 /// and turn them into units.
@@ -874,6 +901,35 @@ fn new_unit_dep_with_profile(
     })
 }
 
+/// Makes each root unit running a post-build script depend on the root units
+/// building the binaries of its package, so that it runs after them.
+fn connect_run_post_build_deps(state: &mut State<'_, '_>, roots: &[Unit]) {
+    for post_build in roots.iter().filter(|unit| unit.mode.is_run_post_build()) {
+        let bins = roots
+            .iter()
+            .filter(|unit| {
+                unit.pkg == post_build.pkg
+                    && unit.kind == post_build.kind
+                    && unit.mode == CompileMode::Build
+                    && unit.target.is_bin()
+            })
+            .map(|unit| UnitDep {
+                unit: unit.clone(),
+                unit_for: UnitFor::new_normal(unit.kind),
+                extern_crate_name: InternedString::new(&unit.target.crate_name()),
+                dep_name: None,
+                public: false,
+                noprelude: false,
+            })
+            .collect::<Vec<_>>();
+        state
+            .unit_dependencies
+            .get_mut(post_build)
+            .expect("post-build root has dependencies")
+            .extend(bins);
+    }
+}
+
 /// Fill in missing dependencies for units of the `RunCustomBuild`
 ///
 /// As mentioned above in `compute_deps_custom_build` each build script
@@ -1043,10 +1099,12 @@ impl<'a, 'gctx> State<'a, 'gctx> {
                 let deps: Vec<_> = deps
                     .iter()
                     .filter(|dep| {
-                        // If this target is a build command, then we only want build
-                        // dependencies, otherwise we want everything *other than* build
-                        // dependencies.
-                        if unit.target.is_custom_build() != dep.is_build() {
+                        // If this target is a build command or post-build script, then
+                        // we only want build dependencies, otherwise we want everything
+                        // *other than* build dependencies.
+                        let is_script =
+                            unit.target.is_custom_build() || unit.target.is_post_build();
+                        if is_script != dep.is_build() {
                             return false;
                         }
 
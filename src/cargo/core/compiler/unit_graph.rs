@@ -2,7 +2,7 @@
 //!
 //! [`--unit-graph`]: https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#unit-graph
 
-use crate::core::compiler::Unit;
+use crate::core::compiler::{artifact, Unit};
 use crate::core::compiler::{CompileKind, CompileMode};
 use crate::core::profiles::{Profile, UnitFor};
 use crate::core::{PackageId, Target};
@@ -69,10 +69,22 @@ struct SerializedUnitDep {
     // This is only set on nightly since it is unstable.
     #[serde(skip_serializing_if = "Option::is_none")]
     noprelude: Option<bool>,
+    // Only set for artifact dependencies, which are unstable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artifact: Option<SerializedArtifact>,
     // Intentionally not including `unit_for` because it is a low-level
     // internal detail that is mostly used for building the graph.
 }
 
+#[derive(serde::Serialize)]
+struct SerializedArtifact {
+    /// The name the artifact environment variables are derived from.
+    dep_name: InternedString,
+    /// The artifact type, like `bin` or `cdylib`.
+    #[serde(rename = "type")]
+    ty: &'static str,
+}
+
 /// Outputs a JSON serialization of [`UnitGraph`] for given `root_units`
 /// to the standard output.
 pub fn emit_serialized_unit_graph(
@@ -92,23 +104,30 @@ pub fn emit_serialized_unit_graph(
     let ser_units = units
         .iter()
         .map(|(unit, unit_deps)| {
-            let dependencies = unit_deps
-                .iter()
-                .map(|unit_dep| {
-                    // https://github.com/rust-lang/rust/issues/64260 when stabilized.
-                    let (public, noprelude) = if gctx.nightly_features_allowed {
-                        (Some(unit_dep.public), Some(unit_dep.noprelude))
-                    } else {
-                        (None, None)
-                    };
-                    SerializedUnitDep {
-                        index: indices[&unit_dep.unit],
-                        extern_crate_name: unit_dep.extern_crate_name,
-                        public,
-                        noprelude,
-                    }
-                })
-                .collect();
+            let dependencies =
+                unit_deps
+                    .iter()
+                    .map(|unit_dep| {
+                        // https://github.com/rust-lang/rust/issues/64260 when stabilized.
+                        let (public, noprelude) = if gctx.nightly_features_allowed {
+                            (Some(unit_dep.public), Some(unit_dep.noprelude))
+                        } else {
+                            (None, None)
+                        };
+                        SerializedUnitDep {
+                            index: indices[&unit_dep.unit],
+                            extern_crate_name: unit_dep.extern_crate_name,
+                            public,
+                            noprelude,
+                            artifact: unit_dep.unit.artifact.is_true().then(|| {
+                                SerializedArtifact {
+                                    dep_name: unit_dep.dep_name.unwrap_or(unit_dep.unit.pkg.name()),
+                                    ty: artifact::unit_artifact_type_name(&unit_dep.unit),
+                                }
+                            }),
+                        }
+                    })
+                    .collect();
             SerializedUnit {
                 pkg_id: unit.pkg.package_id(),
                 target: &unit.target,
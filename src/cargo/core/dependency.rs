@@ -1,10 +1,9 @@
 use cargo_platform::Platform;
+use cargo_util_schemas::metadata;
 use semver::VersionReq;
 use serde::ser;
-use serde::Serialize;
 use std::borrow::Cow;
 use std::fmt;
-use std::path::PathBuf;
 use std::rc::Rc;
 use tracing::trace;
 
@@ -51,50 +50,12 @@ struct Inner {
     platform: Option<Platform>,
 }
 
-#[derive(Serialize)]
-struct SerializedDependency<'a> {
-    name: &'a str,
-    source: SourceId,
-    req: String,
-    kind: DepKind,
-    rename: Option<&'a str>,
-
-    optional: bool,
-    uses_default_features: bool,
-    features: &'a [InternedString],
-    #[serde(skip_serializing_if = "Option::is_none")]
-    artifact: Option<&'a Artifact>,
-    target: Option<&'a Platform>,
-    /// The registry URL this dependency is from.
-    /// If None, then it comes from the default registry (crates.io).
-    registry: Option<&'a str>,
-
-    /// The file system path for a local path dependency.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    path: Option<PathBuf>,
-}
-
 impl ser::Serialize for Dependency {
     fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
     where
         S: ser::Serializer,
     {
-        let registry_id = self.registry_id();
-        SerializedDependency {
-            name: &*self.package_name(),
-            source: self.source_id(),
-            req: self.version_req().to_string(),
-            kind: self.kind(),
-            optional: self.is_optional(),
-            uses_default_features: self.uses_default_features(),
-            features: self.features(),
-            target: self.platform(),
-            rename: self.explicit_name_in_toml().map(|s| s.as_str()),
-            registry: registry_id.as_ref().map(|sid| sid.url().as_str()),
-            path: self.source_id().local_path(),
-            artifact: self.artifact(),
-        }
-        .serialize(s)
+        self.serialized().serialize(s)
     }
 }
 
@@ -115,6 +76,17 @@ impl DepKind {
     }
 }
 
+impl DepKind {
+    /// The kind as `cargo metadata` outputs it, `None` for normal dependencies.
+    pub fn serialized(&self) -> Option<metadata::DependencyKind> {
+        match self {
+            DepKind::Normal => None,
+            DepKind::Development => Some(metadata::DependencyKind::Development),
+            DepKind::Build => Some(metadata::DependencyKind::Build),
+        }
+    }
+}
+
 impl ser::Serialize for DepKind {
     fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
     where
@@ -130,6 +102,24 @@ impl ser::Serialize for DepKind {
 }
 
 impl Dependency {
+    /// The dependency as `cargo metadata` outputs it.
+    pub fn serialized(&self) -> metadata::Dependency {
+        metadata::Dependency {
+            name: self.package_name().to_string(),
+            source: self.source_id().serialized(),
+            req: self.version_req().to_string(),
+            kind: self.kind().serialized(),
+            rename: self.explicit_name_in_toml().map(|s| s.to_string()),
+            optional: self.is_optional(),
+            uses_default_features: self.uses_default_features(),
+            features: self.features().iter().map(|f| f.to_string()).collect(),
+            artifact: self.artifact().map(Artifact::serialized),
+            target: self.platform().map(|p| p.to_string()),
+            registry: self.registry_id().map(|sid| sid.url().to_string()),
+            path: self.source_id().local_path(),
+        }
+    }
+
     /// Attempt to create a `Dependency` from an entry in the manifest.
     pub fn parse(
         name: impl Into<InternedString>,
@@ -458,28 +448,29 @@ pub struct Artifact {
     target: Option<ArtifactTarget>,
 }
 
-#[derive(Serialize)]
-pub struct SerializedArtifact<'a> {
-    kinds: &'a [ArtifactKind],
-    lib: bool,
-    target: Option<&'a str>,
-}
-
 impl ser::Serialize for Artifact {
     fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
     where
         S: ser::Serializer,
     {
-        SerializedArtifact {
-            kinds: self.kinds(),
-            lib: self.is_lib,
-            target: self.target.as_ref().map(ArtifactTarget::as_str),
-        }
-        .serialize(s)
+        self.serialized().serialize(s)
     }
 }
 
 impl Artifact {
+    /// The artifact requirement as `cargo metadata` outputs it.
+    pub fn serialized(&self) -> metadata::ArtifactDependency {
+        metadata::ArtifactDependency {
+            kinds: self
+                .kinds()
+                .iter()
+                .map(|k| k.as_str().into_owned())
+                .collect(),
+            lib: self.is_lib,
+            target: self.target.as_ref().map(|t| t.as_str().to_string()),
+        }
+    }
+
     pub(crate) fn parse(
         artifacts: &[impl AsRef<str>],
         is_lib: bool,
@@ -513,6 +513,27 @@ features! {
 
     /// Allow multiple packages to participate in the same API namespace
     (unstable, open_namespaces, "", "reference/unstable.html#open-namespaces"),
+
+    /// Allow running a `post-build` script after the binaries of a package are built.
+    (unstable, post_build_scripts, "", "reference/unstable.html#post-build-scripts"),
+
+    /// Allow declaring the capabilities a build script needs in a sandbox.
+    (unstable, build_sandbox, "", "reference/unstable.html#build-sandbox"),
+
+    /// Allow declaring linker scripts and object files in `[target.<platform>.link-inputs]`.
+    (unstable, link_inputs, "", "reference/unstable.html#link-inputs"),
+
+    /// Allow declaring the system libraries a package links to in `[package.links-libs]`.
+    (unstable, links_libs, "", "reference/unstable.html#links-libs"),
+
+    /// Allow named sets of lints in `[lints.profile.<name>]`.
+    (unstable, lints_profiles, "", "reference/unstable.html#lints-profile"),
+
+    /// Allow narrowing down git dependencies with `shallow` and `paths`.
+    (unstable, git_checkout_options, "", "reference/unstable.html#git-checkout-options"),
+
+    /// Allow glob patterns in the `tag` and `branch` of git dependencies.
+    (unstable, git_ref_globs, "", "reference/unstable.html#git-ref-globs"),
 }
 
 /// Status and metadata for a single unstable feature.
@@ -749,10 +770,15 @@ unstable_cli_options!(
     // All other unstable features.
     // Please keep this list lexicographically ordered.
     advanced_env: bool,
+    advisories: bool = ("Check new dependencies against the advisory database of the `[advisories]` config"),
+    alias_placeholders: bool = ("Allow `{1}` and `{name}` placeholders for the arguments of `[alias]` commands"),
+    artifact_hashes: bool = ("Report the SHA-256 of the files and the fingerprint of each unit in `compiler-artifact` JSON messages"),
     asymmetric_token: bool = ("Allows authenticating with asymmetric tokens"),
     avoid_dev_deps: bool = ("Avoid installing dev-dependencies if possible"),
     binary_dep_depinfo: bool = ("Track changes to dependency artifacts"),
     bindeps: bool = ("Allow Cargo packages to depend on bin, cdylib, and staticlib crates, and use the artifacts built by those crates"),
+    build_sandbox: bool = ("Enable the `build.sandbox` config to run build scripts in a sandbox"),
+    build_script_cache: bool = ("Cache build script results in CARGO_HOME and reuse them across workspaces"),
     #[serde(deserialize_with = "deserialize_build_std")]
     build_std: Option<Vec<String>>  = ("Enable Cargo to compile the standard library itself as part of a crate graph compilation"),
     build_std_features: Option<Vec<String>>  = ("Configure features enabled for the standard library itself when building the standard library"),
@@ -760,32 +786,48 @@ unstable_cli_options!(
     codegen_backend: bool = ("Enable the `codegen-backend` option in profiles in .cargo/config.toml file"),
     config_include: bool = ("Enable the `include` key in config files"),
     direct_minimal_versions: bool = ("Resolve minimal dependency versions instead of maximum (direct dependencies only)"),
+    doc_merged_index: bool = ("Generate an index page linking the documentation of the crates being documented"),
     doctest_xcompile: bool = ("Compile and run doctests for non-host target using runner config"),
     dual_proc_macros: bool = ("Build proc-macros for both the host and the target"),
+    feature_presets: bool = ("Enable `[workspace.features]` presets usable with `--features preset:<name>`"),
+    feature_unification: bool = ("Enable the `resolver.feature-unification` config to unify features across the whole workspace"),
     features: Option<Vec<String>>,
     gc: bool = ("Track cache usage and \"garbage collect\" unused files"),
     #[serde(deserialize_with = "deserialize_git_features")]
     git: Option<GitFeatures> = ("Enable support for shallow git fetch operations"),
     #[serde(deserialize_with = "deserialize_gitoxide_features")]
     gitoxide: Option<GitoxideFeatures> = ("Use gitoxide for the given git interactions, or all of them if no argument is given"),
+    group_diagnostics: bool = ("Print a diagnostic emitted by several units of a package once"),
     host_config: bool = ("Enable the `[host]` section in the .cargo/config.toml file"),
     minimal_versions: bool = ("Resolve minimal dependency versions instead of maximum"),
     msrv_policy: bool = ("Enable rust-version aware policy within cargo"),
     mtime_on_use: bool = ("Configure Cargo to update the mtime of used files"),
+    native_clippy: bool = ("Run `cargo clippy` with the built-in command instead of `cargo-clippy`"),
+    nested_workspaces: bool = ("Allow a workspace to contain other workspaces"),
     next_lockfile_bump: bool,
     no_index_update: bool = ("Do not update the registry index even if the cache is outdated"),
     panic_abort_tests: bool = ("Enable support to run tests with -Cpanic=abort"),
+    per_target_features: bool = ("Unify features separately for each `--target` with resolver 3"),
     profile_rustflags: bool = ("Enable the `rustflags` option in profiles in .cargo/config.toml file"),
     public_dependency: bool = ("Respect a dependency's `public` field in Cargo.toml to control public/private dependencies"),
     publish_timeout: bool = ("Enable the `publish.timeout` key in .cargo/config.toml file"),
+    registry_remap: bool = ("Enable the `registry-remap` config table to resolve a registry's dependencies against another registry"),
+    runner_composition: bool = ("Allow `runner` to chain the named runners of `[runners]`, and to place the artifact with `{artifact}`"),
     rustdoc_map: bool = ("Allow passing external documentation mappings to rustdoc"),
     rustdoc_scrape_examples: bool = ("Allows Rustdoc to scrape code examples from reverse-dependencies"),
     script: bool = ("Enable support for single-file, `.rs` packages"),
     separate_nightlies: bool,
     skip_rustdoc_fingerprint: bool,
+    source_policy: bool = ("Enable the `[source-policy]` config forbidding git and path dependencies"),
+    sparse_delta: bool = ("Request delta updates of changed index files from sparse registries"),
+    split_target_dirs: bool = ("Enable `workspace.split-target-dirs` to give each member its own target directory"),
+    target_alias: bool = ("Enable the `[target-alias]` config to name a target and its settings for `--target`"),
     target_applies_to_host: bool = ("Enable the `target-applies-to-host` key in the .cargo/config.toml file"),
     trim_paths: bool = ("Enable the `trim-paths` option in profiles"),
     unstable_options: bool = ("Allow the usage of unstable options"),
+    validate_config: bool = ("Warn about unknown keys in config files"),
+    workspace_patch: bool = ("Allow members to inherit `[patch]` and `[replace]` with `workspace = true`"),
+    yanked_policy: bool = ("Enable the `resolver.yanked` config to warn or fail on yanked packages in Cargo.lock"),
 );
 
 const STABILIZED_COMPILE_PROGRESS: &str = "The progress bar is now always \
@@ -1242,10 +1284,15 @@ impl CliUnstable {
             // Unstable features
             // Sorted alphabetically:
             "advanced-env" => self.advanced_env = parse_empty(k, v)?,
+            "advisories" => self.advisories = parse_empty(k, v)?,
+            "alias-placeholders" => self.alias_placeholders = parse_empty(k, v)?,
+            "artifact-hashes" => self.artifact_hashes = parse_empty(k, v)?,
             "asymmetric-token" => self.asymmetric_token = parse_empty(k, v)?,
             "avoid-dev-deps" => self.avoid_dev_deps = parse_empty(k, v)?,
             "binary-dep-depinfo" => self.binary_dep_depinfo = parse_empty(k, v)?,
             "bindeps" => self.bindeps = parse_empty(k, v)?,
+            "build-sandbox" => self.build_sandbox = parse_empty(k, v)?,
+            "build-script-cache" => self.build_script_cache = parse_empty(k, v)?,
             "build-std" => {
                 self.build_std = Some(crate::core::compiler::standard_lib::parse_unstable_flag(v))
             }
@@ -1254,8 +1301,11 @@ impl CliUnstable {
             "codegen-backend" => self.codegen_backend = parse_empty(k, v)?,
             "config-include" => self.config_include = parse_empty(k, v)?,
             "direct-minimal-versions" => self.direct_minimal_versions = parse_empty(k, v)?,
+            "doc-merged-index" => self.doc_merged_index = parse_empty(k, v)?,
             "doctest-xcompile" => self.doctest_xcompile = parse_empty(k, v)?,
             "dual-proc-macros" => self.dual_proc_macros = parse_empty(k, v)?,
+            "feature-presets" => self.feature_presets = parse_empty(k, v)?,
+            "feature-unification" => self.feature_unification = parse_empty(k, v)?,
             "gc" => self.gc = parse_empty(k, v)?,
             "git" => {
                 self.git = v.map_or_else(
@@ -1269,25 +1319,38 @@ impl CliUnstable {
                     |v| parse_gitoxide(v.split(',')),
                 )?
             }
+            "group-diagnostics" => self.group_diagnostics = parse_empty(k, v)?,
             "host-config" => self.host_config = parse_empty(k, v)?,
             "next-lockfile-bump" => self.next_lockfile_bump = parse_empty(k, v)?,
             "minimal-versions" => self.minimal_versions = parse_empty(k, v)?,
             "msrv-policy" => self.msrv_policy = parse_empty(k, v)?,
             // can also be set in .cargo/config or with and ENV
             "mtime-on-use" => self.mtime_on_use = parse_empty(k, v)?,
+            "native-clippy" => self.native_clippy = parse_empty(k, v)?,
+            "nested-workspaces" => self.nested_workspaces = parse_empty(k, v)?,
             "no-index-update" => self.no_index_update = parse_empty(k, v)?,
             "panic-abort-tests" => self.panic_abort_tests = parse_empty(k, v)?,
+            "per-target-features" => self.per_target_features = parse_empty(k, v)?,
             "public-dependency" => self.public_dependency = parse_empty(k, v)?,
             "profile-rustflags" => self.profile_rustflags = parse_empty(k, v)?,
             "trim-paths" => self.trim_paths = parse_empty(k, v)?,
             "publish-timeout" => self.publish_timeout = parse_empty(k, v)?,
+            "registry-remap" => self.registry_remap = parse_empty(k, v)?,
+            "runner-composition" => self.runner_composition = parse_empty(k, v)?,
             "rustdoc-map" => self.rustdoc_map = parse_empty(k, v)?,
             "rustdoc-scrape-examples" => self.rustdoc_scrape_examples = parse_empty(k, v)?,
             "separate-nightlies" => self.separate_nightlies = parse_empty(k, v)?,
             "skip-rustdoc-fingerprint" => self.skip_rustdoc_fingerprint = parse_empty(k, v)?,
+            "source-policy" => self.source_policy = parse_empty(k, v)?,
+            "sparse-delta" => self.sparse_delta = parse_empty(k, v)?,
+            "split-target-dirs" => self.split_target_dirs = parse_empty(k, v)?,
             "script" => self.script = parse_empty(k, v)?,
+            "target-alias" => self.target_alias = parse_empty(k, v)?,
             "target-applies-to-host" => self.target_applies_to_host = parse_empty(k, v)?,
             "unstable-options" => self.unstable_options = parse_empty(k, v)?,
+            "validate-config" => self.validate_config = parse_empty(k, v)?,
+            "workspace-patch" => self.workspace_patch = parse_empty(k, v)?,
+            "yanked-policy" => self.yanked_policy = parse_empty(k, v)?,
             _ => bail!("\
             unknown `-Z` flag specified: {k}\n\n\
             For available unstable features, see https://doc.rust-lang.org/nightly/cargo/reference/unstable.html\n\
@@ -1299,35 +1362,36 @@ impl CliUnstable {
 
     /// Generates an error if `-Z unstable-options` was not used for a new,
     /// unstable command-line flag.
-    pub fn fail_if_stable_opt(&self, flag: &str, issue: u32) -> CargoResult<()> {
+    pub fn fail_if_stable_opt(&self, flag: &str, issue: impl Into<Option<u32>>) -> CargoResult<()> {
         self.fail_if_stable_opt_custom_z(flag, issue, "unstable-options", self.unstable_options)
     }
 
+    /// Generates an error if `enabled` is not set for an unstable
+    /// command-line flag, `issue` being its tracking issue if there is one.
     pub fn fail_if_stable_opt_custom_z(
         &self,
         flag: &str,
-        issue: u32,
+        issue: impl Into<Option<u32>>,
         z_name: &str,
         enabled: bool,
     ) -> CargoResult<()> {
         if !enabled {
-            let see = format!(
-                "See https://github.com/rust-lang/cargo/issues/{issue} for more \
-                 information about the `{flag}` flag."
-            );
+            let see = match issue.into() {
+                Some(issue) => format!(
+                    "\nSee https://github.com/rust-lang/cargo/issues/{issue} for more \
+                     information about the `{flag}` flag."
+                ),
+                None => String::new(),
+            };
             // NOTE: a `config` isn't available here, check the channel directly
             let channel = channel();
             if channel == "nightly" || channel == "dev" {
-                bail!(
-                    "the `{flag}` flag is unstable, pass `-Z {z_name}` to enable it\n\
-                     {see}"
-                );
+                bail!("the `{flag}` flag is unstable, pass `-Z {z_name}` to enable it{see}");
             } else {
                 bail!(
                     "the `{flag}` flag is unstable, and only available on the nightly channel \
                      of Cargo, but this is the `{channel}` channel\n\
-                     {SEE_CHANNELS}\n\
-                     {see}"
+                     {SEE_CHANNELS}{see}"
                 );
             }
         }
@@ -1335,34 +1399,37 @@ impl CliUnstable {
     }
 
     /// Generates an error if `-Z unstable-options` was not used for a new,
-    /// unstable subcommand.
+    /// unstable subcommand, `issue` being its tracking issue if there is one.
     pub fn fail_if_stable_command(
         &self,
         gctx: &GlobalContext,
         command: &str,
-        issue: u32,
+        issue: impl Into<Option<u32>>,
         z_name: &str,
         enabled: bool,
     ) -> CargoResult<()> {
         if enabled {
             return Ok(());
         }
-        let see = format!(
-            "See https://github.com/rust-lang/cargo/issues/{} for more \
-            information about the `cargo {}` command.",
-            issue, command
-        );
+        let see = match issue.into() {
+            Some(issue) => format!(
+                "\nSee https://github.com/rust-lang/cargo/issues/{} for more \
+                information about the `cargo {}` command.",
+                issue, command
+            ),
+            None => String::new(),
+        };
         if gctx.nightly_features_allowed {
             bail!(
                 "the `cargo {command}` command is unstable, pass `-Z {z_name}` \
-                 to enable it\n\
+                 to enable it\
                  {see}",
             );
         } else {
             bail!(
                 "the `cargo {}` command is unstable, and only available on the \
                  nightly channel of Cargo, but this is the `{}` channel\n\
-                 {}\n\
+                 {}\
                  {}",
                 command,
                 channel(),
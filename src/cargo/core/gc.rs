@@ -112,6 +112,14 @@ struct AutoConfig {
     max_git_co_age: Option<String>,
     /// Any git clone older than this duration will be deleted from the git cache.
     max_git_db_age: Option<String>,
+    /// The oldest source cache files will be deleted until the cache is under this size.
+    max_src_size: Option<String>,
+    /// The oldest crate cache files will be deleted until the cache is under this size.
+    max_crate_size: Option<String>,
+    /// The oldest git caches will be deleted until they are under this size.
+    max_git_size: Option<String>,
+    /// The oldest downloaded caches will be deleted until they are under this size.
+    max_download_size: Option<String>,
 }
 
 /// Options to use for garbage collection.
@@ -220,6 +228,26 @@ impl GcOpts {
                 .as_deref()
                 .unwrap_or(DEFAULT_MAX_AGE_DOWNLOADED),
         )?;
+        self.max_src_size = smaller_size_for_config(
+            self.max_src_size,
+            "gc.auto.max-src-size",
+            auto_config.max_src_size.as_deref(),
+        )?;
+        self.max_crate_size = smaller_size_for_config(
+            self.max_crate_size,
+            "gc.auto.max-crate-size",
+            auto_config.max_crate_size.as_deref(),
+        )?;
+        self.max_git_size = smaller_size_for_config(
+            self.max_git_size,
+            "gc.auto.max-git-size",
+            auto_config.max_git_size.as_deref(),
+        )?;
+        self.max_download_size = smaller_size_for_config(
+            self.max_download_size,
+            "gc.auto.max-download-size",
+            auto_config.max_download_size.as_deref(),
+        )?;
         Ok(())
     }
 }
@@ -312,6 +340,25 @@ fn newer_time_span_for_config(
     Ok(Some(maybe_newer_span(config_span, cur_span)))
 }
 
+/// Returns the smaller size from `cur_size` versus `config_size`.
+///
+/// Unlike the time spans, sizes have no built-in default, so this returns
+/// `cur_size` if the config isn't set.
+fn smaller_size_for_config(
+    cur_size: Option<u64>,
+    config_name: &str,
+    config_size: Option<&str>,
+) -> CargoResult<Option<u64>> {
+    let Some(config_size) = config_size else {
+        return Ok(cur_size);
+    };
+    let config_size = parse_human_size(config_size)
+        .with_context(|| format!("failed to parse config option `{config_name}`"))?;
+    Ok(Some(
+        cur_size.map_or(config_size, |cur| cur.min(config_size)),
+    ))
+}
+
 /// Returns whichever [`Duration`] is shorter.
 fn maybe_newer_span(a: Duration, b: Option<Duration>) -> Duration {
     match b {
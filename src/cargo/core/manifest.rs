@@ -6,11 +6,12 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use anyhow::Context as _;
+use cargo_platform::Platform;
 use cargo_util_schemas::manifest::RustVersion;
 use cargo_util_schemas::manifest::{TomlManifest, TomlProfiles};
+use cargo_util_schemas::metadata;
 use semver::Version;
 use serde::ser;
-use serde::Serialize;
 use url::Url;
 
 use crate::core::compiler::rustdoc::RustdocScrapeExamples;
@@ -35,6 +36,7 @@ pub const MANIFEST_PREAMBLE: &str = "\
 # See Cargo.toml.orig for the original contents.
 ";
 
+#[derive(Clone)]
 pub enum EitherManifest {
     Real(Manifest),
     Virtual(VirtualManifest),
@@ -89,6 +91,7 @@ pub struct Manifest {
     metabuild: Option<Vec<String>>,
     resolve_behavior: Option<ResolveBehavior>,
     lint_rustflags: Vec<String>,
+    link_inputs: Vec<LinkInputs>,
     embedded: bool,
 }
 
@@ -146,6 +149,17 @@ pub struct ManifestMetadata {
     pub rust_version: Option<RustVersion>,
 }
 
+/// Linker scripts and object files from a `[target.<platform>.link-inputs]`
+/// table, passed to the linker when building for a matching platform.
+///
+/// Paths are relative to the package root.
+#[derive(Clone, Debug, Hash)]
+pub struct LinkInputs {
+    pub platform: Platform,
+    pub linker_scripts: Vec<PathBuf>,
+    pub objects: Vec<PathBuf>,
+}
+
 #[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TargetKind {
     Lib(Vec<CrateType>),
@@ -155,6 +169,24 @@ pub enum TargetKind {
     ExampleLib(Vec<CrateType>),
     ExampleBin,
     CustomBuild,
+    PostBuild,
+}
+
+impl TargetKind {
+    /// The kind as `cargo metadata` outputs it, a list of strings for
+    /// historical reasons.
+    pub fn serialized(&self) -> Vec<String> {
+        use self::TargetKind::*;
+        match self {
+            Lib(kinds) => kinds.iter().map(|t| t.to_string()).collect(),
+            Bin => vec!["bin".to_string()],
+            ExampleBin | ExampleLib(_) => vec!["example".to_string()],
+            Test => vec!["test".to_string()],
+            CustomBuild => vec!["custom-build".to_string()],
+            PostBuild => vec!["post-build".to_string()],
+            Bench => vec!["bench".to_string()],
+        }
+    }
 }
 
 impl ser::Serialize for TargetKind {
@@ -162,15 +194,7 @@ impl ser::Serialize for TargetKind {
     where
         S: ser::Serializer,
     {
-        use self::TargetKind::*;
-        match self {
-            Lib(kinds) => s.collect_seq(kinds.iter().map(|t| t.to_string())),
-            Bin => ["bin"].serialize(s),
-            ExampleBin | ExampleLib(_) => ["example"].serialize(s),
-            Test => ["test"].serialize(s),
-            CustomBuild => ["custom-build"].serialize(s),
-            Bench => ["bench"].serialize(s),
-        }
+        self.serialized().serialize(s)
     }
 }
 
@@ -183,6 +207,7 @@ impl fmt::Debug for TargetKind {
             ExampleBin | ExampleLib(_) => "example".fmt(f),
             Test => "test".fmt(f),
             CustomBuild => "custom-build".fmt(f),
+            PostBuild => "post-build".fmt(f),
             Bench => "bench".fmt(f),
         }
     }
@@ -197,6 +222,7 @@ impl TargetKind {
             TargetKind::ExampleBin | TargetKind::ExampleLib(..) => "example",
             TargetKind::Bench => "bench",
             TargetKind::CustomBuild => "build-script",
+            TargetKind::PostBuild => "post-build-script",
         }
     }
 
@@ -219,6 +245,7 @@ impl TargetKind {
         match self {
             TargetKind::Lib(kinds) | TargetKind::ExampleLib(kinds) => kinds.clone(),
             TargetKind::CustomBuild
+            | TargetKind::PostBuild
             | TargetKind::Bench
             | TargetKind::Test
             | TargetKind::ExampleBin
@@ -300,49 +327,9 @@ impl From<PathBuf> for TargetSourcePath {
     }
 }
 
-#[derive(Serialize)]
-struct SerializedTarget<'a> {
-    /// Is this a `--bin bin`, `--lib`, `--example ex`?
-    /// Serialized as a list of strings for historical reasons.
-    kind: &'a TargetKind,
-    /// Corresponds to `--crate-type` compiler attribute.
-    /// See <https://doc.rust-lang.org/reference/linkage.html>
-    crate_types: Vec<CrateType>,
-    name: &'a str,
-    src_path: Option<&'a PathBuf>,
-    edition: &'a str,
-    #[serde(rename = "required-features", skip_serializing_if = "Option::is_none")]
-    required_features: Option<Vec<&'a str>>,
-    /// Whether docs should be built for the target via `cargo doc`
-    /// See <https://doc.rust-lang.org/cargo/commands/cargo-doc.html#target-selection>
-    doc: bool,
-    doctest: bool,
-    /// Whether tests should be run for the target (`test` field in `Cargo.toml`)
-    test: bool,
-}
-
 impl ser::Serialize for Target {
     fn serialize<S: ser::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        let src_path = match self.src_path() {
-            TargetSourcePath::Path(p) => Some(p),
-            // Unfortunately getting the correct path would require access to
-            // target_dir, which is not available here.
-            TargetSourcePath::Metabuild => None,
-        };
-        SerializedTarget {
-            kind: self.kind(),
-            crate_types: self.rustc_crate_types(),
-            name: self.name(),
-            src_path,
-            edition: &self.edition().to_string(),
-            required_features: self
-                .required_features()
-                .map(|rf| rf.iter().map(|s| s.as_str()).collect()),
-            doc: self.documented(),
-            doctest: self.doctested() && self.doctestable(),
-            test: self.tested(),
-        }
-        .serialize(s)
+        self.serialized().serialize(s)
     }
 }
 
@@ -445,6 +432,7 @@ impl Manifest {
         metabuild: Option<Vec<String>>,
         resolve_behavior: Option<ResolveBehavior>,
         lint_rustflags: Vec<String>,
+        link_inputs: Vec<LinkInputs>,
         embedded: bool,
     ) -> Manifest {
         Manifest {
@@ -475,6 +463,7 @@ impl Manifest {
             metabuild,
             resolve_behavior,
             lint_rustflags,
+            link_inputs,
             embedded,
         }
     }
@@ -592,6 +581,11 @@ impl Manifest {
         self.lint_rustflags.as_slice()
     }
 
+    /// Linker scripts and object files from the `[target.<platform>.link-inputs]` tables
+    pub fn link_inputs(&self) -> &[LinkInputs] {
+        self.link_inputs.as_slice()
+    }
+
     pub fn map_source(self, to_replace: SourceId, replace_with: SourceId) -> Manifest {
         Manifest {
             summary: self.summary.map_source(to_replace, replace_with),
@@ -761,6 +755,33 @@ impl Target {
         }
     }
 
+    /// The target as `cargo metadata` and JSON messages output it.
+    pub fn serialized(&self) -> metadata::Target {
+        let src_path = match self.src_path() {
+            TargetSourcePath::Path(p) => Some(p.clone()),
+            // Unfortunately getting the correct path would require access to
+            // target_dir, which is not available here.
+            TargetSourcePath::Metabuild => None,
+        };
+        metadata::Target {
+            kind: self.kind().serialized(),
+            crate_types: self
+                .rustc_crate_types()
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+            name: self.name().to_string(),
+            src_path,
+            edition: self.edition().to_string(),
+            required_features: self
+                .required_features()
+                .map(|rf| rf.iter().map(|s| s.to_string()).collect()),
+            doc: self.documented(),
+            doctest: self.doctested() && self.doctestable(),
+            test: self.tested(),
+        }
+    }
+
     fn with_path(src_path: PathBuf, edition: Edition) -> Target {
         Target::new(TargetSourcePath::from(src_path), edition)
     }
@@ -810,6 +831,20 @@ impl Target {
         target
     }
 
+    /// Builds a `Target` corresponding to the `post-build = "post_build.rs"`
+    /// entry.
+    pub fn post_build_target(name: &str, src_path: PathBuf, edition: Edition) -> Target {
+        let mut target = Target::with_path(src_path, edition);
+        target
+            .set_kind(TargetKind::PostBuild)
+            .set_name(name)
+            .set_for_host(true)
+            .set_benched(false)
+            .set_tested(false)
+            .set_doc_scrape_examples(RustdocScrapeExamples::Disabled);
+        target
+    }
+
     pub fn metabuild_target(name: &str) -> Target {
         let mut target = Target::new(TargetSourcePath::Metabuild, Edition::Edition2018);
         target
@@ -1002,6 +1037,9 @@ impl Target {
     pub fn is_custom_build(&self) -> bool {
         *self.kind() == TargetKind::CustomBuild
     }
+    pub fn is_post_build(&self) -> bool {
+        *self.kind() == TargetKind::PostBuild
+    }
 
     /// Returns the arguments suitable for `--crate-type` to pass to rustc.
     pub fn rustc_crate_types(&self) -> Vec<CrateType> {
@@ -1080,6 +1118,7 @@ impl Target {
                 format!("example \"{}\"", self.name())
             }
             TargetKind::CustomBuild => "build script".to_string(),
+            TargetKind::PostBuild => "post-build script".to_string(),
         }
     }
 }
@@ -1095,6 +1134,7 @@ impl fmt::Display for Target {
                 write!(f, "Target(example: {})", self.name())
             }
             TargetKind::CustomBuild => write!(f, "Target(script)"),
+            TargetKind::PostBuild => write!(f, "Target(post-build script)"),
         }
     }
 }
@@ -8,11 +8,11 @@ pub use self::package_id_spec::PackageIdSpecQuery;
 pub use self::registry::Registry;
 pub use self::resolver::{Resolve, ResolveVersion};
 pub use self::shell::{Shell, Verbosity};
-pub use self::source_id::SourceId;
+pub use self::source_id::{GitCheckoutOptions, SourceId};
 pub use self::summary::{FeatureMap, FeatureValue, Summary};
 pub use self::workspace::{
-    find_workspace_root, resolve_relative_path, MaybePackage, Workspace, WorkspaceConfig,
-    WorkspaceRootConfig,
+    find_workspace_root, resolve_relative_path, MaybePackage, SourcePolicyScope, Workspace,
+    WorkspaceConfig, WorkspaceRootConfig,
 };
 pub use cargo_util_schemas::core::{GitReference, PackageIdSpec, SourceKind};
 
@@ -1,7 +1,8 @@
 use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::fs;
 use std::hash;
 use std::mem;
 use std::path::{Path, PathBuf};
@@ -10,29 +11,33 @@ use std::time::{Duration, Instant};
 
 use anyhow::Context as _;
 use bytesize::ByteSize;
+use cargo_util::paths;
 use cargo_util_schemas::manifest::RustVersion;
+use cargo_util_schemas::metadata;
 use curl::easy::Easy;
 use curl::multi::{EasyHandle, Multi};
 use lazycell::LazyCell;
 use semver::Version;
-use serde::Serialize;
 use tracing::debug;
 
 use crate::core::compiler::{CompileKind, RustcTargetData};
 use crate::core::dependency::DepKind;
 use crate::core::resolver::features::ForceAllTargets;
 use crate::core::resolver::{HasDevUnits, Resolve};
-use crate::core::{Dependency, Manifest, PackageId, PackageIdSpec, SourceId, Target};
+use crate::core::{Dependency, Manifest, PackageId, SourceId, Target};
 use crate::core::{Summary, Workspace};
+use crate::sources::registry::RegistryMirror;
 use crate::sources::source::{MaybePackage, SourceMap};
 use crate::util::cache_lock::{CacheLock, CacheLockMode};
 use crate::util::errors::{CargoResult, HttpNotSuccessful};
 use crate::util::interning::InternedString;
+use crate::util::machine_message::BuildStatus;
 use crate::util::network::http::http_handle_and_timeout;
 use crate::util::network::http::HttpTimeout;
+use crate::util::network::proxy::registry_proxy;
 use crate::util::network::retry::{Retry, RetryResult};
 use crate::util::network::sleep::SleepTracker;
-use crate::util::{self, internal, GlobalContext, Progress, ProgressStyle};
+use crate::util::{self, internal, Filesystem, GlobalContext, Progress, ProgressStyle};
 
 /// Information about a package that is available somewhere in the file system.
 ///
@@ -63,37 +68,6 @@ impl PartialOrd for Package {
     }
 }
 
-/// A Package in a form where `Serialize` can be derived.
-#[derive(Serialize)]
-pub struct SerializedPackage {
-    name: InternedString,
-    version: Version,
-    id: PackageIdSpec,
-    license: Option<String>,
-    license_file: Option<String>,
-    description: Option<String>,
-    source: SourceId,
-    dependencies: Vec<Dependency>,
-    targets: Vec<Target>,
-    features: BTreeMap<InternedString, Vec<InternedString>>,
-    manifest_path: PathBuf,
-    metadata: Option<toml::Value>,
-    publish: Option<Vec<String>>,
-    authors: Vec<String>,
-    categories: Vec<String>,
-    keywords: Vec<String>,
-    readme: Option<String>,
-    repository: Option<String>,
-    homepage: Option<String>,
-    documentation: Option<String>,
-    edition: String,
-    links: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    metabuild: Option<Vec<String>>,
-    default_run: Option<String>,
-    rust_version: Option<RustVersion>,
-}
-
 impl Package {
     /// Creates a package from a manifest and its location.
     pub fn new(manifest: Manifest, manifest_path: &Path) -> Package {
@@ -188,7 +162,9 @@ impl Package {
         self.targets().iter().any(|t| t.is_example() || t.is_bin())
     }
 
-    pub fn serialized(&self) -> SerializedPackage {
+    /// The package as `cargo metadata` outputs it, without the fields of
+    /// later format versions.
+    pub fn serialized(&self) -> metadata::Package {
         let summary = self.manifest().summary();
         let package_id = summary.package_id();
         let manmeta = self.manifest().metadata();
@@ -201,31 +177,36 @@ impl Package {
             .iter()
             .filter(|t| t.src_path().is_path())
             .cloned()
+            .map(|mut t| {
+                // Single-file packages are built from a copy of the script
+                // without its embedded manifest, but tools want to work on
+                // the script itself.
+                if self.manifest().is_embedded() {
+                    t.set_src_path(self.manifest_path().to_path_buf().into());
+                }
+                t
+            })
             .collect();
-        // Convert Vec<FeatureValue> to Vec<InternedString>
         let features = summary
             .features()
             .iter()
-            .map(|(k, v)| {
-                (
-                    *k,
-                    v.iter()
-                        .map(|fv| InternedString::new(&fv.to_string()))
-                        .collect(),
-                )
-            })
+            .map(|(k, v)| (k.to_string(), v.iter().map(|fv| fv.to_string()).collect()))
             .collect();
 
-        SerializedPackage {
-            name: package_id.name(),
+        metadata::Package {
+            name: package_id.name().to_string(),
             version: package_id.version().clone(),
             id: package_id.to_spec(),
             license: manmeta.license.clone(),
             license_file: manmeta.license_file.clone(),
             description: manmeta.description.clone(),
-            source: summary.source_id(),
-            dependencies: summary.dependencies().to_vec(),
-            targets,
+            source: summary.source_id().serialized(),
+            dependencies: summary
+                .dependencies()
+                .iter()
+                .map(Dependency::serialized)
+                .collect(),
+            targets: targets.iter().map(Target::serialized).collect(),
             features,
             manifest_path: self.manifest_path().to_path_buf(),
             metadata: self.manifest().custom_metadata().cloned(),
@@ -242,6 +223,7 @@ impl Package {
             publish: self.publish().as_ref().cloned(),
             default_run: self.manifest().default_run().map(|s| s.to_owned()),
             rust_version: self.rust_version().cloned(),
+            lints: None,
         }
     }
 }
@@ -379,6 +361,25 @@ struct Download<'gctx> {
 
     /// Logic used to track retrying this download if it's a spurious failure.
     retry: Retry<'gctx>,
+
+    /// Mirrors to fall back to, in order, if downloading from `url` fails.
+    mirrors: Vec<RegistryMirror>,
+
+    /// Authorization to send with the download, if any. Never sent to
+    /// [`Download::mirrors`].
+    authorization: Option<String>,
+
+    /// Proxy of the registry from `registries.<name>.proxy`.
+    proxy: Option<String>,
+
+    /// Where to keep the data received so far if this download fails, so
+    /// that a later download can resume from it. Only accessed while holding
+    /// the package cache lock of [`Downloads`].
+    partial: Option<Filesystem>,
+
+    /// Number of bytes at the start of `data` that were received by earlier
+    /// attempts, and that the current attempt asked the server to skip.
+    resumed: u64,
 }
 
 impl<'gctx> PackageSet<'gctx> {
@@ -487,8 +488,9 @@ impl<'gctx> PackageSet<'gctx> {
         target_data: &RustcTargetData<'gctx>,
         force_all_targets: ForceAllTargets,
     ) -> CargoResult<()> {
+        /// Collects the used dependencies of each package into `used`.
         fn collect_used_deps(
-            used: &mut BTreeSet<PackageId>,
+            used: &mut BTreeMap<PackageId, Vec<PackageId>>,
             resolve: &Resolve,
             pkg_id: PackageId,
             has_dev_units: HasDevUnits,
@@ -496,18 +498,21 @@ impl<'gctx> PackageSet<'gctx> {
             target_data: &RustcTargetData<'_>,
             force_all_targets: ForceAllTargets,
         ) -> CargoResult<()> {
-            if !used.insert(pkg_id) {
+            if used.contains_key(&pkg_id) {
                 return Ok(());
             }
-            let filtered_deps = PackageSet::filter_deps(
+            let filtered_deps: Vec<_> = PackageSet::filter_deps(
                 pkg_id,
                 resolve,
                 has_dev_units,
                 requested_kinds,
                 target_data,
                 force_all_targets,
-            );
-            for (pkg_id, _dep) in filtered_deps {
+            )
+            .map(|(pkg_id, _dep)| pkg_id)
+            .collect();
+            used.insert(pkg_id, filtered_deps.clone());
+            for pkg_id in filtered_deps {
                 collect_used_deps(
                     used,
                     resolve,
@@ -521,14 +526,10 @@ impl<'gctx> PackageSet<'gctx> {
             Ok(())
         }
 
-        // This is sorted by PackageId to get consistent behavior and error
-        // messages for Cargo's testsuite. Perhaps there is a better ordering
-        // that optimizes download time?
-        let mut to_download = BTreeSet::new();
-
+        let mut used = BTreeMap::new();
         for id in root_ids {
             collect_used_deps(
-                &mut to_download,
+                &mut used,
                 resolve,
                 *id,
                 has_dev_units,
@@ -537,7 +538,7 @@ impl<'gctx> PackageSet<'gctx> {
                 force_all_targets,
             )?;
         }
-        self.get_many(to_download.into_iter())?;
+        self.get_many(download_order(&used))?;
         Ok(())
     }
 
@@ -679,10 +680,11 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
         let source = sources
             .get_mut(id.source_id())
             .ok_or_else(|| internal(format!("couldn't find source for `{}`", id)))?;
+        let proxy = registry_proxy(self.set.gctx, source.replaced_source_id())?;
         let pkg = source
             .download(id)
             .with_context(|| "unable to get packages from source")?;
-        let (url, descriptor, authorization) = match pkg {
+        let (url, descriptor, authorization, mirrors, partial) = match pkg {
             MaybePackage::Ready(pkg) => {
                 debug!("{} doesn't need a download", id);
                 assert!(slot.fill(pkg).is_ok());
@@ -692,9 +694,28 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
                 url,
                 descriptor,
                 authorization,
-            } => (url, descriptor, authorization),
+                mirrors,
+                partial,
+            } => (url, descriptor, authorization, mirrors, partial),
         };
 
+        // Pick up the data of an earlier interrupted download, if any. The
+        // checksum is verified once the download completes, so a stale or
+        // corrupt partial file only costs a fresh download.
+        let data = match &partial {
+            Some(partial) => {
+                let path = self
+                    .set
+                    .gctx
+                    .assert_package_cache_locked(CacheLockMode::DownloadExclusive, partial);
+                fs::read(path).unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+        if !data.is_empty() {
+            debug!(target: "network", "resuming {} from {} bytes", id, data.len());
+        }
+
         // Ok we're going to download this crate, so let's set up all our
         // internal state and hand off an `Easy` handle to our libcurl `Multi`
         // handle. This won't actually start the transfer, but later it'll
@@ -704,9 +725,56 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
         debug!(target: "network", "downloading {} as {}", id, token);
         assert!(self.pending_ids.insert(id));
 
+        let handle = self.handle(token, &url, authorization.as_deref(), proxy.as_deref())?;
+
+        // If the progress bar isn't enabled then it may be awhile before the
+        // first crate finishes downloading so we inform immediately that we're
+        // downloading crates here.
+        if self.downloads_finished == 0
+            && self.pending.is_empty()
+            && !self.progress.borrow().as_ref().unwrap().is_enabled()
+        {
+            self.set.gctx.shell().status("Downloading", "crates ...")?;
+        }
+
+        let dl = Download {
+            token,
+            data: RefCell::new(data),
+            headers: RefCell::new(Vec::new()),
+            id,
+            url,
+            descriptor,
+            total: Cell::new(0),
+            current: Cell::new(0),
+            start: Instant::now(),
+            timed_out: Cell::new(None),
+            retry: Retry::new(self.set.gctx)?,
+            mirrors,
+            authorization,
+            proxy,
+            partial,
+            resumed: 0,
+        };
+        self.enqueue(dl, handle)?;
+        self.tick(WhyTick::DownloadStarted)?;
+
+        Ok(None)
+    }
+
+    /// Creates the `Easy` handle downloading `url` for the download `token`.
+    fn handle(
+        &self,
+        token: usize,
+        url: &str,
+        authorization: Option<&str>,
+        proxy: Option<&str>,
+    ) -> CargoResult<Easy> {
         let (mut handle, _timeout) = http_handle_and_timeout(self.set.gctx)?;
+        if let Some(proxy) = proxy {
+            handle.proxy(proxy)?;
+        }
         handle.get(true)?;
-        handle.url(&url)?;
+        handle.url(url)?;
         handle.follow_location(true)?; // follow redirects
 
         // Add authorization header.
@@ -752,33 +820,7 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
             })
         })?;
 
-        // If the progress bar isn't enabled then it may be awhile before the
-        // first crate finishes downloading so we inform immediately that we're
-        // downloading crates here.
-        if self.downloads_finished == 0
-            && self.pending.is_empty()
-            && !self.progress.borrow().as_ref().unwrap().is_enabled()
-        {
-            self.set.gctx.shell().status("Downloading", "crates ...")?;
-        }
-
-        let dl = Download {
-            token,
-            data: RefCell::new(Vec::new()),
-            headers: RefCell::new(Vec::new()),
-            id,
-            url,
-            descriptor,
-            total: Cell::new(0),
-            current: Cell::new(0),
-            start: Instant::now(),
-            timed_out: Cell::new(None),
-            retry: Retry::new(self.set.gctx)?,
-        };
-        self.enqueue(dl, handle)?;
-        self.tick(WhyTick::DownloadStarted)?;
-
-        Ok(None)
+        Ok(handle)
     }
 
     /// Returns the number of crates that are still downloading.
@@ -796,7 +838,104 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
     /// This function will panic if there are no remaining downloads.
     #[tracing::instrument(skip_all)]
     pub fn wait(&mut self) -> CargoResult<&'a Package> {
-        let (dl, data) = loop {
+        loop {
+            let (mut dl, data) = self.wait_for_download()?;
+
+            // If the progress bar isn't enabled then we still want to provide some
+            // semblance of progress of how we're downloading crates, and if the
+            // progress bar is enabled this provides a good log of what's happening.
+            self.progress.borrow_mut().as_mut().unwrap().clear();
+            self.set.gctx.shell().status("Downloaded", &dl.descriptor)?;
+
+            self.downloads_finished += 1;
+            self.downloaded_bytes += dl.total.get();
+            BuildStatus::Downloading {
+                package_id: dl.id.to_spec(),
+                downloaded: self.downloads_finished,
+                total: self.downloads_finished + self.remaining(),
+            }
+            .emit(&mut self.set.gctx.shell())?;
+            if dl.total.get() > self.largest.0 {
+                self.largest = (dl.total.get(), dl.id.name());
+            }
+
+            // We're about to synchronously extract the crate below. While we're
+            // doing that our download progress won't actually be updated, nor do we
+            // have a great view into the progress of the extraction. Let's prepare
+            // the user for this CPU-heavy step if it looks like it'll take some
+            // time to do so.
+            if dl.total.get() < ByteSize::kb(400).0 {
+                self.tick(WhyTick::DownloadFinished)?;
+            } else {
+                self.tick(WhyTick::Extracting(&dl.id.name()))?;
+            }
+
+            // Inform the original source that the download is finished which
+            // should allow us to actually get the package and fill it in now.
+            let start = Instant::now();
+            let pkg = {
+                let mut sources = self.set.sources.borrow_mut();
+                let source = sources
+                    .get_mut(dl.id.source_id())
+                    .ok_or_else(|| internal(format!("couldn't find source for `{}`", dl.id)))?;
+                source.finish_download(dl.id, data)
+            };
+
+            // Assume that no time has passed while we were calling
+            // `finish_download`, update all speed checks and timeout limits of all
+            // active downloads to make sure they don't fire because of a slowly
+            // extracted tarball.
+            let finish_dur = start.elapsed();
+            self.updated_at.set(self.updated_at.get() + finish_dur);
+            self.next_speed_check
+                .set(self.next_speed_check.get() + finish_dur);
+
+            if let Some(partial) = &dl.partial {
+                let path = self
+                    .set
+                    .gctx
+                    .assert_package_cache_locked(CacheLockMode::DownloadExclusive, partial);
+                let _ = fs::remove_file(path);
+            }
+            let pkg = match pkg {
+                Ok(pkg) => pkg,
+                // Data resumed from an earlier attempt may not fit together
+                // with the rest, e.g. if the file changed on the server in the
+                // meantime, so try once more from scratch.
+                Err(e) if dl.resumed > 0 => {
+                    self.set.gctx.shell().warn(format!(
+                        "{e:#}\nthe download was resumed from an earlier attempt, \
+                         downloading `{}` again from scratch",
+                        dl.id
+                    ))?;
+                    self.downloads_finished -= 1;
+                    self.downloaded_bytes -= dl.total.get();
+                    let handle = self.handle(
+                        dl.token,
+                        &dl.url,
+                        dl.authorization.as_deref(),
+                        dl.proxy.as_deref(),
+                    )?;
+                    dl.retry = Retry::new(self.set.gctx)?;
+                    self.pending_ids.insert(dl.id);
+                    self.enqueue(dl, handle)?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let slot = &self.set.packages[&dl.id];
+            assert!(slot.fill(pkg).is_ok());
+            return Ok(slot.borrow().unwrap());
+        }
+    }
+
+    /// Blocks the current thread until a download finishes successfully,
+    /// retrying and falling back to mirrors on the way.
+    ///
+    /// Returns the finished download along with the data downloaded.
+    fn wait_for_download(&mut self) -> CargoResult<(Download<'gctx>, Vec<u8>)> {
+        loop {
             assert_eq!(self.pending.len(), self.pending_ids.len());
             let (token, result) = self.wait_for_curl()?;
             debug!(target: "network", "{} finished with {:?}", token, result);
@@ -805,19 +944,46 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
                 .pending
                 .remove(&token)
                 .expect("got a token for a non-in-progress transfer");
-            let data = mem::take(&mut *dl.data.borrow_mut());
-            let headers = mem::take(&mut *dl.headers.borrow_mut());
             let mut handle = self.set.multi.remove(handle)?;
             self.pending_ids.remove(&dl.id);
 
+            // If the server refused to resume the download, start over with
+            // whatever it has. This isn't an attempt that failed, so it
+            // doesn't count as a retry.
+            if dl.resumed > 0 {
+                let cannot_resume = match &result {
+                    Err(e) => e.is_range_error() || e.is_bad_download_resume(),
+                    Ok(()) => handle.response_code()? == 416,
+                };
+                if cannot_resume {
+                    debug!(target: "network", "{} cannot be resumed, restarting", dl.url);
+                    dl.data.borrow_mut().clear();
+                    dl.headers.borrow_mut().clear();
+                    self.pending_ids.insert(dl.id);
+                    self.enqueue(dl, handle)?;
+                    continue;
+                }
+            }
+
+            let mut data = mem::take(&mut *dl.data.borrow_mut());
+            let headers = mem::take(&mut *dl.headers.borrow_mut());
+
             // Check if this was a spurious error. If it was a spurious error
             // then we want to re-enqueue our request for another attempt and
             // then we wait for another request to finish.
             let ret = {
                 let timed_out = &dl.timed_out;
                 let url = &dl.url;
+                let data = &mut data;
+                let resumed = dl.resumed;
                 dl.retry.r#try(|| {
                     if let Err(e) = result {
+                        // Whatever arrived before the transfer broke off is
+                        // only worth resuming from if it's the crate itself.
+                        if !matches!(handle.response_code(), Ok(0 | 200 | 206)) {
+                            data.truncate(resumed as usize);
+                        }
+
                         // If this error is "aborted by callback" then that's
                         // probably because our progress callback aborted due to
                         // a timeout. We'll find out by looking at the
@@ -842,77 +1008,70 @@ impl<'a, 'gctx> Downloads<'a, 'gctx> {
                     }
 
                     let code = handle.response_code()?;
-                    if code != 200 && code != 0 {
+                    if code != 200 && code != 206 && code != 0 {
+                        // Only keep what belongs to the crate to resume from.
+                        let body = data.split_off(resumed as usize);
                         return Err(HttpNotSuccessful::new_from_handle(
                             &mut handle,
                             &url,
-                            data,
+                            body,
                             headers,
                         )
                         .into());
                     }
-                    Ok(data)
+                    Ok(())
                 })
             };
             match ret {
-                RetryResult::Success(data) => break (dl, data),
+                RetryResult::Success(()) => return Ok((dl, data)),
                 RetryResult::Err(e) => {
-                    return Err(e.context(format!("failed to download from `{}`", dl.url)))
+                    let e = e.context(format!("failed to download from `{}`", dl.url));
+                    let Some(mirror) = self.next_mirror_url(&mut dl) else {
+                        if let Some(partial) = &dl.partial {
+                            save_partial(self.set.gctx, partial, &data);
+                        }
+                        return Err(e);
+                    };
+                    self.set
+                        .gctx
+                        .shell()
+                        .warn(format!("{e:#}\ntrying mirror `{mirror}`"))?;
+                    let handle = self.handle(dl.token, &mirror, None, dl.proxy.as_deref())?;
+                    dl.url = mirror;
+                    dl.authorization = None;
+                    dl.retry = Retry::new(self.set.gctx)?;
+                    *dl.data.borrow_mut() = data;
+                    self.pending_ids.insert(dl.id);
+                    self.enqueue(dl, handle)?;
                 }
                 RetryResult::Retry(sleep) => {
                     debug!(target: "network", "download retry {} for {sleep}ms", dl.url);
+                    *dl.data.borrow_mut() = data;
                     self.sleeping.push(sleep, (dl, handle));
                 }
             }
-        };
-
-        // If the progress bar isn't enabled then we still want to provide some
-        // semblance of progress of how we're downloading crates, and if the
-        // progress bar is enabled this provides a good log of what's happening.
-        self.progress.borrow_mut().as_mut().unwrap().clear();
-        self.set.gctx.shell().status("Downloaded", &dl.descriptor)?;
-
-        self.downloads_finished += 1;
-        self.downloaded_bytes += dl.total.get();
-        if dl.total.get() > self.largest.0 {
-            self.largest = (dl.total.get(), dl.id.name());
         }
+    }
 
-        // We're about to synchronously extract the crate below. While we're
-        // doing that our download progress won't actually be updated, nor do we
-        // have a great view into the progress of the extraction. Let's prepare
-        // the user for this CPU-heavy step if it looks like it'll take some
-        // time to do so.
-        if dl.total.get() < ByteSize::kb(400).0 {
-            self.tick(WhyTick::DownloadFinished)?;
-        } else {
-            self.tick(WhyTick::Extracting(&dl.id.name()))?;
+    /// Returns the URL to download from with the next mirror of `dl` whose
+    /// configuration can be fetched, if any.
+    fn next_mirror_url(&self, dl: &mut Download<'gctx>) -> Option<String> {
+        while !dl.mirrors.is_empty() {
+            let mirror = dl.mirrors.remove(0);
+            match mirror.download_url(self.set.gctx) {
+                Ok(url) => return Some(url),
+                Err(e) => {
+                    debug!(target: "network", "failed to fetch config of mirror `{mirror}`: {e:#}")
+                }
+            }
         }
-
-        // Inform the original source that the download is finished which
-        // should allow us to actually get the package and fill it in now.
-        let mut sources = self.set.sources.borrow_mut();
-        let source = sources
-            .get_mut(dl.id.source_id())
-            .ok_or_else(|| internal(format!("couldn't find source for `{}`", dl.id)))?;
-        let start = Instant::now();
-        let pkg = source.finish_download(dl.id, data)?;
-
-        // Assume that no time has passed while we were calling
-        // `finish_download`, update all speed checks and timeout limits of all
-        // active downloads to make sure they don't fire because of a slowly
-        // extracted tarball.
-        let finish_dur = start.elapsed();
-        self.updated_at.set(self.updated_at.get() + finish_dur);
-        self.next_speed_check
-            .set(self.next_speed_check.get() + finish_dur);
-
-        let slot = &self.set.packages[&dl.id];
-        assert!(slot.fill(pkg).is_ok());
-        Ok(slot.borrow().unwrap())
+        None
     }
 
-    fn enqueue(&mut self, dl: Download<'gctx>, handle: Easy) -> CargoResult<()> {
+    fn enqueue(&mut self, mut dl: Download<'gctx>, mut handle: Easy) -> CargoResult<()> {
+        // Ask the server to skip what earlier attempts already received.
+        dl.resumed = dl.data.borrow().len() as u64;
+        handle.resume_from(dl.resumed)?;
         let mut handle = self.set.multi.add(handle)?;
         let now = Instant::now();
         handle.set_token(dl.token)?;
@@ -1176,3 +1335,107 @@ mod tls {
         })
     }
 }
+
+/// Keeps `data` of a failed download at `path` for a later download to resume
+/// from.
+///
+/// This is best effort, failing to do so shouldn't hide why the download
+/// failed in the first place.
+fn save_partial(gctx: &GlobalContext, partial: &Filesystem, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    let path = gctx.assert_package_cache_locked(CacheLockMode::DownloadExclusive, partial);
+    let res = path
+        .parent()
+        .map_or(Ok(()), paths::create_dir_all)
+        .and_then(|()| paths::write(path, data));
+    if let Err(e) = res {
+        debug!(target: "network", "failed to save partial download: {e:#}");
+    }
+}
+
+/// Orders the packages of `deps`, which maps each package to its
+/// dependencies, in which their downloads are queued.
+///
+/// libcurl starts transfers in the order they are added, queueing those
+/// exceeding its connection and HTTP/2 stream limits, so this only decides
+/// which downloads start first. Nothing is re-prioritized once they are
+/// queued. Like the priority of [`DependencyQueue`], packages with the most
+/// transitive dependents come first, as more of the build waits on them. Ties
+/// are ordered by `PackageId` to get consistent behavior and error messages
+/// for Cargo's testsuite.
+///
+/// [`DependencyQueue`]: crate::util::DependencyQueue
+fn download_order(deps: &BTreeMap<PackageId, Vec<PackageId>>) -> Vec<PackageId> {
+    let mut dependents: HashMap<PackageId, usize> = HashMap::new();
+    for (&id, id_deps) in deps {
+        let mut seen = HashSet::new();
+        let mut stack = id_deps.clone();
+        while let Some(dep) = stack.pop() {
+            if dep != id && seen.insert(dep) {
+                stack.extend(deps.get(&dep).into_iter().flatten());
+            }
+        }
+        for dep in seen {
+            *dependents.entry(dep).or_default() += 1;
+        }
+    }
+    let mut order: Vec<_> = deps.keys().copied().collect();
+    order.sort_by_key(|id| std::cmp::Reverse(dependents.get(id).copied().unwrap_or(0)));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::download_order;
+    use crate::core::{PackageId, SourceId};
+    use crate::sources::CRATES_IO_INDEX;
+    use crate::util::IntoUrl;
+
+    #[test]
+    fn dependencies_are_downloaded_first() {
+        let loc = CRATES_IO_INDEX.into_url().unwrap();
+        let repo = SourceId::for_registry(&loc).unwrap();
+        let id = |name| PackageId::try_new(name, "1.0.0", repo).unwrap();
+
+        // a -> b -> c -> d, a -> e, with a dev-dependency cycle d -> b.
+        let deps = BTreeMap::from([
+            (id("a"), vec![id("b"), id("e")]),
+            (id("b"), vec![id("c")]),
+            (id("c"), vec![id("d")]),
+            (id("d"), vec![id("b")]),
+            (id("e"), vec![]),
+        ]);
+        let order: Vec<_> = download_order(&deps)
+            .into_iter()
+            .map(|id| id.name().to_string())
+            .collect();
+        assert_eq!(order, ["b", "c", "d", "e", "a"]);
+    }
+
+    #[test]
+    fn shared_dependencies_are_downloaded_first() {
+        let loc = CRATES_IO_INDEX.into_url().unwrap();
+        let repo = SourceId::for_registry(&loc).unwrap();
+        let id = |name| PackageId::try_new(name, "1.0.0", repo).unwrap();
+
+        // a -> {b, c, d}, b -> e, c -> e, d -> f, so `e` has three transitive
+        // dependents and `f` two.
+        let deps = BTreeMap::from([
+            (id("a"), vec![id("b"), id("c"), id("d")]),
+            (id("b"), vec![id("e")]),
+            (id("c"), vec![id("e")]),
+            (id("d"), vec![id("f")]),
+            (id("e"), vec![]),
+            (id("f"), vec![]),
+        ]);
+        let order: Vec<_> = download_order(&deps)
+            .into_iter()
+            .map(|id| id.name().to_string())
+            .collect();
+        assert_eq!(order, ["e", "f", "b", "c", "d", "a"]);
+    }
+}
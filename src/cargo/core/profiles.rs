@@ -1157,11 +1157,14 @@ impl UnitFor {
         //
         // A proc-macro should always use host features.
         //
-        // Dependencies of a build script should use host features (subtle
-        // point: the build script itself does *not* use host features, that's
-        // why the parent is checked here, and not the dependency).
-        let host_features =
-            self.host_features || parent.target.is_custom_build() || dep_target.proc_macro();
+        // Dependencies of a build script or post-build script should use host
+        // features (subtle point: the build script itself does *not* use host
+        // features, that's why the parent is checked here, and not the
+        // dependency).
+        let host_features = self.host_features
+            || parent.target.is_custom_build()
+            || parent.target.is_post_build()
+            || dep_target.proc_macro();
         // Build scripts and proc macros, and all of their dependencies are
         // AlwaysUnwind.
         let panic_setting = if dep_for_host {
@@ -1228,16 +1231,25 @@ impl UnitFor {
     /// the artifact is built.
     /// If we are an artifact but don't specify a `target`, we assume the default
     /// compile kind that is suitable in this situation.
+    /// Otherwise, normal dependencies use the features for the root compile kind, in
+    /// case features are unified separately for each requested target.
     pub(crate) fn map_to_features_for(&self, dep_artifact: Option<&Artifact>) -> FeaturesFor {
-        FeaturesFor::from_for_host_or_artifact_target(
-            self.is_for_host_features(),
-            match dep_artifact {
-                Some(artifact) => artifact
-                    .target()
-                    .and_then(|t| t.to_resolved_compile_target(self.root_compile_kind)),
-                None => self.artifact_target_for_features,
-            },
-        )
+        let artifact_target = match dep_artifact {
+            Some(artifact) => artifact
+                .target()
+                .and_then(|t| t.to_resolved_compile_target(self.root_compile_kind)),
+            None => self.artifact_target_for_features,
+        };
+        match artifact_target {
+            Some(_) => FeaturesFor::from_for_host_or_artifact_target(
+                self.is_for_host_features(),
+                artifact_target,
+            ),
+            None => FeaturesFor::from_for_host_and_kind(
+                self.is_for_host_features(),
+                self.root_compile_kind,
+            ),
+        }
     }
 
     pub(crate) fn root_compile_kind(&self) -> CompileKind {
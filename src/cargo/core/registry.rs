@@ -9,19 +9,22 @@
 //! The former is just one kind of source,
 //! while the latter involves operations on the registry Web API.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::mpsc;
 use std::task::{ready, Poll};
+use std::thread;
 
 use crate::core::PackageSet;
 use crate::core::{Dependency, PackageId, SourceId, Summary};
 use crate::sources::config::SourceConfigMap;
+use crate::sources::source::PreparedFetch;
 use crate::sources::source::QueryKind;
 use crate::sources::source::Source;
 use crate::sources::source::SourceMap;
-use crate::sources::IndexSummary;
+use crate::sources::{IndexSummary, CRATES_IO_REGISTRY};
 use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
-use crate::util::{CanonicalUrl, GlobalContext};
+use crate::util::{CanonicalUrl, GlobalContext, Progress, ProgressStyle};
 use anyhow::{bail, Context as _};
 use tracing::{debug, trace};
 use url::Url;
@@ -131,6 +134,14 @@ pub struct PackageRegistry<'gctx> {
     /// This is constructed during calls to [`PackageRegistry::patch`],
     /// along with the `patches` field, thoough these entries never get locked.
     patches_available: HashMap<CanonicalUrl, Vec<PackageId>>,
+
+    /// Registries whose dependencies are resolved against another registry
+    /// instead, from the `[registry-remap]` config table.
+    ///
+    /// Unlike source replacement, the remapped registry may have a different
+    /// set of versions, so the resolve (and lock file) records the packages
+    /// under the registry they were actually resolved from.
+    registry_remap: HashMap<SourceId, SourceId>,
 }
 
 /// A map of all "locked packages" which is filled in when parsing a lock file
@@ -209,9 +220,16 @@ impl<'gctx> PackageRegistry<'gctx> {
             patches: HashMap::new(),
             patches_locked: false,
             patches_available: HashMap::new(),
+            registry_remap: load_registry_remap(gctx)?,
         })
     }
 
+    /// Returns the registry that dependencies on `source_id` are resolved
+    /// against, if remapped by `[registry-remap]`.
+    pub fn remapped_registry(&self, source_id: SourceId) -> Option<SourceId> {
+        self.registry_remap.get(&source_id).copied()
+    }
+
     pub fn get(self, package_ids: &[PackageId]) -> CargoResult<PackageSet<'gctx>> {
         trace!("getting packages; sources={}", self.sources.len());
         PackageSet::new(package_ids, self.sources, self.gctx)
@@ -502,7 +520,12 @@ impl<'gctx> PackageRegistry<'gctx> {
         for summaries in self.patches.values_mut() {
             for summary in summaries {
                 debug!("locking patch {:?}", summary);
-                *summary = lock(&self.locked, &self.patches_available, summary.clone());
+                *summary = lock(
+                    &self.locked,
+                    &self.patches_available,
+                    &self.registry_remap,
+                    summary.clone(),
+                );
             }
         }
         self.patches_locked = true;
@@ -580,7 +603,12 @@ impl<'gctx> PackageRegistry<'gctx> {
     /// through.
     pub fn lock(&self, summary: Summary) -> Summary {
         assert!(self.patches_locked);
-        lock(&self.locked, &self.patches_available, summary)
+        lock(
+            &self.locked,
+            &self.patches_available,
+            &self.registry_remap,
+            summary,
+        )
     }
 
     fn warn_bad_override(
@@ -646,6 +674,7 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
         f: &mut dyn FnMut(IndexSummary),
     ) -> Poll<CargoResult<()>> {
         assert!(self.patches_locked);
+        let dep = &remap_dependency(&self.registry_remap, dep.clone());
         // Look for an override and get ready to query the real source.
         let override_summary = ready!(self.query_overrides(dep))?;
 
@@ -656,7 +685,13 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
         // come from a different source. This means that `dep.matches(..)` will
         // always return false, when what we really care about is the name/version match.
         let mut patches = Vec::<Summary>::new();
-        if let Some(extra) = self.patches.get(dep.source_id().canonical_url()) {
+        // Patches are never downloaded, so they are always available offline.
+        let patches_apply = kind != QueryKind::OfflineUnavailable;
+        if let Some(extra) = self
+            .patches
+            .get(dep.source_id().canonical_url())
+            .filter(|_| patches_apply)
+        {
             patches.extend(
                 extra
                     .iter()
@@ -725,6 +760,7 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
                 // then we skip this `summary`.
                 let locked = &self.locked;
                 let all_patches = &self.patches_available;
+                let registry_remap = &self.registry_remap;
                 let callback = &mut |summary: IndexSummary| {
                     for patch in patches.iter() {
                         let patch = patch.package_id().version();
@@ -733,7 +769,12 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
                         }
                     }
                     let summary = summary.into_summary();
-                    f(IndexSummary::Candidate(lock(locked, all_patches, summary)))
+                    f(IndexSummary::Candidate(lock(
+                        locked,
+                        all_patches,
+                        registry_remap,
+                        summary,
+                    )))
                 };
                 return source.query(dep, kind, callback);
             }
@@ -790,6 +831,19 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
             // happens to behave this time
             self.gctx.shell().verbosity();
         }
+        let jobs = git_fetch_jobs(self.gctx)?;
+        if jobs > 1 {
+            let mut fetches = Vec::new();
+            for (source_id, source) in self.sources.sources_mut() {
+                if let Some(fetch) = source
+                    .prepare_fetch()
+                    .with_context(|| format!("Unable to update {}", source_id))?
+                {
+                    fetches.push((*source_id, fetch));
+                }
+            }
+            run_fetches(fetches, jobs, self.gctx)?;
+        }
         for (source_id, source) in self.sources.sources_mut() {
             source
                 .block_until_ready()
@@ -799,10 +853,80 @@ impl<'gctx> Registry for PackageRegistry<'gctx> {
     }
 }
 
+/// Gets how many git fetches may run at once, as configured by the unstable
+/// `net.git-fetch-jobs`.
+fn git_fetch_jobs(gctx: &GlobalContext) -> CargoResult<usize> {
+    match gctx.net_config()?.git_fetch_jobs {
+        None => Ok(1),
+        Some(_) if !gctx.cli_unstable().unstable_options => bail!(
+            "the `net.git-fetch-jobs` config is unstable, pass `-Zunstable-options` to enable it"
+        ),
+        Some(0) => bail!("`net.git-fetch-jobs` must be at least 1"),
+        Some(jobs) => Ok(jobs as usize),
+    }
+}
+
+/// Runs the fetches from [`Source::prepare_fetch`], at most `jobs` of them at
+/// a time.
+///
+/// Fetches are started from this thread, so that their status is printed
+/// when they actually start.
+fn run_fetches(
+    fetches: Vec<(SourceId, PreparedFetch)>,
+    jobs: usize,
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
+    if fetches.is_empty() {
+        return Ok(());
+    }
+    let total = fetches.len();
+    let mut queue = fetches.into_iter();
+    let (tx, rx) = mpsc::channel();
+    let mut progress = Progress::with_style("Fetch", ProgressStyle::Ratio, gctx);
+    thread::scope(|s| {
+        let mut running = 0;
+        let mut finished = 0;
+        let mut error = None;
+        loop {
+            // Stop starting new fetches once one of them failed.
+            while running < jobs && error.is_none() {
+                let Some((source_id, fetch)) = queue.next() else {
+                    break;
+                };
+                if let Some(status) = &fetch.status {
+                    gctx.shell().status("Updating", status)?;
+                }
+                gctx.shell()
+                    .verbose(|s| s.status("Running", &fetch.cmd.to_string()))?;
+                let tx = tx.clone();
+                s.spawn(move || {
+                    let _ = tx.send((source_id, fetch.cmd.exec_with_output()));
+                });
+                running += 1;
+            }
+            if running == 0 {
+                break;
+            }
+            let (source_id, res) = rx.recv().expect("a fetch is running");
+            running -= 1;
+            finished += 1;
+            if let Err(e) = res {
+                error.get_or_insert_with(|| e.context(format!("Unable to update {}", source_id)));
+            }
+            progress.tick(finished, total, "")?;
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    })
+}
+
 /// See [`PackageRegistry::lock`].
 fn lock(
     locked: &LockedMap,
     patches: &HashMap<CanonicalUrl, Vec<PackageId>>,
+    registry_remap: &HashMap<SourceId, SourceId>,
     summary: Summary,
 ) -> Summary {
     let pair = locked
@@ -817,6 +941,9 @@ fn lock(
         None => summary,
     };
     summary.map_dependencies(|dep| {
+        // Remap the registry first, so the lock entries looked up below are
+        // the ones for the registry actually being resolved against.
+        let dep = remap_dependency(registry_remap, dep);
         trace!(
             "\t{}/{}/{}",
             dep.package_name(),
@@ -908,6 +1035,49 @@ fn lock(
     })
 }
 
+/// Points `dep` at the registry it is remapped to by `[registry-remap]`, if any.
+fn remap_dependency(registry_remap: &HashMap<SourceId, SourceId>, dep: Dependency) -> Dependency {
+    match registry_remap.get(&dep.source_id()) {
+        Some(&to) => {
+            let from = dep.source_id();
+            dep.map_source(from, to)
+        }
+        None => dep,
+    }
+}
+
+/// Loads the `[registry-remap]` config table, mapping registry names to the
+/// name of the registry their dependencies should be resolved against.
+///
+/// This requires `-Zregistry-remap`, otherwise the table is ignored.
+fn load_registry_remap(gctx: &GlobalContext) -> CargoResult<HashMap<SourceId, SourceId>> {
+    let mut remap = HashMap::new();
+    if !gctx.cli_unstable().registry_remap {
+        return Ok(remap);
+    }
+    let Some(table) = gctx.get::<Option<BTreeMap<String, String>>>("registry-remap")? else {
+        return Ok(remap);
+    };
+    let registry_source_id = |name: &str| {
+        if name == CRATES_IO_REGISTRY {
+            SourceId::crates_io(gctx)
+        } else {
+            SourceId::alt_registry(gctx, name)
+        }
+    };
+    for (from, to) in table {
+        if from == to {
+            bail!("`registry-remap.{from}` cannot remap a registry to itself");
+        }
+        let from_id = registry_source_id(&from)
+            .with_context(|| format!("failed to load `registry-remap.{from}`"))?;
+        let to_id = registry_source_id(&to)
+            .with_context(|| format!("failed to load `registry-remap.{from}`"))?;
+        remap.insert(from_id, to_id);
+    }
+    Ok(remap)
+}
+
 /// A helper for selecting the summary, or generating a helpful error message.
 ///
 /// Returns a tuple that the first element is the summary selected. The second
@@ -156,6 +156,7 @@ impl EncodableResolve {
     pub fn into_resolve(self, original: &str, ws: &Workspace<'_>) -> CargoResult<Resolve> {
         let path_deps: HashMap<String, HashMap<semver::Version, SourceId>> = build_path_deps(ws)?;
         let mut checksums = HashMap::new();
+        let mut pubtimes = HashMap::new();
 
         let mut version = match self.version {
             Some(n @ 5) if ws.gctx().nightly_features_allowed => {
@@ -221,7 +222,15 @@ impl EncodableResolve {
                 // didn't ever encode this field.
                 if let Some(cksum) = &pkg.checksum {
                     version = version.max(ResolveVersion::V2);
-                    checksums.insert(id, Some(cksum.clone()));
+                    let cksum = if version >= ResolveVersion::V5 {
+                        decode_checksum(&pkg.name, cksum)?
+                    } else {
+                        cksum.clone()
+                    };
+                    checksums.insert(id, Some(cksum));
+                }
+                if let Some(pubtime) = &pkg.pubtime {
+                    pubtimes.insert(id, pubtime.clone());
                 }
 
                 assert!(live_pkgs.insert(enc_id, (id, pkg)).is_none())
@@ -408,6 +417,7 @@ impl EncodableResolve {
             replacements,
             HashMap::new(),
             checksums,
+            pubtimes,
             metadata,
             unused_patches,
             version,
@@ -524,10 +534,65 @@ pub struct EncodableDependency {
     version: String,
     source: Option<EncodableSourceId>,
     checksum: Option<String>,
+    pubtime: Option<String>,
     dependencies: Option<Vec<EncodablePackageId>>,
     replace: Option<EncodablePackageId>,
 }
 
+/// The algorithm used to compute a `checksum` in `Cargo.lock`.
+///
+/// Starting with [`ResolveVersion::V5`], checksums are written as
+/// `<algorithm>:<hex digest>`. Older versions only ever recorded SHA256
+/// digests, without a prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ChecksumAlgorithm {
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+impl FromStr for ChecksumAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> CargoResult<ChecksumAlgorithm> {
+        match s {
+            "sha256" => Ok(ChecksumAlgorithm::Sha256),
+            _ => bail!("unknown checksum algorithm `{s}`, expected `sha256`"),
+        }
+    }
+}
+
+/// Parses a V5 `checksum` field into the bare SHA256 digest Cargo verifies
+/// packages against.
+///
+/// Digests without an algorithm prefix are assumed to be SHA256, as written
+/// by earlier Cargo versions.
+fn decode_checksum(name: &str, cksum: &str) -> CargoResult<String> {
+    let Some((algorithm, digest)) = cksum.split_once(':') else {
+        return Ok(cksum.to_owned());
+    };
+    let algorithm: ChecksumAlgorithm = algorithm
+        .parse()
+        .with_context(|| format!("invalid checksum for package `{name}`"))?;
+    match algorithm {
+        ChecksumAlgorithm::Sha256 => Ok(digest.to_owned()),
+    }
+}
+
+fn encode_checksum(cksum: &str, version: ResolveVersion) -> String {
+    if version >= ResolveVersion::V5 {
+        format!("{}:{cksum}", ChecksumAlgorithm::Sha256.as_str())
+    } else {
+        cksum.to_owned()
+    }
+}
+
 /// Pretty much equivalent to [`SourceId`] with a different serialization method.
 ///
 /// The serialization for `SourceId` doesn't do URL encode for parameters.
@@ -543,6 +608,10 @@ pub struct EncodableSourceId {
     /// via [`encodable_source_id`].
     #[serde(skip)]
     encoded: bool,
+    /// Whether to serialize the canonical form of a registry URL, see
+    /// [`EncodableSourceId::canonical_registry`].
+    #[serde(skip)]
+    canonical: bool,
 }
 
 impl EncodableSourceId {
@@ -551,6 +620,19 @@ impl EncodableSourceId {
         Self {
             inner,
             encoded: true,
+            canonical: false,
+        }
+    }
+
+    /// Creates a `EncodableSourceId` for a git-index registry that is
+    /// serialized with its canonical URL, so equivalent spellings of the
+    /// index URL (trailing `/`, `.git` suffix, case of GitHub paths) don't
+    /// cause lock file churn.
+    fn canonical_registry(inner: SourceId) -> Self {
+        Self {
+            inner,
+            encoded: true,
+            canonical: true,
         }
     }
 
@@ -560,15 +642,23 @@ impl EncodableSourceId {
         Self {
             inner,
             encoded: false,
+            canonical: false,
         }
     }
 
     /// Encodes the inner [`SourceId`] as a URL.
-    fn as_url(&self) -> impl fmt::Display + '_ {
-        if self.encoded {
-            self.inner.as_encoded_url()
+    fn as_url(&self) -> String {
+        if self.canonical {
+            // Registries compare by their canonical URL already, so this
+            // decodes back to an equal `SourceId`.
+            format!(
+                "registry+{}",
+                self.inner.canonical_url().raw_canonicalized_url()
+            )
+        } else if self.encoded {
+            self.inner.as_encoded_url().to_string()
         } else {
-            self.inner.as_url()
+            self.inner.as_url().to_string()
         }
     }
 }
@@ -719,10 +809,14 @@ impl ser::Serialize for Resolve {
                     dependencies: None,
                     replace: None,
                     checksum: if self.version() >= ResolveVersion::V2 {
-                        self.checksums().get(id).and_then(|x| x.clone())
+                        self.checksums()
+                            .get(id)
+                            .and_then(|x| x.as_deref())
+                            .map(|x| encode_checksum(x, self.version()))
                     } else {
                         None
                     },
+                    pubtime: None,
                 })
                 .collect(),
         };
@@ -731,12 +825,7 @@ impl ser::Serialize for Resolve {
             root: None,
             metadata,
             patch,
-            version: match self.version() {
-                ResolveVersion::V5 => Some(5),
-                ResolveVersion::V4 => Some(4),
-                ResolveVersion::V3 => Some(3),
-                ResolveVersion::V2 | ResolveVersion::V1 => None,
-            },
+            version: self.version().lockfile_version(),
         }
         .serialize(s)
     }
@@ -793,7 +882,16 @@ fn encodable_resolve_node(
         dependencies: deps,
         replace,
         checksum: if resolve.version() >= ResolveVersion::V2 {
-            resolve.checksums().get(&id).and_then(|s| s.clone())
+            resolve
+                .checksums()
+                .get(&id)
+                .and_then(|s| s.as_deref())
+                .map(|s| encode_checksum(s, resolve.version()))
+        } else {
+            None
+        },
+        pubtime: if resolve.version() >= ResolveVersion::V5 {
+            resolve.pubtimes().get(&id).cloned()
         } else {
             None
         },
@@ -836,10 +934,14 @@ fn encodable_source_id(id: SourceId, version: ResolveVersion) -> Option<Encodabl
     if id.is_path() {
         None
     } else {
-        Some(if version >= ResolveVersion::V4 {
-            EncodableSourceId::new(id)
-        } else {
-            EncodableSourceId::without_url_encoded(id)
-        })
+        Some(
+            if version >= ResolveVersion::V5 && id.is_remote_registry() && !id.is_sparse() {
+                EncodableSourceId::canonical_registry(id)
+            } else if version >= ResolveVersion::V4 {
+                EncodableSourceId::new(id)
+            } else {
+                EncodableSourceId::without_url_encoded(id)
+            },
+        )
     }
 }
@@ -6,6 +6,7 @@ use crate::sources::source::QueryKind;
 use crate::util::edit_distance::edit_distance;
 use crate::util::{GlobalContext, OptVersionReq, VersionExt};
 use anyhow::Error;
+use time::format_description::well_known::Rfc3339;
 
 use super::context::ResolverContext;
 use super::types::{ConflictMap, ConflictReason};
@@ -211,6 +212,11 @@ pub(super) fn activation_error(
         msg.push_str(&*dep.package_name());
         msg.push_str("` which could resolve this conflict");
 
+        if let Some(hint) = offline_unavailable_hint(registry, dep, gctx) {
+            msg.push('\n');
+            msg.push_str(&hint);
+        }
+
         return to_resolve_err(anyhow::format_err!("{}", msg));
     }
 
@@ -363,6 +369,11 @@ pub(super) fn activation_error(
         msg
     };
 
+    if let Some(hint) = offline_unavailable_hint(registry, dep, gctx) {
+        msg.push('\n');
+        msg.push_str(&hint);
+    }
+
     if let Some(gctx) = gctx {
         if gctx.offline() {
             msg.push_str(
@@ -372,11 +383,60 @@ pub(super) fn activation_error(
                  without the offline flag.",
             );
         }
+        if let Ok(Some(time)) = gctx.index_snapshot() {
+            if let Ok(time) = time.format(&Rfc3339) {
+                msg.push_str(&format!(
+                    "\nAs a reminder, registry versions published after {time} are ignored \
+                     because of the index snapshot."
+                ));
+            }
+        }
     }
 
     to_resolve_err(anyhow::format_err!("{}", msg))
 }
 
+/// With `--offline`, describes the versions matching `dep` that the cached
+/// registry index knows about but which were never downloaded, and so could
+/// not be considered.
+///
+/// This is best-effort: any error while querying just omits the hint.
+fn offline_unavailable_hint(
+    registry: &mut dyn Registry,
+    dep: &Dependency,
+    gctx: Option<&GlobalContext>,
+) -> Option<String> {
+    if !gctx.is_some_and(|gctx| gctx.offline()) {
+        return None;
+    }
+    let candidates = loop {
+        match registry.query_vec(dep, QueryKind::OfflineUnavailable) {
+            Poll::Ready(Ok(candidates)) => break candidates,
+            Poll::Ready(Err(_)) => return None,
+            Poll::Pending => registry.block_until_ready().ok()?,
+        }
+    };
+    let mut versions: Vec<_> = candidates
+        .iter()
+        .map(|s| s.package_id().version().clone())
+        .collect();
+    versions.sort_unstable();
+    versions.dedup();
+    let name = dep.package_name();
+    let msg = match versions.as_slice() {
+        [] => return None,
+        [version] => format!(
+            "version {version} of `{name}` exists but is not downloaded; \
+             run `cargo fetch` without `--offline` to download it"
+        ),
+        [first, .., last] => format!(
+            "versions {first} through {last} of `{name}` exist but are not downloaded; \
+             run `cargo fetch` without `--offline` to download them"
+        ),
+    };
+    Some(msg)
+}
+
 /// Returns String representation of dependency chain for a particular `pkgid`
 /// within given context.
 pub(super) fn describe_path_in_context(cx: &ResolverContext, id: &PackageId) -> String {
@@ -78,6 +78,13 @@ pub struct FeatureOpts {
     decouple_host_deps: bool,
     /// Dev dep features will not be activated unless needed.
     decouple_dev_deps: bool,
+    /// Normal and dev dependencies built for different `--target`s will not
+    /// share features.
+    decouple_targets: bool,
+    /// The `--target`s whose features are unified separately, if
+    /// `decouple_targets` is enabled. Filled in once the requested targets are
+    /// known by [`FeatureResolver::resolve`].
+    decoupled_targets: Vec<CompileTarget>,
     /// Targets that are not in use will not activate features.
     ignore_inactive_targets: bool,
     /// If enabled, compare against old resolver (for testing).
@@ -113,6 +120,12 @@ pub enum FeaturesFor {
     /// Normal or dev dependency.
     #[default]
     NormalOrDev,
+    /// Normal or dev dependency, built for the given `--target`.
+    ///
+    /// Only distinct from [`FeaturesFor::NormalOrDev`] when features are
+    /// unified separately for each requested target (`resolver = "3"` with
+    /// `-Zper-target-features`).
+    TargetDep(CompileTarget),
     /// Build dependency or proc-macro.
     HostDep,
     /// Any dependency with both artifact and target specified.
@@ -125,7 +138,9 @@ impl std::fmt::Display for FeaturesFor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             FeaturesFor::HostDep => f.write_str("host"),
-            FeaturesFor::ArtifactDep(target) => f.write_str(&target.rustc_target()),
+            FeaturesFor::ArtifactDep(target) | FeaturesFor::TargetDep(target) => {
+                f.write_str(&target.rustc_target())
+            }
             FeaturesFor::NormalOrDev => Ok(()),
         }
     }
@@ -156,11 +171,26 @@ impl FeaturesFor {
         }
     }
 
+    /// Features for a unit built for `kind` that is not a host dependency
+    /// or an artifact dependency with a `target`, or a host dependency if
+    /// `for_host` is set.
+    pub fn from_for_host_and_kind(for_host: bool, kind: CompileKind) -> FeaturesFor {
+        match (for_host, kind) {
+            (true, _) => FeaturesFor::HostDep,
+            (false, CompileKind::Host) => FeaturesFor::NormalOrDev,
+            (false, CompileKind::Target(target)) => FeaturesFor::TargetDep(target),
+        }
+    }
+
     fn apply_opts(self, opts: &FeatureOpts) -> Self {
-        if opts.decouple_host_deps {
-            self
-        } else {
-            FeaturesFor::default()
+        if !opts.decouple_host_deps {
+            return FeaturesFor::default();
+        }
+        match self {
+            FeaturesFor::TargetDep(target) if !opts.decoupled_targets.contains(&target) => {
+                FeaturesFor::NormalOrDev
+            }
+            _ => self,
         }
     }
 }
@@ -200,6 +230,8 @@ impl FeatureOpts {
                 enable(&vec!["all".to_string()]).unwrap();
             }
         }
+        opts.decouple_targets =
+            ws.resolve_behavior() == ResolveBehavior::V3 && unstable_flags.per_target_features;
         if let HasDevUnits::Yes = has_dev_units {
             // Dev deps cannot be decoupled when they are in use.
             opts.decouple_dev_deps = false;
@@ -217,6 +249,8 @@ impl FeatureOpts {
             ResolveBehavior::V2 | ResolveBehavior::V3 => FeatureOpts {
                 decouple_host_deps: true,
                 decouple_dev_deps: has_dev_units == HasDevUnits::No,
+                decouple_targets: false,
+                decoupled_targets: Vec::new(),
                 ignore_inactive_targets: true,
                 compare: false,
             },
@@ -340,6 +374,32 @@ impl ResolvedFeatures {
             .unwrap_or(false)
     }
 
+    /// Returns the features that are enabled for the given package as a
+    /// normal or dev dependency of each requested `--target`.
+    ///
+    /// This is `None` unless features are unified separately for each
+    /// requested target. Targets the package is not used for are left out.
+    pub fn activated_features_per_target(
+        &self,
+        pkg_id: PackageId,
+    ) -> Option<BTreeMap<CompileTarget, Vec<InternedString>>> {
+        if self.opts.decoupled_targets.is_empty() {
+            return None;
+        }
+        let per_target = self
+            .opts
+            .decoupled_targets
+            .iter()
+            .filter_map(|target| {
+                let features = self
+                    .activated_features
+                    .get(&(pkg_id, FeaturesFor::TargetDep(*target)))?;
+                Some((*target, features.iter().cloned().collect()))
+            })
+            .collect();
+        Some(per_target)
+    }
+
     /// Variant of `activated_features` that returns `None` if this is
     /// not a valid pkg_id/is_build combination. Used in places which do
     /// not know which packages are activated (like `cargo clean`).
@@ -455,9 +515,18 @@ impl<'a, 'gctx> FeatureResolver<'a, 'gctx> {
         cli_features: &CliFeatures,
         specs: &[PackageIdSpec],
         requested_targets: &[CompileKind],
-        opts: FeatureOpts,
+        mut opts: FeatureOpts,
     ) -> CargoResult<ResolvedFeatures> {
         let track_for_host = opts.decouple_host_deps || opts.ignore_inactive_targets;
+        if opts.decouple_targets {
+            opts.decoupled_targets = requested_targets
+                .iter()
+                .filter_map(|kind| match kind {
+                    CompileKind::Host => None,
+                    CompileKind::Target(target) => Some(*target),
+                })
+                .collect();
+        }
         let mut r = FeatureResolver {
             ws,
             target_data,
@@ -489,10 +558,22 @@ impl<'a, 'gctx> FeatureResolver<'a, 'gctx> {
         specs: &[PackageIdSpec],
         cli_features: &CliFeatures,
     ) -> CargoResult<()> {
-        let member_features = self.ws.members_with_features(specs, cli_features)?;
+        let member_features = self
+            .ws
+            .members_with_features_to_resolve(specs, cli_features)?;
+        // With features unified per target, each requested target gets its
+        // own set of normal features.
+        let normal_fks: Vec<FeaturesFor> = if self.opts.decoupled_targets.is_empty() {
+            vec![FeaturesFor::default()]
+        } else {
+            self.requested_targets
+                .iter()
+                .map(|kind| FeaturesFor::from_for_host_and_kind(false, *kind))
+                .collect()
+        };
         for (member, cli_features) in &member_features {
             let fvs = self.fvs_from_requested(member.package_id(), cli_features);
-            let fk = if self.track_for_host && self.has_any_proc_macro(member.package_id()) {
+            if self.track_for_host && self.has_any_proc_macro(member.package_id()) {
                 // Also activate for normal dependencies. This is needed if the
                 // proc-macro includes other targets (like binaries or tests),
                 // or running in `cargo test`. Note that in a workspace, if
@@ -500,12 +581,15 @@ impl<'a, 'gctx> FeatureResolver<'a, 'gctx> {
                 // `--workspace`), this forces feature unification with normal
                 // dependencies. This is part of the bigger problem where
                 // features depend on which packages are built.
-                self.activate_pkg(member.package_id(), FeaturesFor::default(), &fvs)?;
-                FeaturesFor::HostDep
+                for fk in &normal_fks {
+                    self.activate_pkg(member.package_id(), *fk, &fvs)?;
+                }
+                self.activate_pkg(member.package_id(), FeaturesFor::HostDep, &fvs)?;
             } else {
-                FeaturesFor::default()
-            };
-            self.activate_pkg(member.package_id(), fk, &fvs)?;
+                for fk in &normal_fks {
+                    self.activate_pkg(member.package_id(), *fk, &fvs)?;
+                }
+            }
         }
         Ok(())
     }
@@ -800,7 +884,7 @@ impl<'a, 'gctx> FeatureResolver<'a, 'gctx> {
                 (_, FeaturesFor::NormalOrDev) => requested_targets
                     .iter()
                     .any(|kind| target_data.dep_platform_activated(dep, *kind)),
-                (_, FeaturesFor::ArtifactDep(target)) => {
+                (_, FeaturesFor::ArtifactDep(target) | FeaturesFor::TargetDep(target)) => {
                     target_data.dep_platform_activated(dep, CompileKind::Target(target))
                 }
             }
@@ -853,12 +937,15 @@ impl<'a, 'gctx> FeatureResolver<'a, 'gctx> {
                         // All this may result in a dependency being built multiple times
                         // for various targets which are either specified in the manifest
                         // or on the cargo command-line.
-                        let lib_fk = if fk == FeaturesFor::default() {
-                            (self.track_for_host && (dep.is_build() || self.has_proc_macro_lib(dep_id)))
-                                .then(|| FeaturesFor::HostDep)
-                                .unwrap_or_default()
-                        } else {
-                            fk
+                        let lib_fk = match fk {
+                            FeaturesFor::NormalOrDev | FeaturesFor::TargetDep(_) => {
+                                let for_host = self.track_for_host
+                                    && (dep.is_build() || self.has_proc_macro_lib(dep_id));
+                                for_host
+                                    .then(|| FeaturesFor::HostDep)
+                                    .unwrap_or(fk)
+                            }
+                            _ => fk,
                         };
 
                         // `artifact_target_keys` are produced to fulfil the needs of artifacts that have a target specification.
@@ -134,7 +134,7 @@ pub fn resolve(
         Some(config) if config.cli_unstable().direct_minimal_versions => {
             Some(VersionOrdering::MinimumVersionsFirst)
         }
-        _ => None,
+        _ => version_prefs.direct_ordering(),
     };
     let mut registry = RegistryQueryer::new(registry, replacements, version_prefs);
     let resolver_ctx = loop {
@@ -149,9 +149,13 @@ pub fn resolve(
     };
 
     let mut cksums = HashMap::new();
+    let mut pubtimes = HashMap::new();
     for (summary, _) in resolver_ctx.activations.values() {
         let cksum = summary.checksum().map(|s| s.to_string());
         cksums.insert(summary.package_id(), cksum);
+        if let Some(pubtime) = summary.pubtime() {
+            pubtimes.insert(summary.package_id(), pubtime.to_string());
+        }
     }
     let graph = resolver_ctx.graph();
     let replacements = resolver_ctx.resolve_replacements(&registry);
@@ -170,6 +174,7 @@ pub fn resolve(
         replacements,
         features,
         cksums,
+        pubtimes,
         BTreeMap::new(),
         Vec::new(),
         resolve_version,
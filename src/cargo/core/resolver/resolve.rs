@@ -31,6 +31,9 @@ pub struct Resolve {
     /// validate the correct crate file is used. This is `None` for sources
     /// that do not use `.crate` files, like path or git dependencies.
     checksums: HashMap<PackageId, Option<String>>,
+    /// When each package was published, for registries which report it.
+    /// Only recorded in `Cargo.lock` starting with [`ResolveVersion::V5`].
+    pubtimes: HashMap<PackageId, String>,
     /// "Unknown" metadata. This is a collection of extra, unrecognized data
     /// found in the `[metadata]` section of `Cargo.lock`, preserved for
     /// forwards compatibility.
@@ -95,6 +98,12 @@ pub enum ResolveVersion {
     /// Unstable. Will collect a certain amount of changes and then go.
     ///
     /// Changes made:
+    ///
+    /// * Checksums are prefixed with the algorithm used to compute them, for
+    ///   example `checksum = "sha256:..."`.
+    /// * Registry sources are recorded with their canonical URL.
+    /// * Packages record when they were published with `pubtime`, if the
+    ///   registry provides it.
     V5,
 }
 
@@ -119,6 +128,32 @@ impl ResolveVersion {
         ResolveVersion::V4
     }
 
+    /// The versions `cargo update --lockfile-version` can pick, oldest first.
+    ///
+    /// Lock files with an older version are migrated to the default version
+    /// whenever they are written, lock files with one of these are left at
+    /// that version.
+    pub const SELECTABLE: &'static [ResolveVersion] =
+        &[ResolveVersion::V3, ResolveVersion::V4, ResolveVersion::V5];
+
+    /// The `version = N` a lock file of this version starts with, if any.
+    pub fn lockfile_version(self) -> Option<u32> {
+        match self {
+            ResolveVersion::V5 => Some(5),
+            ResolveVersion::V4 => Some(4),
+            ResolveVersion::V3 => Some(3),
+            ResolveVersion::V2 | ResolveVersion::V1 => None,
+        }
+    }
+
+    /// The selectable version written as `version = n`, if any.
+    pub fn from_lockfile_version(n: u32) -> Option<ResolveVersion> {
+        ResolveVersion::SELECTABLE
+            .iter()
+            .copied()
+            .find(|version| version.lockfile_version() == Some(n))
+    }
+
     /// Gets the default lockfile version for the given Rust version.
     pub fn with_rust_version(rust_version: Option<&RustVersion>) -> Self {
         let Some(rust_version) = rust_version else {
@@ -160,6 +195,7 @@ impl Resolve {
         replacements: HashMap<PackageId, PackageId>,
         features: HashMap<PackageId, Vec<InternedString>>,
         checksums: HashMap<PackageId, Option<String>>,
+        pubtimes: HashMap<PackageId, String>,
         metadata: Metadata,
         unused_patches: Vec<PackageId>,
         version: ResolveVersion,
@@ -187,6 +223,7 @@ impl Resolve {
             replacements,
             features,
             checksums,
+            pubtimes,
             metadata,
             unused_patches,
             reverse_replacements,
@@ -299,6 +336,13 @@ unable to verify that `{0}` is the same as when the lockfile was generated
             }
         }
 
+        // Keep publish times the registry no longer reports.
+        for (id, pubtime) in previous.pubtimes.iter() {
+            if self.graph.contains(id) {
+                self.pubtimes.entry(*id).or_insert_with(|| pubtime.clone());
+            }
+        }
+
         // Be sure to just copy over any unknown metadata.
         self.metadata = previous.metadata.clone();
 
@@ -394,6 +438,10 @@ unable to verify that `{0}` is the same as when the lockfile was generated
         &self.checksums
     }
 
+    pub fn pubtimes(&self) -> &HashMap<PackageId, String> {
+        &self.pubtimes
+    }
+
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
     }
@@ -476,7 +524,7 @@ impl PartialEq for Resolve {
         compare! {
             // fields to compare
             graph replacements reverse_replacements features
-            checksums metadata unused_patches public_dependencies summaries
+            checksums pubtimes metadata unused_patches public_dependencies summaries
             |
             // fields to ignore
             version
@@ -21,6 +21,7 @@ pub struct VersionPreferences {
     try_to_use: HashSet<PackageId>,
     prefer_patch_deps: HashMap<InternedString, HashSet<Dependency>>,
     version_ordering: VersionOrdering,
+    direct_version_ordering: Option<VersionOrdering>,
     max_rust_version: Option<PartialVersion>,
 }
 
@@ -49,6 +50,16 @@ impl VersionPreferences {
         self.version_ordering = ordering;
     }
 
+    /// Override the ordering for direct dependencies of workspace members, see
+    /// `--direct-minimal-versions`.
+    pub fn direct_version_ordering(&mut self, ordering: Option<VersionOrdering>) {
+        self.direct_version_ordering = ordering;
+    }
+
+    pub(crate) fn direct_ordering(&self) -> Option<VersionOrdering> {
+        self.direct_version_ordering
+    }
+
     pub fn max_rust_version(&mut self, ver: Option<PartialVersion>) {
         self.max_rust_version = ver;
     }
@@ -22,6 +22,8 @@ pub struct Shell {
     /// printing. Used when a progress bar is currently displayed.
     needs_clear: bool,
     hostname: Option<String>,
+    /// How the progress of a build is reported.
+    status_format: StatusFormat,
 }
 
 impl fmt::Debug for Shell {
@@ -60,6 +62,7 @@ impl Shell {
             verbosity: Verbosity::Verbose,
             needs_clear: false,
             hostname: None,
+            status_format: StatusFormat::Human,
         }
     }
 
@@ -70,6 +73,7 @@ impl Shell {
             verbosity: Verbosity::Verbose,
             needs_clear: false,
             hostname: None,
+            status_format: StatusFormat::Human,
         }
     }
 
@@ -230,6 +234,16 @@ impl Shell {
         self.verbosity
     }
 
+    /// Updates how the progress of a build is reported.
+    pub fn set_status_format(&mut self, status_format: StatusFormat) {
+        self.status_format = status_format;
+    }
+
+    /// Gets how the progress of a build is reported.
+    pub fn status_format(&self) -> StatusFormat {
+        self.status_format
+    }
+
     /// Updates the color choice (always, never, or auto) from a string..
     pub fn set_color_choice(&mut self, color: Option<&str>) -> CargoResult<()> {
         if let ShellOut::Stream {
@@ -393,6 +407,23 @@ impl Shell {
         Ok(())
     }
 
+    /// Like [`Shell::print_json`], but writes `obj` to stdout as it gets
+    /// serialized instead of building the whole JSON in memory first.
+    ///
+    /// If `obj` fails to serialize, the JSON written so far is left truncated.
+    pub fn stream_json<T: serde::ser::Serialize>(&mut self, obj: &T) -> CargoResult<()> {
+        let mut out = std::io::BufWriter::new(self.out());
+        match serde_json::to_writer(&mut out, obj) {
+            Ok(()) => {}
+            // Don't fail due to a closed pipe.
+            Err(e) if e.is_io() => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+        drop(writeln!(out));
+        drop(out.flush());
+        Ok(())
+    }
+
     /// Prints the passed in [Message] to stderr
     pub fn print_message(&mut self, message: Message<'_>) -> std::io::Result<()> {
         let term_width = self
@@ -511,6 +542,15 @@ pub enum Verbosity {
     Quiet,
 }
 
+/// How the progress of a build is reported, set by `--status-format`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusFormat {
+    /// Status messages and a progress bar for humans.
+    Human,
+    /// Additionally, `build-status` JSON messages on stdout for tools.
+    Json,
+}
+
 /// Whether messages should use color output
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ColorChoice {
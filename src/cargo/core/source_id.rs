@@ -7,7 +7,6 @@ use crate::sources::{DirectorySource, CRATES_IO_DOMAIN, CRATES_IO_INDEX, CRATES_
 use crate::sources::{GitSource, PathSource, RegistrySource};
 use crate::util::interning::InternedString;
 use crate::util::{context, CanonicalUrl, CargoResult, GlobalContext, IntoUrl};
-use anyhow::Context as _;
 use serde::de;
 use serde::ser;
 use std::cmp::{self, Ordering};
@@ -54,6 +53,8 @@ struct SourceIdInner {
     kind: SourceKind,
     /// For example, the exact Git revision of the specified branch for a Git Source.
     precise: Option<Precise>,
+    /// What to fetch and check out of a Git source, if narrowed down.
+    git_checkout: Option<GitCheckoutOptions>,
     /// Name of the remote registry.
     ///
     /// WARNING: this is not always set when the name is not known,
@@ -64,26 +65,49 @@ struct SourceIdInner {
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 enum Precise {
     Locked,
-    Updated {
-        name: InternedString,
-        from: semver::Version,
-        to: semver::Version,
-    },
+    /// The registry packages `cargo update --precise` updates.
+    Updated(Vec<PreciseUpdate>),
     GitUrlFragment(String),
 }
 
+/// A registry package updated by `cargo update --precise`, from its version
+/// in the lock file to the requested one.
+#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+struct PreciseUpdate {
+    name: InternedString,
+    from: semver::Version,
+    to: semver::Version,
+}
+
 impl fmt::Display for Precise {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Precise::Locked => "locked".fmt(f),
-            Precise::Updated { name, from, to } => {
-                write!(f, "{name}={from}->{to}")
+            Precise::Updated(updates) => {
+                for (i, PreciseUpdate { name, from, to }) in updates.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{name}={from}->{to}")?;
+                }
+                Ok(())
             }
             Precise::GitUrlFragment(s) => s.fmt(f),
         }
     }
 }
 
+/// Narrows down what is fetched and checked out of a Git source, as set by the
+/// `shallow` and `paths` keys of a git dependency.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GitCheckoutOptions {
+    /// Fetches only the commit being checked out, without its history.
+    pub shallow: bool,
+    /// Checks out only these directories, along with the files at the root
+    /// of the repository. Empty means the whole repository.
+    pub paths: Vec<String>,
+}
+
 /// Where the remote source key is defined.
 ///
 /// The purpose of this is to provide better diagnostics for different sources of keys.
@@ -113,6 +137,7 @@ impl SourceId {
             canonical_url: CanonicalUrl::new(&url)?,
             url,
             precise: None,
+            git_checkout: None,
             registry_key: key,
         });
         Ok(source_id)
@@ -159,10 +184,13 @@ impl SourceId {
             "git" => {
                 let mut url = url.into_url()?;
                 let reference = GitReference::from_query(url.query_pairs());
+                let checkout = GitCheckoutOptions::from_query(url.query_pairs());
                 let precise = url.fragment().map(|s| s.to_owned());
                 url.set_fragment(None);
                 url.set_query(None);
-                Ok(SourceId::for_git(&url, reference)?.with_git_precise(precise))
+                Ok(SourceId::for_git(&url, reference)?
+                    .with_git_checkout(checkout)
+                    .with_git_precise(precise))
             }
             "registry" => {
                 let url = url.into_url()?;
@@ -334,6 +362,15 @@ impl SourceId {
         self.inner.kind == SourceKind::Path
     }
 
+    /// The source as `cargo metadata` outputs it, `None` for path sources.
+    pub fn serialized(self) -> Option<String> {
+        if self.is_path() {
+            None
+        } else {
+            Some(self.as_url().to_string())
+        }
+    }
+
     /// Returns the local path if this is a path dependency.
     pub fn local_path(self) -> Option<PathBuf> {
         if self.inner.kind != SourceKind::Path {
@@ -446,17 +483,23 @@ impl SourceId {
     }
 
     /// Check if the precise data field stores information for this `name`
-    /// from a call to [SourceId::with_precise_registry_version].
+    /// from calls to [SourceId::with_precise_registry_version].
     ///
-    /// If so return the version currently in the lock file and the version to be updated to.
-    pub fn precise_registry_version(
+    /// Returns the versions currently in the lock file along with the versions
+    /// they are to be updated to, as several versions of a package can be
+    /// locked.
+    pub fn precise_registry_versions(
         self,
         pkg: &str,
-    ) -> Option<(&semver::Version, &semver::Version)> {
-        match &self.inner.precise {
-            Some(Precise::Updated { name, from, to }) if name == pkg => Some((from, to)),
-            _ => None,
-        }
+    ) -> impl Iterator<Item = (&'static semver::Version, &'static semver::Version)> + '_ {
+        let updates = match &self.inner.precise {
+            Some(Precise::Updated(updates)) => updates.as_slice(),
+            _ => &[],
+        };
+        updates
+            .iter()
+            .filter(move |update| update.name == pkg)
+            .map(|update| (&update.from, &update.to))
     }
 
     pub fn precise_git_fragment(self) -> Option<&'static str> {
@@ -466,6 +509,22 @@ impl SourceId {
         }
     }
 
+    /// Gets what to fetch and check out of this Git source, if narrowed down
+    /// with [`SourceId::with_git_checkout`].
+    pub fn git_checkout(self) -> Option<&'static GitCheckoutOptions> {
+        self.inner.git_checkout.as_ref()
+    }
+
+    /// Creates a new `SourceId` from this Git source that only fetches and
+    /// checks out what `checkout` asks for.
+    pub fn with_git_checkout(self, checkout: GitCheckoutOptions) -> SourceId {
+        let git_checkout = (checkout != GitCheckoutOptions::default()).then_some(checkout);
+        SourceId::wrap(SourceIdInner {
+            git_checkout,
+            ..(*self.inner).clone()
+        })
+    }
+
     /// Creates a new `SourceId` from this source with the given `precise`.
     pub fn with_git_precise(self, fragment: Option<String>) -> SourceId {
         SourceId::wrap(SourceIdInner {
@@ -503,24 +562,30 @@ impl SourceId {
     /// On a registry dependency we also need to keep track of the package that
     /// should be updated and even which of the versions should be updated.
     /// All of this gets encoded in the precise field using this method.
-    /// The data can be read with [SourceId::precise_registry_version]
+    /// The data can be read with [SourceId::precise_registry_versions]
+    ///
+    /// Updates recorded by previous calls are kept, so that several packages
+    /// of the registry can be updated at once.
     pub fn with_precise_registry_version(
         self,
         name: InternedString,
         version: semver::Version,
-        precise: &str,
-    ) -> CargoResult<SourceId> {
-        let precise = semver::Version::parse(precise)
-            .with_context(|| format!("invalid version format for precise version `{precise}`"))?;
-
-        Ok(SourceId::wrap(SourceIdInner {
-            precise: Some(Precise::Updated {
-                name,
-                from: version,
-                to: precise,
-            }),
+        precise: semver::Version,
+    ) -> SourceId {
+        let mut updates = match &self.inner.precise {
+            Some(Precise::Updated(updates)) => updates.clone(),
+            _ => Vec::new(),
+        };
+        updates.retain(|update| update.name != name || update.from != version);
+        updates.push(PreciseUpdate {
+            name,
+            from: version,
+            to: precise,
+        });
+        SourceId::wrap(SourceIdInner {
+            precise: Some(Precise::Updated(updates)),
             ..(*self.inner).clone()
-        }))
+        })
     }
 
     /// Returns `true` if the remote registry is the standard <https://crates.io>.
@@ -595,9 +660,11 @@ impl Ord for SourceId {
         // If the `kind` and the `url` are equal, then for git sources we also
         // ensure that the canonical urls are equal.
         match (&self.inner.kind, &other.inner.kind) {
-            (SourceKind::Git(_), SourceKind::Git(_)) => {
-                self.inner.canonical_url.cmp(&other.inner.canonical_url)
-            }
+            (SourceKind::Git(_), SourceKind::Git(_)) => self
+                .inner
+                .canonical_url
+                .cmp(&other.inner.canonical_url)
+                .then_with(|| self.inner.git_checkout.cmp(&other.inner.git_checkout)),
             _ => self.inner.url.cmp(&other.inner.url),
         }
     }
@@ -648,9 +715,7 @@ impl fmt::Display for SourceId {
                 // TODO(-Znext-lockfile-bump): set it to true when the default is
                 // lockfile v4, because we want Source ID serialization to be
                 // consistent with lockfile.
-                if let Some(pretty) = reference.pretty_ref(false) {
-                    write!(f, "?{}", pretty)?;
-                }
+                write_git_query(f, reference, self.inner.git_checkout.as_ref(), false)?;
 
                 if let Some(s) = &self.inner.precise {
                     let s = s.to_string();
@@ -676,7 +741,12 @@ impl Hash for SourceId {
     fn hash<S: hash::Hasher>(&self, into: &mut S) {
         self.inner.kind.hash(into);
         match self.inner.kind {
-            SourceKind::Git(_) => self.inner.canonical_url.hash(into),
+            SourceKind::Git(_) => {
+                self.inner.canonical_url.hash(into);
+                if let Some(checkout) = &self.inner.git_checkout {
+                    checkout.hash(into);
+                }
+            }
             _ => self.inner.url.as_str().hash(into),
         }
     }
@@ -693,6 +763,7 @@ impl Hash for SourceIdInner {
         self.kind.hash(into);
         self.precise.hash(into);
         self.canonical_url.hash(into);
+        self.git_checkout.hash(into);
     }
 }
 
@@ -702,6 +773,7 @@ impl PartialEq for SourceIdInner {
         self.kind == other.kind
             && self.precise == other.precise
             && self.canonical_url == other.canonical_url
+            && self.git_checkout == other.git_checkout
     }
 }
 
@@ -720,12 +792,11 @@ impl<'a> fmt::Display for SourceIdAsUrl<'a> {
         if let SourceIdInner {
             kind: SourceKind::Git(ref reference),
             ref precise,
+            ref git_checkout,
             ..
         } = *self.inner
         {
-            if let Some(pretty) = reference.pretty_ref(self.encoded) {
-                write!(f, "?{}", pretty)?;
-            }
+            write_git_query(f, reference, git_checkout.as_ref(), self.encoded)?;
             if let Some(precise) = precise.as_ref() {
                 write!(f, "#{}", precise)?;
             }
@@ -734,6 +805,54 @@ impl<'a> fmt::Display for SourceIdAsUrl<'a> {
     }
 }
 
+/// Writes the query of a Git source URL, like `?branch=main&shallow=true`.
+fn write_git_query(
+    f: &mut Formatter<'_>,
+    reference: &GitReference,
+    checkout: Option<&GitCheckoutOptions>,
+    encoded: bool,
+) -> fmt::Result {
+    let mut sep = '?';
+    if let Some(pretty) = reference.pretty_ref(encoded) {
+        write!(f, "{sep}{pretty}")?;
+        sep = '&';
+    }
+    let Some(checkout) = checkout else {
+        return Ok(());
+    };
+    if checkout.shallow {
+        write!(f, "{sep}shallow=true")?;
+        sep = '&';
+    }
+    for path in &checkout.paths {
+        write!(f, "{sep}path=")?;
+        if encoded {
+            for path in url::form_urlencoded::byte_serialize(path.as_bytes()) {
+                write!(f, "{path}")?;
+            }
+        } else {
+            write!(f, "{path}")?;
+        }
+        sep = '&';
+    }
+    Ok(())
+}
+
+impl GitCheckoutOptions {
+    /// Parses the options from the query pairs of a Git source URL.
+    fn from_query(query_pairs: impl Iterator<Item = (impl AsRef<str>, impl AsRef<str>)>) -> Self {
+        let mut checkout = GitCheckoutOptions::default();
+        for (k, v) in query_pairs {
+            match k.as_ref() {
+                "shallow" => checkout.shallow = v.as_ref() == "true",
+                "path" => checkout.paths.push(v.as_ref().to_owned()),
+                _ => {}
+            }
+        }
+        checkout
+    }
+}
+
 impl KeyOf {
     /// Gets the underlying key.
     fn key(&self) -> &str {
@@ -753,7 +872,7 @@ impl KeyOf {
 
 #[cfg(test)]
 mod tests {
-    use super::{GitReference, SourceId, SourceKind};
+    use super::{GitCheckoutOptions, GitReference, SourceId, SourceKind};
     use crate::util::{GlobalContext, IntoUrl};
 
     #[test]
@@ -882,6 +1001,30 @@ mod tests {
             "git+https://host/path?branch=*-._%2B20%2530+Z%2Fz%23foo%3Dbar%26zap%5B%5D%3Fto%5C%28%29%27%22"
         );
     }
+
+    #[test]
+    fn git_checkout_roundtrip() {
+        let base = "https://host/path".into_url().unwrap();
+        let checkout = GitCheckoutOptions {
+            shallow: true,
+            paths: vec!["crates/a".to_string(), "crates/b".to_string()],
+        };
+        let s1 = SourceId::for_git(&base, GitReference::Tag("v1".to_string()))
+            .unwrap()
+            .with_git_checkout(checkout.clone());
+        let ser1 = format!("{}", s1.as_encoded_url());
+        assert_eq!(
+            ser1,
+            "git+https://host/path?tag=v1&shallow=true&path=crates%2Fa&path=crates%2Fb"
+        );
+        let s2 = SourceId::from_url(&ser1).expect("Failed to deserialize");
+        assert_eq!(s1, s2, "SourceId doesn't round-trip");
+        assert_eq!(s2.git_checkout(), Some(&checkout));
+
+        let full = SourceId::for_git(&base, GitReference::Tag("v1".to_string())).unwrap();
+        assert_ne!(s1, full);
+        assert_eq!(full.with_git_checkout(GitCheckoutOptions::default()), full);
+    }
 }
 
 /// Check if `url` equals to the overridden crates.io URL.
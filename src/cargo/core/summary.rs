@@ -26,6 +26,7 @@ struct Inner {
     dependencies: Vec<Dependency>,
     features: Rc<FeatureMap>,
     checksum: Option<String>,
+    pubtime: Option<String>,
     links: Option<InternedString>,
     rust_version: Option<RustVersion>,
 }
@@ -58,6 +59,7 @@ impl Summary {
                 dependencies,
                 features: Rc::new(feature_map),
                 checksum: None,
+                pubtime: None,
                 links: links.map(|l| l.into()),
                 rust_version,
             }),
@@ -86,6 +88,11 @@ impl Summary {
     pub fn checksum(&self) -> Option<&str> {
         self.inner.checksum.as_deref()
     }
+
+    /// When this version was published, as reported by the registry.
+    pub fn pubtime(&self) -> Option<&str> {
+        self.inner.pubtime.as_deref()
+    }
     pub fn links(&self) -> Option<InternedString> {
         self.inner.links
     }
@@ -103,6 +110,10 @@ impl Summary {
         Rc::make_mut(&mut self.inner).checksum = Some(cksum);
     }
 
+    pub fn set_pubtime(&mut self, pubtime: String) {
+        Rc::make_mut(&mut self.inner).pubtime = Some(pubtime);
+    }
+
     pub fn map_dependencies<F>(self, mut f: F) -> Summary
     where
         F: FnMut(Dependency) -> Dependency,
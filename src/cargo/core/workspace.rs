@@ -11,6 +11,7 @@ use tracing::debug;
 use url::Url;
 
 use crate::core::compiler::Unit;
+use crate::core::dependency::DepKind;
 use crate::core::features::Features;
 use crate::core::registry::PackageRegistry;
 use crate::core::resolver::features::CliFeatures;
@@ -27,10 +28,11 @@ use crate::util::interning::InternedString;
 use crate::util::lints::{
     analyze_cargo_lints_table, check_im_a_teapot, check_implicit_features, unused_dependencies,
 };
-use crate::util::toml::{read_manifest, InheritableFields};
+use crate::util::toml::{read_manifest, script_target_dir, InheritableFields};
 use crate::util::{
-    context::CargoResolverConfig, context::CargoResolverPrecedence, context::ConfigRelativePath,
-    Filesystem, GlobalContext, IntoUrl,
+    context::CargoResolverConfig, context::CargoResolverFeatureUnification,
+    context::CargoResolverPrecedence, context::CargoResolverYanked, context::ConfigRelativePath,
+    context::SourcePolicyConfig, Filesystem, GlobalContext, IntoUrl,
 };
 use cargo_util::paths;
 use cargo_util::paths::normalize_path;
@@ -73,6 +75,10 @@ pub struct Workspace<'gctx> {
     members: Vec<PathBuf>,
     member_ids: HashSet<PackageId>,
 
+    // Root manifests of nested workspaces listed in `workspace.members`,
+    // with `-Znested-workspaces`. Their members join this workspace as a group.
+    member_groups: Vec<PathBuf>,
+
     // The subset of `members` that are used by the
     // `build`, `check`, `test`, and `bench` subcommands
     // when no package is selected with `--package` / `-p` and `--workspace`
@@ -102,14 +108,34 @@ pub struct Workspace<'gctx> {
     // If `true`, then the resolver will ignore any existing `Cargo.lock`
     // file. This is set for `cargo install` without `--locked`.
     ignore_lock: bool,
+    /// Packages the resolver may update even though they are pinned by the
+    /// existing `Cargo.lock`, see `install.float`.
+    floating_packages: Vec<PackageIdSpec>,
 
     /// The resolver behavior specified with the `resolver` field.
     resolve_behavior: ResolveBehavior,
     resolve_honors_rust_version: bool,
+    /// How to treat yanked packages pinned by the lock file, see `resolver.yanked`.
+    resolve_yanked: CargoResolverYanked,
+    /// Which packages features are unified across, see `resolver.feature-unification`.
+    resolve_feature_unification: CargoResolverFeatureUnification,
+    /// Set by `--minimal-versions`, see [`Workspace::resolve_minimal_versions`].
+    resolve_minimal_versions: bool,
+    /// Set by `--direct-minimal-versions`, see
+    /// [`Workspace::resolve_direct_minimal_versions`].
+    resolve_direct_minimal_versions: bool,
 
     /// Workspace-level custom metadata
     custom_metadata: Option<toml::Value>,
 
+    /// Named feature sets from `[workspace.features]`, usable on the
+    /// command-line as `--features preset:<name>`.
+    feature_presets: BTreeMap<String, Vec<String>>,
+
+    /// Whether members other than the root build into their own directory
+    /// inside the target directory, from `workspace.split-target-dirs`.
+    split_target_dirs: bool,
+
     /// Local overlay configuration. See [`crate::sources::overlay`].
     local_overlays: HashMap<SourceId, PathBuf>,
 }
@@ -128,6 +154,15 @@ pub enum MaybePackage {
     Virtual(VirtualManifest),
 }
 
+/// What [`Workspace::check_source_policy`] is checking for.
+#[derive(Debug, Clone, Copy)]
+pub enum SourcePolicyScope<'a> {
+    /// A build with the profile of this name.
+    Profile(&'a str),
+    /// Packaging this package, for `cargo package` or `cargo publish`.
+    Package(&'a Package),
+}
+
 /// Configuration of a workspace in a manifest.
 #[derive(Debug, Clone)]
 pub enum WorkspaceConfig {
@@ -189,6 +224,8 @@ pub struct WorkspaceRootConfig {
     exclude: Vec<String>,
     inheritable_fields: InheritableFields,
     custom_metadata: Option<toml::Value>,
+    feature_presets: BTreeMap<String, Vec<String>>,
+    split_target_dirs: bool,
 }
 
 impl<'gctx> Workspace<'gctx> {
@@ -211,9 +248,11 @@ impl<'gctx> Workspace<'gctx> {
             ws.root_manifest = ws.find_root(manifest_path)?;
         }
 
-        ws.custom_metadata = ws
-            .load_workspace_config()?
-            .and_then(|cfg| cfg.custom_metadata);
+        if let Some(cfg) = ws.load_workspace_config()? {
+            ws.custom_metadata = cfg.custom_metadata;
+            ws.feature_presets = cfg.feature_presets;
+            ws.split_target_dirs = cfg.split_target_dirs && gctx.cli_unstable().split_target_dirs;
+        }
         ws.find_members()?;
         ws.set_resolve_behavior()?;
         ws.validate()?;
@@ -232,14 +271,22 @@ impl<'gctx> Workspace<'gctx> {
             target_dir: None,
             members: Vec::new(),
             member_ids: HashSet::new(),
+            member_groups: Vec::new(),
             default_members: Vec::new(),
             is_ephemeral: false,
             require_optional_deps: true,
             loaded_packages: RefCell::new(HashMap::new()),
             ignore_lock: false,
+            floating_packages: Vec::new(),
             resolve_behavior: ResolveBehavior::V1,
             resolve_honors_rust_version: false,
+            resolve_yanked: CargoResolverYanked::default(),
+            resolve_feature_unification: CargoResolverFeatureUnification::default(),
+            resolve_minimal_versions: false,
+            resolve_direct_minimal_versions: false,
             custom_metadata: None,
+            feature_presets: BTreeMap::new(),
+            split_target_dirs: false,
             local_overlays: HashMap::new(),
         }
     }
@@ -320,22 +367,42 @@ impl<'gctx> Workspace<'gctx> {
         }
         match self.gctx().get::<CargoResolverConfig>("resolver") {
             Ok(CargoResolverConfig {
-                something_like_precedence: Some(precedence),
+                something_like_precedence,
+                yanked,
+                feature_unification,
             }) => {
-                if self.gctx().cli_unstable().msrv_policy {
-                    self.resolve_honors_rust_version =
-                        precedence == CargoResolverPrecedence::SomethingLikeRustVersion;
-                } else {
-                    self.gctx()
-                        .shell()
-                        .warn("ignoring `resolver` config table without `-Zmsrv-policy`")?;
+                if let Some(precedence) = something_like_precedence {
+                    if self.gctx().cli_unstable().msrv_policy {
+                        self.resolve_honors_rust_version =
+                            precedence == CargoResolverPrecedence::SomethingLikeRustVersion;
+                    } else {
+                        self.gctx()
+                            .shell()
+                            .warn("ignoring `resolver` config table without `-Zmsrv-policy`")?;
+                    }
+                }
+                if let Some(yanked) = yanked {
+                    if self.gctx().cli_unstable().yanked_policy {
+                        self.resolve_yanked = yanked;
+                    } else {
+                        self.gctx()
+                            .shell()
+                            .warn("ignoring `resolver.yanked` config without `-Zyanked-policy`")?;
+                    }
+                }
+                if let Some(feature_unification) = feature_unification {
+                    if self.gctx().cli_unstable().feature_unification {
+                        self.resolve_feature_unification = feature_unification;
+                    } else {
+                        self.gctx().shell().warn(
+                            "ignoring `resolver.feature-unification` config without `-Zfeature-unification`",
+                        )?;
+                    }
                 }
             }
-            Ok(CargoResolverConfig {
-                something_like_precedence: None,
-            }) => {}
             Err(err) => {
-                if self.gctx().cli_unstable().msrv_policy {
+                let unstable = self.gctx().cli_unstable();
+                if unstable.msrv_policy || unstable.yanked_policy || unstable.feature_unification {
                     return Err(err);
                 } else {
                     self.gctx()
@@ -438,15 +505,28 @@ impl<'gctx> Workspace<'gctx> {
             .unwrap_or_else(|| self.default_target_dir())
     }
 
+    /// With `workspace.split-target-dirs`, the directory the final artifacts
+    /// of the member `pkg_id` are uplifted to. Everything else, including the
+    /// dependencies shared by all members, stays in the target directory.
+    ///
+    /// Returns `None` when the target directory is configured explicitly.
+    pub fn member_target_dir(&self, pkg_id: PackageId) -> Option<Filesystem> {
+        if !self.split_target_dirs
+            || self.target_dir.is_some()
+            || !self.member_ids.contains(&pkg_id)
+        {
+            return None;
+        }
+        Some(
+            self.default_target_dir()
+                .join("members")
+                .join(pkg_id.name()),
+        )
+    }
+
     fn default_target_dir(&self) -> Filesystem {
         if self.root_maybe().is_embedded() {
-            let hash = crate::util::hex::short_hash(&self.root_manifest().to_string_lossy());
-            let mut rel_path = PathBuf::new();
-            rel_path.push("target");
-            rel_path.push(&hash[0..2]);
-            rel_path.push(&hash[2..]);
-
-            self.gctx().home().join(rel_path)
+            script_target_dir(self.root_manifest(), self.gctx())
         } else {
             Filesystem::new(self.root().join("target"))
         }
@@ -555,6 +635,144 @@ impl<'gctx> Workspace<'gctx> {
         Ok(combined)
     }
 
+    /// Fails if `[source-policy]` forbids git or path dependencies for
+    /// `scope` and some are used, listing where they are declared.
+    ///
+    /// A build checks the dependencies of all members as well as `[patch]`,
+    /// packaging only checks the package itself. Dev-dependencies are never
+    /// checked, since they aren't part of what gets released.
+    pub fn check_source_policy(&self, scope: SourcePolicyScope<'_>) -> CargoResult<()> {
+        if !self.gctx.cli_unstable().source_policy {
+            return Ok(());
+        }
+        let Some(config) = self
+            .gctx
+            .get::<Option<SourcePolicyConfig>>("source-policy")?
+        else {
+            return Ok(());
+        };
+        let enforced = match scope {
+            SourcePolicyScope::Profile(profile) => config
+                .profiles
+                .as_ref()
+                .is_some_and(|profiles| profiles.iter().any(|p| p == profile)),
+            SourcePolicyScope::Package(_) => config.package == Some(true),
+        };
+        if !enforced {
+            return Ok(());
+        }
+        let (mut forbid_git, mut forbid_path) = (config.forbid.is_none(), config.forbid.is_none());
+        for kind in config.forbid.iter().flatten() {
+            match kind.as_str() {
+                "git" => forbid_git = true,
+                "path" => forbid_path = true,
+                _ => bail!(
+                    "unknown kind of source `{kind}` in `source-policy.forbid`, \
+                     expected `git` or `path`"
+                ),
+            }
+        }
+
+        // Why a dependency is forbidden, if it is.
+        let forbidden = |dep: &Dependency| {
+            let source_id = dep.source_id();
+            if source_id.is_git() && forbid_git {
+                return Some("a git dependency".to_string());
+            }
+            if source_id.is_path() && forbid_path {
+                let path = source_id.url().to_file_path().ok()?;
+                if !self.members().any(|m| m.root() == path) {
+                    return Some(format!(
+                        "a path dependency on `{}`, outside the workspace",
+                        path.display()
+                    ));
+                }
+            }
+            None
+        };
+
+        let mut offenders = Vec::new();
+        let pkgs: Vec<&Package> = match scope {
+            SourcePolicyScope::Profile(_) => self.members().collect(),
+            SourcePolicyScope::Package(pkg) => vec![pkg],
+        };
+        for pkg in pkgs {
+            for dep in pkg.dependencies() {
+                if dep.kind() == DepKind::Development {
+                    continue;
+                }
+                let Some(why) = forbidden(dep) else {
+                    continue;
+                };
+                let table = match dep.kind() {
+                    DepKind::Build => "build-dependencies",
+                    _ => "dependencies",
+                };
+                let table = match dep.platform() {
+                    Some(platform) => format!("target.'{platform}'.{table}"),
+                    None => table.to_string(),
+                };
+                offenders.push(format!(
+                    "`{}` is {why}, in `[{table}]` of `{}`",
+                    dep.name_in_toml(),
+                    pkg.manifest_path().display()
+                ));
+            }
+        }
+        if let SourcePolicyScope::Profile(_) = scope {
+            let from_manifest = match self.root_maybe() {
+                MaybePackage::Package(p) => p.manifest().patch(),
+                MaybePackage::Virtual(vm) => vm.patch(),
+            };
+            let root_manifest = self.root_manifest().display().to_string();
+            let from_config = self.config_patch()?;
+            for (patch, location) in [
+                (from_manifest, format!("of `{root_manifest}`")),
+                (&from_config, "of the `patch` config".to_string()),
+            ] {
+                for (url, deps) in patch.iter().sorted_by_key(|(url, _)| url.as_str()) {
+                    let table = if url.as_str() == CRATES_IO_INDEX {
+                        CRATES_IO_REGISTRY.to_string()
+                    } else {
+                        format!("'{url}'")
+                    };
+                    for dep in deps {
+                        if let Some(why) = forbidden(dep) {
+                            offenders.push(format!(
+                                "`{}` is {why}, in `[patch.{table}]` {location}",
+                                dep.name_in_toml()
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        if offenders.is_empty() {
+            return Ok(());
+        }
+
+        let kinds = match (forbid_git, forbid_path) {
+            (true, false) => "git dependencies",
+            (false, true) => "path dependencies",
+            _ => "git and path dependencies",
+        };
+        let mut msg = match scope {
+            SourcePolicyScope::Profile(profile) => format!(
+                "the `source-policy` config forbids {kinds} in builds with the `{profile}` profile:"
+            ),
+            SourcePolicyScope::Package(pkg) => format!(
+                "the `source-policy` config forbids {kinds} when packaging `{}`:",
+                pkg.package_id()
+            ),
+        };
+        for offender in offenders {
+            msg.push_str("\n  ");
+            msg.push_str(&offender);
+        }
+        msg.push_str("\n\nhelp: depend on versions from a registry instead");
+        bail!(msg)
+    }
+
     /// Returns an iterator over all packages in this workspace
     pub fn members(&self) -> impl Iterator<Item = &Package> {
         let packages = &self.packages;
@@ -647,12 +865,31 @@ impl<'gctx> Workspace<'gctx> {
         self
     }
 
+    /// Packages that are not kept at their version in `Cargo.lock` when
+    /// resolving, but free to update like with `cargo update --package`.
+    pub fn floating_packages(&self) -> &[PackageIdSpec] {
+        &self.floating_packages
+    }
+
+    pub fn set_floating_packages(&mut self, specs: Vec<PackageIdSpec>) -> &mut Workspace<'gctx> {
+        self.floating_packages = specs;
+        self
+    }
+
     /// Get the lowest-common denominator `package.rust-version` within the workspace, if specified
     /// anywhere
     pub fn rust_version(&self) -> Option<&RustVersion> {
         self.members().filter_map(|pkg| pkg.rust_version()).min()
     }
 
+    /// Get `workspace.package.rust-version` from the workspace root, if specified
+    pub fn workspace_rust_version(&self) -> Option<&RustVersion> {
+        self.root_maybe()
+            .workspace_config()
+            .inheritable()?
+            .package_rust_version()
+    }
+
     pub fn set_resolve_honors_rust_version(&mut self, honor_rust_version: Option<bool>) {
         if let Some(honor_rust_version) = honor_rust_version {
             self.resolve_honors_rust_version = honor_rust_version;
@@ -663,6 +900,37 @@ impl<'gctx> Workspace<'gctx> {
         self.resolve_honors_rust_version
     }
 
+    pub fn resolve_yanked(&self) -> CargoResolverYanked {
+        self.resolve_yanked
+    }
+
+    pub fn resolve_feature_unification(&self) -> CargoResolverFeatureUnification {
+        self.resolve_feature_unification
+    }
+
+    pub fn set_resolve_minimal_versions(&mut self, minimal_versions: bool) {
+        self.resolve_minimal_versions = minimal_versions;
+    }
+
+    /// Whether the resolver should prefer the lowest compatible version of every dependency.
+    ///
+    /// This is enabled by either `--minimal-versions` or `-Zminimal-versions`.
+    pub fn resolve_minimal_versions(&self) -> bool {
+        self.resolve_minimal_versions || self.gctx.cli_unstable().minimal_versions
+    }
+
+    pub fn set_resolve_direct_minimal_versions(&mut self, direct_minimal_versions: bool) {
+        self.resolve_direct_minimal_versions = direct_minimal_versions;
+    }
+
+    /// Whether the resolver should prefer the lowest compatible version of the direct
+    /// dependencies of workspace members, leaving indirect dependencies at their latest.
+    ///
+    /// This is enabled by either `--direct-minimal-versions` or `-Zdirect-minimal-versions`.
+    pub fn resolve_direct_minimal_versions(&self) -> bool {
+        self.resolve_direct_minimal_versions || self.gctx.cli_unstable().direct_minimal_versions
+    }
+
     pub fn custom_metadata(&self) -> Option<&toml::Value> {
         self.custom_metadata.as_ref()
     }
@@ -738,11 +1006,21 @@ impl<'gctx> Workspace<'gctx> {
         // self.root_manifest must be Some to have retrieved workspace_config
         let root_manifest_path = self.root_manifest.clone().unwrap();
 
-        let members_paths =
-            workspace_config.members_paths(workspace_config.members.as_ref().unwrap_or(&vec![]))?;
+        let members = workspace_config.members.as_deref().unwrap_or_default();
+        if let Some(pattern) = members.iter().find(|member| {
+            member.starts_with('!') && !workspace_config.root_dir.join(member).is_dir()
+        }) {
+            self.gctx.shell().warn(format!(
+                "`{pattern}` in `workspace.members` is read as a path, \
+                 exclusion patterns are only supported in `workspace.default-members`\n\
+                 \n\
+                 help: use `workspace.exclude` to leave packages out of the workspace"
+            ))?;
+        }
+        let members_paths = workspace_config.members_paths(members)?;
         let default_members_paths = if root_manifest_path == self.current_manifest {
             if let Some(ref default) = workspace_config.default_members {
-                Some(workspace_config.members_paths(default)?)
+                Some(workspace_config.default_members_paths(default, self.gctx)?)
             } else {
                 None
             }
@@ -750,8 +1028,34 @@ impl<'gctx> Workspace<'gctx> {
             None
         };
 
+        // Register nested workspaces first so path dependencies between their
+        // members are followed regardless of the order of `members`.
+        let mut member_manifests = Vec::new();
         for path in &members_paths {
-            self.find_path_deps(&path.join("Cargo.toml"), &root_manifest_path, false)
+            let manifest_path = path.join("Cargo.toml");
+            if self.is_nested_workspace(&manifest_path)? {
+                let group_root = self.find_root(&manifest_path)?.unwrap();
+                debug!("find_members - nested workspace {}", group_root.display());
+                self.member_groups.push(group_root.clone());
+                if group_root == paths::normalize_path(&manifest_path) {
+                    if let WorkspaceConfig::Root(ref group_config) =
+                        *self.packages.load(&group_root)?.workspace_config()
+                    {
+                        let group_members = group_config.members.as_deref().unwrap_or_default();
+                        member_manifests.extend(
+                            group_config
+                                .members_paths(group_members)?
+                                .into_iter()
+                                .map(|path| (path.join("Cargo.toml"), path)),
+                        );
+                    }
+                }
+            }
+            member_manifests.push((manifest_path, path.clone()));
+        }
+
+        for (manifest_path, path) in &member_manifests {
+            self.find_path_deps(manifest_path, &root_manifest_path, false)
                 .with_context(|| {
                     format!(
                         "failed to load manifest for workspace member `{}`\n\
@@ -764,8 +1068,23 @@ impl<'gctx> Workspace<'gctx> {
 
         self.find_path_deps(&root_manifest_path, &root_manifest_path, false)?;
 
-        if let Some(default) = default_members_paths {
+        if let Some((default, excluded)) = default_members_paths {
+            // A list of only exclusions starts from every member.
+            let default = if default.is_empty() && !excluded.is_empty() {
+                self.members
+                    .iter()
+                    .map(|manifest_path| manifest_path.parent().unwrap().to_path_buf())
+                    .collect()
+            } else {
+                default
+            };
             for path in default {
+                if excluded
+                    .iter()
+                    .any(|pattern| pattern.matches_path(&paths::normalize_path(&path)))
+                {
+                    continue;
+                }
                 let normalized_path = paths::normalize_path(&path);
                 let manifest_path = normalized_path.join("Cargo.toml");
                 if !self.members.contains(&manifest_path) {
@@ -820,6 +1139,11 @@ impl<'gctx> Workspace<'gctx> {
             // don't add it, or any of its dependencies, as a members.
             return Ok(());
         }
+        if is_path_dep && self.is_nested_workspace(&manifest_path)? {
+            // Packages of a nested workspace belong to that workspace, so they
+            // are only regular path dependencies here.
+            return Ok(());
+        }
 
         if let WorkspaceConfig::Root(ref root_config) =
             *self.packages.load(root_manifest)?.workspace_config()
@@ -854,6 +1178,18 @@ impl<'gctx> Workspace<'gctx> {
         Ok(())
     }
 
+    /// Whether `manifest_path` belongs to another workspace nested inside
+    /// this one, with `-Znested-workspaces`, that isn't one of its member groups.
+    fn is_nested_workspace(&mut self, manifest_path: &Path) -> CargoResult<bool> {
+        if !self.gctx.cli_unstable().nested_workspaces || !manifest_path.exists() {
+            return Ok(false);
+        }
+        let root = self.find_root(manifest_path)?;
+        Ok(root.as_ref().is_some_and(|root| {
+            Some(root) != self.root_manifest.as_ref() && !self.member_groups.contains(root)
+        }))
+    }
+
     /// Returns the unstable nightly-only features enabled via `cargo-features` in the manifest.
     pub fn unstable_features(&self) -> &Features {
         match self.root_maybe() {
@@ -887,6 +1223,8 @@ impl<'gctx> Workspace<'gctx> {
     /// 2. All workspace members agree on this one root as the root.
     /// 3. The current crate is a member of this workspace.
     fn validate(&mut self) -> CargoResult<()> {
+        self.validate_inherited_patches()?;
+
         // The rest of the checks require a VirtualManifest or multiple members.
         if self.root_manifest.is_none() {
             return Ok(());
@@ -899,6 +1237,48 @@ impl<'gctx> Workspace<'gctx> {
         self.validate_manifest()
     }
 
+    /// Checks `patch.workspace = true` and `replace.workspace = true`, which
+    /// let a member declare that the root's tables apply to it instead of
+    /// carrying its own (ignored) copies.
+    ///
+    /// Entries next to `workspace = true` are rejected when parsing the
+    /// manifest already.
+    fn validate_inherited_patches(&self) -> CargoResult<()> {
+        let root_manifest = self.root_manifest();
+        let mut manifests: Vec<(&Path, &manifest::TomlManifest)> = self
+            .members()
+            .map(|pkg| (pkg.manifest_path(), pkg.manifest().resolved_toml()))
+            .collect();
+        if let MaybePackage::Virtual(vm) = self.root_maybe() {
+            manifests.push((root_manifest, vm.resolved_toml()));
+        }
+
+        for (manifest_path, toml) in manifests {
+            let inherits_patch = toml.patch.as_ref().is_some_and(|p| p.workspace);
+            let inherits_replace = toml.replace.as_ref().is_some_and(|r| r.workspace);
+            for (what, inherits) in [("patch", inherits_patch), ("replace", inherits_replace)] {
+                if !inherits {
+                    continue;
+                }
+                if !self.gctx.cli_unstable().workspace_patch {
+                    self.gctx.shell().warn(format!(
+                        "{}: ignoring `{what}.workspace`, pass `-Zworkspace-patch` to enable",
+                        manifest_path.display()
+                    ))?;
+                    continue;
+                }
+                if manifest_path == root_manifest {
+                    bail!(
+                        "`{what}.workspace = true` can only be used in workspace members, \
+                         but `{}` is the workspace root",
+                        manifest_path.display()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn validate_unique_names(&self) -> CargoResult<()> {
         let mut names = BTreeMap::new();
         for member in self.members.iter() {
@@ -926,6 +1306,9 @@ impl<'gctx> Workspace<'gctx> {
             .members
             .iter()
             .filter(|&member| {
+                if self.member_groups.contains(member) {
+                    return false;
+                }
                 let config = self.packages.get(member).workspace_config();
                 matches!(config, WorkspaceConfig::Root(_))
             })
@@ -942,8 +1325,13 @@ impl<'gctx> Workspace<'gctx> {
                 self.root_manifest.as_ref().unwrap().display()
             ),
             _ => {
+                let help = if self.gctx.nightly_features_allowed {
+                    "\n\nhelp: pass `-Znested-workspaces` to keep nested workspaces separate"
+                } else {
+                    ""
+                };
                 bail!(
-                    "multiple workspace roots found in the same workspace:\n{}",
+                    "multiple workspace roots found in the same workspace:\n{}{help}",
                     roots
                         .iter()
                         .map(|r| format!("  {}", r.display()))
@@ -957,7 +1345,11 @@ impl<'gctx> Workspace<'gctx> {
     fn validate_members(&mut self) -> CargoResult<()> {
         for member in self.members.clone() {
             let root = self.find_root(&member)?;
-            if root == self.root_manifest {
+            if root == self.root_manifest
+                || root
+                    .as_ref()
+                    .is_some_and(|root| self.member_groups.contains(root))
+            {
                 continue;
             }
 
@@ -1189,9 +1581,10 @@ impl<'gctx> Workspace<'gctx> {
         let toml_lints = pkg
             .manifest()
             .resolved_toml()
-            .lints
-            .clone()
-            .map(|lints| lints.lints)
+            .resolved_lints_with_profile(self.gctx.lints_profile())
+            .ok()
+            .flatten()
+            .map(|lints| lints.into_owned())
             .unwrap_or(manifest::TomlLints::default());
         let cargo_lints = toml_lints
             .get("cargo")
@@ -1258,6 +1651,7 @@ impl<'gctx> Workspace<'gctx> {
                 .map(|m| (m, CliFeatures::new_all(true)))
                 .collect());
         }
+        let cli_features = &self.expand_feature_presets(cli_features)?;
         if self.allows_new_cli_feature_behavior() {
             self.members_with_features_new(specs, cli_features)
         } else {
@@ -1265,6 +1659,83 @@ impl<'gctx> Workspace<'gctx> {
         }
     }
 
+    /// Like [`Workspace::members_with_features`], but for resolving
+    /// dependencies and features rather than selecting what to build.
+    ///
+    /// With `resolver.feature-unification = "workspace"`, every member that
+    /// isn't selected is added with its default features, so that shared
+    /// dependencies get the same features whichever members are selected.
+    pub fn members_with_features_to_resolve(
+        &self,
+        specs: &[PackageIdSpec],
+        cli_features: &CliFeatures,
+    ) -> CargoResult<Vec<(&Package, CliFeatures)>> {
+        let mut members = self.members_with_features(specs, cli_features)?;
+        if self.resolve_feature_unification == CargoResolverFeatureUnification::Workspace {
+            let selected: HashSet<_> = members.iter().map(|(m, _)| m.package_id()).collect();
+            let unselected = self
+                .members()
+                .filter(|m| !selected.contains(&m.package_id()))
+                .map(|m| (m, CliFeatures::new_all(false)))
+                .collect::<Vec<_>>();
+            members.extend(unselected);
+        }
+        Ok(members)
+    }
+
+    /// Replaces any `preset:<name>` features with the feature values listed
+    /// for that preset in `[workspace.features]`.
+    fn expand_feature_presets(&self, cli_features: &CliFeatures) -> CargoResult<CliFeatures> {
+        let is_preset = |f: &FeatureValue| match f {
+            FeatureValue::Feature(name) => name.starts_with("preset:"),
+            _ => false,
+        };
+        if !cli_features.features.iter().any(is_preset) {
+            return Ok(cli_features.clone());
+        }
+        let mut features = Vec::new();
+        for feature in cli_features.features.iter() {
+            let FeatureValue::Feature(name) = feature else {
+                features.push(feature.to_string());
+                continue;
+            };
+            let Some(preset) = name.strip_prefix("preset:") else {
+                features.push(feature.to_string());
+                continue;
+            };
+            if !self.gctx.cli_unstable().feature_presets {
+                bail!("feature `{name}` requires `-Zfeature-presets`");
+            }
+            let Some(values) = self.feature_presets.get(preset) else {
+                let presets = self
+                    .feature_presets
+                    .keys()
+                    .map(|k| format!("`{k}`"))
+                    .collect::<Vec<_>>();
+                if presets.is_empty() {
+                    bail!("feature preset `{preset}` is not defined in `[workspace.features]`");
+                }
+                bail!(
+                    "feature preset `{preset}` is not defined in `[workspace.features]`\n\n\
+                     available presets: {}",
+                    presets.join(", ")
+                );
+            };
+            if let Some(nested) = values.iter().find(|v| v.starts_with("preset:")) {
+                bail!(
+                    "feature preset `{preset}` includes `{nested}`, \
+                     but presets cannot include other presets"
+                );
+            }
+            features.extend(values.iter().cloned());
+        }
+        CliFeatures::from_command_line(
+            &features,
+            cli_features.all_features,
+            cli_features.uses_default_features,
+        )
+    }
+
     /// Returns the requested features for the given member.
     /// This filters out any named features that the member does not have.
     fn collect_matching_features(
@@ -1779,6 +2250,8 @@ impl WorkspaceRootConfig {
         exclude: &Option<Vec<String>>,
         inheritable: &Option<InheritableFields>,
         custom_metadata: &Option<toml::Value>,
+        feature_presets: &Option<BTreeMap<String, Vec<String>>>,
+        split_target_dirs: &Option<bool>,
     ) -> WorkspaceRootConfig {
         WorkspaceRootConfig {
             root_dir: root_dir.to_path_buf(),
@@ -1787,6 +2260,8 @@ impl WorkspaceRootConfig {
             exclude: exclude.clone().unwrap_or_default(),
             inheritable_fields: inheritable.clone().unwrap_or_default(),
             custom_metadata: custom_metadata.clone(),
+            feature_presets: feature_presets.clone().unwrap_or_default(),
+            split_target_dirs: split_target_dirs.unwrap_or_default(),
         }
     }
     /// Checks the path against the `excluded` list.
@@ -1840,6 +2315,41 @@ impl WorkspaceRootConfig {
         Ok(expanded_list)
     }
 
+    /// Expands `default-members` like [`Self::members_paths`], returning the
+    /// paths selected by its regular entries along with the patterns of its
+    /// `!`-prefixed exclusions.
+    ///
+    /// An entry naming an existing directory that starts with `!` is still a
+    /// regular entry, as it was before exclusions were supported.
+    fn default_members_paths(
+        &self,
+        patterns: &[String],
+        gctx: &GlobalContext,
+    ) -> CargoResult<(Vec<PathBuf>, Vec<glob::Pattern>)> {
+        let (excluded, included): (Vec<_>, Vec<_>) = patterns
+            .iter()
+            .cloned()
+            .partition(|pattern| pattern.starts_with('!') && !self.root_dir.join(pattern).is_dir());
+        let mut patterns = Vec::new();
+        for pattern in &excluded {
+            let glob = &pattern[1..];
+            if glob.is_empty() {
+                gctx.shell()
+                    .warn("ignoring empty exclusion pattern `!` in `workspace.default-members`")?;
+                continue;
+            }
+            let path = paths::normalize_path(&self.root_dir.join(glob));
+            let Some(path) = path.to_str() else {
+                bail!("could not parse pattern `{pattern}`: path is not valid UTF-8");
+            };
+            patterns.push(
+                glob::Pattern::new(path)
+                    .with_context(|| format!("could not parse pattern `{pattern}`"))?,
+            );
+        }
+        Ok((self.members_paths(&included)?, patterns))
+    }
+
     fn expand_member_path(path: &Path) -> CargoResult<Vec<PathBuf>> {
         let Some(path) = path.to_str() else {
             return Ok(Vec::new());
@@ -1907,8 +2417,10 @@ fn find_workspace_root_with_loader(
     gctx: &GlobalContext,
     mut loader: impl FnMut(&Path) -> CargoResult<Option<PathBuf>>,
 ) -> CargoResult<Option<PathBuf>> {
-    // Check if there are any workspace roots that have already been found that would work
-    {
+    // Check if there are any workspace roots that have already been found that would work.
+    // With nested workspaces, a closer root that hasn't been loaded yet may take precedence,
+    // so always walk up from the manifest instead.
+    if !gctx.cli_unstable().nested_workspaces {
         let roots = gctx.ws_roots.borrow();
         // Iterate through the manifests parent directories until we find a workspace
         // root. Note we skip the first item since that is just the path itself
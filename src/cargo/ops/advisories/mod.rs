@@ -0,0 +1,238 @@
+//! Checks the packages of a lock file against a database of security
+//! advisories, configured in the `[advisories]` table:
+//!
+//! ```toml
+//! [advisories]
+//! # A directory, or the URL of a git repository.
+//! database = "https://github.com/rustsec/advisory-db"
+//! # `warn` (the default) or `deny`.
+//! policy = "deny"
+//! ignore = ["RUSTSEC-2020-0001"]
+//! ```
+//!
+//! Packages are checked when they are added to the lock file, and all of
+//! them with `cargo report advisories`. Only packages from crates.io are
+//! checked, since that's what advisory databases describe. Informational
+//! advisories, like a package being unmaintained, are only warned about,
+//! whatever the policy.
+
+mod source;
+
+pub use self::source::{
+    open, Advisory, AdvisorySource, DirectoryAdvisorySource, GitAdvisorySource,
+};
+
+use std::fmt::Write as _;
+
+use anyhow::bail;
+use serde::Deserialize;
+
+use crate::core::{PackageId, Resolve, Workspace};
+use crate::drop_println;
+use crate::ops;
+use crate::util::context::{ConfigRelativePath, StringList};
+use crate::util::{CargoResult, GlobalContext};
+
+/// The `[advisories]` config table.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct AdvisoriesConfig {
+    database: Option<ConfigRelativePath>,
+    policy: Option<AdvisoryPolicy>,
+    ignore: Option<StringList>,
+}
+
+/// What to do about packages affected by an advisory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdvisoryPolicy {
+    #[default]
+    Warn,
+    Deny,
+}
+
+/// The configured advisory database, with the policy for it.
+struct Checker<'gctx> {
+    source: Box<dyn AdvisorySource + 'gctx>,
+    policy: AdvisoryPolicy,
+    ignore: Vec<String>,
+}
+
+impl<'gctx> Checker<'gctx> {
+    /// Returns `None` if there's no `advisories.database`.
+    fn load(gctx: &'gctx GlobalContext) -> CargoResult<Option<Checker<'gctx>>> {
+        let config = gctx
+            .get::<Option<AdvisoriesConfig>>("advisories")?
+            .unwrap_or_default();
+        let Some(database) = config.database else {
+            return Ok(None);
+        };
+        let source = open(database.raw_value(), database.resolve_path(gctx), gctx)?;
+        Ok(Some(Checker {
+            source,
+            policy: config.policy.unwrap_or_default(),
+            ignore: config
+                .ignore
+                .map(|ignore| ignore.as_slice().to_vec())
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// Returns the packages of `ids` from crates.io along with the
+    /// advisories affecting them.
+    fn find(
+        &mut self,
+        ids: impl Iterator<Item = PackageId>,
+    ) -> CargoResult<Vec<(PackageId, Advisory)>> {
+        let mut ids: Vec<_> = ids.filter(|id| id.source_id().is_crates_io()).collect();
+        ids.sort();
+        let mut found = Vec::new();
+        for id in ids {
+            for advisory in self.source.query(&id.name())? {
+                if advisory.affects(id.version()) && !self.ignore.contains(&advisory.id) {
+                    found.push((id, advisory));
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// Checks the packages `resolve` adds to the lock file of `prev`, warning
+/// about the ones affected by advisories or failing with `policy = "deny"`.
+///
+/// This does nothing without `-Zadvisories` and `advisories.database`.
+pub fn check_new_packages(
+    ws: &Workspace<'_>,
+    prev: Option<&Resolve>,
+    resolve: &Resolve,
+) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    if !gctx.cli_unstable().advisories {
+        return Ok(());
+    }
+    let Some(mut checker) = Checker::load(gctx)? else {
+        return Ok(());
+    };
+    let new = resolve
+        .iter()
+        .filter(|id| prev.map_or(true, |prev| !prev.contains(id)));
+    let (informational, found): (Vec<_>, Vec<_>) = checker
+        .find(new)?
+        .into_iter()
+        .partition(|(_, advisory)| advisory.informational.is_some());
+    for (id, advisory) in &informational {
+        gctx.shell().warn(describe(*id, advisory))?;
+    }
+    if found.is_empty() {
+        return Ok(());
+    }
+
+    match checker.policy {
+        AdvisoryPolicy::Warn => {
+            for (id, advisory) in &found {
+                gctx.shell().warn(describe(*id, advisory))?;
+            }
+            Ok(())
+        }
+        AdvisoryPolicy::Deny => {
+            let mut msg = format!(
+                "{} affected by advisories of {}:",
+                packages(found.len()),
+                checker.source.describe()
+            );
+            for (id, advisory) in &found {
+                write!(msg, "\n  {}", describe(*id, advisory))?;
+            }
+            msg.push_str(
+                "\n\nupdate the packages, or list the advisories in `advisories.ignore` \
+                 to allow them",
+            );
+            bail!(msg)
+        }
+    }
+}
+
+/// Lists the packages of the lock file affected by advisories, failing if
+/// there are any with `policy = "deny"`.
+pub fn report_advisories(ws: &Workspace<'_>) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let Some(mut checker) = Checker::load(gctx)? else {
+        bail!(
+            "no advisory database is configured\n\n\
+             set `advisories.database` to a directory or the URL of a git repository"
+        );
+    };
+    let resolve = match ops::load_pkg_lockfile(ws)? {
+        Some(resolve) => resolve,
+        None => ops::resolve_ws(ws, true)?.1,
+    };
+    let found = checker.find(resolve.iter())?;
+
+    for (id, advisory) in &found {
+        drop_println!(
+            gctx,
+            "{}: {}",
+            advisory.id,
+            advisory.title.as_deref().unwrap_or("(no title)")
+        );
+        drop_println!(gctx, "  package: {} v{}", id.name(), id.version());
+        if let Some(kind) = &advisory.informational {
+            drop_println!(gctx, "  informational: {kind}");
+        }
+        if !advisory.patched.is_empty() {
+            let patched: Vec<_> = advisory.patched.iter().map(|r| r.to_string()).collect();
+            drop_println!(gctx, "  patched: {}", patched.join(", "));
+        }
+        if let Some(url) = &advisory.url {
+            drop_println!(gctx, "  url: {url}");
+        }
+    }
+
+    if found.is_empty() {
+        let n = resolve.iter().count();
+        gctx.shell().note(format!(
+            "no advisories of {} affect the {n} package{} of the lock file",
+            checker.source.describe(),
+            if n == 1 { "" } else { "s" },
+        ))?;
+        return Ok(());
+    }
+    let msg = format!("{} affected by advisories", packages(found.len()));
+    let vulnerable = found
+        .iter()
+        .any(|(_, advisory)| advisory.informational.is_none());
+    match checker.policy {
+        AdvisoryPolicy::Deny if vulnerable => bail!(msg),
+        _ => gctx.shell().warn(msg)?,
+    }
+    Ok(())
+}
+
+fn describe(id: PackageId, advisory: &Advisory) -> String {
+    let mut msg = format!(
+        "{} v{} is affected by {}",
+        id.name(),
+        id.version(),
+        advisory.id
+    );
+    if let Some(kind) = &advisory.informational {
+        write!(msg, " [{kind}]").unwrap();
+    }
+    if let Some(title) = &advisory.title {
+        write!(msg, " ({title})").unwrap();
+    }
+    if !advisory.patched.is_empty() {
+        let patched: Vec<_> = advisory.patched.iter().map(|r| r.to_string()).collect();
+        write!(msg, ", patched in `{}`", patched.join("`, `")).unwrap();
+    }
+    msg
+}
+
+fn packages(n: usize) -> String {
+    if n == 1 {
+        "1 package is".to_string()
+    } else {
+        format!("{n} packages are")
+    }
+}
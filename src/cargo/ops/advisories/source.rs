@@ -0,0 +1,238 @@
+//! Where advisories come from.
+//!
+//! Databases use the layout of the [RustSec advisory database]: every
+//! advisory is a `crates/<name>/<id>.md` file starting with a TOML block
+//! fenced by ```` ```toml ```` and ```` ``` ````, followed by a Markdown
+//! description whose first `# ` heading is the title:
+//!
+//! ````markdown
+//! ```toml
+//! [advisory]
+//! id = "RUSTSEC-2020-0001"
+//! package = "foo"
+//! date = "2020-01-01"
+//! url = "https://example.com/foo/issues/1"
+//!
+//! [versions]
+//! patched = [">= 1.2.0"]
+//! unaffected = ["< 1.0.0"]
+//! ```
+//!
+//! # Use after free in `Foo::bar`
+//! ````
+//!
+//! Advisories that aren't about a vulnerability, like a package being
+//! unmaintained or unsound, set `advisory.informational` to their kind.
+//!
+//! [RustSec advisory database]: https://github.com/rustsec/advisory-db
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use cargo_util::paths;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::core::{GitReference, SourceId};
+use crate::sources::GitSource;
+use crate::util::cache_lock::CacheLockMode;
+use crate::util::errors::CargoResult;
+use crate::util::{GlobalContext, IntoUrl};
+
+/// A security advisory for the versions of a package.
+#[derive(Debug)]
+pub struct Advisory {
+    pub id: String,
+    pub package: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// Versions with the issue fixed.
+    pub patched: Vec<VersionReq>,
+    /// Versions that never had the issue.
+    pub unaffected: Vec<VersionReq>,
+    /// Whether the advisory was withdrawn, in which case it affects nothing.
+    pub withdrawn: bool,
+    /// The kind of an advisory that isn't about a vulnerability, like
+    /// `unmaintained` or `unsound`.
+    pub informational: Option<String>,
+}
+
+impl Advisory {
+    /// Whether `version` of the package has the issue.
+    pub fn affects(&self, version: &Version) -> bool {
+        !self.withdrawn
+            && !self
+                .patched
+                .iter()
+                .chain(&self.unaffected)
+                .any(|req| req.matches(version))
+    }
+}
+
+/// A database of advisories, queried by package name.
+///
+/// Like a [`Source`](crate::sources::source::Source) of packages, an
+/// implementation is free to fetch its data however it wants; it only has to
+/// be ready by the time it is first queried.
+pub trait AdvisorySource {
+    /// Returns the advisories for the package `name`.
+    fn query(&mut self, name: &str) -> CargoResult<Vec<Advisory>>;
+
+    /// Describes the database, for messages.
+    fn describe(&self) -> String;
+}
+
+/// Opens the advisory database `database`, a git URL if it contains `://` and
+/// otherwise a directory.
+pub fn open<'gctx>(
+    database: &str,
+    path: PathBuf,
+    gctx: &'gctx GlobalContext,
+) -> CargoResult<Box<dyn AdvisorySource + 'gctx>> {
+    if database.contains("://") {
+        Ok(Box::new(GitAdvisorySource::new(database, gctx)?))
+    } else {
+        Ok(Box::new(DirectoryAdvisorySource::new(path)))
+    }
+}
+
+/// An advisory database in a local directory.
+pub struct DirectoryAdvisorySource {
+    root: PathBuf,
+}
+
+impl DirectoryAdvisorySource {
+    pub fn new(root: PathBuf) -> DirectoryAdvisorySource {
+        DirectoryAdvisorySource { root }
+    }
+}
+
+impl AdvisorySource for DirectoryAdvisorySource {
+    fn query(&mut self, name: &str) -> CargoResult<Vec<Advisory>> {
+        if !self.root.is_dir() {
+            anyhow::bail!(
+                "advisory database `{}` is not a directory",
+                self.root.display()
+            );
+        }
+        let dir = self.root.join("crates").join(name);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut files: Vec<_> = entries
+            .map(|entry| Ok(entry?.path()))
+            .collect::<CargoResult<_>>()?;
+        files.retain(|path| path.extension().is_some_and(|ext| ext == "md"));
+        files.sort();
+
+        let mut advisories = Vec::new();
+        for path in files {
+            let advisory = parse(&path)
+                .with_context(|| format!("failed to parse advisory `{}`", path.display()))?;
+            if advisory.package == name {
+                advisories.push(advisory);
+            }
+        }
+        Ok(advisories)
+    }
+
+    fn describe(&self) -> String {
+        format!("`{}`", self.root.display())
+    }
+}
+
+/// An advisory database in a git repository, checked out the first time it
+/// is queried.
+pub struct GitAdvisorySource<'gctx> {
+    source: GitSource<'gctx>,
+    url: String,
+    checkout: Option<DirectoryAdvisorySource>,
+    gctx: &'gctx GlobalContext,
+}
+
+impl<'gctx> GitAdvisorySource<'gctx> {
+    pub fn new(url: &str, gctx: &'gctx GlobalContext) -> CargoResult<GitAdvisorySource<'gctx>> {
+        let source_id = SourceId::for_git(&url.into_url()?, GitReference::DefaultBranch)?;
+        Ok(GitAdvisorySource {
+            source: GitSource::new(source_id, gctx)?,
+            url: url.to_string(),
+            checkout: None,
+            gctx,
+        })
+    }
+}
+
+impl<'gctx> AdvisorySource for GitAdvisorySource<'gctx> {
+    fn query(&mut self, name: &str) -> CargoResult<Vec<Advisory>> {
+        if self.checkout.is_none() {
+            let _lock = self
+                .gctx
+                .acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+            let path = self.source.checkout()?;
+            self.checkout = Some(DirectoryAdvisorySource::new(path));
+        }
+        self.checkout.as_mut().unwrap().query(name)
+    }
+
+    fn describe(&self) -> String {
+        format!("`{}`", self.url)
+    }
+}
+
+#[derive(Deserialize)]
+struct AdvisoryFile {
+    advisory: AdvisoryTable,
+    #[serde(default)]
+    versions: VersionsTable,
+}
+
+#[derive(Deserialize)]
+struct AdvisoryTable {
+    id: String,
+    package: String,
+    url: Option<String>,
+    withdrawn: Option<String>,
+    informational: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct VersionsTable {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+fn parse(path: &Path) -> CargoResult<Advisory> {
+    let contents = paths::read(path)?;
+    let rest = contents
+        .trim_start()
+        .strip_prefix("```toml")
+        .context("expected the advisory to start with a ```toml block")?;
+    let (toml_block, description) = rest
+        .split_once("\n```")
+        .context("expected the ```toml block of the advisory to be closed")?;
+    let file: AdvisoryFile = toml::from_str(toml_block)?;
+    let title = description
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string());
+    let reqs = |reqs: &[String]| -> CargoResult<Vec<VersionReq>> {
+        reqs.iter()
+            .map(|req| {
+                VersionReq::parse(req)
+                    .with_context(|| format!("invalid version requirement `{req}`"))
+            })
+            .collect()
+    };
+    Ok(Advisory {
+        id: file.advisory.id,
+        package: file.advisory.package,
+        title,
+        url: file.advisory.url,
+        patched: reqs(&file.versions.patched)?,
+        unaffected: reqs(&file.versions.unaffected)?,
+        withdrawn: file.advisory.withdrawn.is_some(),
+        informational: file.advisory.informational,
+    })
+}
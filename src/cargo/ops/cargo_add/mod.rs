@@ -93,7 +93,7 @@ pub fn add(workspace: &Workspace<'_>, options: &AddOptions<'_>) -> CargoResult<(
                     &manifest,
                     raw,
                     workspace,
-                    &options.spec,
+                    Some(options.spec),
                     &options.section,
                     options.honor_rust_version,
                     options.gctx,
@@ -125,75 +125,7 @@ pub fn add(workspace: &Workspace<'_>, options: &AddOptions<'_>) -> CargoResult<(
             }
         }
 
-        let available_features = dep
-            .available_features
-            .keys()
-            .map(|s| s.as_ref())
-            .collect::<BTreeSet<&str>>();
-        let mut unknown_features: Vec<&str> = Vec::new();
-        if let Some(req_feats) = dep.features.as_ref() {
-            let req_feats: BTreeSet<_> = req_feats.iter().map(|s| s.as_str()).collect();
-            unknown_features.extend(req_feats.difference(&available_features).copied());
-        }
-        if let Some(inherited_features) = dep.inherited_features.as_ref() {
-            let inherited_features: BTreeSet<_> =
-                inherited_features.iter().map(|s| s.as_str()).collect();
-            unknown_features.extend(inherited_features.difference(&available_features).copied());
-        }
-
-        unknown_features.sort();
-
-        if !unknown_features.is_empty() {
-            let (mut activated, mut deactivated) = dep.features();
-            // Since the unknown features have been added to the DependencyUI we need to remove
-            // them to present the "correct" features that can be specified for the crate.
-            deactivated.retain(|f| !unknown_features.contains(f));
-            activated.retain(|f| !unknown_features.contains(f));
-
-            let mut message = format!(
-                "unrecognized feature{} for crate {}: {}\n",
-                if unknown_features.len() == 1 { "" } else { "s" },
-                dep.name,
-                unknown_features.iter().format(", "),
-            );
-            if activated.is_empty() && deactivated.is_empty() {
-                write!(message, "no features available for crate {}", dep.name)?;
-            } else {
-                if !deactivated.is_empty() {
-                    writeln!(
-                        message,
-                        "disabled features:\n    {}",
-                        deactivated
-                            .iter()
-                            .map(|s| s.to_string())
-                            .coalesce(|x, y| if x.len() + y.len() < 78 {
-                                Ok(format!("{x}, {y}"))
-                            } else {
-                                Err((x, y))
-                            })
-                            .into_iter()
-                            .format("\n    ")
-                    )?
-                }
-                if !activated.is_empty() {
-                    writeln!(
-                        message,
-                        "enabled features:\n    {}",
-                        activated
-                            .iter()
-                            .map(|s| s.to_string())
-                            .coalesce(|x, y| if x.len() + y.len() < 78 {
-                                Ok(format!("{x}, {y}"))
-                            } else {
-                                Err((x, y))
-                            })
-                            .into_iter()
-                            .format("\n    ")
-                    )?
-                }
-            }
-            anyhow::bail!(message.trim().to_owned());
-        }
+        check_unknown_features(&dep)?;
 
         print_dep_table_msg(&mut options.gctx.shell(), &dep)?;
 
@@ -248,6 +180,234 @@ pub fn add(workspace: &Workspace<'_>, options: &AddOptions<'_>) -> CargoResult<(
     Ok(())
 }
 
+/// Fails if `dep` requests features that the selected version doesn't have.
+fn check_unknown_features(dep: &DependencyUI) -> CargoResult<()> {
+    let available_features = dep
+        .available_features
+        .keys()
+        .map(|s| s.as_ref())
+        .collect::<BTreeSet<&str>>();
+    let mut unknown_features: Vec<&str> = Vec::new();
+    if let Some(req_feats) = dep.features.as_ref() {
+        let req_feats: BTreeSet<_> = req_feats.iter().map(|s| s.as_str()).collect();
+        unknown_features.extend(req_feats.difference(&available_features).copied());
+    }
+    if let Some(inherited_features) = dep.inherited_features.as_ref() {
+        let inherited_features: BTreeSet<_> =
+            inherited_features.iter().map(|s| s.as_str()).collect();
+        unknown_features.extend(inherited_features.difference(&available_features).copied());
+    }
+
+    unknown_features.sort();
+
+    if !unknown_features.is_empty() {
+        let (mut activated, mut deactivated) = dep.features();
+        // Since the unknown features have been added to the DependencyUI we need to remove
+        // them to present the "correct" features that can be specified for the crate.
+        deactivated.retain(|f| !unknown_features.contains(f));
+        activated.retain(|f| !unknown_features.contains(f));
+
+        let mut message = format!(
+            "unrecognized feature{} for crate {}: {}\n",
+            if unknown_features.len() == 1 { "" } else { "s" },
+            dep.name,
+            unknown_features.iter().format(", "),
+        );
+        if activated.is_empty() && deactivated.is_empty() {
+            write!(message, "no features available for crate {}", dep.name)?;
+        } else {
+            if !deactivated.is_empty() {
+                writeln!(
+                    message,
+                    "disabled features:\n    {}",
+                    deactivated
+                        .iter()
+                        .map(|s| s.to_string())
+                        .coalesce(|x, y| if x.len() + y.len() < 78 {
+                            Ok(format!("{x}, {y}"))
+                        } else {
+                            Err((x, y))
+                        })
+                        .into_iter()
+                        .format("\n    ")
+                )?
+            }
+            if !activated.is_empty() {
+                writeln!(
+                    message,
+                    "enabled features:\n    {}",
+                    activated
+                        .iter()
+                        .map(|s| s.to_string())
+                        .coalesce(|x, y| if x.len() + y.len() < 78 {
+                            Ok(format!("{x}, {y}"))
+                        } else {
+                            Err((x, y))
+                        })
+                        .into_iter()
+                        .format("\n    ")
+                )?
+            }
+        }
+        anyhow::bail!(message.trim().to_owned());
+    }
+    Ok(())
+}
+
+pub struct AddWorkspaceOptions<'a> {
+    /// Configuration information for cargo operations
+    pub gctx: &'a GlobalContext,
+    /// Dependencies to add or modify
+    pub dependencies: Vec<DepOp>,
+    /// Switch members that already depend on the added dependencies to
+    /// `workspace = true`
+    pub rewrite_members: bool,
+    /// Act as if dependencies will be added
+    pub dry_run: bool,
+    /// Whether the minimum supported Rust version should be considered during resolution
+    pub honor_rust_version: Option<bool>,
+}
+
+/// Add dependencies to the `[workspace.dependencies]` table of the workspace root
+pub fn add_workspace_dependencies(
+    workspace: &Workspace<'_>,
+    options: &AddWorkspaceOptions<'_>,
+) -> CargoResult<()> {
+    let dep_table = vec![String::from("workspace"), String::from("dependencies")];
+
+    let root_manifest_path = workspace.root_manifest().to_path_buf();
+    let mut manifest = LocalManifest::try_new(&root_manifest_path)?;
+    if manifest.get_table(&dep_table[..1]).is_err() {
+        anyhow::bail!(
+            "`--workspace-dependency` requires a `[workspace]` table in {}",
+            root_manifest_path.display()
+        );
+    }
+    let original_raw_manifest = manifest.to_string();
+
+    let mut registry = workspace.package_registry()?;
+
+    let deps = {
+        let _lock = options
+            .gctx
+            .acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+        registry.lock_patches();
+        options
+            .dependencies
+            .iter()
+            .map(|raw| {
+                if raw.optional.is_some() || raw.public.is_some() {
+                    anyhow::bail!(
+                        "`[workspace.dependencies]` cannot declare whether a dependency is \
+                         optional or public, set it in the member instead"
+                    );
+                }
+                resolve_dependency(
+                    &manifest,
+                    raw,
+                    workspace,
+                    None,
+                    &DepTable::new(),
+                    options.honor_rust_version,
+                    options.gctx,
+                    &mut registry,
+                )
+            })
+            .collect::<CargoResult<Vec<_>>>()?
+    };
+
+    let was_sorted = manifest
+        .get_table(&dep_table)
+        .ok()
+        .and_then(TomlItem::as_table_like)
+        .map_or(true, |table| is_sorted(table.iter().map(|(key, _)| key)));
+    for dep in &deps {
+        print_action_msg(&mut options.gctx.shell(), dep, &dep_table)?;
+        check_unknown_features(dep)?;
+        print_dep_table_msg(&mut options.gctx.shell(), dep)?;
+        manifest.insert_into_table(&dep_table, dep)?;
+    }
+    if was_sorted {
+        if let Some(table) = manifest
+            .get_table_mut(&dep_table)
+            .ok()
+            .and_then(TomlItem::as_table_like_mut)
+        {
+            table.sort_values();
+        }
+    }
+
+    // Each manifest to write, along with its original contents.
+    let mut manifests = vec![(manifest, original_raw_manifest)];
+    if options.rewrite_members {
+        for member in workspace.members() {
+            let index = if member.manifest_path() == root_manifest_path {
+                0
+            } else {
+                let manifest = LocalManifest::try_new(member.manifest_path())?;
+                let original_raw_manifest = manifest.to_string();
+                manifests.push((manifest, original_raw_manifest));
+                manifests.len() - 1
+            };
+            for dep in &deps {
+                inherit_workspace_dep(options.gctx, &mut manifests[index].0, dep)?;
+            }
+        }
+    }
+
+    if options.gctx.locked() {
+        for (manifest, original_raw_manifest) in &manifests {
+            if *original_raw_manifest != manifest.to_string() {
+                anyhow::bail!(
+                    "the manifest file {} needs to be updated but --locked was passed to prevent this",
+                    manifest.path.display()
+                );
+            }
+        }
+    }
+
+    if options.dry_run {
+        options.gctx.shell().warn("aborting add due to dry run")?;
+    } else {
+        for (manifest, _) in &manifests {
+            manifest.write()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Switches every entry for `dep` in a member's dependency tables to
+/// `workspace = true`, keeping member-specific keys like `features`.
+pub(crate) fn inherit_workspace_dep(
+    gctx: &GlobalContext,
+    manifest: &mut LocalManifest,
+    dep: &Dependency,
+) -> CargoResult<()> {
+    let dep_key = dep.toml_key();
+    let existing: Vec<_> = manifest.get_dependency_versions(dep_key).collect();
+    for (table, existing) in existing {
+        let Ok(mut existing) = existing else {
+            continue;
+        };
+        if existing.name != dep.name || matches!(existing.source(), Some(Source::Workspace(_))) {
+            continue;
+        }
+        existing.source = Some(Source::Workspace(WorkspaceSource::new()));
+        existing.registry = None;
+        let table_path: Vec<_> = table.to_table().into_iter().map(String::from).collect();
+        manifest.insert_into_table(&table_path, &existing)?;
+        gctx.shell().status(
+            "Updating",
+            format!(
+                "{dep_key} in {} to `workspace = true`",
+                manifest.package_name().unwrap_or("the workspace root")
+            ),
+        )?;
+    }
+    Ok(())
+}
+
 /// Dependency entry operation
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DepOp {
@@ -286,7 +446,7 @@ fn resolve_dependency(
     manifest: &LocalManifest,
     arg: &DepOp,
     ws: &Workspace<'_>,
-    spec: &Package,
+    spec: Option<&Package>,
     section: &DepTable,
     honor_rust_version: Option<bool>,
     gctx: &GlobalContext,
@@ -361,7 +521,10 @@ fn resolve_dependency(
     };
     selected_dep = populate_dependency(selected_dep, arg);
 
-    let old_dep = get_existing_dependency(manifest, selected_dep.toml_key(), section)?;
+    let old_dep = match spec {
+        Some(_) => get_existing_dependency(manifest, selected_dep.toml_key(), section)?,
+        None => find_workspace_dep(selected_dep.toml_key(), ws.root_manifest()).ok(),
+    };
     let mut dependency = if let Some(mut old_dep) = old_dep.clone() {
         if old_dep.name != selected_dep.name {
             // Assuming most existing keys are not relevant when the package changes
@@ -383,7 +546,9 @@ fn resolve_dependency(
     if dependency.source().is_none() {
         // Checking for a workspace dependency happens first since a member could be specified
         // in the workspace dependencies table as a dependency
-        if let Some(_dep) = find_workspace_dep(dependency.toml_key(), ws.root_manifest()).ok() {
+        let inherit =
+            spec.is_some() && find_workspace_dep(dependency.toml_key(), ws.root_manifest()).is_ok();
+        if inherit {
             dependency = dependency.set_source(WorkspaceSource::new());
         } else if let Some(package) = ws.members().find(|p| p.name().as_str() == dependency.name) {
             // Only special-case workspaces when the user doesn't provide any extra
@@ -397,8 +562,18 @@ fn resolve_dependency(
             }
             dependency = dependency.set_source(src);
         } else {
-            let latest =
-                get_latest_dependency(spec, &dependency, honor_rust_version, gctx, registry)?;
+            let (rust_version_owner, rust_version) = match spec {
+                Some(spec) => (spec.name().as_str(), spec.rust_version()),
+                None => ("the workspace", ws.rust_version()),
+            };
+            let latest = get_latest_dependency(
+                rust_version_owner,
+                rust_version,
+                &dependency,
+                honor_rust_version,
+                gctx,
+                registry,
+            )?;
 
             if dependency.name != latest.name {
                 gctx.shell().warn(format!(
@@ -566,7 +741,8 @@ fn get_existing_dependency(
 }
 
 fn get_latest_dependency(
-    spec: &Package,
+    rust_version_owner: &str,
+    rust_version: Option<&RustVersion>,
     dependency: &Dependency,
     honor_rust_version: Option<bool>,
     gctx: &GlobalContext,
@@ -606,8 +782,7 @@ fn get_latest_dependency(
             })?;
 
             if honor_rust_version.unwrap_or(true) {
-                let (req_msrv, is_msrv) = spec
-                    .rust_version()
+                let (req_msrv, is_msrv) = rust_version
                     .cloned()
                     .map(|msrv| CargoResult::Ok((msrv.clone().into_partial(), true)))
                     .unwrap_or_else(|| {
@@ -628,7 +803,7 @@ fn get_latest_dependency(
                 // compare the lowest possible versions they could represent, and treat
                 // candidates without a rust-version as compatible by default.
                 let latest_msrv = latest_compatible(&msrvs, &req_msrv).ok_or_else(|| {
-                        let name = spec.name();
+                        let name = rust_version_owner;
                         let dep_name = &dependency.name;
                         let latest_version = latest.version();
                         let latest_msrv = latest
@@ -652,7 +827,7 @@ help: pass `--ignore-rust-version` to select {dep_name}@{latest_version} which r
                 if latest_msrv.version() < latest.version() {
                     let latest_version = latest.version();
                     let latest_rust_version = latest.rust_version().unwrap();
-                    let name = spec.name();
+                    let name = rust_version_owner;
                     if is_msrv {
                         gctx.shell().warn(format_args!(
                             "\
@@ -998,8 +1173,8 @@ fn print_action_msg(shell: &mut Shell, dep: &DependencyUI, section: &[String]) -
     if dep.public().unwrap_or(false) {
         write!(message, " public")?;
     }
-    let section = if section.len() == 1 {
-        section[0].clone()
+    let section = if section.len() == 1 || section[0] == "workspace" {
+        section.join(".")
     } else {
         format!("{} for target `{}`", &section[2], &section[1])
     };
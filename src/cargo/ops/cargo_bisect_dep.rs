@@ -0,0 +1,375 @@
+//! Implementation of `cargo bisect-dep`.
+//!
+//! Given a lock file a command succeeds with and one it fails with, this
+//! finds which of the package updates between the two make it fail. Each
+//! step writes a `Cargo.lock` with some of the updates applied to the good
+//! lock file, re-resolving whatever they need, and runs the command with it.
+//!
+//! The updates are ordered by name, and the shortest failing prefix is found
+//! by bisection, its last update being a culprit. If the culprits found so
+//! far don't fail on their own, the failure also needs some of the updates
+//! before the last culprit, which are bisected the same way.
+//!
+//! The result of every step is recorded in the target directory, so that an
+//! interrupted bisection picks up where it stopped. The original `Cargo.lock`
+//! is saved there too, and put back by the next run if the process was killed
+//! before it could restore it.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use cargo_util::{paths, ProcessBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::core::resolver::features::{CliFeatures, HasDevUnits};
+use crate::core::shell::Verbosity;
+use crate::core::{PackageId, Resolve, SourceId, Workspace};
+use crate::drop_println;
+use crate::ops;
+use crate::util::cache_lock::CacheLockMode;
+use crate::util::hex::short_hash;
+use crate::util::{CargoResult, GlobalContext};
+
+use super::cargo_update::PackageDiff;
+use super::lockfile::lock_root;
+
+pub struct BisectDepOptions<'a> {
+    pub gctx: &'a GlobalContext,
+    /// A lock file the command succeeds with.
+    pub good: PathBuf,
+    /// A lock file the command fails with, `Cargo.lock` if not given.
+    pub bad: Option<PathBuf>,
+    /// The arguments of the cargo command to run at every step.
+    pub args: Vec<String>,
+    /// Forget the results of a previous bisection.
+    pub reset: bool,
+}
+
+/// The update of a package between the good and the bad lock file.
+struct Update {
+    from: PackageId,
+    to: PackageId,
+}
+
+impl Update {
+    fn label(&self) -> String {
+        let describe = |id: PackageId| match id.source_id().precise_git_fragment() {
+            Some(rev) => format!("v{} ({})", id.version(), &rev[..rev.len().min(8)]),
+            None => format!("v{}", id.version()),
+        };
+        format!(
+            "{} {} -> {}",
+            self.from.name(),
+            describe(self.from),
+            describe(self.to)
+        )
+    }
+}
+
+/// What is recorded in the target directory between runs.
+#[derive(Serialize, Deserialize)]
+struct State {
+    /// A hash of the lock files and the command, to tell whether the results
+    /// are of the same bisection.
+    key: String,
+    /// Whether the command succeeded, by the updates applied.
+    results: BTreeMap<String, bool>,
+}
+
+/// Puts the original `Cargo.lock` back once the bisection is over.
+///
+/// The original is also saved in `backup`, as JSON, for
+/// [`restore_interrupted`] to put it back when the process didn't get to
+/// drop this.
+struct RestoreLockfile {
+    path: PathBuf,
+    contents: Option<String>,
+    backup: PathBuf,
+}
+
+impl RestoreLockfile {
+    fn new(
+        path: PathBuf,
+        contents: Option<String>,
+        backup: PathBuf,
+    ) -> CargoResult<RestoreLockfile> {
+        paths::create_dir_all(backup.parent().unwrap())?;
+        paths::write(&backup, serde_json::to_string(&contents)?)?;
+        Ok(RestoreLockfile {
+            path,
+            contents,
+            backup,
+        })
+    }
+}
+
+impl Drop for RestoreLockfile {
+    fn drop(&mut self) {
+        let restored = match &self.contents {
+            Some(contents) => paths::write(&self.path, contents),
+            None => paths::remove_file(&self.path),
+        };
+        if restored.is_ok() {
+            let _ = paths::remove_file(&self.backup);
+        }
+    }
+}
+
+/// Puts back the `Cargo.lock` saved in `backup` by a bisection that was
+/// interrupted before it could restore it.
+fn restore_interrupted(lock_path: &Path, backup: &Path, gctx: &GlobalContext) -> CargoResult<()> {
+    let Ok(saved) = paths::read(backup) else {
+        return Ok(());
+    };
+    let contents: Option<String> = serde_json::from_str(&saved)
+        .with_context(|| format!("failed to parse `{}`", backup.display()))?;
+    match &contents {
+        Some(contents) => paths::write(lock_path, contents)?,
+        None if lock_path.exists() => paths::remove_file(lock_path)?,
+        None => {}
+    }
+    paths::remove_file(backup)?;
+    gctx.shell().warn(format!(
+        "restored the `Cargo.lock` of an interrupted bisection from `{}`",
+        backup.display()
+    ))
+}
+
+struct Bisection<'a, 'gctx> {
+    ws: &'a Workspace<'gctx>,
+    opts: &'a BisectDepOptions<'a>,
+    good: &'a Resolve,
+    updates: &'a [Update],
+    state: State,
+    state_path: PathBuf,
+}
+
+/// Finds the updates between two lock files that make a cargo command fail,
+/// printing them to stdout.
+pub fn bisect_dep(ws: &Workspace<'_>, opts: &BisectDepOptions<'_>) -> CargoResult<()> {
+    let gctx = opts.gctx;
+    let lock_path = lock_root(ws).as_path_unlocked().join("Cargo.lock");
+    let bisect_dir = ws.target_dir().join("bisect-dep").into_path_unlocked();
+    let backup = bisect_dir.join("original-lock.json");
+    restore_interrupted(&lock_path, &backup, gctx)?;
+    let original = lock_path
+        .exists()
+        .then(|| paths::read(&lock_path))
+        .transpose()?;
+
+    let read = |path: &PathBuf| -> CargoResult<(String, Resolve)> {
+        let contents = paths::read(path)?;
+        let resolve = ops::parse_lockfile(ws, &contents)
+            .with_context(|| format!("failed to parse lock file at: {}", path.display()))?;
+        Ok((contents, resolve))
+    };
+    let (good_contents, good) = read(&opts.good)?;
+    let (bad_contents, bad) = match &opts.bad {
+        Some(bad) => read(bad)?,
+        None if original.is_some() => read(&lock_path)?,
+        None => anyhow::bail!(
+            "there is no `Cargo.lock` to bisect towards, pass `--bad` with the lock file the command fails with"
+        ),
+    };
+
+    let mut updates: Vec<_> = PackageDiff::diff(&good, &bad)
+        .iter()
+        .filter_map(|diff| diff.change())
+        .filter(|(from, _)| !from.source_id().is_path())
+        .map(|(from, to)| Update {
+            from: *from,
+            to: *to,
+        })
+        .collect();
+    updates.sort_by_key(|update| update.from);
+    if updates.is_empty() {
+        anyhow::bail!("there are no package updates between the good and the bad lock files");
+    }
+
+    let state_path = bisect_dir.join("state.json");
+    let key = short_hash(&(&good_contents, &bad_contents, &opts.args));
+    let previous = if opts.reset {
+        None
+    } else {
+        paths::read(&state_path)
+            .ok()
+            .and_then(|state| serde_json::from_str::<State>(&state).ok())
+            .filter(|state| state.key == key)
+    };
+    if let Some(previous) = &previous {
+        gctx.shell().note(format!(
+            "reusing {} results recorded in `{}`, pass `--reset` to start over",
+            previous.results.len(),
+            state_path.display()
+        ))?;
+    }
+
+    let _restore = RestoreLockfile::new(lock_path, original, backup)?;
+    let mut bisection = Bisection {
+        ws,
+        opts,
+        good: &good,
+        updates: &updates,
+        state: previous.unwrap_or_else(|| State {
+            key,
+            results: BTreeMap::new(),
+        }),
+        state_path,
+    };
+
+    let n = updates.len();
+    gctx.shell().status(
+        "Bisecting",
+        format!(
+            "{n} package {} between the good and the bad lock files",
+            plural(n)
+        ),
+    )?;
+    if !bisection.passes(&[])? {
+        anyhow::bail!("the command fails with the good lock file too");
+    }
+    let all: Vec<_> = (0..n).collect();
+    if bisection.passes(&all)? {
+        anyhow::bail!("the command succeeds with the bad lock file, there is nothing to bisect");
+    }
+
+    // The culprits along with all the candidates always fail, and the
+    // culprits alone pass.
+    let mut culprits = Vec::new();
+    let mut candidates = all;
+    loop {
+        let (mut passing, mut failing) = (0, candidates.len());
+        while failing - passing > 1 {
+            let mid = (passing + failing) / 2;
+            let applied: Vec<_> = culprits.iter().chain(&candidates[..mid]).copied().collect();
+            if bisection.passes(&applied)? {
+                passing = mid;
+            } else {
+                failing = mid;
+            }
+        }
+        culprits.push(candidates[failing - 1]);
+        candidates.truncate(failing - 1);
+        if candidates.is_empty() || !bisection.passes(&culprits)? {
+            break;
+        }
+    }
+    culprits.sort();
+
+    gctx.shell().status(
+        "Found",
+        format!(
+            "{} {} making the command fail",
+            culprits.len(),
+            plural(culprits.len())
+        ),
+    )?;
+    for i in culprits {
+        drop_println!(gctx, "{}", updates[i].label());
+    }
+    Ok(())
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        "update"
+    } else {
+        "updates"
+    }
+}
+
+impl Bisection<'_, '_> {
+    /// Runs the command with the given updates applied to the good lock file,
+    /// unless it was already run with them.
+    fn passes(&mut self, applied: &[usize]) -> CargoResult<bool> {
+        let gctx = self.opts.gctx;
+        let mut applied = applied.to_vec();
+        applied.sort();
+        let key = applied
+            .iter()
+            .map(|&i| self.updates[i].label())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let what = format!("with {} of {} updates", applied.len(), self.updates.len());
+
+        if let Some(&passed) = self.state.results.get(&key) {
+            let status = if passed { "Passed" } else { "Failed" };
+            gctx.shell().status(status, format!("{what} (recorded)"))?;
+            return Ok(passed);
+        }
+
+        self.write_lockfile(&applied)
+            .with_context(|| format!("failed to resolve {what}"))?;
+        gctx.shell().status("Testing", &what)?;
+        let mut cmd = ProcessBuilder::new(gctx.cargo_exe()?);
+        cmd.arg("--locked")
+            .args(&self.opts.args)
+            .cwd(self.ws.root());
+        let passed = if gctx.shell().verbosity() == Verbosity::Verbose {
+            cmd.status()?.success()
+        } else {
+            cmd.output()?.status.success()
+        };
+        gctx.shell()
+            .status(if passed { "Passed" } else { "Failed" }, &what)?;
+
+        self.state.results.insert(key, passed);
+        paths::create_dir_all(self.state_path.parent().unwrap())?;
+        paths::write(&self.state_path, serde_json::to_string(&self.state)?)?;
+        Ok(passed)
+    }
+
+    /// Writes the good lock file with the given updates applied as
+    /// `Cargo.lock`.
+    fn write_lockfile(&self, applied: &[usize]) -> CargoResult<()> {
+        let ws = self.ws;
+        let _lock = ws
+            .gctx()
+            .acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+        let mut registry = ws.package_registry()?;
+
+        // Packages of the same registry are all updated through a single
+        // source, like with `cargo update --precise`.
+        let mut sources: Vec<SourceId> = Vec::new();
+        for &i in applied {
+            let Update { from, to } = &self.updates[i];
+            let source_id = match sources.iter().position(|s| *s == from.source_id()) {
+                Some(i) => sources.remove(i),
+                None => from.source_id(),
+            };
+            sources.push(if from.source_id().is_registry() {
+                source_id.with_precise_registry_version(
+                    from.name(),
+                    from.version().clone(),
+                    to.version().clone(),
+                )
+            } else {
+                to.source_id()
+            });
+        }
+        registry.add_sources(sources)?;
+
+        // Non-registry sources can't be locked at several revisions, so all
+        // the packages of an updated one are re-resolved.
+        let keep = |id: &PackageId| {
+            !applied.iter().any(|&i| {
+                let from = self.updates[i].from;
+                from == *id
+                    || (!from.source_id().is_registry() && from.source_id() == id.source_id())
+            })
+        };
+        let mut resolve = ops::resolve_with_previous(
+            &mut registry,
+            ws,
+            &CliFeatures::new_all(true),
+            HasDevUnits::Yes,
+            Some(self.good),
+            Some(&keep),
+            &[],
+            true,
+        )?;
+        ops::write_pkg_lockfile(ws, &mut resolve)?;
+        Ok(())
+    }
+}
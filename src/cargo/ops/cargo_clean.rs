@@ -5,13 +5,15 @@ use crate::ops;
 use crate::util::edit_distance;
 use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
-use crate::util::{human_readable_bytes, GlobalContext, Progress, ProgressStyle};
+use crate::util::toml::{script_target_dir, SCRIPT_PATH_FILE};
+use crate::util::{human_readable_bytes, Filesystem, GlobalContext, Progress, ProgressStyle};
 use anyhow::bail;
 use cargo_util::paths;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::{Duration, SystemTime};
 
 pub struct CleanOptions<'gctx> {
     pub gctx: &'gctx GlobalContext,
@@ -27,6 +29,12 @@ pub struct CleanOptions<'gctx> {
     pub doc: bool,
     /// If set, doesn't delete anything.
     pub dry_run: bool,
+    /// If set, only deletes the build artifacts that haven't been used
+    /// within this duration.
+    pub older_than: Option<Duration>,
+    /// If set, deletes the least recently used build artifacts until the
+    /// target directory is under this size.
+    pub max_size: Option<u64>,
 }
 
 pub struct CleanContext<'gctx> {
@@ -74,7 +82,16 @@ pub fn clean(ws: &Workspace<'_>, opts: &CleanOptions<'_>) -> CargoResult<()> {
         //
         // Note that we don't bother grabbing a lock here as we're just going to
         // blow it all away anyway.
-        if opts.spec.is_empty() {
+        if opts.older_than.is_some() || opts.max_size.is_some() {
+            let profile = opts.profile_specified.then(|| profiles.get_dir_name());
+            clean_unused(
+                &mut clean_ctx,
+                &ws.target_dir().into_path_unlocked(),
+                profile.as_deref(),
+                opts.older_than,
+                opts.max_size,
+            )?;
+        } else if opts.spec.is_empty() {
             clean_ctx.remove_paths(&[target_dir.into_path_unlocked()])?;
         } else {
             clean_specs(
@@ -186,6 +203,11 @@ fn clean_specs(
         let pkg_dir = format!("{}-*", pkg.name());
         clean_ctx.progress.on_cleaning_package(&pkg.name())?;
 
+        // Clean the member's own directory with `workspace.split-target-dirs`.
+        if let Some(member_dir) = ws.member_target_dir(pkg.package_id()) {
+            clean_ctx.rm_rf(member_dir.as_path_unlocked())?;
+        }
+
         // Clean fingerprints.
         for (_, layout) in &layouts_with_host {
             let dir = escape_glob_path(layout.fingerprint())?;
@@ -194,7 +216,7 @@ fn clean_specs(
         }
 
         for target in pkg.targets() {
-            if target.is_custom_build() {
+            if target.is_custom_build() || target.is_post_build() {
                 // Get both the build_script_build and the output directory.
                 for (_, layout) in &layouts_with_host {
                     let dir = escape_glob_path(layout.build())?;
@@ -283,6 +305,186 @@ fn clean_specs(
     Ok(())
 }
 
+/// Build artifacts that `cargo clean --older-than` and `--max-size` delete
+/// together: a unit's fingerprint along with its outputs, or an incremental
+/// compilation session.
+struct UnusedCandidate {
+    /// When the artifacts were last built or found fresh.
+    last_use: SystemTime,
+    size: u64,
+    paths: Vec<PathBuf>,
+}
+
+/// Deletes the build artifacts that haven't been used since `older_than`,
+/// then the least recently used ones until the target directory is under
+/// `max_size`.
+///
+/// A unit's fingerprint is deleted along with its outputs, so the next build
+/// rebuilds it instead of finding it fresh. When a unit is found fresh with
+/// `-Zgc`, its fingerprint is touched to record its last use.
+fn clean_unused(
+    clean_ctx: &mut CleanContext<'_>,
+    target_dir: &Path,
+    profile: Option<&str>,
+    older_than: Option<Duration>,
+    max_size: Option<u64>,
+) -> CargoResult<()> {
+    // Both `target/<profile>` and `target/<triple>/<profile>`.
+    let mut layouts = Vec::new();
+    for dir in read_dirs(target_dir)? {
+        if dir.join(".fingerprint").is_dir() {
+            layouts.push(dir);
+        } else if dir.is_dir() {
+            layouts.extend(
+                read_dirs(&dir)?
+                    .into_iter()
+                    .filter(|dir| dir.join(".fingerprint").is_dir()),
+            );
+        }
+    }
+    if let Some(profile) = profile {
+        layouts.retain(|dir| dir.file_name().is_some_and(|name| name == profile));
+    }
+
+    let mut _locks = Vec::new();
+    let mut candidates = Vec::new();
+    for layout in &layouts {
+        _locks.push(Filesystem::new(layout.clone()).open_rw_exclusive_create(
+            ".cargo-lock",
+            clean_ctx.gctx,
+            "build directory",
+        )?);
+        candidates.extend(unused_candidates(layout)?);
+    }
+    candidates.sort_by_key(|candidate| candidate.last_use);
+
+    let now = SystemTime::now();
+    let mut total_size: u64 = candidates.iter().map(|candidate| candidate.size).sum();
+    for candidate in candidates {
+        let too_old = older_than.is_some_and(|older_than| {
+            now.duration_since(candidate.last_use)
+                .is_ok_and(|age| age > older_than)
+        });
+        let too_big = max_size.is_some_and(|max_size| total_size > max_size);
+        // Candidates are sorted from the least recently used, so the rest are
+        // kept as well.
+        if !too_old && !too_big {
+            break;
+        }
+        for path in &candidate.paths {
+            clean_ctx.rm_rf(path)?;
+        }
+        total_size -= candidate.size;
+    }
+    Ok(())
+}
+
+/// The build artifacts of the `layout` directory of a profile, grouped by
+/// what must be deleted together.
+fn unused_candidates(layout: &Path) -> CargoResult<Vec<UnusedCandidate>> {
+    let mut outputs = read_dirs(&layout.join("deps"))?;
+    outputs.extend(read_dirs(&layout.join("examples"))?);
+
+    let mut candidates = Vec::new();
+    for fingerprint in read_dirs(&layout.join(".fingerprint"))? {
+        let Some(name) = fingerprint.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((_, hash)) = name.rsplit_once('-') else {
+            continue;
+        };
+        let mut paths = vec![fingerprint.clone()];
+        let build = layout.join("build").join(name);
+        if build.exists() {
+            paths.push(build);
+        }
+        // Outputs have the hash of their unit in their name, such as
+        // `libfoo-<hash>.rlib` or `foo-<hash>.d`.
+        paths.extend(outputs.iter().cloned().filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.split(['-', '.']).any(|part| part == hash))
+        }));
+        candidates.push(UnusedCandidate {
+            last_use: last_modified(&fingerprint),
+            size: paths.iter().map(|path| disk_size(path)).sum(),
+            paths,
+        });
+    }
+    // Incremental sessions are named after a hash of rustc rather than the
+    // unit, so they go on their own.
+    for incremental in read_dirs(&layout.join("incremental"))? {
+        candidates.push(UnusedCandidate {
+            last_use: last_modified(&incremental),
+            size: disk_size(&incremental),
+            paths: vec![incremental],
+        });
+    }
+    Ok(candidates)
+}
+
+/// The entries of `dir`, or nothing if it doesn't exist.
+fn read_dirs(dir: &Path) -> CargoResult<Vec<PathBuf>> {
+    match fs::read_dir(dir) {
+        Ok(entries) => Ok(entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => {
+            Err(anyhow::Error::from(e)
+                .context(format!("failed to read directory `{}`", dir.display())))
+        }
+    }
+}
+
+/// The newest modification time of the files in `path`.
+fn last_modified(path: &Path) -> SystemTime {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// The total size of the files in `path`.
+fn disk_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|meta| !meta.is_dir())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Removes the cached builds of `-Zscript` single-file packages that are out of
+/// date, because the script was removed, or its embedded manifest or the
+/// version of Cargo changed since it was built.
+pub fn clean_script_cache(gctx: &GlobalContext, dry_run: bool) -> CargoResult<()> {
+    let mut clean_ctx = CleanContext::new(gctx);
+    clean_ctx.dry_run = dry_run;
+    let cache = gctx.home().join("target").into_path_unlocked();
+    let pattern = format!("{}/*/*", escape_glob_path(&cache)?);
+    let mut stale = Vec::new();
+    for dir in glob::glob(&pattern)? {
+        let dir = dir?;
+        if !dir.is_dir() {
+            continue;
+        }
+        // Only directories created for a script are removed, anything else
+        // in there isn't ours.
+        let Ok(script) = paths::read(&dir.join(SCRIPT_PATH_FILE)) else {
+            continue;
+        };
+        let script = PathBuf::from(script);
+        let fresh = script.is_file() && script_target_dir(&script, gctx).as_path_unlocked() == dir;
+        if !fresh {
+            stale.push(dir);
+        }
+    }
+    clean_ctx.remove_paths(&stale)?;
+    clean_ctx.display_summary()
+}
+
 fn escape_glob_path(pattern: &Path) -> CargoResult<String> {
     let pattern = pattern
         .to_str()
@@ -179,7 +179,7 @@ impl CompileFilter {
     /// and [`generate_root_units`] though.
     ///
     /// [`generate_root_units`]: super::UnitGenerator::generate_root_units
-    /// [`filter_default_targets`]: super::UnitGenerator::filter_default_targets
+    /// [`filter_default_targets`]: super::unit_generator::filter_default_targets
     pub fn all_test_targets() -> Self {
         Self::Only {
             all_targets: false,
@@ -232,7 +232,7 @@ impl CompileFilter {
                     ..
                 } => examples.is_specific() || tests.is_specific() || benches.is_specific(),
             },
-            CompileMode::RunCustomBuild => panic!("Invalid mode"),
+            CompileMode::RunCustomBuild | CompileMode::RunPostBuild => panic!("Invalid mode"),
         }
     }
 
@@ -240,7 +240,7 @@ impl CompileFilter {
     /// subcommands, see [`generate_root_units`] and [`filter_default_targets`].
     ///
     /// [`generate_root_units`]: super::UnitGenerator::generate_root_units
-    /// [`filter_default_targets`]: super::UnitGenerator::filter_default_targets
+    /// [`filter_default_targets`]: super::unit_generator::filter_default_targets
     pub fn target_run(&self, target: &Target) -> bool {
         match *self {
             CompileFilter::Default { .. } => true,
@@ -264,7 +264,7 @@ impl CompileFilter {
                             LibRule::False => false,
                         };
                     }
-                    TargetKind::CustomBuild => return false,
+                    TargetKind::CustomBuild | TargetKind::PostBuild => return false,
                 };
                 rule.matches(target)
             }
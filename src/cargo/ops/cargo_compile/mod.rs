@@ -35,7 +35,7 @@
 //! [`drain_the_queue`]: crate::core::compiler::job_queue
 //! ["Cargo Target"]: https://doc.rust-lang.org/nightly/cargo/reference/cargo-targets.html
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
@@ -48,12 +48,14 @@ use crate::core::compiler::{DefaultExecutor, Executor, UnitInterner};
 use crate::core::profiles::Profiles;
 use crate::core::resolver::features::{self, CliFeatures, FeaturesFor};
 use crate::core::resolver::{HasDevUnits, Resolve};
-use crate::core::{PackageId, PackageSet, SourceId, TargetKind, Workspace};
+use crate::core::{MaybePackage, PackageId, PackageIdSpec, PackageIdSpecQuery, PackageSet};
+use crate::core::{SourceId, SourcePolicyScope, TargetKind, Workspace};
 use crate::drop_println;
 use crate::ops;
 use crate::ops::resolve::WorkspaceResolve;
 use crate::util::context::GlobalContext;
 use crate::util::interning::InternedString;
+use crate::util::machine_message::BuildStatus;
 use crate::util::{CargoResult, StableHasher};
 
 mod compile_filter;
@@ -80,6 +82,8 @@ pub struct CompileOptions {
     pub build_config: BuildConfig,
     /// Feature flags requested by the user.
     pub cli_features: CliFeatures,
+    /// Whether to also enable the `required-features` of the selected targets.
+    pub all_required_features: bool,
     /// A set of packages to build.
     pub spec: Packages,
     /// Filter to apply to the root package to select which targets will be
@@ -107,6 +111,7 @@ impl CompileOptions {
         Ok(CompileOptions {
             build_config: BuildConfig::new(gctx, jobs, keep_going, &[], mode)?,
             cli_features: CliFeatures::new_all(false),
+            all_required_features: false,
             spec: ops::Packages::Packages(Vec::new()),
             filter: CompileFilter::Default {
                 required_features_filterable: false,
@@ -207,6 +212,7 @@ pub fn create_bcx<'a, 'gctx>(
         ref build_config,
         ref spec,
         ref cli_features,
+        all_required_features,
         ref filter,
         ref target_rustdoc_args,
         ref target_rustc_args,
@@ -217,12 +223,14 @@ pub fn create_bcx<'a, 'gctx>(
     let gctx = ws.gctx();
 
     // Perform some pre-flight validation.
+    ws.check_source_policy(SourcePolicyScope::Profile(&build_config.requested_profile))?;
     match build_config.mode {
         CompileMode::Test
         | CompileMode::Build
         | CompileMode::Check { .. }
         | CompileMode::Bench
-        | CompileMode::RunCustomBuild => {
+        | CompileMode::RunCustomBuild
+        | CompileMode::RunPostBuild => {
             if ws.gctx().get_env("RUST_FLAGS").is_ok() {
                 gctx.shell().warn(
                     "Cargo does not read `RUST_FLAGS` environment variable. Did you mean `RUSTFLAGS`?",
@@ -238,10 +246,23 @@ pub fn create_bcx<'a, 'gctx>(
         }
     }
     gctx.validate_term_config()?;
+    if let Some(profile) = gctx.lints_profile() {
+        validate_lints_profile(ws, profile)?;
+    }
 
     let mut target_data = RustcTargetData::new(ws, &build_config.requested_kinds)?;
+    if let Some(clippy) = &build_config.clippy {
+        // Replacing the workspace wrapper also keeps the artifacts of clippy
+        // apart from those of `cargo check`, see `compute_metadata`.
+        target_data.rustc.workspace_wrapper = Some(clippy.driver.clone());
+    }
 
     let specs = spec.to_package_id_specs(ws)?;
+    let cli_features = &if all_required_features {
+        with_required_features(ws, &specs, filter, build_config.mode, cli_features)?
+    } else {
+        cli_features.clone()
+    };
     let has_dev_units = {
         // Rustdoc itself doesn't need dev-dependencies. But to scrape examples from packages in the
         // workspace, if any of those packages need dev-dependencies, then we need include dev-dependencies
@@ -264,6 +285,9 @@ pub fn create_bcx<'a, 'gctx>(
         }
     };
     let dry_run = false;
+    if !build_config.build_plan {
+        BuildStatus::Resolving.emit(&mut gctx.shell())?;
+    }
     let resolve = ops::resolve_ws_with_opts(
         ws,
         &mut target_data,
@@ -378,6 +402,9 @@ pub fn create_bcx<'a, 'gctx>(
         override_rustc_crate_types(&mut units, args, interner)?;
     }
 
+    let post_build_units = generator.generate_post_build_units(&units);
+    units.extend(post_build_units);
+
     let should_scrape = build_config.mode.is_doc() && gctx.cli_unstable().rustdoc_scrape_examples;
     let mut scrape_units = if should_scrape {
         UnitGenerator {
@@ -440,9 +467,19 @@ pub fn create_bcx<'a, 'gctx>(
         host_kind_requested.then_some(explicit_host_kind),
     );
 
+    if build_config.only_deps {
+        units = only_deps_roots(&units, &mut unit_graph);
+    }
+
     let mut extra_compiler_args = HashMap::new();
     if let Some(args) = extra_args {
-        if units.len() != 1 {
+        // Post-build scripts run on their own, after the target the arguments
+        // are for.
+        let compiled_units = units
+            .iter()
+            .filter(|unit| !unit.mode.is_run_post_build())
+            .collect::<Vec<_>>();
+        if compiled_units.len() != 1 {
             anyhow::bail!(
                 "extra arguments to `{}` can only be passed to one \
                  target, consider filtering\nthe package by passing, \
@@ -450,7 +487,18 @@ pub fn create_bcx<'a, 'gctx>(
                 extra_args_name
             );
         }
-        extra_compiler_args.insert(units[0].clone(), args);
+        extra_compiler_args.insert(compiled_units[0].clone(), args);
+    }
+
+    if build_config.unused_dependencies {
+        for unit in units.iter().filter(|unit| {
+            !unit.mode.is_doc() && !unit.mode.is_doc_test() && !unit.mode.is_run_post_build()
+        }) {
+            extra_compiler_args
+                .entry(unit.clone())
+                .or_default()
+                .push("-Wunused-crate-dependencies".into());
+        }
     }
 
     for unit in units
@@ -523,6 +571,47 @@ where `<compatible-ver>` is the latest version supporting rustc {rustc_version}"
             }
             return Err(anyhow::Error::msg(message));
         }
+
+        if let Some(ws_msrv) = ws.workspace_rust_version() {
+            let root = ws.root_manifest().display();
+            if !ws_msrv.is_compatible_with(&rustc_version) {
+                gctx.shell().warn(format!(
+                    "rustc {rustc_version} is not supported by the workspace at `{root}`\n  \
+                     `workspace.package.rust-version` requires rustc {ws_msrv}"
+                ))?;
+            }
+
+            let newer = unit_graph
+                .keys()
+                .filter(|unit| ws.is_member(&unit.pkg))
+                .filter_map(|unit| Some((unit.pkg.package_id(), unit.pkg.rust_version()?)))
+                .filter(|(_, msrv)| !msrv.is_compatible_with(ws_msrv.as_partial()))
+                .collect::<BTreeSet<_>>();
+            if !newer.is_empty() {
+                use std::fmt::Write as _;
+
+                let members = if newer.len() == 1 {
+                    "member requires"
+                } else {
+                    "members require"
+                };
+                let mut message = format!(
+                    "`workspace.package.rust-version` of `{root}` is rustc {ws_msrv}, \
+                     but the following {members} a newer rustc:\n"
+                );
+                for (pkg_id, msrv) in newer {
+                    let name = pkg_id.name();
+                    let version = pkg_id.version();
+                    writeln!(&mut message, "  {name}@{version} requires rustc {msrv}").unwrap();
+                }
+                write!(
+                    &mut message,
+                    "Either raise `workspace.package.rust-version` or lower the `rust-version` of these members"
+                )
+                .unwrap();
+                gctx.shell().warn(message)?;
+            }
+        }
     }
 
     let bcx = BuildContext::new(
@@ -540,6 +629,88 @@ where `<compatible-ver>` is the latest version supporting rustc {rustc_version}"
     Ok(bcx)
 }
 
+/// Checks that the profile of `--lints-profile` is defined somewhere in the
+/// workspace, as packages that don't define it silently keep their default
+/// lints.
+fn validate_lints_profile(ws: &Workspace<'_>, profile: &str) -> CargoResult<()> {
+    let root_toml = match ws.root_maybe() {
+        MaybePackage::Package(pkg) => pkg.manifest().original_toml(),
+        MaybePackage::Virtual(vm) => vm.original_toml(),
+    };
+    let in_workspace = root_toml
+        .workspace
+        .as_ref()
+        .and_then(|ws| ws.lints.as_ref())
+        .is_some_and(|lints| lints.profiles.contains_key(profile));
+    let in_members = ws.members().any(|pkg| {
+        pkg.manifest()
+            .original_toml()
+            .lints
+            .as_ref()
+            .is_some_and(|lints| lints.profiles.contains_key(profile))
+    });
+    if !in_workspace && !in_members {
+        anyhow::bail!(
+            "lints profile `{profile}` is not defined in `[lints]` or `[workspace.lints]` \
+             of any package in the workspace"
+        );
+    }
+    Ok(())
+}
+
+/// Adds the `required-features` of the targets selected by `filter` in the
+/// members matched by `specs` to `cli_features`, for `--all-required-features`.
+fn with_required_features(
+    ws: &Workspace<'_>,
+    specs: &[PackageIdSpec],
+    filter: &CompileFilter,
+    mode: CompileMode,
+    cli_features: &CliFeatures,
+) -> CargoResult<CliFeatures> {
+    let mut features: Vec<String> = cli_features
+        .features
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+    let members = ws
+        .members()
+        .filter(|m| specs.iter().any(|spec| spec.matches(m.package_id())));
+    for member in members {
+        let targets = match filter {
+            CompileFilter::Default { .. } => {
+                unit_generator::filter_default_targets(member.targets(), mode)
+            }
+            CompileFilter::Only { .. } => member
+                .targets()
+                .iter()
+                .filter(|t| filter.target_run(t))
+                .collect(),
+        };
+        let required_features = targets
+            .into_iter()
+            .filter(|t| !t.is_lib())
+            .filter_map(|t| t.required_features())
+            .flatten();
+        for feature in required_features {
+            // Without the new feature behavior, plain features only apply to
+            // the package in the current directory. `dep/feat` values can't be
+            // qualified with the package, so they stay as they are.
+            if feature.contains('/')
+                || (!ws.allows_new_cli_feature_behavior() && ws.current_opt() == Some(member))
+            {
+                features.push(feature.clone());
+            } else {
+                features.push(format!("{}/{feature}", member.name()));
+            }
+        }
+    }
+    CliFeatures::from_command_line(
+        &features,
+        cli_features.all_features,
+        cli_features.uses_default_features,
+    )
+}
+
 /// This is used to rebuild the unit graph, sharing host dependencies if possible,
 /// and applying other unit adjustments based on the whole graph.
 ///
@@ -852,6 +1023,39 @@ fn remove_duplicate_doc(
     unit_graph.retain(|unit, _| visited.contains(unit));
 }
 
+/// Replaces `roots` with the units they depend on, directly or through other
+/// units of their packages, that belong to other packages, for `--only-deps`.
+///
+/// The units that are no longer needed are removed from `unit_graph`.
+fn only_deps_roots(roots: &[Unit], unit_graph: &mut UnitGraph) -> Vec<Unit> {
+    let root_pkgs: HashSet<_> = roots.iter().map(|unit| unit.pkg.package_id()).collect();
+    let mut visited = HashSet::new();
+    let mut deps = BTreeSet::new();
+    let mut queue: Vec<&Unit> = roots.iter().collect();
+    while let Some(unit) = queue.pop() {
+        if !visited.insert(unit) {
+            continue;
+        }
+        for dep in &unit_graph[unit] {
+            if root_pkgs.contains(&dep.unit.pkg.package_id()) {
+                queue.push(&dep.unit);
+            } else {
+                deps.insert(dep.unit.clone());
+            }
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    let mut queue: Vec<&Unit> = deps.iter().collect();
+    while let Some(unit) = queue.pop() {
+        if reachable.insert(unit.clone()) {
+            queue.extend(unit_graph[unit].iter().map(|dep| &dep.unit));
+        }
+    }
+    unit_graph.retain(|unit, _| reachable.contains(unit));
+    deps.into_iter().collect()
+}
+
 /// Override crate types for given units.
 ///
 /// This is primarily used by `cargo rustc --crate-type`.
@@ -900,8 +1104,8 @@ fn override_rustc_crate_types(
     Ok(())
 }
 
-/// Gets all of the features enabled for a package, plus its dependencies'
-/// features.
+/// Gets all of the features enabled for a package built for `kind`, plus its
+/// dependencies' features.
 ///
 /// Dependencies are added as `dep_name/feat_name` because `required-features`
 /// wants to support that syntax.
@@ -910,9 +1114,10 @@ pub fn resolve_all_features(
     resolved_features: &features::ResolvedFeatures,
     package_set: &PackageSet<'_>,
     package_id: PackageId,
+    kind: CompileKind,
 ) -> HashSet<String> {
     let mut features: HashSet<String> = resolved_features
-        .activated_features(package_id, FeaturesFor::NormalOrDev)
+        .activated_features(package_id, FeaturesFor::from_for_host_and_kind(false, kind))
         .iter()
         .map(|s| s.to_string())
         .collect();
@@ -925,7 +1130,8 @@ pub fn resolve_all_features(
             .expect("packages downloaded")
             .proc_macro();
         for dep in deps {
-            let features_for = FeaturesFor::from_for_host(is_proc_macro || dep.is_build());
+            let features_for =
+                FeaturesFor::from_for_host_and_kind(is_proc_macro || dep.is_build(), kind);
             for feature in resolved_features
                 .activated_features_unverified(dep_id, features_for)
                 .unwrap_or_default()
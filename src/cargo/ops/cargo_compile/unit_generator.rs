@@ -99,12 +99,6 @@ impl<'a> UnitGenerator<'a, '_> {
 
         let is_local = pkg.package_id().source_id().is_path();
 
-        // No need to worry about build-dependencies, roots are never build dependencies.
-        let features_for = FeaturesFor::from_for_host(target.proc_macro());
-        let features = self
-            .resolved_features
-            .activated_features(pkg.package_id(), features_for);
-
         // If `--target` has not been specified, then the unit
         // graph is built almost like if `--target $HOST` was
         // specified. See `rebuild_unit_graph_shared` for more on
@@ -162,13 +156,18 @@ impl<'a> UnitGenerator<'a, '_> {
                     unit_for,
                     kind,
                 );
+                // No need to worry about build-dependencies, roots are never build dependencies.
+                let features_for = FeaturesFor::from_for_host_and_kind(target.proc_macro(), kind);
+                let features = self
+                    .resolved_features
+                    .activated_features(pkg.package_id(), features_for);
                 self.interner.intern(
                     pkg,
                     target,
                     profile,
                     kind.for_target(target),
                     target_mode,
-                    features.clone(),
+                    features,
                     /*is_std*/ false,
                     /*dep_hash*/ 0,
                     IsArtifact::No,
@@ -178,38 +177,6 @@ impl<'a> UnitGenerator<'a, '_> {
             .collect()
     }
 
-    /// Given a list of all targets for a package, filters out only the targets
-    /// that are automatically included when the user doesn't specify any targets.
-    fn filter_default_targets<'b>(&self, targets: &'b [Target]) -> Vec<&'b Target> {
-        match self.mode {
-            CompileMode::Bench => targets.iter().filter(|t| t.benched()).collect(),
-            CompileMode::Test => targets
-                .iter()
-                .filter(|t| t.tested() || t.is_example())
-                .collect(),
-            CompileMode::Build | CompileMode::Check { .. } => targets
-                .iter()
-                .filter(|t| t.is_bin() || t.is_lib())
-                .collect(),
-            CompileMode::Doc { .. } => {
-                // `doc` does lib and bins (bin with same name as lib is skipped).
-                targets
-                    .iter()
-                    .filter(|t| {
-                        t.documented()
-                            && (!t.is_bin()
-                                || !targets
-                                    .iter()
-                                    .any(|l| l.is_lib() && l.crate_name() == t.crate_name()))
-                    })
-                    .collect()
-            }
-            CompileMode::Doctest | CompileMode::RunCustomBuild | CompileMode::Docscrape => {
-                panic!("Invalid mode {:?}", self.mode)
-            }
-        }
-    }
-
     /// Filters the set of all possible targets based on the provided predicate.
     fn filter_targets(
         &self,
@@ -325,7 +292,7 @@ impl<'a> UnitGenerator<'a, '_> {
                 required_features_filterable,
             } => {
                 for pkg in self.packages {
-                    let default = self.filter_default_targets(pkg.targets());
+                    let default = filter_default_targets(pkg.targets(), self.mode);
                     proposals.extend(default.into_iter().map(|target| Proposal {
                         pkg,
                         target,
@@ -635,7 +602,7 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
         // Only include targets that are libraries or have all required
         // features available.
         //
-        // `features_map` is a map of &Package -> enabled_features
+        // `features_map` is a map of (&Package, CompileKind) -> enabled_features
         // It is computed by the set of enabled features for the package plus
         // every enabled feature of every enabled dependency.
         let mut features_map = HashMap::new();
@@ -650,40 +617,74 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
             mode,
         } in proposals
         {
-            let unavailable_features = match target.required_features() {
-                Some(rf) => {
-                    self.validate_required_features(target.name(), rf, pkg.summary())?;
-
-                    let features = features_map.entry(pkg).or_insert_with(|| {
-                        super::resolve_all_features(
-                            self.resolve,
-                            self.resolved_features,
-                            self.package_set,
-                            pkg.package_id(),
-                        )
-                    });
-                    rf.iter().filter(|f| !features.contains(*f)).collect()
+            if let Some(rf) = target.required_features() {
+                self.validate_required_features(target.name(), rf, pkg.summary())?;
+            }
+            // Features may be unified separately for each `--target`, so the
+            // required features are checked for each unit's kind.
+            let mut unavailable_features = Vec::new();
+            for unit in self.new_units(pkg, target, mode) {
+                let missing: Vec<&str> = match target.required_features() {
+                    Some(rf) => {
+                        let features = features_map.entry((pkg, unit.kind)).or_insert_with(|| {
+                            super::resolve_all_features(
+                                self.resolve,
+                                self.resolved_features,
+                                self.package_set,
+                                pkg.package_id(),
+                                unit.kind,
+                            )
+                        });
+                        rf.iter()
+                            .filter(|f| !features.contains(*f))
+                            .map(String::as_str)
+                            .collect()
+                    }
+                    None => Vec::new(),
+                };
+                if target.is_lib() || missing.is_empty() {
+                    units.insert(unit);
+                } else if unavailable_features.is_empty() {
+                    unavailable_features = missing;
                 }
-                None => Vec::new(),
-            };
-            if target.is_lib() || unavailable_features.is_empty() {
-                units.extend(self.new_units(pkg, target, mode));
-            } else if requires_features {
+            }
+            if unavailable_features.is_empty() {
+                continue;
+            }
+            if requires_features {
                 let required_features = target.required_features().unwrap();
-                let quoted_required_features: Vec<String> = required_features
-                    .iter()
-                    .map(|s| format!("`{}`", s))
-                    .collect();
-                anyhow::bail!(
-                    "target `{}` in package `{}` requires the features: {}\n\
-               Consider enabling them by passing, e.g., `--features=\"{}\"`",
+                let mut msg = format!(
+                    "target `{}` in package `{}` requires the features: {}\n",
                     target.name(),
                     pkg.name(),
-                    quoted_required_features.join(", "),
-                    required_features.join(" ")
+                    quote_features(required_features),
                 );
+                if unavailable_features.len() < required_features.len() {
+                    writeln!(
+                        msg,
+                        "missing features: {}",
+                        quote_features(&unavailable_features)
+                    )?;
+                }
+                write!(
+                    msg,
+                    "Consider enabling them by passing, e.g., `--features=\"{}\"`",
+                    unavailable_features.join(" ")
+                )?;
+                anyhow::bail!(msg);
+            } else {
+                // Skip the target, only telling why in verbose mode.
+                self.ws.gctx().shell().verbose(|shell| {
+                    shell.note(format!(
+                        "skipping target `{}` in package `{}`, it requires the missing \
+                         features: {} (enable them with `--features=\"{}\"`)",
+                        target.name(),
+                        pkg.name(),
+                        quote_features(&unavailable_features),
+                        unavailable_features.join(" ")
+                    ))
+                })?;
             }
-            // else, silently skip target.
         }
         let mut units: Vec<_> = units.into_iter().collect();
         self.unmatched_target_filters(&units)?;
@@ -702,6 +703,45 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
         self.proposals_to_units(proposals)
     }
 
+    /// Generates the units running the post-build script of each package
+    /// whose binaries are built by `units`, one per package and compile kind.
+    ///
+    /// The binaries themselves are attached as dependencies of these units in
+    /// [`unit_dependencies`].
+    ///
+    /// [`unit_dependencies`]: crate::core::compiler::unit_dependencies
+    pub fn generate_post_build_units(&self, units: &[Unit]) -> Vec<Unit> {
+        let mut post_build_units = Vec::new();
+        let mut seen = HashSet::new();
+        for unit in units {
+            if unit.mode != CompileMode::Build || !unit.target.is_bin() {
+                continue;
+            }
+            let Some(post_build) = unit.pkg.targets().iter().find(|t| t.is_post_build()) else {
+                continue;
+            };
+            if !seen.insert((unit.pkg.package_id(), unit.kind)) {
+                continue;
+            }
+            // Like for running a build script, the profile is only used for
+            // the environment variables of the script.
+            let profile = self.profiles.get_profile_run_custom_build(&unit.profile);
+            post_build_units.push(self.interner.intern(
+                &unit.pkg,
+                post_build,
+                profile,
+                unit.kind,
+                CompileMode::RunPostBuild,
+                unit.features.clone(),
+                /*is_std*/ false,
+                /*dep_hash*/ 0,
+                IsArtifact::No,
+                None,
+            ));
+        }
+        post_build_units
+    }
+
     /// Generates units specifically for doc-scraping.
     ///
     /// This requires a separate entrypoint from [`generate_root_units`] because it
@@ -714,3 +754,47 @@ Rustdoc did not scrape the following examples because they require dev-dependenc
         Ok(scrape_units)
     }
 }
+
+/// Given a list of all targets for a package, filters out only the targets
+/// that are automatically included when the user doesn't specify any targets.
+pub(super) fn filter_default_targets(targets: &[Target], mode: CompileMode) -> Vec<&Target> {
+    match mode {
+        CompileMode::Bench => targets.iter().filter(|t| t.benched()).collect(),
+        CompileMode::Test => targets
+            .iter()
+            .filter(|t| t.tested() || t.is_example())
+            .collect(),
+        CompileMode::Build | CompileMode::Check { .. } => targets
+            .iter()
+            .filter(|t| t.is_bin() || t.is_lib())
+            .collect(),
+        CompileMode::Doc { .. } => {
+            // `doc` does lib and bins (bin with same name as lib is skipped).
+            targets
+                .iter()
+                .filter(|t| {
+                    t.documented()
+                        && (!t.is_bin()
+                            || !targets
+                                .iter()
+                                .any(|l| l.is_lib() && l.crate_name() == t.crate_name()))
+                })
+                .collect()
+        }
+        CompileMode::Doctest
+        | CompileMode::RunCustomBuild
+        | CompileMode::RunPostBuild
+        | CompileMode::Docscrape => {
+            panic!("Invalid mode {:?}", mode)
+        }
+    }
+}
+
+/// Formats `features` as a comma separated list of code spans.
+fn quote_features<S: AsRef<str>>(features: &[S]) -> String {
+    features
+        .iter()
+        .map(|f| format!("`{}`", f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,12 +1,16 @@
 //! Implementation of `cargo config` subcommand.
 
+use crate::core::find_workspace_root;
 use crate::util::context::{ConfigKey, ConfigValue as CV, Definition, GlobalContext};
 use crate::util::errors::CargoResult;
+use crate::util::important_paths::find_root_manifest_for_wd;
 use crate::{drop_eprintln, drop_println};
-use anyhow::{bail, format_err, Error};
+use anyhow::{bail, format_err, Context as _, Error};
+use cargo_util::paths;
 use serde_json::json;
 use std::borrow::Cow;
 use std::fmt;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 pub enum ConfigFormat {
@@ -312,3 +316,159 @@ fn print_toml_unmerged(
     }
     Ok(())
 }
+
+/// The config file `cargo config set` and `cargo config unset` edit.
+pub enum ConfigTarget {
+    /// `config.toml` in the Cargo home directory.
+    Global,
+    /// `.cargo/config.toml` in the root of the current workspace.
+    Workspace,
+}
+
+/// Options for `cargo config set`.
+pub struct SetOptions<'a> {
+    pub key: &'a str,
+    /// A TOML value, or else taken as a string.
+    pub value: &'a str,
+    pub target: ConfigTarget,
+}
+
+/// Options for `cargo config unset`.
+pub struct UnsetOptions<'a> {
+    pub key: &'a str,
+    pub target: ConfigTarget,
+}
+
+pub fn set(gctx: &GlobalContext, opts: &SetOptions<'_>) -> CargoResult<()> {
+    let key = ConfigKey::from_str(opts.key);
+    let parts: Vec<_> = key.parts().collect();
+    if parts.is_empty() {
+        bail!("config key must not be empty");
+    }
+    let mut value = toml_edit::Value::from_str(opts.value).unwrap_or_else(|_| opts.value.into());
+    value.decor_mut().clear();
+
+    let path = target_path(gctx, &opts.target)?;
+    let mut doc = read_document(&path)?;
+    let mut table = doc.as_table_mut() as &mut dyn toml_edit::TableLike;
+    for (i, part) in parts[..parts.len() - 1].iter().enumerate() {
+        if table.get(part).is_none() {
+            let mut new_table = toml_edit::Table::new();
+            new_table.set_implicit(true);
+            table.insert(part, toml_edit::Item::Table(new_table));
+        }
+        table = match table.get_mut(part).unwrap().as_table_like_mut() {
+            Some(table) => table,
+            None => bail!(
+                "expected table for configuration key `{}` in `{}`",
+                parts[..=i].join("."),
+                path.display()
+            ),
+        };
+    }
+    let last = parts[parts.len() - 1];
+    if table.get(last).is_some_and(|item| item.is_table_like()) {
+        bail!(
+            "configuration key `{key}` is a table in `{}`, set its keys instead",
+            path.display()
+        );
+    }
+    table.insert(last, toml_edit::Item::Value(value.clone()));
+    write_document(&path, &doc)?;
+    gctx.shell().status(
+        "Adding",
+        format!("`{key} = {value}` to `{}`", path.display()),
+    )?;
+
+    // Values from elsewhere may still win over the one just written.
+    if let Some(cv) = gctx.get_cv_with_env(&key)? {
+        let def = cv.definition();
+        if !matches!(cv, CV::Table(..)) && takes_precedence(def, &path, &opts.target) {
+            gctx.shell().warn(format!(
+                "`{key}` is also set by {def}, which takes precedence"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn unset(gctx: &GlobalContext, opts: &UnsetOptions<'_>) -> CargoResult<()> {
+    let key = ConfigKey::from_str(opts.key);
+    let parts: Vec<_> = key.parts().collect();
+    if parts.is_empty() {
+        bail!("config key must not be empty");
+    }
+
+    let path = target_path(gctx, &opts.target)?;
+    let mut doc = read_document(&path)?;
+    if !remove_key(doc.as_table_mut(), &parts) {
+        bail!("config value `{key}` is not set in `{}`", path.display());
+    }
+    write_document(&path, &doc)?;
+    gctx.shell()
+        .status("Removing", format!("`{key}` from `{}`", path.display()))?;
+    Ok(())
+}
+
+/// Removes the key made of `parts` from `table`, along with the tables left
+/// empty. Returns whether the key was found.
+fn remove_key(table: &mut dyn toml_edit::TableLike, parts: &[&str]) -> bool {
+    let (first, rest) = parts.split_first().unwrap();
+    if rest.is_empty() {
+        return table.remove(first).is_some();
+    }
+    let Some(inner) = table.get_mut(first).and_then(|i| i.as_table_like_mut()) else {
+        return false;
+    };
+    let removed = remove_key(inner, rest);
+    if removed && inner.is_empty() {
+        table.remove(first);
+    }
+    removed
+}
+
+/// Finds the config file of `target`, which may not exist yet.
+fn target_path(gctx: &GlobalContext, target: &ConfigTarget) -> CargoResult<PathBuf> {
+    let dir = match target {
+        ConfigTarget::Global => gctx.home().as_path_unlocked().to_path_buf(),
+        ConfigTarget::Workspace => {
+            let manifest = find_root_manifest_for_wd(gctx.cwd()).context(
+                "`cargo config` needs a workspace to edit, \
+                 or `--global` to edit the config of the Cargo home instead",
+            )?;
+            let root = find_workspace_root(&manifest, gctx)?.unwrap_or(manifest);
+            root.parent().unwrap().join(".cargo")
+        }
+    };
+    Ok(gctx
+        .get_file_path(&dir, "config", false)?
+        .unwrap_or_else(|| dir.join("config.toml")))
+}
+
+/// Whether a config value defined at `def` overrides the one in the config
+/// file at `path` of `target`.
+fn takes_precedence(def: &Definition, path: &Path, target: &ConfigTarget) -> bool {
+    match def {
+        Definition::Cli(_) | Definition::Environment(_) => true,
+        Definition::Path(p) if p == path => false,
+        // The config of the Cargo home is merged last.
+        Definition::Path(_) if matches!(target, ConfigTarget::Global) => true,
+        // Config files in the workspace, closer to the current directory.
+        Definition::Path(p) => p.starts_with(path.parent().unwrap().parent().unwrap()),
+    }
+}
+
+fn read_document(path: &Path) -> CargoResult<toml_edit::DocumentMut> {
+    if !path.exists() {
+        return Ok(toml_edit::DocumentMut::new());
+    }
+    let contents = paths::read(path)?;
+    contents
+        .parse()
+        .with_context(|| format!("could not parse TOML configuration in `{}`", path.display()))
+}
+
+fn write_document(path: &Path, doc: &toml_edit::DocumentMut) -> CargoResult<()> {
+    paths::create_dir_all(path.parent().unwrap())?;
+    paths::write(path, doc.to_string())
+}
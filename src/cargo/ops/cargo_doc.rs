@@ -1,13 +1,20 @@
 use crate::core::compiler::{Compilation, CompileKind};
 use crate::core::{shell::Verbosity, Shell, Workspace};
 use crate::ops;
+use crate::ops::watch::{describe_changes, Watcher};
 use crate::util::context::{GlobalContext, PathAndArgs};
 use crate::util::CargoResult;
-use anyhow::{bail, Error};
+use anyhow::{bail, Context as _, Error};
+use std::cell::RefCell;
+use std::ffi::OsStr;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
+use std::rc::Rc;
 use std::str::FromStr;
+use std::thread;
 
 /// Format of rustdoc [`--output-format`][1].
 ///
@@ -47,32 +54,31 @@ pub struct DocOptions {
     pub open_result: bool,
     /// Same as `rustdoc --output-format`
     pub output_format: OutputFormat,
+    /// Address to serve the docs on over HTTP, rebuilding them when the
+    /// sources change, instead of exiting after compiling them
+    pub serve: Option<SocketAddr>,
     /// Options to pass through to the compiler
     pub compile_opts: ops::CompileOptions,
 }
 
 /// Main method for `cargo doc`.
 pub fn doc(ws: &Workspace<'_>, options: &DocOptions) -> CargoResult<()> {
+    if let Some(addr) = options.serve {
+        return serve(ws, options, addr);
+    }
+
     let compilation = ops::compile(ws, &options.compile_opts)?;
 
     if options.open_result {
-        let name = &compilation
-            .root_crate_names
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("no crates with documentation"))?;
         let kind = options.compile_opts.build_config.single_requested_kind()?;
-
-        let path = path_by_output_format(&compilation, &kind, &name, &options.output_format);
+        let path = page_to_open(ws, options, &compilation, &kind)?;
 
         if path.exists() {
-            let config_browser = {
-                let cfg: Option<PathAndArgs> = ws.gctx().get("doc.browser")?;
-                cfg.map(|path_args| (path_args.path.resolve_program(ws.gctx()), path_args.args))
-            };
+            let config_browser = config_browser(ws.gctx())?;
             let mut shell = ws.gctx().shell();
             let link = shell.err_file_hyperlink(&path);
             shell.status("Opening", format!("{link}{}{link:#}", path.display()))?;
-            open_docs(&path, &mut shell, config_browser, ws.gctx())?;
+            open_docs(path.as_os_str(), &mut shell, config_browser, ws.gctx())?;
         }
     } else if ws.gctx().shell().verbosity() == Verbosity::Verbose {
         for name in &compilation.root_crate_names {
@@ -116,6 +122,180 @@ pub fn doc(ws: &Workspace<'_>, options: &DocOptions) -> CargoResult<()> {
     Ok(())
 }
 
+/// Documents the packages and serves the documentation directory over HTTP on
+/// `addr`, and documents them again whenever one of their files changes.
+///
+/// This never returns unless an error occurs before serving; the user stops it
+/// with Ctrl-C.
+fn serve(ws: &Workspace<'_>, options: &DocOptions, addr: SocketAddr) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let mut compile_opts = options.compile_opts.clone();
+    let watched_files = Rc::new(RefCell::new(Vec::new()));
+    compile_opts.build_config.watched_files = Some(Rc::clone(&watched_files));
+    let compilation = ops::compile(ws, &compile_opts)?;
+    let compilation = &compilation;
+
+    let kind = options.compile_opts.build_config.single_requested_kind()?;
+    let doc_dir = compilation.root_output[&kind].with_file_name("doc");
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("failed to serve the documentation on `{addr}`"))?;
+    let url = format!("http://{}/", listener.local_addr()?);
+    thread::spawn(move || serve_dir(listener, doc_dir));
+    gctx.shell().status("Serving", &url)?;
+
+    if options.open_result {
+        let path = page_to_open(ws, options, compilation, &kind)?;
+        let page = path.strip_prefix(compilation.root_output[&kind].with_file_name("doc"))?;
+        let page = page
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>();
+        let page_url = format!("{url}{}", page.join("/"));
+        let config_browser = config_browser(gctx)?;
+        let mut shell = gctx.shell();
+        shell.status("Opening", &page_url)?;
+        open_docs(OsStr::new(&page_url), &mut shell, config_browser, gctx)?;
+    }
+
+    let mut watcher = Watcher::new(watched_files.take());
+    loop {
+        let changed = watcher.wait();
+        gctx.shell()
+            .status("Rebuilding", describe_changes(&changed, ws.root()))?;
+        if let Err(e) = ops::compile(ws, &compile_opts) {
+            crate::display_error(&e, &mut gctx.shell());
+        }
+        // Files may have been added or removed.
+        watcher.watch(watched_files.take());
+    }
+}
+
+/// Accepts connections to the documentation server until the process exits.
+fn serve_dir(listener: TcpListener, doc_dir: PathBuf) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let doc_dir = doc_dir.clone();
+        thread::spawn(move || {
+            if let Err(e) = serve_request(stream, &doc_dir) {
+                tracing::debug!("failed to serve documentation request: {e}");
+            }
+        });
+    }
+}
+
+/// Answers a single HTTP request with a file of `doc_dir`.
+fn serve_request(stream: TcpStream, doc_dir: &Path) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // The headers don't matter, but reading them avoids resetting the
+    // connection before the client is done sending its request.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (status, body, content_type) = match (parts.next(), parts.next()) {
+        (Some("GET" | "HEAD"), Some(target)) => match resolve_request(doc_dir, target) {
+            Some(path) => match std::fs::read(&path) {
+                Ok(body) => ("200 OK", body, content_type(&path)),
+                Err(_) => ("404 Not Found", b"not found".to_vec(), "text/plain"),
+            },
+            None => ("404 Not Found", b"not found".to_vec(), "text/plain"),
+        },
+        _ => (
+            "405 Method Not Allowed",
+            b"method not allowed".to_vec(),
+            "text/plain",
+        ),
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Cache-Control: no-cache\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    )?;
+    if !request.starts_with("HEAD ") {
+        stream.write_all(&body)?;
+    }
+    stream.flush()
+}
+
+/// Maps the target of a request to a file of `doc_dir`, refusing paths that
+/// would escape it.
+fn resolve_request(doc_dir: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next()?;
+    let mut file = doc_dir.to_path_buf();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let segment = percent_encoding::percent_decode_str(segment)
+            .decode_utf8()
+            .ok()?;
+        if segment == "." || segment == ".." || segment.contains(['/', '\\']) {
+            return None;
+        }
+        file.push(&*segment);
+    }
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    Some(file)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("woff2") => "font/woff2",
+        Some("txt" | "md") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Gets the page of the documentation that `--open` opens.
+fn page_to_open(
+    ws: &Workspace<'_>,
+    options: &DocOptions,
+    compilation: &Compilation<'_>,
+    kind: &CompileKind,
+) -> CargoResult<PathBuf> {
+    if ws.gctx().cli_unstable().doc_merged_index
+        && matches!(options.output_format, OutputFormat::Html)
+    {
+        // With `-Zdoc-merged-index`, rustdoc writes an index page linking
+        // to every crate that was documented.
+        return Ok(compilation.root_output[kind]
+            .with_file_name("doc")
+            .join("index.html"));
+    }
+    let name = &compilation
+        .root_crate_names
+        .get(0)
+        .ok_or_else(|| anyhow::anyhow!("no crates with documentation"))?;
+    Ok(path_by_output_format(
+        compilation,
+        kind,
+        name,
+        &options.output_format,
+    ))
+}
+
+fn config_browser(gctx: &GlobalContext) -> CargoResult<Option<(PathBuf, Vec<String>)>> {
+    let cfg: Option<PathAndArgs> = gctx.get("doc.browser")?;
+    Ok(cfg.map(|path_args| (path_args.path.resolve_program(gctx), path_args.args)))
+}
+
 fn path_by_output_format(
     compilation: &Compilation<'_>,
     kind: &CompileKind,
@@ -135,7 +315,7 @@ fn path_by_output_format(
 }
 
 fn open_docs(
-    path: &Path,
+    path: &OsStr,
     shell: &mut Shell,
     config_browser: Option<(PathBuf, Vec<String>)>,
     gctx: &GlobalContext,
@@ -1,16 +1,52 @@
 use crate::core::compiler::standard_lib;
 use crate::core::compiler::{BuildConfig, CompileMode, RustcTargetData};
-use crate::core::{PackageSet, Resolve, Workspace};
+use crate::core::{PackageId, PackageSet, Resolve, SourceId, Workspace};
+use crate::drop_println;
 use crate::ops;
+use crate::sources::source::Source;
+use crate::sources::{RegistrySource, SourceConfigMap};
+use crate::util::cache_lock::CacheLockMode;
 use crate::util::context::JobsConfig;
 use crate::util::CargoResult;
 use crate::util::GlobalContext;
-use std::collections::HashSet;
+use anyhow::Context as _;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::task::Poll;
 
 pub struct FetchOptions<'a> {
     pub gctx: &'a GlobalContext,
     /// The target arch triple to fetch dependencies for
     pub targets: Vec<String>,
+    /// Only resolve the dependencies, without downloading them.
+    pub lock_only: bool,
+    /// Where to write the list of downloads, see [`FetchManifest`]. It is
+    /// printed to stdout with `lock_only` if not set.
+    pub output: Option<PathBuf>,
+}
+
+/// The downloads needed by `cargo fetch`, for mirroring them externally.
+#[derive(Serialize)]
+struct FetchManifest {
+    version: u32,
+    downloads: Vec<Download>,
+}
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum Download {
+    /// A `.crate` file of a remote registry.
+    Registry {
+        name: String,
+        version: String,
+        source: String,
+        url: String,
+        checksum: Option<String>,
+    },
+    /// A git repository, at the revision of the lock file.
+    Git { url: String, rev: Option<String> },
 }
 
 /// Executes `cargo fetch`.
@@ -63,6 +99,21 @@ pub fn fetch<'a>(
         deps_to_fetch.extend(deps);
     }
 
+    if options.lock_only || options.output.is_some() {
+        let manifest = FetchManifest {
+            version: 1,
+            downloads: downloads(gctx, &resolve, &to_download)?,
+        };
+        let json = serde_json::to_string_pretty(&manifest)?;
+        match &options.output {
+            Some(path) => cargo_util::paths::write(path, json + "\n")?,
+            None => drop_println!(gctx, "{json}"),
+        }
+    }
+    if options.lock_only {
+        return Ok((resolve, packages));
+    }
+
     // If -Zbuild-std was passed, download dependencies for the standard library.
     // We don't know ahead of time what jobs we'll be running, so tell `std_crates` that.
     if let Some(crates) = standard_lib::std_crates(gctx, None) {
@@ -76,3 +127,64 @@ pub fn fetch<'a>(
 
     Ok((resolve, packages))
 }
+
+/// Lists what downloading `ids` fetches. Packages of other sources, like paths
+/// or vendored sources, aren't downloaded.
+///
+/// The URLs are the ones Cargo downloads from, after any source replacement.
+fn downloads(
+    gctx: &GlobalContext,
+    resolve: &Resolve,
+    ids: &[PackageId],
+) -> CargoResult<Vec<Download>> {
+    let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+    let source_config = SourceConfigMap::new(gctx)?;
+    let mut replacements: HashMap<SourceId, SourceId> = HashMap::new();
+    let mut registries: HashMap<SourceId, RegistrySource<'_>> = HashMap::new();
+    let mut downloads = BTreeSet::new();
+    for &id in ids {
+        let source_id = match replacements.entry(id.source_id()) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => *e.insert(
+                source_config
+                    .load(id.source_id(), &HashSet::new())?
+                    .replaced_source_id(),
+            ),
+        };
+        if source_id.is_git() {
+            downloads.insert(Download::Git {
+                url: source_id.url().to_string(),
+                rev: id.source_id().precise_git_fragment().map(str::to_owned),
+            });
+        } else if source_id.is_remote_registry() {
+            let src = match registries.entry(source_id) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => {
+                    e.insert(RegistrySource::remote(source_id, &HashSet::new(), gctx)?)
+                }
+            };
+            let checksum = resolve.checksums().get(&id).cloned().flatten();
+            let url = loop {
+                match src.download_url(id, checksum.as_deref().unwrap_or_default())? {
+                    Poll::Pending => src
+                        .block_until_ready()
+                        .with_context(|| format!("failed to update {source_id}"))?,
+                    Poll::Ready(url) => break url,
+                }
+            };
+            let url = url.ok_or_else(|| {
+                anyhow::format_err!(
+                    "failed to find the download URL of `{id}`, {source_id} has no `config.json`"
+                )
+            })?;
+            downloads.insert(Download::Registry {
+                name: id.name().to_string(),
+                version: id.version().to_string(),
+                source: id.source_id().as_url().to_string(),
+                url,
+                checksum,
+            });
+        }
+    }
+    Ok(downloads.into_iter().collect())
+}
@@ -1,14 +1,21 @@
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::task::Poll;
 use std::{env, fs};
 
 use crate::core::compiler::{CompileKind, DefaultExecutor, Executor, UnitOutput};
-use crate::core::{Dependency, Edition, Package, PackageId, SourceId, Target, Workspace};
-use crate::ops::{common_for_install_and_uninstall::*, FilterRule};
+use crate::core::{
+    Dependency, Edition, Package, PackageId, PackageIdSpec, PackageIdSpecQuery, Resolve, SourceId,
+    Target, Workspace,
+};
+use crate::ops::{cargo_install_prebuilt, common_for_install_and_uninstall::*, FilterRule};
 use crate::ops::{CompileFilter, Packages};
-use crate::sources::source::Source;
+use crate::sources::source::{QueryKind, Source};
 use crate::sources::{GitSource, PathSource, SourceConfigMap};
+use crate::util::cache_lock::CacheLockMode;
+use crate::util::context::StringList;
 use crate::util::errors::CargoResult;
 use crate::util::{Filesystem, GlobalContext, Rustc};
 use crate::{drop_println, ops};
@@ -51,6 +58,8 @@ struct InstallablePackage<'gctx> {
     ws: Workspace<'gctx>,
     rustc: Rustc,
     target: String,
+    /// The SHA-256 checksum of the prebuilt binaries, from `--prebuilt-checksum`.
+    prebuilt_checksum: Option<String>,
 }
 
 impl<'gctx> InstallablePackage<'gctx> {
@@ -183,7 +192,7 @@ impl<'gctx> InstallablePackage<'gctx> {
             make_ws_rustc_target(gctx, &original_opts, &source_id, pkg.clone())?;
         // If we're installing in --locked mode and there's no `Cargo.lock` published
         // ie. the bin was published before https://github.com/rust-lang/cargo/pull/7026
-        if gctx.locked() && !ws.root().join("Cargo.lock").exists() {
+        if install_locked(gctx)? && !ws.root().join("Cargo.lock").exists() {
             gctx.shell().warn(format!(
                 "no Cargo.lock file published in {}",
                 pkg.to_string()
@@ -251,6 +260,7 @@ impl<'gctx> InstallablePackage<'gctx> {
             ws,
             rustc,
             target,
+            prebuilt_checksum: None,
         };
 
         // WARNING: no_track does not perform locking, so there is no protection
@@ -304,9 +314,24 @@ impl<'gctx> InstallablePackage<'gctx> {
 
         let mut td_opt = None;
         let mut needs_cleanup = false;
+        let mut cache_lock = None;
         if !self.source_id.is_path() {
             let target_dir = if let Some(dir) = self.gctx.target_dir()? {
                 dir
+            } else if install_target_cache(self.gctx)? {
+                let dir = install_cache_dir(self.gctx, self.pkg.package_id());
+                cache_lock = Some(dir.open_rw_exclusive_create(
+                    INSTALL_CACHE_LOCK_FILE,
+                    self.gctx,
+                    "install cache",
+                )?);
+                // Recorded for `cargo uninstall --unused-deps` to know which
+                // install root the directory belongs to.
+                paths::write(
+                    dir.join(INSTALL_CACHE_ROOT_FILE).into_path_unlocked(),
+                    self.root.as_path_unlocked().as_os_str().as_encoded_bytes(),
+                )?;
+                dir
             } else if let Ok(td) = TempFileBuilder::new().prefix("cargo-install").tempdir() {
                 let p = td.path().to_owned();
                 td_opt = Some(td);
@@ -318,36 +343,47 @@ impl<'gctx> InstallablePackage<'gctx> {
             self.ws.set_target_dir(target_dir);
         }
 
-        self.check_yanked_install()?;
+        self.check_locked_install()?;
 
-        let exec: Arc<dyn Executor> = Arc::new(DefaultExecutor);
-        let compile = ops::compile_ws(&self.ws, &self.opts, &exec).with_context(|| {
-            if let Some(td) = td_opt.take() {
-                // preserve the temporary directory, so the user can inspect it
-                drop(td.into_path());
-            }
+        let prebuilt_dir = TempFileBuilder::new().prefix("cargo-install").tempdir()?;
+        let prebuilt = self.try_prebuilt(prebuilt_dir.path())?;
 
-            format!(
-                "failed to compile `{}`, intermediate artifacts can be \
-                 found at `{}`.\nTo reuse those artifacts with a future \
-                 compilation, set the environment variable \
-                 `CARGO_TARGET_DIR` to that path.",
-                self.pkg,
-                self.ws.target_dir().display()
-            )
-        })?;
-        let mut binaries: Vec<(&str, &Path)> = compile
-            .binaries
-            .iter()
-            .map(|UnitOutput { path, .. }| {
-                let name = path.file_name().unwrap();
-                if let Some(s) = name.to_str() {
-                    Ok((s, path.as_ref()))
-                } else {
-                    bail!("Binary `{:?}` name can't be serialized into string", name)
+        let compile;
+        let mut binaries: Vec<(&str, &Path)> = if let Some(prebuilt) = &prebuilt {
+            prebuilt
+                .iter()
+                .map(|(name, path)| (name.as_str(), path.as_path()))
+                .collect()
+        } else {
+            let exec: Arc<dyn Executor> = Arc::new(DefaultExecutor);
+            compile = ops::compile_ws(&self.ws, &self.opts, &exec).with_context(|| {
+                if let Some(td) = td_opt.take() {
+                    // preserve the temporary directory, so the user can inspect it
+                    drop(td.into_path());
                 }
-            })
-            .collect::<CargoResult<_>>()?;
+
+                format!(
+                    "failed to compile `{}`, intermediate artifacts can be \
+                     found at `{}`.\nTo reuse those artifacts with a future \
+                     compilation, set the environment variable \
+                     `CARGO_TARGET_DIR` to that path.",
+                    self.pkg,
+                    self.ws.target_dir().display()
+                )
+            })?;
+            compile
+                .binaries
+                .iter()
+                .map(|UnitOutput { path, .. }| {
+                    let name = path.file_name().unwrap();
+                    if let Some(s) = name.to_str() {
+                        Ok((s, path.as_ref()))
+                    } else {
+                        bail!("Binary `{:?}` name can't be serialized into string", name)
+                    }
+                })
+                .collect::<CargoResult<_>>()?
+        };
         if binaries.is_empty() {
             // Cargo already warns the user if they use a target specifier that matches nothing,
             // but we want to error if the user asked for a _particular_ binary to be installed,
@@ -422,7 +458,7 @@ impl<'gctx> InstallablePackage<'gctx> {
         for &(bin, src) in binaries.iter() {
             let dst = staging_dir.path().join(bin);
             // Try to move if `target_dir` is transient.
-            if !self.source_id.is_path() && fs::rename(src, &dst).is_ok() {
+            if !self.source_id.is_path() && cache_lock.is_none() && fs::rename(src, &dst).is_ok() {
                 continue;
             }
             paths::copy(src, &dst)?;
@@ -554,7 +590,59 @@ impl<'gctx> InstallablePackage<'gctx> {
         }
     }
 
-    fn check_yanked_install(&self) -> CargoResult<()> {
+    /// Downloads the prebuilt binaries the package advertises, with the
+    /// unstable `install.prebuilt` config.
+    ///
+    /// Returns `None` to build from source, e.g. when the package doesn't
+    /// advertise any, or the build is customized with features or a profile.
+    /// Failing to get them is only a warning.
+    fn try_prebuilt(&self, dir: &Path) -> CargoResult<Option<Vec<(String, PathBuf)>>> {
+        if !self.gctx.cli_unstable().unstable_options
+            || !self
+                .gctx
+                .get::<Option<bool>>("install.prebuilt")?
+                .unwrap_or(false)
+        {
+            return Ok(None);
+        }
+        let cli_features = &self.opts.cli_features;
+        let is_default_build = self.source_id.is_registry()
+            && cli_features.features.is_empty()
+            && !cli_features.all_features
+            && cli_features.uses_default_features
+            && self.opts.build_config.requested_profile == "release"
+            && matches!(self.opts.filter, CompileFilter::Default { .. });
+        if !is_default_build {
+            return Ok(None);
+        }
+        let bins = self
+            .pkg
+            .targets()
+            .iter()
+            .filter(|t| t.is_bin())
+            .map(|t| t.name())
+            .collect::<Vec<_>>();
+        match cargo_install_prebuilt::fetch_prebuilt(
+            self.gctx,
+            &self.pkg,
+            &self.target,
+            &bins,
+            dir,
+            self.prebuilt_checksum.as_deref(),
+        ) {
+            Ok(prebuilt) => Ok(prebuilt),
+            Err(e) => {
+                self.gctx.shell().warn(format!(
+                    "failed to install the prebuilt binaries of `{}`, building from source\n\
+                     Caused by:\n  {:#}",
+                    self.pkg, e
+                ))?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn check_locked_install(&self) -> CargoResult<()> {
         if self.ws.ignore_lock() || !self.ws.root().join("Cargo.lock").exists() {
             return Ok(());
         }
@@ -563,6 +651,9 @@ impl<'gctx> InstallablePackage<'gctx> {
         // wouldn't be available for `compile_ws`.
         let dry_run = false;
         let (pkg_set, resolve) = ops::resolve_ws(&self.ws, dry_run)?;
+        if install_locked(self.ws.gctx())? {
+            self.check_published_lock(&resolve)?;
+        }
         ops::check_yanked(
             self.ws.gctx(),
             &pkg_set,
@@ -570,6 +661,39 @@ impl<'gctx> InstallablePackage<'gctx> {
             "consider running without --locked",
         )
     }
+
+    /// Checks that the published `Cargo.lock` needs no update apart from the
+    /// packages of `install.float`, like `--locked` does for a workspace.
+    fn check_published_lock(&self, resolve: &Resolve) -> CargoResult<()> {
+        let Some(published) = ops::load_pkg_lockfile(&self.ws)? else {
+            return Ok(());
+        };
+        let floating = self.ws.floating_packages();
+        let outdated = resolve
+            .iter()
+            .filter(|id| {
+                !published.contains(id)
+                    && !self.ws.members().any(|member| member.package_id() == *id)
+                    && !floating.iter().any(|spec| spec.matches(*id))
+            })
+            .map(|id| format!("`{id}`"))
+            .collect::<Vec<_>>();
+        if outdated.is_empty() {
+            return Ok(());
+        }
+        let flag = if self.ws.gctx().locked() {
+            "--locked"
+        } else {
+            "`install.locked`"
+        };
+        bail!(
+            "the `Cargo.lock` published with `{}` needs to be updated for {}, \
+             but {flag} was set to prevent this\n\
+             help: add the packages to `install.float` to let them update",
+            self.pkg,
+            outdated.join(", "),
+        )
+    }
 }
 
 fn make_warning_about_missing_features(binaries: &[&Target]) -> String {
@@ -620,7 +744,36 @@ pub fn install(
     opts: &ops::CompileOptions,
     force: bool,
     no_track: bool,
+    prebuilt_checksum: Option<&str>,
 ) -> CargoResult<()> {
+    warn_unstable_install_config(gctx)?;
+    install_packages(
+        gctx,
+        root,
+        krates,
+        source_id,
+        from_cwd,
+        opts,
+        force,
+        no_track,
+        prebuilt_checksum,
+    )
+}
+
+fn install_packages(
+    gctx: &GlobalContext,
+    root: Option<&str>,
+    krates: Vec<(String, Option<VersionReq>)>,
+    source_id: SourceId,
+    from_cwd: bool,
+    opts: &ops::CompileOptions,
+    force: bool,
+    no_track: bool,
+    prebuilt_checksum: Option<&str>,
+) -> CargoResult<()> {
+    if prebuilt_checksum.is_some() && krates.len() > 1 {
+        bail!("`--prebuilt-checksum` can only be used when installing a single package");
+    }
     let root = resolve_root(root, gctx)?;
     let dst = root.join("bin").into_path_unlocked();
     let map = SourceConfigMap::new(gctx)?;
@@ -653,7 +806,8 @@ pub fn install(
             current_rust_version.as_ref(),
         )?;
         let mut installed_anything = true;
-        if let Some(installable_pkg) = installable_pkg {
+        if let Some(mut installable_pkg) = installable_pkg {
+            installable_pkg.prebuilt_checksum = prebuilt_checksum.map(str::to_owned);
             installed_anything = installable_pkg.install_one()?;
         }
         (installed_anything, false)
@@ -824,7 +978,11 @@ fn make_ws_rustc_target<'gctx>(
         ws.set_resolve_honors_rust_version(Some(false));
         ws
     };
-    ws.set_ignore_lock(gctx.lock_update_allowed());
+    let locked = install_locked(gctx)?;
+    ws.set_ignore_lock(gctx.lock_update_allowed() && !locked);
+    if locked {
+        ws.set_floating_packages(install_float(gctx)?);
+    }
     ws.set_require_optional_deps(false);
 
     let rustc = gctx.load_global_rustc(Some(&ws))?;
@@ -836,6 +994,77 @@ fn make_ws_rustc_target<'gctx>(
     Ok((ws, rustc, target))
 }
 
+/// Warns about the unstable `[install]` config keys that are set but ignored
+/// without `-Zunstable-options`.
+fn warn_unstable_install_config(gctx: &GlobalContext) -> CargoResult<()> {
+    if gctx.cli_unstable().unstable_options {
+        return Ok(());
+    }
+    let mut set = Vec::new();
+    for key in ["install.locked", "install.prebuilt", "install.target-cache"] {
+        if gctx.get::<Option<bool>>(key)?.is_some() {
+            set.push(key);
+        }
+    }
+    if gctx.get::<Option<StringList>>("install.float")?.is_some() {
+        set.push("install.float");
+    }
+    for key in set {
+        gctx.shell().warn(format!(
+            "ignoring `{key}` config, pass `-Zunstable-options` to enable it"
+        ))?;
+    }
+    Ok(())
+}
+
+/// Whether to install with the published `Cargo.lock`, with `--locked` or
+/// the unstable `install.locked` config.
+///
+/// `--locked` always wins, as the config can't turn it off. Either way the
+/// packages of `install.float` may update.
+fn install_locked(gctx: &GlobalContext) -> CargoResult<bool> {
+    if gctx.locked() {
+        return Ok(true);
+    }
+    if !gctx.cli_unstable().unstable_options {
+        return Ok(false);
+    }
+    Ok(gctx.get::<Option<bool>>("install.locked")?.unwrap_or(false))
+}
+
+/// Whether to build in the target directories of the unstable
+/// `install.target-cache` config, which are reused by later installs of the
+/// same package.
+fn install_target_cache(gctx: &GlobalContext) -> CargoResult<bool> {
+    if !gctx.cli_unstable().unstable_options {
+        return Ok(false);
+    }
+    Ok(gctx
+        .get::<Option<bool>>("install.target-cache")?
+        .unwrap_or(false))
+}
+
+/// Packages of the unstable `install.float` config, which may update past the
+/// version pinned by the published `Cargo.lock`, e.g. to pick up a security
+/// fix in a transitive dependency.
+fn install_float(gctx: &GlobalContext) -> CargoResult<Vec<PackageIdSpec>> {
+    if !gctx.cli_unstable().unstable_options {
+        return Ok(Vec::new());
+    }
+    let Some(specs) = gctx.get::<Option<StringList>>("install.float")? else {
+        return Ok(Vec::new());
+    };
+    specs
+        .as_slice()
+        .iter()
+        .map(|spec| {
+            PackageIdSpec::parse(spec).with_context(|| {
+                format!("invalid package ID specification `{spec}` in `install.float`")
+            })
+        })
+        .collect()
+}
+
 /// Display a list of installed binaries.
 pub fn install_list(dst: Option<&str>, gctx: &GlobalContext) -> CargoResult<()> {
     let root = resolve_root(dst, gctx)?;
@@ -849,6 +1078,141 @@ pub fn install_list(dst: Option<&str>, gctx: &GlobalContext) -> CargoResult<()>
     Ok(())
 }
 
+/// Lists the installed packages that have a newer version available.
+pub fn install_list_outdated(dst: Option<&str>, gctx: &GlobalContext) -> CargoResult<()> {
+    let root = resolve_root(dst, gctx)?;
+    let tracker = InstallTracker::load(gctx, &root)?;
+    let outdated = outdated_packages(&tracker, gctx)?;
+    if outdated.is_empty() {
+        gctx.shell().note("all installed packages are up to date")?;
+    }
+    for (pkg_id, latest) in outdated {
+        drop_println!(
+            gctx,
+            "{} v{} (latest: v{})",
+            pkg_id.name(),
+            pkg_id.version(),
+            latest
+        );
+    }
+    Ok(())
+}
+
+/// Reinstalls the installed packages that have a newer version available,
+/// with the settings they were installed with.
+pub fn install_outdated(
+    gctx: &GlobalContext,
+    dst: Option<&str>,
+    opts: &ops::CompileOptions,
+) -> CargoResult<()> {
+    warn_unstable_install_config(gctx)?;
+    let root = resolve_root(dst, gctx)?;
+    let host = gctx.load_global_rustc(None)?.host;
+    let to_update = {
+        let tracker = InstallTracker::load(gctx, &root)?;
+        outdated_packages(&tracker, gctx)?
+            .into_iter()
+            .map(|(pkg_id, latest)| {
+                let opts = tracker.reinstall_options(pkg_id, opts, &host)?;
+                Ok((pkg_id, latest, opts))
+            })
+            .collect::<CargoResult<Vec<_>>>()?
+        // The tracker is unlocked here, for `install` to lock it again.
+    };
+    if to_update.is_empty() {
+        gctx.shell().note("all installed packages are up to date")?;
+        return Ok(());
+    }
+
+    let mut succeeded = vec![];
+    let mut failed = vec![];
+    for (pkg_id, latest, opts) in to_update {
+        let vers = VersionReq::parse(&format!("={latest}"))?;
+        let krates = vec![(pkg_id.name().to_string(), Some(vers))];
+        match install_packages(
+            gctx,
+            dst,
+            krates,
+            pkg_id.source_id(),
+            false,
+            &opts,
+            false,
+            false,
+            None,
+        ) {
+            Ok(()) => succeeded.push(pkg_id.name()),
+            Err(e) => {
+                crate::display_error(&e, &mut gctx.shell());
+                failed.push(pkg_id.name());
+            }
+        }
+    }
+
+    let mut summary = vec![];
+    if !succeeded.is_empty() {
+        summary.push(format!("Successfully updated {}!", succeeded.join(", ")));
+    }
+    if !failed.is_empty() {
+        summary.push(format!(
+            "Failed to update {} (see error(s) above).",
+            failed.join(", ")
+        ));
+    }
+    gctx.shell().status("Summary", summary.join(" "))?;
+    if !failed.is_empty() {
+        bail!("some packages failed to update");
+    }
+    Ok(())
+}
+
+/// Installed registry packages with a newer version in their registry, along
+/// with the newest version.
+///
+/// Packages installed from git or a path are not checked, and yanked versions
+/// are never offered.
+fn outdated_packages(
+    tracker: &InstallTracker,
+    gctx: &GlobalContext,
+) -> CargoResult<Vec<(PackageId, semver::Version)>> {
+    let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+    let map = SourceConfigMap::new(gctx)?;
+    let mut sources: HashMap<SourceId, Box<dyn Source + '_>> = HashMap::new();
+    let mut outdated = Vec::new();
+    for (pkg_id, _) in tracker.all_installed_bins() {
+        let source_id = pkg_id.source_id();
+        if !source_id.is_registry() {
+            continue;
+        }
+        let source = match sources.entry(source_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut source = map.load(source_id, &HashSet::new())?;
+                source.invalidate_cache();
+                entry.insert(source)
+            }
+        };
+        let dep = Dependency::parse(pkg_id.name(), None, source_id)?;
+        let summaries = loop {
+            match source.query_vec(&dep, QueryKind::Exact)? {
+                Poll::Ready(summaries) => break summaries,
+                Poll::Pending => source.block_until_ready()?,
+            }
+        };
+        let latest = summaries
+            .iter()
+            .filter(|s| !s.is_yanked())
+            .map(|s| s.as_summary().version())
+            // Pre-releases are only offered to those already on one.
+            .filter(|v| v.pre.is_empty() || !pkg_id.version().pre.is_empty())
+            .max()
+            .filter(|latest| *latest > pkg_id.version());
+        if let Some(latest) = latest {
+            outdated.push((*pkg_id, latest.clone()));
+        }
+    }
+    Ok(outdated)
+}
+
 /// Removes executables that are no longer part of a package that was
 /// previously installed.
 fn remove_orphaned_bins(
@@ -0,0 +1,248 @@
+//! Installation of prebuilt binaries for `cargo install`.
+//!
+//! A package advertises its prebuilt binaries with the keys of
+//! `[package.metadata.binstall]` understood by [cargo-binstall]:
+//!
+//! * `pkg-url` --- Template of the URL to download the binaries of a target
+//!   from. Defaults to a GitHub release of the package's `repository`.
+//! * `pkg-fmt` --- Format of the download, `tgz`, `tar` or `bin` (the
+//!   binary itself). Defaults to `tgz`.
+//! * `bin-dir` --- Template of the path of each binary inside the archive.
+//!
+//! The templates may use the placeholders `{ name }`, `{ version }`,
+//! `{ target }`, `{ repo }`, `{ bin }`, `{ binary-ext }` and
+//! `{ archive-suffix }`.
+//!
+//! The download is only used if its SHA-256 checksum matches the one the user
+//! passed with `--prebuilt-checksum`. Nothing served from the same place as
+//! the binaries can vouch for them, so without it Cargo builds from source.
+//!
+//! [cargo-binstall]: https://github.com/cargo-bins/cargo-binstall
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _};
+use cargo_util::{paths, Sha256};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use tar::Archive;
+
+use crate::core::Package;
+use crate::util::errors::CargoResult;
+use crate::util::network::http::http_handle;
+use crate::util::GlobalContext;
+
+/// The keys of `[package.metadata.binstall]`.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct BinstallMetadata {
+    pkg_url: Option<String>,
+    pkg_fmt: Option<String>,
+    bin_dir: Option<String>,
+}
+
+const DEFAULT_PKG_URL: &str =
+    "{ repo }/releases/download/v{ version }/{ name }-{ target }-v{ version }{ archive-suffix }";
+const DEFAULT_BIN_DIR: &str = "{ name }-{ target }-v{ version }/{ bin }{ binary-ext }";
+
+/// Downloads the prebuilt binaries `bins` of `pkg` for `target` into `dir`,
+/// returning the file name and path of each.
+///
+/// The download must have the SHA-256 `checksum`, which is an error if it is
+/// not given. Returns `Ok(None)` if the package doesn't advertise prebuilt
+/// binaries.
+pub fn fetch_prebuilt(
+    gctx: &GlobalContext,
+    pkg: &Package,
+    target: &str,
+    bins: &[&str],
+    dir: &Path,
+    checksum: Option<&str>,
+) -> CargoResult<Option<Vec<(String, PathBuf)>>> {
+    let Some(metadata) = pkg
+        .manifest()
+        .custom_metadata()
+        .and_then(|metadata| metadata.get("binstall"))
+    else {
+        return Ok(None);
+    };
+    let metadata: BinstallMetadata = metadata
+        .clone()
+        .try_into()
+        .context("failed to parse `package.metadata.binstall`")?;
+    let Some(checksum) = checksum else {
+        bail!(
+            "the checksum of the prebuilt binaries is unknown, pass it with `--prebuilt-checksum`"
+        );
+    };
+    let checksum = checksum.to_lowercase();
+
+    let fmt = metadata.pkg_fmt.as_deref().unwrap_or("tgz");
+    let archive_suffix = match fmt {
+        "tgz" => ".tgz",
+        "tar" => ".tar",
+        "bin" => "",
+        _ => bail!("unsupported `pkg-fmt` `{fmt}`, expected `tgz`, `tar` or `bin`"),
+    };
+    if fmt == "bin" && bins.len() != 1 {
+        bail!("`pkg-fmt = \"bin\"` can only provide a single binary");
+    }
+    let binary_ext = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    let repo = pkg
+        .manifest()
+        .metadata()
+        .repository
+        .as_deref()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let name = pkg.name();
+    let version = pkg.version().to_string();
+    let vars = [
+        ("name", name.as_str()),
+        ("version", version.as_str()),
+        ("target", target),
+        ("repo", repo),
+        ("binary-ext", binary_ext),
+        ("archive-suffix", archive_suffix),
+    ];
+    let vars_with_bin = |bin| {
+        let mut vars = vars.to_vec();
+        vars.push(("bin", bin));
+        vars
+    };
+
+    let pkg_url = metadata.pkg_url.as_deref().unwrap_or(DEFAULT_PKG_URL);
+    let url = render(pkg_url, &vars)?;
+
+    gctx.shell()
+        .status("Downloading", format!("prebuilt binaries from {url}"))?;
+    let data = download(gctx, &url)?;
+    let actual = Sha256::new().update(&data).finish_hex();
+    if actual != checksum {
+        bail!(
+            "checksum of `{url}` does not match:\n\
+             expected: {checksum}\n\
+             actual:   {actual}"
+        );
+    }
+
+    let mut files = Vec::new();
+    if fmt == "bin" {
+        let file_name = format!("{}{binary_ext}", bins[0]);
+        let path = dir.join(&file_name);
+        write_executable(&path, &data)?;
+        files.push((file_name, path));
+        return Ok(Some(files));
+    }
+
+    let bin_dir = metadata.bin_dir.as_deref().unwrap_or(DEFAULT_BIN_DIR);
+    let mut wanted = bins
+        .iter()
+        .map(|bin| Ok((render(bin_dir, &vars_with_bin(bin))?, *bin)))
+        .collect::<CargoResult<Vec<_>>>()?;
+    let mut archive = if fmt == "tgz" {
+        Archive::new(Box::new(GzDecoder::new(&data[..])) as Box<dyn Read>)
+    } else {
+        Archive::new(Box::new(&data[..]) as Box<dyn Read>)
+    };
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_string_lossy().replace('\\', "/");
+        let entry_path = entry_path.trim_start_matches("./");
+        let Some(i) = wanted.iter().position(|(path, _)| path == entry_path) else {
+            continue;
+        };
+        let (_, bin) = wanted.remove(i);
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        let file_name = format!("{bin}{binary_ext}");
+        let path = dir.join(&file_name);
+        write_executable(&path, &contents)?;
+        files.push((file_name, path));
+    }
+    if let Some((path, _)) = wanted.first() {
+        bail!("`{url}` does not contain `{path}`");
+    }
+    Ok(Some(files))
+}
+
+/// Fills in the `{ key }` placeholders of a `[package.metadata.binstall]`
+/// template.
+fn render(template: &str, vars: &[(&str, &str)]) -> CargoResult<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            bail!("unclosed `{{` in `{template}`");
+        };
+        let key = rest[start + 1..start + len].trim();
+        let Some((_, value)) = vars.iter().find(|(name, _)| *name == key) else {
+            bail!("unknown placeholder `{key}` in `{template}`");
+        };
+        if key == "repo" && value.is_empty() {
+            bail!("`{template}` uses the `repository` of the package, which is not set");
+        }
+        out.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn download(gctx: &GlobalContext, url: &str) -> CargoResult<Vec<u8>> {
+    // Release downloads redirect, which the handle shared with the registries
+    // must not follow, so this uses one of its own.
+    let mut handle = http_handle(gctx)?;
+    handle.get(true)?;
+    handle.url(url)?;
+    handle.follow_location(true)?;
+    let mut body = Vec::new();
+    let mut transfer = handle.transfer();
+    transfer.write_function(|data| {
+        body.extend_from_slice(data);
+        Ok(data.len())
+    })?;
+    transfer
+        .perform()
+        .with_context(|| format!("failed to download `{url}`"))?;
+    drop(transfer);
+    // `file:` URLs have no response code.
+    let code = handle.response_code()?;
+    if code != 200 && code != 0 {
+        bail!("failed to download `{url}`, got {code}");
+    }
+    Ok(body)
+}
+
+fn write_executable(path: &Path, contents: &[u8]) -> CargoResult<()> {
+    paths::write(path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn render_placeholders() {
+        let vars = [("name", "foo"), ("version", "1.0.0"), ("bin", "foo")];
+        assert_eq!(
+            render("{ name }-v{version}/{ bin }", &vars).unwrap(),
+            "foo-v1.0.0/foo"
+        );
+        assert!(render("{ nope }", &vars).is_err());
+        assert!(render("{ name", &vars).is_err());
+    }
+}
@@ -0,0 +1,460 @@
+//! Implementation of `cargo license-report`.
+//!
+//! The report lists the license of every package the workspace depends on,
+//! grouped by license. The license comes from the `license` key of the
+//! package, normalized to [SPDX] identifiers, or is otherwise detected from
+//! the license files the package ships. Licenses listed in
+//! `license-report.deny` fail the report.
+//!
+//! [SPDX]: https://spdx.org/licenses/
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Package, Workspace};
+use crate::drop_print;
+use crate::ops;
+use crate::util::context::StringList;
+use crate::util::{CargoResult, GlobalContext};
+
+pub struct LicenseReportOptions<'a> {
+    pub gctx: &'a GlobalContext,
+    pub format: LicenseReportFormat,
+    /// Licenses to deny besides the ones of `license-report.deny`.
+    pub deny: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LicenseReportFormat {
+    Markdown,
+    Json,
+    Csv,
+}
+
+/// The `[license-report]` config table.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct LicenseReportConfig {
+    deny: Option<StringList>,
+}
+
+/// Common SPDX license identifiers, to fix the case of the ones in
+/// manifests.
+const SPDX_IDS: &[&str] = &[
+    "0BSD",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-2.0",
+    "Artistic-2.0",
+    "BlueOak-1.0.0",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSL-1.0",
+    "CC-BY-4.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "EPL-2.0",
+    "EUPL-1.2",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "ISC",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "LLVM-exception",
+    "MIT",
+    "MIT-0",
+    "MPL-2.0",
+    "NCSA",
+    "OpenSSL",
+    "Unicode-3.0",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "Zlib",
+];
+
+/// Deprecated SPDX identifiers and what they stand for now.
+const DEPRECATED_SPDX_IDS: &[(&str, &str)] = &[
+    ("AGPL-3.0", "AGPL-3.0-only"),
+    ("AGPL-3.0+", "AGPL-3.0-or-later"),
+    ("GPL-2.0", "GPL-2.0-only"),
+    ("GPL-2.0+", "GPL-2.0-or-later"),
+    ("GPL-3.0", "GPL-3.0-only"),
+    ("GPL-3.0+", "GPL-3.0-or-later"),
+    ("LGPL-2.1", "LGPL-2.1-only"),
+    ("LGPL-2.1+", "LGPL-2.1-or-later"),
+    ("LGPL-3.0", "LGPL-3.0-only"),
+    ("LGPL-3.0+", "LGPL-3.0-or-later"),
+];
+
+/// Phrases identifying the text of a license, to detect the license of
+/// packages without a `license` key.
+const LICENSE_TEXTS: &[(&str, &str)] = &[
+    ("Apache License", "Apache-2.0"),
+    ("Permission is hereby granted, free of charge", "MIT"),
+    ("Mozilla Public License Version 2.0", "MPL-2.0"),
+    ("This is free and unencumbered software", "Unlicense"),
+    ("Permission to use, copy, modify, and/or distribute", "ISC"),
+    ("Neither the name of", "BSD-3-Clause"),
+    (
+        "Redistribution and use in source and binary forms",
+        "BSD-2-Clause",
+    ),
+];
+
+/// One package of the report.
+#[derive(Serialize)]
+struct ReportPackage {
+    name: String,
+    version: String,
+    /// The source of the package, `None` for path dependencies.
+    source: Option<String>,
+    /// The checksum recorded in `Cargo.lock`.
+    checksum: Option<String>,
+    /// The `license` key as written in the manifest.
+    declared_license: Option<String>,
+    license_files: Vec<String>,
+    denied: bool,
+}
+
+/// The packages that have the same license.
+#[derive(Serialize)]
+struct ReportGroup {
+    /// The normalized license expression, `None` if it's unknown.
+    license: Option<String>,
+    packages: Vec<ReportPackage>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    licenses: Vec<ReportGroup>,
+}
+
+/// Prints the license report of the dependencies of the workspace, and fails
+/// if one of them only has denied licenses.
+pub fn license_report(ws: &Workspace<'_>, opts: &LicenseReportOptions<'_>) -> CargoResult<()> {
+    let gctx = opts.gctx;
+    let config = gctx
+        .get::<Option<LicenseReportConfig>>("license-report")?
+        .unwrap_or_default();
+    let deny: BTreeSet<String> = config
+        .deny
+        .map(|deny| deny.as_slice().to_vec())
+        .unwrap_or_default()
+        .iter()
+        .chain(&opts.deny)
+        .map(|license| normalize(license))
+        .collect();
+
+    let (pkg_set, resolve) = ops::resolve_ws(ws, false)?;
+    let ids: Vec<_> = resolve
+        .iter()
+        .filter(|id| !ws.members().any(|m| m.package_id() == *id))
+        .collect();
+    let packages = pkg_set.get_many(ids)?;
+
+    let mut groups: BTreeMap<Option<String>, Vec<ReportPackage>> = BTreeMap::new();
+    for pkg in packages {
+        let metadata = pkg.manifest().metadata();
+        let license_files = license_files(pkg);
+        let license = match &metadata.license {
+            Some(license) => Some(normalize(license)),
+            None => detect_license(pkg, &license_files),
+        };
+        let denied = license
+            .as_deref()
+            .is_some_and(|license| !is_allowed(license, &deny));
+        let id = pkg.package_id();
+        let source = id.source_id();
+        groups.entry(license).or_default().push(ReportPackage {
+            name: id.name().to_string(),
+            version: id.version().to_string(),
+            source: (!source.is_path()).then(|| source.as_url().to_string()),
+            checksum: resolve.checksums().get(&id).cloned().flatten(),
+            declared_license: metadata.license.clone(),
+            license_files,
+            denied,
+        });
+    }
+    // Unknown licenses go last.
+    let mut licenses: Vec<_> = groups
+        .into_iter()
+        .map(|(license, mut packages)| {
+            packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+            ReportGroup { license, packages }
+        })
+        .collect();
+    licenses.sort_by_key(|group| group.license.is_none());
+    let report = Report { licenses };
+
+    match opts.format {
+        LicenseReportFormat::Json => gctx.shell().print_json(&report)?,
+        LicenseReportFormat::Markdown => drop_print!(gctx, "{}", markdown(&report)),
+        LicenseReportFormat::Csv => drop_print!(gctx, "{}", csv(&report)),
+    }
+
+    let denied: Vec<_> = report
+        .licenses
+        .iter()
+        .flat_map(|group| {
+            group
+                .packages
+                .iter()
+                .filter(|pkg| pkg.denied)
+                .map(move |pkg| (pkg, group.license.as_deref().unwrap_or_default()))
+        })
+        .collect();
+    if !denied.is_empty() {
+        let mut msg = format!(
+            "{} package{} only allowed under licenses denied by `license-report.deny`:",
+            denied.len(),
+            if denied.len() == 1 { " is" } else { "s are" }
+        );
+        for (pkg, license) in denied {
+            write!(msg, "\n  {} v{} ({license})", pkg.name, pkg.version)?;
+        }
+        bail!(msg);
+    }
+    Ok(())
+}
+
+/// Normalizes a license expression: identifiers get the case SPDX uses,
+/// deprecated identifiers are replaced, operators are upper case, the old `/`
+/// separator is turned into `OR` and parentheses around the whole expression
+/// are dropped.
+fn normalize(license: &str) -> String {
+    let mut tokens = tokenize(license);
+    while tokens.len() > 2 && encloses(&tokens) {
+        tokens = tokens[1..tokens.len() - 1].to_vec();
+    }
+    let mut out = String::new();
+    for token in tokens {
+        let token = match token.to_ascii_uppercase().as_str() {
+            "/" | "OR" => "OR".to_string(),
+            "AND" => "AND".to_string(),
+            "WITH" => "WITH".to_string(),
+            "(" | ")" => token.to_string(),
+            _ => normalize_id(token),
+        };
+        if !(out.is_empty() || out.ends_with('(') || token == ")") {
+            out.push(' ');
+        }
+        out.push_str(&token);
+    }
+    out
+}
+
+/// Whether the first and last tokens are parentheses matching each other.
+fn encloses(tokens: &[&str]) -> bool {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match *token {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return i == tokens.len() - 1 && tokens[0] == "(";
+        }
+    }
+    false
+}
+
+fn normalize_id(id: &str) -> String {
+    if let Some((_, new)) = DEPRECATED_SPDX_IDS
+        .iter()
+        .find(|(old, _)| old.eq_ignore_ascii_case(id))
+    {
+        return new.to_string();
+    }
+    match SPDX_IDS.iter().find(|known| known.eq_ignore_ascii_case(id)) {
+        Some(known) => known.to_string(),
+        None => id.to_string(),
+    }
+}
+
+/// Splits a license expression into identifiers, operators and parentheses.
+fn tokenize(license: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in license.char_indices() {
+        if c.is_whitespace() || matches!(c, '(' | ')' | '/') {
+            if let Some(start) = start.take() {
+                tokens.push(&license[start..i]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&license[i..i + 1]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(start) = start {
+        tokens.push(&license[start..]);
+    }
+    tokens
+}
+
+/// Whether a normalized license expression can be satisfied without the
+/// `deny` licenses. An expression that doesn't parse is allowed unless it
+/// mentions a denied license.
+fn is_allowed(license: &str, deny: &BTreeSet<String>) -> bool {
+    let tokens = tokenize(license);
+    let mut pos = 0;
+    match parse_or(&tokens, &mut pos, deny) {
+        Some(allowed) if pos == tokens.len() => allowed,
+        _ => !tokens.iter().any(|token| deny.contains(*token)),
+    }
+}
+
+fn parse_or(tokens: &[&str], pos: &mut usize, deny: &BTreeSet<String>) -> Option<bool> {
+    let mut allowed = parse_and(tokens, pos, deny)?;
+    while tokens.get(*pos) == Some(&"OR") {
+        *pos += 1;
+        allowed |= parse_and(tokens, pos, deny)?;
+    }
+    Some(allowed)
+}
+
+fn parse_and(tokens: &[&str], pos: &mut usize, deny: &BTreeSet<String>) -> Option<bool> {
+    let mut allowed = parse_term(tokens, pos, deny)?;
+    while tokens.get(*pos) == Some(&"AND") {
+        *pos += 1;
+        allowed &= parse_term(tokens, pos, deny)?;
+    }
+    Some(allowed)
+}
+
+fn parse_term(tokens: &[&str], pos: &mut usize, deny: &BTreeSet<String>) -> Option<bool> {
+    let token = *tokens.get(*pos)?;
+    *pos += 1;
+    match token {
+        "(" => {
+            let allowed = parse_or(tokens, pos, deny)?;
+            if tokens.get(*pos) != Some(&")") {
+                return None;
+            }
+            *pos += 1;
+            Some(allowed)
+        }
+        ")" | "OR" | "AND" | "WITH" => None,
+        id => {
+            let mut allowed = !deny.contains(id);
+            if tokens.get(*pos) == Some(&"WITH") {
+                let exception = *tokens.get(*pos + 1)?;
+                *pos += 2;
+                allowed &= !deny.contains(&format!("{id} WITH {exception}"));
+            }
+            Some(allowed)
+        }
+    }
+}
+
+/// The license files at the root of a package, along with its
+/// `license-file`.
+fn license_files(pkg: &Package) -> Vec<String> {
+    let mut files = BTreeSet::new();
+    if let Some(license_file) = &pkg.manifest().metadata().license_file {
+        files.insert(license_file.clone());
+    }
+    if let Ok(entries) = std::fs::read_dir(pkg.root()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let upper = name.to_ascii_uppercase();
+            if ["LICENSE", "LICENCE", "COPYING", "UNLICENSE"]
+                .iter()
+                .any(|prefix| upper.starts_with(prefix))
+                && entry.path().is_file()
+            {
+                files.insert(name);
+            }
+        }
+    }
+    files.into_iter().collect()
+}
+
+/// Detects the license of a package from the text of its license files.
+///
+/// Several license files, like `LICENSE-MIT` and `LICENSE-APACHE`, are how
+/// packages usually offer a choice of licenses, so they are joined with `OR`.
+fn detect_license(pkg: &Package, license_files: &[String]) -> Option<String> {
+    let mut found = BTreeSet::new();
+    for file in license_files {
+        let Ok(text) = std::fs::read_to_string(pkg.root().join(file)) else {
+            continue;
+        };
+        if let Some((_, id)) = LICENSE_TEXTS.iter().find(|(text_, _)| text.contains(text_)) {
+            found.insert(*id);
+        }
+    }
+    if found.is_empty() {
+        return None;
+    }
+    Some(found.into_iter().collect::<Vec<_>>().join(" OR "))
+}
+
+fn markdown(report: &Report) -> String {
+    let mut out = String::from("# License report\n");
+    for group in &report.licenses {
+        let license = group.license.as_deref().unwrap_or("Unknown");
+        out.push_str(&format!("\n## {license}\n\n"));
+        out.push_str("| Package | Version | Source | License files |\n");
+        out.push_str("| --- | --- | --- | --- |\n");
+        for pkg in &group.packages {
+            let mut name = pkg.name.clone();
+            if pkg.denied {
+                name.push_str(" (denied)");
+            }
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                markdown_cell(&name),
+                markdown_cell(&pkg.version),
+                markdown_cell(pkg.source.as_deref().unwrap_or("path")),
+                markdown_cell(&pkg.license_files.join(", "))
+            ));
+        }
+    }
+    out
+}
+
+fn csv(report: &Report) -> String {
+    let mut out = String::from("name,version,license,source,checksum,license_files,denied\n");
+    for group in &report.licenses {
+        for pkg in &group.packages {
+            let row = [
+                pkg.name.as_str(),
+                pkg.version.as_str(),
+                group.license.as_deref().unwrap_or_default(),
+                pkg.source.as_deref().unwrap_or_default(),
+                pkg.checksum.as_deref().unwrap_or_default(),
+                &pkg.license_files.join(" "),
+                if pkg.denied { "true" } else { "false" },
+            ];
+            let row: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Escapes the `|` of a Markdown table cell, which would end the cell.
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
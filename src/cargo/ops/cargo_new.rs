@@ -1,4 +1,5 @@
 use crate::core::{Edition, Shell, Workspace};
+use crate::ops::cargo_new_template::Template;
 use crate::util::errors::CargoResult;
 use crate::util::important_paths::find_root_manifest_for_wd;
 use crate::util::toml_mut::is_sorted;
@@ -12,7 +13,7 @@ use serde::Deserialize;
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::io::{BufRead, BufReader, ErrorKind};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 use std::{fmt, slice};
 use toml_edit::{Array, Value};
@@ -61,6 +62,11 @@ pub struct NewOptions {
     pub name: Option<String>,
     pub edition: Option<String>,
     pub registry: Option<String>,
+    /// Template to create the package from instead of the built-in ones:
+    /// a path, a git URL or a `name[@version]` registry package.
+    pub template: Option<String>,
+    /// Values of template variables given with `--define`.
+    pub template_variables: Vec<(String, String)>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -97,6 +103,7 @@ struct MkOptions<'a> {
     source_files: Vec<SourceFileInformation>,
     edition: Option<&'a str>,
     registry: Option<&'a str>,
+    template: Option<&'a Template>,
 }
 
 impl NewOptions {
@@ -125,6 +132,8 @@ impl NewOptions {
             name,
             edition,
             registry,
+            template: None,
+            template_variables: Vec::new(),
         };
         Ok(opts)
     }
@@ -435,8 +444,15 @@ fn calculate_new_project_kind(
 pub fn new(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<()> {
     let path = &opts.path;
     let name = get_name(path, opts)?;
-    gctx.shell()
-        .status("Creating", format!("{} `{}` package", opts.kind, name))?;
+    match &opts.template {
+        Some(template) => gctx.shell().status(
+            "Creating",
+            format!("`{}` package from template `{}`", name, template),
+        )?,
+        None => gctx
+            .shell()
+            .status("Creating", format!("{} `{}` package", opts.kind, name))?,
+    }
 
     if path.exists() {
         anyhow::bail!(
@@ -451,6 +467,7 @@ pub fn new(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<()> {
 
     check_name(name, opts.name.is_none(), is_bin, &mut gctx.shell())?;
 
+    let template = load_template(opts, name, gctx)?;
     let mkopts = MkOptions {
         version_control: opts.version_control,
         path,
@@ -458,6 +475,7 @@ pub fn new(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<()> {
         source_files: vec![plan_new_source_file(opts.kind.is_bin())],
         edition: opts.edition.as_deref(),
         registry: opts.registry.as_deref(),
+        template: template.as_ref(),
     };
 
     mk(gctx, &mkopts).with_context(|| {
@@ -481,8 +499,14 @@ pub fn init(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<NewProjectKi
     let mut src_paths_types = vec![];
     detect_source_paths_and_types(path, name, &mut src_paths_types)?;
     let kind = calculate_new_project_kind(opts.kind, opts.auto_detect_kind, &src_paths_types);
-    gctx.shell()
-        .status("Creating", format!("{} package", opts.kind))?;
+    match &opts.template {
+        Some(template) => gctx
+            .shell()
+            .status("Creating", format!("package from template `{}`", template))?,
+        None => gctx
+            .shell()
+            .status("Creating", format!("{} package", opts.kind))?,
+    }
 
     if path.join("Cargo.toml").exists() {
         anyhow::bail!("`cargo init` cannot be run on existing Cargo packages")
@@ -518,42 +542,12 @@ pub fn init(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<NewProjectKi
 
     check_name(name, opts.name.is_none(), has_bin, &mut gctx.shell())?;
 
-    let mut version_control = opts.version_control;
-
-    if version_control == None {
-        let mut num_detected_vcses = 0;
-
-        if path.join(".git").exists() {
-            version_control = Some(VersionControl::Git);
-            num_detected_vcses += 1;
-        }
-
-        if path.join(".hg").exists() {
-            version_control = Some(VersionControl::Hg);
-            num_detected_vcses += 1;
-        }
-
-        if path.join(".pijul").exists() {
-            version_control = Some(VersionControl::Pijul);
-            num_detected_vcses += 1;
-        }
-
-        if path.join(".fossil").exists() {
-            version_control = Some(VersionControl::Fossil);
-            num_detected_vcses += 1;
-        }
-
-        // if none exists, maybe create git, like in `cargo new`
-
-        if num_detected_vcses > 1 {
-            anyhow::bail!(
-                "more than one of .hg, .git, .pijul, .fossil configurations \
-                 found and the ignore file can't be filled in as \
-                 a result. specify --vcs to override detection"
-            );
-        }
-    }
+    let version_control = match opts.version_control {
+        Some(vcs) => Some(vcs),
+        None => detect_version_control(path)?,
+    };
 
+    let template = load_template(opts, name, gctx)?;
     let mkopts = MkOptions {
         version_control,
         path,
@@ -561,6 +555,7 @@ pub fn init(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<NewProjectKi
         source_files: src_paths_types,
         edition: opts.edition.as_deref(),
         registry: opts.registry.as_deref(),
+        template: template.as_ref(),
     };
 
     mk(gctx, &mkopts).with_context(|| {
@@ -573,6 +568,324 @@ pub fn init(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<NewProjectKi
     Ok(kind)
 }
 
+/// Directory of the members of a workspace created by `cargo init --workspace`,
+/// matched by its `members` glob.
+const WORKSPACE_MEMBERS_DIR: &str = "crates";
+
+/// Tables of a package manifest that are only honored at the workspace root.
+const WORKSPACE_ROOT_TABLES: &[&str] = &["patch", "replace", "profile"];
+
+/// Tables of a package manifest listing dependencies.
+const DEPENDENCY_TABLES: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "dev_dependencies",
+    "build-dependencies",
+    "build_dependencies",
+];
+
+/// Moves the files of the package at `path` into `member_path`, recording the
+/// names of those moved so far in `moved`.
+fn move_package_files(path: &Path, member_path: &Path, moved: &mut Vec<String>) -> CargoResult<()> {
+    for entry in std::fs::read_dir(path)
+        .with_context(|| format!("failed to read directory `{}`", path.display()))?
+    {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        // Hidden files are left alone as they're mostly about the
+        // repository, like `.git` or `.gitignore`, rather than the package.
+        if file_name.starts_with('.')
+            || ["target", "Cargo.lock", WORKSPACE_MEMBERS_DIR].contains(&file_name)
+        {
+            continue;
+        }
+        std::fs::rename(entry.path(), member_path.join(file_name)).with_context(|| {
+            format!(
+                "failed to move `{}` to `{}`",
+                entry.path().display(),
+                member_path.display()
+            )
+        })?;
+        moved.push(file_name.to_string());
+    }
+    Ok(())
+}
+
+/// The value of a manifest key without its formatting, to compare it with
+/// another one.
+fn toml_value(item: &toml_edit::Item) -> Option<toml::Value> {
+    let mut value = item.as_value()?.clone();
+    value.decor_mut().clear();
+    toml::Value::deserialize(toml::de::ValueDeserializer::new(&value.to_string())).ok()
+}
+
+/// Makes `opts.path` the root of a new workspace with a virtual manifest.
+///
+/// A package already in the directory becomes the first member: it's moved
+/// into the members directory, its relative path dependencies are adjusted,
+/// and the tables only honored at the workspace root move to the new root
+/// manifest.
+pub fn init_workspace(opts: &NewOptions, gctx: &GlobalContext) -> CargoResult<()> {
+    let path = &opts.path;
+    gctx.shell().status("Creating", "workspace")?;
+
+    let manifest_path = path.join("Cargo.toml");
+    let package = if manifest_path.exists() {
+        let document = paths::read(&manifest_path)?
+            .parse::<toml_edit::DocumentMut>()
+            .with_context(|| {
+                format!("failed to parse manifest at `{}`", manifest_path.display())
+            })?;
+        if document.contains_key("workspace") {
+            anyhow::bail!("`cargo init --workspace` cannot be run on an existing workspace")
+        }
+        let Some(package) = document.get("package").and_then(|p| p.as_table_like()) else {
+            anyhow::bail!(
+                "`{}` is not the manifest of a package",
+                manifest_path.display()
+            )
+        };
+        let Some(name) = package.get("name").and_then(|n| n.as_str()) else {
+            anyhow::bail!("`{}` has no `package.name`", manifest_path.display())
+        };
+        if package.contains_key("workspace") {
+            anyhow::bail!("package `{name}` is already a member of a workspace")
+        }
+        let member = format!("{WORKSPACE_MEMBERS_DIR}/{name}");
+        if path.join(&member).exists() {
+            anyhow::bail!("`{}` already exists", path.join(&member).display())
+        }
+        Some((member, document))
+    } else {
+        None
+    };
+    check_path(path, &mut gctx.shell())?;
+
+    let package_str = |key: &str| {
+        package
+            .as_ref()
+            .and_then(|(_, document)| document["package"].get(key)?.as_str())
+            .map(|s| s.to_string())
+    };
+    let edition = opts
+        .edition
+        .clone()
+        .or_else(|| package_str("edition"))
+        .unwrap_or_else(|| Edition::LATEST_STABLE.to_string());
+    let resolver = Edition::from_str(&edition)?
+        .default_resolve_behavior()
+        .to_manifest();
+    let version = package_str("version").unwrap_or_else(|| "0.1.0".to_string());
+
+    let mut root = toml_edit::DocumentMut::new();
+    root["workspace"] = toml_edit::Item::Table(toml_edit::Table::new());
+    root["workspace"]["resolver"] = toml_edit::value(resolver);
+    let mut members = Array::new();
+    members.push(format!("{WORKSPACE_MEMBERS_DIR}/*"));
+    root["workspace"]["members"] = toml_edit::value(members);
+    let mut workspace_package = toml_edit::Table::new();
+    workspace_package["version"] = toml_edit::value(version);
+    workspace_package["edition"] = toml_edit::value(edition);
+    if let Some(registry) = &opts.registry {
+        let mut array = Array::new();
+        array.push(registry);
+        workspace_package["publish"] = toml_edit::value(array);
+    }
+
+    let requested_vcs = match opts.version_control {
+        Some(vcs) => Some(vcs),
+        None => detect_version_control(path)?,
+    };
+    let vcs = version_control(gctx, path, requested_vcs)?;
+    init_vcs(path, vcs, gctx)?;
+
+    let has_member = package.is_some();
+    if let Some((member, mut document)) = package {
+        gctx.shell().status(
+            "Moving",
+            format!(
+                "package `{}` to `{member}`",
+                document["package"]["name"].as_str().unwrap()
+            ),
+        )?;
+        let member_path = path.join(&member);
+        let members_dir_existed = path.join(WORKSPACE_MEMBERS_DIR).exists();
+        paths::create_dir_all(&member_path)?;
+        let mut moved = Vec::new();
+        if let Err(e) = move_package_files(path, &member_path, &mut moved) {
+            // Put back what was moved, so the package is left as it was.
+            for file_name in moved.iter().rev() {
+                let _ = std::fs::rename(member_path.join(file_name), path.join(file_name));
+            }
+            let _ = std::fs::remove_dir(&member_path);
+            if !members_dir_existed {
+                let _ = std::fs::remove_dir(path.join(WORKSPACE_MEMBERS_DIR));
+            }
+            return Err(e);
+        }
+
+        for key in WORKSPACE_ROOT_TABLES {
+            if let Some(table) = document.remove(key) {
+                root[key] = table;
+            }
+        }
+        let prefix = vec![".."; Path::new(&member).components().count()].join("/");
+        rebase_path_dependencies(document.as_table_mut(), &prefix, &moved);
+        if let Some(targets) = document
+            .get_mut("target")
+            .and_then(|t| t.as_table_like_mut())
+        {
+            for (_, target) in targets.iter_mut() {
+                if let Some(target) = target.as_table_like_mut() {
+                    rebase_path_dependencies(target, &prefix, &moved);
+                }
+            }
+        }
+
+        // Inherit the keys the package has the same value for.
+        let package = document["package"]
+            .as_table_like_mut()
+            .expect("package is a table");
+        for (key, value) in workspace_package.iter() {
+            let value = toml_value(value);
+            if value.is_some() && package.get(key).and_then(toml_value) == value {
+                let mut table = toml_edit::Table::new();
+                table.set_dotted(true);
+                table["workspace"] = toml_edit::value(true);
+                package.insert(key, toml_edit::Item::Table(table));
+            }
+        }
+        paths::write(member_path.join("Cargo.toml"), document.to_string())?;
+    }
+
+    root["workspace"]["package"] = toml_edit::Item::Table(workspace_package);
+    paths::write(&manifest_path, root.to_string())?;
+
+    let mut ignore = IgnoreList::new();
+    ignore.push("/target", "^target$", "target");
+    write_ignore_file(path, &ignore, vcs)?;
+
+    // The `members` glob doesn't match anything until a member is created.
+    if has_member {
+        if let Err(e) = Workspace::new(&manifest_path, gctx) {
+            crate::display_warning_with_error(
+                "compiling this workspace may not work due to invalid \
+                 workspace configuration",
+                &e,
+                &mut gctx.shell(),
+            );
+        }
+    }
+
+    gctx.shell().note(format!(
+        "create members with `cargo new {WORKSPACE_MEMBERS_DIR}/<name>` to have them \
+         inherit `[workspace.package]`"
+    ))?;
+
+    Ok(())
+}
+
+/// Adjusts the relative `path` of the dependencies listed in `table` for a
+/// package manifest moved down into a subdirectory, `prefix` being the way
+/// back up. Dependencies under one of the `moved` entries moved along and are
+/// left alone.
+fn rebase_path_dependencies(table: &mut dyn toml_edit::TableLike, prefix: &str, moved: &[String]) {
+    for key in DEPENDENCY_TABLES {
+        let Some(deps) = table.get_mut(key).and_then(|d| d.as_table_like_mut()) else {
+            continue;
+        };
+        for (_, dep) in deps.iter_mut() {
+            let Some(dep_path) = dep
+                .as_table_like_mut()
+                .and_then(|dep| dep.get_mut("path"))
+                .and_then(|p| p.as_value_mut())
+            else {
+                continue;
+            };
+            let Some(old) = dep_path.as_str() else {
+                continue;
+            };
+            let old = old.trim_start_matches("./");
+            if Path::new(old).is_absolute() {
+                continue;
+            }
+            let first = Path::new(old).components().next();
+            if matches!(first, Some(Component::Normal(c)) if moved.iter().any(|m| c == m.as_str()))
+            {
+                continue;
+            }
+            let rebased = format!("{prefix}/{old}");
+            let decor = dep_path.decor().clone();
+            *dep_path = rebased.into();
+            *dep_path.decor_mut() = decor;
+        }
+    }
+}
+
+/// Detects the version control system already set up in `path`.
+fn detect_version_control(path: &Path) -> CargoResult<Option<VersionControl>> {
+    let mut version_control = None;
+    let mut num_detected_vcses = 0;
+
+    if path.join(".git").exists() {
+        version_control = Some(VersionControl::Git);
+        num_detected_vcses += 1;
+    }
+
+    if path.join(".hg").exists() {
+        version_control = Some(VersionControl::Hg);
+        num_detected_vcses += 1;
+    }
+
+    if path.join(".pijul").exists() {
+        version_control = Some(VersionControl::Pijul);
+        num_detected_vcses += 1;
+    }
+
+    if path.join(".fossil").exists() {
+        version_control = Some(VersionControl::Fossil);
+        num_detected_vcses += 1;
+    }
+
+    // if none exists, maybe create git, like in `cargo new`
+
+    if num_detected_vcses > 1 {
+        anyhow::bail!(
+            "more than one of .hg, .git, .pijul, .fossil configurations \
+             found and the ignore file can't be filled in as \
+             a result. specify --vcs to override detection"
+        );
+    }
+    Ok(version_control)
+}
+
+/// Fetches the template of `opts`, if any, for a package named `name`.
+fn load_template(
+    opts: &NewOptions,
+    name: &str,
+    gctx: &GlobalContext,
+) -> CargoResult<Option<Template>> {
+    let Some(spec) = &opts.template else {
+        return Ok(None);
+    };
+    let edition = match &opts.edition {
+        Some(edition) => edition.clone(),
+        None => Edition::LATEST_STABLE.to_string(),
+    };
+    let template = Template::load(
+        spec,
+        opts.registry.as_deref(),
+        name,
+        &edition,
+        &opts.template_variables,
+        gctx,
+    )?;
+    Ok(Some(template))
+}
+
 /// IgnoreList
 struct IgnoreList {
     /// git like formatted entries
@@ -740,17 +1053,15 @@ fn init_vcs(path: &Path, vcs: VersionControl, gctx: &GlobalContext) -> CargoResu
     Ok(())
 }
 
-fn mk(gctx: &GlobalContext, opts: &MkOptions<'_>) -> CargoResult<()> {
-    let path = opts.path;
-    let name = opts.name;
+/// Picks the version control system to set up in `path`: the requested one,
+/// or else the `cargo-new.vcs` config unless `path` is already in a repository.
+fn version_control(
+    gctx: &GlobalContext,
+    path: &Path,
+    requested: Option<VersionControl>,
+) -> CargoResult<VersionControl> {
     let cfg = gctx.get::<CargoNewConfig>("cargo-new")?;
-
-    // Using the push method with multiple arguments ensures that the entries
-    // for all mutually-incompatible VCS in terms of syntax are in sync.
-    let mut ignore = IgnoreList::new();
-    ignore.push("/target", "^target$", "target");
-
-    let vcs = opts.version_control.unwrap_or_else(|| {
+    let vcs = requested.unwrap_or_else(|| {
         let in_existing_vcs = existing_vcs_repo(path.parent().unwrap_or(path), gctx.cwd());
         match (cfg.version_control, in_existing_vcs) {
             (None, false) => VersionControl::Git,
@@ -758,9 +1069,53 @@ fn mk(gctx: &GlobalContext, opts: &MkOptions<'_>) -> CargoResult<()> {
             (_, true) => VersionControl::NoVcs,
         }
     });
+    Ok(vcs)
+}
+
+fn mk(gctx: &GlobalContext, opts: &MkOptions<'_>) -> CargoResult<()> {
+    let path = opts.path;
+
+    // Using the push method with multiple arguments ensures that the entries
+    // for all mutually-incompatible VCS in terms of syntax are in sync.
+    let mut ignore = IgnoreList::new();
+    ignore.push("/target", "^target$", "target");
+
+    let vcs = version_control(gctx, path, opts.version_control)?;
 
     init_vcs(path, vcs, gctx)?;
-    write_ignore_file(path, &ignore, vcs)?;
+    match opts.template {
+        Some(template) => {
+            template.write(path)?;
+            write_ignore_file(path, &ignore, vcs)?;
+            add_to_workspace(gctx, path)?;
+        }
+        None => {
+            write_ignore_file(path, &ignore, vcs)?;
+            write_default_package(gctx, opts)?;
+        }
+    }
+
+    if let Err(e) = Workspace::new(&path.join("Cargo.toml"), gctx) {
+        crate::display_warning_with_error(
+            "compiling this new package may not work due to invalid \
+             workspace configuration",
+            &e,
+            &mut gctx.shell(),
+        );
+    }
+
+    gctx.shell().note(
+        "see more `Cargo.toml` keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html",
+    )?;
+
+    Ok(())
+}
+
+/// Writes the manifest and source files of a package from the built-in
+/// templates.
+fn write_default_package(gctx: &GlobalContext, opts: &MkOptions<'_>) -> CargoResult<()> {
+    let path = opts.path;
+    let name = opts.name;
 
     // Create `Cargo.toml` file with necessary `[lib]` and `[[bin]]` sections, if needed.
     let mut manifest = toml_edit::DocumentMut::new();
@@ -906,19 +1261,38 @@ mod tests {
         }
     }
 
-    if let Err(e) = Workspace::new(&path.join("Cargo.toml"), gctx) {
-        crate::display_warning_with_error(
-            "compiling this new package may not work due to invalid \
-             workspace configuration",
-            &e,
-            &mut gctx.shell(),
-        );
-    }
-
-    gctx.shell().note(
-        "see more `Cargo.toml` keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html",
-    )?;
+    Ok(())
+}
 
+/// Adds the package at `path` to the members of the workspace it's in, if it
+/// can be one.
+fn add_to_workspace(gctx: &GlobalContext, path: &Path) -> CargoResult<()> {
+    let Ok(root_manifest_path) = find_root_manifest_for_wd(&path.join("Cargo.toml")) else {
+        return Ok(());
+    };
+    let root_manifest = paths::read(&root_manifest_path)?;
+    let Ok(mut workspace_document) = root_manifest.parse::<toml_edit::DocumentMut>() else {
+        return Ok(());
+    };
+    let display_path = get_display_path(&root_manifest_path, &path)?;
+    if !can_be_workspace_member(&display_path, &workspace_document)? {
+        return Ok(());
+    }
+    if update_manifest_with_new_member(&root_manifest_path, &mut workspace_document, &display_path)?
+    {
+        gctx.shell().status(
+            "Adding",
+            format!(
+                "`{}` as member of workspace at `{}`",
+                PathBuf::from(&display_path)
+                    .file_name()
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+                root_manifest_path.parent().unwrap().display()
+            ),
+        )?
+    }
     Ok(())
 }
 
@@ -975,12 +1349,32 @@ fn update_manifest_with_new_member(
         return Ok(false);
     };
 
-    // If the members element already exist, check if one of the patterns
+    if !insert_workspace_path(workspace, "members", display_path)? {
+        return Ok(false);
+    }
+
+    write_atomic(
+        &root_manifest_path,
+        workspace_document.to_string().to_string().as_bytes(),
+    )?;
+    Ok(true)
+}
+
+/// Adds `display_path` to the `key` array (like `members`) of a `[workspace]` table,
+/// returning `false` if one of the patterns in the array already includes it.
+///
+/// The array is created if it doesn't exist, and kept sorted if it was sorted.
+pub(crate) fn insert_workspace_path(
+    workspace: &mut toml_edit::Item,
+    key: &str,
+    display_path: &str,
+) -> CargoResult<bool> {
+    // If the array already exist, check if one of the patterns
     // in the array already includes the new package's relative path.
-    // - Add the relative path if the members don't match the new package's path.
-    // - Create a new members array if there are no members element in the workspace yet.
+    // - Add the relative path if the patterns don't match the new package's path.
+    // - Create a new array if there is no such element in the workspace yet.
     if let Some(members) = workspace
-        .get_mut("members")
+        .get_mut(key)
         .and_then(|members| members.as_array_mut())
     {
         for member in members.iter() {
@@ -996,7 +1390,18 @@ fn update_manifest_with_new_member(
         }
 
         let was_sorted = is_sorted(members.iter().map(Value::as_str));
-        members.push(display_path);
+        // Keep one-entry-per-line arrays that way.
+        let multiline_prefix = members
+            .iter()
+            .last()
+            .and_then(|last| last.decor().prefix())
+            .and_then(|prefix| prefix.as_str())
+            .filter(|prefix| prefix.contains('\n'))
+            .map(str::to_owned);
+        match multiline_prefix {
+            Some(prefix) => members.push_formatted(Value::from(display_path).decorated(prefix, "")),
+            None => members.push(display_path),
+        }
         if was_sorted {
             members.sort_by(|lhs, rhs| lhs.as_str().cmp(&rhs.as_str()));
         }
@@ -1004,17 +1409,15 @@ fn update_manifest_with_new_member(
         let mut array = Array::new();
         array.push(display_path);
 
-        workspace["members"] = toml_edit::value(array);
+        workspace[key] = toml_edit::value(array);
     }
-
-    write_atomic(
-        &root_manifest_path,
-        workspace_document.to_string().to_string().as_bytes(),
-    )?;
     Ok(true)
 }
 
-fn get_display_path(root_manifest_path: &Path, package_path: &Path) -> CargoResult<String> {
+pub(crate) fn get_display_path(
+    root_manifest_path: &Path,
+    package_path: &Path,
+) -> CargoResult<String> {
     // Find the relative path for the package from the workspace root directory.
     let workspace_root = root_manifest_path.parent().with_context(|| {
         format!(
@@ -1042,7 +1445,7 @@ fn get_display_path(root_manifest_path: &Path, package_path: &Path) -> CargoResu
 }
 
 // Check if the package can be a member of the workspace.
-fn can_be_workspace_member(
+pub(crate) fn can_be_workspace_member(
     display_path: &str,
     workspace_document: &toml_edit::DocumentMut,
 ) -> CargoResult<bool> {
@@ -0,0 +1,296 @@
+//! Project templates for `cargo new --template` and `cargo init --template`.
+//!
+//! A template is a directory with a `cargo-template.toml` at its root, taken
+//! from a local path, a git repository or a package in a registry:
+//!
+//! ```toml
+//! [template]
+//! # Directory holding the files of the new package, relative to this file.
+//! # Defaults to the directory of this file.
+//! path = "template"
+//!
+//! [template.variables.license]
+//! prompt = "License of the package"
+//! default = "MIT OR Apache-2.0"
+//! ```
+//!
+//! Every file is copied into the new package with `{{ variable }}` replaced by
+//! the value of the variable, in both its contents and its path. Besides the
+//! declared variables there are `name`, `crate_name` and `edition`. Files
+//! ending in `.tmpl` lose that suffix, so a template published to a registry
+//! can carry a `Cargo.toml.tmpl` without it being taken for a nested package.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::IsTerminal as _;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context as _};
+use cargo_util::paths;
+use serde::Deserialize;
+
+use super::common_for_install_and_uninstall::select_dep_pkg;
+use crate::core::{Dependency, GitReference, SourceId};
+use crate::sources::{GitSource, SourceConfigMap};
+use crate::util::cache_lock::CacheLockMode;
+use crate::util::errors::CargoResult;
+use crate::util::{GlobalContext, IntoUrl};
+
+/// Name of the file describing a template.
+const TEMPLATE_MANIFEST: &str = "cargo-template.toml";
+
+/// Suffix stripped from the names of template files.
+const TEMPLATE_SUFFIX: &str = ".tmpl";
+
+#[derive(Deserialize)]
+struct TemplateManifest {
+    template: TemplateTable,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct TemplateTable {
+    path: Option<PathBuf>,
+    #[serde(default)]
+    variables: BTreeMap<String, TemplateVariable>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct TemplateVariable {
+    prompt: Option<String>,
+    default: Option<String>,
+}
+
+/// A template ready to be written out, with the values of all its variables.
+pub struct Template {
+    /// Directory holding the files of the template.
+    root: PathBuf,
+    variables: BTreeMap<String, String>,
+}
+
+impl Template {
+    /// Fetches the template `spec` and resolves its variables, from
+    /// `defines` or else by prompting for them.
+    ///
+    /// `spec` is a directory if one exists at that path, a git repository if
+    /// it's a URL, and otherwise a `name[@version]` package of `registry`.
+    pub fn load(
+        spec: &str,
+        registry: Option<&str>,
+        name: &str,
+        edition: &str,
+        defines: &[(String, String)],
+        gctx: &GlobalContext,
+    ) -> CargoResult<Template> {
+        let dir = fetch(spec, registry, gctx)?.canonicalize()?;
+        let manifest_path = dir.join(TEMPLATE_MANIFEST);
+        if !manifest_path.is_file() {
+            bail!("template `{spec}` has no `{TEMPLATE_MANIFEST}`");
+        }
+        let manifest: TemplateManifest = toml::from_str(&paths::read(&manifest_path)?)
+            .with_context(|| format!("failed to parse `{}`", manifest_path.display()))?;
+        let table = manifest.template;
+        let root = match table.path {
+            Some(path) => dir.join(path),
+            None => dir.clone(),
+        };
+        if !root.is_dir() {
+            bail!(
+                "`template.path` of template `{spec}` is not a directory: `{}`",
+                root.display()
+            );
+        }
+        // Resolve symlinks too, so the template can't copy files from
+        // elsewhere on the machine.
+        let root = root.canonicalize()?;
+        if !root.starts_with(&dir) {
+            bail!(
+                "`template.path` of template `{spec}` is outside of the template: `{}`",
+                root.display()
+            );
+        }
+
+        let mut variables = BTreeMap::new();
+        variables.insert("name".to_string(), name.to_string());
+        variables.insert("crate_name".to_string(), name.replace('-', "_"));
+        variables.insert("edition".to_string(), edition.to_string());
+        for (key, _) in defines {
+            if variables.contains_key(key) {
+                bail!("the template variable `{key}` can't be set with `--define`");
+            }
+            if !table.variables.contains_key(key) {
+                bail!("template `{spec}` has no variable `{key}`");
+            }
+        }
+        for (key, variable) in &table.variables {
+            if variables.contains_key(key) {
+                bail!("template `{spec}` declares the built-in variable `{key}`");
+            }
+            let value = match defines.iter().rev().find(|(k, _)| k == key) {
+                Some((_, value)) => value.clone(),
+                None => ask(key, variable, gctx)?,
+            };
+            variables.insert(key.clone(), value);
+        }
+
+        Ok(Template { root, variables })
+    }
+
+    /// Writes the files of the template into `dst`, refusing to overwrite
+    /// any existing file.
+    pub fn write(&self, dst: &Path) -> CargoResult<()> {
+        let mut files = Vec::new();
+        for entry in walkdir::WalkDir::new(&self.root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git")
+        {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(&self.root)?;
+            if relative == Path::new(TEMPLATE_MANIFEST) {
+                continue;
+            }
+            let mut target = self.substitute(&relative.to_string_lossy());
+            if let Some(stripped) = target.strip_suffix(TEMPLATE_SUFFIX) {
+                target.truncate(stripped.len());
+            }
+            // A variable may expand to `..` or an absolute path.
+            if !Path::new(&target)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                bail!(
+                    "template file `{}` would be written outside of the package: `{target}`",
+                    relative.display()
+                );
+            }
+            files.push((entry.into_path(), dst.join(target)));
+        }
+
+        if !files
+            .iter()
+            .any(|(_, target)| *target == dst.join("Cargo.toml"))
+        {
+            bail!("template does not have a `Cargo.toml`");
+        }
+        let mut seen = HashSet::new();
+        for (_, target) in &files {
+            if target.exists() || !seen.insert(target) {
+                bail!("`{}` already exists", target.display());
+            }
+        }
+
+        for (source, target) in &files {
+            if let Some(parent) = target.parent() {
+                paths::create_dir_all(parent)?;
+            }
+            let contents = paths::read_bytes(source)?;
+            match String::from_utf8(contents) {
+                Ok(text) => paths::write(target, self.substitute(&text))?,
+                Err(e) => paths::write(target, e.into_bytes())?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces every `{{ variable }}` in `text`. Anything else between
+    /// braces, like a `{{x}}` in a format string, is left alone.
+    fn substitute(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let value = after.find("}}").and_then(|end| {
+                let value = self.variables.get(after[..end].trim())?;
+                Some((value, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    out.push_str(value);
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    out.push_str("{{");
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Makes the template `spec` available locally, returning its directory.
+fn fetch(spec: &str, registry: Option<&str>, gctx: &GlobalContext) -> CargoResult<PathBuf> {
+    let path = gctx.cwd().join(spec);
+    if path.is_dir() {
+        return Ok(path);
+    }
+
+    if spec.contains("://") {
+        let url = spec.into_url()?;
+        let source_id = SourceId::for_git(&url, GitReference::DefaultBranch)?;
+        let mut source = GitSource::new(source_id, gctx)?;
+        let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+        return source.checkout();
+    }
+
+    if spec.contains(['/', '\\']) || spec.starts_with('.') {
+        bail!("template `{spec}` is not a directory");
+    }
+    let (name, version) = match spec.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None),
+    };
+    let source_id = match registry {
+        Some(registry) => SourceId::alt_registry(gctx, registry)?,
+        None => SourceId::crates_io_maybe_sparse_http(gctx)?,
+    };
+    let dep = Dependency::parse(name, version, source_id)?;
+    let map = SourceConfigMap::new(gctx)?;
+    let mut source = map.load(source_id, &HashSet::new())?;
+    let pkg = select_dep_pkg(&mut source, dep, gctx, true, None)?;
+    Ok(pkg.root().to_path_buf())
+}
+
+/// Asks the user for the value of `key`, if stdin is a terminal, falling back
+/// to the default of the variable.
+fn ask(key: &str, variable: &TemplateVariable, gctx: &GlobalContext) -> CargoResult<String> {
+    if !std::io::stdin().is_terminal() {
+        return match &variable.default {
+            Some(default) => Ok(default.clone()),
+            None => bail!(
+                "no value for the template variable `{key}`\n\n\
+                 pass `--define {key}=<VALUE>` to set it"
+            ),
+        };
+    }
+
+    let prompt = variable.prompt.as_deref().unwrap_or(key);
+    loop {
+        {
+            let mut shell = gctx.shell();
+            let err = shell.err();
+            match &variable.default {
+                Some(default) => write!(err, "{prompt} [{default}]: ")?,
+                None => write!(err, "{prompt}: ")?,
+            }
+            err.flush()?;
+        }
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            bail!("no value for the template variable `{key}`");
+        }
+        let line = line.trim();
+        if !line.is_empty() {
+            return Ok(line.to_string());
+        }
+        if let Some(default) = &variable.default {
+            return Ok(default.clone());
+        }
+    }
+}
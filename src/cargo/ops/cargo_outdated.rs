@@ -0,0 +1,305 @@
+//! Implementation of `cargo outdated`.
+//!
+//! For every direct registry dependency of the selected workspace members,
+//! the version in `Cargo.lock` is compared against the versions in the index:
+//!
+//! * the newest one the version requirement allows, which `cargo update`
+//!   would lock;
+//! * the newest one overall, which needs the requirement to be changed, like
+//!   `cargo update --breaking` does.
+//!
+//! Versions needing a newer Rust than the `rust-version` of the member are
+//! left out of both, but are reported separately.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+use cargo_util_schemas::manifest::RustVersion;
+use semver::Version;
+use serde::Serialize;
+
+use crate::core::dependency::{DepKind, Dependency};
+use crate::core::registry::PackageRegistry;
+use crate::core::{PackageIdSpec, Registry as _, SourceId, Summary, Workspace};
+use crate::drop_print;
+use crate::ops::{self, Packages};
+use crate::sources::source::QueryKind;
+use crate::util::cache_lock::CacheLockMode;
+use crate::util::interning::InternedString;
+use crate::util::{CargoResult, GlobalContext, OptVersionReq};
+
+use super::cargo_update::is_latest;
+
+pub struct OutdatedOptions<'a> {
+    pub gctx: &'a GlobalContext,
+    /// The members to check the dependencies of.
+    pub packages: Packages,
+    pub format: OutdatedFormat,
+    /// Also suggest versions needing a newer Rust than the `rust-version` of
+    /// the member.
+    pub ignore_rust_version: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutdatedFormat {
+    Human,
+    Json,
+}
+
+/// An outdated dependency of a member.
+#[derive(Serialize)]
+struct OutdatedDependency {
+    /// The name of the dependency in the manifest.
+    name: InternedString,
+    /// The name of the package, if the dependency is renamed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package: Option<InternedString>,
+    kind: &'static str,
+    /// The platform the dependency is limited to.
+    target: Option<String>,
+    requirement: String,
+    source: Option<String>,
+    locked: Version,
+    /// The newest version the requirement allows, if it's newer than the
+    /// locked one.
+    compatible: Option<Version>,
+    /// The newest version, if it's newer than the locked one.
+    latest: Option<Version>,
+    /// The versions that would be `compatible` or `latest` if they didn't
+    /// need a newer Rust than the member.
+    needs_newer_rust: Vec<IncompatibleVersion>,
+}
+
+#[derive(Serialize)]
+struct IncompatibleVersion {
+    version: Version,
+    rust_version: RustVersion,
+}
+
+#[derive(Serialize)]
+struct OutdatedMember {
+    id: PackageIdSpec,
+    name: InternedString,
+    version: Version,
+    rust_version: Option<RustVersion>,
+    dependencies: Vec<OutdatedDependency>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    members: Vec<OutdatedMember>,
+}
+
+/// Prints the dependencies of the selected members with newer versions in
+/// the index.
+pub fn outdated(ws: &Workspace<'_>, opts: &OutdatedOptions<'_>) -> CargoResult<()> {
+    let gctx = opts.gctx;
+    let mut members = opts.packages.get_packages(ws)?;
+    members.sort_by_key(|member| member.package_id());
+    // Only reports, so the lock file is left as it is.
+    let dry_run = true;
+    let (_pkg_set, resolve) = ops::resolve_ws(ws, dry_run)?;
+
+    let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+    let mut registry = ws.package_registry()?;
+    registry.lock_patches();
+    let mut versions = HashMap::new();
+
+    let mut report = Report {
+        members: Vec::new(),
+    };
+    for member in members {
+        let member_id = member.package_id();
+        let rust_version = member.rust_version().filter(|_| !opts.ignore_rust_version);
+        // One row for every declaration, for dependencies that are both
+        // normal and dev-dependencies for example.
+        let mut rows = BTreeMap::new();
+        for (dep_id, deps) in resolve.deps(member_id) {
+            if !dep_id.source_id().is_registry() {
+                continue;
+            }
+            let summaries = query(
+                &mut registry,
+                &mut versions,
+                dep_id.name(),
+                dep_id.source_id(),
+            )?;
+            for dep in deps {
+                // The requirement as written, not locked to the version of
+                // the lock file.
+                let requirement = match dep.version_req() {
+                    OptVersionReq::Any => None,
+                    OptVersionReq::Req(req)
+                    | OptVersionReq::Locked(_, req)
+                    | OptVersionReq::Precise(_, req) => Some(req),
+                };
+                let newer = |allowed: &dyn Fn(&Summary) -> bool| {
+                    summaries
+                        .iter()
+                        .filter(|s| is_latest(s.version(), dep_id.version()) && allowed(s))
+                        .max_by_key(|s| s.version())
+                };
+                let fits_rust = |s: &Summary| match (s.rust_version(), rust_version) {
+                    (Some(needed), Some(msrv)) => needed.is_compatible_with(msrv.as_partial()),
+                    _ => true,
+                };
+                let allowed =
+                    |s: &Summary| requirement.map_or(true, |req| req.matches(s.version()));
+                let compatible = newer(&|s| fits_rust(s) && allowed(s));
+                let latest = newer(&|s| fits_rust(s));
+                let mut needs_newer_rust: Vec<_> = [newer(&allowed), newer(&|_| true)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|s| !fits_rust(s))
+                    .map(|s| IncompatibleVersion {
+                        version: s.version().clone(),
+                        rust_version: s.rust_version().unwrap().clone(),
+                    })
+                    .collect();
+                needs_newer_rust.dedup_by(|a, b| a.version == b.version);
+                if compatible.is_none() && latest.is_none() && needs_newer_rust.is_empty() {
+                    continue;
+                }
+                let row = OutdatedDependency {
+                    name: dep.name_in_toml(),
+                    package: (dep.name_in_toml() != dep.package_name()).then(|| dep.package_name()),
+                    kind: match dep.kind() {
+                        DepKind::Normal => "normal",
+                        DepKind::Development => "dev",
+                        DepKind::Build => "build",
+                    },
+                    target: dep.platform().map(|p| p.to_string()),
+                    requirement: requirement.map_or("*".to_string(), |req| req.to_string()),
+                    source: dep_id.source_id().serialized(),
+                    locked: dep_id.version().clone(),
+                    compatible: compatible.map(|s| s.version().clone()),
+                    latest: latest.map(|s| s.version().clone()),
+                    needs_newer_rust,
+                };
+                rows.insert((row.name, dep.kind(), row.target.clone()), row);
+            }
+        }
+        report.members.push(OutdatedMember {
+            id: member_id.to_spec(),
+            name: member.name(),
+            version: member.version().clone(),
+            rust_version: member.rust_version().cloned(),
+            dependencies: rows.into_values().collect(),
+        });
+    }
+
+    match opts.format {
+        OutdatedFormat::Json => gctx.shell().print_json(&report)?,
+        OutdatedFormat::Human => {
+            let members: Vec<_> = report
+                .members
+                .iter()
+                .filter(|member| !member.dependencies.is_empty())
+                .collect();
+            if members.is_empty() {
+                gctx.shell().note("all dependencies are up to date")?;
+            }
+            let many = ws.members().count() > 1;
+            for (i, member) in members.into_iter().enumerate() {
+                if i != 0 {
+                    drop_print!(gctx, "\n");
+                }
+                drop_print!(gctx, "{}", table(member, many));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the summaries of every version of a package in a registry.
+fn query<'a>(
+    registry: &mut PackageRegistry<'_>,
+    versions: &'a mut HashMap<(InternedString, SourceId), Vec<Summary>>,
+    name: InternedString,
+    source_id: SourceId,
+) -> CargoResult<&'a [Summary]> {
+    // Without the `locked` precise of the lock file, the index is updated.
+    let source_id = source_id.without_precise();
+    if !versions.contains_key(&(name, source_id)) {
+        let query = Dependency::parse(name, None, source_id)?;
+        let summaries = loop {
+            match registry.query_vec(&query, QueryKind::Exact) {
+                std::task::Poll::Ready(res) => break res?,
+                std::task::Poll::Pending => registry.block_until_ready()?,
+            }
+        };
+        let summaries = summaries.into_iter().map(|s| s.into_summary()).collect();
+        versions.insert((name, source_id), summaries);
+    }
+    Ok(&versions[&(name, source_id)])
+}
+
+/// Formats the outdated dependencies of a member as a table, with a heading
+/// naming the member if the workspace has several.
+fn table(member: &OutdatedMember, with_heading: bool) -> String {
+    let version = |v: &Option<Version>| v.as_ref().map_or("-".to_string(), |v| v.to_string());
+    let mut rows = vec![[
+        "name".to_string(),
+        "kind".to_string(),
+        "requirement".to_string(),
+        "locked".to_string(),
+        "compatible".to_string(),
+        "latest".to_string(),
+    ]];
+    let mut notes = Vec::new();
+    for dep in &member.dependencies {
+        let mut kind = dep.kind.to_string();
+        if let Some(target) = &dep.target {
+            write!(kind, " ({target})").unwrap();
+        }
+        rows.push([
+            dep.name.to_string(),
+            kind,
+            dep.requirement.clone(),
+            dep.locked.to_string(),
+            version(&dep.compatible),
+            version(&dep.latest),
+        ]);
+        for incompatible in &dep.needs_newer_rust {
+            notes.push(format!(
+                "{} v{} (Rust {})",
+                dep.package.unwrap_or(dep.name),
+                incompatible.version,
+                incompatible.rust_version
+            ));
+        }
+    }
+
+    let mut widths = [0; 6];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut out = String::new();
+    if with_heading {
+        writeln!(out, "{} v{}:", member.name, member.version).unwrap();
+    }
+    for row in &rows {
+        let line: Vec<_> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end()).unwrap();
+    }
+    if !notes.is_empty() {
+        let rust_version = member
+            .rust_version
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "\nnot suggested for needing a newer Rust than {rust_version}: {}",
+            notes.join(", ")
+        )
+        .unwrap();
+    }
+    out
+}
@@ -1,132 +1,238 @@
 use crate::core::compiler::artifact::match_artifacts_kind_with_targets;
 use crate::core::compiler::{CompileKind, RustcTargetData};
 use crate::core::dependency::DepKind;
-use crate::core::package::SerializedPackage;
-use crate::core::resolver::{features::CliFeatures, HasDevUnits, Resolve};
-use crate::core::{Package, PackageId, PackageIdSpec, Workspace};
+use crate::core::resolver::features::{CliFeatures, FeaturesFor, ResolvedFeatures};
+use crate::core::resolver::{HasDevUnits, Resolve};
+use crate::core::{FeatureValue, Package, PackageId, PackageIdSpec, Workspace};
 use crate::ops::{self, Packages};
 use crate::util::interning::InternedString;
 use crate::util::CargoResult;
 use cargo_platform::Platform;
+use cargo_util_schemas::metadata::{self, FeatureEdge, FeatureGraph, FeatureNode};
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
-const VERSION: u32 = 1;
+/// The top-level sections `--only` can select, by the name of their key
+/// with `-` in place of `_`.
+pub const METADATA_SECTIONS: &[&str] = &[
+    "packages",
+    "workspace-members",
+    "workspace-default-members",
+    "resolve",
+    "target-directory",
+    "workspace-root",
+    "metadata",
+];
 
 pub struct OutputMetadataOptions {
     pub cli_features: CliFeatures,
     pub no_deps: bool,
     pub version: u32,
     pub filter_platforms: Vec<String>,
+    /// Adds the `feature_graph` section.
+    pub include_feature_graph: bool,
+    /// Sections of [`METADATA_SECTIONS`] to output, all of them if empty.
+    pub only: Vec<String>,
+    /// Lists the packages of the dependency graph without resolving their
+    /// features, leaving `resolve` null.
+    pub no_feature_resolution: bool,
 }
 
 /// Loads the manifest, resolves the dependencies of the package to the concrete
 /// used versions - considering overrides - and writes all dependencies in a JSON
 /// format to stdout.
 pub fn output_metadata(ws: &Workspace<'_>, opt: &OutputMetadataOptions) -> CargoResult<ExportInfo> {
-    if opt.version != VERSION {
+    if !metadata::FORMAT_VERSIONS.contains(&opt.version) {
+        let supported: Vec<_> = metadata::FORMAT_VERSIONS
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
         anyhow::bail!(
-            "metadata version {} not supported, only {} is currently supported",
+            "metadata version {} not supported, only {} are currently supported",
             opt.version,
-            VERSION
+            supported.join(" and ")
         );
     }
-    let (packages, resolve) = if opt.no_deps {
-        let packages = ws.members().map(|pkg| pkg.serialized()).collect();
-        (packages, None)
+    // Format version 2 always has the feature graph.
+    let include_feature_graph = opt.include_feature_graph || opt.version >= 2;
+    let wants = |section: &str| opt.only.is_empty() || opt.only.iter().any(|s| s == section);
+    if opt.no_feature_resolution && opt.only.iter().any(|s| s == "resolve") {
+        anyhow::bail!("`--only resolve` can't be used with `--no-feature-resolution`");
+    }
+
+    // Skip as much of the resolver as the requested sections allow.
+    let (packages, resolve, feature_graph) = if opt.no_deps {
+        (ws.members().cloned().collect(), None, None)
+    } else if opt.no_feature_resolution {
+        let packages = if wants("packages") {
+            resolved_packages(ws)?
+        } else {
+            Vec::new()
+        };
+        (packages, None, None)
+    } else if wants("packages") || wants("resolve") || opt.include_feature_graph {
+        let (packages, resolve, feature_graph) =
+            build_resolve_graph(ws, opt, include_feature_graph)?;
+        (packages, Some(resolve), feature_graph)
     } else {
-        let (packages, resolve) = build_resolve_graph(ws, opt)?;
-        (packages, Some(resolve))
+        (Vec::new(), None, None)
     };
 
     Ok(ExportInfo {
-        packages,
-        workspace_members: ws.members().map(|pkg| pkg.package_id().to_spec()).collect(),
-        workspace_default_members: ws
-            .default_members()
-            .map(|pkg| pkg.package_id().to_spec())
-            .collect(),
-        resolve,
-        target_directory: ws.target_dir().into_path_unlocked(),
-        version: VERSION,
-        workspace_root: ws.root().to_path_buf(),
-        metadata: ws.custom_metadata().cloned(),
+        packages: Section::new(
+            wants("packages"),
+            SerializedPackages {
+                packages,
+                version: opt.version,
+            },
+        ),
+        workspace_members: Section::new(
+            wants("workspace-members"),
+            ws.members().map(|pkg| pkg.package_id().to_spec()).collect(),
+        ),
+        workspace_default_members: Section::new(
+            wants("workspace-default-members"),
+            ws.default_members()
+                .map(|pkg| pkg.package_id().to_spec())
+                .collect(),
+        ),
+        resolve: Section::new(wants("resolve"), resolve),
+        feature_graph,
+        target_directory: Section::new(
+            wants("target-directory"),
+            ws.target_dir().into_path_unlocked(),
+        ),
+        version: opt.version,
+        workspace_root: Section::new(wants("workspace-root"), ws.root().to_path_buf()),
+        metadata: Section::new(wants("metadata"), ws.custom_metadata().cloned()),
     })
 }
 
+/// The packages of the dependency graph, without resolving features or
+/// looking at targets.
+fn resolved_packages(ws: &Workspace<'_>) -> CargoResult<Vec<Package>> {
+    let dry_run = false;
+    let (pkg_set, resolve) = ops::resolve_ws(ws, dry_run)?;
+    let mut packages: Vec<_> = pkg_set
+        .get_many(resolve.iter())?
+        .into_iter()
+        .cloned()
+        .collect();
+    packages.sort_by_key(|pkg| pkg.package_id());
+    Ok(packages)
+}
+
 /// This is the structure that is serialized and displayed to the user.
 ///
 /// See cargo-metadata.adoc for detailed documentation of the format.
 #[derive(Serialize)]
 pub struct ExportInfo {
-    packages: Vec<SerializedPackage>,
-    workspace_members: Vec<PackageIdSpec>,
-    workspace_default_members: Vec<PackageIdSpec>,
-    resolve: Option<MetadataResolve>,
-    target_directory: PathBuf,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    packages: Section<SerializedPackages>,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    workspace_members: Section<Vec<PackageIdSpec>>,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    workspace_default_members: Section<Vec<PackageIdSpec>>,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    resolve: Section<Option<metadata::Resolve>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feature_graph: Option<FeatureGraph>,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    target_directory: Section<PathBuf>,
     version: u32,
-    workspace_root: PathBuf,
-    metadata: Option<toml::Value>,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    workspace_root: Section<PathBuf>,
+    #[serde(skip_serializing_if = "Section::is_omitted")]
+    metadata: Section<Option<toml::Value>>,
 }
 
-#[derive(Serialize)]
-struct MetadataResolve {
-    nodes: Vec<MetadataResolveNode>,
-    root: Option<PackageIdSpec>,
+/// A top-level section of [`ExportInfo`], which `--only` may leave out.
+enum Section<T> {
+    Included(T),
+    Omitted,
 }
 
-#[derive(Serialize)]
-struct MetadataResolveNode {
-    id: PackageIdSpec,
-    dependencies: Vec<PackageIdSpec>,
-    deps: Vec<Dep>,
-    features: Vec<InternedString>,
+impl<T> Section<T> {
+    fn new(included: bool, value: T) -> Section<T> {
+        if included {
+            Section::Included(value)
+        } else {
+            Section::Omitted
+        }
+    }
+
+    fn is_omitted(&self) -> bool {
+        matches!(self, Section::Omitted)
+    }
 }
 
-#[derive(Serialize)]
-struct Dep {
-    // TODO(bindeps): after -Zbindeps gets stabilized,
-    // mark this field as deprecated in the help manual of cargo-metadata
-    name: InternedString,
-    pkg: PackageIdSpec,
-    #[serde(skip)]
-    pkg_id: PackageId,
-    dep_kinds: Vec<DepKindInfo>,
+impl<T: Serialize> Serialize for Section<T> {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Section::Included(value) => value.serialize(s),
+            Section::Omitted => s.serialize_none(),
+        }
+    }
+}
+
+/// Packages serialized one at a time as the output is written, rather than
+/// all of them up front.
+struct SerializedPackages {
+    packages: Vec<Package>,
+    version: u32,
 }
 
-#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+impl Serialize for SerializedPackages {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(self.packages.iter().map(|pkg| {
+            let mut serialized = pkg.serialized();
+            if self.version >= 2 {
+                serialized.lints = Some(
+                    pkg.manifest()
+                        .resolved_toml()
+                        .lints
+                        .clone()
+                        .map(|lints| lints.lints)
+                        .unwrap_or_default(),
+                );
+            }
+            serialized
+        }))
+    }
+}
+
+/// A [`metadata::DepKindInfo`] that sorts the way the resolver does.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
 struct DepKindInfo {
     kind: DepKind,
     target: Option<Platform>,
-
-    // vvvvv The fields below are introduced for `-Z bindeps`.
-    /// What the manifest calls the crate.
-    ///
-    /// A renamed dependency will show the rename instead of original name.
     // TODO(bindeps): Remove `Option` after -Zbindeps get stabilized.
-    #[serde(skip_serializing_if = "Option::is_none")]
     extern_name: Option<InternedString>,
-    /// Artifact's crate type, e.g. staticlib, cdylib, bin...
-    #[serde(skip_serializing_if = "Option::is_none")]
     artifact: Option<&'static str>,
-    /// Equivalent to `{ target = "…" }` in an artifact dependency requirement.
-    ///
-    /// * If the target points to a custom target JSON file, the path will be absolute.
-    /// * If the target is a build assumed target `{ target = "target" }`, it will show as `<target>`.
-    #[serde(skip_serializing_if = "Option::is_none")]
     compile_target: Option<InternedString>,
-    /// Executable name for an artifact binary dependency.
-    #[serde(skip_serializing_if = "Option::is_none")]
     bin_name: Option<String>,
-    // ^^^^^ The fields above are introduced for `-Z bindeps`.
+}
+
+impl DepKindInfo {
+    fn serialized(self) -> metadata::DepKindInfo {
+        metadata::DepKindInfo {
+            kind: self.kind.serialized(),
+            target: self.target.map(|p| p.to_string()),
+            extern_name: self.extern_name.map(|n| n.to_string()),
+            artifact: self.artifact.map(|a| a.to_string()),
+            compile_target: self.compile_target.map(|t| t.to_string()),
+            bin_name: self.bin_name,
+        }
+    }
 }
 
 /// Builds the resolve graph as it will be displayed to the user.
 fn build_resolve_graph(
     ws: &Workspace<'_>,
     metadata_opts: &OutputMetadataOptions,
-) -> CargoResult<(Vec<SerializedPackage>, MetadataResolve)> {
+    include_feature_graph: bool,
+) -> CargoResult<(Vec<Package>, metadata::Resolve, Option<FeatureGraph>)> {
     // TODO: Without --filter-platform, features are being resolved for `host` only.
     // How should this work?
     let requested_kinds =
@@ -169,29 +275,41 @@ fn build_resolve_graph(
             &mut node_map,
             member_pkg.package_id(),
             &ws_resolve.targeted_resolve,
+            &ws_resolve.resolved_features,
             &package_map,
             &target_data,
             &requested_kinds,
         )?;
     }
+    let feature_graph = include_feature_graph.then(|| {
+        build_feature_graph(
+            ws,
+            &metadata_opts.cli_features,
+            &ws_resolve.targeted_resolve,
+            &ws_resolve.resolved_features,
+            &requested_kinds,
+            &package_map,
+            &node_map,
+        )
+    });
     // Get a Vec of Packages.
     let actual_packages = package_map
         .into_iter()
         .filter_map(|(pkg_id, pkg)| node_map.get(&pkg_id).map(|_| pkg))
-        .map(|pkg| pkg.serialized())
         .collect();
 
-    let mr = MetadataResolve {
-        nodes: node_map.into_iter().map(|(_pkg_id, node)| node).collect(),
+    let mr = metadata::Resolve {
+        nodes: node_map.into_values().collect(),
         root: ws.current_opt().map(|pkg| pkg.package_id().to_spec()),
     };
-    Ok((actual_packages, mr))
+    Ok((actual_packages, mr, feature_graph))
 }
 
 fn build_resolve_graph_r(
-    node_map: &mut BTreeMap<PackageId, MetadataResolveNode>,
+    node_map: &mut BTreeMap<PackageId, metadata::Node>,
     pkg_id: PackageId,
     resolve: &Resolve,
+    resolved_features: &ResolvedFeatures,
     package_map: &BTreeMap<PackageId, Package>,
     target_data: &RustcTargetData<'_>,
     requested_kinds: &[CompileKind],
@@ -215,6 +333,18 @@ fn build_resolve_graph_r(
     // an older (or newer!) version of Cargo which uses a different style.
     let normalize_id = |id| -> PackageId { *package_map.get_key_value(&id).unwrap().0 };
     let features = resolve.features(pkg_id).to_vec();
+    let target_features =
+        resolved_features
+            .activated_features_per_target(pkg_id)
+            .map(|per_target| {
+                per_target
+                    .into_iter()
+                    .map(|(target, features)| {
+                        let features = features.iter().map(|f| f.to_string()).collect();
+                        (target.rustc_target().to_string(), features)
+                    })
+                    .collect()
+            });
 
     let deps = {
         let mut dep_metadatas = Vec::new();
@@ -307,36 +437,36 @@ fn build_resolve_graph_r(
 
             let pkg_id = normalize_id(dep_id);
 
-            let dep = match (lib_target, dep_kinds.len()) {
-                (Some(target), _) => Dep {
-                    name: extern_name(target)?,
-                    pkg: pkg_id.to_spec(),
-                    pkg_id,
-                    dep_kinds,
-                },
+            // TODO(bindeps): after -Zbindeps gets stabilized,
+            // mark `name` as deprecated in the help manual of cargo-metadata
+            let name = match (lib_target, dep_kinds.len()) {
+                (Some(target), _) => extern_name(target)?,
                 // No lib target exists but contains artifact deps.
-                (None, 1..) => Dep {
-                    name: InternedString::new(""),
-                    pkg: pkg_id.to_spec(),
-                    pkg_id,
-                    dep_kinds,
-                },
+                (None, 1..) => InternedString::new(""),
                 // No lib or artifact dep exists.
                 // Usually this mean parent depending on non-lib bin crate.
                 (None, _) => continue,
             };
 
-            dep_metadatas.push(dep)
+            dep_metadatas.push((
+                pkg_id,
+                metadata::NodeDep {
+                    name: name.to_string(),
+                    pkg: pkg_id.to_spec(),
+                    dep_kinds: dep_kinds.into_iter().map(DepKindInfo::serialized).collect(),
+                },
+            ))
         }
         dep_metadatas
     };
 
-    let to_visit: Vec<PackageId> = deps.iter().map(|dep| dep.pkg_id).collect();
-    let node = MetadataResolveNode {
+    let to_visit: Vec<PackageId> = deps.iter().map(|(dep_id, _)| *dep_id).collect();
+    let node = metadata::Node {
         id: normalize_id(pkg_id).to_spec(),
         dependencies: to_visit.iter().map(|id| id.to_spec()).collect(),
-        deps,
-        features,
+        deps: deps.into_iter().map(|(_, dep)| dep).collect(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target_features,
     };
     node_map.insert(pkg_id, node);
     for dep_id in to_visit {
@@ -344,6 +474,7 @@ fn build_resolve_graph_r(
             node_map,
             dep_id,
             resolve,
+            resolved_features,
             package_map,
             target_data,
             requested_kinds,
@@ -352,3 +483,149 @@ fn build_resolve_graph_r(
 
     Ok(())
 }
+
+/// A package, or one of its features when the name is set.
+type FeatureGraphNode = (PackageId, Option<InternedString>);
+
+/// Records which edges activated which features of the packages in
+/// `node_map`, following the feature tables and dependency declarations the
+/// resolver activated them from.
+fn build_feature_graph(
+    ws: &Workspace<'_>,
+    cli_features: &CliFeatures,
+    resolve: &Resolve,
+    resolved_features: &ResolvedFeatures,
+    requested_kinds: &[CompileKind],
+    package_map: &BTreeMap<PackageId, Package>,
+    node_map: &BTreeMap<PackageId, metadata::Node>,
+) -> FeatureGraph {
+    // The graph is not split by target, so it has the features the feature
+    // resolver activated for each package for any of the requested targets
+    // or the host.
+    let features_fors: Vec<FeaturesFor> = requested_kinds
+        .iter()
+        .map(|kind| FeaturesFor::from_for_host_and_kind(false, *kind))
+        .chain([FeaturesFor::HostDep])
+        .collect();
+    let activated: BTreeMap<PackageId, BTreeSet<InternedString>> = node_map
+        .keys()
+        .map(|&pkg_id| {
+            let features = features_fors
+                .iter()
+                .flat_map(|fk| {
+                    resolved_features
+                        .activated_features_unverified(pkg_id, *fk)
+                        .unwrap_or_default()
+                })
+                .collect();
+            (pkg_id, features)
+        })
+        .collect();
+    let is_activated = |pkg_id: PackageId, feature: InternedString| {
+        activated
+            .get(&pkg_id)
+            .is_some_and(|features| features.contains(&feature))
+    };
+    // Dependencies of `pkg_id` that are part of the graph, by the name
+    // `pkg_id` uses for them in its manifest.
+    let deps_named = |pkg_id: PackageId, dep_name: InternedString| {
+        resolve
+            .deps(pkg_id)
+            .filter(move |(dep_id, deps)| {
+                node_map.contains_key(dep_id)
+                    && deps.iter().any(|dep| dep.name_in_toml() == dep_name)
+            })
+            .map(|(dep_id, _)| dep_id)
+    };
+
+    let mut edges: BTreeSet<(Option<FeatureGraphNode>, FeatureGraphNode)> = BTreeSet::new();
+    let add_feature_value =
+        |edges: &mut BTreeSet<_>, from: Option<FeatureGraphNode>, pkg_id, fv: &FeatureValue| {
+            match fv {
+                FeatureValue::Feature(name) => {
+                    if is_activated(pkg_id, *name) {
+                        edges.insert((from, (pkg_id, Some(*name))));
+                    }
+                }
+                FeatureValue::Dep { dep_name } => {
+                    for dep_id in deps_named(pkg_id, *dep_name) {
+                        edges.insert((from, (dep_id, None)));
+                    }
+                }
+                FeatureValue::DepFeature {
+                    dep_name,
+                    dep_feature,
+                    weak: _,
+                } => {
+                    for dep_id in deps_named(pkg_id, *dep_name) {
+                        if is_activated(dep_id, *dep_feature) {
+                            edges.insert((from, (dep_id, Some(*dep_feature))));
+                        }
+                    }
+                }
+            }
+        };
+
+    for member in ws.members() {
+        let pkg_id = member.package_id();
+        if !node_map.contains_key(&pkg_id) {
+            continue;
+        }
+        let feature_map = member.summary().features();
+        if cli_features.all_features {
+            for name in feature_map.keys() {
+                add_feature_value(&mut edges, None, pkg_id, &FeatureValue::Feature(*name));
+            }
+        }
+        if cli_features.uses_default_features && feature_map.contains_key("default") {
+            let default = FeatureValue::Feature(InternedString::new("default"));
+            add_feature_value(&mut edges, None, pkg_id, &default);
+        }
+        for fv in cli_features.features.iter() {
+            add_feature_value(&mut edges, None, pkg_id, fv);
+        }
+    }
+
+    for &pkg_id in node_map.keys() {
+        let feature_map = package_map[&pkg_id].summary().features();
+        for feature in &activated[&pkg_id] {
+            let Some(fvs) = feature_map.get(feature) else {
+                continue;
+            };
+            for fv in fvs {
+                add_feature_value(&mut edges, Some((pkg_id, Some(*feature))), pkg_id, fv);
+            }
+        }
+        for (dep_id, deps) in resolve.deps(pkg_id) {
+            if !node_map.contains_key(&dep_id) {
+                continue;
+            }
+            let dep_feature_map = package_map[&dep_id].summary().features();
+            for dep in deps {
+                let mut requested = dep.features().to_vec();
+                if dep.uses_default_features() && dep_feature_map.contains_key("default") {
+                    requested.push(InternedString::new("default"));
+                }
+                for feature in requested {
+                    if is_activated(dep_id, feature) {
+                        edges.insert((Some((pkg_id, None)), (dep_id, Some(feature))));
+                    }
+                }
+            }
+        }
+    }
+
+    let node = |(pkg_id, feature): FeatureGraphNode| FeatureNode {
+        pkg: package_map.get_key_value(&pkg_id).unwrap().0.to_spec(),
+        feature: feature.map(|f| f.to_string()),
+    };
+    FeatureGraph {
+        edges: edges
+            .into_iter()
+            .map(|(from, to)| FeatureEdge {
+                from: from.map(node),
+                to: node(to),
+            })
+            .collect(),
+    }
+}
@@ -10,7 +10,7 @@ use crate::core::compiler::{BuildConfig, CompileMode, DefaultExecutor, Executor}
 use crate::core::manifest::Target;
 use crate::core::resolver::CliFeatures;
 use crate::core::resolver::HasDevUnits;
-use crate::core::{Feature, PackageIdSpecQuery, Shell, Verbosity, Workspace};
+use crate::core::{Feature, PackageIdSpecQuery, Shell, SourcePolicyScope, Verbosity, Workspace};
 use crate::core::{Package, PackageId, PackageSet, Resolve, SourceId};
 use crate::sources::PathSource;
 use crate::util::cache_lock::CacheLockMode;
@@ -229,6 +229,7 @@ fn prepare_archive(
     if opts.check_metadata {
         check_metadata(pkg, gctx)?;
     }
+    ws.check_source_policy(SourcePolicyScope::Package(pkg))?;
 
     if !pkg.manifest().exclude().is_empty() && !pkg.manifest().include().is_empty() {
         gctx.shell().warn(
@@ -878,6 +879,30 @@ pub fn check_yanked(
     resolve: &Resolve,
     hint: &str,
 ) -> CargoResult<()> {
+    // Make sure the yanked status is up-to-date, not what was cached when the
+    // lock file was last resolved.
+    let invalidate_cache = true;
+    for pkg_id in yanked_packages(gctx, pkg_set, resolve, invalidate_cache)? {
+        gctx.shell().warn(format!(
+            "package `{}` in Cargo.lock is yanked in registry `{}`, {}",
+            pkg_id,
+            pkg_id.source_id().display_registry_name(),
+            hint
+        ))?;
+    }
+    Ok(())
+}
+
+/// Returns the packages in `resolve` that are yanked in their registry.
+///
+/// With `invalidate_cache`, sources re-fetch their index instead of answering
+/// from what was loaded during resolution.
+pub fn yanked_packages(
+    gctx: &GlobalContext,
+    pkg_set: &PackageSet<'_>,
+    resolve: &Resolve,
+    invalidate_cache: bool,
+) -> CargoResult<Vec<PackageId>> {
     // Checking the yanked status involves taking a look at the registry and
     // maybe updating files, so be sure to lock it here.
     let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
@@ -885,8 +910,10 @@ pub fn check_yanked(
     let mut sources = pkg_set.sources_mut();
     let mut pending: Vec<PackageId> = resolve.iter().collect();
     let mut results = Vec::new();
-    for (_id, source) in sources.sources_mut() {
-        source.invalidate_cache();
+    if invalidate_cache {
+        for (_id, source) in sources.sources_mut() {
+            source.invalidate_cache();
+        }
     }
     while !pending.is_empty() {
         pending.retain(|pkg_id| {
@@ -903,17 +930,14 @@ pub fn check_yanked(
         }
     }
 
+    let mut yanked = Vec::new();
     for (pkg_id, is_yanked) in results {
         if is_yanked? {
-            gctx.shell().warn(format!(
-                "package `{}` in Cargo.lock is yanked in registry `{}`, {}",
-                pkg_id,
-                pkg_id.source_id().display_registry_name(),
-                hint
-            ))?;
+            yanked.push(pkg_id);
         }
     }
-    Ok(())
+    yanked.sort();
+    Ok(yanked)
 }
 
 fn run_verify(
@@ -973,6 +997,7 @@ fn run_verify(
                 CompileMode::Build,
             )?,
             cli_features: opts.cli_features.clone(),
+            all_required_features: false,
             spec: ops::Packages::Packages(Vec::new()),
             filter: ops::CompileFilter::Default {
                 required_features_filterable: true,
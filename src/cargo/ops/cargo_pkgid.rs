@@ -1,11 +1,15 @@
-use crate::core::{PackageIdSpec, PackageIdSpecQuery, Workspace};
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::core::{PackageId, PackageIdSpec, PackageIdSpecQuery, Resolve, Workspace};
+use crate::drop_println;
 use crate::ops;
 use crate::util::CargoResult;
+use cargo_util::paths;
 
 pub fn pkgid(ws: &Workspace<'_>, spec: Option<&str>) -> CargoResult<PackageIdSpec> {
-    let Some(resolve) = ops::load_pkg_lockfile(ws)? else {
-        anyhow::bail!("a Cargo.lock must exist for this command")
-    };
+    let resolve = load_resolve(ws)?;
 
     let pkgid = match spec {
         Some(spec) => PackageIdSpec::query_str(spec, resolve.iter())?,
@@ -13,3 +17,205 @@ pub fn pkgid(ws: &Workspace<'_>, spec: Option<&str>) -> CargoResult<PackageIdSpe
     };
     Ok(pkgid.to_spec())
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PkgidFormat {
+    Text,
+    Json,
+}
+
+pub struct PkgidOptions {
+    /// Specs to look up, in addition to `paths`.
+    pub specs: Vec<String>,
+    /// Look up every package of the lock file instead.
+    pub all: bool,
+    /// Paths to find the owning package of.
+    pub paths: Vec<PathBuf>,
+    pub format: PkgidFormat,
+}
+
+/// A package ID as `cargo pkgid --format json` outputs it.
+#[derive(Serialize)]
+struct SerializedPkgid<'a> {
+    id: PackageIdSpec,
+    name: &'a str,
+    version: String,
+    source: Option<String>,
+    /// The path the package was looked up from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'a Path>,
+}
+
+/// Prints the package IDs of several specs and paths, one per line.
+pub fn pkgids(ws: &Workspace<'_>, opts: &PkgidOptions) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let resolve = load_resolve(ws)?;
+
+    let mut found: Vec<(PackageId, Option<&Path>)> = Vec::new();
+    if opts.all {
+        let mut ids: Vec<_> = resolve.iter().collect();
+        ids.sort();
+        found.extend(ids.into_iter().map(|id| (id, None)));
+    }
+    for spec in &opts.specs {
+        found.push((PackageIdSpec::query_str(spec, resolve.iter())?, None));
+    }
+    for path in &opts.paths {
+        found.push((pkgid_of_path(ws, &resolve, path)?, Some(path)));
+    }
+
+    for (id, path) in found {
+        match opts.format {
+            PkgidFormat::Text => drop_println!(gctx, "{}", id.to_spec()),
+            PkgidFormat::Json => gctx.shell().print_json(&SerializedPkgid {
+                id: id.to_spec(),
+                name: &id.name(),
+                version: id.version().to_string(),
+                source: id.source_id().serialized(),
+                path,
+            })?,
+        }
+    }
+    Ok(())
+}
+
+/// Finds the package of the lock file that `path` belongs to.
+///
+/// This understands paths inside:
+///
+/// * the `build`, `.fingerprint` and `deps` directories of the target
+///   directory, whose entries are named after packages or their crates;
+/// * the registry cache, with a directory or `.crate` file for every package;
+/// * path packages, like workspace members.
+fn pkgid_of_path(ws: &Workspace<'_>, resolve: &Resolve, path: &Path) -> CargoResult<PackageId> {
+    let gctx = ws.gctx();
+    let path = paths::normalize_path(&gctx.cwd().join(path));
+
+    let target_dir = ws.target_dir().into_path_unlocked();
+    if let Ok(rel) = path.strip_prefix(&target_dir) {
+        return pkgid_in_target_dir(resolve, &path, rel);
+    }
+
+    let registry_dirs = [
+        gctx.registry_source_path().into_path_unlocked(),
+        gctx.registry_cache_path().into_path_unlocked(),
+    ];
+    for dir in registry_dirs {
+        if let Ok(rel) = path.strip_prefix(&dir) {
+            return pkgid_in_registry(resolve, &path, rel);
+        }
+    }
+
+    // The deepest path package containing `path`, in case they're nested.
+    resolve
+        .iter()
+        .filter_map(|id| {
+            let root = id.source_id().local_path()?;
+            path.starts_with(&root)
+                .then(|| (root.components().count(), id))
+        })
+        .max()
+        .map(|(_, id)| id)
+        .ok_or_else(|| {
+            anyhow::format_err!(
+                "`{}` isn't inside a package of the lock file, the target directory \
+                 or the registry cache",
+                path.display()
+            )
+        })
+}
+
+/// Finds the package owning `rel`, a path relative to the target directory,
+/// from the name of its entry in a `build`, `.fingerprint` or `deps`
+/// directory.
+fn pkgid_in_target_dir(resolve: &Resolve, path: &Path, rel: &Path) -> CargoResult<PackageId> {
+    let components: Vec<_> = rel
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(c) => c.to_str(),
+            _ => None,
+        })
+        .collect();
+    let entry = components
+        .windows(2)
+        .find_map(|w| matches!(w[0], "build" | ".fingerprint" | "deps").then_some(w[1]));
+    // Entries are `<name>-<hash>`, with an extension and a `lib` prefix for
+    // some of the files in `deps`.
+    let name = entry
+        .map(|entry| entry.split('.').next().unwrap())
+        .and_then(|entry| entry.rsplit_once('-'))
+        .map(|(name, _hash)| name);
+    let Some(name) = name else {
+        anyhow::bail!(
+            "`{}` isn't in a `build`, `.fingerprint` or `deps` directory of the target \
+             directory, so its package is unknown",
+            path.display()
+        );
+    };
+
+    let crate_name = |name: &str| name.replace('-', "_");
+    let candidates: Vec<_> = resolve
+        .iter()
+        .filter(|id| {
+            let pkg_name = crate_name(&id.name());
+            crate_name(name) == pkg_name || name.strip_prefix("lib") == Some(pkg_name.as_str())
+        })
+        .collect();
+    unique(path, candidates)
+}
+
+/// Finds the package owning `rel`, a path relative to the `src` or `cache`
+/// directory of the registry cache, named `<index>/<name>-<version>`.
+fn pkgid_in_registry(resolve: &Resolve, path: &Path, rel: &Path) -> CargoResult<PackageId> {
+    let mut components = rel.components().filter_map(|c| match c {
+        Component::Normal(c) => c.to_str(),
+        _ => None,
+    });
+    // The index directory is named after the source the packages were
+    // downloaded from, which can be a replacement of the one in the lock
+    // file, so only the package directory is looked at.
+    let (Some(_index), Some(entry)) = (components.next(), components.next()) else {
+        anyhow::bail!(
+            "`{}` isn't inside a package of the registry cache",
+            path.display()
+        );
+    };
+    let entry = entry.strip_suffix(".crate").unwrap_or(entry);
+
+    let candidates: Vec<_> = resolve
+        .iter()
+        .filter(|id| {
+            id.source_id().is_registry() && format!("{}-{}", id.name(), id.version()) == entry
+        })
+        .collect();
+    unique(path, candidates)
+}
+
+fn unique(path: &Path, mut candidates: Vec<PackageId>) -> CargoResult<PackageId> {
+    match candidates.len() {
+        0 => anyhow::bail!(
+            "`{}` doesn't belong to a package of the lock file",
+            path.display()
+        ),
+        1 => Ok(candidates[0]),
+        _ => {
+            candidates.sort();
+            let candidates: Vec<_> = candidates
+                .iter()
+                .map(|id| format!("  {}", id.to_spec()))
+                .collect();
+            anyhow::bail!(
+                "`{}` could belong to any of these packages:\n{}",
+                path.display(),
+                candidates.join("\n")
+            )
+        }
+    }
+}
+
+fn load_resolve(ws: &Workspace<'_>) -> CargoResult<Resolve> {
+    let Some(resolve) = ops::load_pkg_lockfile(ws)? else {
+        anyhow::bail!("a Cargo.lock must exist for this command")
+    };
+    Ok(resolve)
+}
@@ -1,6 +1,13 @@
 //! Core of cargo-remove command
 
+use crate::core::compiler::CompileMode;
+use crate::core::dependency::DepKind;
 use crate::core::Package;
+use crate::core::Workspace;
+use crate::ops;
+use crate::ops::CompileFilter;
+use crate::ops::CompileOptions;
+use crate::ops::Packages;
 use crate::util::toml_mut::manifest::DepTable;
 use crate::util::toml_mut::manifest::LocalManifest;
 use crate::CargoResult;
@@ -63,3 +70,100 @@ pub fn remove(options: &RemoveOptions<'_>) -> CargoResult<()> {
 
     Ok(())
 }
+
+/// Remove dependencies that no target of a package uses.
+#[derive(Debug)]
+pub struct RemoveUnusedOptions<'a> {
+    /// Configuration information for Cargo operations
+    pub gctx: &'a GlobalContext,
+    /// Packages to remove dependencies from
+    pub packages: Vec<&'a Package>,
+    /// Whether or not to actually write the manifests
+    pub dry_run: bool,
+}
+
+/// Check `options.packages` and remove the dependencies `rustc` reports as
+/// unused by all of their targets.
+///
+/// Only `[dependencies]` and `[dev-dependencies]` are considered. Optional and
+/// platform-specific dependencies are skipped since they might not be part of
+/// this build, as are `[dev-dependencies]` of packages with doctests, which
+/// are not checked.
+pub fn remove_unused(ws: &Workspace<'_>, options: &RemoveUnusedOptions<'_>) -> CargoResult<()> {
+    let mut compile_opts = CompileOptions::new(options.gctx, CompileMode::Check { test: false })?;
+    compile_opts.spec = Packages::Packages(
+        options
+            .packages
+            .iter()
+            .map(|pkg| pkg.name().to_string())
+            .collect(),
+    );
+    compile_opts.filter = CompileFilter::new_all_targets();
+    compile_opts.build_config.unused_dependencies = true;
+    let compilation = ops::compile(ws, &compile_opts)?;
+
+    let mut removed = false;
+    for pkg in &options.packages {
+        let units = compilation
+            .unused_dependencies
+            .iter()
+            .filter(|(unit, _)| unit.pkg.package_id() == pkg.package_id())
+            .collect::<Vec<_>>();
+        let has_doctests = pkg.targets().iter().any(|target| target.doctested());
+
+        let mut manifest = LocalManifest::try_new(pkg.manifest_path())?;
+        let mut modified = false;
+        for dep in pkg.dependencies() {
+            if dep.is_optional() || dep.platform().is_some() {
+                continue;
+            }
+            let section = match dep.kind() {
+                DepKind::Normal => "dependencies",
+                DepKind::Development if !has_doctests => "dev-dependencies",
+                _ => continue,
+            };
+            // Mirrors which units get `[dev-dependencies]` in `unit_dependencies`.
+            let receivers = units
+                .iter()
+                .filter(|(unit, _)| {
+                    dep.is_transitive()
+                        || unit.target.is_test()
+                        || unit.target.is_example()
+                        || unit.mode.is_any_test()
+                })
+                .collect::<Vec<_>>();
+            let unused = !receivers.is_empty()
+                && receivers
+                    .iter()
+                    .all(|(_, unused)| unused.iter().any(|dep_id| dep.matches_id(*dep_id)));
+            if !unused {
+                continue;
+            }
+
+            let key = dep.name_in_toml();
+            options.gctx.shell().status(
+                "Removing",
+                format!("{key} from {section} of `{}`", pkg.name()),
+            )?;
+            manifest.remove_from_table(&[section.to_owned()], &key)?;
+            manifest.gc_dep(&key);
+            modified = true;
+        }
+
+        if modified && !options.dry_run {
+            manifest.write()?;
+        }
+        removed |= modified;
+    }
+
+    if !removed {
+        options.gctx.shell().note("no unused dependencies found")?;
+    } else if options.dry_run {
+        options
+            .gctx
+            .shell()
+            .warn("aborting remove due to dry run")?;
+    }
+
+    Ok(())
+}
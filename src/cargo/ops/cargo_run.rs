@@ -1,16 +1,26 @@
 use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::io;
 use std::iter;
 use std::path::Path;
+use std::sync::Arc;
 
-use crate::core::compiler::UnitOutput;
-use crate::core::{TargetKind, Workspace};
-use crate::ops;
-use crate::util::CargoResult;
+use crate::core::compiler::{DefaultExecutor, Executor, UnitOutput};
+use crate::core::{Package, Target, TargetKind, Workspace};
+use crate::ops::{self, CompileFilter, FilterRule, LibRule, Packages};
+use crate::util::edit_distance::edit_distance;
+use crate::util::{CargoResult, GlobalContext};
 
+/// Runs the single binary or example selected by `options`.
+///
+/// With `interactive`, the user is asked to choose the target to run when
+/// several binaries are available, or when the requested target does not
+/// exist.
 pub fn run(
     ws: &Workspace<'_>,
     options: &ops::CompileOptions,
     args: &[OsString],
+    interactive: bool,
 ) -> CargoResult<()> {
     let gctx = ws.gctx();
 
@@ -18,12 +28,16 @@ pub fn run(
         anyhow::bail!("`cargo run` does not support glob patterns on target selection")
     }
 
+    // Target selection below may fail before compiling, so manifest warnings
+    // are emitted first.
+    ws.emit_warnings()?;
+
     // We compute the `bins` here *just for diagnosis*. The actual set of
-    // packages to be run is determined by the `ops::compile` call below.
+    // packages to be run is determined by the `ops::compile_ws` call below.
     let packages = options.spec.get_packages(ws)?;
-    let bins: Vec<_> = packages
-        .into_iter()
-        .flat_map(|pkg| {
+    let mut bins: Vec<_> = packages
+        .iter()
+        .flat_map(|&pkg| {
             iter::repeat(pkg).zip(pkg.manifest().targets().iter().filter(|target| {
                 !target.is_lib()
                     && !target.is_custom_build()
@@ -35,10 +49,37 @@ pub fn run(
             }))
         })
         .collect();
+    // The target chosen by the user, when it differs from `options.filter`.
+    let mut selected = None;
 
     if bins.is_empty() {
         if !options.filter.is_specific() {
             anyhow::bail!("a bin target must be available for `cargo run`")
+        } else if let Some((name, kind)) = single_named_target(&options.filter) {
+            let candidates: Vec<_> = packages
+                .iter()
+                .flat_map(|&pkg| {
+                    iter::repeat(pkg).zip(
+                        pkg.targets()
+                            .iter()
+                            .filter(|target| kind.is_expected_kind(target)),
+                    )
+                })
+                .collect();
+            let suggestions = closest_targets(name, &candidates);
+            if !interactive || candidates.is_empty() {
+                return Err(no_target_named(name, kind, &candidates, &suggestions));
+            }
+            gctx.shell()
+                .warn(format!("no {} target named `{name}`", kind.desc()))?;
+            let choices = if suggestions.is_empty() {
+                candidates
+            } else {
+                suggestions
+            };
+            let choice = choose_target(gctx, kind, &choices)?;
+            selected = Some(choice);
+            bins = vec![choice];
         } else {
             // This will be verified in `cargo_compile`.
         }
@@ -55,7 +96,12 @@ pub fn run(
     }
 
     if bins.len() > 1 {
-        if !options.filter.is_specific() {
+        if !options.filter.is_specific() && interactive {
+            bins.sort_by_key(|(_pkg, target)| target.name());
+            let choice = choose_target(gctx, RunKind::Bin, &bins)?;
+            selected = Some(choice);
+            bins = vec![choice];
+        } else if !options.filter.is_specific() {
             let mut names: Vec<&str> = bins
                 .into_iter()
                 .map(|(_pkg, target)| target.name())
@@ -76,10 +122,20 @@ pub fn run(
         }
     }
 
+    let selected_options;
+    let options = match selected {
+        Some((pkg, target)) => {
+            selected_options = select_target(options, pkg, target);
+            &selected_options
+        }
+        None => options,
+    };
+
     // `cargo run` is only compatible with one `--target` flag at most
     options.build_config.single_requested_kind()?;
 
-    let compile = ops::compile(ws, options)?;
+    let exec: Arc<dyn Executor> = Arc::new(DefaultExecutor);
+    let compile = ops::compile_ws(ws, options, &exec)?;
     assert_eq!(compile.binaries.len(), 1);
     let UnitOutput {
         unit,
@@ -106,5 +162,184 @@ pub fn run(
 
     gctx.shell().status("Running", process.to_string())?;
 
-    process.exec_replace()
+    if options.build_config.watched_files.is_some() {
+        // Cargo keeps watching for changes after the program exits.
+        process.exec()
+    } else {
+        process.exec_replace()
+    }
+}
+
+/// Kind of a target that `cargo run` can run.
+#[derive(Clone, Copy)]
+enum RunKind {
+    Bin,
+    Example,
+}
+
+impl RunKind {
+    fn desc(self) -> &'static str {
+        match self {
+            RunKind::Bin => "bin",
+            RunKind::Example => "example",
+        }
+    }
+
+    fn is_expected_kind(self, target: &Target) -> bool {
+        match self {
+            RunKind::Bin => target.is_bin(),
+            RunKind::Example => target.is_example(),
+        }
+    }
+}
+
+/// Returns the name given to `--bin` or `--example`, if only one target was
+/// requested by name.
+fn single_named_target(filter: &CompileFilter) -> Option<(&str, RunKind)> {
+    let CompileFilter::Only { bins, examples, .. } = filter else {
+        return None;
+    };
+    match (bins, examples) {
+        (FilterRule::Just(bins), FilterRule::Just(examples)) => match (&bins[..], &examples[..]) {
+            ([name], []) => Some((name, RunKind::Bin)),
+            ([], [name]) => Some((name, RunKind::Example)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns up to three of `candidates` whose names are close to `name`,
+/// closest first.
+///
+/// A candidate is close when it is a few edits away from `name`, or when it
+/// contains `name`, like `my-server` for `server`. Names shorter than three
+/// characters are not looked up within candidates, as they would match too
+/// many of them.
+fn closest_targets<'a>(
+    name: &str,
+    candidates: &[(&'a Package, &'a Target)],
+) -> Vec<(&'a Package, &'a Target)> {
+    let lowercase = name.to_lowercase();
+    let mut ranked: Vec<_> = candidates
+        .iter()
+        .filter_map(|&(pkg, target)| {
+            let candidate = target.name().to_lowercase();
+            let distance = edit_distance(name, target.name(), 3).or_else(|| {
+                (lowercase.chars().count() >= 3 && candidate.contains(&lowercase))
+                    .then_some(usize::MAX)
+            })?;
+            Some((distance, (pkg, target)))
+        })
+        .collect();
+    ranked.sort_by_key(|&(distance, (_pkg, target))| (distance, target.name()));
+    ranked.dedup_by_key(|(_distance, (_pkg, target))| target.name());
+    ranked
+        .into_iter()
+        .take(3)
+        .map(|(_distance, candidate)| candidate)
+        .collect()
+}
+
+fn no_target_named(
+    name: &str,
+    kind: RunKind,
+    candidates: &[(&Package, &Target)],
+    suggestions: &[(&Package, &Target)],
+) -> anyhow::Error {
+    let desc = kind.desc();
+    let mut msg = format!("no {desc} target named `{name}`");
+    match suggestions {
+        [] => {
+            msg.push('.');
+            if !candidates.is_empty() {
+                msg.push_str(&format!("\nAvailable {desc} targets:\n"));
+                for (_pkg, target) in candidates {
+                    let _ = writeln!(msg, "    {}", target.name());
+                }
+            }
+        }
+        [(_pkg, target)] => {
+            let _ = write!(msg, "\n\n\tDid you mean `{}`?", target.name());
+        }
+        _ => {
+            let names: Vec<_> = suggestions
+                .iter()
+                .map(|(_pkg, target)| format!("`{}`", target.name()))
+                .collect();
+            let _ = write!(msg, "\n\n\tDid you mean one of {}?", names.join(", "));
+        }
+    }
+    anyhow::format_err!(msg)
+}
+
+/// Asks the user to choose one of `choices`, by number or by name.
+fn choose_target<'a>(
+    gctx: &GlobalContext,
+    kind: RunKind,
+    choices: &[(&'a Package, &'a Target)],
+) -> CargoResult<(&'a Package, &'a Target)> {
+    let several_packages = choices
+        .iter()
+        .any(|(pkg, _target)| pkg.package_id() != choices[0].0.package_id());
+    {
+        let mut shell = gctx.shell();
+        let err = shell.err();
+        writeln!(err, "Which {} target do you want to run?", kind.desc())?;
+        for (i, (pkg, target)) in choices.iter().enumerate() {
+            if several_packages {
+                writeln!(err, "  {}) {} ({})", i + 1, target.name(), pkg.name())?;
+            } else {
+                writeln!(err, "  {}) {}", i + 1, target.name())?;
+            }
+        }
+        write!(err, "> ")?;
+        err.flush()?;
+    }
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    let by_number = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|i| choices.get(i.checked_sub(1)?));
+    let mut by_name = choices
+        .iter()
+        .filter(|(_pkg, target)| target.name() == answer);
+    let choice = by_number.or_else(|| match (by_name.next(), by_name.next()) {
+        (Some(choice), None) => Some(choice),
+        _ => None,
+    });
+    choice.copied().ok_or_else(|| {
+        anyhow::format_err!(
+            "`{answer}` is not one of the listed {} targets",
+            kind.desc()
+        )
+    })
+}
+
+/// Returns `options` changed to run only `target` of `pkg`.
+fn select_target(
+    options: &ops::CompileOptions,
+    pkg: &Package,
+    target: &Target,
+) -> ops::CompileOptions {
+    let name = vec![target.name().to_string()];
+    let (bins, examples) = if target.is_bin() {
+        (FilterRule::Just(name), FilterRule::none())
+    } else {
+        (FilterRule::none(), FilterRule::Just(name))
+    };
+    let mut options = options.clone();
+    options.spec = Packages::Packages(vec![pkg.name().to_string()]);
+    options.filter = CompileFilter::Only {
+        all_targets: false,
+        lib: LibRule::False,
+        bins,
+        examples,
+        tests: FilterRule::none(),
+        benches: FilterRule::none(),
+    };
+    options
 }
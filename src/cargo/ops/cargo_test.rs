@@ -1,20 +1,82 @@
+use crate::core::compiler::build_plan;
+use crate::core::compiler::ARTIFACT_PLACEHOLDER;
 use crate::core::compiler::{Compilation, CompileKind, Doctest, Metadata, Unit, UnitOutput};
 use crate::core::profiles::PanicStrategy;
 use crate::core::shell::Verbosity;
 use crate::core::{TargetKind, Workspace};
 use crate::ops;
 use crate::util::errors::CargoResult;
+use crate::util::machine_message::{self, Message};
 use crate::util::{add_path_args, CliError, CliResult, GlobalContext};
-use anyhow::format_err;
-use cargo_util::{ProcessBuilder, ProcessError};
-use std::ffi::OsString;
+use anyhow::{format_err, Context as _};
+use cargo_util::{paths, ProcessBuilder, ProcessError};
+use cargo_util_schemas::test_event::TestEvent;
+use cargo_util_schemas::test_manifest::{TestExecutable, TestManifest, TEST_MANIFEST_VERSION};
+use serde::{Deserialize, Serialize};
+use std::ffi::{OsStr, OsString};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 pub struct TestOptions {
     pub compile_opts: ops::CompileOptions,
     pub no_run: bool,
     pub no_fail_fast: bool,
+    /// Whether to have the test harness report its results as JSON, and
+    /// forward them as `test-event` messages (`--no-capture-json`).
+    pub json_events: bool,
+    /// Where to write the manifest of the test executables
+    /// (`--test-manifest`).
+    pub test_manifest: Option<PathBuf>,
+    /// Only run the share of the tests of one CI shard (`--partition`).
+    pub partition: Option<Partition>,
+    /// Name to save the results of the benchmarks under
+    /// (`--save-baseline`).
+    pub save_baseline: Option<String>,
+    /// Name of the saved results to compare the benchmarks to (`--baseline`).
+    pub baseline: Option<String>,
+}
+
+/// A share of the test executables to run, from `--partition M/N`.
+///
+/// The executables, followed by the doctests of each library, are assigned
+/// round-robin to the `N` shards, in the order `cargo test` runs them. Every
+/// shard builds the same things, so the partition only decides what runs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Partition {
+    /// Which shard this is, starting at 1.
+    shard: usize,
+    /// The number of shards.
+    total: usize,
+}
+
+impl Partition {
+    /// Whether the executable at `index` in the run order belongs to this
+    /// shard.
+    fn includes(&self, index: usize) -> bool {
+        index % self.total == self.shard - 1
+    }
+}
+
+impl FromStr for Partition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (shard, total) = s.split_once('/')?;
+            let shard = shard.parse().ok()?;
+            let total = total.parse().ok()?;
+            (1..=total)
+                .contains(&shard)
+                .then_some(Partition { shard, total })
+        };
+        parse().ok_or_else(|| {
+            format_err!(
+                "invalid value `{s}` for `--partition`, \
+                 expected `M/N` where M is between 1 and N"
+            )
+        })
+    }
 }
 
 /// The kind of test.
@@ -51,7 +113,9 @@ impl UnitTestError {
                 TargetKind::Test => add("test"),
                 TargetKind::Bench => add("bench"),
                 TargetKind::ExampleLib(_) | TargetKind::ExampleBin => add("example"),
-                TargetKind::CustomBuild => panic!("unexpected CustomBuild kind"),
+                TargetKind::CustomBuild | TargetKind::PostBuild => {
+                    panic!("unexpected build script kind")
+                }
             },
             TestKind::Doctest => args.push_str("--doc"),
         }
@@ -66,13 +130,17 @@ impl UnitTestError {
 pub fn run_tests(ws: &Workspace<'_>, options: &TestOptions, test_args: &[&str]) -> CliResult {
     let compilation = compile_tests(ws, options)?;
 
+    if let Some(path) = &options.test_manifest {
+        write_test_manifest(ws, path, test_args, &compilation)?;
+    }
+
     if options.no_run {
         if !options.compile_opts.build_config.emit_json() {
             display_no_run_information(ws, test_args, &compilation, "unittests")?;
         }
         return Ok(());
     }
-    let mut errors = run_unit_tests(ws, options, test_args, &compilation, TestKind::Test)?;
+    let mut errors = run_unit_tests(ws, options, test_args, &compilation, TestKind::Test, None)?;
 
     let doctest_errors = run_doc_tests(ws, options, test_args, &compilation)?;
     errors.extend(doctest_errors);
@@ -84,6 +152,16 @@ pub fn run_tests(ws: &Workspace<'_>, options: &TestOptions, test_args: &[&str])
 /// On error, the returned [`CliError`] will have the appropriate process exit
 /// code that Cargo should use.
 pub fn run_benches(ws: &Workspace<'_>, options: &TestOptions, args: &[&str]) -> CliResult {
+    let baseline = options
+        .baseline
+        .as_deref()
+        .map(|name| load_baseline(ws, name))
+        .transpose()?;
+    if let Some(name) = &options.save_baseline {
+        // Reject an invalid name before building anything.
+        baseline_path(ws, name)?;
+    }
+
     let compilation = compile_tests(ws, options)?;
 
     if options.no_run {
@@ -96,7 +174,25 @@ pub fn run_benches(ws: &Workspace<'_>, options: &TestOptions, args: &[&str]) ->
     let mut args = args.to_vec();
     args.push("--bench");
 
-    let errors = run_unit_tests(ws, options, &args, &compilation, TestKind::Bench)?;
+    let mut recorder =
+        (options.save_baseline.is_some() || baseline.is_some()).then(|| BenchRecorder {
+            baseline,
+            results: Vec::new(),
+        });
+    let errors = run_unit_tests(
+        ws,
+        options,
+        &args,
+        &compilation,
+        TestKind::Bench,
+        recorder.as_mut(),
+    )?;
+    if let (Some(name), Some(recorder)) = (&options.save_baseline, recorder) {
+        // Don't replace a baseline with the results of a partial run.
+        if errors.is_empty() {
+            save_baseline(ws, name, recorder.results)?;
+        }
+    }
     no_fail_fast_err(ws, &options.compile_opts, &errors)
 }
 
@@ -116,17 +212,24 @@ fn run_unit_tests(
     test_args: &[&str],
     compilation: &Compilation<'_>,
     test_kind: TestKind,
+    mut bench_recorder: Option<&mut BenchRecorder>,
 ) -> Result<Vec<UnitTestError>, CliError> {
     let gctx = ws.gctx();
     let cwd = gctx.cwd();
     let mut errors = Vec::new();
 
-    for UnitOutput {
-        unit,
-        path,
-        script_meta,
-    } in compilation.tests.iter()
+    for (
+        index,
+        UnitOutput {
+            unit,
+            path,
+            script_meta,
+        },
+    ) in compilation.tests.iter().enumerate()
     {
+        if !in_partition(options, index) {
+            continue;
+        }
         let (exe_display, mut cmd) = cmd_builds(
             gctx,
             cwd,
@@ -138,6 +241,11 @@ fn run_unit_tests(
             "unittests",
         )?;
 
+        let json_events = options.json_events && unit.target.harness();
+        if json_events {
+            cmd.arg("-Zunstable-options").arg("--format=json");
+        }
+
         if gctx.extra_verbose() {
             cmd.display_env_vars();
         }
@@ -147,7 +255,14 @@ fn run_unit_tests(
         gctx.shell()
             .verbose(|shell| shell.status("Running", &cmd))?;
 
-        if let Err(e) = cmd.exec() {
+        let result = if json_events {
+            exec_with_test_events(gctx, unit, &cmd)
+        } else if let Some(recorder) = bench_recorder.as_deref_mut() {
+            exec_with_bench_recorder(gctx, unit, &cmd, recorder)
+        } else {
+            cmd.exec()
+        };
+        if let Err(e) = result {
             let code = fail_fast_code(&e);
             let unit_err = UnitTestError {
                 unit: unit.clone(),
@@ -177,7 +292,10 @@ fn run_doc_tests(
     let mut errors = Vec::new();
     let doctest_xcompile = gctx.cli_unstable().doctest_xcompile;
 
-    for doctest_info in &compilation.to_doc_test {
+    for (index, doctest_info) in compilation.to_doc_test.iter().enumerate() {
+        if !in_partition(options, compilation.tests.len() + index) {
+            continue;
+        }
         let Doctest {
             args,
             unstable_opts,
@@ -231,6 +349,17 @@ fn run_doc_tests(
             p.arg("-Zunstable-options");
             p.arg("--enable-per-target-ignores");
             if let Some((runtool, runtool_args)) = compilation.target_runner(unit.kind) {
+                if gctx.cli_unstable().runner_composition
+                    && runtool_args
+                        .iter()
+                        .any(|arg| arg.contains(ARTIFACT_PLACEHOLDER))
+                {
+                    return Err(format_err!(
+                        "the runner of doctests cannot use `{ARTIFACT_PLACEHOLDER}`, \
+                         as rustdoc passes the doctest as its last argument"
+                    )
+                    .into());
+                }
                 p.arg("--runtool").arg(runtool);
                 for arg in runtool_args {
                     p.arg("--runtool-arg").arg(arg);
@@ -268,6 +397,11 @@ fn run_doc_tests(
             p.arg("--test-args").arg("--quiet");
         }
 
+        if options.json_events {
+            p.arg("--test-args").arg("-Zunstable-options");
+            p.arg("--test-args").arg("--format=json");
+        }
+
         p.args(unit.pkg.manifest().lint_rustflags());
 
         p.args(args);
@@ -283,7 +417,12 @@ fn run_doc_tests(
         gctx.shell()
             .verbose(|shell| shell.status("Running", p.to_string()))?;
 
-        if let Err(e) = p.exec() {
+        let result = if options.json_events {
+            exec_with_test_events(gctx, unit, &p)
+        } else {
+            p.exec()
+        };
+        if let Err(e) = result {
             let code = fail_fast_code(&e);
             let unit_err = UnitTestError {
                 unit: unit.clone(),
@@ -299,6 +438,14 @@ fn run_doc_tests(
     Ok(errors)
 }
 
+/// Whether the executable at `index` in the run order should run, given the
+/// `--partition` option.
+fn in_partition(options: &TestOptions, index: usize) -> bool {
+    options
+        .partition
+        .map_or(true, |partition| partition.includes(index))
+}
+
 /// Displays human-readable descriptions of the test executables.
 ///
 /// This is used when `cargo test --no-run` is used.
@@ -335,6 +482,74 @@ fn display_no_run_information(
     return Ok(());
 }
 
+/// Writes the manifest of the test executables of `compilation`, with the
+/// commands to run them, for `--test-manifest`.
+fn write_test_manifest(
+    ws: &Workspace<'_>,
+    manifest_path: &Path,
+    test_args: &[&str],
+    compilation: &Compilation<'_>,
+) -> CargoResult<()> {
+    let gctx = ws.gctx();
+    let to_string = |value: &OsStr, what: &str| {
+        value
+            .to_str()
+            .map(str::to_string)
+            .ok_or_else(|| format_err!("unicode {what} string required"))
+    };
+    let mut executables = Vec::new();
+    for UnitOutput {
+        unit,
+        path,
+        script_meta,
+    } in compilation.tests.iter()
+    {
+        let (_, cmd) = cmd_builds(
+            gctx,
+            gctx.cwd(),
+            unit,
+            path,
+            script_meta,
+            test_args,
+            compilation,
+            "unittests",
+        )?;
+        let id = unit.pkg.package_id();
+        executables.push(TestExecutable {
+            package_id: id.to_spec(),
+            package_name: id.name().to_string(),
+            package_version: id.version().clone(),
+            manifest_path: unit.pkg.manifest_path().to_path_buf(),
+            target_name: unit.target.name().to_string(),
+            target_kind: build_plan::target_kind(unit.target.kind()),
+            target_src_path: unit.target.src_path().path().map(Path::to_path_buf),
+            kind: match unit.kind {
+                CompileKind::Host => None,
+                CompileKind::Target(target) => Some(target.rustc_target().to_string()),
+            },
+            path: path.clone(),
+            harness: unit.target.harness(),
+            program: to_string(cmd.get_program(), "program")?,
+            args: cmd
+                .get_args()
+                .map(|arg| to_string(arg, "argument"))
+                .collect::<CargoResult<_>>()?,
+            env: cmd
+                .get_envs()
+                .iter()
+                .filter_map(|(var, value)| Some((var, value.as_ref()?)))
+                .map(|(var, value)| Ok((var.clone(), to_string(value, "environment value")?)))
+                .collect::<CargoResult<_>>()?,
+            cwd: cmd.get_cwd().map(Path::to_path_buf),
+        });
+    }
+    let manifest = TestManifest {
+        version: TEST_MANIFEST_VERSION,
+        executables,
+    };
+    paths::write(manifest_path, serde_json::to_string_pretty(&manifest)?)
+}
+
 /// Creates a [`ProcessBuilder`] for executing a single test.
 ///
 /// Returns a tuple `(exe_display, process)` where `exe_display` is a string
@@ -379,6 +594,177 @@ fn cmd_builds(
     Ok((exe_display, cmd))
 }
 
+/// Runs a test harness that was asked for JSON output, reporting each event
+/// it writes to stdout as a `test-event` message.
+///
+/// Anything else the tests write is forwarded to stderr, so that stdout
+/// stays machine readable.
+fn exec_with_test_events(
+    gctx: &GlobalContext,
+    unit: &Unit,
+    cmd: &ProcessBuilder,
+) -> CargoResult<()> {
+    let package_id = unit.pkg.package_id().to_spec();
+    let manifest_path = unit.pkg.manifest_path();
+    cmd.exec_with_streaming(
+        &mut |line| {
+            let Ok(event) = serde_json::from_str::<TestEvent>(line) else {
+                writeln!(gctx.shell().err(), "{line}")?;
+                return Ok(());
+            };
+            let msg = machine_message::TestEvent {
+                package_id: package_id.clone(),
+                manifest_path,
+                target: &unit.target,
+                event,
+            }
+            .to_json_string();
+            writeln!(gctx.shell().out(), "{msg}")?;
+            Ok(())
+        },
+        &mut |line| {
+            writeln!(gctx.shell().err(), "{line}")?;
+            Ok(())
+        },
+        false,
+    )?;
+    Ok(())
+}
+
+/// Saved results of benchmarks, from `cargo bench --save-baseline`.
+#[derive(Default, Serialize, Deserialize)]
+struct Baseline {
+    results: Vec<BenchResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BenchResult {
+    package: String,
+    target: String,
+    name: String,
+    /// Median time of an iteration, in nanoseconds.
+    median: f64,
+    /// Deviation of the time of an iteration, in nanoseconds.
+    deviation: f64,
+}
+
+/// Collects the results of benchmarks as they run, comparing them to a saved
+/// baseline if any.
+struct BenchRecorder {
+    baseline: Option<Baseline>,
+    results: Vec<BenchResult>,
+}
+
+impl BenchRecorder {
+    /// Records the result in a line of output of a benchmark of `unit`, if
+    /// there is one.
+    fn record(&mut self, gctx: &GlobalContext, unit: &Unit, line: &str) -> CargoResult<()> {
+        let Some((name, median, deviation)) = parse_bench_result(line) else {
+            return Ok(());
+        };
+        let result = BenchResult {
+            package: unit.pkg.name().to_string(),
+            target: unit.target.name().to_string(),
+            name,
+            median,
+            deviation,
+        };
+        if let Some(baseline) = &self.baseline {
+            let saved = baseline.results.iter().find(|saved| {
+                saved.package == result.package
+                    && saved.target == result.target
+                    && saved.name == result.name
+            });
+            let msg = match saved {
+                Some(saved) if saved.median > 0.0 => format!(
+                    "{}: {:.2} ns/iter -> {:.2} ns/iter ({:+.2}%)",
+                    result.name,
+                    saved.median,
+                    result.median,
+                    (result.median - saved.median) / saved.median * 100.0
+                ),
+                Some(saved) => format!(
+                    "{}: {:.2} ns/iter -> {:.2} ns/iter",
+                    result.name, saved.median, result.median
+                ),
+                None => format!("{}: not in the baseline", result.name),
+            };
+            gctx.shell().status("Baseline", msg)?;
+        }
+        self.results.push(result);
+        Ok(())
+    }
+}
+
+/// Parses the result of a benchmark in the output format of libtest, which
+/// `criterion` also uses with `--output-format bencher`, like:
+///
+/// ```text
+/// test bench_add ... bench:       1,234 ns/iter (+/- 56)
+/// ```
+fn parse_bench_result(line: &str) -> Option<(String, f64, f64)> {
+    let (name, rest) = line.strip_prefix("test ")?.split_once(" ... bench:")?;
+    let (median, rest) = rest.split_once(" ns/iter (+/- ")?;
+    let (deviation, _) = rest.split_once(')')?;
+    let number = |s: &str| s.trim().replace(',', "").parse::<f64>().ok();
+    Some((name.to_string(), number(median)?, number(deviation)?))
+}
+
+/// Runs a benchmark, recording its results while forwarding its output.
+fn exec_with_bench_recorder(
+    gctx: &GlobalContext,
+    unit: &Unit,
+    cmd: &ProcessBuilder,
+    recorder: &mut BenchRecorder,
+) -> CargoResult<()> {
+    cmd.exec_with_streaming(
+        &mut |line| {
+            writeln!(gctx.shell().out(), "{line}")?;
+            recorder.record(gctx, unit, line)
+        },
+        &mut |line| {
+            writeln!(gctx.shell().err(), "{line}")?;
+            Ok(())
+        },
+        false,
+    )?;
+    Ok(())
+}
+
+/// Returns the path of the file the baseline `name` is saved to.
+fn baseline_path(ws: &Workspace<'_>, name: &str) -> CargoResult<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        anyhow::bail!("invalid baseline name `{name}`, it must be usable as a file name");
+    }
+    Ok(ws
+        .target_dir()
+        .join("benches")
+        .join("baselines")
+        .into_path_unlocked()
+        .join(format!("{name}.json")))
+}
+
+fn load_baseline(ws: &Workspace<'_>, name: &str) -> CargoResult<Baseline> {
+    let path = baseline_path(ws, name)?;
+    if !path.exists() {
+        anyhow::bail!(
+            "no baseline named `{name}` was found, save one with `--save-baseline {name}`"
+        );
+    }
+    let contents = paths::read(&path)?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse baseline at `{}`", path.display()))
+}
+
+fn save_baseline(ws: &Workspace<'_>, name: &str, results: Vec<BenchResult>) -> CargoResult<()> {
+    let path = baseline_path(ws, name)?;
+    paths::create_dir_all(path.parent().unwrap())?;
+    paths::write(&path, serde_json::to_string_pretty(&Baseline { results })?)?;
+    ws.gctx()
+        .shell()
+        .status("Saved", format!("baseline `{name}` to {}", path.display()))
+}
+
 /// Returns the error code to use when *not* using `--no-fail-fast`.
 ///
 /// Cargo will return the error code from the test process itself. If some
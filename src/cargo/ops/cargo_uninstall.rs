@@ -5,9 +5,12 @@ use crate::sources::PathSource;
 use crate::util::errors::CargoResult;
 use crate::util::Filesystem;
 use crate::util::GlobalContext;
-use anyhow::bail;
+use anyhow::{bail, Context as _};
+use cargo_util::paths;
 use std::collections::BTreeSet;
 use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub fn uninstall(
     root: Option<&str>,
@@ -68,6 +71,91 @@ pub fn uninstall(
     Ok(())
 }
 
+/// Removes what `cargo uninstall --unused-deps` finds unused in `root`: the
+/// tracking entries of binaries that are no longer installed, and the install
+/// cache directories of packages that are no longer installed.
+pub fn uninstall_unused(root: Option<&str>, gctx: &GlobalContext) -> CargoResult<()> {
+    let root = resolve_root(root, gctx)?;
+    let mut tracker = InstallTracker::load(gctx, &root)?;
+    let dst = root.join("bin").into_path_unlocked();
+    let mut removed = 0;
+
+    let missing = tracker
+        .all_installed_bins()
+        .filter_map(|(pkg_id, bins)| {
+            let missing = bins
+                .iter()
+                .filter(|bin| !dst.join(bin).exists())
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            (!missing.is_empty()).then(|| (*pkg_id, missing))
+        })
+        .collect::<Vec<_>>();
+    for (pkg_id, bins) in missing {
+        for bin in &bins {
+            gctx.shell().status(
+                "Removing",
+                format!("`{bin}` of `{pkg_id}` from the installed packages, it no longer exists"),
+            )?;
+        }
+        tracker.remove(pkg_id, &bins);
+        removed += 1;
+    }
+    tracker.save()?;
+
+    // Only directories recorded as belonging to `root` are considered, the
+    // cache is shared with the other install roots.
+    let cache = gctx.home().join("install-cache");
+    let used = tracker
+        .all_installed_bins()
+        .map(|(pkg_id, _)| install_cache_dir(gctx, *pkg_id).into_path_unlocked())
+        .collect::<BTreeSet<_>>();
+    let root_bytes = root.as_path_unlocked().as_os_str().as_encoded_bytes();
+    let entries = if cache.as_path_unlocked().exists() {
+        read_dir(cache.as_path_unlocked())?
+    } else {
+        Vec::new()
+    };
+    for path in entries {
+        if used.contains(&path) {
+            continue;
+        }
+        match fs::read(path.join(INSTALL_CACHE_ROOT_FILE)) {
+            Ok(recorded) if recorded == root_bytes => {}
+            _ => continue,
+        }
+        gctx.shell().status("Removing", path.display())?;
+        // Empty the directory while holding its lock, in case it's being
+        // installed from again, then remove what's left.
+        let dir = Filesystem::new(path.clone());
+        let lock = dir.open_rw_exclusive_create(INSTALL_CACHE_LOCK_FILE, gctx, "install cache")?;
+        for entry in read_dir(&path)? {
+            if entry == lock.path() {
+                continue;
+            }
+            if entry.is_dir() {
+                paths::remove_dir_all(&entry)?;
+            } else {
+                paths::remove_file(&entry)?;
+            }
+        }
+        drop(lock);
+        paths::remove_dir_all(&path)?;
+        removed += 1;
+    }
+
+    if removed == 0 {
+        gctx.shell().note("nothing unused to remove")?;
+    }
+    Ok(())
+}
+
+fn read_dir(path: &Path) -> CargoResult<Vec<PathBuf>> {
+    fs::read_dir(path)
+        .and_then(|entries| entries.map(|entry| Ok(entry?.path())).collect())
+        .with_context(|| format!("failed to read directory `{}`", path.display()))
+}
+
 pub fn uninstall_one(
     root: &Filesystem,
     spec: &str,
@@ -4,7 +4,7 @@ use crate::core::resolver::features::{CliFeatures, HasDevUnits};
 use crate::core::shell::Verbosity;
 use crate::core::Registry as _;
 use crate::core::{PackageId, PackageIdSpec, PackageIdSpecQuery};
-use crate::core::{Resolve, SourceId, Workspace};
+use crate::core::{Resolve, ResolveVersion, SourceId, Workspace};
 use crate::ops;
 use crate::sources::source::QueryKind;
 use crate::util::cache_lock::CacheLockMode;
@@ -14,6 +14,7 @@ use crate::util::toml_mut::manifest::LocalManifest;
 use crate::util::toml_mut::upgrade::upgrade_requirement;
 use crate::util::{style, OptVersionReq};
 use crate::util::{CargoResult, VersionExt};
+use anyhow::Context as _;
 use itertools::Itertools;
 use semver::{Op, Version, VersionReq};
 use std::cmp::Ordering;
@@ -25,13 +26,27 @@ pub type UpgradeMap = HashMap<(String, SourceId), Version>;
 pub struct UpdateOptions<'a> {
     pub gctx: &'a GlobalContext,
     pub to_update: Vec<String>,
-    pub precise: Option<&'a str>,
+    /// What to update the packages of `to_update` to: a single value applies
+    /// to all of them, otherwise there is one for each, in order.
+    ///
+    /// For registry packages, this is a version, or a version requirement to
+    /// pick the newest matching version of.
+    pub precise: Vec<&'a str>,
     pub recursive: bool,
     pub dry_run: bool,
     pub workspace: bool,
+    /// Rewrite the lock file using this format version.
+    pub lockfile_version: Option<u32>,
 }
 
 pub fn generate_lockfile(ws: &Workspace<'_>) -> CargoResult<()> {
+    generate_lockfile_with_version(ws, None)
+}
+
+fn generate_lockfile_with_version(
+    ws: &Workspace<'_>,
+    lockfile_version: Option<ResolveVersion>,
+) -> CargoResult<()> {
     let mut registry = ws.package_registry()?;
     let previous_resolve = None;
     let mut resolve = ops::resolve_with_previous(
@@ -44,20 +59,54 @@ pub fn generate_lockfile(ws: &Workspace<'_>) -> CargoResult<()> {
         &[],
         true,
     )?;
+    if let Some(version) = lockfile_version {
+        resolve.set_version(version);
+    }
+    ops::advisories::check_new_packages(ws, previous_resolve, &resolve)?;
     ops::write_pkg_lockfile(ws, &mut resolve)?;
     print_lockfile_changes(ws, previous_resolve, &resolve, &mut registry)?;
     Ok(())
 }
 
 pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoResult<()> {
-    if opts.recursive && opts.precise.is_some() {
+    if opts.recursive && !opts.precise.is_empty() {
         anyhow::bail!("cannot specify both recursive and precise simultaneously")
     }
+    if opts.precise.len() > 1 && opts.precise.len() != opts.to_update.len() {
+        anyhow::bail!(
+            "`--precise` was passed {} times to update {} packages, \
+             pass it either once for all of them or once for each",
+            opts.precise.len(),
+            opts.to_update.len()
+        )
+    }
 
     if ws.members().count() == 0 {
         anyhow::bail!("you can't generate a lockfile for an empty workspace.")
     }
 
+    let lockfile_version = opts
+        .lockfile_version
+        .map(|n| {
+            ResolveVersion::from_lockfile_version(n).ok_or_else(|| {
+                let expected = ResolveVersion::SELECTABLE
+                    .iter()
+                    .filter_map(|v| v.lockfile_version())
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>();
+                anyhow::format_err!(
+                    "unsupported lock file version `{n}`, expected one of {}",
+                    expected.join(", ")
+                )
+            })
+        })
+        .transpose()?;
+    if let (Some(n), Some(version)) = (opts.lockfile_version, lockfile_version) {
+        if version > ResolveVersion::max_stable() && !opts.gctx.cli_unstable().next_lockfile_bump {
+            anyhow::bail!("lock file version `{n}` requires `-Znext-lockfile-bump`")
+        }
+    }
+
     // Updates often require a lot of modifications to the registry, so ensure
     // that we're synchronized against other Cargos.
     let _lock = ws
@@ -67,12 +116,12 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
     let previous_resolve = match ops::load_pkg_lockfile(ws)? {
         Some(resolve) => resolve,
         None => {
-            match opts.precise {
-                None => return generate_lockfile(ws),
+            match opts.precise.is_empty() {
+                true => return generate_lockfile_with_version(ws, lockfile_version),
 
                 // Precise option specified, so calculate a previous_resolve required
                 // by precise package update later.
-                Some(_) => {
+                false => {
                     let mut registry = ws.package_registry()?;
                     ops::resolve_with_previous(
                         &mut registry,
@@ -97,24 +146,37 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
             to_avoid.extend(previous_resolve.unused_patches());
         }
     } else {
-        let mut sources = Vec::new();
-        for name in opts.to_update.iter() {
+        let mut sources: Vec<SourceId> = Vec::new();
+        for (i, name) in opts.to_update.iter().enumerate() {
             let pid = previous_resolve.query(name)?;
             if opts.recursive {
                 fill_with_deps(&previous_resolve, pid, &mut to_avoid, &mut HashSet::new());
             } else {
                 to_avoid.insert(pid);
-                sources.push(match opts.precise {
+                let precise = match opts.precise.as_slice() {
+                    [] => None,
+                    [precise] => Some(*precise),
+                    precise => Some(precise[i]),
+                };
+                // Packages of the same registry are all updated through a
+                // single source.
+                let source_id = match sources.iter().position(|s| *s == pid.source_id()) {
+                    Some(i) => sources.remove(i),
+                    None => pid.source_id(),
+                };
+                sources.push(match precise {
                     Some(precise) => {
                         // TODO: see comment in `resolve.rs` as well, but this
                         //       seems like a pretty hokey reason to single out
                         //       the registry as well.
                         if pid.source_id().is_registry() {
-                            pid.source_id().with_precise_registry_version(
+                            let precise =
+                                precise_registry_version(ws, &previous_resolve, pid, precise)?;
+                            source_id.with_precise_registry_version(
                                 pid.name(),
                                 pid.version().clone(),
                                 precise,
-                            )?
+                            )
                         } else {
                             pid.source_id().with_git_precise(Some(precise.to_string()))
                         }
@@ -178,11 +240,20 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
         true,
     )?;
 
+    if let (Some(n), Some(version)) = (opts.lockfile_version, lockfile_version) {
+        if version != resolve.version() {
+            opts.gctx
+                .shell()
+                .status("Migrating", format!("lock file to version {n}"))?;
+            resolve.set_version(version);
+        }
+    }
+
     print_lockfile_updates(
         ws,
         &previous_resolve,
         &resolve,
-        opts.precise.is_some(),
+        !opts.precise.is_empty(),
         &mut registry,
     )?;
     if opts.dry_run {
@@ -190,11 +261,366 @@ pub fn update_lockfile(ws: &Workspace<'_>, opts: &UpdateOptions<'_>) -> CargoRes
             .shell()
             .warn("not updating lockfile due to dry run")?;
     } else {
+        ops::advisories::check_new_packages(ws, Some(&previous_resolve), &resolve)?;
+        ops::write_pkg_lockfile(ws, &mut resolve)?;
+    }
+    Ok(())
+}
+
+/// Parses the `--precise` value for a registry package, either a version or,
+/// with `-Z unstable-options`, a version requirement.
+///
+/// For a requirement, this is the newest version of the registry matching
+/// it as well as what the dependents of the package require, leaving out
+/// yanked versions.
+fn precise_registry_version(
+    ws: &Workspace<'_>,
+    previous_resolve: &Resolve,
+    pid: PackageId,
+    precise: &str,
+) -> CargoResult<Version> {
+    if !ws.gctx().cli_unstable().unstable_options {
+        return Version::parse(precise)
+            .with_context(|| format!("invalid version format for precise version `{precise}`"));
+    }
+    if let Ok(version) = Version::parse(precise) {
+        return Ok(version);
+    }
+    let req = VersionReq::parse(precise).with_context(|| {
+        format!("invalid version or version requirement for precise version `{precise}`")
+    })?;
+    // Dependents may have been resolved with a different requirement than
+    // the current one in their manifest, which the resolver would report.
+    let dependents_reqs: Vec<_> = previous_resolve
+        .iter()
+        .flat_map(|parent| previous_resolve.deps(parent))
+        .filter(|(dep_id, _)| *dep_id == pid)
+        .flat_map(|(_, deps)| deps.iter().map(|dep| dep.version_req().clone()))
+        .collect();
+
+    // A registry of its own, as loading the source here would keep it from
+    // being updated to the precise versions later.
+    let mut registry = ws.package_registry()?;
+    registry.lock_patches();
+    let query = Dependency::parse(pid.name(), None, pid.source_id().without_precise())?;
+    let candidates = loop {
+        match registry.query_vec(&query, QueryKind::Exact) {
+            std::task::Poll::Ready(res) => break res?,
+            std::task::Poll::Pending => registry.block_until_ready()?,
+        }
+    };
+    candidates
+        .into_iter()
+        .filter(|s| !s.is_yanked())
+        .map(|s| s.as_summary().version().clone())
+        .filter(|version| {
+            req.matches(version)
+                && dependents_reqs.iter().all(|dep_req| match dep_req {
+                    OptVersionReq::Any => true,
+                    OptVersionReq::Req(dep_req)
+                    | OptVersionReq::Locked(_, dep_req)
+                    | OptVersionReq::Precise(_, dep_req) => dep_req.matches(version),
+                })
+        })
+        .max()
+        .ok_or_else(|| {
+            anyhow::format_err!(
+                "no version of `{}` matches `{req}` and what its dependents require",
+                pid.name()
+            )
+        })
+}
+
+pub struct ResolveLockfileOptions<'a> {
+    pub gctx: &'a GlobalContext,
+    /// Don't write `Cargo.lock`, print it to stdout instead.
+    pub dry_run: bool,
+    pub format: ResolveLockfileFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolveLockfileFormat {
+    /// Report changes with the usual shell status messages.
+    Human,
+    /// Print a JSON description of the changes to stdout.
+    Json,
+}
+
+/// Resolves the workspace and writes (or prints) `Cargo.lock`, without
+/// downloading any packages or touching the target directory.
+///
+/// Unlike [`update_lockfile`] this only fills in what the existing lock file
+/// is missing, the same way a build would.
+pub fn resolve_lockfile(ws: &Workspace<'_>, opts: &ResolveLockfileOptions<'_>) -> CargoResult<()> {
+    if ws.members().count() == 0 {
+        anyhow::bail!("you can't generate a lockfile for an empty workspace.")
+    }
+
+    let previous_resolve = ops::load_pkg_lockfile(ws)?;
+    let mut registry = ws.package_registry()?;
+    let mut resolve = ops::resolve_with_previous(
+        &mut registry,
+        ws,
+        &CliFeatures::new_all(true),
+        HasDevUnits::Yes,
+        previous_resolve.as_ref(),
+        None,
+        &[],
+        true,
+    )?;
+
+    match opts.format {
+        ResolveLockfileFormat::Human => {
+            print_lockfile_changes(ws, previous_resolve.as_ref(), &resolve, &mut registry)?;
+        }
+        ResolveLockfileFormat::Json => {
+            let diff = match &previous_resolve {
+                Some(previous_resolve) => PackageDiff::diff(previous_resolve, &resolve),
+                None => PackageDiff::new(&resolve),
+            };
+            opts.gctx
+                .shell()
+                .print_json(&LockfileDelta::new(previous_resolve.is_some(), &diff))?;
+        }
+    }
+
+    if opts.dry_run {
+        if opts.format == ResolveLockfileFormat::Human {
+            let out = ops::resolve_to_string(ws, &resolve)?;
+            crate::drop_print!(opts.gctx, "{}", out);
+        }
+        opts.gctx
+            .shell()
+            .warn("not updating lockfile due to dry run")?;
+    } else {
+        ops::advisories::check_new_packages(ws, previous_resolve.as_ref(), &resolve)?;
         ops::write_pkg_lockfile(ws, &mut resolve)?;
     }
     Ok(())
 }
 
+/// JSON output of `cargo resolve --format json`.
+#[derive(serde::Serialize)]
+struct LockfileDelta {
+    /// Whether a `Cargo.lock` existed before resolving.
+    lockfile_existed: bool,
+    added: Vec<LockfilePackage>,
+    removed: Vec<LockfilePackage>,
+    /// Packages whose only version within a source changed.
+    updated: Vec<LockfileUpdate>,
+}
+
+#[derive(serde::Serialize)]
+struct LockfilePackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+impl LockfilePackage {
+    fn new(id: &PackageId) -> LockfilePackage {
+        LockfilePackage {
+            name: id.name().to_string(),
+            version: id.version().to_string(),
+            source: LockfilePackage::source(id),
+        }
+    }
+
+    fn source(id: &PackageId) -> Option<String> {
+        (!id.source_id().is_path()).then(|| id.source_id().as_url().to_string())
+    }
+}
+
+#[derive(serde::Serialize)]
+struct LockfileUpdate {
+    name: String,
+    source: Option<String>,
+    from: String,
+    to: String,
+}
+
+impl LockfileDelta {
+    fn new(lockfile_existed: bool, diff: &[PackageDiff]) -> LockfileDelta {
+        let mut delta = LockfileDelta {
+            lockfile_existed,
+            added: Vec::new(),
+            removed: Vec::new(),
+            updated: Vec::new(),
+        };
+        for diff in diff {
+            if let Some((from, to)) = diff.change() {
+                delta.updated.push(LockfileUpdate {
+                    name: to.name().to_string(),
+                    source: LockfilePackage::source(to),
+                    from: from.version().to_string(),
+                    to: to.version().to_string(),
+                });
+            } else {
+                delta
+                    .added
+                    .extend(diff.added.iter().map(LockfilePackage::new));
+                delta
+                    .removed
+                    .extend(diff.removed.iter().map(LockfilePackage::new));
+            }
+        }
+        delta
+    }
+}
+
+pub struct VerifyLockfileOptions<'a> {
+    pub gctx: &'a GlobalContext,
+    pub format: VerifyLockfileFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyLockfileFormat {
+    /// Fail with an error listing every problem found.
+    Human,
+    /// Print a JSON report to stdout.
+    Json,
+}
+
+/// Checks that `Cargo.lock` agrees with the workspace manifests, that the
+/// checksums it records match any `.crate` files already in the cache, and
+/// that none of its entries are yanked. Nothing is written.
+///
+/// Returns `false` if a check failed and the JSON report was printed. For the
+/// human format a failure is an error instead.
+pub fn verify_lockfile(ws: &Workspace<'_>, opts: &VerifyLockfileOptions<'_>) -> CargoResult<bool> {
+    if ws.members().count() == 0 {
+        anyhow::bail!("you can't verify a lockfile for an empty workspace.")
+    }
+
+    let Some(previous_resolve) = ops::load_pkg_lockfile(ws)? else {
+        anyhow::bail!(
+            "no `Cargo.lock` to verify\n\n\
+             help: run `cargo generate-lockfile` to create one"
+        )
+    };
+    let mut registry = ws.package_registry()?;
+    let resolve = ops::resolve_with_previous(
+        &mut registry,
+        ws,
+        &CliFeatures::new_all(true),
+        HasDevUnits::Yes,
+        Some(&previous_resolve),
+        None,
+        &[],
+        true,
+    )?;
+    let diff = PackageDiff::diff(&previous_resolve, &resolve);
+    let up_to_date = ops::is_lockfile_up_to_date(ws, &resolve);
+
+    let locked: Vec<_> = previous_resolve.iter().collect();
+    let pkg_set = registry.get(&locked)?;
+    let mut checksum_mismatches = Vec::new();
+    {
+        let _lock = opts
+            .gctx
+            .acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+        let mut sources = pkg_set.sources_mut();
+        for id in previous_resolve.iter().sorted() {
+            let Some(Some(expected)) = previous_resolve.checksums().get(&id) else {
+                continue;
+            };
+            let Some(source) = sources.get_mut(id.source_id()) else {
+                continue;
+            };
+            if let Some(actual) = source.cached_checksum(id)? {
+                if &actual != expected {
+                    checksum_mismatches.push((id, expected.clone(), actual));
+                }
+            }
+        }
+    }
+    let yanked = ops::yanked_packages(opts.gctx, &pkg_set, &previous_resolve, true)?;
+
+    let ok = up_to_date && checksum_mismatches.is_empty() && yanked.is_empty();
+    match opts.format {
+        VerifyLockfileFormat::Human => {
+            if ok {
+                opts.gctx.shell().status(
+                    "Verified",
+                    format!("{} packages in `Cargo.lock`", locked.len()),
+                )?;
+                return Ok(true);
+            }
+            let mut problems = Vec::new();
+            for diff in &diff {
+                if let Some((from, to)) = diff.change() {
+                    problems.push(format!("changed: {from} -> v{}", to.version()));
+                } else {
+                    problems.extend(diff.added.iter().map(|id| format!("missing: {id}")));
+                    problems.extend(diff.removed.iter().map(|id| format!("extra: {id}")));
+                }
+            }
+            if problems.is_empty() && !up_to_date {
+                problems.push("out of date: dependencies differ from the manifests".to_owned());
+            }
+            for (id, expected, actual) in &checksum_mismatches {
+                problems.push(format!(
+                    "checksum mismatch: {id} (`Cargo.lock` has `{expected}`, \
+                     the cached `.crate` file has `{actual}`)"
+                ));
+            }
+            problems.extend(yanked.iter().map(|id| format!("yanked: {id}")));
+            anyhow::bail!(
+                "`Cargo.lock` failed verification:\n  {}",
+                problems.join("\n  ")
+            )
+        }
+        VerifyLockfileFormat::Json => {
+            let delta = LockfileDelta::new(true, &diff);
+            let report = LockfileReport {
+                ok,
+                up_to_date,
+                missing: delta.added,
+                extra: delta.removed,
+                changed: delta.updated,
+                checksum_mismatches: checksum_mismatches
+                    .into_iter()
+                    .map(|(id, expected, actual)| ChecksumMismatch {
+                        package: LockfilePackage::new(&id),
+                        expected,
+                        actual,
+                    })
+                    .collect(),
+                yanked: yanked.iter().map(LockfilePackage::new).collect(),
+            };
+            opts.gctx.shell().print_json(&report)?;
+            Ok(ok)
+        }
+    }
+}
+
+/// JSON output of `cargo verify-lockfile --format json`.
+#[derive(serde::Serialize)]
+struct LockfileReport {
+    /// Whether every check passed.
+    ok: bool,
+    /// Whether resolving would leave `Cargo.lock` untouched.
+    up_to_date: bool,
+    /// Packages the manifests need that `Cargo.lock` doesn't list.
+    missing: Vec<LockfilePackage>,
+    /// Packages `Cargo.lock` lists that the manifests no longer need.
+    extra: Vec<LockfilePackage>,
+    /// Packages locked at a version the manifests no longer accept.
+    changed: Vec<LockfileUpdate>,
+    checksum_mismatches: Vec<ChecksumMismatch>,
+    yanked: Vec<LockfilePackage>,
+}
+
+#[derive(serde::Serialize)]
+struct ChecksumMismatch {
+    #[serde(flatten)]
+    package: LockfilePackage,
+    /// Checksum recorded in `Cargo.lock`.
+    expected: String,
+    /// Checksum of the cached `.crate` file.
+    actual: String,
+}
+
 /// Prints lockfile change statuses.
 ///
 /// This would acquire the package-cache lock, as it may update the index to
@@ -776,9 +1202,9 @@ fn status_locking(ws: &Workspace<'_>, num_pkgs: usize) -> CargoResult<()> {
 
     let mut cfg = String::new();
     // Don't have a good way to describe `direct_minimal_versions` atm
-    if !ws.gctx().cli_unstable().direct_minimal_versions {
+    if !ws.resolve_direct_minimal_versions() {
         write!(&mut cfg, " to")?;
-        if ws.gctx().cli_unstable().minimal_versions {
+        if ws.resolve_minimal_versions() {
             write!(&mut cfg, " earliest")?;
         } else {
             write!(&mut cfg, " latest")?;
@@ -803,7 +1229,7 @@ fn status_locking(ws: &Workspace<'_>, num_pkgs: usize) -> CargoResult<()> {
     Ok(())
 }
 
-fn is_latest(candidate: &semver::Version, current: &semver::Version) -> bool {
+pub(super) fn is_latest(candidate: &semver::Version, current: &semver::Version) -> bool {
     current < candidate
                 // Only match pre-release if major.minor.patch are the same
                 && (candidate.pre.is_empty()
@@ -0,0 +1,241 @@
+//! Core of the `cargo workspace` command, which edits the member lists of a
+//! workspace root manifest.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use cargo_util::paths;
+
+use crate::core::SourceId;
+use crate::core::Workspace;
+use crate::ops;
+use crate::ops::cargo_add::inherit_workspace_dep;
+use crate::ops::cargo_new::can_be_workspace_member;
+use crate::ops::cargo_new::get_display_path;
+use crate::ops::cargo_new::insert_workspace_path;
+use crate::util::toml_mut::dependency::Dependency;
+use crate::util::toml_mut::manifest::LocalManifest;
+use crate::CargoResult;
+use crate::GlobalContext;
+
+/// Add a package to the members of a workspace.
+#[derive(Debug)]
+pub struct AddMemberOptions<'a> {
+    /// Configuration information for Cargo operations
+    pub gctx: &'a GlobalContext,
+    /// Directory of the package to add
+    pub path: PathBuf,
+    /// Whether to also add the package to `workspace.default-members`, which
+    /// must be present
+    pub default_member: bool,
+    /// Whether to switch dependencies also listed in `[workspace.dependencies]`
+    /// to `workspace = true`
+    pub inherit_dependencies: bool,
+}
+
+/// Remove a package from the members of a workspace.
+#[derive(Debug)]
+pub struct RemoveMemberOptions<'a> {
+    /// Configuration information for Cargo operations
+    pub gctx: &'a GlobalContext,
+    /// Directory of the package to remove
+    pub path: PathBuf,
+}
+
+/// Registers the package at `options.path` in `workspace.members`, preserving
+/// the formatting of the root manifest.
+pub fn add_member(ws: &Workspace<'_>, options: &AddMemberOptions<'_>) -> CargoResult<()> {
+    let gctx = options.gctx;
+    let root_manifest_path = ws.root_manifest();
+    let original = paths::read(root_manifest_path)?;
+    let mut document: toml_edit::DocumentMut = original.parse()?;
+    let display_path = get_display_path(root_manifest_path, &options.path)?;
+    let name = member_name(&options.path);
+
+    if !can_be_workspace_member(&display_path, &document)? {
+        anyhow::bail!(
+            "`{display_path}` is listed in `workspace.exclude` of `{}`",
+            root_manifest_path.display()
+        );
+    }
+    let Some(workspace) = document.get_mut("workspace") else {
+        anyhow::bail!(
+            "no `[workspace]` table in `{}`",
+            root_manifest_path.display()
+        );
+    };
+    // Without `default-members`, every member is already a default member,
+    // and creating the key would make this one the only default member.
+    if options.default_member && workspace.get("default-members").is_none() {
+        anyhow::bail!(
+            "cannot add `{display_path}` to `workspace.default-members`, \
+             there is no such key in `{}`\n\
+             \n\
+             help: without it, every member is a default member",
+            root_manifest_path.display()
+        );
+    }
+
+    // Check the package before touching the root manifest.
+    let member_manifest_path = options.path.join("Cargo.toml");
+    ops::read_package(
+        &member_manifest_path,
+        SourceId::for_path(&options.path)?,
+        gctx,
+    )
+    .with_context(|| format!("failed to add `{display_path}` to the workspace"))?;
+
+    let mut modified = false;
+    if insert_workspace_path(workspace, "members", &display_path)? {
+        gctx.shell().status(
+            "Adding",
+            format!(
+                "`{name}` as member of workspace at `{}`",
+                ws.root().display()
+            ),
+        )?;
+        modified = true;
+    }
+    if options.default_member && insert_workspace_path(workspace, "default-members", &display_path)?
+    {
+        gctx.shell().status(
+            "Adding",
+            format!(
+                "`{name}` as default member of workspace at `{}`",
+                ws.root().display()
+            ),
+        )?;
+        modified = true;
+    }
+
+    if modified {
+        paths::write_atomic(root_manifest_path, document.to_string().as_bytes())?;
+        // Make sure the new member list still forms a valid workspace, and
+        // leave the root manifest as it was otherwise.
+        if let Err(e) = Workspace::new(root_manifest_path, gctx) {
+            paths::write_atomic(root_manifest_path, original)?;
+            return Err(e);
+        }
+    }
+
+    if options.inherit_dependencies {
+        inherit_dependencies(gctx, ws.root(), &document, &member_manifest_path)?;
+    }
+    Ok(())
+}
+
+/// Removes the package at `options.path` from `workspace.members` and
+/// `workspace.default-members`, leaving the package itself untouched.
+pub fn remove_member(ws: &Workspace<'_>, options: &RemoveMemberOptions<'_>) -> CargoResult<()> {
+    let gctx = options.gctx;
+    let root_manifest_path = ws.root_manifest();
+    let mut document: toml_edit::DocumentMut = paths::read(root_manifest_path)?.parse()?;
+    let display_path = get_display_path(root_manifest_path, &options.path)?;
+    let name = member_name(&options.path);
+
+    let Some(workspace) = document.get_mut("workspace") else {
+        anyhow::bail!(
+            "no `[workspace]` table in `{}`",
+            root_manifest_path.display()
+        );
+    };
+
+    let members = workspace
+        .get("members")
+        .and_then(|members| members.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|member| member.as_str())
+        .collect::<Vec<_>>();
+    if !members
+        .iter()
+        .any(|member| normalize_member(member) == display_path)
+    {
+        for member in &members {
+            let pattern = glob::Pattern::new(member)
+                .with_context(|| format!("cannot build glob pattern from `{}`", member))?;
+            if pattern.matches(&display_path) {
+                anyhow::bail!(
+                    "`{display_path}` is included by the pattern `{member}` in `workspace.members`\n\
+                     \n\
+                     help: add it to `workspace.exclude` to remove it from the workspace"
+                );
+            }
+        }
+        anyhow::bail!(
+            "`{display_path}` is not a member of workspace at `{}`",
+            ws.root().display()
+        );
+    }
+
+    for key in ["members", "default-members"] {
+        let Some(array) = workspace.get_mut(key).and_then(|item| item.as_array_mut()) else {
+            continue;
+        };
+        let len = array.len();
+        array.retain(|member| {
+            member
+                .as_str()
+                .map_or(true, |member| normalize_member(member) != display_path)
+        });
+        if array.len() != len {
+            let what = if key == "members" {
+                "members"
+            } else {
+                "default members"
+            };
+            gctx.shell().status(
+                "Removing",
+                format!(
+                    "`{name}` from {what} of workspace at `{}`",
+                    ws.root().display()
+                ),
+            )?;
+        }
+    }
+
+    paths::write_atomic(root_manifest_path, document.to_string().as_bytes())?;
+    Ok(())
+}
+
+/// Switches the dependencies of the member at `manifest_path` that are also
+/// listed in `[workspace.dependencies]` to `workspace = true`.
+fn inherit_dependencies(
+    gctx: &GlobalContext,
+    root: &Path,
+    document: &toml_edit::DocumentMut,
+    manifest_path: &Path,
+) -> CargoResult<()> {
+    let Some(workspace_deps) = document
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(|deps| deps.as_table_like())
+    else {
+        return Ok(());
+    };
+
+    let mut manifest = LocalManifest::try_new(manifest_path)?;
+    let original = manifest.to_string();
+    for (key, item) in workspace_deps.iter() {
+        let dep = Dependency::from_toml(root, key, item)?;
+        inherit_workspace_dep(gctx, &mut manifest, &dep)?;
+    }
+    if manifest.to_string() != original {
+        manifest.write()?;
+    }
+    Ok(())
+}
+
+/// The name used for a member in status messages, like `cargo new` does.
+fn member_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Strips the decorations a hand-written member path may carry.
+fn normalize_member(member: &str) -> &str {
+    let member = member.strip_prefix("./").unwrap_or(member);
+    member.trim_end_matches('/')
+}
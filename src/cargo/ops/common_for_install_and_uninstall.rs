@@ -12,7 +12,8 @@ use cargo_util_schemas::core::PartialVersion;
 use ops::FilterRule;
 use serde::{Deserialize, Serialize};
 
-use crate::core::compiler::{DirtyReason, Freshness};
+use crate::core::compiler::{CompileKind, CompileTarget, DirtyReason, Freshness};
+use crate::core::resolver::CliFeatures;
 use crate::core::Target;
 use crate::core::{Dependency, FeatureValue, Package, PackageId, SourceId};
 use crate::ops::{self, CompileFilter, CompileOptions};
@@ -21,6 +22,8 @@ use crate::sources::source::Source;
 use crate::sources::PathSource;
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::errors::CargoResult;
+use crate::util::hex::short_hash;
+use crate::util::interning::InternedString;
 use crate::util::GlobalContext;
 use crate::util::{FileLock, Filesystem};
 
@@ -85,11 +88,84 @@ struct InstallInfo {
     /// None if unknown (when loading from v1).
     /// Currently not used, possibly may be used in the future.
     rustc: Option<String>,
+    /// The binaries and examples selected with `--bin` or `--example`.
+    /// None if the default binaries were installed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filter: Option<InstallFilter>,
     /// Forwards compatibility.
     #[serde(flatten)]
     other: BTreeMap<String, serde_json::Value>,
 }
 
+/// The targets selected when installing a package, to select them again
+/// when reinstalling it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct InstallFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bins: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    all_bins: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    examples: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    all_examples: bool,
+}
+
+impl InstallFilter {
+    fn new(filter: &CompileFilter) -> Option<InstallFilter> {
+        let CompileFilter::Only { bins, examples, .. } = filter else {
+            return None;
+        };
+        let split = |rule: &FilterRule| match rule {
+            FilterRule::All => (Vec::new(), true),
+            FilterRule::Just(names) => (names.clone(), false),
+        };
+        let (bins, all_bins) = split(bins);
+        let (examples, all_examples) = split(examples);
+        Some(InstallFilter {
+            bins,
+            all_bins,
+            examples,
+            all_examples,
+        })
+    }
+
+    fn union(mut self, other: InstallFilter) -> InstallFilter {
+        for bin in other.bins {
+            if !self.bins.contains(&bin) {
+                self.bins.push(bin);
+            }
+        }
+        for example in other.examples {
+            if !self.examples.contains(&example) {
+                self.examples.push(example);
+            }
+        }
+        self.all_bins |= other.all_bins;
+        self.all_examples |= other.all_examples;
+        self
+    }
+
+    fn to_compile_filter(&self) -> CompileFilter {
+        CompileFilter::from_raw_arguments(
+            false,
+            self.bins.clone(),
+            self.all_bins,
+            Vec::new(),
+            false,
+            self.examples.clone(),
+            self.all_examples,
+            Vec::new(),
+            false,
+            false,
+        )
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
 /// Tracking information for the set of installed packages.
 #[derive(Default, Deserialize, Serialize)]
 pub struct CrateListingV1 {
@@ -310,6 +386,38 @@ impl InstallTracker {
         self.v1.v1.iter()
     }
 
+    /// Compile options to reinstall `pkg_id` with the features, profile,
+    /// target and `--bin` or `--example` selection it was installed with,
+    /// starting from `opts`.
+    ///
+    /// Packages only tracked in the v1 format keep the settings of `opts`.
+    pub fn reinstall_options(
+        &self,
+        pkg_id: PackageId,
+        opts: &CompileOptions,
+        host: &str,
+    ) -> CargoResult<CompileOptions> {
+        let mut opts = opts.clone();
+        let Some(info) = self.v2.installs.get(&pkg_id) else {
+            return Ok(opts);
+        };
+        let features = info.features.iter().cloned().collect::<Vec<_>>();
+        opts.cli_features = CliFeatures::from_command_line(
+            &features,
+            info.all_features,
+            !info.no_default_features,
+        )?;
+        opts.build_config.requested_profile = InternedString::new(&info.profile);
+        if let Some(filter) = &info.filter {
+            opts.filter = filter.to_compile_filter();
+        }
+        if let Some(target) = info.target.as_deref().filter(|target| *target != host) {
+            opts.build_config.requested_kinds =
+                vec![CompileKind::Target(CompileTarget::new(target)?)];
+        }
+        Ok(opts)
+    }
+
     /// Set of binaries installed by a particular package.
     /// Returns None if the package is not installed.
     pub fn installed_bins(&self, pkg_id: PackageId) -> Option<&BTreeSet<String>> {
@@ -463,6 +571,11 @@ impl CrateListingV2 {
             info.profile = opts.build_config.requested_profile.to_string();
             info.target = Some(target.to_string());
             info.rustc = Some(rustc.to_string());
+            // The bins installed before are kept, so keep selecting them.
+            info.filter = match (info.filter.take(), InstallFilter::new(&opts.filter)) {
+                (Some(prev), Some(filter)) => Some(prev.union(filter)),
+                _ => None,
+            };
         } else {
             self.installs.insert(
                 pkg.package_id(),
@@ -475,6 +588,7 @@ impl CrateListingV2 {
                     profile: opts.build_config.requested_profile.to_string(),
                     target: Some(target.to_string()),
                     rustc: Some(rustc.to_string()),
+                    filter: InstallFilter::new(&opts.filter),
                     other: BTreeMap::new(),
                 },
             );
@@ -527,6 +641,7 @@ impl InstallInfo {
             profile: "release".to_string(),
             target: None,
             rustc: None,
+            filter: None,
             other: BTreeMap::new(),
         }
     }
@@ -544,6 +659,33 @@ impl InstallInfo {
     }
 }
 
+/// Lock file of an install cache directory, held while a package is
+/// installed from it.
+pub const INSTALL_CACHE_LOCK_FILE: &str = ".install-lock";
+/// File of an install cache directory recording the install root of its
+/// package.
+pub const INSTALL_CACHE_ROOT_FILE: &str = ".install-root";
+
+/// The target directory kept for `pkg_id` in `$CARGO_HOME/install-cache` by
+/// the unstable `install.target-cache` config.
+///
+/// Each package gets its own directory, so installs of different packages
+/// don't wait on each other, while installs of the same package take turns.
+/// The version isn't part of it, for upgrades to reuse the dependencies.
+pub fn install_cache_dir(gctx: &GlobalContext, pkg_id: PackageId) -> Filesystem {
+    let hash = short_hash(&(
+        pkg_id.name().as_str(),
+        pkg_id
+            .source_id()
+            .canonical_url()
+            .raw_canonicalized_url()
+            .as_str(),
+    ));
+    gctx.home()
+        .join("install-cache")
+        .join(format!("{}-{hash}", pkg_id.name()))
+}
+
 /// Determines the root directory where installation is done.
 pub fn resolve_root(flag: Option<&str>, gctx: &GlobalContext) -> CargoResult<Filesystem> {
     let config_root = gctx.get_path("install.root")?;
@@ -36,7 +36,7 @@
 //!   break anything. The change will be backed out if it fails (unless
 //!   `--broken-code` is used).
 
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::OsString;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -44,7 +44,7 @@ use std::process::{self, ExitStatus, Output};
 use std::{env, fs, str};
 
 use anyhow::{bail, Context as _};
-use cargo_util::{exit_status_to_string, is_simple_exit_code, paths, ProcessBuilder};
+use cargo_util::{exit_status_to_string, is_simple_exit_code, paths, ProcessBuilder, Sha256};
 use cargo_util_schemas::manifest::TomlManifest;
 use rustfix::diagnostics::Diagnostic;
 use rustfix::CodeFix;
@@ -97,6 +97,9 @@ pub struct FixOptions {
     pub allow_no_vcs: bool,
     pub allow_staged: bool,
     pub broken_code: bool,
+    /// A file of JSON messages of a previous build to apply the suggestions
+    /// of, instead of compiling.
+    pub from_json: Option<PathBuf>,
 }
 
 pub fn fix(
@@ -105,8 +108,20 @@ pub fn fix(
     root_manifest: &Path,
     opts: &mut FixOptions,
 ) -> CargoResult<()> {
+    if original_ws.root_maybe().is_embedded() {
+        // rustc builds a copy of the script, which isn't updated as fixes
+        // are applied to the script.
+        bail!("`cargo fix` does not support single-file packages");
+    }
     check_version_control(gctx, opts)?;
 
+    if let Some(path) = &opts.from_json {
+        if opts.edition || opts.idioms {
+            bail!("`--from-json` can't be used to migrate to another edition");
+        }
+        return fix_from_json(original_ws, opts, path);
+    }
+
     let mut target_data =
         RustcTargetData::new(original_ws, &opts.compile_opts.build_config.requested_kinds)?;
     if opts.edition {
@@ -162,6 +177,12 @@ pub fn fix(
         server.configure(&mut wrapper);
     }
 
+    // The proxy runs `clippy-driver` like the workspace wrapper of a normal
+    // build, for `cargo clippy --fix`.
+    if let Some(clippy) = &opts.compile_opts.build_config.clippy {
+        wrapper.env("RUSTC_WORKSPACE_WRAPPER", &clippy.driver);
+    }
+
     let rustc = ws.gctx().load_global_rustc(Some(&ws))?;
     wrapper.arg(&rustc.path);
     // This is calling rustc in cargo fix-proxy-mode, so it also need to retry.
@@ -176,6 +197,139 @@ pub fn fix(
     Ok(())
 }
 
+/// Applies the machine-applicable suggestions of the diagnostics in `path`,
+/// which holds the output of `cargo check --message-format=json`.
+///
+/// Paths of the diagnostics are relative to the workspace root, so the file
+/// can come from another machine, like a CI run. Only the files of the
+/// selected packages are fixed. A file is left untouched unless the messages
+/// record a hash of it, which `-Zunstable-options` does, and the hash still
+/// matches, as the suggestions may not apply to a file that changed since.
+fn fix_from_json(ws: &Workspace<'_>, opts: &FixOptions, path: &Path) -> CargoResult<()> {
+    #[derive(serde::Deserialize)]
+    #[serde(tag = "reason", rename_all = "kebab-case")]
+    enum JsonMessage {
+        CompilerMessage {
+            message: Diagnostic,
+            #[serde(default)]
+            source_hashes: BTreeMap<String, String>,
+        },
+        #[serde(other)]
+        Other,
+    }
+
+    let gctx = ws.gctx();
+    let specs = opts.compile_opts.spec.to_package_id_specs(ws)?;
+    let roots: Vec<&Path> = ws
+        .members()
+        .filter(|m| specs.iter().any(|spec| spec.matches(m.package_id())))
+        .map(|m| m.root())
+        .collect();
+    let contents = paths::read(path)?;
+    let diagnostics = contents
+        .lines()
+        // Ignore anything that isn't a JSON message, like with `rustc` output.
+        .filter_map(|line| serde_json::from_str::<JsonMessage>(line).ok())
+        .filter_map(|msg| match msg {
+            JsonMessage::CompilerMessage {
+                message,
+                source_hashes,
+            } => Some((message, source_hashes)),
+            JsonMessage::Other => None,
+        })
+        .collect::<Vec<_>>();
+
+    // The suggestions to apply to each file, with the hash of the file when
+    // the diagnostics were emitted.
+    let mut file_map: BTreeMap<String, (Option<&String>, Vec<rustfix::Suggestion>)> =
+        BTreeMap::new();
+    for (diag, source_hashes) in &diagnostics {
+        let only = HashSet::new();
+        let Some(suggestion) =
+            rustfix::collect_suggestions(diag, &only, rustfix::Filter::MachineApplicableOnly)
+        else {
+            continue;
+        };
+        let mut file_names = suggestion
+            .solutions
+            .iter()
+            .flat_map(|s| s.replacements.iter())
+            .map(|r| &r.snippet.file_name);
+        let Some(file_name) = file_names.next().cloned() else {
+            continue;
+        };
+        if !file_names.all(|f| *f == file_name) {
+            continue;
+        }
+        let file_path = ws.root().join(&file_name);
+        if !roots.iter().any(|root| file_path.starts_with(root)) {
+            continue;
+        }
+        let hash = source_hashes.get(&file_name);
+        let (file_hash, suggestions) = file_map.entry(file_name).or_default();
+        *file_hash = file_hash.or(hash);
+        suggestions.push(suggestion);
+    }
+
+    for (file_name, (file_hash, suggestions)) in file_map {
+        let file_path = ws.root().join(&file_name);
+        let code = match paths::read(&file_path) {
+            Ok(code) => code,
+            Err(e) => {
+                gctx.shell().warn(format!(
+                    "failed to read `{file_name}`, not applying its suggestions: {e}"
+                ))?;
+                continue;
+            }
+        };
+        let Some(file_hash) = file_hash else {
+            gctx.shell().warn(format!(
+                "the messages don't record a hash of `{file_name}`, \
+                 not applying its suggestions\n\
+                 note: pass `-Zunstable-options` to the build writing the messages to record it"
+            ))?;
+            continue;
+        };
+        if *file_hash != Sha256::new().update(code.as_bytes()).finish_hex() {
+            gctx.shell().warn(format!(
+                "`{file_name}` changed since the diagnostics were emitted, \
+                 not applying its suggestions"
+            ))?;
+            continue;
+        }
+
+        let mut fixed = CodeFix::new(&code);
+        let mut already_applied = HashSet::new();
+        let mut fixes = 0;
+        for suggestion in suggestions.iter().rev() {
+            // The same suggestion is emitted for each unit that compiles the
+            // file, like a library and its tests.
+            if suggestion
+                .solutions
+                .iter()
+                .any(|sol| !already_applied.insert(sol))
+            {
+                continue;
+            }
+            match fixed.apply(suggestion) {
+                Ok(()) => fixes += 1,
+                Err(e) => {
+                    gctx.shell().warn(format!(
+                        "failed to apply a suggestion to `{file_name}`: {e}"
+                    ))?;
+                }
+            }
+        }
+        if fixed.modified() {
+            paths::write(&file_path, fixed.finish()?)?;
+            let msg = if fixes == 1 { "fix" } else { "fixes" };
+            gctx.shell()
+                .status("Fixed", format!("{file_name} ({fixes} {msg})"))?;
+        }
+    }
+    Ok(())
+}
+
 fn check_version_control(gctx: &GlobalContext, opts: &FixOptions) -> CargoResult<()> {
     if opts.allow_no_vcs {
         return Ok(());
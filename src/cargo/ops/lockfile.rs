@@ -19,12 +19,16 @@ pub fn load_pkg_lockfile(ws: &Workspace<'_>) -> CargoResult<Option<Resolve>> {
     f.read_to_string(&mut s)
         .with_context(|| format!("failed to read file: {}", f.path().display()))?;
 
-    let resolve = (|| -> CargoResult<Option<Resolve>> {
-        let v: resolver::EncodableResolve = toml::from_str(&s)?;
-        Ok(Some(v.into_resolve(&s, ws)?))
-    })()
-    .with_context(|| format!("failed to parse lock file at: {}", f.path().display()))?;
-    Ok(resolve)
+    let resolve = parse_lockfile(ws, &s)
+        .with_context(|| format!("failed to parse lock file at: {}", f.path().display()))?;
+    Ok(Some(resolve))
+}
+
+/// Parses the contents of a lock file of the workspace, which needn't be its
+/// `Cargo.lock`, like a copy from another revision.
+pub fn parse_lockfile(ws: &Workspace<'_>, contents: &str) -> CargoResult<Resolve> {
+    let v: resolver::EncodableResolve = toml::from_str(contents)?;
+    v.into_resolve(contents, ws)
 }
 
 /// Generate a toml String of Cargo.lock from a Resolve.
@@ -33,6 +37,13 @@ pub fn resolve_to_string(ws: &Workspace<'_>, resolve: &Resolve) -> CargoResult<S
     Ok(out)
 }
 
+/// Returns `true` if writing `resolve` would leave the existing `Cargo.lock`
+/// untouched.
+pub fn is_lockfile_up_to_date(ws: &Workspace<'_>, resolve: &Resolve) -> bool {
+    let (orig, out, _lock_root) = resolve_to_string_orig(ws, resolve);
+    orig.is_some_and(|orig| are_equal_lockfiles(&orig, &out, ws))
+}
+
 /// Ensure the resolve result is written to fisk
 ///
 /// Returns `true` if the lockfile changed
@@ -64,22 +75,25 @@ pub fn write_pkg_lockfile(ws: &Workspace<'_>, resolve: &mut Resolve) -> CargoRes
         );
     }
 
-    // While we're updating the lock file anyway go ahead and update its
+    // While we're updating the lock file anyway go ahead and update a legacy
     // encoding to whatever the latest default is. That way we can slowly roll
     // out lock file updates as they're otherwise already updated, and changes
     // which don't touch dependencies won't seemingly spuriously update the lock
-    // file.
+    // file. Versions `cargo update --lockfile-version` can pick are left alone,
+    // so that a deliberate downgrade isn't undone.
     let default_version = ResolveVersion::with_rust_version(ws.rust_version());
     let current_version = resolve.version();
     let next_lockfile_bump = ws.gctx().cli_unstable().next_lockfile_bump;
     tracing::debug!("lockfile - current: {current_version:?}, default: {default_version:?}");
 
-    if current_version < default_version {
+    let selectable = ResolveVersion::SELECTABLE.contains(&current_version);
+    if current_version < default_version && !selectable {
         resolve.set_version(default_version);
         out = serialize_resolve(resolve, orig.as_deref());
     } else if current_version > ResolveVersion::max_stable() && !next_lockfile_bump {
         // The next version hasn't yet stabilized.
-        anyhow::bail!("lock file version `{current_version:?}` requires `-Znext-lockfile-bump`")
+        let n = current_version.lockfile_version().unwrap();
+        anyhow::bail!("lock file version `{n}` requires `-Znext-lockfile-bump`")
     }
 
     // Ok, if that didn't work just write it out
@@ -227,6 +241,9 @@ fn emit_package(dep: &toml::Table, out: &mut String) {
     if dep.contains_key("checksum") {
         out.push_str(&format!("checksum = {}\n", &dep["checksum"]));
     }
+    if dep.contains_key("pubtime") {
+        out.push_str(&format!("pubtime = {}\n", &dep["pubtime"]));
+    }
 
     if let Some(s) = dep.get("dependencies") {
         let slice = s.as_array().unwrap();
@@ -246,7 +263,7 @@ fn emit_package(dep: &toml::Table, out: &mut String) {
     }
 }
 
-fn lock_root(ws: &Workspace<'_>) -> Filesystem {
+pub(crate) fn lock_root(ws: &Workspace<'_>) -> Filesystem {
     if ws.root_maybe().is_embedded() {
         ws.target_dir()
     } else {
@@ -1,29 +1,41 @@
 use crate::sources::CRATES_IO_DOMAIN;
 
-pub use self::cargo_clean::{clean, CleanContext, CleanOptions};
+pub use self::cargo_bisect_dep::{bisect_dep, BisectDepOptions};
+pub use self::cargo_clean::{clean, clean_script_cache, CleanContext, CleanOptions};
 pub use self::cargo_compile::{
     compile, compile_with_exec, compile_ws, create_bcx, print, resolve_all_features, CompileOptions,
 };
 pub use self::cargo_compile::{CompileFilter, FilterRule, LibRule, Packages};
 pub use self::cargo_doc::{doc, DocOptions, OutputFormat};
 pub use self::cargo_fetch::{fetch, FetchOptions};
-pub use self::cargo_install::{install, install_list};
-pub use self::cargo_new::{init, new, NewOptions, NewProjectKind, VersionControl};
-pub use self::cargo_output_metadata::{output_metadata, ExportInfo, OutputMetadataOptions};
-pub use self::cargo_package::{check_yanked, package, package_one, PackageOpts};
-pub use self::cargo_pkgid::pkgid;
+pub use self::cargo_install::{install, install_list, install_list_outdated, install_outdated};
+pub use self::cargo_license_report::{license_report, LicenseReportFormat, LicenseReportOptions};
+pub use self::cargo_new::{init, init_workspace, new, NewOptions, NewProjectKind, VersionControl};
+pub use self::cargo_outdated::{outdated, OutdatedFormat, OutdatedOptions};
+pub use self::cargo_output_metadata::{
+    output_metadata, ExportInfo, OutputMetadataOptions, METADATA_SECTIONS,
+};
+pub use self::cargo_package::{check_yanked, package, package_one, yanked_packages, PackageOpts};
+pub use self::cargo_pkgid::{pkgid, pkgids, PkgidFormat, PkgidOptions};
 pub use self::cargo_read_manifest::{read_package, read_packages};
 pub use self::cargo_run::run;
-pub use self::cargo_test::{run_benches, run_tests, TestOptions};
-pub use self::cargo_uninstall::uninstall;
+pub use self::cargo_test::{run_benches, run_tests, Partition, TestOptions};
+pub use self::cargo_uninstall::{uninstall, uninstall_unused};
 pub use self::cargo_update::generate_lockfile;
 pub use self::cargo_update::print_lockfile_changes;
+pub use self::cargo_update::resolve_lockfile;
 pub use self::cargo_update::update_lockfile;
 pub use self::cargo_update::upgrade_manifests;
+pub use self::cargo_update::verify_lockfile;
 pub use self::cargo_update::write_manifest_upgrades;
 pub use self::cargo_update::UpdateOptions;
+pub use self::cargo_update::{ResolveLockfileFormat, ResolveLockfileOptions};
+pub use self::cargo_update::{VerifyLockfileFormat, VerifyLockfileOptions};
 pub use self::fix::{fix, fix_exec_rustc, fix_get_proxy_lock_addr, FixOptions};
-pub use self::lockfile::{load_pkg_lockfile, resolve_to_string, write_pkg_lockfile};
+pub use self::lockfile::{
+    is_lockfile_up_to_date, load_pkg_lockfile, parse_lockfile, resolve_to_string,
+    write_pkg_lockfile,
+};
 pub use self::registry::modify_owners;
 pub use self::registry::publish;
 pub use self::registry::registry_login;
@@ -33,21 +45,29 @@ pub use self::registry::yank;
 pub use self::registry::OwnersOptions;
 pub use self::registry::PublishOpts;
 pub use self::registry::RegistryCredentialConfig;
+pub use self::registry::RegistryMessageFormat;
 pub use self::registry::RegistryOrIndex;
 pub use self::resolve::{
     add_overrides, get_resolved_packages, resolve_with_previous, resolve_ws, resolve_ws_with_opts,
     WorkspaceResolve,
 };
 pub use self::vendor::{vendor, VendorOptions};
+pub use self::watch::watch;
 
+pub mod advisories;
 pub mod cargo_add;
+mod cargo_bisect_dep;
 mod cargo_clean;
 pub(crate) mod cargo_compile;
 pub mod cargo_config;
 mod cargo_doc;
 mod cargo_fetch;
 mod cargo_install;
-mod cargo_new;
+mod cargo_install_prebuilt;
+mod cargo_license_report;
+pub(crate) mod cargo_new;
+mod cargo_new_template;
+mod cargo_outdated;
 mod cargo_output_metadata;
 mod cargo_package;
 mod cargo_pkgid;
@@ -57,6 +77,7 @@ mod cargo_run;
 mod cargo_test;
 mod cargo_uninstall;
 mod cargo_update;
+pub mod cargo_workspace;
 mod common_for_install_and_uninstall;
 mod fix;
 pub(crate) mod lockfile;
@@ -64,6 +85,7 @@ pub(crate) mod registry;
 pub(crate) mod resolve;
 pub mod tree;
 mod vendor;
+pub(crate) mod watch;
 
 /// Returns true if the dependency is either git or path, false otherwise
 /// Error if a git/path dep is transitive, but has no version (registry source).
@@ -1,28 +1,77 @@
 //! Interacts with the registry [login API][1].
 //!
 //! This doesn't really call any web API at this moment. Instead, it's just an
-//! operation for `cargo login`.
+//! operation for `cargo login`. The exception is the unstable OAuth 2.0 device
+//! authorization flow, used when the registry advertises it.
 //!
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html#login
 
 use std::io::IsTerminal;
+use std::io::Read as _;
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::core::SourceId;
+use crate::sources::registry::DeviceAuthorization;
 use crate::util::auth;
 use crate::util::auth::AuthorizationError;
+use crate::util::network::http::registry_http_handle;
 use crate::CargoResult;
 use crate::GlobalContext;
+use anyhow::{bail, Context as _};
 use cargo_credential::LoginOptions;
 use cargo_credential::Secret;
+use serde::Deserialize;
+use url::form_urlencoded;
 
 use super::get_source_id;
 use super::registry;
+use super::registry_config;
 use super::RegistryOrIndex;
 
+/// The grant type of [RFC 8628] to poll for the token with.
+///
+/// [RFC 8628]: https://www.rfc-editor.org/rfc/rfc8628#section-3.4
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// The longest to wait for the user to log in, whatever the `expires_in` of
+/// the device code says.
+const MAX_EXPIRES_IN: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The answer of the device authorization endpoint.
+#[derive(Deserialize)]
+struct DeviceCode {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+/// The answer of the token endpoint once the user logged in.
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+/// The answer of the token endpoint while the user hasn't logged in yet, or
+/// failed to.
+#[derive(Deserialize)]
+struct TokenError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
 pub fn registry_login(
     gctx: &GlobalContext,
     token_from_cmdline: Option<Secret<&str>>,
     reg_or_index: Option<&RegistryOrIndex>,
     args: &[&str],
+    generate_keypair: bool,
 ) -> CargoResult<()> {
     let source_ids = get_source_id(gctx, reg_or_index)?;
 
@@ -36,6 +85,10 @@ pub fn registry_login(
         Err(e) => return Err(e),
     };
 
+    if generate_keypair {
+        return auth::generate_keypair(gctx, &source_ids.original, login_url.as_deref(), args);
+    }
+
     let mut token_from_stdin = None;
     let token = token_from_cmdline.or_else(|| {
         if !std::io::stdin().is_terminal() {
@@ -47,6 +100,29 @@ pub fn registry_login(
         token_from_stdin.as_deref().map(Secret::from)
     });
 
+    let mut token_from_device = None;
+    let token = match token {
+        Some(token) => Some(token),
+        None if gctx.cli_unstable().unstable_options => {
+            // Registries requiring authentication may not hand out their
+            // `config.json` before login, those are left to the provider.
+            let device_authorization = match registry_config(gctx, &source_ids, false) {
+                Ok(cfg) => cfg.device_authorization,
+                Err(e) if e.downcast_ref::<AuthorizationError>().is_some() => None,
+                Err(e) => return Err(e),
+            };
+            if let Some(device_authorization) = device_authorization {
+                token_from_device = Some(device_login(
+                    gctx,
+                    source_ids.original,
+                    &device_authorization,
+                )?);
+            }
+            token_from_device.as_deref().map(Secret::from)
+        }
+        None => None,
+    };
+
     let options = LoginOptions {
         token,
         login_url: login_url.as_deref(),
@@ -55,3 +131,125 @@ pub fn registry_login(
     auth::login(gctx, &source_ids.original, options, args)?;
     Ok(())
 }
+
+/// Gets a token with the OAuth 2.0 device authorization flow of [RFC 8628]:
+/// the user logs in with a code on a web page, while the token endpoint is
+/// polled until the login completes.
+///
+/// [RFC 8628]: https://www.rfc-editor.org/rfc/rfc8628
+fn device_login(
+    gctx: &GlobalContext,
+    sid: SourceId,
+    device_authorization: &DeviceAuthorization,
+) -> CargoResult<String> {
+    let client_id = device_authorization.client_id.as_deref().unwrap_or("cargo");
+    let url = &device_authorization.url;
+    let (code, body) = post_form(gctx, sid, url, &[("client_id", client_id)])
+        .with_context(|| format!("failed to request a device code from `{url}`"))?;
+    if code != 200 {
+        bail!(
+            "failed to request a device code from `{url}`, got {code}: {}",
+            String::from_utf8_lossy(&body)
+        );
+    }
+    let device_code: DeviceCode =
+        serde_json::from_slice(&body).with_context(|| format!("invalid response of `{url}`"))?;
+
+    let page = device_code
+        .verification_uri_complete
+        .as_ref()
+        .unwrap_or(&device_code.verification_uri);
+    gctx.shell().note(format!(
+        "to log in to {}, open `{page}` and enter the code `{}`",
+        sid.display_registry_name(),
+        device_code.user_code
+    ))?;
+
+    let url = &device_authorization.token_url;
+    // A code that practically never expires still needs a deadline that fits
+    // into an `Instant`.
+    let expires_in = Duration::from_secs(device_code.expires_in).min(MAX_EXPIRES_IN);
+    let deadline = Instant::now()
+        .checked_add(expires_in)
+        .unwrap_or_else(|| Instant::now() + MAX_EXPIRES_IN);
+    let mut interval = Duration::from_secs(device_code.interval.unwrap_or(5));
+    loop {
+        if Instant::now() >= deadline {
+            bail!(
+                "the code `{}` expired before logging in",
+                device_code.user_code
+            );
+        }
+        thread::sleep(interval);
+
+        let form = [
+            ("grant_type", DEVICE_CODE_GRANT_TYPE),
+            ("device_code", &device_code.device_code),
+            ("client_id", client_id),
+        ];
+        let (code, body) = post_form(gctx, sid, url, &form)
+            .with_context(|| format!("failed to request a token from `{url}`"))?;
+        if code == 200 {
+            let token: AccessToken = serde_json::from_slice(&body)
+                .with_context(|| format!("invalid response of `{url}`"))?;
+            return Ok(token.access_token);
+        }
+        let Ok(error) = serde_json::from_slice::<TokenError>(&body) else {
+            bail!(
+                "failed to request a token from `{url}`, got {code}: {}",
+                String::from_utf8_lossy(&body)
+            );
+        };
+        match error.error.as_str() {
+            "authorization_pending" => {}
+            // https://www.rfc-editor.org/rfc/rfc8628#section-3.5
+            "slow_down" => interval += Duration::from_secs(5),
+            "access_denied" => bail!("the login was denied"),
+            "expired_token" => bail!(
+                "the code `{}` expired before logging in",
+                device_code.user_code
+            ),
+            _ => bail!(
+                "failed to request a token from `{url}`: {}{}",
+                error.error,
+                error
+                    .error_description
+                    .map(|d| format!(" ({d})"))
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Posts `form` to `url`, returning the status code and body of the
+/// response.
+fn post_form(
+    gctx: &GlobalContext,
+    sid: SourceId,
+    url: &str,
+    form: &[(&str, &str)],
+) -> CargoResult<(u32, Vec<u8>)> {
+    let body = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(form)
+        .finish();
+    let mut body = body.as_bytes();
+    let mut handle = registry_http_handle(gctx, sid)?;
+    handle.url(url)?;
+    handle.post(true)?;
+    handle.post_field_size(body.len() as u64)?;
+    let mut headers = curl::easy::List::new();
+    headers.append("Accept: application/json")?;
+    headers.append("Content-Type: application/x-www-form-urlencoded")?;
+    handle.http_headers(headers)?;
+    let mut response = Vec::new();
+    {
+        let mut transfer = handle.transfer();
+        transfer.read_function(|buf| Ok(body.read(buf).unwrap_or(0)))?;
+        transfer.write_function(|data| {
+            response.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+    Ok((handle.response_code()?, response))
+}
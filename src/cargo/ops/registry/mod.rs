@@ -19,13 +19,14 @@ use crates_io::Registry;
 use url::Url;
 
 use crate::core::SourceId;
+use crate::sources::registry::RegistryConfig;
 use crate::sources::source::Source;
 use crate::sources::{RegistrySource, SourceConfigMap};
 use crate::util::auth;
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::context::{GlobalContext, PathAndArgs};
 use crate::util::errors::CargoResult;
-use crate::util::network::http::http_handle;
+use crate::util::network::http::registry_http_handle;
 
 pub use self::login::registry_login;
 pub use self::logout::registry_logout;
@@ -49,6 +50,15 @@ impl RegistryOrIndex {
     }
 }
 
+/// How `cargo owner`, `cargo yank` and `cargo publish` report their results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryMessageFormat {
+    /// Status messages and a plain list of owners.
+    Human,
+    /// A JSON object on stdout, in addition to the status messages.
+    Json,
+}
+
 /// Registry settings loaded from config files.
 ///
 /// This is loaded based on the `--registry` flag and the config settings.
@@ -131,23 +141,7 @@ fn registry(
         auth::cache_token_from_commandline(gctx, &source_ids.original, token);
     }
 
-    let cfg = {
-        let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
-        let mut src = RegistrySource::remote(source_ids.replacement, &HashSet::new(), gctx)?;
-        // Only update the index if `force_update` is set.
-        if force_update {
-            src.invalidate_cache()
-        }
-        let cfg = loop {
-            match src.config()? {
-                Poll::Pending => src
-                    .block_until_ready()
-                    .with_context(|| format!("failed to update {}", source_ids.replacement))?,
-                Poll::Ready(cfg) => break cfg,
-            }
-        };
-        cfg.expect("remote registries must have config")
-    };
+    let cfg = registry_config(gctx, &source_ids, force_update)?;
     let api_host = cfg
         .api
         .ok_or_else(|| format_err!("{} does not support API commands", source_ids.replacement))?;
@@ -164,11 +158,34 @@ fn registry(
     } else {
         None
     };
-    let handle = http_handle(gctx)?;
-    Ok((
-        Registry::new_handle(api_host, token, handle, cfg.auth_required),
-        source_ids,
-    ))
+    let handle = registry_http_handle(gctx, source_ids.original)?;
+    let mut registry = Registry::new_handle(api_host, token, handle, cfg.auth_required);
+    registry.set_capabilities(cfg.capabilities);
+    Ok((registry, source_ids))
+}
+
+/// Loads the [`RegistryConfig`] of the registry, or its replacement.
+///
+/// The index is only updated if `force_update` is set.
+fn registry_config(
+    gctx: &GlobalContext,
+    source_ids: &RegistrySourceIds,
+    force_update: bool,
+) -> CargoResult<RegistryConfig> {
+    let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+    let mut src = RegistrySource::remote(source_ids.replacement, &HashSet::new(), gctx)?;
+    if force_update {
+        src.invalidate_cache()
+    }
+    let cfg = loop {
+        match src.config()? {
+            Poll::Pending => src
+                .block_until_ready()
+                .with_context(|| format!("failed to update {}", source_ids.replacement))?,
+            Poll::Ready(cfg) => break cfg,
+        }
+    };
+    Ok(cfg.expect("remote registries must have config"))
 }
 
 /// Gets the SourceId for an index or registry setting.
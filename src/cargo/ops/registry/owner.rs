@@ -2,9 +2,11 @@
 //!
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html#owners
 
+use anyhow::bail;
 use anyhow::Context as _;
 use cargo_credential::Operation;
 use cargo_credential::Secret;
+use serde::Serialize;
 
 use crate::core::Workspace;
 use crate::drop_print;
@@ -13,6 +15,7 @@ use crate::util::important_paths::find_root_manifest_for_wd;
 use crate::CargoResult;
 use crate::GlobalContext;
 
+use super::RegistryMessageFormat;
 use super::RegistryOrIndex;
 
 pub struct OwnersOptions {
@@ -20,8 +23,34 @@ pub struct OwnersOptions {
     pub token: Option<Secret<String>>,
     pub reg_or_index: Option<RegistryOrIndex>,
     pub to_add: Option<Vec<String>>,
+    /// The role to invite the owners in `to_add` with.
+    pub role: Option<String>,
     pub to_remove: Option<Vec<String>>,
     pub list: bool,
+    pub format: RegistryMessageFormat,
+}
+
+/// The result of `cargo owner` with [`RegistryMessageFormat::Json`].
+#[derive(Serialize)]
+struct OwnersOutput<'a> {
+    #[serde(rename = "crate")]
+    krate: &'a str,
+    added: &'a [String],
+    role: Option<&'a str>,
+    /// The message of the registry about the invitation.
+    message: Option<String>,
+    removed: &'a [String],
+    /// The owners, if they were listed.
+    owners: Option<Vec<Owner>>,
+}
+
+#[derive(Serialize)]
+struct Owner {
+    login: String,
+    name: Option<String>,
+    email: Option<String>,
+    kind: Option<String>,
+    role: Option<String>,
 }
 
 pub fn modify_owners(gctx: &GlobalContext, opts: &OwnersOptions) -> CargoResult<()> {
@@ -44,17 +73,30 @@ pub fn modify_owners(gctx: &GlobalContext, opts: &OwnersOptions) -> CargoResult<
         Some(operation),
     )?;
 
+    if opts.role.is_some() && !registry.has_capability(crates_io::OWNER_ROLES) {
+        bail!(
+            "the registry at {} does not support owner roles, \
+             as its `config.json` doesn't list the `{}` capability",
+            registry.host(),
+            crates_io::OWNER_ROLES
+        );
+    }
+
+    let mut message = None;
     if let Some(ref v) = opts.to_add {
         let v = v.iter().map(|s| &s[..]).collect::<Vec<_>>();
-        let msg = registry.add_owners(&name, &v).with_context(|| {
-            format!(
-                "failed to invite owners to crate `{}` on registry at {}",
-                name,
-                registry.host()
-            )
-        })?;
+        let msg = registry
+            .add_owners_with_role(&name, &v, opts.role.as_deref())
+            .with_context(|| {
+                format!(
+                    "failed to invite owners to crate `{}` on registry at {}",
+                    name,
+                    registry.host()
+                )
+            })?;
 
-        gctx.shell().status("Owner", msg)?;
+        gctx.shell().status("Owner", &msg)?;
+        message = Some(msg);
     }
 
     if let Some(ref v) = opts.to_remove {
@@ -70,22 +112,54 @@ pub fn modify_owners(gctx: &GlobalContext, opts: &OwnersOptions) -> CargoResult<
         })?;
     }
 
+    let mut owners = None;
     if opts.list {
-        let owners = registry.list_owners(&name).with_context(|| {
+        owners = Some(registry.list_owners(&name).with_context(|| {
             format!(
                 "failed to list owners of crate `{}` on registry at {}",
                 name,
                 registry.host()
             )
-        })?;
-        for owner in owners.iter() {
-            drop_print!(gctx, "{}", owner.login);
-            match (owner.name.as_ref(), owner.email.as_ref()) {
-                (Some(name), Some(email)) => drop_println!(gctx, " ({} <{}>)", name, email),
-                (Some(s), None) | (None, Some(s)) => drop_println!(gctx, " ({})", s),
-                (None, None) => drop_println!(gctx),
+        })?);
+    }
+
+    match opts.format {
+        RegistryMessageFormat::Human => {
+            for owner in owners.iter().flatten() {
+                drop_print!(gctx, "{}", owner.login);
+                match (owner.name.as_ref(), owner.email.as_ref()) {
+                    (Some(name), Some(email)) => drop_print!(gctx, " ({} <{}>)", name, email),
+                    (Some(s), None) | (None, Some(s)) => drop_print!(gctx, " ({})", s),
+                    (None, None) => {}
+                }
+                match owner.role.as_ref() {
+                    Some(role) => drop_println!(gctx, " [{}]", role),
+                    None => drop_println!(gctx),
+                }
             }
         }
+        RegistryMessageFormat::Json => {
+            let output = OwnersOutput {
+                krate: &name,
+                added: opts.to_add.as_deref().unwrap_or_default(),
+                role: opts.role.as_deref(),
+                message,
+                removed: opts.to_remove.as_deref().unwrap_or_default(),
+                owners: owners.map(|owners| {
+                    owners
+                        .into_iter()
+                        .map(|owner| Owner {
+                            login: owner.login,
+                            name: owner.name,
+                            email: owner.email,
+                            kind: owner.kind,
+                            role: owner.role,
+                        })
+                        .collect()
+                }),
+            };
+            drop_println!(gctx, "{}", serde_json::to_string(&output)?);
+        }
     }
 
     Ok(())
@@ -2,11 +2,14 @@
 //!
 //! [1]: https://doc.rust-lang.org/nightly/cargo/reference/registry-web-api.html#publish
 
+use std::cmp::min;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashSet;
 use std::fs::File;
+use std::task::Poll;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::bail;
 use anyhow::Context as _;
@@ -16,6 +19,10 @@ use cargo_util::paths;
 use crates_io::NewCrate;
 use crates_io::NewCrateDependency;
 use crates_io::Registry;
+use curl::easy::List;
+use serde::Serialize;
+use time::format_description::well_known::Rfc2822;
+use time::OffsetDateTime;
 
 use crate::core::dependency::DepKind;
 use crate::core::manifest::ManifestMetadata;
@@ -23,25 +30,31 @@ use crate::core::resolver::CliFeatures;
 use crate::core::Dependency;
 use crate::core::FeatureValue;
 use crate::core::Package;
+use crate::core::PackageId;
 use crate::core::PackageIdSpecQuery;
 use crate::core::SourceId;
 use crate::core::Workspace;
+use crate::drop_println;
 use crate::ops;
 use crate::ops::PackageOpts;
 use crate::ops::Packages;
 use crate::sources::source::QueryKind;
+use crate::sources::source::Source;
+use crate::sources::RegistrySource;
 use crate::sources::SourceConfigMap;
 use crate::sources::CRATES_IO_REGISTRY;
 use crate::util::auth;
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::context::JobsConfig;
 use crate::util::interning::InternedString;
+use crate::util::network::http::registry_http_handle;
 use crate::util::Progress;
 use crate::util::ProgressStyle;
 use crate::CargoResult;
 use crate::GlobalContext;
 
 use super::super::check_dep_has_version;
+use super::RegistryMessageFormat;
 use super::RegistryOrIndex;
 
 pub struct PublishOpts<'gctx> {
@@ -56,6 +69,39 @@ pub struct PublishOpts<'gctx> {
     pub targets: Vec<String>,
     pub dry_run: bool,
     pub cli_features: CliFeatures,
+    /// How long to wait for the package to be available, overriding
+    /// `publish.timeout`.
+    pub wait_timeout: Option<u64>,
+    pub format: RegistryMessageFormat,
+}
+
+/// How [`wait_for_publish`] checks that a package is available.
+struct WaitOpts {
+    timeout: Duration,
+    poll_interval: Duration,
+    /// Whether to also download the `.crate` file and compare its checksum.
+    verify_download: bool,
+    format: RegistryMessageFormat,
+}
+
+/// The event of [`wait_for_publish`] with [`RegistryMessageFormat::Json`].
+#[derive(Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum PublishEvent<'a> {
+    /// The package can be depended on.
+    PublishAvailable {
+        name: &'a str,
+        version: String,
+        source: String,
+        /// What was checked, `index` and possibly `download`.
+        verified: Vec<&'static str>,
+    },
+    /// The package wasn't available before the timeout.
+    PublishTimeout {
+        name: &'a str,
+        version: String,
+        source: String,
+    },
 }
 
 pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
@@ -158,10 +204,10 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
         },
     )?;
 
+    let hash = cargo_util::Sha256::new()
+        .update_file(tarball.file())?
+        .finish_hex();
     if !opts.dry_run {
-        let hash = cargo_util::Sha256::new()
-            .update_file(tarball.file())?
-            .finish_hex();
         let operation = Operation::Publish {
             name: pkg.name().as_str(),
             vers: &ver,
@@ -189,27 +235,50 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
         opts.dry_run,
     )?;
     if !opts.dry_run {
-        const DEFAULT_TIMEOUT: u64 = 60;
-        let timeout = if opts.gctx.cli_unstable().publish_timeout {
-            let timeout: Option<u64> = opts.gctx.get("publish.timeout")?;
-            timeout.unwrap_or(DEFAULT_TIMEOUT)
-        } else {
-            DEFAULT_TIMEOUT
-        };
-        if 0 < timeout {
-            let timeout = Duration::from_secs(timeout);
-            wait_for_publish(opts.gctx, reg_ids.original, pkg, timeout)?;
+        let wait_opts = wait_opts(opts)?;
+        if !wait_opts.timeout.is_zero() {
+            wait_for_publish(opts.gctx, reg_ids.original, pkg, &hash, &wait_opts)?;
         }
     }
 
     Ok(())
 }
 
+/// Loads how to wait for a published package from `--wait-timeout` and the
+/// `publish` config table.
+fn wait_opts(opts: &PublishOpts<'_>) -> CargoResult<WaitOpts> {
+    const DEFAULT_TIMEOUT: u64 = 60;
+    const DEFAULT_POLL_INTERVAL: u64 = 1;
+    let gctx = opts.gctx;
+    let (timeout, poll_interval, verify_download) = if gctx.cli_unstable().publish_timeout {
+        let timeout: Option<u64> = gctx.get("publish.timeout")?;
+        let poll_interval: Option<u64> = gctx.get("publish.poll-interval")?;
+        let verify_download: Option<bool> = gctx.get("publish.verify-download")?;
+        (
+            opts.wait_timeout.or(timeout).unwrap_or(DEFAULT_TIMEOUT),
+            poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL),
+            verify_download.unwrap_or(false),
+        )
+    } else {
+        (DEFAULT_TIMEOUT, DEFAULT_POLL_INTERVAL, false)
+    };
+    if poll_interval == 0 {
+        bail!("`publish.poll-interval` must be at least 1 second");
+    }
+    Ok(WaitOpts {
+        timeout: Duration::from_secs(timeout),
+        poll_interval: Duration::from_secs(poll_interval),
+        verify_download,
+        format: opts.format,
+    })
+}
+
 fn wait_for_publish(
     gctx: &GlobalContext,
     registry_src: SourceId,
     pkg: &Package,
-    timeout: Duration,
+    cksum: &str,
+    opts: &WaitOpts,
 ) -> CargoResult<()> {
     let version_req = format!("={}", pkg.version());
     let mut source = SourceConfigMap::empty(gctx)?.load(registry_src, &HashSet::new())?;
@@ -221,7 +290,7 @@ fn wait_for_publish(
     let query = Dependency::parse(pkg.name(), Some(&version_req), registry_src)?;
 
     let now = std::time::Instant::now();
-    let sleep_time = Duration::from_secs(1);
+    let timeout = opts.timeout;
     let max = timeout.as_secs() as usize;
     // Short does not include the registry name.
     let short_pkg_description = format!("{} v{}", pkg.name(), pkg.version());
@@ -235,6 +304,8 @@ fn wait_for_publish(
     ))?;
     let mut progress = Progress::with_style("Waiting", ProgressStyle::Ratio, gctx);
     progress.tick_now(0, max, "")?;
+    // Where the `.crate` file is downloaded from, once the index has it.
+    let mut download = None;
     let is_available = loop {
         {
             let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
@@ -254,16 +325,39 @@ fn wait_for_publish(
                     std::task::Poll::Pending => source.block_until_ready()?,
                 }
             };
-            if !summaries.is_empty() {
-                break true;
+            if let Some(summary) = summaries.first() {
+                if !opts.verify_download {
+                    break true;
+                }
+                if download.is_none() {
+                    download = Some(crate_download(
+                        gctx,
+                        source.replaced_source_id(),
+                        summary.package_id(),
+                        cksum,
+                    )?);
+                }
             }
         }
+        match &download {
+            Some(None) => break true,
+            Some(Some(download)) if is_downloadable(gctx, registry_src, download, cksum)? => {
+                break true
+            }
+            _ => {}
+        }
 
         let elapsed = now.elapsed();
         if timeout < elapsed {
-            gctx.shell().warn(format!(
-                "timed out waiting for `{short_pkg_description}` to be available in {source_description}",
-            ))?;
+            match &download {
+                Some(Some(download)) => gctx.shell().warn(format!(
+                    "timed out waiting for `{short_pkg_description}` to be downloadable from `{}`",
+                    download.url
+                ))?,
+                _ => gctx.shell().warn(format!(
+                    "timed out waiting for `{short_pkg_description}` to be available in {source_description}",
+                ))?,
+            }
             gctx.shell().note(
                 "the registry may have a backlog that is delaying making the \
                 crate available. The crate should be available soon.",
@@ -272,7 +366,7 @@ fn wait_for_publish(
         }
 
         progress.tick_now(elapsed.as_secs() as usize, max, "")?;
-        std::thread::sleep(sleep_time);
+        std::thread::sleep(opts.poll_interval);
     };
     if is_available {
         gctx.shell().status(
@@ -280,10 +374,149 @@ fn wait_for_publish(
             format!("{short_pkg_description} at {source_description}"),
         )?;
     }
+    if opts.format == RegistryMessageFormat::Json {
+        let name = pkg.name().as_str();
+        let version = pkg.version().to_string();
+        let source = registry_src.as_url().to_string();
+        let event = if is_available {
+            let mut verified = vec!["index"];
+            if opts.verify_download {
+                verified.push("download");
+            }
+            PublishEvent::PublishAvailable {
+                name,
+                version,
+                source,
+                verified,
+            }
+        } else {
+            PublishEvent::PublishTimeout {
+                name,
+                version,
+                source,
+            }
+        };
+        drop_println!(gctx, "{}", serde_json::to_string(&event)?);
+    }
 
     Ok(())
 }
 
+/// Where the `.crate` file of a published package is downloaded from.
+struct CrateDownload {
+    url: String,
+    /// Whether the registry requires a token for downloads.
+    auth_required: bool,
+}
+
+/// Finds where the `.crate` file of `pkg_id` is downloaded from, or `None` if
+/// `source_id` isn't a remote registry.
+fn crate_download(
+    gctx: &GlobalContext,
+    source_id: SourceId,
+    pkg_id: PackageId,
+    cksum: &str,
+) -> CargoResult<Option<CrateDownload>> {
+    if !source_id.is_remote_registry() {
+        return Ok(None);
+    }
+    let mut src = RegistrySource::remote(source_id, &HashSet::new(), gctx)?;
+    src.set_quiet(true);
+    let (config, url) = loop {
+        let config = match src.config()? {
+            Poll::Ready(config) => config,
+            Poll::Pending => None,
+        };
+        match (config, src.download_url(pkg_id, cksum)?) {
+            (Some(config), Poll::Ready(Some(url))) => break (config, url),
+            (_, Poll::Ready(None)) => return Ok(None),
+            _ => src
+                .block_until_ready()
+                .with_context(|| format!("failed to update {source_id}"))?,
+        }
+    };
+    Ok(Some(CrateDownload {
+        url,
+        auth_required: config.auth_required,
+    }))
+}
+
+/// Checks whether `download` serves the `.crate` file with the checksum
+/// `cksum`.
+///
+/// Network errors don't fail the publish, as the file is expected to be
+/// missing for a while.
+fn is_downloadable(
+    gctx: &GlobalContext,
+    registry_src: SourceId,
+    download: &CrateDownload,
+    cksum: &str,
+) -> CargoResult<bool> {
+    let url = &download.url;
+    let mut handle = registry_http_handle(gctx, registry_src)?;
+    handle.get(true)?;
+    handle.url(url)?;
+    handle.follow_location(true)?;
+    if download.auth_required {
+        let token = auth::auth_token(gctx, &registry_src, None, Operation::Read, vec![], false)?;
+        let mut headers = List::new();
+        headers.append(&format!("Authorization: {token}"))?;
+        handle.http_headers(headers)?;
+    }
+    let mut hasher = cargo_util::Sha256::new();
+    let result = {
+        let mut transfer = handle.transfer();
+        transfer.write_function(|data| {
+            hasher.update(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()
+    };
+    if let Err(e) = result {
+        tracing::debug!("failed to download `{url}`: {e}");
+        return Ok(false);
+    }
+    // `file://` URLs don't have status codes.
+    let code = handle.response_code()?;
+    Ok((code == 0 || code == 200) && hasher.finish_hex() == cksum)
+}
+
+/// The maximum time to spend retrying a publish the registry rate limited,
+/// from `publish.rate-limit-timeout`. Zero unless `-Zunstable-options`.
+fn rate_limit_timeout(gctx: &GlobalContext) -> CargoResult<Duration> {
+    const DEFAULT_TIMEOUT: u64 = 600;
+    if !gctx.cli_unstable().unstable_options {
+        return Ok(Duration::ZERO);
+    }
+    let timeout: Option<u64> = gctx.get("publish.rate-limit-timeout")?;
+    Ok(Duration::from_secs(timeout.unwrap_or(DEFAULT_TIMEOUT)))
+}
+
+/// Gets the delay requested by the `Retry-After` header of a response, given
+/// in seconds or as an HTTP date.
+fn retry_after(headers: &[String]) -> Option<Duration> {
+    let value = headers.iter().find_map(|header| {
+        let (name, value) = header.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("retry-after")
+            .then(|| value.trim())
+    })?;
+    if let Ok(secs) = value.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let secs = (date - OffsetDateTime::now_utc()).whole_seconds();
+    Some(Duration::from_secs(secs.max(0) as u64))
+}
+
+/// The delay before retrying a rate limited publish without a `Retry-After`
+/// header, doubling with each retry.
+fn rate_limit_backoff(retries: u32) -> Duration {
+    const INITIAL_SECS: u64 = 10;
+    const MAX_SECS: u64 = 300;
+    Duration::from_secs(min(INITIAL_SECS << min(retries, 5), MAX_SECS))
+}
+
 fn verify_dependencies(
     pkg: &Package,
     registry: &Registry,
@@ -445,31 +678,80 @@ fn transmit(
         None => BTreeMap::new(),
     };
 
-    let warnings = registry
-        .publish(
-            &NewCrate {
-                name: pkg.name().to_string(),
-                vers: pkg.version().to_string(),
-                deps,
-                features: string_features,
-                authors: authors.clone(),
-                description: description.clone(),
-                homepage: homepage.clone(),
-                documentation: documentation.clone(),
-                keywords: keywords.clone(),
-                categories: categories.clone(),
-                readme: readme_content,
-                readme_file: readme.clone(),
-                repository: repository.clone(),
-                license: license.clone(),
-                license_file: license_file.clone(),
-                badges: badges.clone(),
-                links: links.clone(),
-                rust_version,
-            },
-            tarball,
-        )
-        .with_context(|| format!("failed to publish to registry at {}", registry.host()))?;
+    let new_crate = NewCrate {
+        name: pkg.name().to_string(),
+        vers: pkg.version().to_string(),
+        deps,
+        features: string_features,
+        authors: authors.clone(),
+        description: description.clone(),
+        homepage: homepage.clone(),
+        documentation: documentation.clone(),
+        keywords: keywords.clone(),
+        categories: categories.clone(),
+        readme: readme_content,
+        readme_file: readme.clone(),
+        repository: repository.clone(),
+        license: license.clone(),
+        license_file: license_file.clone(),
+        badges: badges.clone(),
+        links: links.clone(),
+        rust_version,
+    };
+    let rate_limit_timeout = rate_limit_timeout(gctx)?;
+    let started = Instant::now();
+    let mut retries = 0;
+    let warnings = loop {
+        let e = match registry.publish(&new_crate, tarball) {
+            Ok(warnings) => break warnings,
+            Err(e) => e,
+        };
+        let delay = match &e {
+            crates_io::Error::Api {
+                code: 429, headers, ..
+            }
+            | crates_io::Error::Code {
+                code: 429, headers, ..
+            } if !rate_limit_timeout.is_zero() => {
+                let delay = retry_after(headers).unwrap_or_else(|| rate_limit_backoff(retries));
+                // A delay too large to add up only exceeds the timeout.
+                let retry_at = started.elapsed().checked_add(delay);
+                if retry_at.is_some_and(|retry_at| retry_at <= rate_limit_timeout) {
+                    Some(delay)
+                } else {
+                    gctx.shell().note(format!(
+                        "not retrying in {}s, as that exceeds `publish.rate-limit-timeout`",
+                        delay.as_secs()
+                    ))?;
+                    None
+                }
+            }
+            _ => None,
+        };
+        let Some(delay) = delay else {
+            return Err(anyhow::Error::from(e).context(format!(
+                "failed to publish to registry at {}",
+                registry.host()
+            )));
+        };
+        retries += 1;
+        gctx.shell().warn(format!(
+            "the registry rate limited publishing `{} v{}`, retrying in {}s",
+            pkg.name(),
+            pkg.version(),
+            delay.as_secs()
+        ))?;
+        let mut progress = Progress::with_style("Waiting", ProgressStyle::Ratio, gctx);
+        let waiting = Instant::now();
+        while waiting.elapsed() < delay {
+            progress.tick_now(
+                waiting.elapsed().as_secs() as usize,
+                delay.as_secs() as usize,
+                "",
+            )?;
+            std::thread::sleep(min(delay - waiting.elapsed(), Duration::from_secs(1)));
+        }
+    };
 
     if !warnings.invalid_categories.is_empty() {
         let msg = format!(
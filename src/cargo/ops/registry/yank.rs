@@ -7,14 +7,26 @@ use anyhow::bail;
 use anyhow::Context as _;
 use cargo_credential::Operation;
 use cargo_credential::Secret;
+use serde::Serialize;
 
 use crate::core::Workspace;
+use crate::drop_println;
 use crate::util::context::GlobalContext;
 use crate::util::errors::CargoResult;
 use crate::util::important_paths::find_root_manifest_for_wd;
 
+use super::RegistryMessageFormat;
 use super::RegistryOrIndex;
 
+/// The result of `cargo yank` with [`RegistryMessageFormat::Json`].
+#[derive(Serialize)]
+struct YankOutput<'a> {
+    #[serde(rename = "crate")]
+    krate: &'a str,
+    version: &'a str,
+    yanked: bool,
+}
+
 pub fn yank(
     gctx: &GlobalContext,
     krate: Option<String>,
@@ -22,6 +34,7 @@ pub fn yank(
     token: Option<Secret<String>>,
     reg_or_index: Option<RegistryOrIndex>,
     undo: bool,
+    format: RegistryMessageFormat,
 ) -> CargoResult<()> {
     let name = match krate {
         Some(name) => name,
@@ -71,5 +84,14 @@ pub fn yank(
             .with_context(|| format!("failed to yank from the registry at {}", registry.host()))?;
     }
 
+    if format == RegistryMessageFormat::Json {
+        let output = YankOutput {
+            krate: &name,
+            version: &version,
+            yanked: !undo,
+        };
+        drop_println!(gctx, "{}", serde_json::to_string(&output)?);
+    }
+
     Ok(())
 }
@@ -75,6 +75,7 @@ use crate::core::Workspace;
 use crate::ops;
 use crate::sources::RecursivePathSource;
 use crate::util::cache_lock::CacheLockMode;
+use crate::util::context::CargoResolverYanked;
 use crate::util::errors::CargoResult;
 use crate::util::CanonicalUrl;
 use anyhow::Context as _;
@@ -222,8 +223,10 @@ pub fn resolve_ws_with_opts<'gctx>(
 
     let pkg_set = get_resolved_packages(&resolved_with_overrides, registry)?;
 
+    check_yanked_policy(ws, &pkg_set, &resolved_with_overrides)?;
+
     let member_ids = ws
-        .members_with_features(specs, cli_features)?
+        .members_with_features_to_resolve(specs, cli_features)?
         .into_iter()
         .map(|(p, _fts)| p.package_id())
         .collect::<Vec<_>>();
@@ -266,6 +269,57 @@ pub fn resolve_ws_with_opts<'gctx>(
     })
 }
 
+/// Enforces `resolver.yanked` for the packages about to be built.
+fn check_yanked_policy(
+    ws: &Workspace<'_>,
+    pkg_set: &PackageSet<'_>,
+    resolve: &Resolve,
+) -> CargoResult<()> {
+    let policy = ws.resolve_yanked();
+    if policy == CargoResolverYanked::Allow {
+        return Ok(());
+    }
+    // The index was just consulted while resolving, no need to fetch it again.
+    let invalidate_cache = false;
+    let yanked = ops::yanked_packages(ws.gctx(), pkg_set, resolve, invalidate_cache)?;
+    if yanked.is_empty() {
+        return Ok(());
+    }
+    match policy {
+        CargoResolverYanked::Allow => {}
+        CargoResolverYanked::Warn => {
+            for pkg_id in yanked {
+                ws.gctx().shell().warn(format!(
+                    "package `{}` in Cargo.lock is yanked in registry `{}`, \
+                     consider running `cargo update -p {}`",
+                    pkg_id,
+                    pkg_id.source_id().display_registry_name(),
+                    pkg_id.name(),
+                ))?;
+            }
+        }
+        CargoResolverYanked::Deny => {
+            let list = yanked
+                .iter()
+                .map(|pkg_id| {
+                    format!(
+                        "  {} (registry `{}`)",
+                        pkg_id,
+                        pkg_id.source_id().display_registry_name()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            anyhow::bail!(
+                "Cargo.lock contains yanked packages, which is denied by `resolver.yanked`:\n\
+                 {list}\n\n\
+                 help: run `cargo update` to select versions that are not yanked"
+            );
+        }
+    }
+    Ok(())
+}
+
 #[tracing::instrument(skip_all)]
 fn resolve_with_registry<'gctx>(
     ws: &Workspace<'gctx>,
@@ -286,6 +340,7 @@ fn resolve_with_registry<'gctx>(
 
     let print = if !ws.is_ephemeral() && ws.require_optional_deps() {
         if !dry_run {
+            ops::advisories::check_new_packages(ws, prev.as_ref(), &resolve)?;
             ops::write_pkg_lockfile(ws, &mut resolve)?
         } else {
             true
@@ -346,15 +401,22 @@ pub fn resolve_with_previous<'gctx>(
         registry.add_sources(Some(member.package_id().source_id()))?;
     }
 
-    // Try to keep all from previous resolve if no instruction given.
+    // Try to keep all from previous resolve if no instruction given, except
+    // the packages the workspace lets float.
     let keep_previous = keep_previous.unwrap_or(&|_| true);
+    let floating = ws.floating_packages();
+    let keep_previous =
+        &|p: &PackageId| keep_previous(p) && !floating.iter().any(|spec| spec.matches(*p));
 
     // While registering patches, we will record preferences for particular versions
     // of various packages.
     let mut version_prefs = VersionPreferences::default();
-    if ws.gctx().cli_unstable().minimal_versions {
+    if ws.resolve_minimal_versions() {
         version_prefs.version_ordering(VersionOrdering::MinimumVersionsFirst)
     }
+    if ws.resolve_direct_minimal_versions() {
+        version_prefs.direct_version_ordering(Some(VersionOrdering::MinimumVersionsFirst))
+    }
     if ws.resolve_honors_rust_version() {
         let rust_version = if let Some(ver) = ws.rust_version() {
             ver.clone().into_partial()
@@ -389,6 +451,11 @@ pub fn resolve_with_previous<'gctx>(
         for id in r.iter().filter(keep) {
             debug!("attempting to prefer {}", id);
             version_prefs.prefer_package_id(id);
+            // Versions locked from a remapped registry are kept if the
+            // registry being resolved against has them too.
+            if let Some(remapped) = registry.remapped_registry(id.source_id()) {
+                version_prefs.prefer_package_id(id.with_source_id(remapped));
+            }
         }
     }
 
@@ -397,7 +464,7 @@ pub fn resolve_with_previous<'gctx>(
     }
 
     let summaries: Vec<(Summary, ResolveOpts)> = ws
-        .members_with_features(specs, cli_features)?
+        .members_with_features_to_resolve(specs, cli_features)?
         .into_iter()
         .map(|(member, features)| {
             let summary = registry.lock(member.summary().clone());
@@ -0,0 +1,341 @@
+//! Output of the graph `cargo tree` draws, as JSON or in the languages of
+//! graph renderers.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use super::format::{self, Pattern};
+use super::{EdgeKind, Graph, Node, TreeOptions};
+use crate::core::compiler::CompileKind;
+use crate::core::dependency::DepKind;
+use crate::core::{PackageId, PackageIdSpec, PackageIdSpecQuery};
+use crate::drop_print;
+use crate::util::interning::InternedString;
+use crate::util::{CargoResult, GlobalContext};
+
+/// The order dependencies are shown in, like in the tree.
+const KINDS: [EdgeKind; 4] = [
+    EdgeKind::Dep(DepKind::Normal),
+    EdgeKind::Dep(DepKind::Build),
+    EdgeKind::Dep(DepKind::Development),
+    EdgeKind::Feature,
+];
+
+/// The part of the graph the tree would show.
+struct Subgraph {
+    /// Indexes into the graph of the nodes, in the order the tree would first
+    /// show them. Nodes are numbered by their position here.
+    nodes: Vec<usize>,
+    /// Maps the index of a node in the graph to its number.
+    numbering: HashMap<usize, usize>,
+    /// Edges as `(from, kind, to)`, with indexes into the graph.
+    edges: Vec<(usize, EdgeKind, usize)>,
+}
+
+impl Subgraph {
+    /// Walks the graph from `roots`, leaving out pruned packages and whatever
+    /// is deeper than `--depth`.
+    fn new(
+        opts: &TreeOptions,
+        roots: &[usize],
+        pkgs_to_prune: &[PackageIdSpec],
+        graph: &Graph<'_>,
+    ) -> Subgraph {
+        let mut subgraph = Subgraph {
+            nodes: Vec::new(),
+            numbering: HashMap::new(),
+            edges: Vec::new(),
+        };
+        let mut stack: Vec<(usize, u32)> = roots.iter().rev().map(|root| (*root, 0)).collect();
+        let mut visited = HashSet::new();
+        while let Some((index, depth)) = stack.pop() {
+            subgraph.number(index);
+            if !visited.insert(index) || depth >= opts.max_display_depth {
+                continue;
+            }
+            let mut next = Vec::new();
+            for kind in KINDS {
+                for dep in graph.connected_nodes(index, &kind) {
+                    if let Node::Package { package_id, .. } = graph.node(dep) {
+                        if pkgs_to_prune.iter().any(|spec| spec.matches(*package_id)) {
+                            continue;
+                        }
+                    }
+                    subgraph.edges.push((index, kind, dep));
+                    next.push((dep, depth + 1));
+                }
+            }
+            stack.extend(next.into_iter().rev());
+        }
+        // The package of a feature isn't necessarily reachable, like when
+        // inverted.
+        let mut i = 0;
+        while i < subgraph.nodes.len() {
+            if let Node::Feature { node_index, .. } = graph.node(subgraph.nodes[i]) {
+                subgraph.number(*node_index);
+            }
+            i += 1;
+        }
+        subgraph
+    }
+
+    fn number(&mut self, index: usize) -> usize {
+        *self.numbering.entry(index).or_insert_with(|| {
+            self.nodes.push(index);
+            self.nodes.len() - 1
+        })
+    }
+
+    /// Returns the numbers of the package nodes whose package is also shown
+    /// with another version or source.
+    fn duplicates(&self, graph: &Graph<'_>) -> BTreeSet<usize> {
+        let mut by_name: HashMap<InternedString, HashSet<PackageId>> = HashMap::new();
+        for index in &self.nodes {
+            if let Node::Package { package_id, .. } = graph.node(*index) {
+                by_name
+                    .entry(package_id.name())
+                    .or_default()
+                    .insert(*package_id);
+            }
+        }
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, index)| match graph.node(**index) {
+                Node::Package { package_id, .. } => by_name[&package_id.name()].len() > 1,
+                Node::Feature { .. } => false,
+            })
+            .map(|(number, _)| number)
+            .collect()
+    }
+}
+
+fn edge_kind_name(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Dep(DepKind::Normal) => "normal",
+        EdgeKind::Dep(DepKind::Build) => "build",
+        EdgeKind::Dep(DepKind::Development) => "dev",
+        EdgeKind::Feature => "feature",
+    }
+}
+
+/// A node of the graph printed by `--output-format json`.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum SerializedNode {
+    Package {
+        id: PackageIdSpec,
+        name: InternedString,
+        version: String,
+        source: Option<String>,
+        source_kind: &'static str,
+        license: Option<String>,
+        rust_version: Option<String>,
+        features: Vec<InternedString>,
+        /// `host`, or the target the package is built for.
+        target: String,
+        proc_macro: bool,
+    },
+    Feature {
+        name: InternedString,
+        /// The package node the feature is of.
+        package: usize,
+        command_line: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct SerializedEdge {
+    from: usize,
+    to: usize,
+    kind: &'static str,
+    /// The platforms the dependency is limited to, empty for every platform.
+    targets: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SerializedGraph {
+    roots: Vec<usize>,
+    nodes: Vec<SerializedNode>,
+    edges: Vec<SerializedEdge>,
+}
+
+/// Prints the part of the graph reachable from the given roots as JSON, for
+/// `--output-format json`.
+///
+/// Nodes are numbered in the order the text output would first show them.
+pub fn print_json(
+    gctx: &GlobalContext,
+    opts: &TreeOptions,
+    roots: &[usize],
+    pkgs_to_prune: &[PackageIdSpec],
+    graph: &Graph<'_>,
+) -> CargoResult<()> {
+    let subgraph = Subgraph::new(opts, roots, pkgs_to_prune, graph);
+
+    let nodes = subgraph
+        .nodes
+        .iter()
+        .map(|index| match graph.node(*index) {
+            Node::Package {
+                package_id,
+                features,
+                kind,
+            } => {
+                let package = graph.package_for_id(*package_id);
+                let metadata = package.manifest().metadata();
+                SerializedNode::Package {
+                    id: package_id.to_spec(),
+                    name: package_id.name(),
+                    version: package_id.version().to_string(),
+                    source: package_id.source_id().serialized(),
+                    source_kind: format::source_kind(package_id.source_id()),
+                    license: metadata.license.clone(),
+                    rust_version: package.rust_version().map(|v| v.to_string()),
+                    features: features.clone(),
+                    target: match kind {
+                        CompileKind::Host => "host".to_string(),
+                        CompileKind::Target(target) => target.short_name().to_string(),
+                    },
+                    proc_macro: package.proc_macro(),
+                }
+            }
+            Node::Feature { node_index, name } => SerializedNode::Feature {
+                name: *name,
+                package: subgraph.numbering[node_index],
+                command_line: graph.is_cli_feature(*index),
+            },
+        })
+        .collect();
+    let edges = subgraph
+        .edges
+        .iter()
+        .map(|(from, kind, to)| SerializedEdge {
+            from: subgraph.numbering[from],
+            to: subgraph.numbering[to],
+            kind: edge_kind_name(*kind),
+            targets: graph.edge_targets(*from, *kind, *to),
+        })
+        .collect();
+
+    gctx.shell().print_json(&SerializedGraph {
+        roots: roots.iter().map(|root| subgraph.numbering[root]).collect(),
+        nodes,
+        edges,
+    })
+}
+
+/// Prints the part of the graph reachable from the given roots in the DOT
+/// language of Graphviz, for `--graph dot`.
+///
+/// Nodes are labeled with `format`. For styling, build and dev dependencies
+/// are dashed edges with the `build` and `dev` classes, and packages shown
+/// with several versions or sources are filled nodes with the `duplicate`
+/// class.
+pub fn print_dot(
+    gctx: &GlobalContext,
+    opts: &TreeOptions,
+    roots: &[usize],
+    pkgs_to_prune: &[PackageIdSpec],
+    graph: &Graph<'_>,
+    format: &Pattern,
+) -> CargoResult<()> {
+    let subgraph = Subgraph::new(opts, roots, pkgs_to_prune, graph);
+    let duplicates = subgraph.duplicates(graph);
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut out = String::from("digraph {\n");
+    for (number, index) in subgraph.nodes.iter().enumerate() {
+        let mut attrs = vec![format!(
+            "label={}",
+            quote(&format.display(graph, *index).to_string())
+        )];
+        match graph.node(*index) {
+            Node::Package { .. } => attrs.push("shape=box".to_string()),
+            Node::Feature { .. } => attrs.push("shape=ellipse".to_string()),
+        }
+        if duplicates.contains(&number) {
+            attrs.push("class=\"duplicate\"".to_string());
+            attrs.push("style=filled".to_string());
+            attrs.push("fillcolor=\"#ffd7d7\"".to_string());
+        }
+        writeln!(out, "    n{} [{}];", number, attrs.join(", "))?;
+    }
+    for (from, kind, to) in &subgraph.edges {
+        let mut attrs = Vec::new();
+        if let EdgeKind::Dep(DepKind::Build | DepKind::Development) = kind {
+            attrs.push(format!("class=\"{}\"", edge_kind_name(*kind)));
+            attrs.push("style=dashed".to_string());
+        }
+        let targets = graph.edge_targets(*from, *kind, *to);
+        if !targets.is_empty() {
+            attrs.push(format!("label={}", quote(&targets.join(", "))));
+        }
+        let attrs = if attrs.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attrs.join(", "))
+        };
+        writeln!(
+            out,
+            "    n{} -> n{}{};",
+            subgraph.numbering[from], subgraph.numbering[to], attrs
+        )?;
+    }
+    out.push_str("}\n");
+    drop_print!(gctx, "{}", out);
+    Ok(())
+}
+
+/// Prints the part of the graph reachable from the given roots as a Mermaid
+/// flowchart, for `--graph mermaid`.
+///
+/// Styled like [`print_dot`], with dotted links for build and dev
+/// dependencies.
+pub fn print_mermaid(
+    gctx: &GlobalContext,
+    opts: &TreeOptions,
+    roots: &[usize],
+    pkgs_to_prune: &[PackageIdSpec],
+    graph: &Graph<'_>,
+    format: &Pattern,
+) -> CargoResult<()> {
+    let subgraph = Subgraph::new(opts, roots, pkgs_to_prune, graph);
+    let duplicates = subgraph.duplicates(graph);
+    let quote = |s: &str| format!("\"{}\"", s.replace('"', "#quot;"));
+
+    let mut out = String::from("flowchart TD\n");
+    for (number, index) in subgraph.nodes.iter().enumerate() {
+        let label = quote(&format.display(graph, *index).to_string());
+        match graph.node(*index) {
+            Node::Package { .. } => writeln!(out, "    n{number}[{label}]")?,
+            Node::Feature { .. } => writeln!(out, "    n{number}([{label}])")?,
+        }
+    }
+    for (from, kind, to) in &subgraph.edges {
+        let link = match kind {
+            EdgeKind::Dep(DepKind::Build | DepKind::Development) => "-.->",
+            _ => "-->",
+        };
+        let targets = graph.edge_targets(*from, *kind, *to);
+        let label = if targets.is_empty() {
+            String::new()
+        } else {
+            format!("|{}|", quote(&targets.join(", ")))
+        };
+        writeln!(
+            out,
+            "    n{} {}{} n{}",
+            subgraph.numbering[from], link, label, subgraph.numbering[to]
+        )?;
+    }
+    if !duplicates.is_empty() {
+        let numbers: Vec<_> = duplicates.iter().map(|n| format!("n{n}")).collect();
+        out.push_str("    classDef duplicate fill:#ffd7d7\n");
+        writeln!(out, "    class {} duplicate", numbers.join(","))?;
+    }
+    drop_print!(gctx, "{}", out);
+    Ok(())
+}
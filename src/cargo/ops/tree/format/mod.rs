@@ -4,6 +4,7 @@ use anyhow::{bail, Error};
 
 use self::parse::{Parser, RawChunk};
 use super::{Graph, Node};
+use crate::core::{SourceId, SourceKind};
 
 mod parse;
 
@@ -14,15 +15,27 @@ enum Chunk {
     Repository,
     Features,
     LibName,
+    RustVersion,
+    SourceKind,
 }
 
 pub struct Pattern(Vec<Chunk>);
 
 impl Pattern {
-    pub fn new(format: &str) -> Result<Pattern, Error> {
+    /// Parses `format`, with the unstable placeholders only allowed if
+    /// `unstable` is set.
+    pub fn new(format: &str, unstable: bool) -> Result<Pattern, Error> {
         let mut chunks = vec![];
 
         for raw in Parser::new(format) {
+            if let RawChunk::Argument(a @ ("rv" | "s")) = raw {
+                if !unstable {
+                    bail!(
+                        "the `{{{a}}}` placeholder is unstable, \
+                         pass `-Z unstable-options` to enable it"
+                    );
+                }
+            }
             let chunk = match raw {
                 RawChunk::Text(text) => Chunk::Raw(text.to_owned()),
                 RawChunk::Argument("p") => Chunk::Package,
@@ -30,6 +43,8 @@ impl Pattern {
                 RawChunk::Argument("r") => Chunk::Repository,
                 RawChunk::Argument("f") => Chunk::Features,
                 RawChunk::Argument("lib") => Chunk::LibName,
+                RawChunk::Argument("rv") => Chunk::RustVersion,
+                RawChunk::Argument("s") => Chunk::SourceKind,
                 RawChunk::Argument(a) => {
                     bail!("unsupported pattern `{}`", a);
                 }
@@ -50,6 +65,17 @@ impl Pattern {
     }
 }
 
+/// The kind of source a package comes from, as `{s}` displays it.
+pub fn source_kind(source_id: SourceId) -> &'static str {
+    match source_id.kind() {
+        SourceKind::Path => "path",
+        SourceKind::Git(_) => "git",
+        SourceKind::Registry | SourceKind::SparseRegistry => "registry",
+        SourceKind::LocalRegistry => "local-registry",
+        SourceKind::Directory => "directory",
+    }
+}
+
 pub struct Display<'a> {
     pattern: &'a Pattern,
     graph: &'a Graph<'a>,
@@ -111,6 +137,14 @@ impl<'a> fmt::Display for Display<'a> {
                                 write!(fmt, "{}", target.crate_name())?;
                             }
                         }
+                        Chunk::RustVersion => {
+                            if let Some(rust_version) = package.rust_version() {
+                                write!(fmt, "{}", rust_version)?;
+                            }
+                        }
+                        Chunk::SourceKind => {
+                            write!(fmt, "{}", source_kind(package.package_id().source_id()))?;
+                        }
                     }
                 }
             }
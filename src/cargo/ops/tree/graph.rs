@@ -8,7 +8,7 @@ use crate::core::resolver::Resolve;
 use crate::core::{FeatureMap, FeatureValue, Package, PackageId, PackageIdSpec, Workspace};
 use crate::util::interning::InternedString;
 use crate::util::CargoResult;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Node {
@@ -58,6 +58,15 @@ impl Edges {
     }
 }
 
+/// The features requested on a package by one of its dependents.
+pub struct FeatureRequest {
+    /// Index of the package node that made the request, or `None` if the
+    /// features were requested on the command-line.
+    pub from: Option<usize>,
+    /// The requested features, sorted.
+    pub features: Vec<InternedString>,
+}
+
 /// A graph of dependencies.
 pub struct Graph<'a> {
     nodes: Vec<Node>,
@@ -79,6 +88,9 @@ pub struct Graph<'a> {
     /// Key is the index of a package node, value is a map of dep_name to a
     /// set of `(pkg_node_index, is_optional)`.
     dep_name_map: HashMap<usize, HashMap<InternedString, HashSet<(usize, bool)>>>,
+    /// The platforms each dependency edge is limited to, keyed by
+    /// `(from, kind, to)`. `None` is a dependency for every platform.
+    edge_targets: HashMap<(usize, EdgeKind, usize), BTreeSet<Option<String>>>,
 }
 
 impl<'a> Graph<'a> {
@@ -90,6 +102,7 @@ impl<'a> Graph<'a> {
             package_map,
             cli_features: HashSet::new(),
             dep_name_map: HashMap::new(),
+            edge_targets: HashMap::new(),
         }
     }
 
@@ -116,6 +129,24 @@ impl<'a> Graph<'a> {
         }
     }
 
+    /// Returns the `cfg` expressions or target triples the edge is limited
+    /// to, or nothing if it applies to every platform.
+    pub fn edge_targets(&self, from: usize, kind: EdgeKind, to: usize) -> Vec<String> {
+        match self.edge_targets.get(&(from, kind, to)) {
+            Some(targets) if !targets.contains(&None) => {
+                targets.iter().flatten().cloned().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn add_edge_target(&mut self, from: usize, kind: EdgeKind, to: usize, target: Option<String>) {
+        self.edge_targets
+            .entry((from, kind, to))
+            .or_default()
+            .insert(target);
+    }
+
     /// Returns `true` if the given node has any outgoing edges.
     pub fn has_outgoing_edges(&self, index: usize) -> bool {
         !self.edges[index].0.is_empty()
@@ -188,6 +219,12 @@ impl<'a> Graph<'a> {
                 for edge_index in edge_indexes {
                     let new_to_index = visit(graph, new_graph, remap, *edge_index);
                     new_graph.edges[new_from].add_edge(*edge_kind, new_to_index);
+                    if let Some(targets) = graph.edge_targets.get(&(index, *edge_kind, *edge_index))
+                    {
+                        new_graph
+                            .edge_targets
+                            .insert((new_from, *edge_kind, new_to_index), targets.clone());
+                    }
                 }
             }
             new_from
@@ -201,6 +238,81 @@ impl<'a> Graph<'a> {
         new_graph
     }
 
+    /// Removes all edges that are not part of a shortest path from one of
+    /// the `roots` to one of the `targets`.
+    ///
+    /// Each target is considered separately, so every target keeps the
+    /// shortest path(s) to its nearest root, even if another target is
+    /// closer. Nodes are not removed, only the edges between them.
+    pub fn retain_shortest_paths(&mut self, roots: &[usize], targets: &[usize]) {
+        // Reverse adjacency list, for walking from a target back to the roots.
+        let mut incoming = vec![Vec::new(); self.nodes.len()];
+        for (from_idx, node_edges) in self.edges.iter().enumerate() {
+            for edges in node_edges.0.values() {
+                for edge_idx in edges {
+                    incoming[*edge_idx].push(from_idx);
+                }
+            }
+        }
+        let from_roots = self.distances(roots.iter().copied(), |index| {
+            self.edges[index].0.values().flatten().copied().collect()
+        });
+
+        let mut keep = HashSet::new();
+        for target in targets {
+            let to_target =
+                self.distances(std::iter::once(*target), |index| incoming[index].clone());
+            let Some(shortest) = roots.iter().filter_map(|root| to_target[*root]).min() else {
+                continue;
+            };
+            for (from_idx, node_edges) in self.edges.iter().enumerate() {
+                let Some(from_dist) = from_roots[from_idx] else {
+                    continue;
+                };
+                for edge_idx in node_edges.0.values().flatten() {
+                    if to_target[*edge_idx].map(|d| from_dist + 1 + d) == Some(shortest) {
+                        keep.insert((from_idx, *edge_idx));
+                    }
+                }
+            }
+        }
+
+        for (from_idx, node_edges) in self.edges.iter_mut().enumerate() {
+            for edges in node_edges.0.values_mut() {
+                edges.retain(|edge_idx| keep.contains(&(from_idx, *edge_idx)));
+            }
+            node_edges.0.retain(|_kind, edges| !edges.is_empty());
+        }
+    }
+
+    /// Computes the number of edges from the nearest of `starts` to every
+    /// node, using `next` to find the neighbors of a node. `None` means the
+    /// node is not reachable.
+    fn distances(
+        &self,
+        starts: impl Iterator<Item = usize>,
+        next: impl Fn(usize) -> Vec<usize>,
+    ) -> Vec<Option<usize>> {
+        let mut dist = vec![None; self.nodes.len()];
+        let mut queue = VecDeque::new();
+        for start in starts {
+            if dist[start].is_none() {
+                dist[start] = Some(0);
+                queue.push_back(start);
+            }
+        }
+        while let Some(index) = queue.pop_front() {
+            let d = dist[index].unwrap() + 1;
+            for next_index in next(index) {
+                if dist[next_index].is_none() {
+                    dist[next_index] = Some(d);
+                    queue.push_back(next_index);
+                }
+            }
+        }
+        dist
+    }
+
     /// Inverts the direction of all edges.
     pub fn invert(&mut self) {
         let mut new_edges = vec![Edges::new(); self.edges.len()];
@@ -212,6 +324,10 @@ impl<'a> Graph<'a> {
             }
         }
         self.edges = new_edges;
+        self.edge_targets = std::mem::take(&mut self.edge_targets)
+            .into_iter()
+            .map(|((from, kind, to), targets)| ((to, kind, from), targets))
+            .collect();
     }
 
     /// Returns a list of nodes that are considered "duplicates" (same package
@@ -264,6 +380,97 @@ impl<'a> Graph<'a> {
         dupes.sort_unstable();
         dupes.into_iter().map(|(_node, i)| i).collect()
     }
+
+    /// Returns, for every package node reachable from `roots` whose
+    /// dependents requested differing sets of features, the index of the
+    /// package node along with what each dependent requested.
+    ///
+    /// The graph must have been built with features, and must not be
+    /// inverted.
+    pub fn find_feature_requests(&self, roots: &[usize]) -> Vec<(usize, Vec<FeatureRequest>)> {
+        let reachable = self.distances(roots.iter().copied(), |index| {
+            self.edges[index].0.values().flatten().copied().collect()
+        });
+        // Map of package index to a map of requester to requested features.
+        let mut requests: HashMap<usize, HashMap<Option<usize>, BTreeSet<InternedString>>> =
+            HashMap::new();
+        for (from_idx, node_edges) in self.edges.iter().enumerate() {
+            if reachable[from_idx].is_none() {
+                continue;
+            }
+            let requester = match self.nodes[from_idx] {
+                Node::Package { .. } => from_idx,
+                // A feature of the dependent enabling `dep/feat`.
+                Node::Feature { node_index, .. } => node_index,
+            };
+            for (kind, edges) in &node_edges.0 {
+                for edge_idx in edges {
+                    match self.nodes[*edge_idx] {
+                        // A dependency declared without any features.
+                        Node::Package { .. } if *kind != EdgeKind::Feature => {
+                            requests
+                                .entry(*edge_idx)
+                                .or_default()
+                                .entry(Some(requester))
+                                .or_default();
+                        }
+                        Node::Package { .. } => {}
+                        Node::Feature { node_index, name } => {
+                            // Features enabling other features of the same
+                            // package are not a request from a dependent.
+                            if node_index == requester {
+                                continue;
+                            }
+                            requests
+                                .entry(node_index)
+                                .or_default()
+                                .entry(Some(requester))
+                                .or_default()
+                                .insert(name);
+                        }
+                    }
+                }
+            }
+        }
+        for cli_feature in &self.cli_features {
+            if let Node::Feature { node_index, name } = self.nodes[*cli_feature] {
+                if reachable[node_index].is_none() {
+                    continue;
+                }
+                requests
+                    .entry(node_index)
+                    .or_default()
+                    .entry(None)
+                    .or_default()
+                    .insert(name);
+            }
+        }
+
+        let mut result: Vec<_> = requests
+            .into_iter()
+            .filter(|(_index, by_requester)| {
+                by_requester.values().collect::<HashSet<_>>().len() > 1
+            })
+            .map(|(index, by_requester)| {
+                let mut by_requester: Vec<_> = by_requester
+                    .into_iter()
+                    .map(|(from, features)| FeatureRequest {
+                        from,
+                        features: features.into_iter().collect(),
+                    })
+                    .collect();
+                by_requester.sort_unstable_by(|a, b| {
+                    let a = a.from.map(|i| &self.nodes[i]);
+                    let b = b.from.map(|i| &self.nodes[i]);
+                    a.cmp(&b)
+                });
+                (index, by_requester)
+            })
+            .collect();
+        // For consistent output.
+        result.sort_unstable_by(|a, b| self.nodes[a.0].cmp(&self.nodes[b.0]));
+        result
+    }
 }
 
 /// Builds the graph.
@@ -283,8 +490,8 @@ pub fn build<'a>(
     members_with_features.sort_unstable_by_key(|e| e.0.package_id());
     for (member, cli_features) in members_with_features {
         let member_id = member.package_id();
-        let features_for = FeaturesFor::from_for_host(member.proc_macro());
         for kind in requested_kinds {
+            let features_for = FeaturesFor::from_for_host_and_kind(member.proc_macro(), *kind);
             let member_index = add_pkg(
                 &mut graph,
                 resolve,
@@ -325,7 +532,9 @@ fn add_pkg(
     let node_features = resolved_features.activated_features(package_id, features_for);
     let node_kind = match features_for {
         FeaturesFor::HostDep => CompileKind::Host,
-        FeaturesFor::ArtifactDep(target) => CompileKind::Target(target),
+        FeaturesFor::ArtifactDep(target) | FeaturesFor::TargetDep(target) => {
+            CompileKind::Target(target)
+        }
         FeaturesFor::NormalOrDev => requested_kind,
     };
     let node = Node::Package {
@@ -391,6 +600,8 @@ fn add_pkg(
         let dep_pkg = graph.package_map[&dep_id];
 
         for dep in deps {
+            let dep_kind = EdgeKind::Dep(dep.kind());
+            let dep_target = dep.platform().map(|p| p.to_string());
             let dep_features_for = if dep.is_build() || dep_pkg.proc_macro() {
                 FeaturesFor::HostDep
             } else {
@@ -412,30 +623,22 @@ fn add_pkg(
                     .entry(dep.name_in_toml())
                     .or_default()
                     .insert((dep_index, dep.is_optional()));
-                if dep.uses_default_features() {
-                    add_feature(
-                        graph,
-                        InternedString::new("default"),
-                        Some(from_index),
-                        dep_index,
-                        EdgeKind::Dep(dep.kind()),
-                    );
-                }
-                for feature in dep.features().iter() {
-                    add_feature(
-                        graph,
-                        *feature,
-                        Some(from_index),
-                        dep_index,
-                        EdgeKind::Dep(dep.kind()),
-                    );
+                let default = dep
+                    .uses_default_features()
+                    .then(|| InternedString::new("default"));
+                for feature in default.iter().chain(dep.features()) {
+                    let feature_index =
+                        add_feature(graph, *feature, Some(from_index), dep_index, dep_kind).1;
+                    graph.add_edge_target(from_index, dep_kind, feature_index, dep_target.clone());
                 }
                 if !dep.uses_default_features() && dep.features().is_empty() {
                     // No features, use a direct connection.
-                    graph.edges[from_index].add_edge(EdgeKind::Dep(dep.kind()), dep_index);
+                    graph.edges[from_index].add_edge(dep_kind, dep_index);
+                    graph.add_edge_target(from_index, dep_kind, dep_index, dep_target);
                 }
             } else {
-                graph.edges[from_index].add_edge(EdgeKind::Dep(dep.kind()), dep_index);
+                graph.edges[from_index].add_edge(dep_kind, dep_index);
+                graph.add_edge_target(from_index, dep_kind, dep_index, dep_target);
             }
         }
     }
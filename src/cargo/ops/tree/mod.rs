@@ -6,6 +6,7 @@ use crate::core::dependency::DepKind;
 use crate::core::resolver::{features::CliFeatures, ForceAllTargets, HasDevUnits};
 use crate::core::{Package, PackageId, PackageIdSpec, PackageIdSpecQuery, Workspace};
 use crate::ops::{self, Packages};
+use crate::util::interning::InternedString;
 use crate::util::{CargoResult, GlobalContext};
 use crate::{drop_print, drop_println};
 use anyhow::Context as _;
@@ -13,10 +14,11 @@ use graph::Graph;
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
+mod export;
 mod format;
 mod graph;
 
-pub use {graph::EdgeKind, graph::Node};
+pub use {graph::EdgeKind, graph::FeatureRequest, graph::Node};
 
 pub struct TreeOptions {
     pub cli_features: CliFeatures,
@@ -27,6 +29,9 @@ pub struct TreeOptions {
     /// The dependency kinds to display.
     pub edge_kinds: HashSet<EdgeKind>,
     pub invert: Vec<String>,
+    /// The packages to explain, by showing only the shortest path(s) from
+    /// the roots to each of them. Implies `invert`.
+    pub why: Vec<String>,
     /// The packages to prune from the display of the dependency tree.
     pub pkgs_to_prune: Vec<String>,
     /// The style of prefix for each line.
@@ -39,6 +44,10 @@ pub struct TreeOptions {
     /// appear with different versions, and report if any where found. Implies
     /// `invert`.
     pub duplicates: bool,
+    /// If `true`, run in a special mode where it will scan for packages that
+    /// had differing sets of features requested by their dependents, and
+    /// report what each dependent requested. Implies `graph_features`.
+    pub duplicates_features: bool,
     /// A format string indicating how each package should be displayed.
     pub format: String,
     /// Includes features in the tree as separate nodes.
@@ -47,6 +56,19 @@ pub struct TreeOptions {
     pub max_display_depth: u32,
     /// Excludes proc-macro dependencies.
     pub no_proc_macro: bool,
+    pub output_format: OutputFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The tree, with each package displayed with `format`.
+    Text,
+    /// The graph the tree is drawn from, as JSON.
+    Json,
+    /// The graph in the DOT language of Graphviz.
+    Dot,
+    /// The graph as a Mermaid flowchart.
+    Mermaid,
 }
 
 #[derive(PartialEq)]
@@ -161,10 +183,15 @@ pub fn build_and_print(ws: &Workspace<'_>, opts: &TreeOptions) -> CargoResult<()
         opts,
     )?;
 
-    let root_specs = if opts.invert.is_empty() {
-        specs
+    let inverted_specs = if opts.why.is_empty() {
+        &opts.invert
     } else {
-        opts.invert
+        &opts.why
+    };
+    let root_specs = if inverted_specs.is_empty() {
+        specs.clone()
+    } else {
+        inverted_specs
             .iter()
             .map(|p| PackageIdSpec::parse(p))
             .collect::<Result<Vec<PackageIdSpec>, _>>()?
@@ -172,6 +199,25 @@ pub fn build_and_print(ws: &Workspace<'_>, opts: &TreeOptions) -> CargoResult<()
     let root_ids = ws_resolve.targeted_resolve.specs_to_ids(&root_specs)?;
     let root_indexes = graph.indexes_from_ids(&root_ids);
 
+    if !opts.why.is_empty() {
+        let member_ids = ws_resolve.targeted_resolve.specs_to_ids(&specs)?;
+        let member_indexes = graph.indexes_from_ids(&member_ids);
+        graph.retain_shortest_paths(&member_indexes, &root_indexes);
+    }
+
+    if opts.duplicates_features {
+        // `--duplicates-features -p foo` will only look within foo's subtree
+        let requests = graph.find_feature_requests(&root_indexes);
+        if requests.is_empty() {
+            ws.gctx()
+                .shell()
+                .warn("no packages with differing feature requests found")?;
+        } else {
+            print_feature_requests(ws.gctx(), opts, &requests, &graph)?;
+        }
+        return Ok(());
+    }
+
     let root_indexes = if opts.duplicates {
         // `-d -p foo` will only show duplicates within foo's subtree
         graph = graph.from_reachable(root_indexes.as_slice());
@@ -180,7 +226,7 @@ pub fn build_and_print(ws: &Workspace<'_>, opts: &TreeOptions) -> CargoResult<()
         root_indexes
     };
 
-    if !opts.invert.is_empty() || opts.duplicates {
+    if !inverted_specs.is_empty() || opts.duplicates {
         graph.invert();
     }
 
@@ -196,6 +242,33 @@ pub fn build_and_print(ws: &Workspace<'_>, opts: &TreeOptions) -> CargoResult<()
         })
         .collect::<CargoResult<Vec<PackageIdSpec>>>()?;
 
+    let gctx = ws.gctx();
+    let format = || {
+        Pattern::new(&opts.format, gctx.cli_unstable().unstable_options)
+            .with_context(|| format!("tree format `{}` not valid", opts.format))
+    };
+    match opts.output_format {
+        OutputFormat::Json => {
+            return export::print_json(gctx, opts, &root_indexes, &pkgs_to_prune, &graph);
+        }
+        OutputFormat::Dot => {
+            let format = format()?;
+            return export::print_dot(gctx, opts, &root_indexes, &pkgs_to_prune, &graph, &format);
+        }
+        OutputFormat::Mermaid => {
+            let format = format()?;
+            return export::print_mermaid(
+                gctx,
+                opts,
+                &root_indexes,
+                &pkgs_to_prune,
+                &graph,
+                &format,
+            );
+        }
+        OutputFormat::Text => {}
+    }
+
     if root_indexes.len() == 0 {
         ws.gctx().shell().warn(
             "nothing to print.\n\n\
@@ -216,7 +289,7 @@ fn print(
     pkgs_to_prune: &[PackageIdSpec],
     graph: &Graph<'_>,
 ) -> CargoResult<()> {
-    let format = Pattern::new(&opts.format)
+    let format = Pattern::new(&opts.format, gctx.cli_unstable().unstable_options)
         .with_context(|| format!("tree format `{}` not valid", opts.format))?;
 
     let symbols = if gctx.shell().out_unicode() {
@@ -260,6 +333,69 @@ fn print(
     Ok(())
 }
 
+/// Prints the features requested by each dependent of a package, for
+/// `--duplicates-features`.
+fn print_feature_requests(
+    gctx: &GlobalContext,
+    opts: &TreeOptions,
+    requests: &[(usize, Vec<FeatureRequest>)],
+    graph: &Graph<'_>,
+) -> CargoResult<()> {
+    let format = Pattern::new(&opts.format, gctx.cli_unstable().unstable_options)
+        .with_context(|| format!("tree format `{}` not valid", opts.format))?;
+
+    let symbols = if gctx.shell().out_unicode() {
+        &UTF8_SYMBOLS
+    } else {
+        &ASCII_SYMBOLS
+    };
+
+    let join = |features: &[InternedString]| {
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    };
+
+    for (i, (package_index, by_requester)) in requests.iter().enumerate() {
+        if i != 0 {
+            drop_println!(gctx);
+        }
+        let Node::Package { features, .. } = graph.node(*package_index) else {
+            unreachable!("expected a package node");
+        };
+        drop_println!(
+            gctx,
+            "{} features: {}",
+            format.display(graph, *package_index),
+            join(features)
+        );
+        let mut it = by_requester.iter().peekable();
+        while let Some(request) = it.next() {
+            let c = if it.peek().is_some() {
+                symbols.tee
+            } else {
+                symbols.ell
+            };
+            let from = match request.from {
+                Some(from) => format.display(graph, from).to_string(),
+                None => "(command-line)".to_string(),
+            };
+            drop_println!(
+                gctx,
+                "{0}{1}{1} {2} requested: {3}",
+                c,
+                symbols.right,
+                from,
+                join(&request.features)
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Prints a package and all of its dependencies.
 fn print_node<'a>(
     gctx: &GlobalContext,
@@ -7,7 +7,7 @@ use crate::util::cache_lock::CacheLockMode;
 use crate::util::{try_canonicalize, CargoResult, GlobalContext};
 use anyhow::{bail, Context as _};
 use cargo_util::{paths, Sha256};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::OsStr;
@@ -20,8 +20,25 @@ pub struct VendorOptions<'a> {
     pub versioned_dirs: bool,
     pub destination: &'a Path,
     pub extra: Vec<PathBuf>,
+    /// Where to write the JSON summary of what a `--versioned-dirs` sync
+    /// changed.
+    pub summary: Option<PathBuf>,
 }
 
+/// Name of the directory, inside the vendor directory, holding local files
+/// that are laid over the vendored crate of the same directory name.
+///
+/// Only used with versioned directories, where no crate can be vendored
+/// into a directory of this name.
+const PATCHES_DIR: &str = "patches";
+
+/// Name of the file recording where a vendored git dependency came from.
+const GIT_PROVENANCE_FILE: &str = ".cargo-git-source.json";
+
+/// Name of the file recording the overlays a `--versioned-dirs` sync has
+/// applied.
+const STATE_FILE: &str = ".cargo-vendor-state.json";
+
 pub fn vendor(ws: &Workspace<'_>, opts: &VendorOptions<'_>) -> CargoResult<()> {
     let gctx = ws.gctx();
     let mut extra_workspaces = Vec::new();
@@ -49,6 +66,33 @@ pub fn vendor(ws: &Workspace<'_>, opts: &VendorOptions<'_>) -> CargoResult<()> {
     Ok(())
 }
 
+/// What a `--versioned-dirs` sync vendored, keyed by the directory name.
+#[derive(Serialize, Deserialize, Default)]
+struct VendorState {
+    version: u32,
+    packages: BTreeMap<String, VendoredPackage>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct VendoredPackage {
+    name: String,
+    version: String,
+    source: String,
+    /// Checksum of the overlay in `patches`, if any was applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    patch: Option<String>,
+}
+
+/// The `--summary` output of a `--versioned-dirs` sync.
+#[derive(Serialize, Default)]
+struct VendorSummary {
+    version: u32,
+    added: BTreeMap<String, VendoredPackage>,
+    updated: BTreeMap<String, VendoredPackage>,
+    removed: BTreeSet<String>,
+    unchanged: BTreeSet<String>,
+}
+
 #[derive(Serialize)]
 struct VendorConfig {
     source: BTreeMap<String, VendorSource>,
@@ -90,16 +134,26 @@ fn sync(
     if !opts.no_delete {
         for entry in canonical_destination.read_dir()? {
             let entry = entry?;
-            if !entry
-                .file_name()
-                .to_str()
-                .map_or(false, |s| s.starts_with('.'))
-            {
+            let is_kept = entry.file_name().to_str().map_or(false, |s| {
+                s.starts_with('.') || (opts.versioned_dirs && s == PATCHES_DIR)
+            });
+            if !is_kept {
                 to_remove.insert(entry.path());
             }
         }
     }
 
+    // Versioned directories are updated in place, applying the unstable
+    // `patches` overlay.
+    let patches_dir = canonical_destination.join(PATCHES_DIR);
+    let in_place = opts.versioned_dirs && gctx.cli_unstable().unstable_options;
+    if opts.versioned_dirs && !in_place && patches_dir.is_dir() {
+        gctx.shell().warn(format!(
+            "ignoring `{}`, pass `-Zunstable-options` to apply it",
+            patches_dir.display()
+        ))?;
+    }
+
     // First up attempt to work around rust-lang/cargo#5956. Apparently build
     // artifacts sprout up in Cargo's global cache for whatever reason, although
     // it's unsure what tool is causing these issues at this time. For now we
@@ -188,6 +242,23 @@ fn sync(
         map.insert(id.version(), id.source_id());
     }
 
+    let state_path = canonical_destination.join(STATE_FILE);
+    let prev_state = if in_place && state_path.exists() {
+        let contents = paths::read(&state_path)?;
+        serde_json::from_str::<VendorState>(&contents)
+            .with_context(|| format!("failed to parse `{}`", state_path.display()))?
+    } else {
+        VendorState::default()
+    };
+    let mut state = VendorState {
+        version: 1,
+        packages: BTreeMap::new(),
+    };
+    let mut summary = VendorSummary {
+        version: 1,
+        ..Default::default()
+    };
+
     let mut sources = BTreeSet::new();
     let mut tmp_buf = [0; 64 * 1024];
     for (id, pkg) in ids.iter() {
@@ -207,7 +278,35 @@ fn sync(
         let dst = canonical_destination.join(&dst_name);
         to_remove.remove(&dst);
         let cksum = dst.join(".cargo-checksum.json");
-        if dir_has_version_suffix && cksum.exists() {
+        let patch_dir = patches_dir.join(&dst_name);
+        let patch = if in_place && patch_dir.is_dir() {
+            Some(overlay_checksum(&patch_dir)?)
+        } else {
+            None
+        };
+        let mut prev_files = None;
+        if in_place {
+            let entry = VendoredPackage {
+                name: id.name().to_string(),
+                version: id.version().to_string(),
+                source: id.source_id().as_url().to_string(),
+                patch: patch.clone(),
+            };
+            // A directory vendored before any overlay was tracked is the
+            // pristine crate, as its name includes the version.
+            let prev = prev_state.packages.get(&dst_name);
+            let unchanged = cksum.exists()
+                && match prev {
+                    Some(prev) => *prev == entry,
+                    None => patch.is_none(),
+                };
+            state.packages.insert(dst_name.clone(), entry);
+            if unchanged {
+                summary.unchanged.insert(dst_name.clone());
+                continue;
+            }
+            prev_files = Some(vendored_files(&cksum));
+        } else if dir_has_version_suffix && cksum.exists() {
             // Always re-copy directory without version suffix in case the version changed
             continue;
         }
@@ -223,6 +322,28 @@ fn sync(
         let mut map = BTreeMap::new();
         cp_sources(pkg, src, &paths, &dst, &mut map, &mut tmp_buf)
             .with_context(|| format!("failed to copy over vendored sources for: {}", id))?;
+        if patch.is_some() {
+            apply_overlay(&patch_dir, &dst, &mut map, &mut tmp_buf)
+                .with_context(|| format!("failed to apply `{}`", patch_dir.display()))?;
+        }
+
+        if id.source_id().is_git() {
+            write_git_provenance(gctx, pkg, &dst, &mut map)?;
+        }
+        if let Some(prev_files) = prev_files {
+            let entry = state.packages[&dst_name].clone();
+            match prev_files {
+                Some(prev_files) if prev_files == map => {
+                    summary.unchanged.insert(dst_name.clone());
+                }
+                Some(_) => {
+                    summary.updated.insert(dst_name.clone(), entry);
+                }
+                None => {
+                    summary.added.insert(dst_name.clone(), entry);
+                }
+            }
+        }
 
         // Finally, emit the metadata about this package
         let json = serde_json::json!({
@@ -235,12 +356,33 @@ fn sync(
 
     for path in to_remove {
         if path.is_dir() {
+            if in_place && path.join(".cargo-checksum.json").exists() {
+                if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                    summary.removed.insert(name.to_owned());
+                }
+            }
             paths::remove_dir_all(&path)?;
         } else {
             paths::remove_file(&path)?;
         }
     }
 
+    if in_place {
+        for (dst_name, entry) in prev_state.packages {
+            if !state.packages.contains_key(&dst_name)
+                && canonical_destination.join(&dst_name).exists()
+            {
+                // Kept on disk by `--no-delete`, so keep tracking it.
+                state.packages.insert(dst_name, entry);
+            }
+        }
+        paths::write(&state_path, serde_json::to_string_pretty(&state)?)?;
+        if let Some(summary_path) = &opts.summary {
+            let summary_path = gctx.cwd().join(summary_path);
+            paths::write(&summary_path, serde_json::to_string_pretty(&summary)?)?;
+        }
+    }
+
     // add our vendored source
     let mut config = BTreeMap::new();
 
@@ -392,6 +534,120 @@ fn cp_sources(
     Ok(())
 }
 
+/// Records where a vendored git dependency came from, so that the directory
+/// source can check it against the locked commit and audits can map it back
+/// to upstream.
+fn write_git_provenance(
+    gctx: &GlobalContext,
+    pkg: &Package,
+    dst: &Path,
+    cksums: &mut BTreeMap<String, String>,
+) -> CargoResult<()> {
+    let source_id = pkg.package_id().source_id();
+    let Some(rev) = source_id.precise_git_fragment() else {
+        bail!(
+            "git dependency `{}` is not locked to a commit",
+            pkg.package_id()
+        );
+    };
+    // Checkouts live in `<checkouts>/<repo ident>/<short rev>/`.
+    let checkouts = gctx.git_checkouts_path().into_path_unlocked();
+    let path_in_vcs = pkg
+        .root()
+        .strip_prefix(&checkouts)
+        .map(|relative| {
+            relative
+                .iter()
+                .skip(2)
+                .map(|c| c.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default();
+    let provenance = serde_json::json!({
+        "git": source_id.url().as_str(),
+        "rev": rev,
+        "path_in_vcs": path_in_vcs,
+    });
+    let contents = serde_json::to_string_pretty(&provenance)?;
+    paths::write(&dst.join(GIT_PROVENANCE_FILE), &contents)?;
+    let cksum = Sha256::new().update(contents.as_bytes()).finish_hex();
+    cksums.insert(GIT_PROVENANCE_FILE.to_string(), cksum);
+    Ok(())
+}
+
+/// Reads the checksums of the files of a vendored crate from its
+/// `.cargo-checksum.json`, if it was vendored before.
+fn vendored_files(cksum: &Path) -> Option<BTreeMap<String, String>> {
+    #[derive(Deserialize)]
+    struct Checksum {
+        files: BTreeMap<String, String>,
+    }
+    let contents = paths::read(cksum).ok()?;
+    let cksum: Checksum = serde_json::from_str(&contents).ok()?;
+    Some(cksum.files)
+}
+
+/// Computes a checksum over the paths and contents of every file in the
+/// overlay directory, so that edits to the overlay re-vendor the crate.
+fn overlay_checksum(patch_dir: &Path) -> CargoResult<String> {
+    let mut cksum = Sha256::new();
+    for (relative, path) in overlay_files(patch_dir)? {
+        cksum.update(relative.as_bytes());
+        cksum.update(&[0]);
+        cksum.update_path(&path)?;
+    }
+    Ok(cksum.finish_hex())
+}
+
+/// Copies the files of the overlay directory over the vendored crate,
+/// replacing their entries in the checksum map.
+fn apply_overlay(
+    patch_dir: &Path,
+    dst: &Path,
+    cksums: &mut BTreeMap<String, String>,
+    tmp_buf: &mut [u8],
+) -> CargoResult<()> {
+    for (relative, path) in overlay_files(patch_dir)? {
+        let dst = relative
+            .split('/')
+            .fold(dst.to_owned(), |acc, component| acc.join(component));
+        paths::create_dir_all(dst.parent().unwrap())?;
+        let mut dst_opts = OpenOptions::new();
+        dst_opts.write(true).create(true).truncate(true);
+        let mut src = File::open(&path).with_context(|| format!("failed to open {:?}", &path))?;
+        let cksum = copy_and_checksum(
+            &dst,
+            &mut dst_opts,
+            &mut src,
+            &path.display().to_string(),
+            tmp_buf,
+        )?;
+        cksums.insert(relative, cksum);
+    }
+    Ok(())
+}
+
+/// Lists the files of the overlay directory, sorted by their `/`-separated
+/// path relative to it.
+fn overlay_files(patch_dir: &Path) -> CargoResult<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(patch_dir).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(patch_dir).unwrap();
+        let relative = relative
+            .iter()
+            .map(|c| c.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push((relative, entry.into_path()));
+    }
+    Ok(files)
+}
+
 fn copy_and_checksum<T: Read>(
     dst_path: &Path,
     dst_opts: &mut OpenOptions,
@@ -0,0 +1,152 @@
+//! Polling of files for the commands that run again when their inputs change,
+//! like `cargo doc --serve` and `--watch`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use cargo_util::paths;
+use filetime::FileTime;
+
+use crate::util::errors::CliResult;
+use crate::GlobalContext;
+
+/// How often files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long files must stay unchanged before a change is reported, so that
+/// saving several files at once causes a single rerun.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches a set of files by polling their modification time.
+///
+/// Polling avoids platform-specific notification APIs, and the number of files
+/// a build depends on is small enough for it to be cheap.
+pub struct Watcher {
+    /// The last seen modification time of each watched file, or `None` if it
+    /// didn't exist.
+    mtimes: HashMap<PathBuf, Option<FileTime>>,
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Watcher {
+        let mut watcher = Watcher {
+            mtimes: HashMap::new(),
+        };
+        watcher.watch(paths);
+        watcher
+    }
+
+    /// Replaces the watched files with `paths`.
+    ///
+    /// Files that were already watched keep their last seen modification time,
+    /// so that changes made while the command was running aren't missed.
+    pub fn watch(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        let mut mtimes = HashMap::new();
+        for path in paths {
+            let mtime = match self.mtimes.remove(&path) {
+                Some(mtime) => mtime,
+                None => paths::mtime(&path).ok(),
+            };
+            mtimes.insert(path, mtime);
+        }
+        self.mtimes = mtimes;
+    }
+
+    /// Returns the number of watched files.
+    pub fn len(&self) -> usize {
+        self.mtimes.len()
+    }
+
+    /// Blocks until some of the watched files change, and returns them.
+    pub fn wait(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        while changed.is_empty() {
+            thread::sleep(POLL_INTERVAL);
+            changed = self.poll();
+        }
+        loop {
+            thread::sleep(DEBOUNCE);
+            let more = self.poll();
+            if more.is_empty() {
+                break;
+            }
+            for path in more {
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        changed.sort();
+        changed
+    }
+
+    /// Returns the files that changed since the last poll.
+    fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, last) in &mut self.mtimes {
+            let mtime = paths::mtime(path).ok();
+            if mtime != *last {
+                *last = mtime;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+/// Describes the files returned by [`Watcher::wait`], relative to `root`.
+pub fn describe_changes(changed: &[PathBuf], root: &Path) -> String {
+    let first = changed[0].strip_prefix(root).unwrap_or(&changed[0]);
+    let others = match changed.len() - 1 {
+        0 => String::new(),
+        1 => " and 1 other file".to_owned(),
+        n => format!(" and {n} other files"),
+    };
+    format!("{} changed{others}", first.display())
+}
+
+/// Runs a command, and runs it again whenever one of the files that it built
+/// from changes, for `--watch`.
+///
+/// `run` is given where to collect the files to watch, to set as
+/// [`BuildConfig::watched_files`]. It should load the workspace again, so that
+/// changes to manifests are picked up. Its errors are displayed without
+/// stopping, unless it fails before there is anything to watch.
+///
+/// [`BuildConfig::watched_files`]: crate::core::compiler::BuildConfig::watched_files
+pub fn watch(
+    gctx: &GlobalContext,
+    mut run: impl FnMut(Rc<RefCell<Vec<PathBuf>>>) -> CliResult,
+) -> CliResult {
+    let mut watcher = Watcher::new([]);
+    loop {
+        let watched_files = Rc::new(RefCell::new(Vec::new()));
+        let result = run(Rc::clone(&watched_files));
+        let watched_files = watched_files.take();
+        // Keep watching the previous files if the command failed before
+        // collecting any, like when a manifest fails to parse.
+        if !watched_files.is_empty() {
+            watcher.watch(watched_files);
+        }
+        if let Err(e) = result {
+            if watcher.len() == 0 {
+                return Err(e);
+            }
+            if let Some(error) = e.error {
+                crate::display_error(&error, &mut gctx.shell());
+            }
+        }
+
+        gctx.shell().status(
+            "Watching",
+            format!("{} files for changes, press Ctrl-C to stop", watcher.len()),
+        )?;
+        let changed = watcher.wait();
+        gctx.shell()
+            .status("Rerunning", describe_changes(&changed, gctx.cwd()))?;
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::core::{GitReference, PackageId, SourceId};
 use crate::sources::overlay::DependencyConfusionThreatOverlaySource;
+use crate::sources::registry::requires_signatures;
 use crate::sources::source::Source;
 use crate::sources::{ReplacedSource, CRATES_IO_REGISTRY};
 use crate::util::context::{self, ConfigRelativePath, OptValue};
@@ -215,6 +216,17 @@ a lock file compatible with `{orig}` cannot be generated in this situation
             );
         }
 
+        if requires_signatures(self.gctx, id)? && !requires_signatures(self.gctx, new_id)? {
+            bail!(
+                "\
+cannot replace `{orig}` with `{name}`, the source `{orig}` requires signed \
+index entries, but `{name}` does not
+",
+                orig = orig_name,
+                name = name
+            );
+        }
+
         if old_src.requires_precise() && !id.has_precise() {
             bail!(
                 "\
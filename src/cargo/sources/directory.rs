@@ -10,6 +10,7 @@ use crate::sources::source::Source;
 use crate::sources::IndexSummary;
 use crate::sources::PathSource;
 use crate::util::errors::CargoResult;
+use crate::util::CanonicalUrl;
 use crate::util::GlobalContext;
 
 use anyhow::Context as _;
@@ -32,6 +33,10 @@ use serde::Deserialize;
 ///   level of `Cargo.toml` to ensure the integrity when a directory source was
 ///   created (usually by `cargo vendor`). A failure to find or parse a single
 ///   checksum results in a denial of loading any package in this source.
+/// * A git dependency vendored by `cargo vendor` also has a [`GitProvenance`]
+///   file `.cargo-git-source.json`, recording where it came from. When the
+///   directory source replaces that git source, the recorded repository and
+///   commit must match the locked ones.
 /// * Otherwise, there is no other restrction of the name of directories. At
 ///   this moment, it is `cargo vendor` that defines the layout and the name of
 ///   each directory.
@@ -44,6 +49,11 @@ use serde::Deserialize;
 /// │  ├── src/
 /// │  ├── .cargo-checksum.json
 /// │  └── Cargo.toml
+/// ├── a-vendored-git-crate/
+/// │  ├── src/
+/// │  ├── .cargo-checksum.json
+/// │  ├── .cargo-git-source.json
+/// │  └── Cargo.toml
 /// ├── .ignored-a-dot-crate/
 /// │  ├── src/
 /// │  ├── .cargo-checksum.json
@@ -60,7 +70,7 @@ pub struct DirectorySource<'gctx> {
     /// The root path of this source.
     root: PathBuf,
     /// Packages that this sources has discovered.
-    packages: HashMap<PackageId, (Package, Checksum)>,
+    packages: HashMap<PackageId, (Package, Checksum, Option<GitProvenance>)>,
     gctx: &'gctx GlobalContext,
     updated: bool,
 }
@@ -78,6 +88,19 @@ struct Checksum {
     files: HashMap<String, String>,
 }
 
+/// Where a vendored git dependency came from.
+///
+/// The file name is `.cargo-git-source.json`, written by `cargo vendor`. It
+/// also records the `path_in_vcs` of the package inside the repository, which
+/// is only informational.
+#[derive(Deserialize)]
+struct GitProvenance {
+    /// The URL of the git repository.
+    git: String,
+    /// The full commit hash the package was vendored from.
+    rev: String,
+}
+
 impl<'gctx> DirectorySource<'gctx> {
     pub fn new(path: &Path, id: SourceId, gctx: &'gctx GlobalContext) -> DirectorySource<'gctx> {
         DirectorySource {
@@ -111,6 +134,7 @@ impl<'gctx> Source for DirectorySource<'gctx> {
             QueryKind::Exact => dep.matches(pkg.summary()),
             QueryKind::Alternatives => true,
             QueryKind::Normalized => dep.matches(pkg.summary()),
+            QueryKind::OfflineUnavailable => false,
         });
         for summary in matches.map(|pkg| pkg.summary().clone()) {
             f(IndexSummary::Candidate(summary));
@@ -196,12 +220,30 @@ impl<'gctx> Source for DirectorySource<'gctx> {
                 )
             })?;
 
+            let provenance_file = path.join(".cargo-git-source.json");
+            let provenance = if provenance_file.exists() {
+                let provenance = paths::read(&provenance_file)?;
+                let provenance: GitProvenance =
+                    serde_json::from_str(&provenance).with_context(|| {
+                        format!(
+                            "failed to decode `.cargo-git-source.json` of \
+                             {} v{}",
+                            pkg.package_id().name(),
+                            pkg.package_id().version()
+                        )
+                    })?;
+                Some(provenance)
+            } else {
+                None
+            };
+
             if let Some(package) = &cksum.package {
                 pkg.manifest_mut()
                     .summary_mut()
                     .set_checksum(package.clone());
             }
-            self.packages.insert(pkg.package_id(), (pkg, cksum));
+            self.packages
+                .insert(pkg.package_id(), (pkg, cksum, provenance));
         }
 
         self.updated = true;
@@ -226,7 +268,7 @@ impl<'gctx> Source for DirectorySource<'gctx> {
     }
 
     fn verify(&self, id: PackageId) -> CargoResult<()> {
-        let Some((pkg, cksum)) = self.packages.get(&id) else {
+        let Some((pkg, cksum, _)) = self.packages.get(&id) else {
             anyhow::bail!("failed to find entry for `{}` in directory source", id);
         };
 
@@ -257,6 +299,43 @@ impl<'gctx> Source for DirectorySource<'gctx> {
         Ok(())
     }
 
+    fn verify_replacement(&self, id: PackageId, replaced: PackageId) -> CargoResult<()> {
+        self.verify(id)?;
+        let Some((_, _, Some(provenance))) = self.packages.get(&id) else {
+            return Ok(());
+        };
+        let replaced_source = replaced.source_id();
+        let same_repo = replaced_source.is_git()
+            && url::Url::parse(&provenance.git)
+                .ok()
+                .and_then(|url| CanonicalUrl::new(&url).ok())
+                .map_or(false, |url| &url == replaced_source.canonical_url());
+        if !same_repo {
+            anyhow::bail!(
+                "`{} v{}` was vendored from git repository `{}`, \
+                 but is locked to {}",
+                id.name(),
+                id.version(),
+                provenance.git,
+                replaced_source,
+            );
+        }
+        if let Some(rev) = replaced_source.precise_git_fragment() {
+            if rev != provenance.rev {
+                anyhow::bail!(
+                    "`{} v{}` was vendored from commit {} of `{}`, \
+                     but is locked to commit {}",
+                    id.name(),
+                    id.version(),
+                    provenance.rev,
+                    provenance.git,
+                    rev,
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn describe(&self) -> String {
         format!("directory source `{}`", self.root.display())
     }
@@ -8,7 +8,7 @@
 //! [CVE-2022-46176]: https://blog.rust-lang.org/2023/01/10/cve-2022-46176.html
 
 pub use self::source::GitSource;
-pub use self::utils::{fetch, resolve_ref, GitCheckout, GitDatabase, GitRemote};
+pub use self::utils::{fetch, is_ref_glob, resolve_ref, GitCheckout, GitDatabase, GitRemote};
 mod known_hosts;
 mod oxide;
 mod source;
@@ -24,6 +24,8 @@ pub mod fetch {
     pub enum RemoteKind {
         /// A repository belongs to a git dependency.
         GitDependency,
+        /// A repository belongs to a git dependency with `shallow = true`.
+        ShallowGitDependency,
         /// A repository belongs to a Cargo registry.
         Registry,
     }
@@ -48,6 +50,7 @@ pub mod fetch {
                 match self {
                     RemoteKind::GitDependency if has_feature(&|features| features.shallow_deps) => {
                     }
+                    RemoteKind::ShallowGitDependency => {}
                     RemoteKind::Registry if has_feature(&|features| features.shallow_index) => {}
                     _ => return gix::remote::fetch::Shallow::NoChange,
                 }
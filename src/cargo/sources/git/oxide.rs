@@ -286,6 +286,11 @@ pub fn cargo_config_to_gitoxide_overrides(gctx: &GlobalContext) -> CargoResult<V
     if let Some(proxy) = &http.proxy {
         values.push(Http::PROXY.validated_assignment_fmt(proxy)?);
     }
+    if let Some(no_proxy) = &http.no_proxy {
+        values.push(
+            gitoxide::Http::NO_PROXY.validated_assignment_fmt(&no_proxy.as_slice().join(","))?,
+        );
+    }
     if let Some(check_revoke) = http.check_revoke {
         values.push(Http::SCHANNEL_CHECK_REVOKE.validated_assignment_fmt(&check_revoke)?);
     }
@@ -4,9 +4,11 @@ use crate::core::global_cache_tracker;
 use crate::core::GitReference;
 use crate::core::SourceId;
 use crate::core::{Dependency, Package, PackageId};
+use crate::sources::git::fetch::RemoteKind;
 use crate::sources::git::utils::rev_to_oid;
 use crate::sources::git::utils::GitRemote;
 use crate::sources::source::MaybePackage;
+use crate::sources::source::PreparedFetch;
 use crate::sources::source::QueryKind;
 use crate::sources::source::Source;
 use crate::sources::IndexSummary;
@@ -15,10 +17,12 @@ use crate::util::cache_lock::CacheLockMode;
 use crate::util::errors::CargoResult;
 use crate::util::hex::short_hash;
 use crate::util::interning::InternedString;
+use crate::util::network::retry::fallback_to_cache;
 use crate::util::GlobalContext;
 use anyhow::Context as _;
 use cargo_util::paths::exclude_from_backups_and_indexing;
 use std::fmt::{self, Debug, Formatter};
+use std::path::PathBuf;
 use std::task::Poll;
 use tracing::trace;
 use url::Url;
@@ -94,6 +98,9 @@ pub struct GitSource<'gctx> {
     gctx: &'gctx GlobalContext,
     /// Disables status messages.
     quiet: bool,
+    /// Whether the Git database has already been fetched by running the
+    /// command from [`Source::prepare_fetch`].
+    prefetched: bool,
 }
 
 impl<'gctx> GitSource<'gctx> {
@@ -112,7 +119,8 @@ impl<'gctx> GitSource<'gctx> {
             &source_id,
             gctx.cli_unstable()
                 .git
-                .map_or(false, |features| features.shallow_deps),
+                .map_or(false, |features| features.shallow_deps)
+                || source_id.git_checkout().map_or(false, |c| c.shallow),
         );
 
         let source = GitSource {
@@ -124,6 +132,7 @@ impl<'gctx> GitSource<'gctx> {
             ident: ident.into(),
             gctx,
             quiet: false,
+            prefetched: false,
         };
 
         Ok(source)
@@ -145,6 +154,51 @@ impl<'gctx> GitSource<'gctx> {
         self.path_source.as_mut().unwrap().read_packages()
     }
 
+    /// Gets the kind of remote to fetch, depending on whether this source
+    /// asks for a shallow fetch.
+    fn remote_kind(&self) -> RemoteKind {
+        if self.source_id.git_checkout().map_or(false, |c| c.shallow) {
+            RemoteKind::ShallowGitDependency
+        } else {
+            RemoteKind::GitDependency
+        }
+    }
+
+    /// Gets the directories to check out, or an empty list for the whole
+    /// repository.
+    fn sparse_paths(&self) -> &'static [String] {
+        self.source_id
+            .git_checkout()
+            .map_or(&[], |c| c.paths.as_slice())
+    }
+
+    /// Gets the path to the Git database of this source, making sure
+    /// `<cargo_home>/git` exists.
+    fn db_path(&self) -> PathBuf {
+        let git_fs = self.gctx.git_path();
+        // Ignore errors creating it, in case this is a read-only filesystem:
+        // perhaps the later operations can succeed anyhow.
+        let _ = git_fs.create_dir();
+        let git_path = self
+            .gctx
+            .assert_package_cache_locked(CacheLockMode::DownloadExclusive, &git_fs);
+
+        // Before getting a checkout, make sure that `<cargo_home>/git` is
+        // marked as excluded from indexing and backups. Older versions of Cargo
+        // didn't do this, so we do it here regardless of whether `<cargo_home>`
+        // exists.
+        //
+        // This does not use `create_dir_all_excluded_from_backups_atomic` for
+        // the same reason: we want to exclude it even if the directory already
+        // exists.
+        exclude_from_backups_and_indexing(&git_path);
+
+        self.gctx
+            .git_db_path()
+            .join(&self.ident)
+            .into_path_unlocked()
+    }
+
     fn mark_used(&self, size: Option<u64>) -> CargoResult<()> {
         self.gctx
             .deferred_global_last_use()?
@@ -155,6 +209,125 @@ impl<'gctx> GitSource<'gctx> {
             });
         Ok(())
     }
+
+    /// Fetches the repository if needed and checks out the requested
+    /// revision, returning the path of the checkout and the commit it's at.
+    fn fetch_checkout(&mut self) -> CargoResult<(PathBuf, git2::Oid)> {
+        let db_path = self.db_path();
+        let db = self.remote.db_at(&db_path).ok();
+        let prefetched = std::mem::take(&mut self.prefetched);
+
+        let (db, actual_rev) = match (&self.locked_rev, db) {
+            // If we have a locked revision, and we have a preexisting database
+            // which has that revision, then no update needs to happen.
+            (Revision::Locked(oid), Some(db)) if db.contains(*oid) => (db, *oid),
+
+            // If we're in offline mode, we're not locked, and we have a
+            // database, then try to resolve our reference with the preexisting
+            // repository.
+            (Revision::Deferred(git_ref), Some(db)) if self.gctx.offline() => {
+                let rev = db.resolve(&git_ref).with_context(|| {
+                    "failed to lookup reference in preexisting repository, and \
+                         can't check for updates in offline mode (--offline)"
+                })?;
+                (db, rev)
+            }
+
+            // If the database was just fetched alongside other git sources,
+            // resolve our reference with what was fetched.
+            (locked_rev, Some(db))
+                if prefetched && db.resolve(&locked_rev.clone().into()).is_ok() =>
+            {
+                let rev = db.resolve(&locked_rev.clone().into())?;
+                (db, rev)
+            }
+
+            // ... otherwise we use this state to update the git database. Note
+            // that we still check for being offline here, for example in the
+            // situation that we have a locked revision but the database
+            // doesn't have it.
+            (locked_rev, db) => {
+                if self.gctx.offline() {
+                    anyhow::bail!(
+                        "can't checkout from '{}': you are in the offline mode (--offline)",
+                        self.remote.url()
+                    );
+                }
+
+                if !self.quiet {
+                    self.gctx.shell().status(
+                        "Updating",
+                        format!("git repository `{}`", self.remote.url()),
+                    )?;
+                }
+
+                trace!("updating git source `{:?}`", self.remote);
+
+                let locked_rev = locked_rev.clone().into();
+                match self
+                    .remote
+                    .checkout(&db_path, db, &locked_rev, self.remote_kind(), self.gctx)
+                {
+                    Ok(checkout) => checkout,
+                    // Carry on with what an earlier run fetched, if that has
+                    // the revision we're after.
+                    Err(e) => {
+                        let cached = self.remote.db_at(&db_path).ok().and_then(|db| {
+                            let rev = db.resolve(&locked_rev).ok()?;
+                            Some((db, rev))
+                        });
+                        match cached {
+                            Some(cached) if fallback_to_cache(self.gctx)? => {
+                                self.gctx.shell().warn(format!(
+                                    "{e:#}\nfalling back to the cached git repository `{}`",
+                                    self.remote.url()
+                                ))?;
+                                cached
+                            }
+                            _ => return Err(e),
+                        }
+                    }
+                }
+            }
+        };
+
+        // Don’t use the full hash, in order to contribute less to reaching the
+        // path length limit on Windows. See
+        // <https://github.com/servo/servo/pull/14397>.
+        let mut short_id = db.to_short_id(actual_rev)?.as_str().to_owned();
+        // Sparse checkouts of different directories can't share a checkout.
+        let sparse_paths = self.sparse_paths();
+        if !sparse_paths.is_empty() {
+            short_id.push_str("-sparse-");
+            short_id.push_str(&short_hash(&sparse_paths));
+        }
+
+        // Check out `actual_rev` from the database to a scoped location on the
+        // filesystem. This will use hard links and such to ideally make the
+        // checkout operation here pretty fast.
+        let checkout_path = self
+            .gctx
+            .git_checkouts_path()
+            .join(&self.ident)
+            .join(&short_id);
+        let checkout_path = checkout_path.into_path_unlocked();
+        db.copy_to(actual_rev, &checkout_path, sparse_paths, self.gctx)?;
+
+        self.short_id = Some(short_id.into());
+        self.locked_rev = Revision::Locked(actual_rev);
+        Ok((checkout_path, actual_rev))
+    }
+
+    /// Checks out the repository without reading any packages from it.
+    ///
+    /// This is for callers which need the files of the repository rather than
+    /// its packages, like `cargo new --template`.
+    pub fn checkout(&mut self) -> CargoResult<PathBuf> {
+        let (checkout_path, _) = self.fetch_checkout()?;
+        let size = global_cache_tracker::du_git_checkout(&checkout_path)?;
+        self.mark_used(Some(size))?;
+        Ok(checkout_path)
+    }
 }
 
 /// Indicates a [Git revision] that might be locked or deferred to be resolved.
@@ -266,92 +439,45 @@ impl<'gctx> Source for GitSource<'gctx> {
         self.source_id
     }
 
-    fn block_until_ready(&mut self) -> CargoResult<()> {
-        if self.path_source.is_some() {
-            self.mark_used(None)?;
-            return Ok(());
+    fn prepare_fetch(&mut self) -> CargoResult<Option<PreparedFetch>> {
+        if self.path_source.is_some() || self.prefetched || self.gctx.offline() {
+            return Ok(None);
         }
 
-        let git_fs = self.gctx.git_path();
-        // Ignore errors creating it, in case this is a read-only filesystem:
-        // perhaps the later operations can succeed anyhow.
-        let _ = git_fs.create_dir();
-        let git_path = self
-            .gctx
-            .assert_package_cache_locked(CacheLockMode::DownloadExclusive, &git_fs);
-
-        // Before getting a checkout, make sure that `<cargo_home>/git` is
-        // marked as excluded from indexing and backups. Older versions of Cargo
-        // didn't do this, so we do it here regardless of whether `<cargo_home>`
-        // exists.
-        //
-        // This does not use `create_dir_all_excluded_from_backups_atomic` for
-        // the same reason: we want to exclude it even if the directory already
-        // exists.
-        exclude_from_backups_and_indexing(&git_path);
-
-        let db_path = self.gctx.git_db_path().join(&self.ident);
-        let db_path = db_path.into_path_unlocked();
-
+        let db_path = self.db_path();
         let db = self.remote.db_at(&db_path).ok();
-
-        let (db, actual_rev) = match (&self.locked_rev, db) {
-            // If we have a locked revision, and we have a preexisting database
-            // which has that revision, then no update needs to happen.
-            (Revision::Locked(oid), Some(db)) if db.contains(*oid) => (db, *oid),
-
-            // If we're in offline mode, we're not locked, and we have a
-            // database, then try to resolve our reference with the preexisting
-            // repository.
-            (Revision::Deferred(git_ref), Some(db)) if self.gctx.offline() => {
-                let rev = db.resolve(&git_ref).with_context(|| {
-                    "failed to lookup reference in preexisting repository, and \
-                         can't check for updates in offline mode (--offline)"
-                })?;
-                (db, rev)
+        if let (Revision::Locked(oid), Some(db)) = (&self.locked_rev, &db) {
+            if db.contains(*oid) {
+                return Ok(None);
             }
+        }
 
-            // ... otherwise we use this state to update the git database. Note
-            // that we still check for being offline here, for example in the
-            // situation that we have a locked revision but the database
-            // doesn't have it.
-            (locked_rev, db) => {
-                if self.gctx.offline() {
-                    anyhow::bail!(
-                        "can't checkout from '{}': you are in the offline mode (--offline)",
-                        self.remote.url()
-                    );
-                }
-
-                if !self.quiet {
-                    self.gctx.shell().status(
-                        "Updating",
-                        format!("git repository `{}`", self.remote.url()),
-                    )?;
-                }
-
-                trace!("updating git source `{:?}`", self.remote);
-
-                let locked_rev = locked_rev.clone().into();
-                self.remote.checkout(&db_path, db, &locked_rev, self.gctx)?
-            }
+        let locked_rev = self.locked_rev.clone().into();
+        let Some(cmd) = self.remote.prepare_cli_fetch(
+            &db_path,
+            db,
+            &locked_rev,
+            self.remote_kind(),
+            self.gctx,
+        )?
+        else {
+            return Ok(None);
         };
 
-        // Don’t use the full hash, in order to contribute less to reaching the
-        // path length limit on Windows. See
-        // <https://github.com/servo/servo/pull/14397>.
-        let short_id = db.to_short_id(actual_rev)?;
+        self.prefetched = true;
+        Ok(Some(PreparedFetch {
+            cmd,
+            status: (!self.quiet).then(|| format!("git repository `{}`", self.remote.url())),
+        }))
+    }
 
-        // Check out `actual_rev` from the database to a scoped location on the
-        // filesystem. This will use hard links and such to ideally make the
-        // checkout operation here pretty fast.
-        let checkout_path = self
-            .gctx
-            .git_checkouts_path()
-            .join(&self.ident)
-            .join(short_id.as_str());
-        let checkout_path = checkout_path.into_path_unlocked();
-        db.copy_to(actual_rev, &checkout_path, self.gctx)?;
+    fn block_until_ready(&mut self) -> CargoResult<()> {
+        if self.path_source.is_some() {
+            self.mark_used(None)?;
+            return Ok(());
+        }
+
+        let (checkout_path, actual_rev) = self.fetch_checkout()?;
 
         let source_id = self
             .source_id
@@ -359,8 +485,6 @@ impl<'gctx> Source for GitSource<'gctx> {
         let path_source = RecursivePathSource::new(&checkout_path, source_id, self.gctx);
 
         self.path_source = Some(path_source);
-        self.short_id = Some(short_id.as_str().into());
-        self.locked_rev = Revision::Locked(actual_rev);
         self.path_source.as_mut().unwrap().load()?;
 
         // Hopefully this shouldn't incur too much of a performance hit since
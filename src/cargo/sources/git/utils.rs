@@ -106,6 +106,7 @@ impl GitRemote {
         into: &Path,
         db: Option<GitDatabase>,
         reference: &GitReference,
+        remote_kind: RemoteKind,
         gctx: &GlobalContext,
     ) -> CargoResult<(GitDatabase, git2::Oid)> {
         if let Some(mut db) = db {
@@ -114,7 +115,7 @@ impl GitRemote {
                 self.url.as_str(),
                 reference,
                 gctx,
-                RemoteKind::GitDependency,
+                remote_kind,
             )
             .with_context(|| format!("failed to fetch into: {}", into.display()))?;
 
@@ -131,14 +132,8 @@ impl GitRemote {
         }
         paths::create_dir_all(into)?;
         let mut repo = init(into, true)?;
-        fetch(
-            &mut repo,
-            self.url.as_str(),
-            reference,
-            gctx,
-            RemoteKind::GitDependency,
-        )
-        .with_context(|| format!("failed to clone into: {}", into.display()))?;
+        fetch(&mut repo, self.url.as_str(), reference, gctx, remote_kind)
+            .with_context(|| format!("failed to clone into: {}", into.display()))?;
         let rev = resolve_ref(reference, &repo)?;
 
         Ok((
@@ -151,6 +146,51 @@ impl GitRemote {
         ))
     }
 
+    /// Prepares the `git fetch` command that [`GitRemote::checkout`] would
+    /// run for `reference`, so that it can be run alongside the fetches of
+    /// other remotes.
+    ///
+    /// This initializes a new database at `into` if `db` is `None`. Returns
+    /// `None` if the database is already up-to-date, or if fetches don't go
+    /// through the git CLI as configured by [`net.git-fetch-with-cli`][1].
+    ///
+    /// [1]: https://doc.rust-lang.org/nightly/cargo/reference/config.html#netgit-fetch-with-cli
+    pub fn prepare_cli_fetch(
+        &self,
+        into: &Path,
+        db: Option<GitDatabase>,
+        reference: &GitReference,
+        remote_kind: RemoteKind,
+        gctx: &GlobalContext,
+    ) -> CargoResult<Option<ProcessBuilder>> {
+        if gctx.net_config()?.git_fetch_with_cli != Some(true) {
+            return Ok(None);
+        }
+        let mut repo = match db {
+            Some(db) => db.repo,
+            None => {
+                if into.exists() {
+                    paths::remove_dir_all(into)?;
+                }
+                paths::create_dir_all(into)?;
+                init(into, true)?
+            }
+        };
+        let Some((refspecs, tags, shallow)) =
+            fetch_refspecs(&mut repo, self.url.as_str(), reference, gctx, remote_kind)?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(cli_fetch_command(
+            &repo,
+            self.url.as_str(),
+            &refspecs,
+            tags,
+            shallow,
+            gctx,
+        )))
+    }
+
     /// Creates a [`GitDatabase`] of this remote at `db_path`.
     pub fn db_at(&self, db_path: &Path) -> CargoResult<GitDatabase> {
         let repo = git2::Repository::open(db_path)?;
@@ -164,10 +204,14 @@ impl GitRemote {
 
 impl GitDatabase {
     /// Checkouts to a revision at `dest`ination from this database.
+    ///
+    /// If `paths` isn't empty, only those directories and the files at the
+    /// root of the repository are checked out.
     pub fn copy_to(
         &self,
         rev: git2::Oid,
         dest: &Path,
+        paths: &[String],
         gctx: &GlobalContext,
     ) -> CargoResult<GitCheckout<'_>> {
         // If the existing checkout exists, and it is fresh, use it.
@@ -180,9 +224,9 @@ impl GitDatabase {
             .filter(|co| co.is_fresh())
         {
             Some(co) => co,
-            None => GitCheckout::clone_into(dest, self, rev, gctx)?,
+            None => GitCheckout::clone_into(dest, self, rev, paths, gctx)?,
         };
-        checkout.update_submodules(gctx)?;
+        checkout.update_submodules(paths, gctx)?;
         Ok(checkout)
     }
 
@@ -206,6 +250,27 @@ impl GitDatabase {
 /// Resolves [`GitReference`] to an object ID with objects the `repo` currently has.
 pub fn resolve_ref(gitref: &GitReference, repo: &git2::Repository) -> CargoResult<git2::Oid> {
     let id = match gitref {
+        GitReference::Tag(s) if is_ref_glob(s) => (|| -> CargoResult<git2::Oid> {
+            let refname = highest_matching_ref(repo, "refs/remotes/origin/tags/", s, |_| true)?
+                .ok_or_else(|| anyhow::format_err!("no tag matches the pattern"))?;
+            let obj = repo.revparse_single(&refname)?;
+            let obj = obj.peel(ObjectType::Commit)?;
+            Ok(obj.id())
+        })()
+        .with_context(|| format!("failed to find tag `{}`", s))?,
+
+        GitReference::Branch(s) if is_ref_glob(s) => (|| -> CargoResult<git2::Oid> {
+            // Tags are fetched next to the branches, see `fetch` below.
+            let refname = highest_matching_ref(repo, "refs/remotes/origin/", s, |name| {
+                name != "HEAD" && !name.starts_with("tags/")
+            })?
+            .ok_or_else(|| anyhow::format_err!("no branch matches the pattern"))?;
+            let obj = repo.revparse_single(&refname)?;
+            let obj = obj.peel(ObjectType::Commit)?;
+            Ok(obj.id())
+        })()
+        .with_context(|| format!("failed to find branch `{}`", s))?,
+
         // Note that we resolve the named tag here in sync with where it's
         // fetched into via `fetch` below.
         GitReference::Tag(s) => (|| -> CargoResult<git2::Oid> {
@@ -247,6 +312,85 @@ pub fn resolve_ref(gitref: &GitReference, repo: &git2::Repository) -> CargoResul
     Ok(id)
 }
 
+/// Checks if the name of a `tag` or `branch` is a glob pattern like `v1.*`.
+///
+/// Git doesn't allow `*`, `?` or `[` in ref names, so these can't be mistaken
+/// for the name of an actual ref.
+pub fn is_ref_glob(name: &str) -> bool {
+    name.contains(['*', '?', '['])
+}
+
+/// Finds the highest of the refs under `prefix` whose names match the glob
+/// `pattern` and pass `filter`, comparing their names as versions.
+fn highest_matching_ref(
+    repo: &git2::Repository,
+    prefix: &str,
+    pattern: &str,
+    filter: impl Fn(&str) -> bool,
+) -> CargoResult<Option<String>> {
+    let pattern = glob::Pattern::new(pattern)?;
+    let mut highest: Option<String> = None;
+    for reference in repo.references_glob(&format!("{prefix}*"))? {
+        let reference = reference?;
+        let Some(refname) = reference.name() else {
+            continue;
+        };
+        let name = &refname[prefix.len()..];
+        if !filter(name) || !pattern.matches(name) {
+            continue;
+        }
+        if highest
+            .as_deref()
+            .map_or(true, |h| cmp_ref_versions(name, &h[prefix.len()..]).is_gt())
+        {
+            highest = Some(refname.to_owned());
+        }
+    }
+    Ok(highest)
+}
+
+/// Compares the names of refs as versions, so that `v1.10.0` is higher than
+/// `v1.9.0` and `v1.0.0-rc.1` is lower than `v1.0.0`.
+///
+/// Names that aren't semver versions after a prefix like `v` or `release/`
+/// compare their runs of digits as numbers instead.
+fn cmp_ref_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let version = |name: &str| {
+        let start = name.find(|c: char| c.is_ascii_digit())?;
+        semver::Version::parse(&name[start..]).ok()
+    };
+    if let (Some(va), Some(vb)) = (version(a), version(b)) {
+        return va.cmp(&vb).then_with(|| a.cmp(b));
+    }
+
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let digits = |s: &[u8]| s.iter().take_while(|c| c.is_ascii_digit()).count();
+                let (na, rest_a) = a.split_at(digits(a));
+                let (nb, rest_b) = b.split_at(digits(b));
+                let na = &na[na.iter().take_while(|&&c| c == b'0').count()..];
+                let nb = &nb[nb.iter().take_while(|&&c| c == b'0').count()..];
+                let ord = na.len().cmp(&nb.len()).then_with(|| na.cmp(nb));
+                if ord.is_ne() {
+                    return ord;
+                }
+                (a, b) = (rest_a, rest_b);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(y);
+                }
+                (a, b) = (&a[1..], &b[1..]);
+            }
+        }
+    }
+}
+
 impl<'a> GitCheckout<'a> {
     /// Creates an instance of [`GitCheckout`]. This doesn't imply the checkout
     /// is done. Use [`GitCheckout::is_fresh`] to check.
@@ -278,6 +422,7 @@ impl<'a> GitCheckout<'a> {
         into: &Path,
         database: &'a GitDatabase,
         revision: git2::Oid,
+        paths: &[String],
         gctx: &GlobalContext,
     ) -> CargoResult<GitCheckout<'a>> {
         let dirname = into.parent().unwrap();
@@ -328,7 +473,7 @@ impl<'a> GitCheckout<'a> {
         let repo = repo.unwrap();
 
         let checkout = GitCheckout::new(database, revision, repo);
-        checkout.reset(gctx)?;
+        checkout.reset(paths, gctx)?;
         Ok(checkout)
     }
 
@@ -357,7 +502,7 @@ impl<'a> GitCheckout<'a> {
     /// *doesn't* exist, and then once we're done we create the file.
     ///
     /// [`.cargo-ok`]: CHECKOUT_READY_LOCK
-    fn reset(&self, gctx: &GlobalContext) -> CargoResult<()> {
+    fn reset(&self, paths: &[String], gctx: &GlobalContext) -> CargoResult<()> {
         let ok_file = self.path.join(CHECKOUT_READY_LOCK);
         let _ = paths::remove_file(&ok_file);
         info!("reset {} to {}", self.repo.path().display(), self.revision);
@@ -368,7 +513,7 @@ impl<'a> GitCheckout<'a> {
         }
 
         let object = self.repo.find_object(self.revision, None)?;
-        reset(&self.repo, &object, gctx)?;
+        reset(&self.repo, &object, paths, gctx)?;
         paths::create(ok_file)?;
         Ok(())
     }
@@ -379,18 +524,25 @@ impl<'a> GitCheckout<'a> {
     /// Submodules set to `none` won't be fetched.
     ///
     /// [^1]: <https://git-scm.com/docs/git-submodule#Documentation/git-submodule.txt-none>
-    fn update_submodules(&self, gctx: &GlobalContext) -> CargoResult<()> {
-        return update_submodules(&self.repo, gctx, self.remote_url().as_str());
+    ///
+    /// If `paths` isn't empty, only the submodules inside those directories
+    /// are updated.
+    fn update_submodules(&self, paths: &[String], gctx: &GlobalContext) -> CargoResult<()> {
+        return update_submodules(&self.repo, paths, gctx, self.remote_url().as_str());
 
         /// Recursive helper for [`GitCheckout::update_submodules`].
         fn update_submodules(
             repo: &git2::Repository,
+            paths: &[String],
             gctx: &GlobalContext,
             parent_remote_url: &str,
         ) -> CargoResult<()> {
             debug!("update submodules for: {:?}", repo.workdir().unwrap());
 
             for mut child in repo.submodules()? {
+                if !paths.is_empty() && !paths.iter().any(|path| child.path().starts_with(path)) {
+                    continue;
+                }
                 update_submodule(repo, &mut child, gctx, parent_remote_url).with_context(|| {
                     format!(
                         "failed to update submodule `{}`",
@@ -445,7 +597,7 @@ impl<'a> GitCheckout<'a> {
             let mut repo = match head_and_repo {
                 Ok((head, repo)) => {
                     if child.head_id() == head {
-                        return update_submodules(&repo, gctx, &child_remote_url);
+                        return update_submodules(&repo, &[], gctx, &child_remote_url);
                     }
                     repo
                 }
@@ -472,8 +624,8 @@ impl<'a> GitCheckout<'a> {
             })?;
 
             let obj = repo.find_object(head, None)?;
-            reset(&repo, &obj, gctx)?;
-            update_submodules(&repo, gctx, &child_remote_url)
+            reset(&repo, &obj, &[], gctx)?;
+            update_submodules(&repo, &[], gctx, &child_remote_url)
         }
     }
 }
@@ -800,9 +952,27 @@ where
 /// `git reset --hard` to the given `obj` for the `repo`.
 ///
 /// The `obj` is a commit-ish to which the head should be moved.
-fn reset(repo: &git2::Repository, obj: &git2::Object<'_>, gctx: &GlobalContext) -> CargoResult<()> {
+///
+/// If `paths` isn't empty, only those directories and the files at the root
+/// of the tree are checked out, like the cone mode of `git sparse-checkout`.
+fn reset(
+    repo: &git2::Repository,
+    obj: &git2::Object<'_>,
+    paths: &[String],
+    gctx: &GlobalContext,
+) -> CargoResult<()> {
     let mut pb = Progress::new("Checkout", gctx);
     let mut opts = git2::build::CheckoutBuilder::new();
+    if !paths.is_empty() {
+        for path in paths {
+            opts.path(path);
+        }
+        for entry in obj.peel_to_tree()?.iter() {
+            if entry.kind() == Some(ObjectType::Blob) {
+                opts.path(entry.name_bytes());
+            }
+        }
+    }
     opts.progress(|_, cur, max| {
         drop(pb.tick(cur, max, ""));
     });
@@ -931,82 +1101,14 @@ pub fn fetch(
     gctx: &GlobalContext,
     remote_kind: RemoteKind,
 ) -> CargoResult<()> {
-    if gctx.frozen() {
-        anyhow::bail!(
-            "attempting to update a git repository, but --frozen \
-             was specified"
-        )
-    }
-    if !gctx.network_allowed() {
-        anyhow::bail!("can't update a git repository in the offline mode")
-    }
-
-    let shallow = remote_kind.to_shallow_setting(repo.is_shallow(), gctx);
-
-    let oid_to_fetch = match github_fast_path(repo, remote_url, reference, gctx) {
-        Ok(FastPathRev::UpToDate) => return Ok(()),
-        Ok(FastPathRev::NeedsFetch(rev)) => Some(rev),
-        Ok(FastPathRev::Indeterminate) => None,
-        Err(e) => {
-            debug!("failed to check github {:?}", e);
-            None
-        }
+    let Some((refspecs, tags, shallow)) =
+        fetch_refspecs(repo, remote_url, reference, gctx, remote_kind)?
+    else {
+        return Ok(());
     };
 
-    maybe_gc_repo(repo, gctx)?;
-
-    clean_repo_temp_files(repo);
-
-    // Translate the reference desired here into an actual list of refspecs
-    // which need to get fetched. Additionally record if we're fetching tags.
-    let mut refspecs = Vec::new();
-    let mut tags = false;
-    // The `+` symbol on the refspec means to allow a forced (fast-forward)
-    // update which is needed if there is ever a force push that requires a
-    // fast-forward.
-    match reference {
-        // For branches and tags we can fetch simply one reference and copy it
-        // locally, no need to fetch other branches/tags.
-        GitReference::Branch(b) => {
-            refspecs.push(format!("+refs/heads/{0}:refs/remotes/origin/{0}", b));
-        }
-
-        GitReference::Tag(t) => {
-            refspecs.push(format!("+refs/tags/{0}:refs/remotes/origin/tags/{0}", t));
-        }
-
-        GitReference::DefaultBranch => {
-            refspecs.push(String::from("+HEAD:refs/remotes/origin/HEAD"));
-        }
-
-        GitReference::Rev(rev) => {
-            if rev.starts_with("refs/") {
-                refspecs.push(format!("+{0}:{0}", rev));
-            } else if let Some(oid_to_fetch) = oid_to_fetch {
-                refspecs.push(format!("+{0}:refs/commit/{0}", oid_to_fetch));
-            } else if !matches!(shallow, gix::remote::fetch::Shallow::NoChange)
-                && rev.parse::<Oid>().is_ok()
-            {
-                // There is a specific commit to fetch and we will do so in shallow-mode only
-                // to not disturb the previous logic.
-                // Note that with typical settings for shallowing, we will just fetch a single `rev`
-                // as single commit.
-                // The reason we write to `refs/remotes/origin/HEAD` is that it's of special significance
-                // when during `GitReference::resolve()`, but otherwise it shouldn't matter.
-                refspecs.push(format!("+{0}:refs/remotes/origin/HEAD", rev));
-            } else {
-                // We don't know what the rev will point to. To handle this
-                // situation we fetch all branches and tags, and then we pray
-                // it's somewhere in there.
-                refspecs.push(String::from("+refs/heads/*:refs/remotes/origin/*"));
-                refspecs.push(String::from("+HEAD:refs/remotes/origin/HEAD"));
-                tags = true;
-            }
-        }
-    }
-
     if let Some(true) = gctx.net_config()?.git_fetch_with_cli {
-        return fetch_with_cli(repo, remote_url, &refspecs, tags, gctx);
+        return fetch_with_cli(repo, remote_url, &refspecs, tags, shallow, gctx);
     }
 
     if gctx.cli_unstable().gitoxide.map_or(false, |git| git.fetch) {
@@ -1114,8 +1216,12 @@ pub fn fetch(
             if tags {
                 opts.download_tags(git2::AutotagOption::All);
             }
+            // libgit2's local transport doesn't support shallow fetches, so
+            // fetch everything from local repositories instead.
             if let gix::remote::fetch::Shallow::DepthAtRemote(depth) = shallow {
-                opts.depth(0i32.saturating_add_unsigned(depth.get()));
+                if !remote_url.starts_with("file:") {
+                    opts.depth(0i32.saturating_add_unsigned(depth.get()));
+                }
             }
             // The `fetch` operation here may fail spuriously due to a corrupt
             // repository. It could also fail, however, for a whole slew of other
@@ -1159,6 +1265,105 @@ pub fn fetch(
     }
 }
 
+/// Prepares `repo` for fetching the given git `reference`, and turns the
+/// [`GitReference`] into refspecs accordingly.
+///
+/// Returns the refspecs, whether tags need to be fetched, and the shallow
+/// setting to use, or `None` if the repository is known to be up-to-date.
+fn fetch_refspecs(
+    repo: &mut git2::Repository,
+    remote_url: &str,
+    reference: &GitReference,
+    gctx: &GlobalContext,
+    remote_kind: RemoteKind,
+) -> CargoResult<Option<(Vec<String>, bool, gix::remote::fetch::Shallow)>> {
+    if gctx.frozen() {
+        anyhow::bail!(
+            "attempting to update a git repository, but --frozen \
+             was specified"
+        )
+    }
+    if !gctx.network_allowed() {
+        anyhow::bail!("can't update a git repository in the offline mode")
+    }
+
+    let shallow = remote_kind.to_shallow_setting(repo.is_shallow(), gctx);
+
+    let oid_to_fetch = match github_fast_path(repo, remote_url, reference, gctx) {
+        Ok(FastPathRev::UpToDate) => return Ok(None),
+        Ok(FastPathRev::NeedsFetch(rev)) => Some(rev),
+        Ok(FastPathRev::Indeterminate) => None,
+        Err(e) => {
+            debug!("failed to check github {:?}", e);
+            None
+        }
+    };
+
+    maybe_gc_repo(repo, gctx)?;
+
+    clean_repo_temp_files(repo);
+
+    // Translate the reference desired here into an actual list of refspecs
+    // which need to get fetched. Additionally record if we're fetching tags.
+    let mut refspecs = Vec::new();
+    let mut tags = false;
+    // The `+` symbol on the refspec means to allow a forced (fast-forward)
+    // update which is needed if there is ever a force push that requires a
+    // fast-forward.
+    match reference {
+        // For branches and tags we can fetch simply one reference and copy it
+        // locally, no need to fetch other branches/tags.
+        // For patterns we fetch all branches or tags, and pick the highest
+        // matching one in `resolve_ref`.
+        GitReference::Branch(b) if is_ref_glob(b) => {
+            refspecs.push(String::from("+refs/heads/*:refs/remotes/origin/*"));
+        }
+
+        GitReference::Tag(t) if is_ref_glob(t) => {
+            refspecs.push(String::from("+refs/tags/*:refs/remotes/origin/tags/*"));
+        }
+
+        GitReference::Branch(b) => {
+            refspecs.push(format!("+refs/heads/{0}:refs/remotes/origin/{0}", b));
+        }
+
+        GitReference::Tag(t) => {
+            refspecs.push(format!("+refs/tags/{0}:refs/remotes/origin/tags/{0}", t));
+        }
+
+        GitReference::DefaultBranch => {
+            refspecs.push(String::from("+HEAD:refs/remotes/origin/HEAD"));
+        }
+
+        GitReference::Rev(rev) => {
+            if rev.starts_with("refs/") {
+                refspecs.push(format!("+{0}:{0}", rev));
+            } else if let Some(oid_to_fetch) = oid_to_fetch {
+                refspecs.push(format!("+{0}:refs/commit/{0}", oid_to_fetch));
+            } else if !matches!(shallow, gix::remote::fetch::Shallow::NoChange)
+                && rev.parse::<Oid>().is_ok()
+            {
+                // There is a specific commit to fetch and we will do so in shallow-mode only
+                // to not disturb the previous logic.
+                // Note that with typical settings for shallowing, we will just fetch a single `rev`
+                // as single commit.
+                // The reason we write to `refs/remotes/origin/HEAD` is that it's of special significance
+                // when during `GitReference::resolve()`, but otherwise it shouldn't matter.
+                refspecs.push(format!("+{0}:refs/remotes/origin/HEAD", rev));
+            } else {
+                // We don't know what the rev will point to. To handle this
+                // situation we fetch all branches and tags, and then we pray
+                // it's somewhere in there.
+                refspecs.push(String::from("+refs/heads/*:refs/remotes/origin/*"));
+                refspecs.push(String::from("+HEAD:refs/remotes/origin/HEAD"));
+                tags = true;
+            }
+        }
+    }
+
+    Ok(Some((refspecs, tags, shallow)))
+}
+
 /// `gitoxide` uses shallow locks to assure consistency when fetching to and to avoid races, and to write
 /// files atomically.
 /// Cargo has its own lock files and doesn't need that mechanism for race protection, so a stray lock means
@@ -1186,13 +1391,33 @@ fn fetch_with_cli(
     url: &str,
     refspecs: &[String],
     tags: bool,
+    shallow: gix::remote::fetch::Shallow,
     gctx: &GlobalContext,
 ) -> CargoResult<()> {
+    let cmd = cli_fetch_command(repo, url, refspecs, tags, shallow, gctx);
+    gctx.shell()
+        .verbose(|s| s.status("Running", &cmd.to_string()))?;
+    cmd.exec()?;
+    Ok(())
+}
+
+/// Builds the `git fetch` command [`fetch_with_cli`] runs.
+fn cli_fetch_command(
+    repo: &git2::Repository,
+    url: &str,
+    refspecs: &[String],
+    tags: bool,
+    shallow: gix::remote::fetch::Shallow,
+    gctx: &GlobalContext,
+) -> ProcessBuilder {
     let mut cmd = ProcessBuilder::new("git");
     cmd.arg("fetch");
     if tags {
         cmd.arg("--tags");
     }
+    if let gix::remote::fetch::Shallow::DepthAtRemote(depth) = shallow {
+        cmd.arg(format!("--depth={depth}"));
+    }
     match gctx.shell().verbosity() {
         Verbosity::Normal => {}
         Verbosity::Verbose => {
@@ -1218,10 +1443,7 @@ fn fetch_with_cli(
         .env_remove("GIT_OBJECT_DIRECTORY")
         .env_remove("GIT_ALTERNATE_OBJECT_DIRECTORIES")
         .cwd(repo.path());
-    gctx.shell()
-        .verbose(|s| s.status("Running", &cmd.to_string()))?;
-    cmd.exec()?;
-    Ok(())
+    cmd
 }
 
 /// Attempts to `git gc` a repository.
@@ -1404,6 +1626,10 @@ fn github_fast_path(
     let local_object = resolve_ref(reference, repo).ok();
 
     let github_branch_name = match reference {
+        GitReference::Branch(name) | GitReference::Tag(name) if is_ref_glob(name) => {
+            debug!("can't use github fast path with the pattern `{}`", name);
+            return Ok(FastPathRev::Indeterminate);
+        }
         GitReference::Branch(branch) => branch,
         GitReference::Tag(tag) => tag,
         GitReference::DefaultBranch => "HEAD",
@@ -1535,7 +1761,7 @@ fn is_short_hash_of(rev: &str, oid: Oid) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::absolute_submodule_url;
+    use super::{absolute_submodule_url, cmp_ref_versions};
 
     #[test]
     fn test_absolute_submodule_url() {
@@ -1630,6 +1856,32 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_cmp_ref_versions() {
+        let mut names = vec![
+            "v1.10.0",
+            "v1.9.0",
+            "v1.10.0-rc.1",
+            "v2.0.0",
+            "release/1.10",
+            "release/1.9",
+            "release/1.09.1",
+        ];
+        names.sort_by(|a, b| cmp_ref_versions(a, b));
+        assert_eq!(
+            names,
+            [
+                "release/1.9",
+                "release/1.09.1",
+                "release/1.10",
+                "v1.9.0",
+                "v1.10.0-rc.1",
+                "v1.10.0",
+                "v2.0.0",
+            ]
+        );
+    }
 }
 
 /// Turns a full commit hash revision into an oid.
@@ -141,6 +141,17 @@ impl<'gctx> Source for DependencyConfusionThreatOverlaySource<'gctx> {
         self.remote.is_yanked(pkg)
     }
 
+    fn cached_checksum(
+        &mut self,
+        pkg: crate::core::PackageId,
+    ) -> crate::CargoResult<Option<String>> {
+        let local_pkg = pkg.map_source(self.remote.source_id(), self.local.source_id());
+        match self.local.cached_checksum(local_pkg)? {
+            Some(checksum) => Ok(Some(checksum)),
+            None => self.remote.cached_checksum(pkg),
+        }
+    }
+
     fn block_until_ready(&mut self) -> crate::CargoResult<()> {
         self.local.block_until_ready()?;
         self.remote.block_until_ready()
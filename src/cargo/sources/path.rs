@@ -141,6 +141,7 @@ impl<'gctx> Source for PathSource<'gctx> {
                 QueryKind::Exact => dep.matches(s),
                 QueryKind::Alternatives => true,
                 QueryKind::Normalized => dep.matches(s),
+                QueryKind::OfflineUnavailable => false,
             };
             if matched {
                 f(IndexSummary::Candidate(s.clone()))
@@ -316,6 +317,7 @@ impl<'gctx> Source for RecursivePathSource<'gctx> {
                 QueryKind::Exact => dep.matches(s),
                 QueryKind::Alternatives => true,
                 QueryKind::Normalized => dep.matches(s),
+                QueryKind::OfflineUnavailable => false,
             };
             if matched {
                 f(IndexSummary::Candidate(s.clone()))
@@ -21,6 +21,7 @@ use std::fmt::Write as FmtWrite;
 use std::fs::{self, File, OpenOptions};
 use std::io::prelude::*;
 use std::io::SeekFrom;
+use std::path::PathBuf;
 use std::str;
 
 const CRATE_TEMPLATE: &str = "{crate}";
@@ -64,7 +65,36 @@ pub(super) fn download(
         }
     }
 
-    let mut url = registry_config.dl;
+    let url = download_url(registry_config.dl, pkg, checksum);
+
+    let authorization = if registry_config.auth_required {
+        Some(auth::auth_token(
+            gctx,
+            &pkg.source_id(),
+            None,
+            Operation::Read,
+            vec![],
+            true,
+        )?)
+    } else {
+        None
+    };
+
+    let partial = cache_path.join(format!("{}.partial", pkg.tarball_name()));
+
+    Ok(MaybeLock::Download {
+        url,
+        descriptor: pkg.to_string(),
+        authorization: authorization,
+        mirrors: Vec::new(),
+        partial: Some(partial),
+    })
+}
+
+/// Expands the `dl` template of the [`RegistryConfig`] into the URL of the
+/// `.crate` file of `pkg`.
+pub(super) fn download_url(dl: String, pkg: PackageId, checksum: &str) -> String {
+    let mut url = dl;
     if !url.contains(CRATE_TEMPLATE)
         && !url.contains(VERSION_TEMPLATE)
         && !url.contains(PREFIX_TEMPLATE)
@@ -88,25 +118,7 @@ pub(super) fn download(
             .replace(LOWER_PREFIX_TEMPLATE, &prefix.to_lowercase())
             .replace(CHECKSUM_TEMPLATE, checksum);
     }
-
-    let authorization = if registry_config.auth_required {
-        Some(auth::auth_token(
-            gctx,
-            &pkg.source_id(),
-            None,
-            Operation::Read,
-            vec![],
-            true,
-        )?)
-    } else {
-        None
-    };
-
-    Ok(MaybeLock::Download {
-        url,
-        descriptor: pkg.to_string(),
-        authorization: authorization,
-    })
+    url
 }
 
 /// Verifies the integrity of `data` with `checksum` and persists it under the
@@ -153,6 +165,19 @@ pub(super) fn finish_download(
     Ok(dst)
 }
 
+/// Returns the path to the tarball of `pkg` under the directory at
+/// `cache_path`, if it has been already downloaded.
+///
+/// This is primarily called by [`RegistryData::downloaded_crate_path`](super::RegistryData::downloaded_crate_path).
+pub(super) fn downloaded_crate_path(
+    cache_path: &Filesystem,
+    gctx: &GlobalContext,
+    pkg: PackageId,
+) -> Option<PathBuf> {
+    is_crate_downloaded(cache_path, gctx, pkg)
+        .then(|| cache_path.join(pkg.tarball_name()).into_path_unlocked())
+}
+
 /// Checks if a tarball of `pkg` has been already downloaded under the
 /// directory at `cache_path`.
 ///
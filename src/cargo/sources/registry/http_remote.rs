@@ -8,8 +8,8 @@ use crate::sources::registry::{LoadResponse, RegistryConfig, RegistryData};
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::errors::{CargoResult, HttpNotSuccessful};
 use crate::util::interning::InternedString;
-use crate::util::network::http::http_handle;
-use crate::util::network::retry::{Retry, RetryResult};
+use crate::util::network::http::registry_http_handle;
+use crate::util::network::retry::{fallback_to_cache, Retry, RetryResult};
 use crate::util::network::sleep::SleepTracker;
 use crate::util::{auth, Filesystem, GlobalContext, IntoUrl, Progress, ProgressStyle};
 use anyhow::Context as _;
@@ -34,6 +34,15 @@ const LAST_MODIFIED: &'static str = "last-modified";
 const WWW_AUTHENTICATE: &'static str = "www-authenticate";
 const IF_NONE_MATCH: &'static str = "if-none-match";
 const IF_MODIFIED_SINCE: &'static str = "if-modified-since";
+const A_IM: &'static str = "a-im";
+const IM: &'static str = "im";
+
+/// The instance manipulation of [RFC 3229] requested by `-Zsparse-delta`. The
+/// delta of an index file has the lines of the versions that were added or
+/// changed, see [`LoadResponse::Delta`].
+///
+/// [RFC 3229]: https://www.rfc-editor.org/rfc/rfc3229
+const INDEX_DELTA: &'static str = "cargo-index-delta";
 
 const UNKNOWN: &'static str = "Unknown";
 
@@ -70,8 +79,17 @@ pub struct HttpRegistry<'gctx> {
     gctx: &'gctx GlobalContext,
 
     /// Store the server URL without the protocol prefix (sparse+)
+    ///
+    /// This is the URL of a mirror after falling back to it.
     url: Url,
 
+    /// URLs of the mirrors of the index from `registries.<name>.mirrors`,
+    /// without the protocol prefix.
+    mirrors: Vec<Url>,
+
+    /// How many of the [`HttpRegistry::mirrors`] have been fallen back to.
+    mirrors_used: usize,
+
     /// HTTP multi-handle for asynchronous/parallel requests.
     multi: Multi,
 
@@ -106,6 +124,11 @@ pub struct HttpRegistry<'gctx> {
     /// Headers received with an HTTP 401.
     auth_error_headers: Vec<String>,
 
+    /// Has a failed index file download fallen back to the cached copy yet?
+    ///
+    /// Used to only warn about it once.
+    fell_back_to_cache: bool,
+
     /// Disables status messages.
     quiet: bool,
 }
@@ -143,6 +166,9 @@ struct Download<'gctx> {
     /// The path of the package that we're downloading.
     path: PathBuf,
 
+    /// The URL that we're downloading from.
+    url: String,
+
     /// Actual downloaded data, updated throughout the lifetime of this download.
     data: RefCell<Vec<u8>>,
 
@@ -158,6 +184,8 @@ struct Download<'gctx> {
 struct Headers {
     last_modified: Option<String>,
     etag: Option<String>,
+    /// The instance manipulations applied to a delta response.
+    im: Option<String>,
     www_authenticate: Vec<String>,
     /// All headers, including explicit headers above.
     all: Vec<String>,
@@ -166,6 +194,8 @@ struct Headers {
 /// HTTP status code [`HttpRegistry`] cares about.
 enum StatusCode {
     Success,
+    /// HTTP 226 IM Used, for a delta of the cached file.
+    Delta,
     NotModified,
     NotFound,
     Unauthorized,
@@ -202,6 +232,7 @@ impl<'gctx> HttpRegistry<'gctx> {
             .expect("sparse registry needs sparse+ prefix")
             .into_url()
             .expect("a url with the sparse+ stripped should still be valid");
+        let mirrors = mirrors(gctx, source_id)?;
 
         Ok(HttpRegistry {
             name: name.into(),
@@ -210,6 +241,8 @@ impl<'gctx> HttpRegistry<'gctx> {
             source_id,
             gctx,
             url,
+            mirrors,
+            mirrors_used: 0,
             multi: Multi::new(),
             multiplexing: false,
             downloads: Downloads {
@@ -233,6 +266,7 @@ impl<'gctx> HttpRegistry<'gctx> {
             auth_required: false,
             login_url: None,
             auth_error_headers: vec![],
+            fell_back_to_cache: false,
             quiet: false,
         })
     }
@@ -313,13 +347,14 @@ impl<'gctx> HttpRegistry<'gctx> {
             );
             let mut handle = self.multi.remove(handle)?;
             let data = download.data.take();
-            let url = self.full_url(&download.path);
+            let url = &download.url;
             let result = match download.retry.r#try(|| {
                 result.with_context(|| format!("failed to download from `{}`", url))?;
                 let code = handle.response_code()?;
                 // Keep this list of expected status codes in sync with the codes handled in `load`
                 let code = match code {
                     200 => StatusCode::Success,
+                    226 => StatusCode::Delta,
                     304 => StatusCode::NotModified,
                     401 => StatusCode::Unauthorized,
                     404 | 410 | 451 => StatusCode::NotFound,
@@ -340,6 +375,20 @@ impl<'gctx> HttpRegistry<'gctx> {
                     data,
                     header_map: download.header_map.take(),
                 }),
+                RetryResult::Err(e) if self.mirrors_used < self.mirrors.len() => {
+                    // Other downloads from the same URL may have failed too,
+                    // only the first of them moves on to the next mirror.
+                    if download.url.starts_with(self.url.as_str()) {
+                        let mirror = self.mirrors[self.mirrors_used].clone();
+                        self.mirrors_used += 1;
+                        self.gctx
+                            .shell()
+                            .warn(format!("{e:#}\nfalling back to mirror `{mirror}`"))?;
+                        self.url = mirror;
+                    }
+                    // The next `load` of the path fetches it again.
+                    continue;
+                }
                 RetryResult::Err(e) => Err(e),
                 RetryResult::Retry(sleep) => {
                     debug!(target: "network", "download retry {:?} for {sleep}ms", download.path);
@@ -438,12 +487,26 @@ impl<'gctx> HttpRegistry<'gctx> {
             LoadResponse::NotFound => {
                 Poll::Ready(Err(anyhow::anyhow!("config.json not found in registry")))
             }
-            LoadResponse::CacheValid => Poll::Ready(Err(crate::util::internal(
-                "config.json is never stored in the index cache",
-            ))),
+            LoadResponse::CacheValid | LoadResponse::Delta { .. } => Poll::Ready(Err(
+                crate::util::internal("config.json is never stored in the index cache"),
+            )),
         }
     }
 
+    /// Returns the mirrors to download the `.crate` file of `pkg` from that
+    /// haven't been fallen back to yet.
+    fn mirror_downloads(&self, pkg: PackageId, checksum: &str) -> Vec<RegistryMirror> {
+        self.mirrors[self.mirrors_used..]
+            .iter()
+            .map(|mirror| RegistryMirror {
+                source_id: self.source_id,
+                url: mirror.clone(),
+                pkg,
+                checksum: checksum.to_string(),
+            })
+            .collect()
+    }
+
     /// Moves failed [`Download`]s that are ready to retry to the pending queue.
     fn add_sleepers(&mut self) -> CargoResult<()> {
         for (dl, handle) in self.downloads.sleeping.to_retry() {
@@ -520,7 +583,22 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
 
         if let Some(result) = self.downloads.results.remove(path) {
             let result =
-                result.with_context(|| format!("download of {} failed", path.display()))?;
+                match result.with_context(|| format!("download of {} failed", path.display())) {
+                    Ok(result) => result,
+                    // Carry on with the copy fetched by an earlier run, if there is one.
+                    Err(e) if index_version.is_some() && fallback_to_cache(self.gctx)? => {
+                        if !self.fell_back_to_cache {
+                            self.fell_back_to_cache = true;
+                            self.gctx.shell().warn(format!(
+                                "{e:#}\nfalling back to the cached index of `{}`",
+                                self.source_id.display_registry_name()
+                            ))?;
+                        }
+                        self.fresh.insert(path.to_path_buf());
+                        return Poll::Ready(Ok(LoadResponse::CacheValid));
+                    }
+                    Err(e) => return Poll::Ready(Err(e)),
+                };
 
             let is_new = self.fresh.insert(path.to_path_buf());
             assert!(
@@ -531,21 +609,39 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
 
             // The status handled here need to be kept in sync with the codes handled
             // in `handle_completed_downloads`
+            let response_index_version = if let Some(etag) = &result.header_map.etag {
+                format!("{}: {}", ETAG, etag)
+            } else if let Some(lm) = &result.header_map.last_modified {
+                format!("{}: {}", LAST_MODIFIED, lm)
+            } else {
+                UNKNOWN.to_string()
+            };
             match result.response_code {
                 StatusCode::Success => {
-                    let response_index_version = if let Some(etag) = result.header_map.etag {
-                        format!("{}: {}", ETAG, etag)
-                    } else if let Some(lm) = result.header_map.last_modified {
-                        format!("{}: {}", LAST_MODIFIED, lm)
-                    } else {
-                        UNKNOWN.to_string()
-                    };
                     trace!("index file version: {}", response_index_version);
                     return Poll::Ready(Ok(LoadResponse::Data {
                         raw_data: result.data,
                         index_version: Some(response_index_version),
                     }));
                 }
+                StatusCode::Delta => {
+                    // A delta only makes sense on top of the cached file it
+                    // was requested for.
+                    if index_version.is_none()
+                        || result.header_map.im.as_deref() != Some(INDEX_DELTA)
+                        || result.header_map.etag.is_none()
+                    {
+                        return Poll::Ready(Err(anyhow::anyhow!(
+                            "server sent an unexpected delta (HTTP 226) of `{}`",
+                            path.display()
+                        )));
+                    }
+                    trace!("index file delta to version: {}", response_index_version);
+                    return Poll::Ready(Ok(LoadResponse::Delta {
+                        raw_data: result.data,
+                        index_version: Some(response_index_version),
+                    }));
+                }
                 StatusCode::NotModified => {
                     // Not Modified: the data in the cache is still the latest.
                     if index_version.is_none() {
@@ -626,7 +722,7 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
         // Looks like we're going to have to do a network request.
         self.start_fetch()?;
 
-        let mut handle = http_handle(self.gctx)?;
+        let mut handle = registry_http_handle(self.gctx, self.source_id)?;
         let full_url = self.full_url(path);
         debug!(target: "network", "fetch {}", full_url);
         handle.get(true)?;
@@ -646,7 +742,13 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
         if let Some(index_version) = index_version {
             if let Some((key, value)) = index_version.split_once(':') {
                 match key {
-                    ETAG => headers.append(&format!("{}: {}", IF_NONE_MATCH, value.trim()))?,
+                    ETAG => {
+                        headers.append(&format!("{}: {}", IF_NONE_MATCH, value.trim()))?;
+                        // Deltas are computed from the `ETag` of the cached file.
+                        if self.gctx.cli_unstable().sparse_delta {
+                            headers.append(&format!("{}: {}", A_IM, INDEX_DELTA))?;
+                        }
+                    }
                     LAST_MODIFIED => {
                         headers.append(&format!("{}: {}", IF_MODIFIED_SINCE, value.trim()))?
                     }
@@ -654,7 +756,8 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
                 }
             }
         }
-        if self.auth_required {
+        // The token of the registry is never sent to its mirrors.
+        if self.auth_required && self.mirrors_used == 0 {
             let authorization = auth::auth_token(
                 self.gctx,
                 &self.source_id,
@@ -705,6 +808,7 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
                         match tag.to_ascii_lowercase().as_str() {
                             LAST_MODIFIED => header_map.last_modified = Some(value.to_string()),
                             ETAG => header_map.etag = Some(value.to_string()),
+                            IM => header_map.im = Some(value.to_string()),
                             WWW_AUTHENTICATE => header_map.www_authenticate.push(value.to_string()),
                             _ => {}
                         }
@@ -718,6 +822,7 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
         let dl = Download {
             token,
             path: path.to_path_buf(),
+            url: full_url,
             data: RefCell::new(Vec::new()),
             header_map: Default::default(),
             retry: Retry::new(self.gctx)?,
@@ -758,14 +863,18 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
             }
         };
 
-        download::download(
+        let mut lock = download::download(
             &self.cache_path,
             &self.gctx,
             self.name.clone(),
             pkg,
             checksum,
             registry_config,
-        )
+        )?;
+        if let MaybeLock::Download { mirrors, .. } = &mut lock {
+            *mirrors = self.mirror_downloads(pkg, checksum);
+        }
+        Ok(lock)
     }
 
     fn finish_download(
@@ -788,6 +897,10 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
         download::is_crate_downloaded(&self.cache_path, &self.gctx, pkg)
     }
 
+    fn downloaded_crate_path(&self, pkg: PackageId) -> Option<PathBuf> {
+        download::downloaded_crate_path(&self.cache_path, &self.gctx, pkg)
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         trace!(target: "network",
             "block_until_ready: {} transfers pending",
@@ -829,6 +942,99 @@ impl<'gctx> RegistryData for HttpRegistry<'gctx> {
     }
 }
 
+/// Gets the mirrors configured for the registry `source_id`.
+///
+/// These are `registries.<name>.mirrors`, or `registries.crates-io.mirrors`
+/// for crates.io, and require `-Zunstable-options`.
+fn mirrors(gctx: &GlobalContext, source_id: SourceId) -> CargoResult<Vec<Url>> {
+    if !gctx.cli_unstable().unstable_options {
+        let name = if source_id.is_crates_io() {
+            Some("crates-io")
+        } else {
+            source_id.alt_registry_key()
+        };
+        if let Some(name) = name {
+            let key = format!("registries.{name}.mirrors");
+            if let Ok(Some(_)) = gctx.get::<Option<Vec<String>>>(&key) {
+                gctx.shell().warn(format!(
+                    "ignoring `{key}` config, pass `-Zunstable-options` to enable it"
+                ))?;
+            }
+        }
+        return Ok(Vec::new());
+    }
+    let mirrors = if source_id.is_crates_io() {
+        gctx.get::<Option<Vec<String>>>("registries.crates-io.mirrors")?
+    } else {
+        auth::registry_credential_config_raw(gctx, &source_id)?.and_then(|cfg| cfg.mirrors)
+    };
+    mirrors
+        .unwrap_or_default()
+        .iter()
+        .map(|mirror| {
+            let url = mirror.strip_prefix("sparse+").unwrap_or(mirror);
+            // Like the registry url, paths are concatenated to the mirror url.
+            if !url.ends_with('/') {
+                anyhow::bail!("registry mirror url must end in a slash `/`: {mirror}")
+            }
+            url.into_url()
+        })
+        .collect()
+}
+
+/// A mirror of a registry to download the `.crate` file of a package from.
+///
+/// The registry configuration of the mirror, which tells where its `.crate`
+/// files are, is only fetched once the download falls back to it.
+pub struct RegistryMirror {
+    source_id: SourceId,
+    url: Url,
+    pkg: PackageId,
+    checksum: String,
+}
+
+impl RegistryMirror {
+    /// Fetches the configuration of the mirror and returns the URL to
+    /// download the `.crate` file from.
+    pub fn download_url(&self, gctx: &GlobalContext) -> CargoResult<String> {
+        let config = fetch_config(gctx, self.source_id, &self.url)?;
+        Ok(download::download_url(config.dl, self.pkg, &self.checksum))
+    }
+}
+
+impl std::fmt::Display for RegistryMirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.url.fmt(f)
+    }
+}
+
+/// Fetches the registry configuration of the registry `mirror` of
+/// `source_id` right away.
+fn fetch_config(
+    gctx: &GlobalContext,
+    source_id: SourceId,
+    mirror: &Url,
+) -> CargoResult<RegistryConfig> {
+    let url = format!("{mirror}{}", RegistryConfig::NAME);
+    let mut handle = registry_http_handle(gctx, source_id)?;
+    handle.get(true)?;
+    handle.url(&url)?;
+    handle.follow_location(true)?;
+    let mut body = Vec::new();
+    let mut transfer = handle.transfer();
+    transfer.write_function(|data| {
+        body.extend_from_slice(data);
+        Ok(data.len())
+    })?;
+    transfer.perform()?;
+    drop(transfer);
+    let code = handle.response_code()?;
+    if code != 200 {
+        anyhow::bail!("failed to fetch `{url}`, got {code}");
+    }
+    Ok(serde_json::from_slice(&body)?)
+}
+
 impl<'gctx> Downloads<'gctx> {
     /// Updates the state of the progress bar for downloads.
     fn tick(&self) -> CargoResult<()> {
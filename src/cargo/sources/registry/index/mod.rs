@@ -27,21 +27,29 @@ use crate::sources::registry::{LoadResponse, RegistryData};
 use crate::util::interning::InternedString;
 use crate::util::IntoUrl;
 use crate::util::{internal, CargoResult, Filesystem, GlobalContext, OptVersionReq};
+use anyhow::format_err;
 use cargo_util::registry::make_dep_path;
 use cargo_util_schemas::manifest::RustVersion;
+use lazycell::LazyCell;
 use semver::Version;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 use std::str;
 use std::task::{ready, Poll};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 use tracing::{debug, info};
 
 mod cache;
+mod trust;
 use self::cache::CacheManager;
 use self::cache::SummariesCache;
+pub(crate) use self::trust::requires_signatures;
+use self::trust::TrustRoot;
 
 /// The maximum schema version of the `v` field in the index this version of
 /// cargo understands. See [`IndexPackage::v`] for the detail.
@@ -79,6 +87,9 @@ pub struct RegistryIndex<'gctx> {
     gctx: &'gctx GlobalContext,
     /// Manager of on-disk caches.
     cache_manager: CacheManager<'gctx>,
+    /// Keys index entries must be signed with, if the registry is configured
+    /// with `require-signatures`. Loaded on first use.
+    trust_root: LazyCell<Option<Rc<TrustRoot>>>,
 }
 
 /// An internal cache of summaries for a particular package.
@@ -135,6 +146,8 @@ pub enum IndexSummary {
     Offline(Summary),
     /// From a newer schema version and is likely incomplete or inaccurate
     Unsupported(Summary, u32),
+    /// Failed signature verification, with the reason why
+    Unverified(Summary, String),
 }
 
 impl IndexSummary {
@@ -144,7 +157,8 @@ impl IndexSummary {
             IndexSummary::Candidate(sum)
             | IndexSummary::Yanked(sum)
             | IndexSummary::Offline(sum)
-            | IndexSummary::Unsupported(sum, _) => sum,
+            | IndexSummary::Unsupported(sum, _)
+            | IndexSummary::Unverified(sum, _) => sum,
         }
     }
 
@@ -154,7 +168,8 @@ impl IndexSummary {
             IndexSummary::Candidate(sum)
             | IndexSummary::Yanked(sum)
             | IndexSummary::Offline(sum)
-            | IndexSummary::Unsupported(sum, _) => sum,
+            | IndexSummary::Unsupported(sum, _)
+            | IndexSummary::Unverified(sum, _) => sum,
         }
     }
 
@@ -164,6 +179,7 @@ impl IndexSummary {
             IndexSummary::Yanked(s) => IndexSummary::Yanked(f(s)),
             IndexSummary::Offline(s) => IndexSummary::Offline(f(s)),
             IndexSummary::Unsupported(s, v) => IndexSummary::Unsupported(f(s), v.clone()),
+            IndexSummary::Unverified(s, r) => IndexSummary::Unverified(f(s), r.clone()),
         }
     }
 
@@ -173,7 +189,8 @@ impl IndexSummary {
             IndexSummary::Candidate(sum)
             | IndexSummary::Yanked(sum)
             | IndexSummary::Offline(sum)
-            | IndexSummary::Unsupported(sum, _) => sum.package_id(),
+            | IndexSummary::Unsupported(sum, _)
+            | IndexSummary::Unverified(sum, _) => sum.package_id(),
         }
     }
 
@@ -192,6 +209,15 @@ impl IndexSummary {
     pub fn is_offline(&self) -> bool {
         matches!(self, Self::Offline(..))
     }
+
+    /// Returns `true` if the version was published by `time`, or if the
+    /// registry doesn't tell when it was published.
+    pub fn is_published_by(&self, time: OffsetDateTime) -> bool {
+        self.as_summary()
+            .pubtime()
+            .and_then(|pubtime| OffsetDateTime::parse(pubtime, &Rfc3339).ok())
+            .map_or(true, |pubtime| pubtime <= time)
+    }
 }
 
 /// A single line in the index representing a single version of a package.
@@ -233,6 +259,16 @@ pub struct IndexPackage<'a> {
     /// Added in 2023 (see <https://github.com/rust-lang/crates.io/pull/6267>),
     /// can be `None` if published before then or if not set in the manifest.
     rust_version: Option<RustVersion>,
+    /// The time this version was published, as an RFC 3339 timestamp.
+    ///
+    /// Recorded as provenance in `Cargo.lock` starting with lock file
+    /// version 5. Can be `None` for registries that don't report it.
+    pubtime: Option<String>,
+    /// A signature of this entry by the registry, checked against the keys
+    /// of `trusted-keys` when the registry is configured with
+    /// `require-signatures`. See the `trust` module for its format.
+    #[serde(borrow)]
+    sig: Option<Cow<'a, str>>,
     /// The schema version for this entry.
     ///
     /// If this is None, it defaults to version `1`. Entries with unknown
@@ -305,6 +341,7 @@ impl<'gctx> RegistryIndex<'gctx> {
             summaries_cache: HashMap::new(),
             gctx,
             cache_manager: CacheManager::new(path.join(".cache"), gctx),
+            trust_root: LazyCell::new(),
         }
     }
 
@@ -345,9 +382,10 @@ impl<'gctx> RegistryIndex<'gctx> {
         let bindeps = self.gctx.cli_unstable().bindeps;
 
         let source_id = self.source_id;
+        let trust_root = self.trust_root()?;
 
         // First up parse what summaries we have available.
-        let summaries = ready!(self.load_summaries(name, load)?);
+        let summaries = ready!(self.load_summaries(name, load, trust_root.as_deref())?);
 
         // Iterate over our summaries, extract all relevant ones which match our
         // version requirement, and then parse all corresponding rows in the
@@ -356,12 +394,34 @@ impl<'gctx> RegistryIndex<'gctx> {
         // minimize the amount of work being done here and parse as little as
         // necessary.
         let raw_data = &summaries.raw_data;
+
+        // Entries failing signature verification are an error rather than
+        // being skipped, so look for them upfront.
+        if let Some(trust_root) = trust_root.as_deref() {
+            for (_, maybe) in summaries
+                .versions
+                .iter_mut()
+                .filter(|(k, _)| req.matches(k))
+            {
+                if let Ok(IndexSummary::Unverified(summary, reason)) =
+                    maybe.parse(raw_data, source_id, bindeps, Some(trust_root))
+                {
+                    return Poll::Ready(Err(format_err!("{reason}").context(format!(
+                        "failed to verify the signature of `{} v{}` from {}",
+                        summary.name(),
+                        summary.version(),
+                        source_id
+                    ))));
+                }
+            }
+        }
+
         Poll::Ready(Ok(summaries
             .versions
             .iter_mut()
             .filter_map(move |(k, v)| if req.matches(k) { Some(v) } else { None })
             .filter_map(move |maybe| {
-                match maybe.parse(raw_data, source_id, bindeps) {
+                match maybe.parse(raw_data, source_id, bindeps, trust_root.as_deref()) {
                     Ok(sum @ IndexSummary::Candidate(_) | sum @ IndexSummary::Yanked(_)) => {
                         Some(sum)
                     }
@@ -377,6 +437,9 @@ impl<'gctx> RegistryIndex<'gctx> {
                     Ok(IndexSummary::Offline(_)) => {
                         unreachable!("We do not check for off-line until later")
                     }
+                    Ok(IndexSummary::Unverified(..)) => {
+                        unreachable!("We already checked for unverified summaries")
+                    }
                     Err(e) => {
                         info!("failed to parse `{}` registry package: {}", name, e);
                         None
@@ -404,6 +467,7 @@ impl<'gctx> RegistryIndex<'gctx> {
         &mut self,
         name: InternedString,
         load: &mut dyn RegistryData,
+        trust_root: Option<&TrustRoot>,
     ) -> Poll<CargoResult<&mut Summaries>> {
         // If we've previously loaded what versions are present for `name`, just
         // return that since our in-memory cache should still be valid.
@@ -422,6 +486,7 @@ impl<'gctx> RegistryIndex<'gctx> {
             self.source_id,
             load,
             self.gctx.cli_unstable().bindeps,
+            trust_root,
             &self.cache_manager,
         ))?
         .unwrap_or_default();
@@ -429,6 +494,15 @@ impl<'gctx> RegistryIndex<'gctx> {
         Poll::Ready(Ok(self.summaries_cache.get_mut(&name).unwrap()))
     }
 
+    /// Returns the keys index entries must be signed with, if the registry
+    /// requires signatures.
+    fn trust_root(&self) -> CargoResult<Option<Rc<TrustRoot>>> {
+        let trust_root = self.trust_root.try_borrow_with(|| {
+            TrustRoot::load(self.gctx, self.source_id).map(|t| t.map(Rc::new))
+        })?;
+        Ok(trust_root.clone())
+    }
+
     /// Clears the in-memory summaries cache.
     pub fn clear_summaries_cache(&mut self) {
         self.summaries_cache.clear();
@@ -482,7 +556,11 @@ impl<'gctx> RegistryIndex<'gctx> {
         f: &mut dyn FnMut(IndexSummary),
         online: bool,
     ) -> Poll<CargoResult<()>> {
+        let snapshot = self.gctx.index_snapshot()?;
         ready!(self.summaries(name, &req, load))?
+            // Versions published after the `--index-snapshot` don't exist
+            // yet as far as resolution is concerned.
+            .filter(|s| snapshot.map_or(true, |time| s.is_published_by(time)))
             // First filter summaries for `--offline`. If we're online then
             // everything is a candidate, otherwise if we're offline we're only
             // going to consider candidates which are actually present on disk.
@@ -504,6 +582,27 @@ impl<'gctx> RegistryIndex<'gctx> {
         Poll::Ready(Ok(()))
     }
 
+    /// Finds the non-yanked packages matching `name` and `req` that are in the
+    /// index, but aren't downloaded and so are unusable with `--offline`.
+    ///
+    /// This never touches the network, see [`QueryKind::OfflineUnavailable`].
+    ///
+    /// [`QueryKind::OfflineUnavailable`]: crate::sources::source::QueryKind::OfflineUnavailable
+    pub fn query_offline_unavailable(
+        &mut self,
+        name: InternedString,
+        req: &OptVersionReq,
+        load: &mut dyn RegistryData,
+        f: &mut dyn FnMut(IndexSummary),
+    ) -> Poll<CargoResult<()>> {
+        let snapshot = self.gctx.index_snapshot()?;
+        ready!(self.summaries(name, &req, load))?
+            .filter(|s| snapshot.map_or(true, |time| s.is_published_by(time)))
+            .filter(|s| !s.is_yanked() && !load.is_crate_downloaded(s.package_id()))
+            .for_each(|s| f(IndexSummary::Offline(s.as_summary().clone())));
+        Poll::Ready(Ok(()))
+    }
+
     /// Looks into the summaries to check if a package has been yanked.
     pub fn is_yanked(
         &mut self,
@@ -536,12 +635,15 @@ impl Summaries {
     /// * `load` --- the actual index implementation which may be very slow to
     ///   call. We avoid this if we can.
     /// * `bindeps` --- whether the `-Zbindeps` unstable flag is enabled
+    /// * `trust_root` --- the keys to verify entries with, if the registry
+    ///   requires signatures
     pub fn parse(
         root: &Path,
         name: &str,
         source_id: SourceId,
         load: &mut dyn RegistryData,
         bindeps: bool,
+        trust_root: Option<&TrustRoot>,
         cache_manager: &CacheManager<'_>,
     ) -> Poll<CargoResult<Option<Summaries>>> {
         // This is the file we're loading from cache or the index data.
@@ -565,7 +667,7 @@ impl Summaries {
 
         let response = ready!(load.load(root, relative.as_ref(), index_version.as_deref())?);
 
-        match response {
+        let (raw_data, index_version) = match response {
             LoadResponse::CacheValid => {
                 tracing::debug!("fast path for registry cache of {:?}", relative);
                 return Poll::Ready(Ok(cached_summaries));
@@ -577,67 +679,103 @@ impl Summaries {
             LoadResponse::Data {
                 raw_data,
                 index_version,
+            } => (raw_data, index_version),
+            LoadResponse::Delta {
+                raw_data,
+                index_version,
             } => {
-                // This is the fallback path where we actually talk to the registry backend to load
-                // information. Here we parse every single line in the index (as we need
-                // to find the versions)
-                tracing::debug!("slow path for {:?}", relative);
-                let mut cache = SummariesCache::default();
-                let mut ret = Summaries::default();
-                ret.raw_data = raw_data;
-                for line in split(&ret.raw_data, b'\n') {
-                    // Attempt forwards-compatibility on the index by ignoring
-                    // everything that we ourselves don't understand, that should
-                    // allow future cargo implementations to break the
-                    // interpretation of each line here and older cargo will simply
-                    // ignore the new lines.
-                    let summary = match IndexSummary::parse(line, source_id, bindeps) {
-                        Ok(summary) => summary,
-                        Err(e) => {
-                            // This should only happen when there is an index
-                            // entry from a future version of cargo that this
-                            // version doesn't understand. Hopefully, those future
-                            // versions of cargo correctly set INDEX_V_MAX and
-                            // CURRENT_CACHE_VERSION, otherwise this will skip
-                            // entries in the cache preventing those newer
-                            // versions from reading them (that is, until the
-                            // cache is rebuilt).
-                            tracing::info!(
-                                "failed to parse {:?} registry package: {}",
-                                relative,
-                                e
-                            );
-                            continue;
-                        }
-                    };
-                    let version = summary.package_id().version().clone();
-                    cache.versions.push((version.clone(), line));
-                    ret.versions.insert(version, summary.into());
+                tracing::debug!("applying delta to registry cache of {:?}", relative);
+                let Some(cached_summaries) = cached_summaries else {
+                    return Poll::Ready(Err(internal(format!(
+                        "received a delta of `{}` without a cached copy",
+                        relative
+                    ))));
+                };
+                (
+                    cached_summaries.apply_delta(&raw_data, source_id, bindeps),
+                    index_version,
+                )
+            }
+        };
+
+        // This is the fallback path where we actually talk to the registry backend to load
+        // information. Here we parse every single line in the index (as we need
+        // to find the versions)
+        tracing::debug!("slow path for {:?}", relative);
+        let mut cache = SummariesCache::default();
+        let mut ret = Summaries::default();
+        ret.raw_data = raw_data;
+        for line in split(&ret.raw_data, b'\n') {
+            // Attempt forwards-compatibility on the index by ignoring
+            // everything that we ourselves don't understand, that should
+            // allow future cargo implementations to break the
+            // interpretation of each line here and older cargo will simply
+            // ignore the new lines.
+            let summary = match IndexSummary::parse(line, source_id, bindeps, trust_root) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    // This should only happen when there is an index
+                    // entry from a future version of cargo that this
+                    // version doesn't understand. Hopefully, those future
+                    // versions of cargo correctly set INDEX_V_MAX and
+                    // CURRENT_CACHE_VERSION, otherwise this will skip
+                    // entries in the cache preventing those newer
+                    // versions from reading them (that is, until the
+                    // cache is rebuilt).
+                    tracing::info!("failed to parse {:?} registry package: {}", relative, e);
+                    continue;
                 }
-                if let Some(index_version) = index_version {
-                    tracing::trace!("caching index_version {}", index_version);
-                    let cache_bytes = cache.serialize(index_version.as_str());
-                    // Once we have our `cache_bytes` which represents the `Summaries` we're
-                    // about to return, write that back out to disk so future Cargo
-                    // invocations can use it.
-                    cache_manager.put(name, &cache_bytes);
-
-                    // If we've got debug assertions enabled read back in the cached values
-                    // and assert they match the expected result.
-                    #[cfg(debug_assertions)]
-                    {
-                        let readback = SummariesCache::parse(&cache_bytes)
-                            .expect("failed to parse cache we just wrote");
-                        assert_eq!(
-                            readback.index_version, index_version,
-                            "index_version mismatch"
-                        );
-                        assert_eq!(readback.versions, cache.versions, "versions mismatch");
-                    }
+            };
+            let version = summary.package_id().version().clone();
+            cache.versions.push((version.clone(), line));
+            ret.versions.insert(version, summary.into());
+        }
+        if let Some(index_version) = index_version {
+            tracing::trace!("caching index_version {}", index_version);
+            let cache_bytes = cache.serialize(index_version.as_str());
+            // Once we have our `cache_bytes` which represents the `Summaries` we're
+            // about to return, write that back out to disk so future Cargo
+            // invocations can use it.
+            cache_manager.put(name, &cache_bytes);
+
+            // If we've got debug assertions enabled read back in the cached values
+            // and assert they match the expected result.
+            #[cfg(debug_assertions)]
+            {
+                let readback = SummariesCache::parse(&cache_bytes)
+                    .expect("failed to parse cache we just wrote");
+                assert_eq!(
+                    readback.index_version, index_version,
+                    "index_version mismatch"
+                );
+                assert_eq!(readback.versions, cache.versions, "versions mismatch");
+            }
+        }
+        Poll::Ready(Ok(Some(ret)))
+    }
+
+    /// Merges the lines of a [`LoadResponse::Delta`] into the lines of these
+    /// cached summaries, returning the contents of the updated index file.
+    fn apply_delta(&self, delta: &[u8], source_id: SourceId, bindeps: bool) -> Vec<u8> {
+        // Summaries loaded from the on-disk cache are all unparsed lines.
+        let mut lines = self
+            .versions
+            .iter()
+            .filter_map(|(version, summary)| match summary {
+                MaybeIndexSummary::Unparsed { start, end } => {
+                    Some((version.clone(), &self.raw_data[*start..*end]))
                 }
-                Poll::Ready(Ok(Some(ret)))
+                MaybeIndexSummary::Parsed(_) => None,
+            })
+            .collect::<BTreeMap<_, _>>();
+        for line in split(delta, b'\n') {
+            // Lines this version of cargo doesn't understand are skipped, like
+            // when parsing the whole file.
+            if let Ok(summary) = IndexSummary::parse(line, source_id, bindeps, None) {
+                lines.insert(summary.package_id().version().clone(), line);
             }
         }
+        lines.into_values().collect::<Vec<_>>().join(&b'\n')
     }
 
     /// Parses the contents of an on-disk cache, aka [`SummariesCache`], which
@@ -679,12 +817,13 @@ impl MaybeIndexSummary {
         raw_data: &[u8],
         source_id: SourceId,
         bindeps: bool,
+        trust_root: Option<&TrustRoot>,
     ) -> CargoResult<&IndexSummary> {
         let (start, end) = match self {
             MaybeIndexSummary::Unparsed { start, end } => (*start, *end),
             MaybeIndexSummary::Parsed(summary) => return Ok(summary),
         };
-        let summary = IndexSummary::parse(&raw_data[start..end], source_id, bindeps)?;
+        let summary = IndexSummary::parse(&raw_data[start..end], source_id, bindeps, trust_root)?;
         *self = MaybeIndexSummary::Parsed(summary);
         match self {
             MaybeIndexSummary::Unparsed { .. } => unreachable!(),
@@ -704,8 +843,13 @@ impl IndexSummary {
     /// for a package.
     ///
     /// The `line` provided is expected to be valid JSON. It is supposed to be
-    /// a [`IndexPackage`].
-    fn parse(line: &[u8], source_id: SourceId, bindeps: bool) -> CargoResult<IndexSummary> {
+    /// a [`IndexPackage`]. With a `trust_root`, its signature is verified too.
+    fn parse(
+        line: &[u8],
+        source_id: SourceId,
+        bindeps: bool,
+        trust_root: Option<&TrustRoot>,
+    ) -> CargoResult<IndexSummary> {
         // ****CAUTION**** Please be extremely careful with returning errors
         // from this function. Entries that error are not included in the
         // index cache, and can cause cargo to get confused when switching
@@ -722,6 +866,8 @@ impl IndexSummary {
             yanked,
             links,
             rust_version,
+            pubtime,
+            sig,
             v,
         } = serde_json::from_slice(line)?;
         let v = v.unwrap_or(1);
@@ -736,8 +882,15 @@ impl IndexSummary {
                 features.entry(name).or_default().extend(values);
             }
         }
+        let verified = match trust_root {
+            Some(trust_root) => trust_root.verify(line, sig.as_deref()),
+            None => Ok(()),
+        };
         let mut summary = Summary::new(pkgid, deps, &features, links, rust_version)?;
         summary.set_checksum(cksum);
+        if let Some(pubtime) = pubtime {
+            summary.set_pubtime(pubtime);
+        }
 
         let v_max = if bindeps {
             INDEX_V_MAX + 1
@@ -747,6 +900,8 @@ impl IndexSummary {
 
         if v_max < v {
             Ok(IndexSummary::Unsupported(summary, v))
+        } else if let Err(reason) = verified {
+            Ok(IndexSummary::Unverified(summary, reason))
         } else if yanked.unwrap_or(false) {
             Ok(IndexSummary::Yanked(summary))
         } else {
@@ -0,0 +1,150 @@
+//! Verification of signed index entries.
+//!
+//! A registry configured with `require-signatures` only accepts index entries
+//! carrying a `sig` field, which is a [PASETO] `v3.public` token signed by one
+//! of the keys listed in `trusted-keys` of the registry's configuration. The
+//! payload of the token is the JSON object of the index entry it signs,
+//! without its `sig` and `yanked` fields, as yanking doesn't need a new
+//! signature. The payload must be the same JSON value as the entry, so
+//! everything Cargo reads from the index, like the dependencies and features,
+//! is covered, and the checksum ties the downloaded package to the key holder.
+//!
+//! Requiring signatures is only supported with `-Zunstable-options`, for
+//! registries that aren't replaced by another source. Otherwise it is an
+//! error, so that the registry isn't silently used without verification.
+//!
+//! [PASETO]: https://github.com/paseto-standard/paseto-spec
+
+use anyhow::{bail, Context as _};
+use pasetors::keys::AsymmetricPublicKey;
+use pasetors::token::UntrustedToken;
+use pasetors::version3::{PublicToken, V3};
+use serde_json::Value;
+
+use crate::core::SourceId;
+use crate::util::auth::{registry_credential_config_raw, RegistryConfig};
+use crate::util::{CargoResult, GlobalContext};
+
+/// The set of keys index entries of a registry must be signed with.
+pub struct TrustRoot {
+    keys: Vec<AsymmetricPublicKey<V3>>,
+}
+
+/// The fields of an index entry that are not covered by its signature.
+const UNSIGNED_FIELDS: &[&str] = &["sig", "yanked"];
+
+/// Returns the configuration of the registry `source_id` if it requires
+/// signed index entries.
+fn signatures_config(
+    gctx: &GlobalContext,
+    source_id: SourceId,
+) -> CargoResult<Option<RegistryConfig>> {
+    if !source_id.is_remote_registry() || !configured(gctx)? {
+        return Ok(None);
+    }
+    let Some(cfg) = registry_credential_config_raw(gctx, &source_id)? else {
+        return Ok(None);
+    };
+    if !cfg.require_signatures.unwrap_or(false) {
+        return Ok(None);
+    }
+    if !gctx.cli_unstable().unstable_options {
+        bail!(
+            "{source_id} requires signatures, which is unstable, \
+             pass `-Z unstable-options` to enable it"
+        );
+    }
+    Ok(Some(cfg))
+}
+
+/// Returns whether any registry may require signed index entries.
+///
+/// Finding the configuration of a particular registry can fail or warn about
+/// unrelated settings, so it is only looked up if this is the case.
+fn configured(gctx: &GlobalContext) -> CargoResult<bool> {
+    if gctx
+        .get::<Option<bool>>("registry.require-signatures")?
+        .unwrap_or(false)
+    {
+        return Ok(true);
+    }
+    if gctx.env().any(|(k, _)| {
+        k.strip_prefix("CARGO_REGISTRIES_")
+            .is_some_and(|k| k.ends_with("_REQUIRE_SIGNATURES"))
+    }) {
+        return Ok(true);
+    }
+    let Some(registries) = gctx.values()?.get("registries") else {
+        return Ok(false);
+    };
+    let (registries, _) = registries.table("registries")?;
+    for name in registries.keys() {
+        let key = format!("registries.{name}.require-signatures");
+        if gctx.get::<Option<bool>>(&key)?.unwrap_or(false) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Returns whether the registry `source_id` requires signed index entries.
+pub fn requires_signatures(gctx: &GlobalContext, source_id: SourceId) -> CargoResult<bool> {
+    Ok(signatures_config(gctx, source_id)?.is_some())
+}
+
+impl TrustRoot {
+    /// Loads the trusted keys of the registry `source_id`, if it requires
+    /// signed index entries.
+    pub fn load(gctx: &GlobalContext, source_id: SourceId) -> CargoResult<Option<TrustRoot>> {
+        let Some(cfg) = signatures_config(gctx, source_id)? else {
+            return Ok(None);
+        };
+        let keys = cfg
+            .trusted_keys
+            .unwrap_or_default()
+            .iter()
+            .map(|key| {
+                AsymmetricPublicKey::<V3>::try_from(key.as_str())
+                    .with_context(|| format!("failed to parse trusted key `{key}` of {source_id}"))
+            })
+            .collect::<CargoResult<Vec<_>>>()?;
+        if keys.is_empty() {
+            bail!("{source_id} requires signatures, but has no `trusted-keys` configured");
+        }
+        Ok(Some(TrustRoot { keys }))
+    }
+
+    /// Checks that `sig` is a signature of the index entry `line` by one of
+    /// the trusted keys, returning the reason if it isn't.
+    pub fn verify(&self, line: &[u8], sig: Option<&str>) -> Result<(), String> {
+        let Some(sig) = sig else {
+            return Err("the index entry is not signed".to_string());
+        };
+        let token = UntrustedToken::try_from(sig)
+            .map_err(|e| format!("the signature is not a valid PASETO token: {e}"))?;
+        let Some(trusted) = self
+            .keys
+            .iter()
+            .find_map(|key| PublicToken::verify(key, &token, None, None).ok())
+        else {
+            return Err("the signature does not match any of the trusted keys".to_string());
+        };
+        let signed: Value = serde_json::from_str(trusted.payload())
+            .map_err(|e| format!("the signed payload is invalid: {e}"))?;
+        let mut entry: Value =
+            serde_json::from_slice(line).map_err(|e| format!("the index entry is invalid: {e}"))?;
+        if let Some(entry) = entry.as_object_mut() {
+            for field in UNSIGNED_FIELDS {
+                entry.remove(*field);
+            }
+        }
+        if signed != entry {
+            return Err(format!(
+                "the signature is for `{} v{}` with different contents than its index entry",
+                signed["name"].as_str().unwrap_or_default(),
+                signed["vers"].as_str().unwrap_or_default(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@ use cargo_util::{paths, Sha256};
 use std::fs::File;
 use std::io::SeekFrom;
 use std::io::{self, prelude::*};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::task::Poll;
 
 /// A local registry is a registry that lives on the filesystem as a set of
@@ -196,6 +196,12 @@ impl<'gctx> RegistryData for LocalRegistry<'gctx> {
         Ok(MaybeLock::Ready(crate_file))
     }
 
+    fn downloaded_crate_path(&self, pkg: PackageId) -> Option<PathBuf> {
+        // See `download` for why this doesn't need the package cache lock.
+        let path = self.root.join(&pkg.tarball_name()).into_path_unlocked();
+        path.exists().then_some(path)
+    }
+
     fn finish_download(
         &mut self,
         _pkg: PackageId,
@@ -194,6 +194,7 @@ use std::task::{ready, Poll};
 
 use anyhow::Context as _;
 use cargo_util::paths::{self, exclude_from_backups_and_indexing};
+use cargo_util::Sha256;
 use flate2::read::GzDecoder;
 use serde::Deserialize;
 use serde::Serialize;
@@ -317,6 +318,31 @@ pub struct RegistryConfig {
     /// [RFC 3139]: https://rust-lang.github.io/rfcs/3139-cargo-alternative-registry-auth.html
     #[serde(default)]
     pub auth_required: bool,
+
+    /// Optional API features the registry supports, such as `owner-roles`.
+    /// Cargo doesn't use features the registry doesn't list.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+
+    /// Endpoints of the OAuth 2.0 device authorization flow `cargo login`
+    /// can get a token with, see [RFC 8628].
+    ///
+    /// [RFC 8628]: https://www.rfc-editor.org/rfc/rfc8628
+    #[serde(default)]
+    pub device_authorization: Option<DeviceAuthorization>,
+}
+
+/// The `device-authorization` object of a [`RegistryConfig`].
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeviceAuthorization {
+    /// Endpoint handing out the device and user codes.
+    pub url: String,
+    /// Endpoint polled for the token once the user entered their code.
+    pub token_url: String,
+    /// Client identifier of Cargo at the registry, `cargo` by default.
+    #[serde(default)]
+    pub client_id: Option<String>,
 }
 
 /// Result from loading data from a registry.
@@ -331,6 +357,16 @@ pub enum LoadResponse {
         index_version: Option<String>,
     },
 
+    /// The cache is out of date, but the registry only sent the lines of the
+    /// versions that were added or changed since the cached version.
+    ///
+    /// Each line replaces the cached line of the same version, if any.
+    Delta {
+        raw_data: Vec<u8>,
+        /// Version of the data once the delta is applied.
+        index_version: Option<String>,
+    },
+
     /// The requested crate was found.
     NotFound,
 }
@@ -413,6 +449,11 @@ pub trait RegistryData {
         true
     }
 
+    /// Returns the path to the `.crate` file if it is already downloaded.
+    fn downloaded_crate_path(&self, _pkg: PackageId) -> Option<PathBuf> {
+        None
+    }
+
     /// Validates that the global package cache lock is held.
     ///
     /// Given the [`Filesystem`], this will make sure that the package cache
@@ -435,17 +476,23 @@ pub enum MaybeLock {
     /// The `.crate` file is not downloaded, here's the URL to download it from.
     ///
     /// `descriptor` is just a text string to display to the user of what is
-    /// being downloaded.
+    /// being downloaded. `mirrors` are fallen back to if downloading from
+    /// `url` fails. `partial` is where an interrupted download is kept
+    /// so that it can be resumed later.
     Download {
         url: String,
         descriptor: String,
         authorization: Option<String>,
+        mirrors: Vec<RegistryMirror>,
+        partial: Option<Filesystem>,
     },
 }
 
 mod download;
 mod http_remote;
+pub use http_remote::RegistryMirror;
 mod index;
+pub(crate) use index::requires_signatures;
 pub use index::IndexSummary;
 mod local;
 mod remote;
@@ -479,6 +526,7 @@ impl<'gctx> RegistrySource<'gctx> {
         gctx: &'gctx GlobalContext,
     ) -> CargoResult<RegistrySource<'gctx>> {
         assert!(source_id.is_remote_registry());
+        crate::util::network::proxy::warn_ignored_registry_proxy(gctx, source_id)?;
         let name = short_name(
             source_id,
             gctx.cli_unstable()
@@ -548,6 +596,21 @@ impl<'gctx> RegistrySource<'gctx> {
         self.ops.config()
     }
 
+    /// Returns the URL the `.crate` file of `pkg` is downloaded from, or
+    /// `None` if the registry is local.
+    ///
+    /// This requires that the index has been at least checked out.
+    pub fn download_url(
+        &mut self,
+        pkg: PackageId,
+        checksum: &str,
+    ) -> Poll<CargoResult<Option<String>>> {
+        let config = ready!(self.config())?;
+        Poll::Ready(Ok(
+            config.map(|config| download::download_url(config.dl, pkg, checksum))
+        ))
+    }
+
     /// Unpacks a downloaded package into a location where it's ready to be
     /// compiled.
     ///
@@ -752,8 +815,8 @@ impl<'gctx> Source for RegistrySource<'gctx> {
         // Handle `cargo update --precise` here.
         if let Some((_, requested)) = self
             .source_id
-            .precise_registry_version(dep.package_name().as_str())
-            .filter(|(c, to)| {
+            .precise_registry_versions(dep.package_name().as_str())
+            .find(|(c, to)| {
                 if to.is_prerelease() && self.gctx.cli_unstable().unstable_options {
                     req.matches_prerelease(c)
                 } else {
@@ -764,6 +827,22 @@ impl<'gctx> Source for RegistrySource<'gctx> {
             req.precise_to(&requested);
         }
 
+        if kind == QueryKind::OfflineUnavailable {
+            if !self.gctx.offline() {
+                return Poll::Ready(Ok(()));
+            }
+            return self.index.query_offline_unavailable(
+                dep.package_name(),
+                &req,
+                &mut *self.ops,
+                &mut |s| {
+                    if dep.matches(s.as_summary()) {
+                        f(s)
+                    }
+                },
+            );
+        }
+
         let mut called = false;
         let callback = &mut |s| {
             called = true;
@@ -806,6 +885,7 @@ impl<'gctx> Source for RegistrySource<'gctx> {
                         }
                         QueryKind::Alternatives => true,
                         QueryKind::Normalized => true,
+                        QueryKind::OfflineUnavailable => unreachable!("handled above"),
                     };
                     if !matched {
                         return;
@@ -902,10 +982,14 @@ impl<'gctx> Source for RegistrySource<'gctx> {
                 url,
                 descriptor,
                 authorization,
+                mirrors,
+                partial,
             } => Ok(MaybePackage::Download {
                 url,
                 descriptor,
                 authorization,
+                mirrors,
+                partial,
             }),
         }
     }
@@ -937,6 +1021,14 @@ impl<'gctx> Source for RegistrySource<'gctx> {
         self.index.is_yanked(pkg, &mut *self.ops)
     }
 
+    fn cached_checksum(&mut self, pkg: PackageId) -> CargoResult<Option<String>> {
+        let Some(path) = self.ops.downloaded_crate_path(pkg) else {
+            return Ok(None);
+        };
+        let file = paths::open(&path)?;
+        Ok(Some(Sha256::new().update_file(&file)?.finish_hex()))
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         // Before starting to work on the registry, make sure that
         // `<cargo_home>/registry` is marked as excluded from indexing and
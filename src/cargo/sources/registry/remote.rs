@@ -11,6 +11,7 @@ use crate::sources::registry::{LoadResponse, RegistryConfig, RegistryData};
 use crate::util::cache_lock::CacheLockMode;
 use crate::util::errors::CargoResult;
 use crate::util::interning::InternedString;
+use crate::util::network::retry::fallback_to_cache;
 use crate::util::{Filesystem, GlobalContext};
 use anyhow::Context as _;
 use cargo_util::paths;
@@ -18,7 +19,7 @@ use lazycell::LazyCell;
 use std::cell::{Cell, Ref, RefCell};
 use std::fs::File;
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 use std::task::{ready, Poll};
 use tracing::{debug, trace};
@@ -379,14 +380,25 @@ impl<'gctx> RegistryData for RemoteRegistry<'gctx> {
         // checkout.
         let url = self.source_id.url();
         let repo = self.repo.borrow_mut().unwrap();
-        git::fetch(
+        let result = git::fetch(
             repo,
             url.as_str(),
             &self.index_git_ref,
             self.gctx,
             RemoteKind::Registry,
         )
-        .with_context(|| format!("failed to fetch `{}`", url))?;
+        .with_context(|| format!("failed to fetch `{}`", url));
+
+        // Carry on with the index fetched by an earlier run, if there is one.
+        if let Err(e) = result {
+            if !fallback_to_cache(self.gctx)? || self.head().is_err() {
+                return Err(e);
+            }
+            self.gctx.shell().warn(format!(
+                "{e:#}\nfalling back to the cached index of `{}`",
+                self.source_id.display_registry_name()
+            ))?;
+        }
 
         Ok(())
     }
@@ -444,6 +456,10 @@ impl<'gctx> RegistryData for RemoteRegistry<'gctx> {
     fn is_crate_downloaded(&self, pkg: PackageId) -> bool {
         download::is_crate_downloaded(&self.cache_path, &self.gctx, pkg)
     }
+
+    fn downloaded_crate_path(&self, pkg: PackageId) -> Option<PathBuf> {
+        download::downloaded_crate_path(&self.cache_path, &self.gctx, pkg)
+    }
 }
 
 /// Implemented to just be sure to drop `tree` field before our other fields.
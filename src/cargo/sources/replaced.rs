@@ -1,5 +1,6 @@
 use crate::core::{Dependency, Package, PackageId, SourceId};
 use crate::sources::source::MaybePackage;
+use crate::sources::source::PreparedFetch;
 use crate::sources::source::QueryKind;
 use crate::sources::source::Source;
 use crate::sources::IndexSummary;
@@ -135,8 +136,8 @@ impl<'gctx> Source for ReplacedSource<'gctx> {
     }
 
     fn verify(&self, id: PackageId) -> CargoResult<()> {
-        let id = id.with_source_id(self.replace_with);
-        self.inner.verify(id)
+        self.inner
+            .verify_replacement(id.with_source_id(self.replace_with), id)
     }
 
     fn describe(&self) -> String {
@@ -167,6 +168,15 @@ impl<'gctx> Source for ReplacedSource<'gctx> {
         self.inner.is_yanked(pkg)
     }
 
+    fn cached_checksum(&mut self, pkg: PackageId) -> CargoResult<Option<String>> {
+        self.inner
+            .cached_checksum(pkg.with_source_id(self.replace_with))
+    }
+
+    fn prepare_fetch(&mut self) -> CargoResult<Option<PreparedFetch>> {
+        self.inner.prepare_fetch()
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         self.inner.block_until_ready().map_err(|e| {
             if self.is_builtin_replacement() {
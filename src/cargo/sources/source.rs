@@ -4,11 +4,14 @@ use std::collections::hash_map::HashMap;
 use std::fmt;
 use std::task::Poll;
 
+use cargo_util::ProcessBuilder;
+
 use crate::core::package::PackageSet;
 use crate::core::SourceId;
 use crate::core::{Dependency, Package, PackageId};
+use crate::sources::registry::RegistryMirror;
 use crate::sources::IndexSummary;
-use crate::util::{CargoResult, GlobalContext};
+use crate::util::{CargoResult, Filesystem, GlobalContext};
 
 /// An abstraction of different sources of Cargo packages.
 ///
@@ -141,6 +144,16 @@ pub trait Source {
         Ok(())
     }
 
+    /// Like [`Source::verify`], for a package of this source that stands in
+    /// for the package `replaced` of a source it replaces.
+    ///
+    /// This lets a source check that the package is really the one it
+    /// replaces, e.g. that a vendored git dependency comes from the locked
+    /// commit.
+    fn verify_replacement(&self, pkg: PackageId, _replaced: PackageId) -> CargoResult<()> {
+        self.verify(pkg)
+    }
+
     /// Describes this source in a human readable fashion, used for display in
     /// resolver error messages currently.
     fn describe(&self) -> String;
@@ -161,6 +174,27 @@ pub trait Source {
     /// as yanked. This ignores the yanked whitelist.
     fn is_yanked(&mut self, _pkg: PackageId) -> Poll<CargoResult<bool>>;
 
+    /// Returns the SHA-256 checksum of the `.crate` file of a package if it is
+    /// already available locally, without downloading anything. Only registry
+    /// sources keep `.crate` files.
+    fn cached_checksum(&mut self, _pkg: PackageId) -> CargoResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Prepares the network fetch this source needs to answer outstanding
+    /// [`Poll::Pending`] requests as an external command, so that it can run
+    /// alongside the fetches of other sources.
+    ///
+    /// The source doesn't print its status itself, as the caller may only
+    /// start the fetch later.
+    ///
+    /// The caller runs the returned command before calling
+    /// [`Source::block_until_ready`], which then uses what it fetched.
+    /// Currently only git sources fetching with the `git` CLI do this.
+    fn prepare_fetch(&mut self) -> CargoResult<Option<PreparedFetch>> {
+        Ok(None)
+    }
+
     /// Block until all outstanding [`Poll::Pending`] requests are [`Poll::Ready`].
     ///
     /// After calling this function, the source should return `Poll::Ready` for
@@ -171,6 +205,14 @@ pub trait Source {
     fn block_until_ready(&mut self) -> CargoResult<()>;
 }
 
+/// A network fetch prepared by [`Source::prepare_fetch`].
+pub struct PreparedFetch {
+    /// The command doing the fetch.
+    pub cmd: ProcessBuilder,
+    /// What the `Updating` status shows once the fetch starts, if anything.
+    pub status: Option<String>,
+}
+
 /// Defines how a dependency query will be performed for a [`Source`].
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum QueryKind {
@@ -189,6 +231,13 @@ pub enum QueryKind {
     /// Match a dependency in all ways and will normalize the package name.
     /// Each source defines what normalizing means.
     Normalized,
+    /// A query for packages matching the given dependency requirement that
+    /// the source knows about but which are unavailable in `--offline` mode,
+    /// like versions in a cached registry index that were never downloaded.
+    ///
+    /// This is used for error messages. Sources that never need the network
+    /// return nothing.
+    OfflineUnavailable,
 }
 
 /// A download status that represents if a [`Package`] has already been
@@ -204,6 +253,12 @@ pub enum MaybePackage {
         descriptor: String,
         /// Authorization data that may be required to attach when downloading.
         authorization: Option<String>,
+        /// Mirrors to fall back to, in order, if downloading from `url`
+        /// fails. No authorization is sent to them.
+        mirrors: Vec<RegistryMirror>,
+        /// Path to keep the data received so far if the download fails, so
+        /// that a later download can resume from it instead of starting over.
+        partial: Option<Filesystem>,
     },
 }
 
@@ -258,6 +313,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for Box<T> {
         (**self).verify(pkg)
     }
 
+    fn verify_replacement(&self, pkg: PackageId, replaced: PackageId) -> CargoResult<()> {
+        (**self).verify_replacement(pkg, replaced)
+    }
+
     fn describe(&self) -> String {
         (**self).describe()
     }
@@ -274,6 +333,14 @@ impl<'a, T: Source + ?Sized + 'a> Source for Box<T> {
         (**self).is_yanked(pkg)
     }
 
+    fn cached_checksum(&mut self, pkg: PackageId) -> CargoResult<Option<String>> {
+        (**self).cached_checksum(pkg)
+    }
+
+    fn prepare_fetch(&mut self) -> CargoResult<Option<PreparedFetch>> {
+        (**self).prepare_fetch()
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         (**self).block_until_ready()
     }
@@ -330,6 +397,10 @@ impl<'a, T: Source + ?Sized + 'a> Source for &'a mut T {
         (**self).verify(pkg)
     }
 
+    fn verify_replacement(&self, pkg: PackageId, replaced: PackageId) -> CargoResult<()> {
+        (**self).verify_replacement(pkg, replaced)
+    }
+
     fn describe(&self) -> String {
         (**self).describe()
     }
@@ -346,6 +417,14 @@ impl<'a, T: Source + ?Sized + 'a> Source for &'a mut T {
         (**self).is_yanked(pkg)
     }
 
+    fn cached_checksum(&mut self, pkg: PackageId) -> CargoResult<Option<String>> {
+        (**self).cached_checksum(pkg)
+    }
+
+    fn prepare_fetch(&mut self) -> CargoResult<Option<PreparedFetch>> {
+        (**self).prepare_fetch()
+    }
+
     fn block_until_ready(&mut self) -> CargoResult<()> {
         (**self).block_until_ready()
     }
@@ -7,7 +7,7 @@ use crate::{
 use anyhow::{bail, Context as _};
 use cargo_credential::{
     Action, CacheControl, Credential, CredentialResponse, LoginOptions, Operation, RegistryInfo,
-    Secret,
+    Secret, TokenScope,
 };
 
 use core::fmt;
@@ -19,7 +19,8 @@ use url::Url;
 use crate::core::SourceId;
 use crate::util::context::Value;
 use crate::util::credential::adaptor::BasicProcessCredential;
-use crate::util::credential::paseto::PasetoCredential;
+use crate::util::credential::oidc::OidcCredential;
+use crate::util::credential::paseto::{paserk_public_from_paserk_secret, PasetoCredential};
 
 use super::{
     context::{CredentialCacheValue, OptValue, PathAndArgs},
@@ -38,6 +39,10 @@ pub struct RegistryConfig {
     pub credential_provider: Option<PathAndArgs>,
     pub secret_key: OptValue<Secret<String>>,
     pub secret_key_subject: Option<String>,
+    pub require_signatures: Option<bool>,
+    pub trusted_keys: Option<Vec<String>>,
+    pub mirrors: Option<Vec<String>>,
+    pub proxy: Option<String>,
     #[serde(rename = "protocol")]
     _protocol: Option<String>,
 }
@@ -54,6 +59,8 @@ pub struct RegistryConfigExtended {
     pub credential_provider: Option<PathAndArgs>,
     pub secret_key: OptValue<Secret<String>>,
     pub secret_key_subject: Option<String>,
+    pub require_signatures: Option<bool>,
+    pub trusted_keys: Option<Vec<String>>,
     #[serde(rename = "default")]
     _default: Option<String>,
     #[serde(rename = "global-credential-providers")]
@@ -68,6 +75,10 @@ impl RegistryConfigExtended {
             credential_provider: self.credential_provider,
             secret_key: self.secret_key,
             secret_key_subject: self.secret_key_subject,
+            require_signatures: self.require_signatures,
+            trusted_keys: self.trusted_keys,
+            mirrors: None,
+            proxy: None,
             _protocol: None,
         }
     }
@@ -481,7 +492,7 @@ pub fn cache_token_from_commandline(gctx: &GlobalContext, sid: &SourceId, token:
         CredentialCacheValue {
             token_value: token.to_owned(),
             expiration: None,
-            operation_independent: true,
+            scope: TokenScope::Publish,
         },
     );
 }
@@ -495,6 +506,7 @@ static BUILT_IN_PROVIDERS: &[&'static str] = &[
     "cargo:wincred",
     "cargo:macos-keychain",
     "cargo:libsecret",
+    "cargo:oidc",
 ];
 
 fn credential_action(
@@ -529,6 +541,10 @@ fn credential_action(
             }
             "cargo:paseto" => bail!("cargo:paseto requires -Zasymmetric-token"),
             "cargo:token-from-stdout" => Box::new(BasicProcessCredential {}),
+            "cargo:oidc" if gctx.cli_unstable().unstable_options => {
+                Box::new(OidcCredential::new(gctx))
+            }
+            "cargo:oidc" => bail!("cargo:oidc requires -Zunstable-options"),
             #[cfg(windows)]
             "cargo:wincred" => Box::new(cargo_credential_wincred::WindowsCredential {}),
             #[cfg(target_os = "macos")]
@@ -538,7 +554,10 @@ fn credential_action(
             name if BUILT_IN_PROVIDERS.contains(&name) => {
                 Box::new(cargo_credential::UnsupportedCredential {})
             }
-            process => Box::new(CredentialProcessCredential::new(process)),
+            process => Box::new(CredentialProcessCredential::new(
+                process,
+                gctx.cli_unstable().unstable_options,
+            )),
         };
         gctx.shell().verbose(|c| {
             c.status(
@@ -611,7 +630,7 @@ fn auth_token_optional(
             .map(|exp| OffsetDateTime::now_utc() + Duration::minutes(1) < exp)
             .unwrap_or(true)
         {
-            if cached_token.operation_independent || matches!(operation, Operation::Read) {
+            if cached_token.scope == TokenScope::Publish || matches!(operation, Operation::Read) {
                 tracing::trace!("using token from in-memory cache");
                 return Ok(Some(cached_token.token_value.clone()));
             }
@@ -642,14 +661,27 @@ fn auth_token_optional(
         token,
         cache: cache_control,
         operation_independent,
+        scope,
+        message,
     } = credential_response
     else {
         bail!("credential provider produced unexpected response for `get` request: {credential_response:?}")
     };
     let token = Secret::from(token);
     tracing::trace!("found token");
+    if let Some(message) = message {
+        gctx.shell().note(message)?;
+    }
+    let scope = match scope {
+        Some(scope) => scope,
+        None if operation_independent => TokenScope::Publish,
+        None => TokenScope::Read,
+    };
     let expiration = match cache_control {
         CacheControl::Expires { expiration } => Some(expiration),
+        CacheControl::ExpiresIn { expires_in } => i64::try_from(expires_in)
+            .ok()
+            .and_then(|secs| OffsetDateTime::now_utc().checked_add(Duration::seconds(secs))),
         CacheControl::Session => None,
         CacheControl::Never | _ => return Ok(Some(token)),
     };
@@ -659,7 +691,7 @@ fn auth_token_optional(
         CredentialCacheValue {
             token_value: token.clone(),
             expiration,
-            operation_independent,
+            scope,
         },
     );
     Ok(Some(token))
@@ -689,6 +721,61 @@ pub fn logout(gctx: &GlobalContext, sid: &SourceId) -> CargoResult<()> {
     Ok(())
 }
 
+/// Generates a new keypair for the given registry with the `cargo:paseto`
+/// provider, replacing the existing one.
+pub fn generate_keypair(
+    gctx: &GlobalContext,
+    sid: &SourceId,
+    login_url: Option<&str>,
+    args: &[&str],
+) -> CargoResult<()> {
+    let providers = credential_provider(gctx, sid, false, false)?;
+    let Some(provider) = providers
+        .iter()
+        .find(|p| p.first().map(String::as_str) == Some("cargo:paseto"))
+    else {
+        bail!(
+            "cannot generate a keypair for `{}`, as the `cargo:paseto` credential provider \
+             is not configured for it",
+            sid.display_registry_name()
+        );
+    };
+    let args: Vec<&str> = provider
+        .iter()
+        .map(String::as_str)
+        .chain(args.iter().copied())
+        .collect();
+    let old_public_key = registry_credential_config_raw(gctx, sid)?
+        .and_then(|cfg| cfg.secret_key)
+        .and_then(|key| paserk_public_from_paserk_secret(key.val.as_deref()));
+
+    let registry = RegistryInfo {
+        index_url: sid.url().as_str(),
+        name: sid.alt_registry_key(),
+        headers: vec![],
+    };
+    let options = LoginOptions {
+        token: None,
+        login_url,
+    };
+    PasetoCredential::new(gctx)
+        .perform(&registry, &Action::Login(options), &args[1..])
+        .with_context(|| {
+            format!(
+                "credential provider `{}` failed action `login`",
+                args.join(" ")
+            )
+        })?;
+    if let Some(old_public_key) = old_public_key {
+        gctx.shell().note(format!(
+            "replaced the keypair of `{}`, whose public key was `{old_public_key}`\n\
+             Register the new public key with the registry, then revoke the old one.",
+            sid.display_registry_name()
+        ))?;
+    }
+    Ok(())
+}
+
 /// Log in to the given registry.
 pub fn login(
     gctx: &GlobalContext,
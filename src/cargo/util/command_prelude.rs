@@ -1,8 +1,11 @@
-use crate::core::compiler::{BuildConfig, MessageFormat, TimingOutput};
+use crate::core::compiler::{
+    BuildConfig, MessageFormat, PrintEnvFormat, PrintEnvSelection, TimingOutput,
+};
 use crate::core::resolver::CliFeatures;
+use crate::core::shell::StatusFormat;
 use crate::core::{Edition, Workspace};
 use crate::ops::registry::RegistryOrIndex;
-use crate::ops::{CompileFilter, CompileOptions, NewOptions, Packages, VersionControl};
+use crate::ops::{self, CompileFilter, CompileOptions, NewOptions, Packages, VersionControl};
 use crate::util::important_paths::find_root_manifest_for_wd;
 use crate::util::interning::InternedString;
 use crate::util::is_rustup;
@@ -18,9 +21,11 @@ use cargo_util_schemas::manifest::ProfileName;
 use cargo_util_schemas::manifest::RegistryName;
 use cargo_util_schemas::manifest::StringOrVec;
 use clap::builder::UnknownArgumentValueParser;
+use std::cell::RefCell;
 use std::ffi::{OsStr, OsString};
 use std::path::Path;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 pub use crate::core::compiler::CompileMode;
 pub use crate::{CliError, CliResult, GlobalContext};
@@ -227,6 +232,16 @@ pub trait CommandExt: Sized {
         )
     }
 
+    fn arg_all_required_features(self) -> Self {
+        self._arg(
+            flag(
+                "all-required-features",
+                "Activate the `required-features` of the selected targets (unstable)",
+            )
+            .help_heading(heading::FEATURE_SELECTION),
+        )
+    }
+
     fn arg_release(self, release: &'static str) -> Self {
         self._arg(
             flag("release", release)
@@ -273,6 +288,28 @@ pub trait CommandExt: Sized {
         )
     }
 
+    fn arg_lints_profile(self) -> Self {
+        self._arg(
+            opt(
+                "lints-profile",
+                "Profile of the `[lints]` tables to use (unstable)",
+            )
+            .value_name("PROFILE")
+            .help_heading(heading::COMPILATION_OPTIONS),
+        )
+    }
+
+    fn arg_index_snapshot(self) -> Self {
+        self._arg(
+            opt(
+                "index-snapshot",
+                "Only use registry versions published by this date or RFC 3339 time (unstable)",
+            )
+            .value_name("TIME")
+            .help_heading(heading::MANIFEST_OPTIONS),
+        )
+    }
+
     fn arg_manifest_path(self) -> Self {
         // We use `--manifest-path` instead of `--path`.
         let unsupported_path_arg = {
@@ -297,6 +334,11 @@ pub trait CommandExt: Sized {
 
     fn arg_message_format(self) -> Self {
         self._arg(multi_opt("message-format", "FMT", "Error format"))
+            ._arg(
+                opt("status-format", "Format of the build progress (unstable)")
+                    .value_name("FMT")
+                    .value_parser(["human", "json"]),
+            )
     }
 
     fn arg_build_plan(self) -> Self {
@@ -306,6 +348,48 @@ pub trait CommandExt: Sized {
         )
     }
 
+    fn arg_print_env(self) -> Self {
+        self._arg(
+            opt(
+                "print-env",
+                "Output the environment variables of the processes the build runs (unstable)",
+            )
+            .value_name("FMT")
+            .value_parser(["human", "json"])
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("human")
+            .conflicts_with("build-plan")
+            .help_heading(heading::COMPILATION_OPTIONS),
+        )
+        ._arg(
+            multi_opt(
+                "print-env-for",
+                "SPEC[:TARGET]",
+                "Only output the environment of these packages or targets (unstable)",
+            )
+            .requires("print-env")
+            .help_heading(heading::COMPILATION_OPTIONS),
+        )
+    }
+
+    fn arg_watch(self) -> Self {
+        self._arg(flag(
+            "watch",
+            "Run again when the files it was built from change (unstable)",
+        ))
+    }
+
+    fn arg_only_deps(self) -> Self {
+        self._arg(
+            flag(
+                "only-deps",
+                "Only compile the dependencies of the selected packages (unstable)",
+            )
+            .help_heading(heading::PACKAGE_SELECTION),
+        )
+    }
+
     fn arg_unit_graph(self) -> Self {
         self._arg(
             flag("unit-graph", "Output build graph in JSON (unstable)")
@@ -326,6 +410,23 @@ pub trait CommandExt: Sized {
         )
         ._arg(flag("bin", "Use a binary (application) template [default]"))
         ._arg(flag("lib", "Use a library template"))
+        ._arg(
+            opt(
+                "template",
+                "Create the package from a template at a path, a git URL or \
+                 in a registry (unstable)",
+            )
+            .value_name("TEMPLATE")
+            .conflicts_with_all(["bin", "lib"]),
+        )
+        ._arg(
+            multi_opt(
+                "define",
+                "NAME=VALUE",
+                "Set a variable of the template (unstable)",
+            )
+            .requires("template"),
+        )
         ._arg(
             opt("edition", "Edition to set for the crate generated")
                 .value_parser(Edition::CLI_VALUES)
@@ -365,6 +466,20 @@ pub trait CommandExt: Sized {
         self._arg(flag("ignore-rust-version", help).help_heading(heading::MANIFEST_OPTIONS))
     }
 
+    fn arg_minimal_versions(self) -> Self {
+        self._arg(
+            flag(
+                "minimal-versions",
+                "Resolve all dependencies to their lowest compatible versions",
+            )
+            .conflicts_with("direct-minimal-versions"),
+        )
+        ._arg(flag(
+            "direct-minimal-versions",
+            "Resolve direct dependencies to their lowest compatible versions",
+        ))
+    }
+
     fn arg_future_incompat_report(self) -> Self {
         self._arg(flag(
             "future-incompat-report",
@@ -411,12 +526,9 @@ pub trait CommandExt: Sized {
         };
 
         self._arg(
-            opt(
-                "artifact-dir",
-                "Copy final artifacts to this directory (unstable)",
-            )
-            .value_name("PATH")
-            .help_heading(heading::COMPILATION_OPTIONS),
+            opt("artifact-dir", "Copy final artifacts to this directory")
+                .value_name("PATH")
+                .help_heading(heading::COMPILATION_OPTIONS),
         )
         ._arg(unsupported_short_arg)
         ._arg(
@@ -552,6 +664,21 @@ pub trait ArgMatchesExt {
         self.maybe_flag("keep-going")
     }
 
+    /// Runs `exec` once, or with `--watch`, again whenever one of the files
+    /// that it built from changes. `exec` gets where to collect those files,
+    /// for [`BuildConfig::watched_files`].
+    fn exec_watching(
+        &self,
+        gctx: &GlobalContext,
+        mut exec: impl FnMut(Option<Rc<RefCell<Vec<PathBuf>>>>) -> CliResult,
+    ) -> CliResult {
+        if !self.flag("watch") {
+            return exec(None);
+        }
+        gctx.cli_unstable().fail_if_stable_opt("--watch", None)?;
+        ops::watch(gctx, |watched_files| exec(Some(watched_files)))
+    }
+
     fn honor_rust_version(&self) -> Option<bool> {
         self.flag("ignore-rust-version").then_some(false)
     }
@@ -662,6 +789,14 @@ Run `{cmd}` to see possible targets."
                         }
                         message_format = Some(MessageFormat::Short);
                     }
+                    "sarif" => {
+                        gctx.cli_unstable()
+                            .fail_if_stable_opt("--message-format=sarif", None)?;
+                        if message_format.is_some() {
+                            bail!(two_kinds_of_msg_format_err);
+                        }
+                        message_format = Some(MessageFormat::Sarif);
+                    }
                     "json-render-diagnostics" => {
                         if message_format.is_none() {
                             message_format = Some(default_json);
@@ -707,6 +842,7 @@ Run `{cmd}` to see possible targets."
         build_config.requested_profile = self.get_profile_name("dev", profile_checking)?;
         build_config.build_plan = self.flag("build-plan");
         build_config.unit_graph = self.flag("unit-graph");
+        build_config.only_deps = self.flag("only-deps");
         build_config.future_incompat_report = self.flag("future-incompat-report");
 
         if self._contains("timings") {
@@ -734,18 +870,56 @@ Run `{cmd}` to see possible targets."
             }
         }
 
-        if build_config.build_plan {
+        // `--print-env` also sets `build_plan`, so check the flag itself.
+        if self.flag("build-plan") {
             gctx.cli_unstable()
                 .fail_if_stable_opt("--build-plan", 5579)?;
-        };
+        }
         if build_config.unit_graph {
             gctx.cli_unstable()
                 .fail_if_stable_opt("--unit-graph", 8002)?;
         }
 
+        if let Some(format) = self._value_of("print-env") {
+            gctx.cli_unstable()
+                .fail_if_stable_opt("--print-env", None)?;
+            build_config.print_env = Some(match format {
+                "json" => PrintEnvFormat::Json,
+                _ => PrintEnvFormat::Human,
+            });
+            build_config.print_env_for = self
+                ._values_of("print-env-for")
+                .iter()
+                .map(|selection| PrintEnvSelection::parse(selection))
+                .collect::<CargoResult<_>>()?;
+            // The environment is collected from the build plan.
+            build_config.build_plan = true;
+        }
+
+        if build_config.only_deps {
+            gctx.cli_unstable()
+                .fail_if_stable_opt("--only-deps", None)?;
+        }
+
+        if let Some(status_format) = self._value_of("status-format") {
+            gctx.cli_unstable()
+                .fail_if_stable_opt("--status-format", None)?;
+            gctx.shell().set_status_format(match status_format {
+                "json" => StatusFormat::Json,
+                _ => StatusFormat::Human,
+            });
+        }
+
+        let all_required_features = self.flag("all-required-features");
+        if all_required_features {
+            gctx.cli_unstable()
+                .fail_if_stable_opt("--all-required-features", None)?;
+        }
+
         let opts = CompileOptions {
             build_config,
             cli_features: self.cli_features()?,
+            all_required_features,
             spec,
             filter: CompileFilter::from_raw_arguments(
                 self.flag("lib"),
@@ -814,7 +988,7 @@ Run `{cmd}` to see possible targets."
             "none" => VersionControl::NoVcs,
             vcs => panic!("Impossible vcs: {:?}", vcs),
         });
-        NewOptions::new(
+        let mut opts = NewOptions::new(
             vcs,
             self.flag("bin"),
             self.flag("lib"),
@@ -822,7 +996,19 @@ Run `{cmd}` to see possible targets."
             self._value_of("name").map(|s| s.to_string()),
             self._value_of("edition").map(|s| s.to_string()),
             self.registry(gctx)?,
-        )
+        )?;
+        if let Some(template) = self._value_of("template") {
+            gctx.cli_unstable().fail_if_stable_opt("--template", None)?;
+            opts.template = Some(template.to_string());
+            for define in self._values_of("define") {
+                let Some((name, value)) = define.split_once('=') else {
+                    bail!("invalid `--define` value `{define}`, expected `NAME=VALUE`");
+                };
+                opts.template_variables
+                    .push((name.trim().to_string(), value.to_string()));
+            }
+        }
+        Ok(opts)
     }
 
     fn registry_or_index(&self, gctx: &GlobalContext) -> CargoResult<Option<RegistryOrIndex>> {
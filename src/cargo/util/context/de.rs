@@ -254,6 +254,16 @@ impl<'gctx> ConfigMapAccess<'gctx> {
                 .iter()
                 .filter(|(k, _v)| !given_fields.iter().any(|gk| gk == k));
             for (unused_key, unused_value) in unused_keys {
+                // `-Zvalidate-config` already reports the keys of config
+                // files, with a more precise location.
+                if super::schema::enabled(de.gctx)
+                    && matches!(
+                        unused_value.definition(),
+                        Definition::Path(_) | Definition::Cli(Some(_))
+                    )
+                {
+                    continue;
+                }
                 de.gctx.shell().warn(format!(
                     "unused config key `{}.{}` in `{}`",
                     de.key,
@@ -51,7 +51,7 @@
 
 use crate::util::cache_lock::{CacheLock, CacheLockMode, CacheLocker};
 use std::borrow::Cow;
-use std::cell::{RefCell, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::{HashMap, HashSet};
 use std::env;
@@ -68,6 +68,7 @@ use std::time::Instant;
 
 use self::ConfigValue as CV;
 use crate::core::compiler::rustdoc::RustdocExternMap;
+use crate::core::compiler::CompileTarget;
 use crate::core::global_cache_tracker::{DeferredGlobalLastUse, GlobalCacheTracker};
 use crate::core::shell::Verbosity;
 use crate::core::{features, CliUnstable, Shell, SourceId, Workspace, WorkspaceRootConfig};
@@ -77,11 +78,12 @@ use crate::sources::CRATES_IO_REGISTRY;
 use crate::util::errors::CargoResult;
 use crate::util::network::http::configure_http_handle;
 use crate::util::network::http::http_handle;
+use crate::util::toml::ManifestCache;
 use crate::util::try_canonicalize;
 use crate::util::{internal, CanonicalUrl};
 use crate::util::{Filesystem, IntoUrl, IntoUrlWithBase, Rustc};
 use anyhow::{anyhow, bail, format_err, Context as _};
-use cargo_credential::Secret;
+use cargo_credential::{Secret, TokenScope};
 use cargo_util::paths;
 use cargo_util_schemas::manifest::RegistryName;
 use curl::easy::Easy;
@@ -106,11 +108,13 @@ mod path;
 pub use path::{ConfigRelativePath, PathAndArgs};
 
 mod target;
-pub use target::{TargetCfgConfig, TargetConfig};
+pub use target::{TargetAliasConfig, TargetCfgConfig, TargetConfig, TargetProfileConfig};
 
 mod environment;
 use environment::Env;
 
+mod schema;
+
 use super::auth::RegistryConfig;
 
 // Helper macro for creating typed access methods.
@@ -156,7 +160,8 @@ enum WhyLoad {
 pub struct CredentialCacheValue {
     pub token_value: Secret<String>,
     pub expiration: Option<OffsetDateTime>,
-    pub operation_independent: bool,
+    /// Operations the token may be used for.
+    pub scope: TokenScope,
 }
 
 /// Configuration information for cargo. This is not specific to a build, it is information
@@ -208,6 +213,16 @@ pub struct GlobalContext {
     creation_time: Instant,
     /// Target Directory via resolved Cli parameter
     target_dir: Option<Filesystem>,
+    /// The profile of the `[lints]` tables selected with `--lints-profile`
+    lints_profile: Option<String>,
+    /// The time passed to `--index-snapshot`, unparsed.
+    index_snapshot_arg: Option<String>,
+    /// The time registry indexes are restricted to, from `--index-snapshot`
+    /// or the `registry.index-snapshot` config.
+    index_snapshot: LazyCell<Option<OffsetDateTime>>,
+    /// The output of `rustc -vV` for the global `rustc`, see
+    /// [`GlobalContext::rustc_verbose_version`].
+    rustc_verbose_version: LazyCell<String>,
     /// Environment variable snapshot.
     env: Env,
     /// Tracks which sources have been updated to avoid multiple updates.
@@ -228,6 +243,9 @@ pub struct GlobalContext {
     doc_extern_map: LazyCell<RustdocExternMap>,
     progress_config: ProgressConfig,
     env_config: LazyCell<EnvConfig>,
+    /// `[target-alias]` tables expanded by [`GlobalContext::expand_target_alias`],
+    /// keyed by the short name of the target they stand for.
+    target_aliases: RefCell<HashMap<String, (String, TargetAliasConfig)>>,
     /// This should be false if:
     /// - this is an artifact of the rustc distribution process for "stable" or for "beta"
     /// - this is an `#[test]` that does not opt in with `enable_nightly_features`
@@ -246,6 +264,8 @@ pub struct GlobalContext {
     pub nightly_features_allowed: bool,
     /// WorkspaceRootConfigs that have been found
     pub ws_roots: RefCell<HashMap<PathBuf, WorkspaceRootConfig>>,
+    /// Parsed manifests kept across workspace loads, if enabled.
+    manifest_cache: Option<ManifestCache>,
     /// The global cache tracker is a database used to track disk cache usage.
     global_cache_tracker: LazyCell<RefCell<GlobalCacheTracker>>,
     /// A cache of modifications to make to [`GlobalContext::global_cache_tracker`],
@@ -309,6 +329,10 @@ impl GlobalContext {
             cache_rustc_info,
             creation_time: Instant::now(),
             target_dir: None,
+            lints_profile: None,
+            index_snapshot_arg: None,
+            index_snapshot: LazyCell::new(),
+            rustc_verbose_version: LazyCell::new(),
             env,
             updated_sources: LazyCell::new(),
             credential_cache: LazyCell::new(),
@@ -324,6 +348,8 @@ impl GlobalContext {
             env_config: LazyCell::new(),
             nightly_features_allowed: matches!(&*features::channel(), "nightly" | "dev"),
             ws_roots: RefCell::new(HashMap::new()),
+            manifest_cache: None,
+            target_aliases: RefCell::new(HashMap::new()),
             global_cache_tracker: LazyCell::new(),
             deferred_global_last_use: LazyCell::new(),
         }
@@ -419,6 +445,35 @@ impl GlobalContext {
             .map(AsRef::as_ref)
     }
 
+    /// Gets the path to the `clippy-driver` executable for `cargo clippy`.
+    ///
+    /// This is `CLIPPY_DRIVER` if set, or else the one next to `rustc`, as
+    /// they come from the same toolchain.
+    pub fn clippy_driver(&self) -> CargoResult<PathBuf> {
+        if let Some(driver) = self.maybe_get_tool("clippy_driver", &None) {
+            return Ok(driver);
+        }
+        let rustc = self.get_tool(Tool::Rustc, &self.build_config()?.rustc);
+        let driver = rustc
+            .with_file_name("clippy-driver")
+            .with_extension(env::consts::EXE_EXTENSION);
+        if driver.is_file() {
+            Ok(driver)
+        } else {
+            Ok(PathBuf::from("clippy-driver"))
+        }
+    }
+
+    /// The output of `rustc -vV` for the `rustc` returned by
+    /// [`GlobalContext::load_global_rustc`], including its version and host.
+    ///
+    /// It's only queried once per process.
+    pub fn rustc_verbose_version(&self) -> CargoResult<&str> {
+        self.rustc_verbose_version
+            .try_borrow_with(|| Ok(self.load_global_rustc(None)?.verbose_version))
+            .map(String::as_str)
+    }
+
     /// Gets the path to the `rustc` executable.
     pub fn load_global_rustc(&self, ws: Option<&Workspace<'_>>) -> CargoResult<Rustc> {
         let cache_location = ws.map(|ws| {
@@ -557,6 +612,22 @@ impl GlobalContext {
         }
     }
 
+    /// Keeps parsed manifests around, to only parse them again once they or
+    /// the files they were parsed from change.
+    ///
+    /// This is meant for long-running consumers of Cargo as a library, which
+    /// load the same workspace many times with one `GlobalContext`.
+    pub fn enable_manifest_cache(&mut self) {
+        self.manifest_cache
+            .get_or_insert_with(ManifestCache::default);
+    }
+
+    /// The cache of parsed manifests, if [`Self::enable_manifest_cache`] was
+    /// called.
+    pub fn manifest_cache(&self) -> Option<&ManifestCache> {
+        self.manifest_cache.as_ref()
+    }
+
     /// Sets the path where ancestor config file searching will stop. The
     /// given path is included, but its ancestors are not.
     pub fn set_search_stop_path<P: Into<PathBuf>>(&mut self, path: P) {
@@ -599,6 +670,71 @@ impl GlobalContext {
         &self.cwd
     }
 
+    /// Selects the profile of the `[lints]` tables to merge over their
+    /// default lints when parsing manifests.
+    pub fn set_lints_profile(&mut self, profile: Option<String>) {
+        if profile != self.lints_profile {
+            // The lints of cached manifests were resolved with the old one.
+            if let Some(cache) = &self.manifest_cache {
+                cache.clear();
+            }
+        }
+        self.lints_profile = profile;
+    }
+
+    /// The profile of the `[lints]` tables selected with `--lints-profile`.
+    pub fn lints_profile(&self) -> Option<&str> {
+        self.lints_profile.as_deref()
+    }
+
+    /// Restricts registry indexes to the versions published by `snapshot`,
+    /// an RFC 3339 timestamp or a date, overriding the
+    /// `registry.index-snapshot` config.
+    pub fn set_index_snapshot(&mut self, snapshot: Option<String>) {
+        self.index_snapshot_arg = snapshot;
+        self.index_snapshot = LazyCell::new();
+    }
+
+    /// The time registry indexes are restricted to with `--index-snapshot` or
+    /// the `registry.index-snapshot` config. Versions published later are
+    /// left out of queries.
+    ///
+    /// The config is only honored with `-Z unstable-options`, and warned
+    /// about otherwise.
+    pub fn index_snapshot(&self) -> CargoResult<Option<OffsetDateTime>> {
+        self.index_snapshot
+            .try_borrow_with(|| {
+                let (value, what) = match &self.index_snapshot_arg {
+                    Some(value) => (value.clone(), "`--index-snapshot`".to_string()),
+                    None if self.cli_unstable().unstable_options => {
+                        match self.get_string("registry.index-snapshot")? {
+                            Some(value) => (
+                                value.val,
+                                format!("`registry.index-snapshot` in {}", value.definition),
+                            ),
+                            None => return Ok(None),
+                        }
+                    }
+                    None => {
+                        if self.get_string("registry.index-snapshot")?.is_some() {
+                            self.shell().warn(
+                                "ignoring `registry.index-snapshot` config, \
+                                 pass `-Zunstable-options` to enable it",
+                            )?;
+                        }
+                        return Ok(None);
+                    }
+                };
+                parse_index_snapshot(&value).map(Some).ok_or_else(|| {
+                    anyhow!(
+                        "invalid time `{value}` for {what}, \
+                             expected a date like `2024-05-01` or an RFC 3339 timestamp"
+                    )
+                })
+            })
+            .copied()
+    }
+
     /// The `target` output directory to use.
     ///
     /// Returns `None` if the user has not chosen an explicit directory.
@@ -785,7 +921,7 @@ impl GlobalContext {
     }
 
     /// Returns all environment variable keys, filtering out keys that are not valid UTF-8.
-    fn env_keys(&self) -> impl Iterator<Item = &str> {
+    pub(crate) fn env_keys(&self) -> impl Iterator<Item = &str> {
         self.env.keys_str()
     }
 
@@ -1020,6 +1156,10 @@ impl GlobalContext {
         unstable_flags: &[String],
         cli_config: &[String],
     ) -> CargoResult<()> {
+        // Manifests are parsed according to the `-Z` flags and config.
+        if let Some(cache) = &self.manifest_cache {
+            cache.clear();
+        }
         for warning in self
             .unstable_flags
             .parse(unstable_flags, self.nightly_features_allowed)?
@@ -1093,6 +1233,10 @@ impl GlobalContext {
 
         self.load_unstable_flags_from_config()?;
 
+        if self.unstable_flags.validate_config {
+            schema::validate(self)?;
+        }
+
         Ok(())
     }
 
@@ -1528,7 +1672,7 @@ impl GlobalContext {
     /// Both 'config.toml' and 'credentials.toml' should be valid with or without extension.
     /// When both exist, we want to prefer the one without an extension for
     /// backwards compatibility, but warn the user appropriately.
-    fn get_file_path(
+    pub(crate) fn get_file_path(
         &self,
         dir: &Path,
         filename_without_extension: &str,
@@ -1800,6 +1944,12 @@ impl GlobalContext {
     pub fn http_config(&self) -> CargoResult<&CargoHttpConfig> {
         self.http_config.try_borrow_with(|| {
             let mut http = self.get::<CargoHttpConfig>("http")?;
+            if http.no_proxy.is_some() && !self.cli_unstable().unstable_options {
+                self.shell().warn(
+                    "ignoring `http.no-proxy` config, pass `-Zunstable-options` to enable it",
+                )?;
+                http.no_proxy = None;
+            }
             let curl_v = curl::Version::get();
             disables_multiplexing_for_bad_curl(curl_v.version(), &mut http, self);
             Ok(http)
@@ -1898,6 +2048,45 @@ impl GlobalContext {
         target::load_target_triple(self, target)
     }
 
+    /// Returns the target `target` stands for if it is the name of a
+    /// `[target-alias]` table, or `target` itself otherwise.
+    ///
+    /// The settings of the alias then apply to the target it expands to.
+    pub fn expand_target_alias(&self, target: &str) -> CargoResult<String> {
+        if target.ends_with(".json") {
+            return Ok(target.to_string());
+        }
+        let key = format!("target-alias.{target}");
+        let Some(alias) = self.get::<Option<TargetAliasConfig>>(&key)? else {
+            return Ok(target.to_string());
+        };
+        if !self.cli_unstable().target_alias {
+            self.shell().warn(format!(
+                "ignoring `{key}` config, pass `-Ztarget-alias` to enable it"
+            ))?;
+            return Ok(target.to_string());
+        }
+        let triple = alias.triple.val.clone();
+        let short_name = CompileTarget::new(&triple)?.short_name().to_string();
+        let mut aliases = self.target_aliases.borrow_mut();
+        if let Some((other, _)) = aliases.get(&short_name) {
+            if other != target {
+                bail!(
+                    "target aliases `{other}` and `{target}` both stand for `{triple}`, \
+                    only one of them can be used at a time"
+                );
+            }
+        }
+        aliases.insert(short_name, (target.to_string(), alias));
+        Ok(triple)
+    }
+
+    /// Returns the `[target-alias]` tables expanded so far, along with their
+    /// names.
+    pub fn target_aliases(&self) -> Ref<'_, HashMap<String, (String, TargetAliasConfig)>> {
+        self.target_aliases.borrow()
+    }
+
     /// Returns the cached [`SourceId`] corresponding to the main repository.
     ///
     /// This is the main cargo registry by default, but it can be overridden in
@@ -2322,6 +2511,16 @@ pub fn homedir(cwd: &Path) -> Option<PathBuf> {
     ::home::cargo_home_with_cwd(cwd).ok()
 }
 
+/// Parses the time of an index snapshot, either an RFC 3339 timestamp or a
+/// date, which stands for its start in UTC.
+fn parse_index_snapshot(value: &str) -> Option<OffsetDateTime> {
+    use time::format_description::well_known::Rfc3339;
+
+    OffsetDateTime::parse(value, &Rfc3339)
+        .or_else(|_| OffsetDateTime::parse(&format!("{value}T00:00:00Z"), &Rfc3339))
+        .ok()
+}
+
 pub fn save_credentials(
     gctx: &GlobalContext,
     token: Option<RegistryCredentialConfig>,
@@ -2474,6 +2673,7 @@ pub fn save_credentials(
 #[serde(rename_all = "kebab-case")]
 pub struct CargoHttpConfig {
     pub proxy: Option<String>,
+    pub no_proxy: Option<StringList>,
     pub low_speed_limit: Option<u32>,
     pub timeout: Option<u64>,
     pub cainfo: Option<ConfigRelativePath>,
@@ -2554,6 +2754,8 @@ pub struct CargoNetConfig {
     pub retry: Option<u32>,
     pub offline: Option<bool>,
     pub git_fetch_with_cli: Option<bool>,
+    pub git_fetch_jobs: Option<u32>,
+    pub fallback_to_cache: Option<bool>,
     pub ssh: Option<CargoSshConfig>,
 }
 
@@ -2612,6 +2814,8 @@ pub struct CargoBuildConfig {
     // deprecated alias for artifact-dir
     pub out_dir: Option<ConfigRelativePath>,
     pub artifact_dir: Option<ConfigRelativePath>,
+    pub sandbox: Option<bool>,
+    pub sandbox_trust: Option<StringList>,
 }
 
 /// Configuration for `build.target`.
@@ -2678,6 +2882,8 @@ impl BuildTargetConfig {
 #[serde(rename_all = "kebab-case")]
 pub struct CargoResolverConfig {
     pub something_like_precedence: Option<CargoResolverPrecedence>,
+    pub yanked: Option<CargoResolverYanked>,
+    pub feature_unification: Option<CargoResolverFeatureUnification>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -2687,6 +2893,44 @@ pub enum CargoResolverPrecedence {
     SomethingLikeRustVersion,
 }
 
+/// What to do when the lock file pins a yanked version, see `resolver.yanked`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CargoResolverYanked {
+    /// Emit a warning for each yanked package.
+    Warn,
+    /// Fail with an error listing the yanked packages.
+    Deny,
+    /// Don't check for yanked packages.
+    #[default]
+    Allow,
+}
+
+/// Which packages features are unified across, see `resolver.feature-unification`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CargoResolverFeatureUnification {
+    /// Unify features across the packages selected on the command-line.
+    #[default]
+    Selected,
+    /// Unify features across all workspace members, whichever are selected.
+    Workspace,
+}
+
+/// The `[source-policy]` table, see [`Workspace::check_source_policy`].
+///
+/// [`Workspace::check_source_policy`]: crate::core::Workspace::check_source_policy
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SourcePolicyConfig {
+    /// Kinds of sources to forbid, `git` and `path`, all of them if unset.
+    pub forbid: Option<Vec<String>>,
+    /// Profiles whose builds are checked.
+    pub profiles: Option<Vec<String>>,
+    /// Whether `cargo package` and `cargo publish` are checked.
+    pub package: Option<bool>,
+}
+
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct TermConfig {
@@ -2863,7 +3107,7 @@ fn parse_document(toml: &str, _file: &Path, _gctx: &GlobalContext) -> CargoResul
 /// a = 'a b c'
 /// b = ['a', 'b', 'c']
 /// ```
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct StringList(Vec<String>);
 
 impl StringList {
@@ -0,0 +1,198 @@
+//! Checks config files against [`cargo_util_schemas::config`] for
+//! `-Zvalidate-config`, warning about the keys Cargo doesn't know about.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use cargo_util::paths;
+use cargo_util_schemas::config::{ConfigSchema, CONFIG};
+
+use super::{ConfigKey, ConfigValue as CV, Definition, GlobalContext};
+use crate::util::edit_distance::closest_msg;
+use crate::util::errors::CargoResult;
+
+/// Whether `-Zvalidate-config` is set, also looking at the `[unstable]` table
+/// in case [`GlobalContext::configure`] hasn't read it yet.
+pub(super) fn enabled(gctx: &GlobalContext) -> bool {
+    gctx.cli_unstable().validate_config
+        || (gctx.nightly_features_allowed
+            && matches!(
+                gctx.get_cv(&ConfigKey::from_str("unstable.validate-config")),
+                Ok(Some(CV::Boolean(true, _)))
+            ))
+}
+
+/// Warns about the unknown keys of every config file that was loaded.
+pub(super) fn validate(gctx: &GlobalContext) -> CargoResult<()> {
+    // Errors loading the config are reported once it is used.
+    let Ok(values) = gctx.values() else {
+        return Ok(());
+    };
+    let mut files = BTreeSet::new();
+    for cv in values.values() {
+        collect_files(cv, &mut files);
+    }
+    for path in files {
+        validate_file(gctx, &path)?;
+    }
+    Ok(())
+}
+
+/// Collects the config files `cv` and its contents were defined in.
+fn collect_files(cv: &CV, files: &mut BTreeSet<PathBuf>) {
+    if let Definition::Path(path) | Definition::Cli(Some(path)) = cv.definition() {
+        files.insert(path.clone());
+    }
+    if let CV::Table(table, _) = cv {
+        for value in table.values() {
+            collect_files(value, files);
+        }
+    }
+}
+
+fn validate_file(gctx: &GlobalContext, path: &Path) -> CargoResult<()> {
+    let contents = paths::read(path)?;
+    // The file was already parsed when it was loaded, so errors can't happen
+    // here.
+    let Ok(doc) = toml_edit::ImDocument::parse(contents.as_str()) else {
+        return Ok(());
+    };
+    let mut unknown = Vec::new();
+    validate_table(doc.as_table(), &CONFIG, &mut Vec::new(), None, &mut unknown);
+    for (key, suggestion, span) in unknown {
+        let location = match span {
+            Some(span) => {
+                let before = &contents[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                let column = before[line_start..].chars().count() + 1;
+                format!("{}:{line}:{column}", path.display())
+            }
+            None => path.display().to_string(),
+        };
+        gctx.shell().warn(format!(
+            "unused config key `{key}` in `{location}`{suggestion}"
+        ))?;
+    }
+    Ok(())
+}
+
+/// Collects the keys of `table` unknown to `schema`, along with a suggestion
+/// of a similar key and where the key is in the file.
+///
+/// Some keys, like the parts of dotted keys, have no location of their own,
+/// and are reported at `parent_span`, the location of the closest parent key.
+fn validate_table(
+    table: &dyn toml_edit::TableLike,
+    schema: &ConfigSchema,
+    parents: &mut Vec<String>,
+    parent_span: Option<Range<usize>>,
+    unknown: &mut Vec<(String, String, Option<Range<usize>>)>,
+) {
+    if matches!(schema, ConfigSchema::Any) {
+        return;
+    }
+    for (key, item) in table.iter() {
+        parents.push(key.to_owned());
+        let span = table
+            .key(key)
+            .and_then(|key| key.span())
+            .or_else(|| parent_span.clone());
+        match schema.get(key) {
+            Some(inner) => {
+                if let Some(inner_table) = item.as_table_like() {
+                    validate_table(inner_table, inner, parents, span, unknown);
+                }
+            }
+            None => {
+                let suggestion = closest_msg(key, schema.keys(), |k| k);
+                unknown.push((parents.join("."), suggestion, span));
+            }
+        }
+        parents.pop();
+    }
+}
+
+/// Checks that the keys Cargo reads, either one at a time or as the fields
+/// of a struct, are all part of the schema, so `-Zvalidate-config` doesn't
+/// warn about them.
+#[test]
+fn every_config_key_read_is_known() {
+    use regex::Regex;
+    use std::collections::BTreeMap;
+
+    let key_read = Regex::new(
+        r#"\bget(?:_[a-z_]+)?(?:::<(?:Option<)?(\w+)[^(]*>)?\(\s*"([a-z][a-z0-9-]*(?:\.[a-z0-9-]+)*)""#,
+    )
+    .unwrap();
+    let kebab_struct = Regex::new(
+        r#"rename_all = "kebab-case"\)\]\s*pub(?:\([a-z]+\))? struct (\w+) \{([^}]*)\}"#,
+    )
+    .unwrap();
+    let field =
+        Regex::new(r#"(?:#\[serde\(rename = "([a-z0-9-]+)"\)\]\s*)?pub(?:\([a-z]+\))? (\w+):"#)
+            .unwrap();
+
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let files: Vec<_> = walkdir::WalkDir::new(&src)
+        .into_iter()
+        .map(|entry| entry.unwrap().into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .map(|path| {
+            // Examples in comments don't read anything.
+            let contents = paths::read(&path)
+                .unwrap()
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("//"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (path, contents)
+        })
+        .collect();
+
+    // The keys of the structs config tables are deserialized into.
+    let mut struct_keys = BTreeMap::new();
+    for (_, contents) in &files {
+        for caps in kebab_struct.captures_iter(contents) {
+            let keys: Vec<_> = field
+                .captures_iter(&caps[2])
+                .map(|field| match field.get(1) {
+                    Some(rename) => rename.as_str().to_owned(),
+                    None => field[2].replace('_', "-"),
+                })
+                .collect();
+            struct_keys.insert(caps[1].to_owned(), keys);
+        }
+    }
+
+    let mut unknown = BTreeSet::new();
+    for (path, contents) in &files {
+        for caps in key_read.captures_iter(contents) {
+            let key = &caps[2];
+            let mut keys = vec![key.to_owned()];
+            if let Some(fields) = caps.get(1).and_then(|ty| struct_keys.get(ty.as_str())) {
+                keys.extend(fields.iter().map(|field| format!("{key}.{field}")));
+            } else if !key.contains('.') {
+                // Not necessarily a config key.
+                continue;
+            }
+            for key in keys {
+                let mut schema = &CONFIG;
+                for part in key.split('.') {
+                    match schema.get(part) {
+                        Some(inner) => schema = inner,
+                        None => {
+                            unknown.insert(format!("{key} in {}", path.display()));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    assert!(
+        unknown.is_empty(),
+        "config keys missing from the schema: {unknown:#?}"
+    );
+}
@@ -1,4 +1,8 @@
-use super::{ConfigKey, ConfigRelativePath, GlobalContext, OptValue, PathAndArgs, StringList, CV};
+use super::de::Deserializer;
+use super::{
+    ConfigKey, ConfigRelativePath, EnvConfig, GlobalContext, OptValue, PathAndArgs, StringList,
+    Value, CV,
+};
 use crate::core::compiler::{BuildOutput, LinkArgTarget};
 use crate::util::CargoResult;
 use serde::Deserialize;
@@ -13,6 +17,12 @@ pub struct TargetCfgConfig {
     pub runner: OptValue<PathAndArgs>,
     pub rustflags: OptValue<StringList>,
     pub linker: OptValue<ConfigRelativePath>,
+    /// Per-profile settings, see [`TargetProfileConfig`].
+    ///
+    /// Loaded separately in [`load_target_cfgs`], as `[target.<triple>]`
+    /// tables may use `profile` as the name of a build script override.
+    #[serde(skip)]
+    pub profile: Option<BTreeMap<String, TargetProfileConfig>>,
     // This is here just to ignore fields from normal `TargetConfig` because
     // all `[target]` tables are getting deserialized, whether they start with
     // `cfg(` or not.
@@ -20,6 +30,16 @@ pub struct TargetCfgConfig {
     pub other: BTreeMap<String, toml::Value>,
 }
 
+/// Config definition of a `[target.<triple>.profile.<name>]` or
+/// `[target.'cfg(…)'.profile.<name>]` table.
+///
+/// Requires `-Zprofile-rustflags`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TargetProfileConfig {
+    /// Additional rustc flags to pass when building with the given profile.
+    pub rustflags: OptValue<StringList>,
+}
+
 /// Config definition of a `[target]` table or `[host]`.
 #[derive(Debug, Clone, Default)]
 pub struct TargetConfig {
@@ -31,6 +51,8 @@ pub struct TargetConfig {
     pub rustdocflags: OptValue<StringList>,
     /// The path of the linker for this target.
     pub linker: OptValue<ConfigRelativePath>,
+    /// Per-profile settings, see [`TargetProfileConfig`].
+    pub profile: Option<BTreeMap<String, TargetProfileConfig>>,
     /// Build script override for the given library name.
     ///
     /// Any package with a `links` value for the given library name will skip
@@ -39,6 +61,22 @@ pub struct TargetConfig {
     pub links_overrides: BTreeMap<String, BuildOutput>,
 }
 
+/// Config definition of a `[target-alias.<name>]` table.
+///
+/// Requires `-Ztarget-alias`. Passing the name of the table to `--target`
+/// builds for `triple`, with the other settings taking precedence over the
+/// ones in `[target.<triple>]`.
+#[derive(Debug, Deserialize)]
+pub struct TargetAliasConfig {
+    /// The target this alias stands for.
+    pub triple: Value<String>,
+    pub runner: OptValue<PathAndArgs>,
+    pub rustflags: OptValue<StringList>,
+    pub linker: OptValue<ConfigRelativePath>,
+    /// Environment variables set like with `[env]` when the alias is used.
+    pub env: Option<EnvConfig>,
+}
+
 /// Loads all of the `target.'cfg()'` tables.
 pub(super) fn load_target_cfgs(
     gctx: &GlobalContext,
@@ -51,8 +89,18 @@ pub(super) fn load_target_cfgs(
     // ordering via the order keys were defined in files perhaps.
     let target: BTreeMap<String, TargetCfgConfig> = gctx.get("target")?;
     tracing::debug!("Got all targets {:#?}", target);
-    for (key, cfg) in target {
+    for (key, mut cfg) in target {
         if key.starts_with("cfg(") {
+            if cfg.other.remove("profile").is_some() {
+                let mut profile_key = ConfigKey::from_str("target");
+                profile_key.push(&key);
+                profile_key.push("profile");
+                cfg.profile = Deserialize::deserialize(Deserializer {
+                    gctx,
+                    key: profile_key,
+                    env_prefix_ok: true,
+                })?;
+            }
             // Unfortunately this is not able to display the location of the
             // unused key. Using config::Value<toml::Value> doesn't work. One
             // solution might be to create a special "Any" type, but I think
@@ -63,6 +111,13 @@ pub(super) fn load_target_cfgs(
                     other_key, key
                 ))?;
             }
+            if cfg.profile.is_some() && !gctx.cli_unstable().profile_rustflags {
+                gctx.shell().warn(format!(
+                    "unused key `profile` in [target] config table `{}`\n\
+                     note: `profile` tables in [target] require `-Zprofile-rustflags`",
+                    key
+                ))?;
+            }
             result.push((key, cfg));
         }
     }
@@ -103,7 +158,19 @@ pub(super) fn load_host_triple(gctx: &GlobalContext, triple: &str) -> CargoResul
 
 /// Loads a single `[target]` table for the given triple.
 pub(super) fn load_target_triple(gctx: &GlobalContext, triple: &str) -> CargoResult<TargetConfig> {
-    load_config_table(gctx, &format!("target.{}", triple))
+    let mut config = load_config_table(gctx, &format!("target.{}", triple))?;
+    if let Some((_, alias)) = gctx.target_aliases.borrow().get(triple) {
+        if alias.runner.is_some() {
+            config.runner = alias.runner.clone();
+        }
+        if alias.rustflags.is_some() {
+            config.rustflags = alias.rustflags.clone();
+        }
+        if alias.linker.is_some() {
+            config.linker = alias.linker.clone();
+        }
+    }
+    Ok(config)
 }
 
 /// Loads a single table for the given prefix.
@@ -119,6 +186,11 @@ fn load_config_table(gctx: &GlobalContext, prefix: &str) -> CargoResult<TargetCo
     let linker: OptValue<ConfigRelativePath> = gctx.get(&format!("{prefix}.linker"))?;
     // Links do not support environment variables.
     let target_key = ConfigKey::from_str(prefix);
+    let profile_key = format!("{prefix}.profile");
+    let profile = match gctx.get_cv(&ConfigKey::from_str(&profile_key))? {
+        Some(cv) if is_target_profile_table(&target_key, &cv) => gctx.get(&profile_key)?,
+        _ => None,
+    };
     let links_overrides = match gctx.get_table(&target_key)? {
         Some(links) => parse_links_overrides(&target_key, links.val)?,
         None => BTreeMap::new(),
@@ -128,10 +200,28 @@ fn load_config_table(gctx: &GlobalContext, prefix: &str) -> CargoResult<TargetCo
         rustflags,
         rustdocflags,
         linker,
+        profile,
         links_overrides,
     })
 }
 
+/// Whether `value` under `target_key.profile` is a table of
+/// [`TargetProfileConfig`]s rather than a build script override.
+///
+/// Only `[target.<triple>]` has profile tables, and every entry of such a
+/// table is itself a table, whereas an override only nests `rustc-env`.
+fn is_target_profile_table(target_key: &ConfigKey, value: &CV) -> bool {
+    if target_key.parts().next() != Some("target") {
+        return false;
+    }
+    let CV::Table(table, _) = value else {
+        return false;
+    };
+    table
+        .iter()
+        .all(|(k, v)| k != "rustc-env" && matches!(v, CV::Table(..)))
+}
+
 fn parse_links_overrides(
     target_key: &ConfigKey,
     links: HashMap<String, CV>,
@@ -143,6 +233,10 @@ fn parse_links_overrides(
         match lib_name.as_str() {
             // `ar` is a historical thing.
             "ar" | "linker" | "runner" | "rustflags" | "rustdocflags" => continue,
+            // Loaded separately as `TargetProfileConfig`. A library may also
+            // be called `profile`, so only skip it when nested like
+            // `target.<triple>.profile.<name>`.
+            "profile" if is_target_profile_table(target_key, &value) => continue,
             _ => {}
         }
         let mut output = BuildOutput::default();
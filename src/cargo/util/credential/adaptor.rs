@@ -60,6 +60,8 @@ impl Credential for BasicProcessCredential {
                     token: Secret::from(buffer),
                     cache: CacheControl::Session,
                     operation_independent: true,
+                    scope: None,
+                    message: None,
                 })
             }
             _ => Err(cargo_credential::Error::OperationNotSupported),
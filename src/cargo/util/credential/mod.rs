@@ -3,6 +3,7 @@
 #![allow(clippy::print_stderr)]
 
 pub mod adaptor;
+pub mod oidc;
 pub mod paseto;
 pub mod process;
 pub mod token;
@@ -0,0 +1,250 @@
+//! Credential provider that exchanges the OIDC token of a CI job for a
+//! short-lived registry token, also known as "trusted publishing".
+//!
+//! The OIDC token is requested from GitHub Actions if the job is allowed to,
+//! or read from the `CARGO_OIDC_TOKEN` environment variable for other CI
+//! systems. It is then posted to the `/api/v1/trusted_publishing/tokens`
+//! endpoint of the registry, which answers with a token for the registry if
+//! it trusts the job.
+
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::io::Read as _;
+use std::task::Poll;
+
+use anyhow::{bail, Context as _};
+use cargo_credential::{Action, CacheControl, Credential, CredentialResponse, Error, RegistryInfo};
+use clap::Command;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use url::Url;
+
+use crate::core::SourceId;
+use crate::sources::source::Source;
+use crate::sources::RegistrySource;
+use crate::util::cache_lock::CacheLockMode;
+use crate::util::command_prelude::opt;
+use crate::util::network::http::http_handle;
+use crate::{CargoResult, GlobalContext};
+
+thread_local! {
+    /// Set while a token is exchanged. Loading the `config.json` of a registry
+    /// requiring authentication asks for a token again, which this provider
+    /// can't give without the `config.json`.
+    static EXCHANGING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The answer of GitHub Actions to an OIDC token request.
+#[derive(Deserialize)]
+struct GitHubOidcToken {
+    value: String,
+}
+
+#[derive(Serialize)]
+struct ExchangeRequest<'a> {
+    jwt: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ExchangeResponse {
+    token: String,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+pub(crate) struct OidcCredential<'a> {
+    gctx: &'a GlobalContext,
+}
+
+impl<'a> OidcCredential<'a> {
+    pub fn new(gctx: &'a GlobalContext) -> Self {
+        Self { gctx }
+    }
+}
+
+impl<'a> Credential for OidcCredential<'a> {
+    fn perform(
+        &self,
+        registry: &RegistryInfo<'_>,
+        action: &Action<'_>,
+        args: &[&str],
+    ) -> Result<CredentialResponse, Error> {
+        if !matches!(action, Action::Get(_)) {
+            return Err(Error::OperationNotSupported);
+        }
+        if EXCHANGING.get() {
+            return Err(Error::NotFound);
+        }
+        let matches = Command::new("cargo:oidc")
+            .no_binary_name(true)
+            .arg(opt("api", "API URL of the registry").value_name("URL"))
+            .arg(opt("audience", "Audience of the OIDC token").value_name("AUDIENCE"))
+            .try_get_matches_from(args)
+            .map_err(Box::new)?;
+
+        EXCHANGING.set(true);
+        let result = self.exchange(
+            registry,
+            matches.get_one::<String>("api").map(String::as_str),
+            matches.get_one::<String>("audience").map(String::as_str),
+        );
+        EXCHANGING.set(false);
+        let Some(response) = result? else {
+            return Err(Error::NotFound);
+        };
+
+        let cache = match response.expires_at {
+            Some(expires_at) => CacheControl::Expires {
+                expiration: OffsetDateTime::parse(&expires_at, &Rfc3339)
+                    .with_context(|| format!("invalid `expires_at` of the token: {expires_at}"))?,
+            },
+            None => CacheControl::Session,
+        };
+        Ok(CredentialResponse::Get {
+            token: response.token.into(),
+            cache,
+            operation_independent: true,
+            scope: None,
+            message: None,
+        })
+    }
+}
+
+impl<'a> OidcCredential<'a> {
+    /// Exchanges the OIDC token of the CI job for a registry token, or returns
+    /// `None` outside of a CI job with an OIDC token.
+    fn exchange(
+        &self,
+        registry: &RegistryInfo<'_>,
+        api: Option<&str>,
+        audience: Option<&str>,
+    ) -> CargoResult<Option<ExchangeResponse>> {
+        let api = match api {
+            Some(api) => api.to_string(),
+            None => self.api(registry)?,
+        };
+        let api = Url::parse(&api).with_context(|| format!("invalid API URL `{api}`"))?;
+        let audience = match audience {
+            Some(audience) => audience,
+            None => api
+                .host_str()
+                .with_context(|| format!("API URL `{api}` has no host"))?,
+        };
+        let Some(jwt) = self.oidc_token(audience)? else {
+            return Ok(None);
+        };
+
+        let url = format!(
+            "{}/api/v1/trusted_publishing/tokens",
+            api.as_str().trim_end_matches('/')
+        );
+        let body = serde_json::to_vec(&ExchangeRequest { jwt: &jwt })?;
+        let mut handle = http_handle(self.gctx)?;
+        handle.post(true)?;
+        let mut headers = List::new();
+        headers.append("Accept: application/json")?;
+        headers.append("Content-Type: application/json")?;
+        handle.http_headers(headers)?;
+        let response = request(handle, &url, Some(&body))
+            .with_context(|| format!("failed to exchange the OIDC token at `{url}`"))?;
+        let response = serde_json::from_slice(&response)
+            .with_context(|| format!("invalid response of `{url}`"))?;
+        Ok(Some(response))
+    }
+
+    /// Reads the API URL of the registry from its `config.json`.
+    fn api(&self, registry: &RegistryInfo<'_>) -> CargoResult<String> {
+        let index_url = Url::parse(registry.index_url).context("parsing index url")?;
+        let sid = if let Some(name) = registry.name {
+            SourceId::for_alt_registry(&index_url, name)
+        } else {
+            SourceId::for_registry(&index_url)
+        }?;
+        let _lock = self
+            .gctx
+            .acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+        let mut src = RegistrySource::remote(sid, &HashSet::new(), self.gctx)?;
+        src.set_quiet(true);
+        let mut load = || -> CargoResult<_> {
+            loop {
+                match src.config()? {
+                    Poll::Pending => src.block_until_ready()?,
+                    Poll::Ready(cfg) => return Ok(cfg),
+                }
+            }
+        };
+        // Registries requiring authentication for `config.json` end up here
+        // with an error about the missing token.
+        let cfg = load().with_context(|| {
+            format!(
+                "failed to read the API URL of {}, \
+                 pass it with `--api` to the `cargo:oidc` credential provider",
+                sid.display_registry_name()
+            )
+        })?;
+        match cfg.and_then(|cfg| cfg.api) {
+            Some(api) => Ok(api),
+            None => bail!(
+                "{} does not support API commands",
+                sid.display_registry_name()
+            ),
+        }
+    }
+
+    /// Gets the OIDC token of the CI job for `audience`.
+    fn oidc_token(&self, audience: &str) -> CargoResult<Option<String>> {
+        if let Ok(token) = self.gctx.get_env("CARGO_OIDC_TOKEN") {
+            return Ok(Some(token));
+        }
+        // https://docs.github.com/en/actions/security-for-github-actions/security-hardening-your-deployments/about-security-hardening-with-openid-connect
+        let (Ok(request_url), Ok(request_token)) = (
+            self.gctx.get_env("ACTIONS_ID_TOKEN_REQUEST_URL"),
+            self.gctx.get_env("ACTIONS_ID_TOKEN_REQUEST_TOKEN"),
+        ) else {
+            return Ok(None);
+        };
+        let mut url = Url::parse(&request_url)
+            .with_context(|| format!("invalid `ACTIONS_ID_TOKEN_REQUEST_URL`: {request_url}"))?;
+        url.query_pairs_mut().append_pair("audience", audience);
+        let mut handle = http_handle(self.gctx)?;
+        handle.get(true)?;
+        let mut headers = List::new();
+        headers.append("Accept: application/json")?;
+        headers.append(&format!("Authorization: Bearer {request_token}"))?;
+        handle.http_headers(headers)?;
+        let response = request(handle, url.as_str(), None)
+            .context("failed to request the OIDC token from GitHub Actions")?;
+        let token: GitHubOidcToken = serde_json::from_slice(&response)
+            .context("invalid OIDC token response from GitHub Actions")?;
+        Ok(Some(token.value))
+    }
+}
+
+/// Performs a request with `handle`, returning the body of a successful
+/// response.
+fn request(mut handle: Easy, url: &str, mut body: Option<&[u8]>) -> CargoResult<Vec<u8>> {
+    handle.url(url)?;
+    if let Some(body) = body {
+        handle.post_field_size(body.len() as u64)?;
+    }
+    let mut response = Vec::new();
+    {
+        let mut transfer = handle.transfer();
+        transfer.read_function(|buf| Ok(body.as_mut().map_or(0, |b| b.read(buf).unwrap_or(0))))?;
+        transfer.write_function(|data| {
+            response.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+    let code = handle.response_code()?;
+    if code != 200 {
+        bail!(
+            "the server responded with status {code}: {}",
+            String::from_utf8_lossy(&response)
+        );
+    }
+    Ok(response)
+}
@@ -104,6 +104,7 @@ impl<'a> Credential for PasetoCredential<'a> {
                 let kip: pasetors::paserk::Id = (&public).into();
 
                 let iat = OffsetDateTime::now_utc();
+                let challenge = challenge(&registry.headers);
 
                 let message = Message {
                     iat: &iat.format(&Rfc3339).unwrap(),
@@ -132,7 +133,7 @@ impl<'a> Credential for PasetoCredential<'a> {
                         Operation::Publish { cksum, .. } => Some(cksum),
                         _ => None,
                     },
-                    challenge: None, // todo: PASETO with challenges
+                    challenge: challenge.as_deref(),
                     v: None,
                 };
                 let footer = Footer {
@@ -140,9 +141,10 @@ impl<'a> Credential for PasetoCredential<'a> {
                     kip,
                 };
 
-                // Only read operations can be cached with asymmetric tokens.
+                // Only read operations can be cached with asymmetric tokens,
+                // and a challenge can only be answered once.
                 let cache = match operation {
-                    Operation::Read => CacheControl::Session,
+                    Operation::Read if challenge.is_none() => CacheControl::Session,
                     _ => CacheControl::Never,
                 };
 
@@ -168,6 +170,8 @@ impl<'a> Credential for PasetoCredential<'a> {
                     token,
                     cache,
                     operation_independent: false,
+                    scope: None,
+                    message: None,
                 })
             }
             Action::Login(options) => {
@@ -217,6 +221,22 @@ impl<'a> Credential for PasetoCredential<'a> {
     }
 }
 
+/// Finds the `challenge` parameter of a `Cargo` challenge in the
+/// `www-authenticate` headers of a response the registry rejected a request
+/// with.
+fn challenge(headers: &[String]) -> Option<String> {
+    headers
+        .iter()
+        .filter_map(|header| header.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("www-authenticate"))
+        .flat_map(|(_, value)| http_auth::ChallengeParser::new(value.trim()))
+        .filter_map(Result::ok)
+        .filter(|challenge| challenge.scheme.eq_ignore_ascii_case("Cargo"))
+        .flat_map(|challenge| challenge.params)
+        .find(|(param, _)| param.eq_ignore_ascii_case("challenge"))
+        .map(|(_, value)| value.to_unescaped())
+}
+
 /// Checks that a secret key is valid, and returns the associated public key in Paserk format.
 pub(crate) fn paserk_public_from_paserk_secret(secret_key: Secret<&str>) -> Option<String> {
     let secret: Secret<AsymmetricSecretKey<pasetors::version3::V3>> =
@@ -9,17 +9,27 @@ use std::{
 
 use anyhow::Context;
 use cargo_credential::{
-    Action, Credential, CredentialHello, CredentialRequest, CredentialResponse, Error, RegistryInfo,
+    Action, CacheControl, Credential, CredentialHello, CredentialRequest, CredentialResponse,
+    Error, RegistryInfo, PROTOCOL_VERSION_1, PROTOCOL_VERSION_2,
 };
 
 pub struct CredentialProcessCredential {
     path: PathBuf,
+    /// Protocol versions Cargo may use, from oldest to newest.
+    versions: &'static [u32],
 }
 
 impl<'a> CredentialProcessCredential {
-    pub fn new(path: &str) -> Self {
+    /// Creates a provider running the process at `path`. Protocol version 2
+    /// is only used if `protocol_v2` is set.
+    pub fn new(path: &str, protocol_v2: bool) -> Self {
         Self {
             path: PathBuf::from(path),
+            versions: if protocol_v2 {
+                &[PROTOCOL_VERSION_1, PROTOCOL_VERSION_2]
+            } else {
+                &[PROTOCOL_VERSION_1]
+            },
         }
     }
 
@@ -41,21 +51,22 @@ impl<'a> CredentialProcessCredential {
         let credential_hello: CredentialHello =
             serde_json::from_str(&buffer).context("failed to deserialize hello")?;
         tracing::debug!("credential-process > {credential_hello:?}");
-        if !credential_hello
-            .v
-            .contains(&cargo_credential::PROTOCOL_VERSION_1)
-        {
+        let Some(&v) = self
+            .versions
+            .iter()
+            .rev()
+            .find(|v| credential_hello.v.contains(v))
+        else {
             return Err(format!(
                 "credential provider supports protocol versions {:?}, while Cargo supports {:?}",
-                credential_hello.v,
-                [cargo_credential::PROTOCOL_VERSION_1]
+                credential_hello.v, self.versions
             )
             .into());
-        }
+        };
 
         // Send the Credential Request
         let req = CredentialRequest {
-            v: cargo_credential::PROTOCOL_VERSION_1,
+            v,
             action: action.clone(),
             registry: registry.clone(),
             args: args.to_vec(),
@@ -69,9 +80,26 @@ impl<'a> CredentialProcessCredential {
             .context("failed to read response from credential provider")?;
 
         // Read the Credential Response
-        let response: Result<CredentialResponse, Error> =
+        let mut response: Result<CredentialResponse, Error> =
             serde_json::from_str(&buffer).context("failed to deserialize response")?;
         tracing::debug!("credential-process > {response:?}");
+        if v == PROTOCOL_VERSION_1 {
+            // Ignore the additions of version 2, which the provider may send
+            // regardless of the version in use.
+            if let Ok(CredentialResponse::Get {
+                cache,
+                scope,
+                message,
+                ..
+            }) = &mut response
+            {
+                if matches!(cache, CacheControl::ExpiresIn { .. }) {
+                    *cache = CacheControl::Unknown;
+                }
+                *scope = None;
+                *message = None;
+            }
+        }
 
         // Tell the credential process we're done by closing stdin. It should exit cleanly.
         drop(input_to_child);
@@ -43,6 +43,8 @@ impl<'a> Credential for TokenCredential<'a> {
                     token,
                     cache: CacheControl::Session,
                     operation_independent: true,
+                    scope: None,
+                    message: None,
                 })
             }
             Action::Login(options) => {
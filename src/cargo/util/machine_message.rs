@@ -1,12 +1,17 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use cargo_util_schemas::artifact_hashes::ArtifactHashes;
 use cargo_util_schemas::core::PackageIdSpec;
+use cargo_util_schemas::test_event;
 use serde::ser;
 use serde::Serialize;
 use serde_json::{json, value::RawValue};
 
-use crate::core::compiler::CompileMode;
-use crate::core::Target;
+use crate::core::compiler::{CompileKind, CompileMode};
+use crate::core::shell::StatusFormat;
+use crate::core::{Shell, Target};
+use crate::util::CargoResult;
 
 pub trait Message: ser::Serialize {
     fn reason(&self) -> &str;
@@ -25,6 +30,10 @@ pub struct FromCompiler<'a> {
     pub manifest_path: &'a Path,
     pub target: &'a Target,
     pub message: Box<RawValue>,
+    /// SHA-256 hashes of the files the diagnostic points to, keyed by their
+    /// name in the diagnostic. Only recorded with `-Zunstable-options`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub source_hashes: BTreeMap<String, String>,
 }
 
 impl<'a> Message for FromCompiler<'a> {
@@ -38,11 +47,16 @@ pub struct Artifact<'a> {
     pub package_id: PackageIdSpec,
     pub manifest_path: PathBuf,
     pub target: &'a Target,
+    /// The target platform the artifact was built for, `null` for the host.
+    pub platform: CompileKind,
     pub profile: ArtifactProfile,
     pub features: Vec<String>,
     pub filenames: Vec<PathBuf>,
     pub executable: Option<PathBuf>,
     pub fresh: bool,
+    /// Only with `-Z artifact-hashes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<ArtifactHashes>,
 }
 
 impl<'a> Message for Artifact<'a> {
@@ -103,6 +117,21 @@ impl<'a> Message for TimingInfo<'a> {
     }
 }
 
+#[derive(Serialize)]
+pub struct TestEvent<'a> {
+    pub package_id: PackageIdSpec,
+    pub manifest_path: &'a Path,
+    pub target: &'a Target,
+    #[serde(flatten)]
+    pub event: test_event::TestEvent,
+}
+
+impl<'a> Message for TestEvent<'a> {
+    fn reason(&self) -> &str {
+        "test-event"
+    }
+}
+
 #[derive(Serialize)]
 pub struct BuildFinished {
     pub success: bool,
@@ -113,3 +142,46 @@ impl Message for BuildFinished {
         "build-finished"
     }
 }
+
+/// A step in the progress of a build, for `--status-format=json`.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum BuildStatus<'a> {
+    /// The dependencies are being resolved.
+    Resolving,
+    /// A package finished downloading.
+    Downloading {
+        package_id: PackageIdSpec,
+        downloaded: usize,
+        total: usize,
+    },
+    /// A unit started compiling, or is fresh. `current` counts the units
+    /// started so far, out of `total`.
+    Compiling {
+        id: u32,
+        package_id: PackageIdSpec,
+        target: &'a Target,
+        mode: CompileMode,
+        fresh: bool,
+        current: usize,
+        total: usize,
+    },
+    /// All units finished compiling.
+    Finished { success: bool },
+}
+
+impl<'a> Message for BuildStatus<'a> {
+    fn reason(&self) -> &str {
+        "build-status"
+    }
+}
+
+impl<'a> BuildStatus<'a> {
+    /// Prints this step to stdout if `--status-format=json` was passed.
+    pub fn emit(&self, shell: &mut Shell) -> CargoResult<()> {
+        if shell.status_format() == StatusFormat::Json {
+            writeln!(shell.out(), "{}", self.to_json_string())?;
+        }
+        Ok(())
+    }
+}
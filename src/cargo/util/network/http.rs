@@ -11,6 +11,7 @@ use curl::easy::SslVersion;
 use tracing::debug;
 use tracing::trace;
 
+use crate::core::SourceId;
 use crate::util::context::SslVersionConfig;
 use crate::util::context::SslVersionConfigRange;
 use crate::version;
@@ -24,6 +25,16 @@ pub fn http_handle(gctx: &GlobalContext) -> CargoResult<Easy> {
     Ok(handle)
 }
 
+/// Creates a new HTTP handle for requests to the registry `sid`, using its
+/// `registries.<name>.proxy` if it has one.
+pub fn registry_http_handle(gctx: &GlobalContext, sid: SourceId) -> CargoResult<Easy> {
+    let mut handle = http_handle(gctx)?;
+    if let Some(proxy) = super::proxy::registry_proxy(gctx, sid)? {
+        handle.proxy(&proxy)?;
+    }
+    Ok(handle)
+}
+
 pub fn http_handle_and_timeout(gctx: &GlobalContext) -> CargoResult<(Easy, HttpTimeout)> {
     if gctx.frozen() {
         bail!(
@@ -63,6 +74,9 @@ pub fn configure_http_handle(gctx: &GlobalContext, handle: &mut Easy) -> CargoRe
     if let Some(proxy) = super::proxy::http_proxy(http) {
         handle.proxy(&proxy)?;
     }
+    if let Some(no_proxy) = &http.no_proxy {
+        handle.noproxy(&no_proxy.as_slice().join(","))?;
+    }
     if let Some(cainfo) = &http.cainfo {
         let cainfo = cainfo.resolve_path(gctx);
         handle.cainfo(&cainfo)?;
@@ -1,7 +1,10 @@
 //! Utilities for network proxies.
 
+use crate::core::SourceId;
+use crate::util::auth;
 use crate::util::context::CargoHttpConfig;
 use crate::util::context::GlobalContext;
+use crate::CargoResult;
 
 /// Proxy environment variables that are picked up by libcurl.
 const LIBCURL_HTTP_PROXY_ENVS: [&str; 4] =
@@ -40,3 +43,39 @@ pub fn http_proxy_exists(http: &CargoHttpConfig, gctx: &GlobalContext) -> bool {
             .iter()
             .any(|v| gctx.get_env(v).is_ok())
 }
+
+/// Warns that the `registries.<name>.proxy` config of the registry `sid` is
+/// ignored without `-Zunstable-options`.
+pub fn warn_ignored_registry_proxy(gctx: &GlobalContext, sid: SourceId) -> CargoResult<()> {
+    if gctx.cli_unstable().unstable_options {
+        return Ok(());
+    }
+    let name = if sid.is_crates_io() {
+        Some("crates-io")
+    } else {
+        sid.alt_registry_key()
+    };
+    if let Some(name) = name {
+        let key = format!("registries.{name}.proxy");
+        if let Ok(Some(_)) = gctx.get::<Option<String>>(&key) {
+            gctx.shell().warn(format!(
+                "ignoring `{key}` config, pass `-Zunstable-options` to enable it"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Finds the proxy of the registry `sid` from `registries.<name>.proxy`,
+/// which overrides the proxy of [`http_proxy`] for that registry.
+///
+/// An empty proxy means connecting directly to the registry.
+pub fn registry_proxy(gctx: &GlobalContext, sid: SourceId) -> CargoResult<Option<String>> {
+    if !gctx.cli_unstable().unstable_options || !sid.is_remote_registry() {
+        return Ok(None);
+    }
+    if sid.is_crates_io() {
+        return gctx.get::<Option<String>>("registries.crates-io.proxy");
+    }
+    Ok(auth::registry_credential_config_raw(gctx, &sid)?.and_then(|cfg| cfg.proxy))
+}
@@ -36,6 +36,9 @@
 //! handle repeatedly running a callback until it succeeds, or it runs out of
 //! retries.
 //!
+//! [`fallback_to_cache`] tells whether a failure after all retries may be
+//! papered over with cached data instead.
+//!
 //! Some interesting resources about retries:
 //! - <https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/>
 //! - <https://en.wikipedia.org/wiki/Exponential_backoff>
@@ -213,6 +216,22 @@ where
     }
 }
 
+/// Whether to carry on with cached data, when there is enough of it, if a
+/// network operation fails even after retrying, as set by the unstable
+/// `net.fallback-to-cache`.
+pub fn fallback_to_cache(gctx: &GlobalContext) -> CargoResult<bool> {
+    let fallback = gctx.net_config()?.fallback_to_cache;
+    if !gctx.cli_unstable().unstable_options {
+        if fallback.is_some() {
+            gctx.shell().warn(
+                "ignoring `net.fallback-to-cache` config, pass `-Zunstable-options` to enable it",
+            )?;
+        }
+        return Ok(false);
+    }
+    Ok(fallback == Some(true))
+}
+
 #[test]
 fn with_retry_repeats_the_call_then_works() {
     use crate::core::Shell;
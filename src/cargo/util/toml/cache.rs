@@ -0,0 +1,238 @@
+//! Reuse of parsed manifests across workspace loads.
+//!
+//! Long-running consumers of Cargo as a library, like IDEs, load the same
+//! workspace over and over, reading and validating every manifest each time.
+//! With [`GlobalContext::enable_manifest_cache`], a manifest is only parsed
+//! again once something it was parsed from changed:
+//!
+//! * the contents of the manifest itself;
+//! * the contents of the workspace root manifests it inherited fields from;
+//! * the files target and `readme` discovery looks at, like `src/bin`.
+//!
+//! Everything is parsed again after [`GlobalContext::configure`] or a change
+//! of [`GlobalContext::set_lints_profile`], as the `-Z` flags, the config and
+//! the lints profile affect parsing too.
+//!
+//! [`ManifestCache::changed_manifests`] tells which cached manifests are out
+//! of date, so that a consumer can skip reloading the workspace entirely when
+//! nothing changed.
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use cargo_util::paths;
+
+use crate::core::{EitherManifest, SourceId, WorkspaceConfig};
+use crate::util::hex::hash_u64;
+use crate::util::GlobalContext;
+
+/// Directories of a package that target discovery lists.
+const DISCOVERY_DIRS: &[&str] = &["src", "src/bin", "examples", "tests", "benches"];
+
+/// Parsed manifests, along with what they were parsed from.
+#[derive(Default)]
+pub struct ManifestCache {
+    entries: RefCell<HashMap<(PathBuf, SourceId), Entry>>,
+    /// The manifests being parsed, innermost last, with the workspace root
+    /// manifests each one inherits from.
+    reading: RefCell<Vec<(PathBuf, Vec<PathBuf>)>>,
+}
+
+impl std::fmt::Debug for ManifestCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManifestCache")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+struct Entry {
+    fingerprint: Fingerprint,
+    manifest: EitherManifest,
+}
+
+/// What parsing a manifest depended on.
+#[derive(PartialEq, Eq)]
+struct Fingerprint {
+    contents: u64,
+    discovery: u64,
+    /// Workspace root manifests fields were inherited from, with the hash of
+    /// their contents.
+    inherited: Vec<(PathBuf, Option<u64>)>,
+}
+
+impl ManifestCache {
+    /// Returns the manifest at `path` if it was parsed before and nothing it
+    /// was parsed from changed since.
+    pub(crate) fn get(
+        &self,
+        path: &Path,
+        source_id: SourceId,
+        contents: &str,
+        gctx: &GlobalContext,
+    ) -> Option<EitherManifest> {
+        let entries = self.entries.borrow();
+        let entry = entries.get(&(path.to_path_buf(), source_id))?;
+        if entry.fingerprint.contents != hash_contents(contents.as_bytes())
+            || !self.is_fresh(path, &entry.fingerprint)
+        {
+            return None;
+        }
+        let manifest = entry.manifest.clone();
+        drop(entries);
+
+        // Parsing a workspace root records it for members to inherit from,
+        // which has to happen for cached ones too.
+        if let WorkspaceConfig::Root(root) = manifest.workspace_config() {
+            gctx.ws_roots
+                .borrow_mut()
+                .insert(path.parent().unwrap().to_owned(), root.clone());
+        }
+        tracing::trace!("reusing the parsed manifest of `{}`", path.display());
+        Some(manifest)
+    }
+
+    /// Starts recording what the manifest at `path` depends on while it is
+    /// parsed.
+    pub(crate) fn start_reading(&self, path: &Path) {
+        self.reading
+            .borrow_mut()
+            .push((path.to_path_buf(), Vec::new()));
+    }
+
+    /// Records that the manifest being parsed inherits from the workspace
+    /// root manifest at `ws_manifest`.
+    ///
+    /// Returns whether the workspace root recorded in
+    /// [`GlobalContext::ws_roots`] is still up to date.
+    pub(crate) fn record_inherited(&self, ws_manifest: &Path) -> bool {
+        if let Some((_, inherited)) = self.reading.borrow_mut().last_mut() {
+            inherited.push(ws_manifest.to_path_buf());
+        }
+        if self.reading.borrow().iter().any(|(p, _)| p == ws_manifest) {
+            // A root inheriting from itself, which is being parsed right now.
+            return true;
+        }
+        let current = hash_file(ws_manifest);
+        current.is_some()
+            && self.entries.borrow().iter().any(|((p, _), entry)| {
+                p == ws_manifest && Some(entry.fingerprint.contents) == current
+            })
+    }
+
+    /// Stops recording for the manifest at `path`, caching it if it parsed.
+    pub(crate) fn finish_reading(
+        &self,
+        path: &Path,
+        source_id: SourceId,
+        contents: &str,
+        manifest: Option<&EitherManifest>,
+    ) {
+        let Some((_, inherited)) = self.reading.borrow_mut().pop() else {
+            return;
+        };
+        let Some(manifest) = manifest else {
+            return;
+        };
+        let inherited = inherited
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|p| {
+                let hash = if p == path {
+                    Some(hash_contents(contents.as_bytes()))
+                } else {
+                    hash_file(&p)
+                };
+                (p, hash)
+            })
+            .collect();
+        let fingerprint = Fingerprint {
+            contents: hash_contents(contents.as_bytes()),
+            discovery: discovery_hash(path),
+            inherited,
+        };
+        self.entries.borrow_mut().insert(
+            (path.to_path_buf(), source_id),
+            Entry {
+                fingerprint,
+                manifest: manifest.clone(),
+            },
+        );
+    }
+
+    /// Returns the cached manifests that would be parsed again, because
+    /// something they were parsed from changed on disk.
+    pub fn changed_manifests(&self) -> Vec<PathBuf> {
+        let changed: BTreeSet<_> = self
+            .entries
+            .borrow()
+            .iter()
+            .filter(|((path, _), entry)| {
+                hash_file(path) != Some(entry.fingerprint.contents)
+                    || !self.is_fresh(path, &entry.fingerprint)
+            })
+            .map(|((path, _), _)| path.clone())
+            .collect();
+        changed.into_iter().collect()
+    }
+
+    /// The number of cached manifests.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    /// Forgets every cached manifest.
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+
+    /// Whether the files besides the manifest itself are unchanged.
+    fn is_fresh(&self, path: &Path, fingerprint: &Fingerprint) -> bool {
+        fingerprint.discovery == discovery_hash(path)
+            && fingerprint
+                .inherited
+                .iter()
+                .all(|(p, hash)| hash_file(p) == *hash)
+    }
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    paths::read_bytes(path)
+        .ok()
+        .map(|bytes| hash_contents(&bytes))
+}
+
+fn hash_contents(contents: &[u8]) -> u64 {
+    hash_u64(contents)
+}
+
+/// Hashes the names of the files target and `readme` discovery look for in
+/// the package of the manifest at `path`.
+fn discovery_hash(path: &Path) -> u64 {
+    let root = path.parent().unwrap();
+    let list = |dir: &Path| -> Vec<(String, bool)> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut names: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| {
+                let path = entry.path();
+                // Targets can also be directories with a `main.rs`.
+                let has_main = path.join("main.rs").is_file();
+                (entry.file_name().to_string_lossy().into_owned(), has_main)
+            })
+            .collect();
+        names.sort();
+        names
+    };
+    let mut listings = vec![list(root)];
+    listings.extend(DISCOVERY_DIRS.iter().map(|dir| list(&root.join(dir))));
+    hash_u64(listings)
+}
@@ -1,8 +1,11 @@
+use std::path::Path;
+
 use anyhow::Context as _;
 
 use cargo_util_schemas::manifest::PackageName;
 
 use crate::util::restricted_names;
+use crate::util::Filesystem;
 use crate::CargoResult;
 use crate::GlobalContext;
 
@@ -30,33 +33,32 @@ pub(super) fn expand_manifest(
 
         // HACK: until rustc has native support for this syntax, we have to remove it from the
         // source file
-        use std::fmt::Write as _;
-        let hash = crate::util::hex::short_hash(&path.to_string_lossy());
-        let mut rel_path = std::path::PathBuf::new();
-        rel_path.push("target");
-        rel_path.push(&hash[0..2]);
-        rel_path.push(&hash[2..]);
-        let target_dir = gctx.home().join(rel_path);
+        let target_dir = target_dir(path, Some(frontmatter), gctx);
         let hacked_path = target_dir
             .join(
                 path.file_name()
                     .expect("always a name for embedded manifests"),
             )
             .into_path_unlocked();
-        let mut hacked_source = String::new();
-        if let Some(shebang) = source.shebang {
-            writeln!(hacked_source, "{shebang}")?;
-        }
-        writeln!(hacked_source)?; // open
-        for _ in 0..frontmatter.lines().count() {
-            writeln!(hacked_source)?;
-        }
-        writeln!(hacked_source)?; // close
-        writeln!(hacked_source, "{}", source.content)?;
+        // Blank out the frontmatter byte for byte, so that the lines, columns
+        // and offsets of diagnostics match the script.
+        let shebang_end = source.shebang.map_or(0, |shebang| shebang.len());
+        let frontmatter_end = content.len() - source.content.len();
+        let mut hacked_source = content[..shebang_end].to_owned();
+        hacked_source.extend(
+            content.as_bytes()[shebang_end..frontmatter_end]
+                .iter()
+                .map(|&b| if b == b'\n' { '\n' } else { ' ' }),
+        );
+        hacked_source.push_str(source.content);
         if let Some(parent) = hacked_path.parent() {
             cargo_util::paths::create_dir_all(parent)?;
         }
         cargo_util::paths::write_if_changed(&hacked_path, hacked_source)?;
+        cargo_util::paths::write_if_changed(
+            target_dir.as_path_unlocked().join(SCRIPT_PATH_FILE),
+            path.to_string_lossy().as_bytes(),
+        )?;
 
         let manifest = expand_manifest_(&frontmatter, &hacked_path, gctx)
             .with_context(|| format!("failed to parse manifest at {}", path.display()))?;
@@ -71,6 +73,42 @@ pub(super) fn expand_manifest(
     }
 }
 
+/// The file in the cached target directory of a script recording the path of
+/// the script, to tell whether the directory is stale.
+pub const SCRIPT_PATH_FILE: &str = ".cargo-script";
+
+/// Returns the directory in `$CARGO_HOME/target` caching the build of the
+/// script at `path`.
+///
+/// It is keyed on the path of the script, its embedded manifest, the version
+/// of Cargo and the version and host of `rustc`, so that the lock file and the
+/// build are only reused when none of them changed.
+pub fn script_target_dir(path: &Path, gctx: &GlobalContext) -> Filesystem {
+    // Scripts are read when they are loaded, so this can only fail if the
+    // script changed since, and the next run will use the right directory.
+    let content = cargo_util::paths::read(path).unwrap_or_default();
+    let frontmatter = split_source(&content)
+        .ok()
+        .and_then(|source| source.frontmatter);
+    target_dir(path, frontmatter, gctx)
+}
+
+fn target_dir(path: &Path, frontmatter: Option<&str>, gctx: &GlobalContext) -> Filesystem {
+    // If `rustc` can't be run, neither can the build, which reports why.
+    let rustc = gctx.rustc_verbose_version().unwrap_or_default();
+    let hash = crate::util::hex::short_hash(&(
+        path.to_string_lossy(),
+        frontmatter,
+        crate::version().to_string(),
+        rustc,
+    ));
+    let mut rel_path = std::path::PathBuf::new();
+    rel_path.push("target");
+    rel_path.push(&hash[0..2]);
+    rel_path.push(&hash[2..]);
+    gctx.home().join(rel_path)
+}
+
 fn expand_manifest_(
     manifest: &str,
     path: &std::path::Path,
@@ -18,21 +18,26 @@ use url::Url;
 
 use crate::core::compiler::{CompileKind, CompileTarget};
 use crate::core::dependency::{Artifact, ArtifactTarget, DepKind};
-use crate::core::manifest::{ManifestMetadata, TargetSourcePath};
+use crate::core::manifest::{LinkInputs, ManifestMetadata, TargetSourcePath};
 use crate::core::resolver::ResolveBehavior;
 use crate::core::FeatureValue::Dep;
 use crate::core::{find_workspace_root, resolve_relative_path, CliUnstable, FeatureValue};
 use crate::core::{Dependency, Manifest, Package, PackageId, Summary, Target};
 use crate::core::{Edition, EitherManifest, Feature, Features, VirtualManifest, Workspace};
-use crate::core::{GitReference, PackageIdSpec, SourceId, WorkspaceConfig, WorkspaceRootConfig};
+use crate::core::{GitCheckoutOptions, GitReference, PackageIdSpec, SourceId};
+use crate::core::{WorkspaceConfig, WorkspaceRootConfig};
 use crate::sources::{CRATES_IO_INDEX, CRATES_IO_REGISTRY};
 use crate::util::errors::{CargoResult, ManifestError};
 use crate::util::interning::InternedString;
 use crate::util::{self, context::ConfigRelativePath, GlobalContext, IntoUrl, OptVersionReq};
 
+mod cache;
 mod embedded;
 mod targets;
 
+pub use cache::ManifestCache;
+pub use embedded::{script_target_dir, SCRIPT_PATH_FILE};
+
 use self::targets::to_targets;
 
 /// See also `bin/cargo/commands/run.rs`s `is_manifest_command`
@@ -56,12 +61,31 @@ pub fn read_manifest(
     path: &Path,
     source_id: SourceId,
     gctx: &GlobalContext,
+) -> CargoResult<EitherManifest> {
+    let contents =
+        read_toml_string(path, gctx).map_err(|err| ManifestError::new(err, path.into()))?;
+
+    let Some(cache) = gctx.manifest_cache().filter(|_| !is_embedded(path)) else {
+        return parse_manifest(contents, path, source_id, gctx);
+    };
+    if let Some(manifest) = cache.get(path, source_id, &contents, gctx) {
+        return Ok(manifest);
+    }
+    cache.start_reading(path);
+    let manifest = parse_manifest(contents.clone(), path, source_id, gctx);
+    cache.finish_reading(path, source_id, &contents, manifest.as_ref().ok());
+    manifest
+}
+
+fn parse_manifest(
+    contents: String,
+    path: &Path,
+    source_id: SourceId,
+    gctx: &GlobalContext,
 ) -> CargoResult<EitherManifest> {
     let mut warnings = Default::default();
     let mut errors = Default::default();
 
-    let contents =
-        read_toml_string(path, gctx).map_err(|err| ManifestError::new(err, path.into()))?;
     let document =
         parse_document(&contents).map_err(|e| emit_diagnostic(e.into(), &contents, path, gctx))?;
     let original_toml = deserialize_toml(&document)
@@ -205,7 +229,28 @@ fn to_workspace_config(
         original_toml.package().and_then(|p| p.workspace.as_ref()),
     ) {
         (Some(toml_config), None) => {
-            verify_lints(toml_config.lints.as_ref(), gctx, warnings)?;
+            if let Some(lints) = &toml_config.lints {
+                verify_lints(Some(&lints.lints), "lints", gctx, warnings)?;
+                for (name, profile) in &lints.profiles {
+                    verify_lints(
+                        Some(profile),
+                        &format!("lints.profile.{name}"),
+                        gctx,
+                        warnings,
+                    )?;
+                }
+            }
+            if toml_config.features.is_some() && !gctx.cli_unstable().feature_presets {
+                warnings.push(
+                    "ignoring `workspace.features`, pass `-Zfeature-presets` to enable".to_owned(),
+                );
+            }
+            if toml_config.split_target_dirs.is_some() && !gctx.cli_unstable().split_target_dirs {
+                warnings.push(
+                    "ignoring `workspace.split-target-dirs`, pass `-Zsplit-target-dirs` to enable"
+                        .to_owned(),
+                );
+            }
             if let Some(ws_deps) = &toml_config.dependencies {
                 for (name, dep) in ws_deps {
                     if dep.is_optional() {
@@ -252,6 +297,8 @@ fn to_workspace_root_config(
         &resolved_toml.exclude,
         &Some(inheritable),
         &resolved_toml.metadata,
+        &resolved_toml.features,
+        &resolved_toml.split_target_dirs,
     );
     ws_root_config
 }
@@ -487,6 +534,7 @@ fn resolve_toml(
                     build_dependencies2: None,
                     dev_dependencies: resolved_dev_dependencies,
                     dev_dependencies2: None,
+                    link_inputs: platform.link_inputs.clone(),
                 },
             );
         }
@@ -499,7 +547,8 @@ fn resolve_toml(
             .transpose()?;
         resolved_toml.lints = resolved_lints.map(|lints| manifest::InheritableLints {
             workspace: false,
-            lints,
+            lints: lints.lints,
+            profiles: lints.profiles,
         });
 
         resolved_toml.badges = original_toml.badges.clone();
@@ -547,6 +596,9 @@ fn resolve_package_toml<'a>(
             .map(manifest::InheritableField::Value),
         build: targets::resolve_build(original_package.build.as_ref(), package_root),
         metabuild: original_package.metabuild.clone(),
+        post_build: original_package.post_build.clone(),
+        build_sandbox: original_package.build_sandbox.clone(),
+        links_libs: original_package.links_libs.clone(),
         default_target: original_package.default_target.clone(),
         forced_target: original_package.forced_target.clone(),
         links: original_package.links.clone(),
@@ -750,6 +802,16 @@ fn resolve_dependencies<'a>(
                     }
                 }
             }
+            if d.shallow.is_some() || d.paths.is_some() {
+                features.require(Feature::git_checkout_options())?;
+            }
+            if [&d.branch, &d.tag]
+                .into_iter()
+                .flatten()
+                .any(|name| crate::sources::git::is_ref_glob(name))
+            {
+                features.require(Feature::git_ref_globs())?;
+            }
         }
 
         // if the dependency is not optional, it is always used
@@ -802,6 +864,14 @@ fn inheritable_from_path(
     // Workspace path should have Cargo.toml at the end
     let workspace_path_root = workspace_path.parent().unwrap();
 
+    // A workspace root recorded before may be out of date when manifests are
+    // cached across workspace loads.
+    if let Some(cache) = gctx.manifest_cache() {
+        if !cache.record_inherited(&workspace_path) {
+            gctx.ws_roots.borrow_mut().remove(workspace_path_root);
+        }
+    }
+
     // Let the borrow exit scope so that it can be picked up if there is a need to
     // read a manifest
     if let Some(ws_root) = gctx.ws_roots.borrow().get(workspace_path_root) {
@@ -844,7 +914,7 @@ macro_rules! package_field_getter {
 pub struct InheritableFields {
     package: Option<manifest::InheritablePackage>,
     dependencies: Option<BTreeMap<manifest::PackageName, manifest::TomlDependency>>,
-    lints: Option<manifest::TomlLints>,
+    lints: Option<manifest::TomlWorkspaceLints>,
 
     // Bookkeeping to help when resolving values from above
     _ws_root: PathBuf,
@@ -869,6 +939,11 @@ impl InheritableFields {
         ("version",       version       -> semver::Version),
     }
 
+    /// Gets the field `workspace.package.rust-version`, if defined.
+    pub fn package_rust_version(&self) -> Option<&RustVersion> {
+        self.package.as_ref()?.rust_version.as_ref()
+    }
+
     /// Gets a workspace dependency with the `name`.
     fn get_dependency(
         &self,
@@ -896,7 +971,7 @@ impl InheritableFields {
     }
 
     /// Gets the field `workspace.lint`.
-    pub fn lints(&self) -> CargoResult<manifest::TomlLints> {
+    pub fn lints(&self) -> CargoResult<manifest::TomlWorkspaceLints> {
         let Some(val) = &self.lints else {
             bail!("`workspace.lints` was not defined");
         };
@@ -945,17 +1020,20 @@ fn field_inherit_with<'a, T>(
 
 fn lints_inherit_with(
     lints: manifest::InheritableLints,
-    get_ws_inheritable: impl FnOnce() -> CargoResult<manifest::TomlLints>,
-) -> CargoResult<manifest::TomlLints> {
+    get_ws_inheritable: impl FnOnce() -> CargoResult<manifest::TomlWorkspaceLints>,
+) -> CargoResult<manifest::TomlWorkspaceLints> {
     if lints.workspace {
-        if !lints.lints.is_empty() {
+        if !lints.lints.is_empty() || !lints.profiles.is_empty() {
             anyhow::bail!("cannot override `workspace.lints` in `lints`, either remove the overrides or `lints.workspace = true` and manually specify the lints");
         }
         get_ws_inheritable().with_context(|| {
             "error inheriting `lints` from workspace root manifest's `workspace.lints`"
         })
     } else {
-        Ok(lints.lints)
+        Ok(manifest::TomlWorkspaceLints {
+            lints: lints.lints,
+            profiles: lints.profiles,
+        })
     }
 }
 
@@ -1196,6 +1274,18 @@ fn to_real_manifest(
         features.require(Feature::metabuild())?;
     }
 
+    if resolved_package.post_build.is_some() {
+        features.require(Feature::post_build_scripts())?;
+    }
+
+    if resolved_package.build_sandbox.is_some() {
+        features.require(Feature::build_sandbox())?;
+    }
+
+    if resolved_package.links_libs.is_some() {
+        features.require(Feature::links_libs())?;
+    }
+
     let resolve_behavior = match (
         resolved_package.resolver.as_ref(),
         resolved_toml
@@ -1223,7 +1313,10 @@ fn to_real_manifest(
         warnings,
     )?;
 
-    if targets.iter().all(|t| t.is_custom_build()) {
+    if targets
+        .iter()
+        .all(|t| t.is_custom_build() || t.is_post_build())
+    {
         bail!(
             "no targets specified in the manifest\n\
                  either src/lib.rs, src/main.rs, a [lib] section, or \
@@ -1311,6 +1404,7 @@ fn to_real_manifest(
         resolved_toml.build_dependencies(),
         Some(DepKind::Build),
     )?;
+    let mut link_inputs = Vec::new();
     for (name, platform) in resolved_toml.target.iter().flatten() {
         manifest_ctx.platform = Some(name.parse()?);
         gather_dependencies(&mut manifest_ctx, platform.dependencies.as_ref(), None)?;
@@ -1324,6 +1418,21 @@ fn to_real_manifest(
             platform.dev_dependencies(),
             Some(DepKind::Development),
         )?;
+        if let Some(inputs) = &platform.link_inputs {
+            features.require(Feature::link_inputs())?;
+            let paths = |paths: &Option<Vec<String>>| {
+                paths
+                    .iter()
+                    .flatten()
+                    .map(PathBuf::from)
+                    .collect::<Vec<_>>()
+            };
+            link_inputs.push(LinkInputs {
+                platform: name.parse()?,
+                linker_scripts: paths(&inputs.linker_scripts),
+                objects: paths(&inputs.objects),
+            });
+        }
     }
     let replace = replace(&resolved_toml, &mut manifest_ctx)?;
     let patch = patch(&resolved_toml, &mut manifest_ctx)?;
@@ -1346,15 +1455,38 @@ fn to_real_manifest(
 
     verify_lints(
         resolved_toml.resolved_lints().expect("previously resolved"),
+        "lints",
         gctx,
         warnings,
     )?;
+    if let Some(lints) = &original_toml.lints {
+        for (name, profile) in &lints.profiles {
+            verify_lints(
+                Some(profile),
+                &format!("lints.profile.{name}"),
+                gctx,
+                warnings,
+            )?;
+        }
+    }
+    if resolved_toml
+        .lints
+        .as_ref()
+        .is_some_and(|lints| !lints.profiles.is_empty())
+        || original_toml
+            .workspace
+            .as_ref()
+            .and_then(|ws| ws.lints.as_ref())
+            .is_some_and(|lints| !lints.profiles.is_empty())
+    {
+        features.require(Feature::lints_profiles())?;
+    }
     let default = manifest::TomlLints::default();
     let rustflags = lints_to_rustflags(
-        resolved_toml
-            .resolved_lints()
+        &resolved_toml
+            .resolved_lints_with_profile(gctx.lints_profile())
             .expect("previously resolved")
-            .unwrap_or(&default),
+            .unwrap_or(std::borrow::Cow::Borrowed(&default)),
     );
 
     let metadata = ManifestMetadata {
@@ -1526,6 +1658,7 @@ fn to_real_manifest(
         metabuild,
         resolve_behavior,
         rustflags,
+        link_inputs,
         embedded,
     );
     if manifest
@@ -1591,6 +1724,14 @@ fn to_virtual_manifest(
     if let Some(profiles) = &original_toml.profile {
         validate_profiles(profiles, gctx.cli_unstable(), &features, warnings)?;
     }
+    if original_toml
+        .workspace
+        .as_ref()
+        .and_then(|ws| ws.lints.as_ref())
+        .is_some_and(|lints| !lints.profiles.is_empty())
+    {
+        features.require(Feature::lints_profiles())?;
+    }
     let resolve_behavior = original_toml
         .workspace
         .as_ref()
@@ -1678,7 +1819,7 @@ fn replace(
         bail!("cannot specify both [replace] and [patch]");
     }
     let mut replace = Vec::new();
-    for (spec, replacement) in me.replace.iter().flatten() {
+    for (spec, replacement) in me.replace.iter().flat_map(|r| &r.entries) {
         let mut spec = PackageIdSpec::parse(spec).with_context(|| {
             format!(
                 "replacements must specify a valid semver \
@@ -1723,7 +1864,7 @@ fn patch(
     manifest_ctx: &mut ManifestContext<'_, '_>,
 ) -> CargoResult<HashMap<Url, Vec<Dependency>>> {
     let mut patch = HashMap::new();
-    for (toml_url, deps) in me.patch.iter().flatten() {
+    for (toml_url, deps) in me.patch.iter().flat_map(|p| &p.entries) {
         let url = match &toml_url[..] {
             CRATES_IO_REGISTRY => CRATES_IO_INDEX.parse().unwrap(),
             _ => manifest_ctx
@@ -1843,6 +1984,12 @@ fn detailed_dep_to_dependency<P: ResolveToPath + Clone>(
                 );
             }
         }
+        if orig.shallow.is_some() {
+            bail!("key `shallow` is ignored for dependency ({name_in_toml}).");
+        }
+        if orig.paths.is_some() {
+            bail!("key `paths` is ignored for dependency ({name_in_toml}).");
+        }
     }
 
     // Early detection of potentially misused feature syntax
@@ -1945,6 +2092,31 @@ fn detailed_dep_to_dependency<P: ResolveToPath + Clone>(
     Ok(dep)
 }
 
+/// Validates the `paths` of a git dependency, normalizing them so the same set
+/// of directories always ends up with the same [`SourceId`].
+fn git_checkout_paths(paths: &[String], name_in_toml: &str) -> CargoResult<Vec<String>> {
+    let mut normalized = Vec::new();
+    for path in paths {
+        let trimmed = path.trim_matches('/');
+        if trimmed.is_empty()
+            || path.starts_with('/')
+            || path.contains('\\')
+            || trimmed
+                .split('/')
+                .any(|component| matches!(component, "" | "." | ".."))
+        {
+            bail!(
+                "invalid path `{path}` in `paths` of dependency ({name_in_toml}), \
+                 expected a relative directory like `crates/foo`"
+            );
+        }
+        normalized.push(trimmed.to_owned());
+    }
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
 fn to_dependency_source_id<P: ResolveToPath + Clone>(
     orig: &manifest::TomlDetailedDependency<P>,
     name_in_toml: &str,
@@ -2001,7 +2173,12 @@ fn to_dependency_source_id<P: ResolveToPath + Clone>(
                 manifest_ctx.warnings.push(msg);
             }
 
-            SourceId::for_git(&loc, reference)
+            let checkout = GitCheckoutOptions {
+                shallow: orig.shallow.unwrap_or(false),
+                paths: git_checkout_paths(orig.paths.as_deref().unwrap_or_default(), name_in_toml)?,
+            };
+
+            Ok(SourceId::for_git(&loc, reference)?.with_git_checkout(checkout))
         }
         (None, Some(path), _, _) => {
             let path = path.resolve(manifest_ctx.gctx);
@@ -2244,6 +2421,7 @@ fn validate_profile_override(profile: &manifest::TomlProfile, which: &str) -> Ca
 
 fn verify_lints(
     lints: Option<&manifest::TomlLints>,
+    table: &str,
     gctx: &GlobalContext,
     warnings: &mut Vec<String>,
 ) -> CargoResult<()> {
@@ -2255,7 +2433,7 @@ fn verify_lints(
         let supported = ["cargo", "clippy", "rust", "rustdoc"];
         if !supported.contains(&tool.as_str()) {
             let message = format!(
-                "unrecognized lint tool `lints.{tool}`, specifying unrecognized tools may break in the future.
+                "unrecognized lint tool `{table}.{tool}`, specifying unrecognized tools may break in the future.
 supported tools: {}",
                 supported.join(", "),
             );
@@ -2269,14 +2447,14 @@ supported tools: {}",
             if let Some((prefix, suffix)) = name.split_once("::") {
                 if tool == prefix {
                     anyhow::bail!(
-                        "`lints.{tool}.{name}` is not valid lint name; try `lints.{prefix}.{suffix}`"
+                        "`{table}.{tool}.{name}` is not valid lint name; try `{table}.{prefix}.{suffix}`"
                     )
                 } else if tool == "rust" && supported.contains(&prefix) {
                     anyhow::bail!(
-                        "`lints.{tool}.{name}` is not valid lint name; try `lints.{prefix}.{suffix}`"
+                        "`{table}.{tool}.{name}` is not valid lint name; try `{table}.{prefix}.{suffix}`"
                     )
                 } else {
-                    anyhow::bail!("`lints.{tool}.{name}` is not a valid lint name")
+                    anyhow::bail!("`{table}.{tool}.{name}` is not a valid lint name")
                 }
             } else if let Some(config) = config.config() {
                 for config_name in config.keys() {
@@ -2287,7 +2465,7 @@ supported tools: {}",
                     if !(tool == "rust" && name == "unexpected_cfgs" && config_name == "check-cfg")
                     {
                         let message =
-                            format!("unused manifest key: `lints.{tool}.{name}.{config_name}`");
+                            format!("unused manifest key: `{table}.{tool}.{name}.{config_name}`");
                         warnings.push(message);
                     }
                 }
@@ -2642,6 +2820,7 @@ fn prepare_toml_for_publish(
                             dev_dependencies2: None,
                             build_dependencies: map_deps(gctx, v.build_dependencies(), all)?,
                             build_dependencies2: None,
+                            link_inputs: v.link_inputs.clone(),
                         },
                     ))
                 })
@@ -2741,6 +2920,8 @@ fn prepare_toml_for_publish(
                 d.branch.take();
                 d.tag.take();
                 d.rev.take();
+                d.shallow.take();
+                d.paths.take();
                 // registry specifications are elaborated to the index URL
                 if let Some(registry) = d.registry.take() {
                     d.registry_index = Some(gctx.get_registry_index(&registry)?.to_string());
@@ -102,6 +102,21 @@ pub(super) fn to_targets(
             edition,
         ));
     }
+    if let Some(post_build) = &package.post_build {
+        let post_build = Path::new(post_build);
+        let name = format!(
+            "post-build-script-{}",
+            post_build
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+        );
+        targets.push(Target::post_build_target(
+            &name,
+            package_root.join(post_build),
+            edition,
+        ));
+    }
     if let Some(metabuild) = metabuild {
         // Verify names match available build deps.
         let bdeps = resolved_toml.build_dependencies.as_ref();
@@ -0,0 +1,353 @@
+//! Tests for `-Zadvisories`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{git, project, str, Project};
+
+fn advisory(id: &str, package: &str, versions: &str) -> String {
+    format!(
+        r#"```toml
+[advisory]
+id = "{id}"
+package = "{package}"
+date = "2020-01-01"
+url = "https://example.com/{id}"
+
+[versions]
+{versions}
+```
+
+# Use after free in `{package}`
+
+Details.
+"#
+    )
+}
+
+/// A project depending on `foo`, with an advisory database in `db` that
+/// has an advisory for `foo` patched in 1.2.0.
+fn project_with_db(policy: &str) -> Project {
+    Package::new("foo", "1.0.0").publish();
+    Package::new("foo", "1.2.0").publish();
+    Package::new("bar", "1.0.0").publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                foo = "=1.0.0"
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [advisories]
+                    database = "db"
+                    policy = "{policy}"
+                "#
+            ),
+        )
+        .file(
+            "db/crates/foo/RUSTSEC-2020-0001.md",
+            &advisory(
+                "RUSTSEC-2020-0001",
+                "foo",
+                r#"patched = [">= 1.2.0"]
+unaffected = ["< 0.5.0"]"#,
+            ),
+        )
+        .file(
+            "db/crates/bar/RUSTSEC-2020-0002.md",
+            &advisory("RUSTSEC-2020-0002", "bar", r#"patched = [">= 1.0.0"]"#),
+        )
+        .build()
+}
+
+#[cargo_test]
+fn warn_on_new_packages() {
+    let p = project_with_db("warn");
+
+    p.cargo("generate-lockfile -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] foo v1.0.0 is affected by RUSTSEC-2020-0001 (Use after free in `foo`), patched in `>=1.2.0`
+[LOCKING] 3 packages to latest compatible versions
+[ADDING] foo v1.0.0 (latest: v1.2.0)
+
+"#]])
+        .run();
+    assert!(p.root().join("Cargo.lock").exists());
+
+    // Packages already in the lock file aren't checked again.
+    p.cargo("check -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stderr_data(
+            str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[CHECKING] bar v1.0.0
+[CHECKING] foo v1.0.0
+[CHECKING] a v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2015"
+
+            [dependencies]
+            foo = "1.0"
+            bar = "1.0"
+        "#,
+    );
+    p.cargo("update foo -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[UPDATING] foo v1.0.0 -> v1.2.0
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn deny() {
+    let p = project_with_db("deny");
+
+    p.cargo("check -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] 1 package is affected by advisories of `[ROOT]/foo/db`:
+  foo v1.0.0 is affected by RUSTSEC-2020-0001 (Use after free in `foo`), patched in `>=1.2.0`
+
+update the packages, or list the advisories in `advisories.ignore` to allow them
+
+"#]])
+        .run();
+    assert!(!p.root().join("Cargo.lock").exists());
+
+    p.cargo("check -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .env("CARGO_ADVISORIES_IGNORE", "RUSTSEC-2020-0001")
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[ADDING] foo v1.0.0 (latest: v1.2.0)
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[CHECKING] bar v1.0.0
+[CHECKING] foo v1.0.0
+[CHECKING] a v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn report() {
+    let p = project_with_db("deny");
+    p.cargo("generate-lockfile").run();
+
+    p.cargo("report advisories -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_status(101)
+        .with_stdout_data(str![[r#"
+RUSTSEC-2020-0001: Use after free in `foo`
+  package: foo v1.0.0
+  patched: >=1.2.0
+  url: https://example.com/RUSTSEC-2020-0001
+
+"#]])
+        .with_stderr_data(str![[r#"
+[ERROR] 1 package is affected by advisories
+
+"#]])
+        .run();
+
+    p.cargo("report advisories -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .env("CARGO_ADVISORIES_IGNORE", "RUSTSEC-2020-0001")
+        .with_stderr_data(str![[r#"
+[NOTE] no advisories of `[ROOT]/foo/db` affect the 3 packages of the lock file
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn withdrawn() {
+    let p = project_with_db("deny");
+    p.change_file(
+        "db/crates/foo/RUSTSEC-2020-0001.md",
+        &advisory("RUSTSEC-2020-0001", "foo", r#"patched = [">= 1.2.0"]"#)
+            .replace("[versions]", "withdrawn = \"2020-02-01\"\n\n[versions]"),
+    );
+
+    p.cargo("generate-lockfile -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[ADDING] foo v1.0.0 (latest: v1.2.0)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn informational() {
+    let p = project_with_db("deny");
+    p.change_file(
+        "db/crates/foo/RUSTSEC-2020-0001.md",
+        &advisory("RUSTSEC-2020-0001", "foo", r#"patched = [">= 1.2.0"]"#).replace(
+            "[versions]",
+            "informational = \"unmaintained\"\n\n[versions]",
+        ),
+    );
+
+    // Only warned about, even with `policy = "deny"`.
+    p.cargo("generate-lockfile -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] foo v1.0.0 is affected by RUSTSEC-2020-0001 [unmaintained] (Use after free in `foo`), patched in `>=1.2.0`
+[LOCKING] 3 packages to latest compatible versions
+[ADDING] foo v1.0.0 (latest: v1.2.0)
+
+"#]])
+        .run();
+
+    p.cargo("report advisories -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stdout_data(str![[r#"
+RUSTSEC-2020-0001: Use after free in `foo`
+  package: foo v1.0.0
+  informational: unmaintained
+  patched: >=1.2.0
+  url: https://example.com/RUSTSEC-2020-0001
+
+"#]])
+        .with_stderr_data(str![[r#"
+[WARNING] 1 package is affected by advisories
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn git_database() {
+    let db = git::new("advisory-db", |p| {
+        p.file(
+            "crates/foo/RUSTSEC-2020-0001.md",
+            &advisory("RUSTSEC-2020-0001", "foo", r#"patched = [">= 1.2.0"]"#),
+        )
+    });
+    let p = project_with_db("warn");
+    p.change_file(
+        ".cargo/config.toml",
+        &format!(
+            r#"
+                [advisories]
+                database = "{}"
+            "#,
+            db.url()
+        ),
+    );
+
+    p.cargo("report advisories -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_stdout_data(str![[r#"
+RUSTSEC-2020-0001: Use after free in `foo`
+  package: foo v1.0.0
+  patched: >=1.2.0
+  url: https://example.com/RUSTSEC-2020-0001
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[ADDING] foo v1.0.0 (latest: v1.2.0)
+[UPDATING] git repository `[ROOTURL]/advisory-db`
+[WARNING] 1 package is affected by advisories
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_advisory() {
+    let p = project_with_db("warn");
+    p.change_file("db/crates/foo/RUSTSEC-2020-0001.md", "# Not an advisory\n");
+
+    p.cargo("generate-lockfile -Zadvisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] failed to parse advisory `[ROOT]/foo/db/crates/foo/RUSTSEC-2020-0001.md`
+
+Caused by:
+  expected the advisory to start with a ```toml block
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project_with_db("deny");
+
+    // Without `-Zadvisories` the config is ignored.
+    p.cargo("generate-lockfile")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[ADDING] foo v1.0.0 (latest: v1.2.0)
+
+"#]])
+        .run();
+
+    p.cargo("report advisories")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo report advisories` command is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    p.cargo("report advisories")
+        .masquerade_as_nightly_cargo(&["advisories"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo report advisories` command is unstable, pass `-Z advisories` to enable it
+
+"#]])
+        .run();
+}
@@ -5,7 +5,7 @@ use cargo_test_support::prelude::*;
 use cargo_test_support::publish::validate_alt_upload;
 use cargo_test_support::registry::{self, Package, RegistryBuilder};
 use cargo_test_support::str;
-use cargo_test_support::{basic_manifest, paths, project};
+use cargo_test_support::{basic_manifest, git, path2url, paths, project};
 use std::fs;
 
 #[cargo_test]
@@ -89,8 +89,8 @@ fn depend_on_alt_registry_depends_on_same_registry_no_index() {
 [UPDATING] `alternative` index
 [LOCKING] 3 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] baz v0.0.1 (registry `alternative`)
 [DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[DOWNLOADED] baz v0.0.1 (registry `alternative`)
 [CHECKING] baz v0.0.1 (registry `alternative`)
 [CHECKING] bar v0.0.1 (registry `alternative`)
 [CHECKING] foo v0.0.1 ([ROOT]/foo)
@@ -132,8 +132,8 @@ fn depend_on_alt_registry_depends_on_same_registry() {
 [UPDATING] `alternative` index
 [LOCKING] 3 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] baz v0.0.1 (registry `alternative`)
 [DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[DOWNLOADED] baz v0.0.1 (registry `alternative`)
 [CHECKING] baz v0.0.1 (registry `alternative`)
 [CHECKING] bar v0.0.1 (registry `alternative`)
 [CHECKING] foo v0.0.1 ([ROOT]/foo)
@@ -1979,3 +1979,222 @@ fn empty_dependency_registry() {
 "#]])
         .run();
 }
+
+const SIGNING_KEY: &str =
+    "k3.secret.fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36";
+const TRUSTED_KEY: &str =
+    "k3.public.AmDwjlyf8jAV3gm5Z7Kz9xAOcsKslt_Vwp5v-emjFzBHLCtcANzTaVEghTNEMj9PkQ";
+
+fn require_signatures_project() -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.alternative]
+                    require-signatures = true
+                    trusted-keys = ["{TRUSTED_KEY}"]
+                "#
+            ),
+        )
+        .build()
+}
+
+#[cargo_test]
+fn require_signatures() {
+    registry::alt_init();
+    Package::new("bar", "0.0.1")
+        .alternative(true)
+        .signing_key(SIGNING_KEY)
+        .publish();
+
+    let p = require_signatures_project();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["require-signatures"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[CHECKING] bar v0.0.1 (registry `alternative`)
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn require_signatures_unsigned() {
+    registry::alt_init();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    let p = require_signatures_project();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["require-signatures"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  failed to verify the signature of `bar v0.0.1` from registry `alternative`
+
+Caused by:
+  the index entry is not signed
+
+"#]])
+        .run();
+
+    // Without `-Zunstable-options` the registry can't be used at all.
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  registry `alternative` requires signatures, which is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn require_signatures_tampered_entry() {
+    registry::alt_init();
+    Package::new("baz", "0.0.1").alternative(true).publish();
+    Package::new("bar", "0.0.1")
+        .alternative(true)
+        .signing_key(SIGNING_KEY)
+        .publish();
+
+    // Add a dependency to the signed entry, keeping its signature.
+    let index = paths::root().join("alternative-registry");
+    let path = index.join("3/b/bar");
+    let mut entry: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    entry["deps"] = serde_json::json!([{
+        "name": "baz",
+        "req": "^0.0.1",
+        "features": [],
+        "optional": false,
+        "default_features": true,
+        "target": null,
+        "kind": "normal",
+        "registry": null,
+    }]);
+    fs::write(&path, format!("{entry}\n")).unwrap();
+    let repo = git2::Repository::open(&index).unwrap();
+    git::add(&repo);
+    git::commit(&repo);
+
+    let p = require_signatures_project();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["require-signatures"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  failed to verify the signature of `bar v0.0.1` from registry `alternative`
+
+Caused by:
+  the signature is for `bar v0.0.1` with different contents than its index entry
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn require_signatures_replaced() {
+    registry::alt_init();
+    Package::new("bar", "0.0.1")
+        .alternative(true)
+        .signing_key(SIGNING_KEY)
+        .publish();
+
+    let p = require_signatures_project();
+    p.change_file(
+        ".cargo/config.toml",
+        &format!(
+            r#"
+                [registries.alternative]
+                require-signatures = true
+                trusted-keys = ["{TRUSTED_KEY}"]
+
+                [source.alternative]
+                registry = "{}"
+                replace-with = "mirror"
+
+                [source.mirror]
+                registry = "{}"
+            "#,
+            path2url(paths::root().join("alternative-registry")),
+            path2url(registry::registry_path()),
+        ),
+    );
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["require-signatures"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[NOTE] name of alternative registry `[ROOTURL]/registry` set to `dummy-registry`
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  failed to load source for dependency `bar`
+
+Caused by:
+  Unable to update registry `alternative`
+
+Caused by:
+  cannot replace `alternative` with `mirror`, the source `alternative` requires signed index entries, but `mirror` does not
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn require_signatures_untrusted_key() {
+    registry::alt_init();
+    Package::new("bar", "0.0.1")
+        .alternative(true)
+        .signing_key("k3.secret.9Vxr5hVlI_g_orBZN54vPz20bmB4O76wB_MVqUSuJJJqHFLwP8kdn_RY5g6J6pQG")
+        .publish();
+
+    let p = require_signatures_project();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["require-signatures"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  failed to verify the signature of `bar v0.0.1` from registry `alternative`
+
+Caused by:
+  the signature does not match any of the trusted keys
+
+"#]])
+        .run();
+}
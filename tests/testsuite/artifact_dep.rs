@@ -3326,3 +3326,58 @@ staticlib present: true
 "#]],
     );
 }
+
+#[cargo_test]
+fn unit_graph_reports_artifact_dependencies() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.0"
+                edition = "2015"
+                resolver = "2"
+
+                [dependencies]
+                renamed = { package = "bar", path = "bar/", artifact = "bin", lib = true }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_bin_manifest("bar"))
+        .file("bar/src/main.rs", "fn main() {}")
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    let output = p
+        .cargo("build -Z bindeps -Z unstable-options --unit-graph")
+        .masquerade_as_nightly_cargo(&["bindeps", "unit-graph"])
+        .exec_with_output()
+        .unwrap();
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let units = graph["units"].as_array().unwrap();
+    let root = &units[graph["roots"][0].as_u64().unwrap() as usize];
+    let mut deps = root["dependencies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|dep| {
+            let unit = &units[dep["index"].as_u64().unwrap() as usize];
+            (
+                unit["target"]["kind"][0].as_str().unwrap(),
+                dep["artifact"].clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    deps.sort_by_key(|(kind, _)| *kind);
+    assert_eq!(
+        deps,
+        [
+            (
+                "bin",
+                serde_json::json!({ "dep_name": "renamed", "type": "bin" })
+            ),
+            ("lib", serde_json::Value::Null),
+        ]
+    );
+}
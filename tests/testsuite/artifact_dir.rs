@@ -4,6 +4,10 @@ use cargo_test_support::prelude::*;
 use cargo_test_support::sleep_ms;
 use cargo_test_support::str;
 use cargo_test_support::{basic_manifest, project};
+use cargo_util::Sha256;
+use cargo_util_schemas::artifact_dir::{
+    ArtifactManifest, ARTIFACT_MANIFEST_FILE, ARTIFACT_MANIFEST_VERSION,
+};
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -14,10 +18,7 @@ fn binary_with_debug() {
         .file("src/main.rs", r#"fn main() { println!("Hello, World!") }"#)
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .enable_mac_dsym()
-        .run();
+    p.cargo("build --artifact-dir out").enable_mac_dsym().run();
     check_dir_contents(
         &p.root().join("out"),
         &["foo"],
@@ -51,9 +52,7 @@ fn static_library_with_debug() {
         )
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .run();
+    p.cargo("build --artifact-dir out").run();
     check_dir_contents(
         &p.root().join("out"),
         &["libfoo.a"],
@@ -87,10 +86,7 @@ fn dynamic_library_with_debug() {
         )
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .enable_mac_dsym()
-        .run();
+    p.cargo("build --artifact-dir out").enable_mac_dsym().run();
     check_dir_contents(
         &p.root().join("out"),
         &["libfoo.so"],
@@ -123,9 +119,7 @@ fn rlib_with_debug() {
         )
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .run();
+    p.cargo("build --artifact-dir out").run();
     check_dir_contents(
         &p.root().join("out"),
         &["libfoo.rlib"],
@@ -167,8 +161,7 @@ fn include_only_the_binary_from_the_current_package() {
         .file("utils/src/lib.rs", "")
         .build();
 
-    p.cargo("build -Z unstable-options --bin foo --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
+    p.cargo("build --bin foo --artifact-dir out")
         .enable_mac_dsym()
         .run();
     check_dir_contents(
@@ -187,8 +180,7 @@ fn artifact_dir_is_a_file() {
         .file("out", "")
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
+    p.cargo("build --artifact-dir out")
         .with_status(101)
         .with_stderr_data(str![[r#"
 [COMPILING] foo v0.0.1 ([ROOT]/foo)
@@ -207,9 +199,7 @@ fn replaces_artifacts() {
         .file("src/main.rs", r#"fn main() { println!("foo") }"#)
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .run();
+    p.cargo("build --artifact-dir out").run();
     p.process(
         &p.root()
             .join(&format!("out/foo{}", env::consts::EXE_SUFFIX)),
@@ -223,9 +213,7 @@ foo
     sleep_ms(1000);
     p.change_file("src/main.rs", r#"fn main() { println!("bar") }"#);
 
-    p.cargo("build -Z unstable-options --artifact-dir out")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .run();
+    p.cargo("build --artifact-dir out").run();
     p.process(
         &p.root()
             .join(&format!("out/foo{}", env::consts::EXE_SUFFIX)),
@@ -255,8 +243,7 @@ fn avoid_build_scripts() {
         .file("b/build.rs", r#"fn main() { println!("hello-build-b"); }"#)
         .build();
 
-    p.cargo("build -Z unstable-options --artifact-dir out -vv")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
+    p.cargo("build --artifact-dir out -vv")
         .enable_mac_dsym()
         .with_stdout_data(
             str![[r#"
@@ -289,10 +276,7 @@ fn cargo_build_artifact_dir() {
         )
         .build();
 
-    p.cargo("build -Z unstable-options")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
-        .enable_mac_dsym()
-        .run();
+    p.cargo("build").enable_mac_dsym().run();
     check_dir_contents(
         &p.root().join("out"),
         &["foo"],
@@ -308,8 +292,7 @@ fn unsupported_short_artifact_dir_flag() {
         .file("src/main.rs", r#"fn main() { println!("Hello, World!") }"#)
         .build();
 
-    p.cargo("build -Z unstable-options -O")
-        .masquerade_as_nightly_cargo(&["artifact-dir"])
+    p.cargo("build -O")
         .with_stderr_data(str![[r#"
 [ERROR] unexpected argument '-O' found
 
@@ -381,6 +364,95 @@ fn cargo_build_deprecated_out_dir() {
     );
 }
 
+#[cargo_test]
+fn deprecated_out_dir_gated() {
+    let p = project()
+        .file("src/main.rs", r#"fn main() { println!("Hello, World!") }"#)
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [build]
+            out-dir = "out"
+            "#,
+        )
+        .build();
+
+    p.cargo("build --out-dir out")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--out-dir` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/6790 for more information about the `--out-dir` flag.
+
+"#]])
+        .run();
+
+    p.cargo("build")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `build.out-dir` config, pass `-Zunstable-options` to enable it or use `build.artifact-dir` instead
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(!p.root().join("out").exists());
+}
+
+#[cargo_test]
+fn artifact_manifest() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [lib]
+                crate-type = ["rlib"]
+            "#,
+        )
+        .file("src/lib.rs", "pub fn foo() {}")
+        .file("src/main.rs", "fn main() { foo::foo(); }")
+        .file("examples/ex.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build --bins --examples --artifact-dir out").run();
+
+    let manifest: ArtifactManifest =
+        serde_json::from_str(&p.read_file(&format!("out/{ARTIFACT_MANIFEST_FILE}"))).unwrap();
+    assert_eq!(manifest.version, ARTIFACT_MANIFEST_VERSION);
+    let mut artifacts = manifest.artifacts;
+    artifacts.sort_by(|a, b| a.target_name.cmp(&b.target_name));
+    let names = artifacts
+        .iter()
+        .map(|a| (a.target_name.as_str(), a.target_kind.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            ("ex", vec!["example".to_string()]),
+            ("foo", vec!["bin".to_string()]),
+        ]
+    );
+    for artifact in &artifacts {
+        assert_eq!(artifact.package_id.name(), "foo");
+        assert_eq!(artifact.kind, None);
+        let exe = format!("{}{}", artifact.target_name, env::consts::EXE_SUFFIX);
+        let file = artifact
+            .files
+            .iter()
+            .find(|f| f.path == Path::new(&exe))
+            .unwrap();
+        let sha256 = Sha256::new()
+            .update_path(p.root().join("out").join(&exe))
+            .unwrap()
+            .finish_hex();
+        assert_eq!(file.sha256.as_ref(), Some(&sha256));
+    }
+}
+
 fn check_dir_contents(
     artifact_dir: &Path,
     expected_linux: &[&str],
@@ -402,6 +474,7 @@ fn check_dir_contents(
 
     let actual = list_dir(artifact_dir);
     let mut expected = expected.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    expected.push(ARTIFACT_MANIFEST_FILE.to_string());
     expected.sort_unstable();
     assert_eq!(actual, expected);
 }
@@ -0,0 +1,97 @@
+//! Tests for `-Zartifact-hashes`.
+
+use std::collections::HashSet;
+use std::fs;
+
+use cargo_test_support::{project, Project};
+use cargo_util::Sha256;
+use cargo_util_schemas::artifact_hashes::ArtifactHashes;
+use serde_json::Value;
+
+/// Builds with `args` and returns the `compiler-artifact` messages.
+fn artifacts(p: &Project, args: &str) -> Vec<Value> {
+    let output = p
+        .cargo(args)
+        .masquerade_as_nightly_cargo(&["artifact-hashes"])
+        .exec_with_output()
+        .unwrap();
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter(|msg| msg["reason"] == "compiler-artifact")
+        .collect()
+}
+
+/// The hashes written to the `.fingerprint` directory.
+fn fingerprint_hashes(p: &Project) -> HashSet<String> {
+    let mut hashes = HashSet::new();
+    for dir in fs::read_dir(p.build_dir().join("debug/.fingerprint")).unwrap() {
+        for file in fs::read_dir(dir.unwrap().path()).unwrap() {
+            let path = file.unwrap().path();
+            if path.is_file() && path.extension().is_none() {
+                hashes.insert(fs::read_to_string(path).unwrap());
+            }
+        }
+    }
+    hashes
+}
+
+/// Checks the hashes of a message against the files and the fingerprints.
+fn check(p: &Project, msg: &Value) -> ArtifactHashes {
+    let hashes: ArtifactHashes = serde_json::from_value(msg["hashes"].clone()).unwrap();
+    assert!(fingerprint_hashes(p).contains(&hashes.fingerprint));
+    let filenames: Vec<_> = msg["filenames"].as_array().unwrap().iter().collect();
+    assert_eq!(filenames.len(), hashes.files.len());
+    for (filename, file) in filenames.into_iter().zip(&hashes.files) {
+        assert_eq!(filename.as_str().unwrap(), file.path.to_str().unwrap());
+        let sha256 = Sha256::new().update_path(&file.path).unwrap().finish_hex();
+        assert_eq!(file.sha256.as_ref(), Some(&sha256));
+    }
+    hashes
+}
+
+#[cargo_test]
+fn hashes() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() {}")
+        .file("src/main.rs", "fn main() { foo::f(); }")
+        .build();
+
+    let build = "build --message-format=json -Zartifact-hashes";
+    let dirty = artifacts(&p, build);
+    assert_eq!(dirty.len(), 2);
+    let dirty: Vec<_> = dirty.iter().map(|msg| check(&p, msg)).collect();
+
+    // Fresh units have the same hashes.
+    let fresh = artifacts(&p, build);
+    assert!(fresh.iter().all(|msg| msg["fresh"] == true));
+    let fresh: Vec<_> = fresh.iter().map(|msg| check(&p, msg)).collect();
+    assert_eq!(dirty, fresh);
+
+    // The files change with the source, the fingerprint doesn't.
+    p.change_file("src/lib.rs", "pub fn f() { println!(); }");
+    let changed = artifacts(&p, build);
+    let lib = check(&p, &changed[0]);
+    assert_eq!(lib.fingerprint, dirty[0].fingerprint);
+    assert_ne!(lib.files, dirty[0].files);
+
+    // Unlike with another profile.
+    let release = artifacts(
+        &p,
+        "build --release --message-format=json -Zartifact-hashes",
+    );
+    assert_ne!(
+        release[0]["hashes"]["fingerprint"],
+        changed[0]["hashes"]["fingerprint"]
+    );
+}
+
+#[cargo_test]
+fn not_without_flag() {
+    let p = project().file("src/lib.rs", "").build();
+
+    let msgs = artifacts(&p, "build --message-format=json");
+    assert_eq!(msgs.len(), 1);
+    assert!(msgs[0].get("hashes").is_none());
+}
@@ -0,0 +1,116 @@
+//! Tests for `cargo bench --save-baseline` and `--baseline`.
+
+use cargo_test_support::{project, str, Project};
+
+/// A benchmark that reports the time in `BENCH_NS` for `add`, in the output
+/// format of libtest.
+fn bench_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [[bench]]
+                name = "b"
+                harness = false
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "benches/b.rs",
+            r#"
+                fn main() {
+                    let ns = std::env::var("BENCH_NS").unwrap();
+                    println!("test add ... bench: {ns:>12} ns/iter (+/- 10)");
+                    if std::env::var("BENCH_NEW").is_ok() {
+                        println!("test sub ... bench: {ns:>12} ns/iter (+/- 10)");
+                    }
+                }
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = bench_project();
+
+    p.cargo("bench --save-baseline main")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--save-baseline` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn save_and_compare() {
+    let p = bench_project();
+
+    p.cargo("bench -Zunstable-options --bench b --save-baseline main")
+        .masquerade_as_nightly_cargo(&["save-baseline"])
+        .env("BENCH_NS", "1,000")
+        .with_stdout_data(str![[r#"
+test add ... bench: [..] ns/iter (+/- [..])
+
+"#]])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `bench` profile [optimized] target(s) in [ELAPSED]s
+[RUNNING] benches/b.rs (target/release/deps/b-[HASH][EXE])
+       Saved baseline `main` to [ROOT]/foo/target/benches/baselines/main.json
+
+"#]])
+        .run();
+    assert!(p
+        .root()
+        .join("target/benches/baselines/main.json")
+        .is_file());
+
+    p.cargo("bench -Zunstable-options --bench b --baseline main")
+        .masquerade_as_nightly_cargo(&["baseline"])
+        .env("BENCH_NS", "1100.5")
+        .env("BENCH_NEW", "1")
+        .with_stderr_data(str![[r#"
+[FINISHED] `bench` profile [optimized] target(s) in [ELAPSED]s
+[RUNNING] benches/b.rs (target/release/deps/b-[HASH][EXE])
+    Baseline add: [AVG_ELAPSED] ns/iter -> [AVG_ELAPSED] ns/iter (+10.05%)
+    Baseline sub: not in the baseline
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn missing_baseline() {
+    let p = bench_project();
+
+    p.cargo("bench -Zunstable-options --baseline main")
+        .masquerade_as_nightly_cargo(&["baseline"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] no baseline named `main` was found, save one with `--save-baseline main`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_baseline_name() {
+    let p = bench_project();
+
+    p.cargo("bench -Zunstable-options --save-baseline ../main")
+        .masquerade_as_nightly_cargo(&["save-baseline"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid baseline name `../main`, it must be usable as a file name
+
+"#]])
+        .run();
+}
@@ -4167,6 +4167,7 @@ fn compiler_json_error_format() {
                     "src_path":"[..]build.rs",
                     "test": false
                 },
+                "platform": null,
                 "profile": {
                     "debug_assertions": true,
                     "debuginfo": 0,
@@ -4199,6 +4200,7 @@ fn compiler_json_error_format() {
 
             {
                 "reason":"compiler-artifact",
+                "platform": null,
                 "profile": {
                     "debug_assertions": true,
                     "debuginfo": 2,
@@ -4268,6 +4270,7 @@ fn compiler_json_error_format() {
                     "src_path":"[..]main.rs",
                     "test": true
                 },
+                "platform": null,
                 "profile": {
                     "debug_assertions": true,
                     "debuginfo": 2,
@@ -4356,6 +4359,7 @@ fn message_format_json_forward_stderr() {
                         "src_path":"[..]",
                         "test": true
                     },
+                    "platform": null,
                     "profile":{
                         "debug_assertions":false,
                         "debuginfo":0,
@@ -3,7 +3,26 @@
 #![allow(deprecated)]
 
 use cargo_test_support::registry::Package;
-use cargo_test_support::{basic_bin_manifest, basic_manifest, main_file, project};
+use cargo_test_support::{basic_bin_manifest, basic_manifest, main_file, project, str, t};
+use cargo_util_schemas::build_plan::{BuildPlan, BUILD_PLAN_VERSION};
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/foo.rs", &main_file(r#""i am foo""#, &[]))
+        .build();
+
+    p.cargo("build --build-plan")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--build-plan` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/5579 for more information about the `--build-plan` flag.
+
+"#]])
+        .run();
+}
 
 #[cargo_test]
 fn cargo_build_plan_simple() {
@@ -17,7 +36,9 @@ fn cargo_build_plan_simple() {
         .with_json(
             r#"
             {
+                "version": 2,
                 "inputs": [
+                    "[..]/foo/Cargo.lock",
                     "[..]/foo/Cargo.toml"
                 ],
                 "invocations": [
@@ -29,8 +50,11 @@ fn cargo_build_plan_simple() {
                         "kind": null,
                         "links": "{...}",
                         "outputs": "{...}",
+                        "package_id": "path+file://[..]/foo#0.5.0",
                         "package_name": "foo",
                         "package_version": "0.5.0",
+                        "target_name": "foo",
+                        "target_src_path": "[..]/foo/src/foo.rs",
                         "program": "rustc",
                         "target_kind": ["bin"],
                         "compile_mode": "build"
@@ -76,7 +100,9 @@ fn cargo_build_plan_single_dep() {
         .with_json(
             r#"
             {
+                "version": 2,
                 "inputs": [
+                    "[..]/foo/Cargo.lock",
                     "[..]/foo/Cargo.toml",
                     "[..]/foo/bar/Cargo.toml"
                 ],
@@ -92,8 +118,11 @@ fn cargo_build_plan_single_dep() {
                             "[..]/foo/target/debug/deps/libbar-[..].rlib",
                             "[..]/foo/target/debug/deps/libbar-[..].rmeta"
                         ],
+                        "package_id": "path+file://[..]/foo/bar#0.0.1",
                         "package_name": "bar",
                         "package_version": "0.0.1",
+                        "target_name": "bar",
+                        "target_src_path": "[..]/foo/bar/src/lib.rs",
                         "program": "rustc",
                         "target_kind": ["lib"],
                         "compile_mode": "build"
@@ -109,8 +138,11 @@ fn cargo_build_plan_single_dep() {
                             "[..]/foo/target/debug/deps/libfoo-[..].rlib",
                             "[..]/foo/target/debug/deps/libfoo-[..].rmeta"
                         ],
+                        "package_id": "path+file://[..]/foo#0.5.0",
                         "package_name": "foo",
                         "package_version": "0.5.0",
+                        "target_name": "foo",
+                        "target_src_path": "[..]/foo/src/lib.rs",
                         "program": "rustc",
                         "target_kind": ["lib"],
                         "compile_mode": "build"
@@ -145,7 +177,9 @@ fn cargo_build_plan_build_script() {
         .with_json(
             r#"
             {
+                "version": 2,
                 "inputs": [
+                    "[..]/foo/Cargo.lock",
                     "[..]/foo/Cargo.toml"
                 ],
                 "invocations": [
@@ -157,8 +191,11 @@ fn cargo_build_plan_build_script() {
                         "kind": null,
                         "links": "{...}",
                         "outputs": "{...}",
+                        "package_id": "path+file://[..]/foo#0.5.0",
                         "package_name": "foo",
                         "package_version": "0.5.0",
+                        "target_name": "build-script-build",
+                        "target_src_path": "[..]/foo/build.rs",
                         "program": "rustc",
                         "target_kind": ["custom-build"],
                         "compile_mode": "build"
@@ -171,8 +208,11 @@ fn cargo_build_plan_build_script() {
                         "kind": null,
                         "links": "{...}",
                         "outputs": [],
+                        "package_id": "path+file://[..]/foo#0.5.0",
                         "package_name": "foo",
                         "package_version": "0.5.0",
+                        "target_name": "build-script-build",
+                        "target_src_path": "[..]/foo/build.rs",
                         "program": "[..]/build-script-build",
                         "target_kind": ["custom-build"],
                         "compile_mode": "run-custom-build"
@@ -185,8 +225,11 @@ fn cargo_build_plan_build_script() {
                         "kind": null,
                         "links": "{...}",
                         "outputs": "{...}",
+                        "package_id": "path+file://[..]/foo#0.5.0",
                         "package_name": "foo",
                         "package_version": "0.5.0",
+                        "target_name": "foo",
+                        "target_src_path": "[..]/foo/src/main.rs",
                         "program": "rustc",
                         "target_kind": ["bin"],
                         "compile_mode": "build"
@@ -222,3 +265,82 @@ fn build_plan_with_dev_dep() {
         .masquerade_as_nightly_cargo(&["build-plan"])
         .run();
 }
+
+#[cargo_test]
+fn build_plan_matches_schema() {
+    let p = project()
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/foo.rs", &main_file(r#""i am foo""#, &[]))
+        .build();
+
+    let output = t!(p
+        .cargo("build --build-plan -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["build-plan"])
+        .exec_with_output());
+    let plan: BuildPlan = t!(serde_json::from_slice(&output.stdout));
+    assert_eq!(plan.version, BUILD_PLAN_VERSION);
+    assert_eq!(plan.invocations.len(), 1);
+    let invocation = &plan.invocations[0];
+    assert_eq!(invocation.package_id.name(), "foo");
+    assert_eq!(invocation.target_name, "foo");
+    assert_eq!(
+        invocation.target_src_path.as_deref(),
+        Some(p.root().join("src/foo.rs").as_path())
+    );
+}
+
+#[cargo_test]
+fn build_plan_reuses_build_script_output() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.5.0"
+                edition = "2015"
+                links = "foo"
+            "#,
+        )
+        .file("src/main.rs", "#[cfg(has_foo)] fn main() {}")
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    println!("cargo::rerun-if-changed=build.rs");
+                    println!("cargo::rustc-check-cfg=cfg(has_foo)");
+                    println!("cargo::rustc-cfg=has_foo");
+                    println!("cargo::rustc-env=FOO_VERSION=1.2.3");
+                    println!("cargo::metadata=include=/include");
+                }
+            "#,
+        )
+        .build();
+
+    // Before the build script ran, its output is unknown.
+    let output = t!(p
+        .cargo("build --build-plan -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["build-plan"])
+        .exec_with_output());
+    let plan: BuildPlan = t!(serde_json::from_slice(&output.stdout));
+    assert_eq!(plan.invocations[1].compile_mode, "run-custom-build");
+    assert!(plan.invocations[1].build_script_output.is_none());
+    assert!(!plan.invocations[2].args.iter().any(|arg| arg == "has_foo"));
+
+    p.cargo("build").run();
+
+    let output = t!(p
+        .cargo("build --build-plan -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["build-plan"])
+        .exec_with_output());
+    let plan: BuildPlan = t!(serde_json::from_slice(&output.stdout));
+    let script_output = plan.invocations[1].build_script_output.as_ref().unwrap();
+    assert_eq!(script_output.cfgs, ["has_foo"]);
+    assert_eq!(script_output.env["FOO_VERSION"], "1.2.3");
+    assert_eq!(script_output.metadata["include"], "/include");
+    let rustc = &plan.invocations[2];
+    assert!(rustc.args.windows(2).any(|w| w == ["--cfg", "has_foo"]));
+    assert_eq!(rustc.env["FOO_VERSION"], "1.2.3");
+    assert!(plan.inputs.contains(&p.root().join("Cargo.lock")));
+    assert!(plan.inputs.contains(&p.root().join("build.rs")));
+}
@@ -0,0 +1,560 @@
+//! Tests for `-Zbuild-sandbox`.
+
+use std::env;
+use std::ffi::OsString;
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project, str, t};
+
+/// Builds a `bwrap` that runs the command after `--` without sandboxing it,
+/// and returns a `PATH` that finds it first.
+fn fake_bwrap() -> OsString {
+    let p = project()
+        .at("fake-bwrap")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "fake-bwrap"
+                version = "0.0.1"
+                edition = "2015"
+
+                [[bin]]
+                name = "bwrap"
+                path = "src/main.rs"
+            "#,
+        )
+        .file(
+            "src/main.rs",
+            r#"
+                fn main() {
+                    let args: Vec<String> = std::env::args().collect();
+                    let sep = args.iter().position(|a| a == "--").unwrap();
+                    let status = std::process::Command::new(&args[sep + 1])
+                        .args(&args[sep + 2..])
+                        .status()
+                        .unwrap();
+                    std::process::exit(status.code().unwrap_or(1));
+                }
+            "#,
+        )
+        .build();
+    p.cargo("build").run();
+
+    let mut path = vec![p.target_debug_dir()];
+    path.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+    env::join_paths(path).unwrap()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                sandbox = true
+            "#,
+        )
+        .build();
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `build.sandbox` config, pass `-Zbuild-sandbox` to enable it
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+            build-sandbox = { network = true }
+        "#,
+    );
+
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `build-sandbox` is required
+
+  The package requires the Cargo feature called `build-sandbox`, but that feature is not stabilized in this version of Cargo ([..]).
+  Consider adding `cargo-features = ["build-sandbox"]` to the top of Cargo.toml (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#build-sandbox for more information about the status of this feature.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn wraps_build_script() {
+    let path = fake_bwrap();
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    let out = std::env::var("OUT_DIR").unwrap();
+                    std::fs::write(format!("{out}/generated.rs"), "").unwrap();
+                }
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [build]
+                sandbox = true
+            "#,
+        )
+        .build();
+
+    p.cargo("check -v -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `[..]rustc --crate-name build_script_build [..]`
+[RUNNING] `bwrap --die-with-parent --new-session --unshare-all --dev /dev --proc /proc --tmpfs /tmp --ro-bind-try /usr /usr [..] --ro-bind-try [ROOT]/foo [ROOT]/foo --ro-bind-try [ROOT]/foo/target/debug [ROOT]/foo/target/debug --ro-bind-try [..] --bind-try [ROOT]/foo/target/debug/build/foo-[HASH]/out [ROOT]/foo/target/debug/build/foo-[HASH]/out -- [ROOT]/foo/target/debug/build/foo-[HASH]/build-script-build`
+[RUNNING] `[..]rustc --crate-name foo [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn clears_environment() {
+    let path = fake_bwrap();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["build-sandbox"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.build-sandbox]
+                env = ["SANDBOX_ALLOWED"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    assert!(std::env::var_os("SANDBOX_SECRET").is_none());
+                    assert_eq!(std::env::var("SANDBOX_ALLOWED").unwrap(), "yes");
+                    assert!(std::env::var_os("PATH").is_some());
+                    assert!(std::env::var_os("OUT_DIR").is_some());
+                }
+            "#,
+        )
+        .build();
+
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .env("SANDBOX_SECRET", "hunter2")
+        .env("SANDBOX_ALLOWED", "yes")
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn declared_capabilities() {
+    let path = fake_bwrap();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["build-sandbox"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.build-sandbox]
+                network = true
+                read = ["shared"]
+                write = ["generated"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check -v -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `[..]rustc --crate-name build_script_build [..]`
+[RUNNING] `bwrap --die-with-parent --new-session --unshare-all --share-net --dev /dev [..] --ro-bind-try [ROOT]/foo/shared [ROOT]/foo/shared --bind-try [ROOT]/foo/target/debug/build/foo-[HASH]/out [ROOT]/foo/target/debug/build/foo-[HASH]/out --bind-try [ROOT]/foo/generated [ROOT]/foo/generated -- [ROOT]/foo/target/debug/build/foo-[HASH]/build-script-build`
+[RUNNING] `[..]rustc --crate-name foo [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn declared_paths_stay_in_package() {
+    let path = fake_bwrap();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["build-sandbox"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.build-sandbox]
+                read = ["../shared"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .symlink_dir("../secret", "secret")
+        .build();
+
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to set up the build script sandbox for `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  `build-sandbox.read` path `../shared` must be relative to the package root and stay inside of it
+
+"#]])
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["build-sandbox"]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+
+            [package.build-sandbox]
+            write = ["/tmp"]
+        "#,
+    );
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to set up the build script sandbox for `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  `build-sandbox.write` path `/tmp` must be relative to the package root and stay inside of it
+
+"#]])
+        .run();
+
+    std::fs::create_dir_all(p.root().join("../secret")).unwrap();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["build-sandbox"]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+
+            [package.build-sandbox]
+            read = ["secret/keys"]
+        "#,
+    );
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to set up the build script sandbox for `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  `build-sandbox.read` path `secret/keys` must be relative to the package root and stay inside of it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn dependency_cannot_grant_itself() {
+    let path = fake_bwrap();
+    Package::new("bar", "0.1.0")
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["build-sandbox"]
+
+                [package]
+                name = "bar"
+                version = "0.1.0"
+                edition = "2015"
+
+                [package.build-sandbox]
+                network = true
+                read = ["data"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let output = t!(String::from_utf8(
+        t!(p.cargo("check -v -Zbuild-sandbox")
+            .masquerade_as_nightly_cargo(&["build-sandbox"])
+            .env("PATH", &path)
+            .env("CARGO_BUILD_SANDBOX", "true")
+            .exec_with_output())
+        .stderr,
+    ));
+    let bwrap = output
+        .lines()
+        .find(|line| line.contains("bwrap") && line.contains("bar-"))
+        .unwrap();
+    assert!(!bwrap.contains("--share-net"), "{bwrap}");
+    assert!(!bwrap.contains("bar-0.1.0/data"), "{bwrap}");
+
+    p.cargo("clean").run();
+    p.cargo("check -v -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .env("CARGO_BUILD_SANDBOX_TRUST", "bar")
+        .with_stderr_data(str![[r#"
+...
+[RUNNING] `bwrap --die-with-parent --new-session --unshare-all --share-net --dev /dev [..] --ro-bind-try [..]/bar-0.1.0/data [..]/bar-0.1.0/data [..] -- [ROOT]/foo/target/debug/build/bar-[HASH]/build-script-build`
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn build_script_cache_keyed_on_sandbox() {
+    let path = fake_bwrap();
+    Package::new("bar", "0.1.0")
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    println!("cargo::rerun-if-changed=build.rs");
+                }
+            "#,
+        )
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .run();
+
+    // Results of running outside of the sandbox aren't reused inside of it.
+    p.cargo("clean").run();
+    p.cargo("check -v -Zbuild-script-cache -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-script-cache", "build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_stderr_data(str![[r#"
+...
+[RUNNING] `bwrap [..] -- [ROOT]/foo/target/debug/build/bar-[HASH]/build-script-build`
+...
+"#]])
+        .run();
+
+    // But they are between sandboxed runs.
+    p.cargo("clean").run();
+    p.cargo("check -v -Zbuild-script-cache -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-script-cache", "build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_stderr_data(str![[r#"
+...
+[RESTORED] build script output of bar v0.1.0
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn failure_mentions_ignored_declaration() {
+    let path = fake_bwrap();
+    Package::new("bar", "0.1.0")
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["build-sandbox"]
+
+                [package]
+                name = "bar"
+                version = "0.1.0"
+                edition = "2015"
+
+                [package.build-sandbox]
+                network = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", r#"fn main() { panic!("denied"); }"#)
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+...
+[ERROR] failed to run custom build command for `bar v0.1.0`
+...
+[NOTE] the build script was run in a sandbox because `build.sandbox` is set, its `[package.build-sandbox]` table was ignored because the package is not a workspace member, list it in `build.sandbox-trust` to honor it
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn failure_mentions_sandbox() {
+    let path = fake_bwrap();
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .file("build.rs", r#"fn main() { panic!("denied"); }"#)
+        .build();
+
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("PATH", &path)
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[ERROR] failed to run custom build command for `foo v0.0.1 ([ROOT]/foo)`
+...
+[NOTE] the build script was run in a sandbox because `build.sandbox` is set, declare anything else it needs to access in the `[package.build-sandbox]` table of its manifest
+
+Caused by:
+  process didn't exit successfully: `bwrap [..] -- [ROOT]/foo/target/debug/build/foo-[HASH]/build-script-build` ([EXIT_STATUS]: 101)
+...
+"#]])
+        .run();
+}
+
+#[cargo_test(requires_bwrap)]
+#[cfg(target_os = "linux")]
+fn denies_writes_outside_out_dir() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    let out = std::env::var("OUT_DIR").unwrap();
+                    std::fs::write(format!("{out}/generated.rs"), "").unwrap();
+                    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+                    std::fs::write(format!("{dir}/escaped"), "").unwrap();
+                }
+            "#,
+        )
+        .build();
+
+    p.cargo("check -Zbuild-sandbox")
+        .masquerade_as_nightly_cargo(&["build-sandbox"])
+        .env("CARGO_BUILD_SANDBOX", "true")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+...
+[ERROR] failed to run custom build command for `foo v0.0.1 ([ROOT]/foo)`
+...
+"#]])
+        .run();
+    assert!(!p.root().join("escaped").exists());
+    assert!(p.glob("target/debug/build/foo-*/out/generated.rs").count() == 1);
+}
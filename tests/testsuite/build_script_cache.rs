@@ -0,0 +1,205 @@
+//! Tests for `-Zbuild-script-cache`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+fn publish_bar() {
+    Package::new("bar", "1.0.0")
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    println!("cargo::rerun-if-env-changed=BAR_PROBE");
+                    let probe = std::env::var("BAR_PROBE").unwrap_or_default();
+                    let out = std::env::var("OUT_DIR").unwrap();
+                    std::fs::write(
+                        format!("{out}/probe.rs"),
+                        format!("pub const PROBE: &str = {probe:?};"),
+                    )
+                    .unwrap();
+                    println!("cargo::rustc-env=BAR_OUT={out}");
+                }
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+                include!(concat!(env!("OUT_DIR"), "/probe.rs"));
+                pub const OUT: &str = env!("BAR_OUT");
+            "#,
+        )
+        .publish();
+}
+
+fn workspace(name: &str) -> Project {
+    project()
+        .at(name)
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "{name}"
+                    version = "0.0.1"
+                    edition = "2015"
+
+                    [dependencies]
+                    bar = "1.0"
+                "#
+            ),
+        )
+        .file(
+            "src/main.rs",
+            r#"
+                extern crate bar;
+                fn main() {
+                    println!("probe={} out={}", bar::PROBE, bar::OUT);
+                }
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn reused_across_workspaces() {
+    publish_bar();
+
+    let foo = workspace("foo");
+    foo.cargo("run -v -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .env("BAR_PROBE", "1")
+        .with_stdout_data(str![[r#"
+probe=1 out=[ROOT]/foo/target/debug/build/bar-[HASH]/out
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[COMPILING] bar v1.0.0
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RUNNING] `[ROOT]/foo/target/debug/build/bar-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name bar [..]`
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `target/debug/foo[EXE]`
+
+"#]])
+        .run();
+
+    // Another workspace restores the results, with its own `OUT_DIR`.
+    let baz = workspace("baz");
+    baz.cargo("run -v -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .env("BAR_PROBE", "1")
+        .with_stdout_data(str![[r#"
+probe=1 out=[ROOT]/baz/target/debug/build/bar-[HASH]/out
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] bar v1.0.0
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RESTORED] build script output of bar v1.0.0
+[RUNNING] `rustc --crate-name bar [..]`
+[COMPILING] baz v0.0.1 ([ROOT]/baz)
+[RUNNING] `rustc --crate-name baz [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `target/debug/baz[EXE]`
+
+"#]])
+        .run();
+
+    // A different value for a declared input runs the script again.
+    baz.cargo("clean").run();
+    baz.cargo("run -v -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .env("BAR_PROBE", "2")
+        .with_stdout_data(str![[r#"
+probe=2 out=[ROOT]/baz/target/debug/build/bar-[HASH]/out
+
+"#]])
+        .with_stderr_data(str![[r#"
+[COMPILING] bar v1.0.0
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RUNNING] `[ROOT]/baz/target/debug/build/bar-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name bar [..]`
+[COMPILING] baz v0.0.1 ([ROOT]/baz)
+[RUNNING] `rustc --crate-name baz [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `target/debug/baz[EXE]`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn not_cached_without_declared_inputs() {
+    Package::new("bar", "1.0.0")
+        .file("build.rs", "fn main() {}")
+        .file("src/lib.rs", "")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .run();
+    p.cargo("clean").run();
+    p.cargo("check -v -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .with_stderr_data(str![[r#"
+[COMPILING] bar v1.0.0
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RUNNING] `[ROOT]/foo/target/debug/build/bar-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name bar [..]`
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn not_used_without_zflag() {
+    publish_bar();
+
+    let foo = workspace("foo");
+    foo.cargo("build -Zbuild-script-cache")
+        .masquerade_as_nightly_cargo(&["build-script-cache"])
+        .run();
+
+    let baz = workspace("baz");
+    baz.cargo("build -v")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] bar v1.0.0
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RUNNING] `[ROOT]/baz/target/debug/build/bar-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name bar [..]`
+[COMPILING] baz v0.0.1 ([ROOT]/baz)
+[RUNNING] `rustc --crate-name baz [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -129,10 +129,12 @@ mod rustc_incompatible;
 mod rustc_latest;
 mod rustc_older;
 mod sorted_table_with_dotted_item;
-mod target;
+// mod target;
 mod target_cfg;
 mod unknown_inherited_feature;
 mod vers;
+mod workspace_dependency;
+mod workspace_dependency_rewrite_members;
 mod workspace_name;
 mod workspace_path;
 mod workspace_path_dev;
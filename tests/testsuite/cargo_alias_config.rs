@@ -494,3 +494,137 @@ fn alias_no_subcommand() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn alias_placeholders() {
+    let echo = echo_subcommand();
+    let p = project()
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [alias]
+                e-release = "echo --profile {1:-release} --target {target} {{literal}}"
+                e-array = ["echo", "--message={1} and {2:-nothing}", "--jobs={jobs:-2}"]
+            "#,
+        )
+        .build();
+
+    let mut paths: Vec<_> = env::split_paths(&env::var_os("PATH").unwrap_or_default()).collect();
+    paths.push(echo.target_debug_dir());
+    let path = env::join_paths(paths).unwrap();
+
+    p.cargo("-Zalias-placeholders e-release --target thumbv7em-none-eabihf")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .env("PATH", &path)
+        .with_stdout_data(str![[r#"
+echo --profile release --target thumbv7em-none-eabihf {literal}
+
+"#]])
+        .run();
+
+    // Used arguments and flags are removed, and the rest are appended.
+    p.cargo("-Zalias-placeholders e-release dev extra --target=wasm32-wasip1 --verbose")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .env("PATH", &path)
+        .with_stdout_data(str![[r#"
+echo --profile dev --target wasm32-wasip1 {literal} extra --verbose
+
+"#]])
+        .run();
+
+    p.cargo("-Zalias-placeholders e-array one")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .env("PATH", &path)
+        .with_stdout_data(str![[r#"
+echo --message=one and nothing --jobs=2
+
+"#]])
+        .run();
+
+    p.cargo("-Zalias-placeholders e-array one two --jobs 8")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .env("PATH", &path)
+        .with_stdout_data(str![[r#"
+echo --message=one and two --jobs=8
+
+"#]])
+        .run();
+
+    p.cargo("-Zalias-placeholders e-release")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .env("PATH", &path)
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] alias `e-release` requires `--target <VALUE>` for `{target}`
+
+"#]])
+        .run();
+
+    p.cargo("-Zalias-placeholders e-array")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .env("PATH", &path)
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] alias `e-array` requires an argument for `{1}`
+
+"#]])
+        .run();
+
+    // Without the flag, braces are passed through.
+    p.cargo("e-release")
+        .env("PATH", &path)
+        .with_stdout_data(str![[r#"
+echo --profile {1:-release} --target {target} {{literal}}
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn alias_placeholders_invalid() {
+    let p = project()
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [unstable]
+                alias-placeholders = true
+
+                [alias]
+                unclosed = "build --profile {1"
+                zero = "build --profile {0}"
+                bad-name = "build --features {a.b}"
+            "#,
+        )
+        .build();
+
+    p.cargo("unclosed")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid placeholder in `alias.unclosed`: unclosed `{` in `{1`, use `{{` for a literal `{`
+
+"#]])
+        .run();
+
+    p.cargo("zero")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid placeholder in `alias.zero`: `{0}` in `{0}` is not an argument, they are numbered from 1
+
+"#]])
+        .run();
+
+    p.cargo("bad-name")
+        .masquerade_as_nightly_cargo(&["alias-placeholders"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid placeholder in `alias.bad-name`: `{a.b}` in `{a.b}` is neither an argument number nor a flag name
+
+"#]])
+        .run();
+}
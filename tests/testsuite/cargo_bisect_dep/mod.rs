@@ -0,0 +1,248 @@
+//! Tests for the `cargo bisect-dep` command.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+mod help;
+
+/// Publishes a version of a package with a constant `X` set to `x`.
+fn publish(name: &str, version: &str, x: u32) {
+    Package::new(name, version)
+        .file("src/lib.rs", &format!("pub const X: u32 = {x};"))
+        .publish();
+}
+
+/// A package failing to build once `bar::X + baz::X` reaches 3, with
+/// `good.lock` locking the old versions of its dependencies and `Cargo.lock`
+/// the new ones.
+fn foo() -> Project {
+    publish("bar", "1.0.0", 1);
+    publish("baz", "1.0.0", 0);
+    publish("qux", "1.0.0", 0);
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+                baz = "1.0"
+                qux = "1.0"
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            "const _: () = assert!(bar::X + baz::X + qux::X < 3);",
+        )
+        .build();
+    p.cargo("generate-lockfile").run();
+    p.change_file("good.lock", &p.read_lockfile());
+
+    publish("bar", "1.1.0", 2);
+    publish("baz", "1.1.0", 1);
+    publish("qux", "1.1.0", 0);
+    p.cargo("update").run();
+    p
+}
+
+#[cargo_test]
+fn finds_updates() {
+    let p = foo();
+    let bad = p.read_lockfile();
+
+    p.cargo("bisect-dep --good good.lock -Zunstable-options -- check")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_stdout_data(str![[r#"
+bar v1.0.0 -> v1.1.0
+baz v1.0.0 -> v1.1.0
+
+"#]])
+        .with_stderr_data(str![[r#"
+   Bisecting 3 package updates between the good and the bad lock files
+     Testing with 0 of 3 updates
+      Passed with 0 of 3 updates
+[UPDATING] `dummy-registry` index
+     Testing with 3 of 3 updates
+      Failed with 3 of 3 updates
+     Testing with 1 of 3 updates
+      Passed with 1 of 3 updates
+     Testing with 2 of 3 updates
+      Failed with 2 of 3 updates
+     Testing with 1 of 3 updates
+      Passed with 1 of 3 updates
+       Found 2 updates making the command fail
+
+"#]])
+        .run();
+    assert_eq!(p.read_lockfile(), bad);
+
+    // The results are reused.
+    p.cargo("bisect-dep --good good.lock -Zunstable-options -- check")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_stdout_data(str![[r#"
+bar v1.0.0 -> v1.1.0
+baz v1.0.0 -> v1.1.0
+
+"#]])
+        .with_stderr_data(str![[r#"
+[NOTE] reusing 5 results recorded in `[ROOT]/foo/target/bisect-dep/state.json`, pass `--reset` to start over
+   Bisecting 3 package updates between the good and the bad lock files
+      Passed with 0 of 3 updates (recorded)
+      Failed with 3 of 3 updates (recorded)
+      Passed with 1 of 3 updates (recorded)
+      Failed with 2 of 3 updates (recorded)
+      Passed with 1 of 3 updates (recorded)
+       Found 2 updates making the command fail
+
+"#]])
+        .run();
+
+    p.cargo("bisect-dep --good good.lock --reset -Zunstable-options -- check")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_stdout_data(str![[r#"
+bar v1.0.0 -> v1.1.0
+baz v1.0.0 -> v1.1.0
+
+"#]])
+        .with_stderr_data(str![[r#"
+   Bisecting 3 package updates between the good and the bad lock files
+     Testing with 0 of 3 updates
+      Passed with 0 of 3 updates
+[UPDATING] `dummy-registry` index
+     Testing with 3 of 3 updates
+      Failed with 3 of 3 updates
+     Testing with 1 of 3 updates
+      Passed with 1 of 3 updates
+     Testing with 2 of 3 updates
+      Failed with 2 of 3 updates
+     Testing with 1 of 3 updates
+      Passed with 1 of 3 updates
+       Found 2 updates making the command fail
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn single_culprit() {
+    let p = foo();
+    p.change_file(
+        "src/lib.rs",
+        "const _: () = assert!(bar::X < 2 && baz::X + qux::X < 3);",
+    );
+
+    p.cargo("bisect-dep --good good.lock -Zunstable-options -- check")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_stdout_data(str![[r#"
+bar v1.0.0 -> v1.1.0
+
+"#]])
+        .with_stderr_data(str![[r#"
+   Bisecting 3 package updates between the good and the bad lock files
+     Testing with 0 of 3 updates
+      Passed with 0 of 3 updates
+[UPDATING] `dummy-registry` index
+     Testing with 3 of 3 updates
+      Failed with 3 of 3 updates
+     Testing with 1 of 3 updates
+      Failed with 1 of 3 updates
+       Found 1 update making the command fail
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn restores_lockfile_of_interrupted_bisection() {
+    let p = foo();
+    let bad = p.read_lockfile();
+    // As left by a bisection killed while testing with none of the updates.
+    p.change_file(
+        "target/bisect-dep/original-lock.json",
+        &serde_json::to_string(&bad).unwrap(),
+    );
+    p.change_file("Cargo.lock", &p.read_file("good.lock"));
+
+    p.cargo("bisect-dep --good good.lock -Zunstable-options -- check")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_stdout_data(str![[r#"
+bar v1.0.0 -> v1.1.0
+baz v1.0.0 -> v1.1.0
+
+"#]])
+        .with_stderr_data(str![[r#"
+[WARNING] restored the `Cargo.lock` of an interrupted bisection from `[ROOT]/foo/target/bisect-dep/original-lock.json`
+   Bisecting 3 package updates between the good and the bad lock files
+...
+       Found 2 updates making the command fail
+
+"#]])
+        .run();
+    assert_eq!(p.read_lockfile(), bad);
+    assert!(!p
+        .root()
+        .join("target/bisect-dep/original-lock.json")
+        .exists());
+}
+
+#[cargo_test]
+fn bad_does_not_fail() {
+    let p = foo();
+    p.change_file("src/lib.rs", "");
+
+    p.cargo("bisect-dep --good good.lock -Zunstable-options -- check")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+   Bisecting 3 package updates between the good and the bad lock files
+     Testing with 0 of 3 updates
+      Passed with 0 of 3 updates
+[UPDATING] `dummy-registry` index
+     Testing with 3 of 3 updates
+      Passed with 3 of 3 updates
+[ERROR] the command succeeds with the bad lock file, there is nothing to bisect
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn no_updates() {
+    let p = foo();
+
+    p.cargo("bisect-dep --good Cargo.lock -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] there are no package updates between the good and the bad lock files
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("bisect-dep --good Cargo.lock")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo bisect-dep` command is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    p.cargo("bisect-dep --good Cargo.lock")
+        .masquerade_as_nightly_cargo(&["bisect-dep"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo bisect-dep` command is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
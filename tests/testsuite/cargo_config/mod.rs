@@ -1,6 +1,7 @@
 //! Tests for the `cargo config` command.
 
 use super::config::write_config_at;
+use cargo_test_support::compare::assert_e2e;
 use cargo_test_support::paths;
 use cargo_test_support::prelude::*;
 use cargo_test_support::str;
@@ -578,3 +579,177 @@ target.'cfg(target_os = "linux")'.runner = "runme"
         .with_stderr_data(str![[r#""#]])
         .run();
 }
+
+#[cargo_test]
+fn set_workspace() {
+    let p = cargo_test_support::project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["bar"]
+            "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            &cargo_test_support::basic_manifest("bar", "0.1.0"),
+        )
+        .file("bar/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            "# Keep this comment.\n[build]\njobs = 2 # and this one\n",
+        )
+        .build();
+
+    cargo_process("config set -Zunstable-options build.rustflags")
+        .arg(r#"["-Cdebuginfo=1"]"#)
+        .cwd(p.root().join("bar"))
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `build.rustflags = ["-Cdebuginfo=1"]` to `[ROOT]/foo/.cargo/config.toml`
+
+"#]])
+        .run();
+    cargo_process("config set -Zunstable-options net.offline true")
+        .cwd(p.root().join("bar"))
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `net.offline = true` to `[ROOT]/foo/.cargo/config.toml`
+
+"#]])
+        .run();
+    cargo_process("config set -Zunstable-options alias.b build")
+        .cwd(p.root().join("bar"))
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `alias.b = "build"` to `[ROOT]/foo/.cargo/config.toml`
+
+"#]])
+        .run();
+
+    assert_e2e().eq(
+        fs::read_to_string(p.root().join(".cargo/config.toml")).unwrap(),
+        str![[r#"
+# Keep this comment.
+[build]
+jobs = 2 # and this one
+rustflags = ["-Cdebuginfo=1"]
+
+[net]
+offline = true
+
+[alias]
+b = "build"
+
+"#]],
+    );
+    assert!(!p.root().join("bar/.cargo").exists());
+}
+
+#[cargo_test]
+fn set_global() {
+    cargo_process("config set -Zunstable-options --global build.jobs 4")
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `build.jobs = 4` to `[ROOT]/home/.cargo/config.toml`
+
+"#]])
+        .run();
+
+    cargo_process("config get -Zunstable-options --json build.jobs")
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stdout_data(str![[r#"
+{"build":{"jobs":4}}
+
+"#]])
+        .with_stderr_data(str![[r#""#]])
+        .run();
+}
+
+#[cargo_test]
+fn set_without_workspace() {
+    cargo_process("config set -Zunstable-options build.jobs 4")
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `cargo config` needs a workspace to edit, or `--global` to edit the config of the Cargo home instead
+
+Caused by:
+  could not find `Cargo.toml` in `[ROOT]` or any parent directory
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn set_overridden() {
+    let p = cargo_test_support::project().build();
+    write_config_at(p.root().join(".cargo/config.toml"), "build.jobs = 2");
+
+    cargo_process("config set -Zunstable-options --global build.jobs 4")
+        .cwd(p.root())
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `build.jobs = 4` to `[ROOT]/home/.cargo/config.toml`
+[WARNING] `build.jobs` is also set by [ROOT]/foo/.cargo/config.toml, which takes precedence
+
+"#]])
+        .run();
+
+    cargo_process("config set -Zunstable-options build.jobs 8")
+        .cwd(p.root())
+        .env("CARGO_BUILD_JOBS", "1")
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `build.jobs = 8` to `[ROOT]/foo/.cargo/config.toml`
+[WARNING] `build.jobs` is also set by environment variable `CARGO_BUILD_JOBS`, which takes precedence
+
+"#]])
+        .run();
+
+    cargo_process("config set -Zunstable-options build.jobs 16")
+        .cwd(p.root())
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[ADDING] `build.jobs = 16` to `[ROOT]/foo/.cargo/config.toml`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn unset() {
+    let p = cargo_test_support::project().build();
+    write_config_at(
+        p.root().join(".cargo/config.toml"),
+        "# Keep this comment.\n[build]\njobs = 2\n\n[net]\noffline = true\n",
+    );
+
+    cargo_process("config unset -Zunstable-options net.offline")
+        .cwd(p.root())
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_stderr_data(str![[r#"
+[REMOVING] `net.offline` from `[ROOT]/foo/.cargo/config.toml`
+
+"#]])
+        .run();
+    assert_e2e().eq(
+        fs::read_to_string(p.root().join(".cargo/config.toml")).unwrap(),
+        str![[r#"
+# Keep this comment.
+[build]
+jobs = 2
+
+"#]],
+    );
+
+    cargo_process("config unset -Zunstable-options net.offline")
+        .cwd(p.root())
+        .masquerade_as_nightly_cargo(&["cargo-config"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] config value `net.offline` is not set in `[ROOT]/foo/.cargo/config.toml`
+
+"#]])
+        .run();
+}
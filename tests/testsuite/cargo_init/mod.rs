@@ -43,3 +43,6 @@ mod simple_lib;
 mod unknown_flags;
 mod with_argument;
 mod workspace_add_member;
+mod workspace_convert_package;
+mod workspace_empty_dir;
+mod workspace_existing_workspace;
@@ -0,0 +1,32 @@
+use cargo_test_support::compare::assert_ui;
+use cargo_test_support::current_dir;
+use cargo_test_support::file;
+use cargo_test_support::prelude::*;
+use cargo_test_support::str;
+use cargo_test_support::Project;
+
+#[cargo_test]
+fn case() {
+    let project = Project::from_template(current_dir!().join("in"));
+    let project_root = &project.root();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg_line("init --workspace --vcs none -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["init-workspace"])
+        .current_dir(project_root)
+        .assert()
+        .success()
+        .stdout_eq(str![""])
+        .stderr_eq(file!["stderr.term.svg"]);
+
+    assert_ui().subset_matches(current_dir!().join("out"), project_root);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg_line("new crates/foo --vcs none")
+        .current_dir(project_root)
+        .assert()
+        .success();
+
+    assert_ui().subset_matches(current_dir!().join("out"), project_root);
+    assert_ui().subset_matches(current_dir!().join("out-member"), project_root);
+}
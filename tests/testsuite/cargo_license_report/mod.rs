@@ -0,0 +1,360 @@
+//! Tests for the `cargo license-report` command.
+
+mod help;
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+use cargo_test_support::str;
+
+const MIT_TEXT: &str = "MIT License\n\nPermission is hereby granted, free of charge, to any person";
+
+fn publish_packages() {
+    for (name, license) in [
+        ("dual", Some("MIT/Apache-2.0")),
+        ("dual-lower", Some("mit or apache-2.0")),
+        ("gpl", Some("GPL-3.0")),
+        ("gpl-or-mit", Some("(GPL-3.0+ OR mit)")),
+        ("nolicense", None),
+    ] {
+        let license = license
+            .map(|license| format!("license = \"{license}\""))
+            .unwrap_or_default();
+        Package::new(name, "1.0.0")
+            .file(
+                "Cargo.toml",
+                &format!(
+                    r#"
+                        [package]
+                        name = "{name}"
+                        version = "1.0.0"
+                        edition = "2015"
+                        {license}
+                    "#
+                ),
+            )
+            .file("src/lib.rs", "")
+            .publish();
+    }
+    Package::new("detected", "1.0.0")
+        .file("LICENSE", MIT_TEXT)
+        .file("src/lib.rs", "")
+        .publish();
+}
+
+fn project_with_deps() -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                license = "GPL-3.0-only"
+
+                [dependencies]
+                detected = "1.0"
+                dual = "1.0"
+                dual-lower = "1.0"
+                gpl-or-mit = "1.0"
+                nolicense = "1.0"
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.1.0"
+                edition = "2015"
+                license = "Zlib"
+            "#,
+        )
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn markdown() {
+    publish_packages();
+    let p = project_with_deps();
+
+    p.cargo("license-report -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["license-report"])
+        .with_stdout_data(str![[r#"
+# License report
+
+## GPL-3.0-or-later OR MIT
+
+| Package | Version | Source | License files |
+| --- | --- | --- | --- |
+| gpl-or-mit | 1.0.0 | registry+https://github.com/rust-lang/crates.io-index |  |
+
+## MIT
+
+| Package | Version | Source | License files |
+| --- | --- | --- | --- |
+| detected | 1.0.0 | registry+https://github.com/rust-lang/crates.io-index | LICENSE |
+
+## MIT OR Apache-2.0
+
+| Package | Version | Source | License files |
+| --- | --- | --- | --- |
+| dual | 1.0.0 | registry+https://github.com/rust-lang/crates.io-index |  |
+| dual-lower | 1.0.0 | registry+https://github.com/rust-lang/crates.io-index |  |
+
+## Zlib
+
+| Package | Version | Source | License files |
+| --- | --- | --- | --- |
+| bar | 0.1.0 | path |  |
+
+## Unknown
+
+| Package | Version | Source | License files |
+| --- | --- | --- | --- |
+| nolicense | 1.0.0 | registry+https://github.com/rust-lang/crates.io-index |  |
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn json() {
+    publish_packages();
+    let p = project_with_deps();
+
+    p.cargo("license-report --format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["license-report"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "licenses": [
+    {
+      "license": "GPL-3.0-or-later OR MIT",
+      "packages": [
+        {
+          "name": "gpl-or-mit",
+          "version": "1.0.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "checksum": "[..]",
+          "declared_license": "(GPL-3.0+ OR mit)",
+          "license_files": [],
+          "denied": false
+        }
+      ]
+    },
+    {
+      "license": "MIT",
+      "packages": [
+        {
+          "name": "detected",
+          "version": "1.0.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "checksum": "[..]",
+          "declared_license": null,
+          "license_files": [
+            "LICENSE"
+          ],
+          "denied": false
+        }
+      ]
+    },
+    {
+      "license": "MIT OR Apache-2.0",
+      "packages": [
+        {
+          "name": "dual",
+          "version": "1.0.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "checksum": "[..]",
+          "declared_license": "MIT/Apache-2.0",
+          "license_files": [],
+          "denied": false
+        },
+        {
+          "name": "dual-lower",
+          "version": "1.0.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "checksum": "[..]",
+          "declared_license": "mit or apache-2.0",
+          "license_files": [],
+          "denied": false
+        }
+      ]
+    },
+    {
+      "license": "Zlib",
+      "packages": [
+        {
+          "name": "bar",
+          "version": "0.1.0",
+          "source": null,
+          "checksum": null,
+          "declared_license": "Zlib",
+          "license_files": [],
+          "denied": false
+        }
+      ]
+    },
+    {
+      "license": null,
+      "packages": [
+        {
+          "name": "nolicense",
+          "version": "1.0.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "checksum": "[..]",
+          "declared_license": null,
+          "license_files": [],
+          "denied": false
+        }
+      ]
+    }
+  ]
+}
+"#]]
+            .json(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn csv() {
+    publish_packages();
+    let p = project_with_deps();
+
+    p.cargo("license-report --format csv -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["license-report"])
+        .with_stdout_data(str![[r#"
+name,version,license,source,checksum,license_files,denied
+gpl-or-mit,1.0.0,GPL-3.0-or-later OR MIT,registry+https://github.com/rust-lang/crates.io-index,[..],,false
+detected,1.0.0,MIT,registry+https://github.com/rust-lang/crates.io-index,[..],LICENSE,false
+dual,1.0.0,MIT OR Apache-2.0,registry+https://github.com/rust-lang/crates.io-index,[..],,false
+dual-lower,1.0.0,MIT OR Apache-2.0,registry+https://github.com/rust-lang/crates.io-index,[..],,false
+bar,0.1.0,Zlib,,,,false
+nolicense,1.0.0,,registry+https://github.com/rust-lang/crates.io-index,[..],,false
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn deny() {
+    publish_packages();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                gpl = "1.0"
+                gpl-or-mit = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [license-report]
+                deny = ["gpl-3.0"]
+            "#,
+        )
+        .build();
+
+    p.cargo("license-report --format csv -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["license-report"])
+        .with_status(101)
+        .with_stdout_data(str![[r#"
+name,version,license,source,checksum,license_files,denied
+gpl,1.0.0,GPL-3.0-only,registry+https://github.com/rust-lang/crates.io-index,[..],,true
+gpl-or-mit,1.0.0,GPL-3.0-or-later OR MIT,registry+https://github.com/rust-lang/crates.io-index,[..],,false
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] gpl-or-mit v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] gpl v1.0.0 (registry `dummy-registry`)
+[ERROR] 1 package is only allowed under licenses denied by `license-report.deny`:
+  gpl v1.0.0 (GPL-3.0-only)
+
+"#]].unordered())
+        .run();
+
+    p.cargo("license-report --format csv --deny GPL-3.0-or-later --deny mit -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["license-report"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] 2 packages are only allowed under licenses denied by `license-report.deny`:
+  gpl v1.0.0 (GPL-3.0-only)
+  gpl-or-mit v1.0.0 (GPL-3.0-or-later OR MIT)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn detected_license_files() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                license = "MIT"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            &cargo_test_support::basic_lib_manifest("bar"),
+        )
+        .file("bar/src/lib.rs", "")
+        .file(
+            "bar/LICENSE-APACHE",
+            "Apache License\nVersion 2.0, January 2004",
+        )
+        .file("bar/LICENSE|MIT", MIT_TEXT)
+        .build();
+
+    // Snapshots normalize `\` to `/`, so check the escaped `|` by hand.
+    let output = p
+        .cargo("license-report -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["license-report"])
+        .exec_with_output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("## Apache-2.0 OR MIT\n"), "{stdout}");
+    assert!(
+        stdout.contains("| bar | 0.5.0 | path | LICENSE-APACHE, LICENSE\\|MIT |\n"),
+        "{stdout}"
+    );
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("license-report")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo license-report` command is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
@@ -0,0 +1,235 @@
+//! Tests for the `cargo outdated` command.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+mod help;
+
+/// A workspace with the members `foo`, with a `rust-version` of 1.70, and
+/// `bar`, locked to old versions of their dependencies.
+fn workspace() -> Project {
+    Package::new("dep", "1.0.0").publish();
+    Package::new("other", "0.1.0").publish();
+    Package::new("renamed", "2.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                rust-version = "1.70"
+
+                [dependencies]
+                dep = "1.0"
+                new-name = { package = "renamed", version = "2.0" }
+
+                [target.'cfg(unix)'.dev-dependencies]
+                other = "0.1"
+            "#,
+        )
+        .file("foo/src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "1.0"
+                foo = { path = "../foo" }
+            "#,
+        )
+        .file("bar/src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+
+    Package::new("dep", "1.1.0").publish();
+    Package::new("dep", "1.2.0").rust_version("1.80").publish();
+    Package::new("dep", "2.0.0").publish();
+    Package::new("dep", "3.0.0-alpha.1").publish();
+    Package::new("other", "0.2.0").publish();
+    p
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project().build();
+
+    p.cargo("outdated")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo outdated` command is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    p.cargo("outdated")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo outdated` command is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn per_member_tables() {
+    let p = workspace();
+
+    p.cargo("outdated -Zunstable-options --workspace")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+bar v0.1.0:
+name  kind    requirement  locked  compatible  latest
+dep   normal  ^1.0         1.0.0   1.2.0       2.0.0
+
+foo v0.1.0:
+name   kind             requirement  locked  compatible  latest
+dep    normal           ^1.0         1.0.0   1.1.0       2.0.0
+other  dev (cfg(unix))  ^0.1         0.1.0   -           0.2.0
+
+not suggested for needing a newer Rust than 1.70: dep v1.2.0 (Rust 1.80)
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+
+"#]])
+        .run();
+
+    // Only the member in the current directory by default.
+    p.cargo("outdated -Zunstable-options --ignore-rust-version")
+        .cwd("foo")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0:
+name   kind             requirement  locked  compatible  latest
+dep    normal           ^1.0         1.0.0   1.2.0       2.0.0
+other  dev (cfg(unix))  ^0.1         0.1.0   -           0.2.0
+
+"#]])
+        .run();
+
+    // The lock file is left alone.
+    p.cargo("tree -p foo -e normal")
+        .with_stdout_data(str![[r#"
+foo v0.1.0 ([ROOT]/foo/foo)
+├── dep v1.0.0
+└── renamed v2.0.0
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn up_to_date() {
+    Package::new("dep", "1.0.0").publish();
+    Package::new("dep", "1.0.1-beta.1").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("outdated -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[NOTE] all dependencies are up to date
+
+"#]])
+        .run();
+    assert!(!p.root().join("Cargo.lock").exists());
+}
+
+#[cargo_test]
+fn json() {
+    let p = workspace();
+    Package::new("renamed", "2.1.0").publish();
+
+    p.cargo("outdated -Zunstable-options --format json -p foo")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "members": [
+    {
+      "dependencies": [
+        {
+          "compatible": "1.1.0",
+          "kind": "normal",
+          "latest": "2.0.0",
+          "locked": "1.0.0",
+          "name": "dep",
+          "needs_newer_rust": [
+            {
+              "rust_version": "1.80",
+              "version": "1.2.0"
+            }
+          ],
+          "requirement": "^1.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "target": null
+        },
+        {
+          "compatible": "2.1.0",
+          "kind": "normal",
+          "latest": "2.1.0",
+          "locked": "2.0.0",
+          "name": "new-name",
+          "needs_newer_rust": [],
+          "package": "renamed",
+          "requirement": "^2.0",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "target": null
+        },
+        {
+          "compatible": null,
+          "kind": "dev",
+          "latest": "0.2.0",
+          "locked": "0.1.0",
+          "name": "other",
+          "needs_newer_rust": [],
+          "requirement": "^0.1",
+          "source": "registry+https://github.com/rust-lang/crates.io-index",
+          "target": "cfg(unix)"
+        }
+      ],
+      "id": "path+[ROOTURL]/foo/foo#0.1.0",
+      "name": "foo",
+      "rust_version": "1.70",
+      "version": "0.1.0"
+    }
+  ]
+}
+"#]]
+            .json(),
+        )
+        .run();
+}
@@ -25,9 +25,11 @@ mod optional_feature;
 mod package;
 mod remove_basic;
 mod skip_gc_glob_profile;
-mod target;
+// mod target;
 mod target_build;
 mod target_dev;
+mod unused;
+mod unused_dry_run;
 mod update_lock_file;
 mod workspace;
 mod workspace_non_virtual;
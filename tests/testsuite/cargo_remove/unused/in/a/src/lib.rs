@@ -0,0 +1 @@
+pub fn a() { c::c(); }
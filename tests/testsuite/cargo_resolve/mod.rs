@@ -0,0 +1,208 @@
+//! Tests for the `cargo resolve` command.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project, str, Project};
+
+mod help;
+
+fn foo_with_bar() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project().build();
+
+    p.cargo("resolve")
+        .masquerade_as_nightly_cargo(&["cargo-resolve"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo resolve` command is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn writes_lockfile_without_target_dir() {
+    Package::new("bar", "0.1.0").publish();
+    let p = foo_with_bar();
+
+    p.cargo("resolve -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-resolve"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    assert!(p.root().join("Cargo.lock").is_file());
+    assert!(!p.root().join("target").exists());
+}
+
+#[cargo_test]
+fn dry_run_prints_lockfile() {
+    Package::new("bar", "0.1.0").publish();
+    let p = foo_with_bar();
+
+    p.cargo("resolve -Zunstable-options --dry-run")
+        .masquerade_as_nightly_cargo(&["cargo-resolve"])
+        .with_stdout_data(str![[r#"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 3
+
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "[..]"
+
+[[package]]
+name = "foo"
+version = "0.0.1"
+dependencies = [
+ "bar",
+]
+
+"#]])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[WARNING] not updating lockfile due to dry run
+
+"#]])
+        .run();
+
+    assert!(!p.root().join("Cargo.lock").exists());
+}
+
+#[cargo_test]
+fn json_delta() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    let p = foo_with_bar();
+
+    p.cargo("resolve -Zunstable-options --format json")
+        .masquerade_as_nightly_cargo(&["cargo-resolve"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "added": [
+    {
+      "name": "bar",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "version": "0.1.0"
+    },
+    {
+      "name": "foo",
+      "source": null,
+      "version": "0.0.1"
+    }
+  ],
+  "lockfile_existed": false,
+  "removed": [],
+  "updated": []
+}
+"#]]
+            .json(),
+        )
+        .run();
+
+    // Swap `bar` for `baz`, and have a newer `bar` available to the resolver.
+    Package::new("bar", "0.1.1").publish();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.2"
+            edition = "2015"
+
+            [dependencies]
+            baz = "0.1.0"
+        "#,
+    );
+
+    p.cargo("resolve -Zunstable-options --format json --dry-run")
+        .masquerade_as_nightly_cargo(&["cargo-resolve"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "added": [
+    {
+      "name": "baz",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "version": "0.1.0"
+    }
+  ],
+  "lockfile_existed": true,
+  "removed": [
+    {
+      "name": "bar",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "version": "0.1.0"
+    }
+  ],
+  "updated": [
+    {
+      "from": "0.0.1",
+      "name": "foo",
+      "source": null,
+      "to": "0.0.2"
+    }
+  ]
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] not updating lockfile due to dry run
+
+"#]])
+        .run();
+
+    // The lock file from the first run is untouched.
+    assert!(p.read_lockfile().contains("name = \"bar\""));
+}
+
+#[cargo_test]
+fn empty_workspace() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = []
+            "#,
+        )
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("resolve -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-resolve"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] you can't generate a lockfile for an empty workspace.
+
+"#]])
+        .run();
+}
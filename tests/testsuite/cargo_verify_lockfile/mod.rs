@@ -0,0 +1,172 @@
+//! Tests for the `cargo verify-lockfile` command.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{paths, project, str, Project};
+
+mod help;
+
+fn foo_with_bar() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project().build();
+
+    p.cargo("verify-lockfile")
+        .masquerade_as_nightly_cargo(&["cargo-verify-lockfile"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo verify-lockfile` command is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn no_lockfile() {
+    let p = foo_with_bar();
+
+    p.cargo("verify-lockfile -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-verify-lockfile"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] no `Cargo.lock` to verify
+
+[HELP] run `cargo generate-lockfile` to create one
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn verified() {
+    Package::new("bar", "0.1.0").publish();
+    let p = foo_with_bar();
+    p.cargo("fetch").run();
+
+    p.cargo("verify-lockfile -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-verify-lockfile"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[VERIFIED] 2 packages in `Cargo.lock`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn inconsistent_with_manifest() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    let p = foo_with_bar();
+    p.cargo("generate-lockfile").run();
+    let lockfile = p.read_lockfile();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.2"
+            edition = "2015"
+
+            [dependencies]
+            baz = "0.1.0"
+        "#,
+    );
+
+    p.cargo("verify-lockfile -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-verify-lockfile"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] `Cargo.lock` failed verification:
+  extra: bar v0.1.0
+  missing: baz v0.1.0
+  changed: foo v0.0.1 ([ROOT]/foo) -> v0.0.2
+
+"#]])
+        .run();
+
+    // Verification never rewrites the lock file.
+    assert_eq!(p.read_lockfile(), lockfile);
+}
+
+#[cargo_test]
+fn checksum_mismatch() {
+    Package::new("bar", "0.1.0").publish();
+    let p = foo_with_bar();
+    p.cargo("fetch").run();
+
+    let cache = paths::home().join(".cargo/registry/cache");
+    let krate = glob::glob(&format!("{}/*/bar-0.1.0.crate", cache.display()))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    std::fs::write(&krate, b"not a crate").unwrap();
+
+    p.cargo("verify-lockfile -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-verify-lockfile"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] `Cargo.lock` failed verification:
+  checksum mismatch: bar v0.1.0 (`Cargo.lock` has `[..]`, the cached `.crate` file has `[..]`)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn json_report() {
+    Package::new("bar", "0.1.0").publish();
+    let p = foo_with_bar();
+    p.cargo("generate-lockfile").run();
+    Package::new("bar", "0.1.0").yanked(true).publish();
+
+    p.cargo("verify-lockfile -Zunstable-options --format json")
+        .masquerade_as_nightly_cargo(&["cargo-verify-lockfile"])
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+{
+  "changed": [],
+  "checksum_mismatches": [],
+  "extra": [],
+  "missing": [],
+  "ok": false,
+  "up_to_date": true,
+  "yanked": [
+    {
+      "name": "bar",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "version": "0.1.0"
+    }
+  ]
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+
+"#]])
+        .run();
+}
@@ -0,0 +1,281 @@
+//! Tests for the `cargo workspace` command.
+
+use cargo_test_support::compare::assert_e2e;
+use cargo_test_support::{basic_manifest, project, str, Project};
+
+mod help;
+
+fn ws_with_bar() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = [
+                    "bar",  # the original member
+                ]
+                default-members = ["bar"]
+                resolver = "2"
+
+                [workspace.dependencies]
+                baz = { path = "baz" }
+            "#,
+        )
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = ws_with_bar();
+
+    p.cargo("workspace add baz")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `cargo workspace` command is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn add_existing_package() {
+    let p = ws_with_bar();
+
+    p.cargo("workspace add baz --default-member -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_stderr_data(str![[r#"
+[ADDING] `baz` as member of workspace at `[ROOT]/foo`
+[ADDING] `baz` as default member of workspace at `[ROOT]/foo`
+
+"#]])
+        .run();
+
+    assert_e2e().eq(
+        p.read_file("Cargo.toml"),
+        str![[r#"
+
+                [workspace]
+                members = [
+                    "bar",
+                    "baz",  # the original member
+                ]
+                default-members = ["bar", "baz"]
+                resolver = "2"
+
+                [workspace.dependencies]
+                baz = { path = "baz" }
+            
+"#]],
+    );
+
+    p.cargo("workspace add baz -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_stderr_data("")
+        .run();
+}
+
+#[cargo_test]
+fn add_new_package() {
+    let p = ws_with_bar();
+
+    p.cargo("workspace add crates/qux --lib --vcs none -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_stderr_data(str![[r#"
+[CREATING] library `qux` package
+[ADDING] `qux` as member of workspace at `[ROOT]/foo`
+[NOTE] see more `Cargo.toml` keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+"#]])
+        .run();
+
+    assert!(p.root().join("crates/qux/src/lib.rs").is_file());
+    p.cargo("check -p qux").run();
+}
+
+#[cargo_test]
+fn add_inherit_dependencies() {
+    let p = ws_with_bar();
+    p.change_file(
+        "baz/Cargo.toml",
+        r#"
+            [package]
+            name = "baz"
+            version = "0.1.0"
+            edition = "2015"
+
+            [dependencies]
+            bar = { path = "../bar" }
+
+            [dev-dependencies]
+            baz = { path = ".", features = [] }
+        "#,
+    );
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [workspace]
+            members = ["bar"]
+
+            [workspace.dependencies]
+            bar = { path = "bar" }
+        "#,
+    );
+
+    p.cargo("workspace add baz --inherit-dependencies -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_stderr_data(str![[r#"
+[ADDING] `baz` as member of workspace at `[ROOT]/foo`
+[UPDATING] bar in baz to `workspace = true`
+
+"#]])
+        .run();
+
+    assert_e2e().eq(
+        p.read_file("baz/Cargo.toml"),
+        str![[r#"
+
+            [package]
+            name = "baz"
+            version = "0.1.0"
+            edition = "2015"
+
+            [dependencies]
+bar.workspace = true
+
+            [dev-dependencies]
+            baz = { path = ".", features = [] }
+        
+"#]],
+    );
+}
+
+#[cargo_test]
+fn add_default_member_without_default_members() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["bar"]
+            "#,
+        )
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    p.cargo("workspace add baz --default-member -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] cannot add `baz` to `workspace.default-members`, there is no such key in `[ROOT]/foo/Cargo.toml`
+
+[HELP] without it, every member is a default member
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn add_invalid_package() {
+    let p = ws_with_bar();
+    p.change_file(
+        "baz/Cargo.toml",
+        "[package]\nname = \"baz\"\nversion = \"0.1.0\"\nedition = 1",
+    );
+    let original = p.read_file("Cargo.toml");
+
+    p.cargo("workspace add baz -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid type: integer `1`, expected a string or workspace
+ --> baz/Cargo.toml:4:11
+  |
+4 | edition = 1
+  |           ^
+  |
+[ERROR] failed to add `baz` to the workspace
+
+"#]])
+        .run();
+
+    assert_eq!(p.read_file("Cargo.toml"), original);
+}
+
+#[cargo_test]
+fn remove_member() {
+    let p = ws_with_bar();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [workspace]
+            members = ["./bar", "baz/"]
+            default-members = ["baz"]
+        "#,
+    );
+
+    p.cargo("workspace remove baz -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_stderr_data(str![[r#"
+[REMOVING] `baz` from members of workspace at `[ROOT]/foo`
+[REMOVING] `baz` from default members of workspace at `[ROOT]/foo`
+
+"#]])
+        .run();
+
+    assert_e2e().eq(
+        p.read_file("Cargo.toml"),
+        str![[r#"
+
+            [workspace]
+            members = ["./bar"]
+            default-members = []
+        
+"#]],
+    );
+    assert!(p.root().join("baz/Cargo.toml").is_file());
+}
+
+#[cargo_test]
+fn remove_not_a_member() {
+    let p = ws_with_bar();
+
+    p.cargo("workspace remove baz -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `baz` is not a member of workspace at `[ROOT]/foo`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn remove_glob_member() {
+    let p = ws_with_bar();
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [workspace]
+            members = ["ba*"]
+        "#,
+    );
+
+    p.cargo("workspace remove baz -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["cargo-workspace"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `baz` is included by the pattern `ba*` in `workspace.members`
+
+[HELP] add it to `workspace.exclude` to remove it from the workspace
+
+"#]])
+        .run();
+}
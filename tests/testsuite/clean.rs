@@ -917,3 +917,187 @@ fn quiet_does_not_show_summary() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn older_than_requires_gc() {
+    let p = project().file("src/lib.rs", "").build();
+    p.cargo("clean --older-than=1day")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--older-than` flag is unstable, pass `-Z gc` to enable it
+See https://github.com/rust-lang/cargo/issues/12633 for more information about the `--older-than` flag.
+
+"#]])
+        .run();
+    p.cargo("clean --max-size=1GB")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--max-size` flag is unstable, pass `-Z gc` to enable it
+See https://github.com/rust-lang/cargo/issues/12633 for more information about the `--max-size` flag.
+
+"#]])
+        .run();
+}
+
+/// Makes the fingerprints of `pkg` look like they were last used `days` ago.
+fn age_fingerprints(p: &cargo_test_support::Project, pkg: &str, days: u64) {
+    let t = filetime::FileTime::from_system_time(
+        std::time::SystemTime::now() - std::time::Duration::from_secs(days * 24 * 60 * 60),
+    );
+    for dir in p.glob(&format!("target/debug/.fingerprint/{pkg}-*")) {
+        for entry in walkdir::WalkDir::new(dir.unwrap()) {
+            filetime::set_file_mtime(entry.unwrap().path(), t).unwrap();
+        }
+    }
+}
+
+#[cargo_test]
+fn older_than() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .run();
+    age_fingerprints(&p, "bar", 10);
+    let mut expected = String::from(
+        "\
+[REMOVING] [ROOT]/foo/target/debug/.fingerprint/bar-[HASH]
+[REMOVING] [ROOT]/foo/target/debug/deps/libbar-[HASH].rlib
+[REMOVING] [ROOT]/foo/target/debug/deps/bar-[HASH].d
+[REMOVING] [ROOT]/foo/target/debug/deps/libbar-[HASH].rmeta
+",
+    );
+    if cfg!(target_os = "macos") {
+        for _ in p.glob("target/debug/deps/bar-*.o") {
+            expected.push_str("[REMOVING] [ROOT]/foo/target/debug/deps/bar-[HASH][..].o\n");
+        }
+    }
+    expected.push_str("[REMOVED] [FILE_NUM] files, [FILE_SIZE]B total\n");
+    p.cargo("clean --older-than=5days -Zgc --verbose")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(&expected.unordered())
+        .run();
+    assert!(p.glob("target/debug/deps/libbar-*.rlib").next().is_none());
+    assert!(p.glob("target/debug/deps/foo-*").next().is_some());
+
+    // The fingerprint went along with the artifacts, so `bar` is rebuilt.
+    p.cargo("build -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(str![[r#"
+[COMPILING] bar v0.1.0
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn older_than_keeps_fresh_units() {
+    // A unit found fresh with -Zgc is marked as used.
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .run();
+    age_fingerprints(&p, "bar", 10);
+    p.cargo("build -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    p.cargo("clean --older-than=5days -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(str![[r#"
+[REMOVED] [FILE_NUM] files
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn max_size() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .run();
+    p.cargo("clean --max-size=1GB -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(str![[r#"
+[REMOVED] [FILE_NUM] files
+
+"#]])
+        .run();
+    p.cargo("clean --max-size=0 --dry-run -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(str![[r#"
+[SUMMARY] [FILE_NUM] files, [FILE_SIZE]B total
+[WARNING] no files deleted due to --dry-run
+
+"#]])
+        .run();
+    p.cargo("clean --max-size=0 -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .run();
+    assert!(p.glob("target/debug/.fingerprint/*").next().is_none());
+    assert!(p.glob("target/debug/deps/*").next().is_none());
+    p.cargo("build -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr_data(str![[r#"
+[COMPILING] bar v0.1.0
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -1,6 +1,6 @@
 //! Tests for credential-process.
 
-use cargo_test_support::registry::{Package, TestRegistry};
+use cargo_test_support::registry::{Package, Response, TestRegistry};
 use cargo_test_support::{basic_manifest, cargo_process, paths, project, registry, str, Project};
 
 fn toml_bin(proj: &Project, name: &str) -> String {
@@ -229,6 +229,11 @@ Caused by:
 /// Builds a credential provider that echos the request from cargo to stderr,
 /// and prints the `response` to stdout.
 fn build_provider(name: &str, response: &str) -> String {
+    build_provider_with_hello(name, r#"{"v":[1]}"#, response)
+}
+
+/// Like `build_provider`, with `hello` as the hello message.
+fn build_provider_with_hello(name: &str, hello: &str, response: &str) -> String {
     // The credential process to use.
     let cred_proj = project()
         .at(name)
@@ -237,7 +242,7 @@ fn build_provider(name: &str, response: &str) -> String {
             "src/main.rs",
             &r####"
                 fn main() {
-                    println!(r#"{{"v":[1]}}"#);
+                    println!("{}", r###"[HELLO]"###);
                     assert_eq!(std::env::args().skip(1).next().unwrap(), "--cargo-plugin");
                     let mut buffer = String::new();
                     std::io::stdin().read_line(&mut buffer).unwrap();
@@ -246,6 +251,7 @@ fn build_provider(name: &str, response: &str) -> String {
                     std::io::stdout().write_all(r###"[RESPONSE]"###.as_bytes()).unwrap();
                     println!();
                 } "####
+                .replace("[HELLO]", hello)
                 .replace("[RESPONSE]", response),
         )
         .build();
@@ -560,6 +566,102 @@ You may press ctrl-c [..]
         .run();
 }
 
+#[cargo_test]
+fn token_caching_v2() {
+    let server = registry::RegistryBuilder::new()
+        .no_configure_token()
+        .no_configure_registry()
+        .token(cargo_test_support::registry::Token::Plaintext(
+            "sekrit".to_string(),
+        ))
+        .alternative()
+        .http_api()
+        .http_index()
+        .build();
+
+    // The scope takes precedence over `operation_independent`, allowing the
+    // token to be re-used for publishing.
+    let provider = build_provider_with_hello(
+        "v2_provider",
+        r#"{"v":[1,2]}"#,
+        r#"{"Ok":{"kind":"get","token":"sekrit","cache":"expires-in","expires_in":300,"operation_independent":false,"scope":"publish","message":"token expires in 5 minutes"}}"#,
+    );
+
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.alternative]
+                    index = "{}"
+                    credential-provider = ["{provider}"]
+                "#,
+                server.index_url(),
+            ),
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                description = "foo"
+                license = "MIT"
+                homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("publish --registry alternative --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+{"v":2,"registry":{"index-url":"[..]","name":"alternative"},"kind":"get","operation":"read"}
+[NOTE] token expires in 5 minutes
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting [..]
+You may press ctrl-c [..]
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+
+    // Without `-Zunstable-options` protocol version 1 is used, which has no
+    // scopes, messages nor `expires-in`.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.2.0"
+            edition = "2015"
+            description = "foo"
+            license = "MIT"
+            homepage = "https://example.com/"
+        "#,
+    );
+    p.cargo("publish --registry alternative --no-verify")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+{"v":1,"registry":{"index-url":"[..]","name":"alternative"},"kind":"get","operation":"read"}
+[PACKAGING] foo v0.2.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+{"v":1,"registry":{"index-url":"[..]","name":"alternative"},"kind":"get","operation":"publish","name":"foo","vers":"0.2.0","cksum":"[..]"}
+[UPLOADING] foo v0.2.0 ([ROOT]/foo)
+[UPLOADED] foo v0.2.0 to registry `alternative`
+[NOTE] waiting [..]
+You may press ctrl-c [..]
+[PUBLISHED] foo v0.2.0 at registry `alternative`
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn basic_provider() {
     let cred_proj = project()
@@ -710,3 +812,113 @@ fn login_token_from_stdin() {
 "#]])
         .run();
 }
+
+/// Setup for a test of the `cargo:oidc` provider, with GitHub Actions issuing
+/// the OIDC token `oidc-jwt` that the registry exchanges for `sekrit`.
+fn oidc_test() -> (Project, TestRegistry) {
+    let server = registry::RegistryBuilder::new()
+        .no_configure_token()
+        .token(cargo_test_support::registry::Token::Plaintext(
+            "sekrit".to_string(),
+        ))
+        .alternative()
+        .http_api()
+        .http_index()
+        .add_responder("/oidc", |req, _| {
+            assert_eq!(req.authorization.as_deref(), Some("Bearer request-token"));
+            assert_eq!(req.url.query(), Some("api-version=2.0&audience=127.0.0.1"));
+            Response {
+                code: 200,
+                headers: vec![],
+                body: br#"{"value":"oidc-jwt"}"#.to_vec(),
+            }
+        })
+        .add_responder("/api/v1/trusted_publishing/tokens", |req, _| {
+            assert_eq!(req.body.as_deref(), Some(&br#"{"jwt":"oidc-jwt"}"#[..]));
+            Response {
+                code: 200,
+                headers: vec![],
+                body: br#"{"token":"sekrit","expires_at":"2100-01-01T00:00:00Z"}"#.to_vec(),
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [registries.alternative]
+                credential-provider = "cargo:oidc"
+            "#,
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                description = "foo"
+                license = "MIT"
+                homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    (p, server)
+}
+
+#[cargo_test]
+fn oidc_github_actions() {
+    let (p, server) = oidc_test();
+
+    p.cargo("publish --registry alternative --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .env(
+            "ACTIONS_ID_TOKEN_REQUEST_URL",
+            format!("{}oidc?api-version=2.0", server.api_url()),
+        )
+        .env("ACTIONS_ID_TOKEN_REQUEST_TOKEN", "request-token")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[PACKAGING] foo v0.1.0 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.1.0 ([ROOT]/foo)
+[UPLOADED] foo v0.1.0 to registry `alternative`
+[NOTE] waiting for `foo v0.1.0` to be available at registry `alternative`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.1.0 at registry `alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn oidc_not_in_ci() {
+    let (p, _server) = oidc_test();
+
+    p.cargo("publish --registry alternative --no-verify -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] no token found for `alternative`, please run `cargo login --registry alternative`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn oidc_requires_unstable() {
+    let (p, _server) = oidc_test();
+
+    p.cargo("publish --registry alternative --no-verify")
+        .env("CARGO_OIDC_TOKEN", "oidc-jwt")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] cargo:oidc requires -Zunstable-options
+
+"#]])
+        .run();
+}
@@ -253,3 +253,45 @@ failed to select a version for `indirect` which could resolve this conflict
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn direct_minimal_versions_flag() {
+    Package::new("indirect", "2.0.0").publish();
+    Package::new("indirect", "2.1.0").publish();
+    Package::new("direct", "1.0.0")
+        .dep("indirect", "2.0")
+        .publish();
+    Package::new("direct", "1.1.0")
+        .dep("indirect", "2.0")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                authors = []
+                version = "0.0.1"
+
+                [dependencies]
+                direct = "1.0"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("update --direct-minimal-versions")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages
+[ADDING] direct v1.0.0 (latest: v1.1.0)
+
+"#]])
+        .run();
+
+    let lock = p.read_lockfile();
+    assert!(!lock.contains("1.1.0"));
+    assert!(!lock.contains("2.0.0"));
+    assert!(lock.contains("2.1.0"));
+}
@@ -1843,6 +1843,7 @@ fn doc_json_artifacts() {
         "doctest": true,
         "test": true
     },
+    "platform": null,
     "profile": "{...}",
     "features": [],
     "filenames": ["[ROOT]/foo/target/debug/deps/libfoo-[..].rmeta"],
@@ -1865,6 +1866,7 @@ fn doc_json_artifacts() {
         "doctest": true,
         "test": true
     },
+    "platform": null,
     "profile": "{...}",
     "features": [],
     "filenames": ["[ROOT]/foo/target/doc/foo/index.html"],
@@ -1887,6 +1889,7 @@ fn doc_json_artifacts() {
         "doctest": false,
         "test": true
     },
+    "platform": null,
     "profile": "{...}",
     "features": [],
     "filenames": ["[ROOT]/foo/target/doc/somebin/index.html"],
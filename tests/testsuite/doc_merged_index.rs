@@ -0,0 +1,125 @@
+//! Tests for `-Zdoc-merged-index`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{basic_manifest, project, str, tools, Project};
+
+fn workspace() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                description = """The foo
+                    crate"""
+            "#,
+        )
+        .file("foo/src/lib.rs", "/// A function.\npub fn foo() {}")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test(nightly, reason = "rustdoc --index-page is unstable")]
+fn index_lists_members() {
+    let p = workspace();
+
+    p.cargo("doc --workspace -Zdoc-merged-index -v")
+        .masquerade_as_nightly_cargo(&["doc-merged-index"])
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[DOCUMENTING] bar v0.1.0 ([ROOT]/foo/bar)
+[RUNNING] `rustdoc [..]--crate-name bar [..] -Zunstable-options --enable-index-page --index-page [ROOT]/foo/target/tmp/doc-index/index.md --crate-version 0.1.0`
+[DOCUMENTING] foo v0.1.0 ([ROOT]/foo/foo)
+[RUNNING] `rustdoc [..]--crate-name foo [..] -Zunstable-options --enable-index-page --index-page [ROOT]/foo/target/tmp/doc-index/index.md --crate-version 0.1.0`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[GENERATED] [ROOT]/foo/target/doc/bar/index.html
+[GENERATED] [ROOT]/foo/target/doc/foo/index.html
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    assert_eq!(
+        p.read_file("target/tmp/doc-index/index.md"),
+        "# Crates\n\n\
+         * [bar](bar/index.html)\n\
+         * [foo](foo/index.html): The foo crate\n"
+    );
+    let index = p.read_file("target/doc/index.html");
+    assert!(index.contains(r#"href="bar/index.html""#));
+    assert!(index.contains(r#"href="foo/index.html""#));
+}
+
+#[cargo_test(nightly, reason = "rustdoc --index-page is unstable")]
+fn index_follows_selected_packages() {
+    let p = workspace();
+
+    p.cargo("doc -p foo -Zdoc-merged-index")
+        .masquerade_as_nightly_cargo(&["doc-merged-index"])
+        .run();
+    assert_eq!(
+        p.read_file("target/tmp/doc-index/index.md"),
+        "# Crates\n\n* [foo](foo/index.html): The foo crate\n"
+    );
+
+    // Selecting another package changes the index, so the docs of `foo` are
+    // rebuilt to list `bar` as well.
+    p.cargo("doc --workspace -Zdoc-merged-index")
+        .masquerade_as_nightly_cargo(&["doc-merged-index"])
+        .with_stderr_data(
+            str![[r#"
+[DOCUMENTING] bar v0.1.0 ([ROOT]/foo/bar)
+[DOCUMENTING] foo v0.1.0 ([ROOT]/foo/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[GENERATED] [ROOT]/foo/target/doc/bar/index.html and 1 other file
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(p
+        .read_file("target/doc/index.html")
+        .contains(r#"href="bar/index.html""#));
+}
+
+#[cargo_test]
+fn no_index_without_flag() {
+    let p = workspace();
+
+    p.cargo("doc --workspace").run();
+    assert!(!p.root().join("target/tmp/doc-index/index.md").exists());
+    assert!(!p.root().join("target/doc/index.html").exists());
+}
+
+#[cargo_test(nightly, reason = "rustdoc --index-page is unstable")]
+fn open_index() {
+    let p = workspace();
+
+    p.cargo("doc --workspace --open -Zdoc-merged-index")
+        .masquerade_as_nightly_cargo(&["doc-merged-index"])
+        .env("BROWSER", tools::echo())
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[DOCUMENTING] bar v0.1.0 ([ROOT]/foo/bar)
+[DOCUMENTING] foo v0.1.0 ([ROOT]/foo/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+     Opening [ROOT]/foo/target/doc/index.html
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
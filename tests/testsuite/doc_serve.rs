@@ -0,0 +1,121 @@
+//! Tests for `cargo doc --serve`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{ChildStderr, Stdio};
+
+use cargo_test_support::{project, str, tools};
+
+/// Reads the output of cargo until a line containing `needle`, and returns it.
+fn read_until(stderr: &mut BufReader<ChildStderr>, needle: &str) -> String {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert_ne!(
+            stderr.read_line(&mut line).unwrap(),
+            0,
+            "cargo exited before printing `{needle}`"
+        );
+        if line.contains(needle) {
+            return line;
+        }
+    }
+}
+
+/// Sends a `GET` request for `path` to the server at `url`, and returns the
+/// status line and the body of the response.
+fn get(url: &str, path: &str) -> (String, String) {
+    let addr = url.trim_start_matches("http://").trim_end_matches('/');
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.lines().next().unwrap().to_string();
+    (status, body.to_string())
+}
+
+#[cargo_test]
+fn serves_and_rebuilds() {
+    let p = project().file("src/lib.rs", "//! Initial docs.").build();
+
+    let mut cargo = p
+        .cargo("doc -Zunstable-options --serve")
+        .masquerade_as_nightly_cargo(&["doc-serve"])
+        .build_command();
+    let mut child = cargo.stderr(Stdio::piped()).spawn().unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+
+    let serving = read_until(&mut stderr, "Serving");
+    let url = serving.trim().trim_start_matches("Serving").trim();
+    assert!(url.starts_with("http://127.0.0.1:"), "{url}");
+
+    let (status, body) = get(url, "/foo/");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(body.contains("Initial docs."));
+    let (status, _) = get(url, "/foo/missing.html");
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+    let (status, _) = get(url, "/%2e%2e/src/lib.rs");
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+
+    // Make sure the change gets a newer mtime on filesystems with a coarse
+    // resolution.
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    p.change_file("src/lib.rs", "//! Changed docs.");
+    read_until(&mut stderr, "Rebuilding src/lib.rs changed");
+    read_until(&mut stderr, "Finished");
+    let (_, body) = get(url, "/foo/index.html");
+    assert!(body.contains("Changed docs."));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cargo_test]
+fn open_served_page() {
+    let p = project().file("src/lib.rs", "").build();
+
+    let mut cargo = p
+        .cargo("doc -Zunstable-options --serve --open")
+        .masquerade_as_nightly_cargo(&["doc-serve"])
+        .env("BROWSER", tools::echo())
+        .build_command();
+    let mut child = cargo.stderr(Stdio::piped()).spawn().unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+
+    let serving = read_until(&mut stderr, "Serving");
+    let url = serving.trim().trim_start_matches("Serving").trim();
+    let opening = read_until(&mut stderr, "Opening");
+    assert_eq!(opening.trim(), format!("Opening {url}foo/index.html"));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cargo_test]
+fn serve_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("doc --serve")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--serve` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_address() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("doc -Zunstable-options --serve localhost")
+        .masquerade_as_nightly_cargo(&["doc-serve"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid address `localhost` for `--serve`
+
+"#]])
+        .run();
+}
@@ -0,0 +1,219 @@
+//! Tests for `cargo build --executor`.
+
+use std::path::PathBuf;
+
+use cargo_test_support::{basic_manifest, paths, project, str};
+use cargo_util_schemas::executor::{ExecutorRequest, EXECUTOR_PROTOCOL_VERSION};
+
+/// Builds an executor that appends each request to `requests.jsonl` in the
+/// current directory, then runs the requested command locally.
+fn logging_executor() -> PathBuf {
+    let p = project()
+        .at(paths::global_root().join("logging-executor"))
+        .file("Cargo.toml", &basic_manifest("logging-executor", "1.0.0"))
+        .file(
+            "src/main.rs",
+            r#"
+            use std::io::{Read, Write};
+
+            enum Json {
+                Null,
+                Str(String),
+                Array(Vec<Json>),
+                Object(Vec<(String, Json)>),
+            }
+
+            impl Json {
+                fn get(&self, key: &str) -> &Json {
+                    let Json::Object(fields) = self else { panic!("not an object") };
+                    &fields.iter().find(|(k, _)| k == key).unwrap().1
+                }
+                fn str(&self) -> &str {
+                    let Json::Str(s) = self else { panic!("not a string") };
+                    s
+                }
+            }
+
+            fn parse(s: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Json {
+                while s.peek().unwrap().is_whitespace() {
+                    s.next();
+                }
+                match s.next().unwrap() {
+                    '"' => {
+                        let mut out = String::new();
+                        loop {
+                            match s.next().unwrap() {
+                                '"' => return Json::Str(out),
+                                '\\' => match s.next().unwrap() {
+                                    'n' => out.push('\n'),
+                                    't' => out.push('\t'),
+                                    'u' => {
+                                        let hex: String = s.by_ref().take(4).collect();
+                                        let c = u32::from_str_radix(&hex, 16).unwrap();
+                                        out.push(char::from_u32(c).unwrap());
+                                    }
+                                    c => out.push(c),
+                                },
+                                c => out.push(c),
+                            }
+                        }
+                    }
+                    '[' => {
+                        let mut items = Vec::new();
+                        loop {
+                            if *s.peek().unwrap() == ']' {
+                                s.next();
+                                return Json::Array(items);
+                            }
+                            items.push(parse(s));
+                            if s.next().unwrap() == ']' {
+                                return Json::Array(items);
+                            }
+                        }
+                    }
+                    '{' => {
+                        let mut fields = Vec::new();
+                        loop {
+                            if *s.peek().unwrap() == '}' {
+                                s.next();
+                                return Json::Object(fields);
+                            }
+                            let Json::Str(key) = parse(s) else { panic!("bad key") };
+                            assert_eq!(s.next(), Some(':'));
+                            fields.push((key, parse(s)));
+                            if s.next().unwrap() == '}' {
+                                return Json::Object(fields);
+                            }
+                        }
+                    }
+                    _ => {
+                        while s.peek().map_or(false, |c| !",]}".contains(*c)) {
+                            s.next();
+                        }
+                        Json::Null
+                    }
+                }
+            }
+
+            fn main() {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input).unwrap();
+                let mut log = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open("requests.jsonl")
+                    .unwrap();
+                writeln!(log, "{}", input).unwrap();
+
+                let request = parse(&mut input.chars().peekable());
+                let mut cmd = std::process::Command::new(request.get("program").str());
+                if let Json::Array(args) = request.get("args") {
+                    cmd.args(args.iter().map(Json::str));
+                }
+                if let Json::Object(env) = request.get("env") {
+                    cmd.envs(env.iter().map(|(k, v)| (k, v.str())));
+                }
+                if let Json::Str(cwd) = request.get("cwd") {
+                    cmd.current_dir(cwd);
+                }
+                let status = cmd.status().unwrap();
+                std::process::exit(status.code().unwrap_or(1));
+            }
+            "#,
+        )
+        .build();
+    p.cargo("build").run();
+    p.bin("logging-executor")
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build --executor foo")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--executor` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn runs_every_rustc_invocation() {
+    let executor = logging_executor();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() { bar::bar(); }")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "pub fn bar() {}")
+        .build();
+
+    p.cargo("build -Zunstable-options --executor")
+        .arg(&executor)
+        .masquerade_as_nightly_cargo(&["executor"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] bar v0.1.0 ([ROOT]/foo/bar)
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    p.process(&p.bin("foo")).run();
+
+    let requests = p
+        .read_file("requests.jsonl")
+        .lines()
+        .map(|line| serde_json::from_str::<ExecutorRequest>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(requests.len(), 2);
+    for (request, (name, kind)) in requests.iter().zip([("bar", "lib"), ("foo", "bin")]) {
+        assert_eq!(request.version, EXECUTOR_PROTOCOL_VERSION);
+        assert_eq!(request.package_id.name(), name);
+        assert_eq!(request.target_name, name);
+        assert_eq!(request.target_kind, [kind]);
+        assert_eq!(request.compile_mode, "build");
+        assert!(request.args.iter().any(|arg| arg == name));
+        assert_eq!(request.env["CARGO_PKG_NAME"], name);
+    }
+}
+
+#[cargo_test]
+fn executor_failure_fails_the_build() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build -Zunstable-options --executor does-not-exist")
+        .masquerade_as_nightly_cargo(&["executor"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[ERROR] could not compile `foo` (lib)
+
+Caused by:
+  could not execute process `does-not-exist` (never executed)
+
+Caused by:
+  [NOT_FOUND]
+
+"#]])
+        .run();
+}
@@ -0,0 +1,117 @@
+//! Tests for `[workspace.features]` presets.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+fn preset_project() -> Project {
+    Package::new("common", "1.0.0")
+        .feature("std", &[])
+        .feature("serde", &[])
+        .publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+                resolver = "2"
+
+                [workspace.features]
+                ci = ["common/std", "extra"]
+                nested = ["preset:ci"]
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                common = "1.0"
+
+                [features]
+                extra = []
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                common = "1.0"
+            "#,
+        )
+        .file("b/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn expands_preset() {
+    let p = preset_project();
+
+    p.cargo("tree -e features -i common -p a --features preset:ci,common/serde -Zfeature-presets")
+        .masquerade_as_nightly_cargo(&["feature-presets"])
+        .with_stdout_data(str![[r#"
+common v1.0.0
+├── common feature "default"
+│   └── a v0.1.0 ([ROOT]/foo/a)
+│       ├── a feature "default" (command-line)
+│       └── a feature "extra" (command-line)
+├── common feature "serde" (command-line)
+└── common feature "std" (command-line)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_unstable_flag() {
+    let p = preset_project();
+
+    p.cargo("check -p a --features preset:ci")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] [ROOT]/foo/Cargo.toml: ignoring `workspace.features`, pass `-Zfeature-presets` to enable
+[ERROR] feature `preset:ci` requires `-Zfeature-presets`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn unknown_preset() {
+    let p = preset_project();
+
+    p.cargo("check -p a --features preset:release -Zfeature-presets")
+        .masquerade_as_nightly_cargo(&["feature-presets"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] feature preset `release` is not defined in `[workspace.features]`
+
+available presets: `ci`, `nested`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn nested_preset() {
+    let p = preset_project();
+
+    p.cargo("check -p a --features preset:nested -Zfeature-presets")
+        .masquerade_as_nightly_cargo(&["feature-presets"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] feature preset `nested` includes `preset:ci`, but presets cannot include other presets
+
+"#]])
+        .run();
+}
@@ -0,0 +1,161 @@
+//! Tests for `resolver.feature-unification`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+fn workspace_with_shared_dep() -> Project {
+    Package::new("dep", "0.1.0")
+        .feature("f", &[])
+        .file(
+            "src/lib.rs",
+            r#"
+                #[cfg(feature = "f")]
+                pub fn f() {}
+            "#,
+        )
+        .publish();
+
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+                resolver = "2"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "0.1.0"
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = { version = "0.1.0", features = ["f"] }
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() { dep::f() }")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = workspace_with_shared_dep();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [resolver]
+            feature-unification = "workspace"
+        "#,
+    );
+
+    p.cargo("check -p a")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `resolver.feature-unification` config without `-Zfeature-unification`
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v0.1.0 (registry `dummy-registry`)
+[CHECKING] dep v0.1.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn selected() {
+    let p = workspace_with_shared_dep();
+
+    p.cargo("check -p a")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v0.1.0 (registry `dummy-registry`)
+[CHECKING] dep v0.1.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // `b` enables another feature of `dep`, so it is built again.
+    p.cargo("check -p b")
+        .with_stderr_data(str![[r#"
+[CHECKING] dep v0.1.0
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn workspace() {
+    let p = workspace_with_shared_dep();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [resolver]
+            feature-unification = "workspace"
+        "#,
+    );
+
+    p.cargo("check -p a -Zfeature-unification")
+        .masquerade_as_nightly_cargo(&["feature-unification"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v0.1.0 (registry `dummy-registry`)
+[CHECKING] dep v0.1.0
+[CHECKING] a v0.1.0 ([ROOT]/foo/a)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // `dep` was already built with the features `b` needs.
+    p.cargo("check -p b -Zfeature-unification")
+        .masquerade_as_nightly_cargo(&["feature-unification"])
+        .with_stderr_data(str![[r#"
+[CHECKING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.cargo("check --workspace -Zfeature-unification")
+        .masquerade_as_nightly_cargo(&["feature-unification"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.cargo("tree -p a -f {p}:{f} -Zfeature-unification")
+        .masquerade_as_nightly_cargo(&["feature-unification"])
+        .with_stdout_data(str![[r#"
+a v0.1.0 ([ROOT]/foo/a):
+└── dep v0.1.0:f
+
+"#]])
+        .run();
+}
@@ -1855,6 +1855,218 @@ foo v0.1.0 ([ROOT]/foo)
         .run();
 }
 
+#[cargo_test]
+fn decouple_targets() {
+    // resolver = "3" unifies features separately for each `--target` with
+    // `-Zper-target-features`.
+    if cross_compile::disabled() {
+        return;
+    }
+    Package::new("common", "1.0.0")
+        .feature("alt", &[])
+        .file(
+            "src/lib.rs",
+            r#"
+            #[cfg(feature = "alt")]
+            compile_error!("alt should not be enabled");
+            "#,
+        )
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                cargo-features = ["edition2024"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+                resolver = "3"
+
+                [dependencies]
+                common = "1.0"
+
+                [target.'cfg(target_arch = "{}")'.dependencies]
+                common = {{ version = "1.0", features = ["alt"] }}
+                "#,
+                cross_compile::alternate_arch()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("tree -Zper-target-features -f")
+        .arg("{p} {f}")
+        .arg("--target")
+        .arg(rustc_host())
+        .arg("--target")
+        .arg(alternate())
+        .masquerade_as_nightly_cargo(&["edition2024", "per-target-features"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 alt
+
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 
+
+"#]])
+        .run();
+
+    // The host build does not see the features enabled for the alternate target.
+    p.cargo("check -Zper-target-features --target")
+        .arg(rustc_host())
+        .masquerade_as_nightly_cargo(&["edition2024", "per-target-features"])
+        .run();
+
+    // Features are unified across targets without the flag.
+    p.cargo("tree -f")
+        .arg("{p} {f}")
+        .arg("--target")
+        .arg(rustc_host())
+        .arg("--target")
+        .arg(alternate())
+        .masquerade_as_nightly_cargo(&["edition2024"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 alt
+
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 alt
+
+"#]])
+        .run();
+
+    // And with resolver = "2".
+    p.change_file(
+        "Cargo.toml",
+        &p.read_file("Cargo.toml")
+            .replace("resolver = \"3\"", "resolver = \"2\""),
+    );
+    p.cargo("tree -Zper-target-features -f")
+        .arg("{p} {f}")
+        .arg("--target")
+        .arg(rustc_host())
+        .arg("--target")
+        .arg(alternate())
+        .masquerade_as_nightly_cargo(&["edition2024", "per-target-features"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 alt
+
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 alt
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn decouple_targets_features_per_target() {
+    // The features of each `--target` are kept apart in `cargo tree`,
+    // `cargo metadata` and `required-features`. Nothing is built, so this
+    // does not need a cross toolchain.
+    Package::new("common", "1.0.0")
+        .feature("alt", &[])
+        .feature("std", &[])
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["edition2024"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+                resolver = "3"
+
+                [dependencies]
+                common = "1.0"
+
+                [target.'cfg(target_arch = "wasm32")'.dependencies]
+                common = { version = "1.0", features = ["alt"] }
+
+                [target.'cfg(not(target_arch = "wasm32"))'.dependencies]
+                common = { version = "1.0", features = ["std"] }
+
+                [[bin]]
+                name = "needs-alt"
+                required-features = ["common/alt"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("src/bin/needs-alt.rs", "fn main() {}")
+        .build();
+
+    p.cargo("tree -Zper-target-features -f")
+        .arg("{p} {f}")
+        .arg("--target")
+        .arg(rustc_host())
+        .arg("--target=wasm32-unknown-unknown")
+        .masquerade_as_nightly_cargo(&["edition2024", "per-target-features"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 alt
+
+foo v0.1.0 ([ROOT]/foo) 
+└── common v1.0.0 std
+
+"#]])
+        .run();
+
+    let metadata = p
+        .cargo("metadata --format-version 1 -Zper-target-features")
+        .arg("--filter-platform")
+        .arg(rustc_host())
+        .arg("--filter-platform=wasm32-unknown-unknown")
+        .masquerade_as_nightly_cargo(&["edition2024", "per-target-features"])
+        .run_json();
+    let common = metadata["resolve"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|node| node["id"].as_str().unwrap().ends_with("#common@1.0.0"))
+        .unwrap();
+    assert_eq!(
+        common["target_features"],
+        serde_json::json!({
+            rustc_host(): ["std"],
+            "wasm32-unknown-unknown": ["alt"],
+        })
+    );
+
+    // Without the flag the features are unified, and not listed per target.
+    let metadata = p
+        .cargo("metadata --format-version 1")
+        .arg("--filter-platform")
+        .arg(rustc_host())
+        .arg("--filter-platform=wasm32-unknown-unknown")
+        .masquerade_as_nightly_cargo(&["edition2024"])
+        .run_json();
+    assert!(metadata["resolve"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|node| node.get("target_features").is_none()));
+
+    // `common/alt` is only enabled for wasm32, so the host build of
+    // `needs-alt` is missing it.
+    p.cargo("check -Zper-target-features --bin needs-alt --target")
+        .arg(rustc_host())
+        .arg("--target=wasm32-unknown-unknown")
+        .masquerade_as_nightly_cargo(&["edition2024", "per-target-features"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] target `needs-alt` in package `foo` requires the features: `common/alt`
+Consider enabling them by passing, e.g., `--features="common/alt"`
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn shared_dep_same_but_dependencies() {
     // Checks for a bug of nondeterminism. This scenario creates a shared
@@ -2,9 +2,12 @@
 
 #![allow(deprecated)]
 
-use cargo_test_support::registry::Package;
+use std::path::Path;
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::{registry_path, Package};
 use cargo_test_support::rustc_host;
-use cargo_test_support::{basic_manifest, cross_compile, project};
+use cargo_test_support::{basic_manifest, cross_compile, git, project, str};
 
 #[cargo_test]
 fn no_deps() {
@@ -138,3 +141,140 @@ fn fetch_warning() {
         .with_stderr("[WARNING] unused manifest key: package.misspelled")
         .run();
 }
+
+#[cargo_test]
+fn lock_only() {
+    Package::new("bar", "1.0.0").publish();
+    let git = git::new("baz", |p| {
+        p.file("Cargo.toml", &basic_manifest("baz", "0.1.0"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    bar = "1.0"
+                    baz = {{ git = "{}" }}
+                    qux = {{ path = "qux" }}
+                "#,
+                git.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file("qux/Cargo.toml", &basic_manifest("qux", "0.1.0"))
+        .file("qux/src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch -Zunstable-options --lock-only")
+        .masquerade_as_nightly_cargo(&["lock-only"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "downloads": [
+    {
+      "checksum": "[..]",
+      "kind": "registry",
+      "name": "bar",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "url": "[..]/dl/bar/1.0.0/download",
+      "version": "1.0.0"
+    },
+    {
+      "kind": "git",
+      "rev": "[..]",
+      "url": "[ROOTURL]/baz"
+    }
+  ],
+  "version": 1
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[UPDATING] git repository `[ROOTURL]/baz`
+[LOCKING] 4 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    // Nothing was downloaded.
+    p.cargo("fetch -Zunstable-options --output downloads.json")
+        .masquerade_as_nightly_cargo(&["lock-only"])
+        .with_stderr_data(str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+
+"#]])
+        .run();
+    assert!(p
+        .read_file("downloads.json")
+        .contains("/dl/bar/1.0.0/download"));
+}
+
+#[cargo_test]
+fn lock_only_registry_without_config() {
+    Package::new("bar", "1.0.0").publish();
+    let repo = git2::Repository::open(registry_path()).unwrap();
+    let mut index = repo.index().unwrap();
+    index.remove_path(Path::new("config.json")).unwrap();
+    index.write().unwrap();
+    git::commit(&repo);
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("fetch -Zunstable-options --lock-only")
+        .masquerade_as_nightly_cargo(&["lock-only"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[ERROR] failed to find the download URL of `bar v1.0.0`, registry `dummy-registry` has no `config.json`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn lock_only_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("fetch --lock-only")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--lock-only` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    p.cargo("fetch --lock-only")
+        .masquerade_as_nightly_cargo(&["fetch --lock-only"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--lock-only` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
@@ -2889,3 +2889,128 @@ dep_df_false = { workspace = true, default-features = false }
 "#]],
     );
 }
+
+#[cargo_test]
+fn from_json() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+                pub fn foo() -> u32 {
+                    let mut x = 1;
+                    x
+                }
+            "#,
+        )
+        .build();
+
+    let out = p
+        .cargo("check -Zunstable-options --message-format=json")
+        .masquerade_as_nightly_cargo(&["from-json"])
+        .exec_with_output()
+        .unwrap()
+        .stdout;
+    // Only the JSON file is needed, and the build directory may be gone.
+    p.build_dir().rm_rf();
+    p.change_file("out.json", &String::from_utf8(out).unwrap());
+
+    p.cargo("fix -Zunstable-options --from-json out.json --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["from-json"])
+        .with_stderr_data(str![[r#"
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 1;"));
+    assert!(!p.build_dir().exists());
+}
+
+#[cargo_test]
+fn from_json_skips_changed_files() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+                pub fn foo() -> u32 {
+                    let mut x = 1;
+                    x
+                }
+            "#,
+        )
+        .build();
+
+    let out = p
+        .cargo("check -Zunstable-options --message-format=json")
+        .masquerade_as_nightly_cargo(&["from-json"])
+        .exec_with_output()
+        .unwrap()
+        .stdout;
+    p.change_file("out.json", &String::from_utf8(out).unwrap());
+    // The lines the diagnostic points to are the same, but the file isn't.
+    p.change_file(
+        "src/lib.rs",
+        r#"
+                pub fn foo() -> u32 {
+                    let mut x = 1;
+                    x
+                }
+                // changed
+            "#,
+    );
+
+    p.cargo("fix -Zunstable-options --from-json out.json --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["from-json"])
+        .with_stderr_data(str![[r#"
+[WARNING] `src/lib.rs` changed since the diagnostics were emitted, not applying its suggestions
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 1;"));
+}
+
+#[cargo_test]
+fn from_json_requires_source_hashes() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+                pub fn foo() -> u32 {
+                    let mut x = 1;
+                    x
+                }
+            "#,
+        )
+        .build();
+
+    let out = p
+        .cargo("check --message-format=json")
+        .exec_with_output()
+        .unwrap()
+        .stdout;
+    p.change_file("out.json", &String::from_utf8(out).unwrap());
+
+    p.cargo("fix -Zunstable-options --from-json out.json --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["from-json"])
+        .with_stderr_data(str![[r#"
+[WARNING] the messages don't record a hash of `src/lib.rs`, not applying its suggestions
+[NOTE] pass `-Zunstable-options` to the build writing the messages to record it
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 1;"));
+}
+
+#[cargo_test]
+fn from_json_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+    p.change_file("out.json", "");
+
+    p.cargo("fix --from-json out.json --allow-no-vcs")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--from-json` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+}
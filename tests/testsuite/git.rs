@@ -3038,6 +3038,175 @@ fn git_fetch_cli_env_clean() {
         .run();
 }
 
+#[cargo_test(requires_git)]
+fn git_fetch_jobs() {
+    // Fetches several git dependencies at once with the git CLI.
+    let dep1 = git::new("dep1", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep1", "0.5.0"))
+            .file("src/lib.rs", "pub fn f() {}")
+    });
+    let dep2 = git::new("dep2", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep2", "0.5.0"))
+            .file("src/lib.rs", "pub fn f() {}")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep1 = {{ git = '{}' }}
+                dep2 = {{ git = '{}' }}
+                "#,
+                dep1.url(),
+                dep2.url()
+            ),
+        )
+        .file("src/lib.rs", "pub fn f() { dep1::f(); dep2::f(); }")
+        .file(
+            ".cargo/config.toml",
+            "
+            [net]
+            git-fetch-with-cli = true
+            git-fetch-jobs = 2
+            ",
+        )
+        .build();
+
+    p.cargo("fetch -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["git-fetch-jobs"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep1`
+[UPDATING] git repository `[ROOTURL]/dep2`
+[LOCKING] 3 packages to latest compatible versions
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    p.cargo("check --offline -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["git-fetch-jobs"])
+        .with_stderr_data(
+            str![[r#"
+[CHECKING] dep1 v0.5.0 ([ROOTURL]/dep1#[..])
+[CHECKING] dep2 v0.5.0 ([ROOTURL]/dep2#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test(requires_git)]
+fn git_fetch_jobs_zero() {
+    let dep1 = git::new("dep1", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep1", "0.5.0"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep1 = {{ git = '{}' }}
+                "#,
+                dep1.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            "
+            [net]
+            git-fetch-with-cli = true
+            git-fetch-jobs = 0
+            ",
+        )
+        .build();
+
+    p.cargo("fetch -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["git-fetch-jobs"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to get `dep1` as a dependency of package `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  failed to load source for dependency `dep1`
+
+Caused by:
+  `net.git-fetch-jobs` must be at least 1
+
+"#]])
+        .run();
+}
+
+#[cargo_test(requires_git)]
+fn git_fetch_jobs_is_unstable() {
+    let dep1 = git::new("dep1", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep1", "0.5.0"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep1 = {{ git = '{}' }}
+                "#,
+                dep1.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            "
+            [net]
+            git-fetch-with-cli = true
+            git-fetch-jobs = 2
+            ",
+        )
+        .build();
+
+    p.cargo("fetch")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to get `dep1` as a dependency of package `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  failed to load source for dependency `dep1`
+
+Caused by:
+  the `net.git-fetch-jobs` config is unstable, pass `-Zunstable-options` to enable it
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn dirty_submodule() {
     // `cargo package` warns for dirty file in submodule.
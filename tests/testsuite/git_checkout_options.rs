@@ -0,0 +1,256 @@
+//! Tests for the `shallow` and `paths` keys of git dependencies.
+
+use cargo_test_support::{basic_manifest, git, paths, project, str, t, Project};
+
+fn monorepo() -> Project {
+    git::new("monorepo", |project| {
+        project
+            .file(
+                "Cargo.toml",
+                r#"
+                [workspace]
+                members = ["crates/a", "crates/b"]
+
+                [workspace.package]
+                version = "0.1.0"
+                "#,
+            )
+            .file(
+                "crates/a/Cargo.toml",
+                r#"
+                [package]
+                name = "a"
+                version.workspace = true
+                edition = "2021"
+                "#,
+            )
+            .file("crates/a/src/lib.rs", "pub fn a() {}")
+            .file("crates/b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+            .file("crates/b/src/lib.rs", "pub fn b() {}")
+    })
+}
+
+#[cargo_test]
+fn sparse_paths() {
+    let monorepo = monorepo();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                cargo-features = ["git-checkout-options"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                a = {{ git = '{}', paths = ["crates/a/"] }}
+                "#,
+                monorepo.url()
+            ),
+        )
+        .file("src/lib.rs", "pub fn f() { a::a(); }")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-checkout-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/monorepo`
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] a v0.1.0 ([ROOTURL]/monorepo?path=crates/a#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    let lock = p.read_lockfile();
+    assert!(
+        lock.contains("?path=crates/a#"),
+        "lockfile should record the paths:\n{lock}"
+    );
+
+    let checkouts = paths::home().join(".cargo/git/checkouts");
+    let checkout = t!(t!(std::fs::read_dir(&checkouts)).next().unwrap())
+        .path()
+        .read_dir()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    assert!(checkout.join("Cargo.toml").exists());
+    assert!(checkout.join("crates/a/src/lib.rs").exists());
+    assert!(!checkout.join("crates/b").exists());
+
+    // The lockfile is honored on the next build.
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-checkout-options"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test(requires_git)]
+fn shallow() {
+    let monorepo = monorepo();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                cargo-features = ["git-checkout-options"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                b = {{ git = '{}', shallow = true }}
+                "#,
+                monorepo.url()
+            ),
+        )
+        .file("src/lib.rs", "pub fn f() { b::b(); }")
+        .file(
+            ".cargo/config.toml",
+            "
+            [net]
+            git-fetch-with-cli = true
+            ",
+        )
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-checkout-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/monorepo`
+From [ROOTURL]/monorepo
+ * [new ref]                    -> origin/HEAD
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] b v0.1.0 ([ROOTURL]/monorepo?shallow=true#[..])
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    let db = t!(t!(std::fs::read_dir(paths::home().join(".cargo/git/db")))
+        .next()
+        .unwrap())
+    .path();
+    assert!(db.to_str().unwrap().ends_with("-shallow"));
+    assert!(t!(git2::Repository::open(&db)).is_shallow());
+}
+
+#[cargo_test]
+fn requires_cargo_feature() {
+    let monorepo = monorepo();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                a = {{ git = '{}', paths = ["crates/a"] }}
+                "#,
+                monorepo.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-checkout-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `git-checkout-options` is required
+
+  The package requires the Cargo feature called `git-checkout-options`, but that feature is not stabilized in this version of Cargo ([..]).
+  Consider adding `cargo-features = ["git-checkout-options"]` to the top of Cargo.toml (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#git-checkout-options for more information about the status of this feature.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_paths() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["git-checkout-options"]
+
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            a = { git = "https://example.com/monorepo", paths = ["../a"] }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-checkout-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  invalid path `../a` in `paths` of dependency (a), expected a relative directory like `crates/foo`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn not_a_git_dependency() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["git-checkout-options"]
+
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            a = { path = "a", shallow = true }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-checkout-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  key `shallow` is ignored for dependency (a).
+
+"#]])
+        .run();
+}
@@ -0,0 +1,184 @@
+//! Tests for glob patterns in the `tag` and `branch` of git dependencies.
+
+use cargo_test_support::{basic_manifest, git, project, str, Project};
+
+/// Commits a `dep` package whose `VERSION` constant is `version`.
+fn commit_version(dep: &Project, repo: &git2::Repository, version: &str) {
+    dep.change_file(
+        "src/lib.rs",
+        &format!("pub const VERSION: &str = \"{version}\";"),
+    );
+    git::add(repo);
+    git::commit(repo);
+}
+
+fn dependent(dep: &Project, spec: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                cargo-features = ["git-ref-globs"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                dep = {{ git = '{}', {spec} }}
+                "#,
+                dep.url()
+            ),
+        )
+        .file(
+            "src/main.rs",
+            r#"fn main() { println!("{}", dep::VERSION); }"#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn tag_glob_picks_highest_version() {
+    let (dep, repo) = git::new_repo("dep", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep", "0.1.0"))
+            .file("src/lib.rs", "pub const VERSION: &str = \"v1.0.0\";")
+    });
+    git::tag(&repo, "v1.0.0");
+    for version in ["v1.9.0", "v1.10.0-rc.1", "v1.10.0", "v2.0.0"] {
+        commit_version(&dep, &repo, version);
+        git::tag(&repo, version);
+    }
+
+    let p = dependent(&dep, r#"tag = "v1.*""#);
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .with_stdout_data(str![[r#"
+v1.10.0
+
+"#]])
+        .run();
+
+    // The lockfile keeps the resolved commit until `cargo update`.
+    commit_version(&dep, &repo, "v1.11.0");
+    git::tag(&repo, "v1.11.0");
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .with_stdout_data(str![[r#"
+v1.10.0
+
+"#]])
+        .run();
+    p.cargo("update")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .run();
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .with_stdout_data(str![[r#"
+v1.11.0
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn branch_glob_picks_highest_version() {
+    let (dep, repo) = git::new_repo("dep", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep", "0.1.0"))
+            .file("src/lib.rs", "pub const VERSION: &str = \"main\";")
+    });
+    for version in ["release/1.9", "release/1.10", "feature/2.0"] {
+        commit_version(&dep, &repo, version);
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch(version, &head, false).unwrap();
+    }
+
+    let p = dependent(&dep, r#"branch = "release/*""#);
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .with_stdout_data(str![[r#"
+release/1.10
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn no_matching_tag() {
+    let (dep, repo) = git::new_repo("dep", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep", "0.1.0"))
+            .file("src/lib.rs", "pub const VERSION: &str = \"v1.0.0\";")
+    });
+    git::tag(&repo, "v1.0.0");
+
+    let p = dependent(&dep, r#"tag = "v2.*""#);
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/dep`
+[ERROR] failed to get `dep` as a dependency of package `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  failed to load source for dependency `dep`
+
+Caused by:
+  Unable to update [ROOTURL]/dep?tag=v2.*
+
+Caused by:
+  failed to find tag `v2.*`
+
+Caused by:
+  no tag matches the pattern
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_cargo_feature() {
+    let (dep, repo) = git::new_repo("dep", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("dep", "0.1.0"))
+            .file("src/lib.rs", "")
+    });
+    git::tag(&repo, "v1.0.0");
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                dep = {{ git = '{}', tag = "v1.*" }}
+                "#,
+                dep.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["git-ref-globs"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `git-ref-globs` is required
+
+  The package requires the Cargo feature called `git-ref-globs`, but that feature is not stabilized in this version of Cargo ([..]).
+  Consider adding `cargo-features = ["git-ref-globs"]` to the top of Cargo.toml (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#git-ref-globs for more information about the status of this feature.
+
+"#]])
+        .run();
+}
@@ -1153,6 +1153,81 @@ fn max_download_size() {
     }
 }
 
+#[cargo_test]
+fn max_download_size_config() {
+    // gc.auto.max-download-size is used by `clean gc` without options.
+    let gctx = GlobalContextBuilder::new().build();
+    populate_cache(
+        &gctx,
+        &[
+            // name, age, crate_size, src_size
+            ("d-1.0.0", 4, 4, 5),
+            ("c-1.0.0", 3, 3, 3),
+            ("a-1.0.0", 1, 2, 5),
+            ("b-1.0.0", 1, 1, 7),
+        ],
+    );
+    std::fs::write(
+        paths::home().join(".cargo/config.toml"),
+        r#"
+            [gc.auto]
+            max-download-size = "20B"
+        "#,
+    )
+    .unwrap();
+
+    cargo_process("clean gc -Zgc --dry-run")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr(
+            "\
+[SUMMARY] 3 files, 12B total
+[WARNING] no files deleted due to --dry-run",
+        )
+        .run();
+    assert_eq!(get_registry_names("src").len(), 4);
+
+    cargo_process("clean gc -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr("[REMOVED] 3 files, 12B total")
+        .run();
+    assert_eq!(get_registry_names("src"), ["a-1.0.0", "b-1.0.0"]);
+    assert_eq!(
+        get_registry_names("cache"),
+        ["a-1.0.0.crate", "b-1.0.0.crate", "c-1.0.0.crate"]
+    );
+
+    // A size from the command line replaces the config.
+    cargo_process("clean gc -Zgc --max-download-size=0")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_stderr("[REMOVED] 5 files, 18B total")
+        .run();
+}
+
+#[cargo_test]
+fn max_download_size_config_invalid() {
+    paths::home().join(".cargo").mkdir_p();
+    std::fs::write(
+        paths::home().join(".cargo/config.toml"),
+        r#"
+            [gc.auto]
+            max-download-size = "lots"
+        "#,
+    )
+    .unwrap();
+    cargo_process("clean gc -Zgc")
+        .masquerade_as_nightly_cargo(&["gc"])
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse config option `gc.auto.max-download-size`
+
+Caused by:
+  invalid size `lots`, expected a number with an optional B, kB, MB, GB, kiB, MiB, or GiB suffix
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn package_cache_lock_during_build() {
     // Verifies that a shared lock is held during a build. Resolution and
@@ -0,0 +1,138 @@
+//! Tests for `-Zgroup-diagnostics`.
+
+use cargo_test_support::{basic_manifest, project, str};
+
+#[cargo_test]
+fn groups_diagnostics_of_a_package() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let x = 1; }")
+        .build();
+
+    p.cargo("check --lib --tests -Zgroup-diagnostics")
+        .masquerade_as_nightly_cargo(&["group-diagnostics"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[WARNING] unused variable: `x`
+ --> src/lib.rs:1:18
+  |
+1 | pub fn f() { let x = 1; }
+...
+[NOTE] emitted by 2 units: `foo` (lib), `foo` (lib test)
+
+[WARNING] `foo` (lib[..]) generated 1 warning [..]
+[WARNING] `foo` (lib[..]) generated 1 warning [..]
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // Cached diagnostics of fresh units are grouped too.
+    p.cargo("check --lib --tests -Zgroup-diagnostics")
+        .masquerade_as_nightly_cargo(&["group-diagnostics"])
+        .with_stderr_data(str![[r#"
+[WARNING] unused variable: `x`
+ --> src/lib.rs:1:18
+  |
+1 | pub fn f() { let x = 1; }
+...
+[NOTE] emitted by 2 units: `foo` (lib), `foo` (lib test)
+
+[WARNING] `foo` (lib[..]) generated 1 warning [..]
+[WARNING] `foo` (lib[..]) generated 1 warning [..]
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn tells_units_apart_by_features() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                bar = { path = "bar", features = ["a"] }
+
+                [build-dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.1.0"
+                edition = "2021"
+
+                [features]
+                a = []
+            "#,
+        )
+        .file("bar/src/lib.rs", "pub fn f() { let x = 1; }")
+        .build();
+
+    p.cargo("check -Zgroup-diagnostics")
+        .masquerade_as_nightly_cargo(&["group-diagnostics"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] bar v0.1.0 ([ROOT]/foo/bar)
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] unused variable: `x`
+ --> bar/src/lib.rs:1:18
+  |
+1 | pub fn f() { let x = 1; }
+...
+[NOTE] emitted by 2 units: `bar` (lib) for the host with no features, `bar` (lib) for the host with features `a`
+
+[WARNING] `bar` (lib) generated 1 warning (run `cargo fix --lib -p bar` to apply 1 suggestion)
+[WARNING] `bar` (lib) generated 1 warning (1 duplicate)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn errors_are_printed_before_failing() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file("src/lib.rs", "pub fn f() { let x = 1; }")
+        .file("src/main.rs", "fn main() { let x: u32 = \"\"; }")
+        .build();
+
+    p.cargo("check -Zgroup-diagnostics")
+        .masquerade_as_nightly_cargo(&["group-diagnostics"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] unused variable: `x`
+ --> src/lib.rs:1:18
+  |
+1 | pub fn f() { let x = 1; }
+...
+
+error[E0308]: mismatched types
+ --> src/main.rs:1:26
+  |
+1 | fn main() { let x: u32 = ""; }
+  |                    ---   ^^ expected `u32`, found `&str`
+  |                    |
+  |                    expected due to this
+
+For more information about this error, try `rustc --explain E0308`.
+
+[WARNING] `foo` (lib) generated 1 warning (run `cargo fix --lib -p foo` to apply 1 suggestion)
+[ERROR] could not compile `foo` (bin "foo") due to 1 previous error
+
+"#]])
+        .run();
+}
@@ -0,0 +1,209 @@
+//! Tests for `--index-snapshot` and the `registry.index-snapshot` config.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+/// Publishes `bar` before and after 2024-05-01, and `baz` without telling
+/// when it was published.
+fn publish() {
+    Package::new("bar", "1.0.0")
+        .pubtime("2024-01-01T00:00:00Z")
+        .publish();
+    Package::new("bar", "1.1.0")
+        .pubtime("2024-04-30T23:59:59Z")
+        .publish();
+    Package::new("bar", "1.2.0")
+        .pubtime("2024-06-01T00:00:00Z")
+        .publish();
+    Package::new("baz", "0.1.0").publish();
+    Package::new("baz", "0.2.0").publish();
+}
+
+fn foo() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+                baz = "0.2"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn resolves_as_of_date() {
+    publish();
+    let p = foo();
+
+    p.cargo("generate-lockfile --index-snapshot 2024-05-01 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    p.cargo("update")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v1.1.0 -> v1.2.0
+
+"#]])
+        .run();
+    // Locked versions published after the snapshot aren't available, but
+    // `cargo update` replaces them.
+    p.cargo("check --index-snapshot 2024-05-01 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] failed to select a version for the requirement `bar = "^1.0"` (locked to 1.2.0)
+candidate versions found which didn't match: 1.1.0, 1.0.0
+location searched: `dummy-registry` index (which is replacing registry `crates-io`)
+required by package `foo v0.1.0 ([ROOT]/foo)`
+perhaps a crate was updated and forgotten to be re-vendored?
+As a reminder, registry versions published after 2024-05-01T00:00:00Z are ignored because of the index snapshot.
+
+"#]])
+        .run();
+    p.cargo("update --index-snapshot 2024-01-01T12:00:00Z -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNGRADING] bar v1.2.0 -> v1.0.0
+
+"#]])
+        .run();
+
+    // The locked versions satisfy the snapshot, so nothing changes.
+    p.cargo("check --index-snapshot 2024-01-01T12:00:00+01:00 -Zunstable-options --locked")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_stderr_data(
+            str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] baz v0.2.0 (registry `dummy-registry`)
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[CHECKING] bar v1.0.0
+[CHECKING] baz v0.2.0
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn nothing_published_yet() {
+    publish();
+    let p = foo();
+
+    p.cargo("generate-lockfile --index-snapshot 2023-12-31 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] no matching package named `bar` found
+location searched: registry `crates-io`
+required by package `foo v0.1.0 ([ROOT]/foo)`
+As a reminder, registry versions published after 2023-12-31T00:00:00Z are ignored because of the index snapshot.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn config() {
+    publish();
+    let p = foo();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [registry]
+            index-snapshot = "2024-05-01"
+        "#,
+    );
+
+    p.cargo("generate-lockfile -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    // The flag takes precedence.
+    p.cargo("update --index-snapshot 2024-01-02 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[DOWNGRADING] bar v1.1.0 -> v1.0.0
+
+"#]])
+        .run();
+
+    // The config is ignored without `-Zunstable-options`.
+    p.cargo("update")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] ignoring `registry.index-snapshot` config, pass `-Zunstable-options` to enable it
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v1.0.0 -> v1.2.0
+
+"#]])
+        .run();
+
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [registry]
+            index-snapshot = "May 1st"
+        "#,
+    );
+    p.cargo("update -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid time `May 1st` for `registry.index-snapshot` in [ROOT]/foo/.cargo/config.toml, expected a date like `2024-05-01` or an RFC 3339 timestamp
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = foo();
+
+    p.cargo("generate-lockfile --index-snapshot 2024-05-01")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--index-snapshot` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    p.cargo("generate-lockfile --index-snapshot 2024-13-01 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["index-snapshot"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid time `2024-13-01` for `--index-snapshot`, expected a date like `2024-05-01` or an RFC 3339 timestamp
+
+"#]])
+        .run();
+}
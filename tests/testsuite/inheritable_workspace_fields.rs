@@ -637,8 +637,8 @@ fn inherited_dependencies_union_features() {
 [UPDATING] `[..]` index
 [LOCKING] 4 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [DOWNLOADED] dep v0.1.0 ([..])
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [DOWNLOADED] dancy_dep v0.6.8 ([..])
 [CHECKING] [..]
 [CHECKING] [..]
@@ -1124,8 +1124,8 @@ fn inherit_dependency_features() {
 [UPDATING] `[..]` index
 [LOCKING] 3 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [DOWNLOADED] dep v0.1.0 ([..])
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [CHECKING] fancy_dep v0.2.4
 [CHECKING] dep v0.1.0
 [CHECKING] bar v0.2.0 ([CWD])
@@ -1509,8 +1509,8 @@ true for `workspace.dependencies.dep`, this could become a hard error in the fut
 [UPDATING] `dummy-registry` index
 [LOCKING] 3 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [DOWNLOADED] dep v0.1.0 ([..])
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [CHECKING] fancy_dep v0.2.4
 [CHECKING] dep v0.1.0
 [CHECKING] bar v0.2.0 ([CWD])
@@ -1609,8 +1609,8 @@ not specified for `workspace.dependencies.dep`, this could become a hard error i
 [UPDATING] `dummy-registry` index
 [LOCKING] 3 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [DOWNLOADED] dep v0.1.0 ([..])
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [CHECKING] fancy_dep v0.2.4
 [CHECKING] dep v0.1.0
 [CHECKING] bar v0.2.0 ([CWD])
@@ -1707,8 +1707,8 @@ fn inherit_def_feat_false_member_def_feat_true() {
 [UPDATING] `dummy-registry` index
 [LOCKING] 3 packages to latest compatible versions
 [DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [DOWNLOADED] dep v0.1.0 ([..])
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
 [CHECKING] fancy_dep v0.2.4
 [CHECKING] dep v0.1.0
 [CHECKING] bar v0.2.0 ([CWD])
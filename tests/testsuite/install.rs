@@ -10,6 +10,7 @@ use std::thread;
 use cargo_test_support::compare::assert_e2e;
 use cargo_test_support::cross_compile;
 use cargo_test_support::git;
+use cargo_test_support::prelude::*;
 use cargo_test_support::registry::{self, registry_path, Package};
 use cargo_test_support::str;
 use cargo_test_support::{
@@ -147,6 +148,7 @@ fn simple_with_message_format() {
                     "doctest": true,
                     "test": true
                 },
+                "platform": null,
                 "profile": "{...}",
                 "features": [],
                 "filenames": "{...}",
@@ -172,6 +174,7 @@ fn simple_with_message_format() {
                     "doctest": false,
                     "test": true
                 },
+                "platform": null,
                 "profile": "{...}",
                 "features": [],
                 "filenames": "{...}",
@@ -2627,3 +2630,588 @@ fn uninstall_running_binary() {
         )
         .run();
 }
+
+fn append_home_config(config: &str) {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(cargo_home().join("config.toml"))
+        .unwrap();
+    file.write_all(config.as_bytes()).unwrap();
+}
+
+#[cargo_test]
+fn install_locked_config() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("bar", "0.1.1")
+        .file("src/lib.rs", "not rust")
+        .publish();
+    Package::new("foo", "0.1.0")
+        .dep("bar", "0.1")
+        .file("src/main.rs", "extern crate bar; fn main() {}")
+        .file(
+            "Cargo.lock",
+            r#"
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "foo"
+version = "0.1.0"
+dependencies = [
+ "bar 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+]
+"#,
+        )
+        .publish();
+    append_home_config(
+        r#"
+            [install]
+            locked = true
+        "#,
+    );
+
+    // The config is unstable, so it's ignored without `-Zunstable-options`.
+    cargo_process("install foo")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `install.locked` config, pass `-Zunstable-options` to enable it
+...
+[ERROR] expected one of `!` or `::`, found `rust`
+...
+"#]])
+        .with_status(101)
+        .run();
+    cargo_process("install foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[INSTALLING] foo v0.1.0
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.0 (registry `dummy-registry`)
+[COMPILING] bar v0.1.0
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+}
+
+#[cargo_test]
+fn install_locked_config_float() {
+    Package::new("bar", "0.1.0").yanked(true).publish();
+    Package::new("bar", "0.1.1").publish();
+    Package::new("baz", "0.1.0").publish();
+    Package::new("baz", "0.1.1")
+        .file("src/lib.rs", "not rust")
+        .publish();
+    Package::new("foo", "0.1.0")
+        .dep("bar", "0.1")
+        .dep("baz", "0.1")
+        .file(
+            "src/main.rs",
+            "extern crate bar; extern crate baz; fn main() {}",
+        )
+        .file(
+            "Cargo.lock",
+            r#"
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "baz"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "foo"
+version = "0.1.0"
+dependencies = [
+ "bar 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+ "baz 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+]
+"#,
+        )
+        .publish();
+    append_home_config(
+        r#"
+            [install]
+            locked = true
+            float = ["bar"]
+        "#,
+    );
+
+    // `bar` updates past its yanked locked version, `baz` stays locked.
+    cargo_process("install foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.1.0
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.1 (registry `dummy-registry`)
+[DOWNLOADED] baz v0.1.0 (registry `dummy-registry`)
+[COMPILING] bar v0.1.1
+[COMPILING] baz v0.1.0
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+}
+
+#[cargo_test]
+fn install_locked_flag_float() {
+    Package::new("bar", "0.1.0").yanked(true).publish();
+    Package::new("bar", "0.1.1").publish();
+    Package::new("baz", "0.1.0").publish();
+    Package::new("baz", "0.1.1")
+        .file("src/lib.rs", "not rust")
+        .publish();
+    Package::new("foo", "0.1.0")
+        .dep("bar", "0.1")
+        .dep("baz", "0.1")
+        .file(
+            "src/main.rs",
+            "extern crate bar; extern crate baz; fn main() {}",
+        )
+        .file(
+            "Cargo.lock",
+            r#"
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "baz"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "foo"
+version = "0.1.0"
+dependencies = [
+ "bar 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+ "baz 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+]
+"#,
+        )
+        .publish();
+    append_home_config(
+        r#"
+            [install]
+            float = ["bar"]
+        "#,
+    );
+
+    // `--locked` lets the packages of `install.float` update as well.
+    cargo_process("install foo --locked -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.1.0
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.1 (registry `dummy-registry`)
+[DOWNLOADED] baz v0.1.0 (registry `dummy-registry`)
+[COMPILING] bar v0.1.1
+[COMPILING] baz v0.1.0
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+}
+
+#[cargo_test]
+fn install_locked_outdated_lock() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    Package::new("foo", "0.1.0")
+        .dep("bar", "0.1")
+        .dep("baz", "0.1")
+        .file(
+            "src/main.rs",
+            "extern crate bar; extern crate baz; fn main() {}",
+        )
+        .file(
+            "Cargo.lock",
+            r#"
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "foo"
+version = "0.1.0"
+dependencies = [
+ "bar 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)",
+]
+"#,
+        )
+        .publish();
+    append_home_config(
+        r#"
+            [install]
+            locked = true
+        "#,
+    );
+
+    // Both `install.locked` and `--locked` refuse a `Cargo.lock` that needs
+    // an update.
+    cargo_process("install foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+...
+[ERROR] the `Cargo.lock` published with `foo v0.1.0` needs to be updated for `baz v0.1.0`, but `install.locked` was set to prevent this
+[HELP] add the packages to `install.float` to let them update
+
+"#]])
+        .with_status(101)
+        .run();
+    cargo_process("install foo --locked -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+...
+[ERROR] the `Cargo.lock` published with `foo v0.1.0` needs to be updated for `baz v0.1.0`, but --locked was set to prevent this
+[HELP] add the packages to `install.float` to let them update
+
+"#]])
+        .with_status(101)
+        .run();
+
+    append_home_config(
+        r#"
+            float = ["baz"]
+        "#,
+    );
+    cargo_process("install foo --locked -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+}
+
+#[cargo_test]
+fn install_locked_config_invalid_float() {
+    Package::new("foo", "0.1.0")
+        .file("src/main.rs", "fn main() {}")
+        .publish();
+    append_home_config(
+        r#"
+            [install]
+            locked = true
+            float = ["bar@x"]
+        "#,
+    );
+
+    cargo_process("install foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+...
+[ERROR] invalid package ID specification `bar@x` in `install.float`
+...
+"#]])
+        .with_status(101)
+        .run();
+}
+
+#[cargo_test]
+fn install_target_cache() {
+    Package::new("bar", "0.1.0").publish();
+    Package::new("foo", "0.1.0")
+        .dep("bar", "0.1")
+        .file("src/main.rs", "extern crate bar; fn main() {}")
+        .publish();
+    append_home_config(
+        r#"
+            [install]
+            target-cache = true
+        "#,
+    );
+
+    // The config is unstable, so it's ignored without `-Zunstable-options`.
+    cargo_process("install foo").run();
+    assert!(!cargo_home().join("install-cache").exists());
+
+    cargo_process("install foo --force -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[INSTALLING] foo v0.1.0
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] bar v0.1.0
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[REPLACING] [ROOT]/home/.cargo/bin/foo[EXE]
+[REPLACED] package `foo v0.1.0` with `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+    let entries: Vec<_> = fs::read_dir(cargo_home().join("install-cache"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].starts_with("foo-"));
+
+    // The dependencies built by the previous install are reused.
+    cargo_process("install foo --force -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[INSTALLING] foo v0.1.0
+[LOCKING] 2 packages to latest compatible versions
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[REPLACING] [ROOT]/home/.cargo/bin/foo[EXE]
+[REPLACED] package `foo v0.1.0` with `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+}
+
+#[cargo_test]
+fn install_target_cache_separates_packages() {
+    pkg("foo", "0.1.0");
+    pkg("bar", "0.1.0");
+    append_home_config(
+        r#"
+            [install]
+            target-cache = true
+        "#,
+    );
+
+    cargo_process("install foo bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+    assert_has_installed_exe(cargo_home(), "bar");
+    let mut entries: Vec<_> = fs::read_dir(cargo_home().join("install-cache"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    entries.sort();
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].starts_with("bar-"));
+    assert!(entries[1].starts_with("foo-"));
+}
+
+#[cargo_test]
+fn uninstall_unused_deps_requires_unstable_options() {
+    cargo_process("uninstall --unused-deps")
+        .with_stderr_data(str![[r#"
+[ERROR] the `--unused-deps` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .with_status(101)
+        .run();
+}
+
+#[cargo_test]
+fn uninstall_unused_deps() {
+    pkg("foo", "0.1.0");
+    pkg("bar", "0.1.0");
+    pkg("baz", "0.1.0");
+    append_home_config(
+        r#"
+            [install]
+            target-cache = true
+        "#,
+    );
+    cargo_process("install foo bar -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    // The cache of another install root is left alone.
+    cargo_process("install baz --root other -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    let cache_entries = || {
+        let mut entries: Vec<_> = fs::read_dir(cargo_home().join("install-cache"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        entries.sort();
+        entries
+    };
+    assert_eq!(cache_entries().len(), 3);
+
+    fs::remove_file(cargo_home().join("bin").join(exe("foo"))).unwrap();
+    cargo_process("uninstall bar --unused-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(
+            str![[r#"
+[REMOVING] [ROOT]/home/.cargo/bin/bar[EXE]
+[REMOVING] `foo[EXE]` of `foo v0.1.0` from the installed packages, it no longer exists
+[REMOVING] [ROOT]/home/.cargo/install-cache/foo-[HASH]
+[REMOVING] [ROOT]/home/.cargo/install-cache/bar-[HASH]
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert_has_not_installed_exe(cargo_home(), "bar");
+    let entries = cache_entries();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].starts_with("baz-"));
+    cargo_process("install --list").with_stdout_data("").run();
+
+    cargo_process("uninstall --unused-deps -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[NOTE] nothing unused to remove
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn outdated_requires_unstable_options() {
+    cargo_process("install --list --outdated")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--outdated` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn list_outdated() {
+    pkg("foo", "0.1.0");
+    pkg("bar", "0.1.0");
+    cargo_process("install foo bar").run();
+
+    cargo_process("install --list --outdated -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[NOTE] all installed packages are up to date
+
+"#]])
+        .run();
+
+    pkg("foo", "0.2.0");
+    pkg("foo", "0.3.0-beta.1");
+    cargo_process("install --list --outdated -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 (latest: v0.2.0)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn install_outdated() {
+    Package::new("foo", "0.1.0")
+        .feature("feat", &[])
+        .file("src/main.rs", "fn main() {}")
+        .publish();
+    pkg("bar", "0.1.0");
+    cargo_process("install foo --features feat").run();
+    cargo_process("install bar").run();
+
+    // The new version only builds with the feature foo was installed with.
+    Package::new("foo", "0.2.0")
+        .feature("feat", &[])
+        .file(
+            "src/main.rs",
+            r#"#[cfg(not(feature = "feat"))] compile_error!("no feat"); fn main() {}"#,
+        )
+        .publish();
+    cargo_process("install --outdated -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.2.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.2.0
+[COMPILING] foo v0.2.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[REPLACING] [ROOT]/home/.cargo/bin/foo[EXE]
+[REPLACED] package `foo v0.1.0` with `foo v0.2.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+[SUMMARY] Successfully updated foo!
+
+"#]])
+        .run();
+
+    cargo_process("install --list")
+        .with_stdout_data(str![[r#"
+bar v0.1.0:
+    bar[EXE]
+foo v0.2.0:
+    foo[EXE]
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn install_outdated_keeps_bin_filter() {
+    Package::new("foo", "0.1.0")
+        .file("src/bin/a.rs", "fn main() {}")
+        .file("src/bin/b.rs", "fn main() {}")
+        .publish();
+    cargo_process("install foo --bin a").run();
+
+    Package::new("foo", "0.2.0")
+        .file("src/bin/a.rs", "fn main() {}")
+        .file("src/bin/b.rs", "fn main() {}")
+        .publish();
+    // Yanked versions are not offered.
+    Package::new("foo", "0.3.0")
+        .file("src/bin/a.rs", "fn main() {}")
+        .file("src/bin/b.rs", "fn main() {}")
+        .yanked(true)
+        .publish();
+    cargo_process("install --list --outdated -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 (latest: v0.2.0)
+
+"#]])
+        .run();
+
+    cargo_process("install --outdated -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run();
+    cargo_process("install --list")
+        .with_stdout_data(str![[r#"
+foo v0.2.0:
+    a[EXE]
+
+"#]])
+        .run();
+}
@@ -0,0 +1,231 @@
+//! Tests for installing prebuilt binaries with the unstable `install.prebuilt`
+//! config.
+
+use std::fs::{self, File};
+
+use cargo_test_support::install::{assert_has_installed_exe, cargo_home, exe};
+use cargo_test_support::paths;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{cargo_process, path2url, rustc_host, str};
+use cargo_util::Sha256;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tar::{Builder, Header};
+
+/// Publishes `foo`, advertising prebuilt binaries in `prebuilt/`, whose
+/// sources only build if `builds` is set.
+fn publish_foo(builds: bool) {
+    let main = if builds { "fn main() {}" } else { "not rust" };
+    Package::new("foo", "0.1.0")
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [package.metadata.binstall]
+                    pkg-url = "{}/{{ name }}-{{ target }}-v{{ version }}{{ archive-suffix }}"
+                "#,
+                path2url(paths::root().join("prebuilt"))
+            ),
+        )
+        .file("src/main.rs", main)
+        .publish();
+}
+
+/// Writes the archive of the prebuilt `foo` binary, returning its checksum.
+fn write_prebuilt() -> String {
+    let dir = paths::root().join("prebuilt");
+    fs::create_dir_all(&dir).unwrap();
+    let name = format!("foo-{}-v0.1.0", rustc_host());
+    let archive = dir.join(format!("{name}.tgz"));
+
+    let mut builder = Builder::new(GzEncoder::new(
+        File::create(&archive).unwrap(),
+        Compression::default(),
+    ));
+    let contents = b"prebuilt foo";
+    let mut header = Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, format!("{name}/{}", exe("foo")), &contents[..])
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    Sha256::new().update_path(&archive).unwrap().finish_hex()
+}
+
+fn enable_prebuilt() {
+    let config = cargo_home().join("config.toml");
+    let mut contents = fs::read_to_string(&config).unwrap_or_default();
+    contents.push_str("\n[install]\nprebuilt = true\n");
+    fs::write(config, contents).unwrap();
+}
+
+#[cargo_test]
+fn install_prebuilt() {
+    publish_foo(false);
+    let checksum = write_prebuilt();
+    enable_prebuilt();
+
+    cargo_process(&format!(
+        "install foo -Zunstable-options --prebuilt-checksum {checksum}"
+    ))
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.1.0
+[DOWNLOADING] prebuilt binaries from [ROOTURL]/prebuilt/foo-[HOST_TARGET]-v0.1.0.tgz
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+    assert_has_installed_exe(cargo_home(), "foo");
+    assert_eq!(
+        fs::read_to_string(cargo_home().join("bin").join(exe("foo"))).unwrap(),
+        "prebuilt foo"
+    );
+}
+
+#[cargo_test]
+fn install_prebuilt_requires_unstable_options() {
+    publish_foo(true);
+    let checksum = write_prebuilt();
+    enable_prebuilt();
+
+    cargo_process(&format!("install foo --prebuilt-checksum {checksum}"))
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--prebuilt-checksum` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    cargo_process("install foo")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `install.prebuilt` config, pass `-Zunstable-options` to enable it
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.1.0
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn checksum_mismatch_builds_from_source() {
+    publish_foo(true);
+    write_prebuilt();
+    enable_prebuilt();
+
+    cargo_process(&format!(
+        "install foo -Zunstable-options --prebuilt-checksum {}",
+        "0".repeat(64)
+    ))
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.1.0
+[DOWNLOADING] prebuilt binaries from [ROOTURL]/prebuilt/foo-[HOST_TARGET]-v0.1.0.tgz
+[WARNING] failed to install the prebuilt binaries of `foo v0.1.0`, building from source
+Caused by:
+  checksum of `[ROOTURL]/prebuilt/foo-[HOST_TARGET]-v0.1.0.tgz` does not match:
+expected: 0000000000000000000000000000000000000000000000000000000000000000
+actual:   [..]
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn checksum_next_to_download_is_not_trusted() {
+    publish_foo(true);
+    let checksum = write_prebuilt();
+    let name = format!("foo-{}-v0.1.0.tgz", rustc_host());
+    fs::write(
+        paths::root()
+            .join("prebuilt")
+            .join(format!("{name}.sha256")),
+        checksum,
+    )
+    .unwrap();
+    enable_prebuilt();
+
+    cargo_process("install foo -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] foo v0.1.0 (registry `dummy-registry`)
+[INSTALLING] foo v0.1.0
+[WARNING] failed to install the prebuilt binaries of `foo v0.1.0`, building from source
+Caused by:
+  the checksum of the prebuilt binaries is unknown, pass it with `--prebuilt-checksum`
+[COMPILING] foo v0.1.0
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+[INSTALLING] [ROOT]/home/.cargo/bin/foo[EXE]
+[INSTALLED] package `foo v0.1.0` (executable `foo[EXE]`)
+[WARNING] be sure to add `[ROOT]/home/.cargo/bin` to your PATH to be able to run the installed binaries
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn checksum_requires_single_package() {
+    publish_foo(true);
+    Package::new("bar", "0.1.0").publish();
+
+    cargo_process(&format!(
+        "install foo bar -Zunstable-options --prebuilt-checksum {}",
+        "0".repeat(64)
+    ))
+    .masquerade_as_nightly_cargo(&["unstable-options"])
+    .with_status(101)
+    .with_stderr_data(str![[r#"
+[ERROR] `--prebuilt-checksum` can only be used when installing a single package
+
+"#]])
+    .run();
+}
+
+#[cargo_test]
+fn customized_build_builds_from_source() {
+    publish_foo(true);
+    let checksum = write_prebuilt();
+    enable_prebuilt();
+
+    cargo_process(&format!(
+        "install foo --debug -Zunstable-options --prebuilt-checksum {checksum}"
+    ))
+    .masquerade_as_nightly_cargo(&["unstable-options"])
+    .with_stderr_data(str![[r#"
+...
+[COMPILING] foo v0.1.0
+...
+"#]])
+    .run();
+}
@@ -0,0 +1,170 @@
+//! Tests for `[target.<platform>.link-inputs]`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{project, rustc_host, sleep_ms, str};
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [target.{}.link-inputs]
+                    linker-scripts = ["memory.x"]
+                "#,
+                rustc_host()
+            ),
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo(&["link-inputs"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `link-inputs` is required
+
+  The package requires the Cargo feature called `link-inputs`, but that feature is not stabilized in this version of Cargo ([..]).
+  Consider adding `cargo-features = ["link-inputs"]` to the top of Cargo.toml (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#link-inputs for more information about the status of this feature.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn passed_to_linked_targets() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["link-inputs"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [target.'cfg(target_os = "linux")'.link-inputs]
+                linker-scripts = ["extra.ld"]
+                objects = ["inputs.ld"]
+
+                [target.'cfg(target_os = "none")'.link-inputs]
+                objects = ["not-this-platform.o"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("src/main.rs", "fn main() {}")
+        .file("build.rs", "fn main() {}")
+        .file(
+            "extra.ld",
+            "SECTIONS { .cargo_test : { KEEP(*(.cargo_test)) } } INSERT AFTER .text;",
+        )
+        // GNU ld takes linker scripts in place of object files too.
+        .file("inputs.ld", "/* nothing to add */")
+        .build();
+
+    p.cargo("build -v")
+        .masquerade_as_nightly_cargo(&["link-inputs"])
+        .with_stderr_data(
+            str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name build_script_build [..]--crate-type bin --emit=dep-info,link -C embed-bitcode=no --check-cfg [..]`
+[RUNNING] `[ROOT]/foo/target/debug/build/foo-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name foo [..]--crate-type lib --emit=dep-info,metadata,link -C embed-bitcode=no -C debuginfo=2 --check-cfg [..]`
+[RUNNING] `rustc --crate-name foo [..]--crate-type bin --emit=dep-info,link -C embed-bitcode=no -C debuginfo=2 -C link-arg=-T[ROOT]/foo/extra.ld -C link-arg=[ROOT]/foo/inputs.ld --check-cfg [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    p.cargo("test -v")
+        .masquerade_as_nightly_cargo(&["link-inputs"])
+        .with_stderr_data(
+            str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]--emit=dep-info,link -C embed-bitcode=no -C debuginfo=2 -C link-arg=-T[ROOT]/foo/extra.ld -C link-arg=[ROOT]/foo/inputs.ld --test [..]`
+[RUNNING] `rustc --crate-name foo [..]--emit=dep-info,link -C embed-bitcode=no -C debuginfo=2 -C link-arg=-T[ROOT]/foo/extra.ld -C link-arg=[ROOT]/foo/inputs.ld --test [..]`
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/foo/target/debug/deps/foo-[HASH][EXE]`
+[RUNNING] `[ROOT]/foo/target/debug/deps/foo-[HASH][EXE]`
+[DOCTEST] foo
+[RUNNING] `rustdoc [..]`
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+#[cfg(target_os = "linux")]
+fn relinks_when_changed() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["link-inputs"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [target.'cfg(target_os = "linux")'.link-inputs]
+                linker-scripts = ["extra.ld"]
+            "#,
+        )
+        .file("src/main.rs", r#"fn main() { println!("linked"); }"#)
+        // Extends the default linker script instead of replacing it.
+        .file(
+            "extra.ld",
+            "SECTIONS { .cargo_test : { KEEP(*(.cargo_test)) } } INSERT AFTER .text;",
+        )
+        .build();
+
+    p.cargo("run")
+        .masquerade_as_nightly_cargo(&["link-inputs"])
+        .with_stdout_data(str![[r#"
+linked
+
+"#]])
+        .run();
+
+    p.cargo("build -v")
+        .masquerade_as_nightly_cargo(&["link-inputs"])
+        .with_stderr_data(str![[r#"
+[FRESH] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    sleep_ms(1000);
+    p.change_file(
+        "extra.ld",
+        "SECTIONS { .cargo_test2 : { KEEP(*(.cargo_test2)) } } INSERT AFTER .text;",
+    );
+    p.cargo("build -v")
+        .masquerade_as_nightly_cargo(&["link-inputs"])
+        .with_stderr_data(str![[r#"
+[DIRTY] foo v0.1.0 ([ROOT]/foo): the file `extra.ld` has changed ([..])
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]-C link-arg=-T[ROOT]/foo/extra.ld [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
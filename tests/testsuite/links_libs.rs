@@ -0,0 +1,315 @@
+//! Tests for `[package.links-libs]`.
+
+use std::path::PathBuf;
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{basic_manifest, project, str};
+
+/// Builds a `pkg-config` that only knows about `openssl` 1.1.1, and returns
+/// its path.
+///
+/// It reports `FAKE_PCFILEDIR` as the directory of `openssl.pc`, and fails
+/// when `FAKE_PKG_CONFIG_FAIL` is set.
+fn fake_pkg_config() -> PathBuf {
+    let p = project()
+        .at("fake-pkg-config")
+        .file("Cargo.toml", &basic_manifest("pkg-config", "0.0.1"))
+        .file(
+            "src/main.rs",
+            r#"
+                fn main() {
+                    let args: Vec<String> = std::env::args().skip(1).collect();
+                    let module = args.last().unwrap();
+                    if std::env::var_os("FAKE_PKG_CONFIG_FAIL").is_some() {
+                        eprintln!("pkg-config ran");
+                        std::process::exit(1);
+                    }
+                    if module != "openssl" {
+                        eprintln!("Package {module} was not found in the pkg-config search path.");
+                        std::process::exit(1);
+                    }
+                    match args[0].as_str() {
+                        "--modversion" => println!("1.1.1"),
+                        "--atleast-version=3.0" => std::process::exit(1),
+                        "--libs" => println!("-L/opt/ssl/lib -lssl -l crypto -pthread"),
+                        "--cflags" => println!(r"-I/opt/ssl/include -I /opt/my\ ssl -DSSL"),
+                        "--variable=pcfiledir" => {
+                            println!("{}", std::env::var("FAKE_PCFILEDIR").unwrap_or_default())
+                        }
+                        _ => {}
+                    }
+                }
+            "#,
+        )
+        .build();
+    p.cargo("build").run();
+    p.bin("pkg-config")
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.links-libs.ssl]
+                pkg-config = "openssl"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `links-libs` is required
+
+  The package requires the Cargo feature called `links-libs`, but that feature is not stabilized in this version of Cargo ([..]).
+  Consider adding `cargo-features = ["links-libs"]` to the top of Cargo.toml (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#links-libs for more information about the status of this feature.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn links_and_exports_to_dependents() {
+    let pkg_config = fake_pkg_config();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                mid = { path = "mid" }
+            "#,
+        )
+        .file(
+            "build.rs",
+            r#"
+                fn main() {
+                    assert_eq!(std::env::var("CARGO_LINKS_LIB_SSL_VERSION").unwrap(), "1.1.1");
+                    let include = std::env::var_os("CARGO_LINKS_LIB_SSL_INCLUDE").unwrap();
+                    assert_eq!(
+                        std::env::split_paths(&include).collect::<Vec<_>>(),
+                        ["/opt/ssl/include", "/opt/my ssl"].map(std::path::PathBuf::from),
+                    );
+                }
+            "#,
+        )
+        .file("src/lib.rs", "extern crate mid;")
+        .file(
+            "mid/Cargo.toml",
+            r#"
+                [package]
+                name = "mid"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                ssl-sys = { path = "../ssl-sys" }
+            "#,
+        )
+        .file("mid/src/lib.rs", "extern crate ssl_sys;")
+        .file(
+            "ssl-sys/Cargo.toml",
+            r#"
+                cargo-features = ["links-libs"]
+
+                [package]
+                name = "ssl-sys"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.links-libs.ssl]
+                pkg-config = "openssl"
+                version = "1.1"
+            "#,
+        )
+        .file(
+            "ssl-sys/build.rs",
+            r#"
+                fn main() {
+                    assert_eq!(std::env::var("CARGO_LINKS_LIB_SSL_VERSION").unwrap(), "1.1.1");
+                }
+            "#,
+        )
+        .file("ssl-sys/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -v")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .env("PKG_CONFIG", &pkg_config)
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 3 packages to latest compatible versions
+[COMPILING] ssl-sys v0.0.1 ([ROOT]/foo/ssl-sys)
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RUNNING] `rustc --crate-name build_script_build [..]`
+[RUNNING] `[ROOT]/foo/target/debug/build/ssl-sys-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name ssl_sys [..] -L native=/opt/ssl/lib -l ssl -l crypto [..]`
+[CHECKING] mid v0.0.1 ([ROOT]/foo/mid)
+[RUNNING] `rustc --crate-name mid [..] -L native=/opt/ssl/lib [..]`
+[RUNNING] `[ROOT]/foo/target/debug/build/foo-[HASH]/build-script-build`
+[RUNNING] `rustc --crate-name foo [..] -L native=/opt/ssl/lib [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn missing_library() {
+    let pkg_config = fake_pkg_config();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["links-libs"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.links-libs.z]
+                install = { apt = "zlib1g-dev", brew = "zlib" }
+                vcpkg = "zlib"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .env("PKG_CONFIG", &pkg_config)
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to find system library `z` required by package `foo v0.0.1 ([ROOT]/foo)`
+[HELP] install it with one of
+  apt install zlib1g-dev
+  brew install zlib
+  vcpkg install zlib
+
+Caused by:
+  process didn't exit successfully: `[ROOT]/fake-pkg-config/target/debug/pkg-config[EXE] --modversion z` ([EXIT_STATUS]: 1)
+  --- stderr
+  Package z was not found in the pkg-config search path.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn version_too_old() {
+    let pkg_config = fake_pkg_config();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["links-libs"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.links-libs.ssl]
+                pkg-config = "openssl"
+                version = "3.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .env("PKG_CONFIG", &pkg_config)
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to find system library `ssl` required by package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  found version 1.1.1 of `openssl`, but at least 3.0 is required
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn probe_is_cached() {
+    let pkg_config = fake_pkg_config();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["links-libs"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [package.links-libs.ssl]
+                pkg-config = "openssl"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("pc/openssl.pc", "")
+        .build();
+    let pc_dir = p.root().join("pc");
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .env("PKG_CONFIG", &pkg_config)
+        .env("FAKE_PCFILEDIR", &pc_dir)
+        .run();
+
+    // `pkg-config` doesn't run again while the results are up to date.
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .env("PKG_CONFIG", &pkg_config)
+        .env("FAKE_PCFILEDIR", &pc_dir)
+        .env("FAKE_PKG_CONFIG_FAIL", "1")
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // It does once the `.pc` file changes.
+    let mtime = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_times(pc_dir.join("openssl.pc"), mtime, mtime).unwrap();
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["links-libs"])
+        .env("PKG_CONFIG", &pkg_config)
+        .env("FAKE_PCFILEDIR", &pc_dir)
+        .env("FAKE_PKG_CONFIG_FAIL", "1")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to find system library `ssl` required by package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  process didn't exit successfully: `[ROOT]/fake-pkg-config/target/debug/pkg-config[EXE] --modversion openssl` ([EXIT_STATUS]: 1)
+  --- stderr
+  pkg-config ran
+
+"#]])
+        .run();
+}
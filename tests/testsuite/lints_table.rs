@@ -840,3 +840,185 @@ im_a_teapot = "warn"
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn lints_profile() {
+    let foo = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lints-profiles"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [lints.rust]
+                unsafe_code = "warn"
+
+                [lints.profile.ci.rust]
+                unsafe_code = "deny"
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            "pub fn foo(p: *const u8) -> u8 { unsafe { *p } }",
+        )
+        .build();
+
+    foo.cargo("check")
+        .masquerade_as_nightly_cargo(&["lints-profiles"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[WARNING] usage of an `unsafe` block
+...
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // The lints of the profile are part of the fingerprint.
+    foo.cargo("check -Zunstable-options --lints-profile ci")
+        .masquerade_as_nightly_cargo(&["lints-profiles"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[ERROR] usage of an `unsafe` block
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn lints_profile_inherited_from_workspace() {
+    let foo = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lints-profiles"]
+
+                [workspace]
+                members = ["bar"]
+
+                [workspace.lints.rust]
+                unsafe_code = "allow"
+
+                [workspace.lints.profile.ci.rust]
+                unsafe_code = "deny"
+            "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                cargo-features = ["lints-profiles"]
+
+                [package]
+                name = "bar"
+                version = "0.0.1"
+                edition = "2015"
+
+                [lints]
+                workspace = true
+            "#,
+        )
+        .file(
+            "bar/src/lib.rs",
+            "pub fn bar(p: *const u8) -> u8 { unsafe { *p } }",
+        )
+        .build();
+
+    foo.cargo("check -Zunstable-options --lints-profile ci")
+        .masquerade_as_nightly_cargo(&["lints-profiles"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CHECKING] bar v0.0.1 ([ROOT]/foo/bar)
+[ERROR] usage of an `unsafe` block
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn lints_profile_unknown() {
+    let foo = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lints-profiles"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [lints.profile.ci.rust]
+                unsafe_code = "deny"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    foo.cargo("check -Zunstable-options --lints-profile dev")
+        .masquerade_as_nightly_cargo(&["lints-profiles"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] lints profile `dev` is not defined in `[lints]` or `[workspace.lints]` of any package in the workspace
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn lints_profile_is_unstable() {
+    let foo = project().file("src/lib.rs", "").build();
+
+    foo.cargo("check --lints-profile ci")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--lints-profile` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    foo.cargo("check --lints-profile ci")
+        .masquerade_as_nightly_cargo(&["lints-profiles"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--lints-profile` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn lints_profile_requires_feature() {
+    let foo = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [lints.profile.ci.rust]
+                unsafe_code = "deny"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    foo.cargo("check")
+        .masquerade_as_nightly_cargo(&["lints-profiles"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `lints-profiles` is required
+...
+"#]])
+        .run();
+}
@@ -1311,3 +1311,318 @@ dependencies = [
         );
     }
 }
+
+#[cargo_test]
+fn v5_records_checksum_algorithm_and_pubtime() {
+    let cksum = Package::new("bar", "0.1.0")
+        .pubtime("2024-05-01T12:00:00Z")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("Cargo.lock", "version = 5")
+        .build();
+
+    p.cargo("fetch -Znext-lockfile-bump")
+        .masquerade_as_nightly_cargo(&["-Znext-lockfile-bump"])
+        .run();
+
+    let lock = p.read_lockfile();
+    assert_e2e().eq(
+        &lock,
+        format!(
+            r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 5
+
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "sha256:{cksum}"
+pubtime = "2024-05-01T12:00:00Z"
+
+[[package]]
+name = "foo"
+version = "0.0.1"
+dependencies = [
+ "bar",
+]
+"#
+        ),
+    );
+
+    // The prefixed checksum is understood when reading the lock file back.
+    p.cargo("fetch -Znext-lockfile-bump --locked")
+        .masquerade_as_nightly_cargo(&["-Znext-lockfile-bump"])
+        .run();
+}
+
+#[cargo_test]
+fn v5_unknown_checksum_algorithm() {
+    Package::new("bar", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.lock",
+            r#"version = 5
+
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "blake3:abcd"
+
+[[package]]
+name = "foo"
+version = "0.0.1"
+dependencies = [
+ "bar",
+]
+"#,
+        )
+        .build();
+
+    p.cargo("fetch -Znext-lockfile-bump")
+        .masquerade_as_nightly_cargo(&["-Znext-lockfile-bump"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse lock file at: [ROOT]/foo/Cargo.lock
+
+Caused by:
+  invalid checksum for package `bar`
+
+Caused by:
+  unknown checksum algorithm `blake3`, expected `sha256`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn update_lockfile_version() {
+    let cksum = Package::new("bar", "0.1.0")
+        .pubtime("2024-05-01T12:00:00Z")
+        .publish();
+
+    let v4_lockfile = format!(
+        r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "{cksum}"
+
+[[package]]
+name = "foo"
+version = "0.0.1"
+dependencies = [
+ "bar",
+]
+"#
+    );
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("Cargo.lock", &v4_lockfile)
+        .build();
+
+    p.cargo("update --lockfile-version 5")
+        .masquerade_as_nightly_cargo(&["-Znext-lockfile-bump"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--lockfile-version` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    p.cargo("update --lockfile-version 5 -Zunstable-options -Znext-lockfile-bump")
+        .masquerade_as_nightly_cargo(&["-Znext-lockfile-bump"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[MIGRATING] lock file to version 5
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    let lock = p.read_lockfile();
+    assert_e2e().eq(
+        &lock,
+        format!(
+            r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 5
+
+[[package]]
+name = "bar"
+version = "0.1.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "sha256:{cksum}"
+pubtime = "2024-05-01T12:00:00Z"
+
+[[package]]
+name = "foo"
+version = "0.0.1"
+dependencies = [
+ "bar",
+]
+"#
+        ),
+    );
+
+    p.cargo("update --lockfile-version 4 -Zunstable-options -Znext-lockfile-bump")
+        .masquerade_as_nightly_cargo(&["-Znext-lockfile-bump"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[MIGRATING] lock file to version 4
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    let lock = p.read_lockfile();
+    assert_e2e().eq(&lock, &v4_lockfile);
+}
+
+#[cargo_test]
+fn update_lockfile_version_downgrade_is_kept() {
+    Package::new("bar", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("Cargo.lock", "version = 4")
+        .build();
+
+    p.cargo("fetch").run();
+    assert!(p.read_lockfile().contains("\nversion = 4\n"));
+
+    p.cargo("update --lockfile-version 5 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] lock file version `5` requires `-Znext-lockfile-bump`
+
+"#]])
+        .run();
+
+    p.cargo("update --lockfile-version 3 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[MIGRATING] lock file to version 3
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+    assert!(p.read_lockfile().contains("\nversion = 3\n"));
+
+    // Writing the lock file again doesn't undo the downgrade.
+    Package::new("bar", "0.1.1").publish();
+    p.cargo("update")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v0.1.0 -> v0.1.1
+
+"#]])
+        .run();
+    assert!(p.read_lockfile().contains("\nversion = 3\n"));
+}
+
+#[cargo_test]
+fn lockfile_version_kept_with_next_version_available() {
+    // V3 and V4 lock files are not migrated when they are written, even when
+    // a newer version is available with `-Znext-lockfile-bump`.
+    Package::new("bar", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    for version in ["3", "4"] {
+        p.change_file("Cargo.lock", &format!("version = {version}"));
+        p.cargo("fetch -Znext-lockfile-bump")
+            .masquerade_as_nightly_cargo(&["next-lockfile-bump"])
+            .run();
+        assert!(p
+            .read_lockfile()
+            .contains(&format!("\nversion = {version}\n")));
+    }
+
+    // Moving to the next version is still possible.
+    p.cargo("update --lockfile-version 5 -Zunstable-options -Znext-lockfile-bump")
+        .masquerade_as_nightly_cargo(&["unstable-options", "next-lockfile-bump"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[MIGRATING] lock file to version 5
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+    assert!(p.read_lockfile().contains("\nversion = 5\n"));
+}
@@ -2,11 +2,12 @@
 
 use cargo_test_support::cargo_process;
 use cargo_test_support::paths::{self, CargoPathExt};
-use cargo_test_support::registry::{self, RegistryBuilder};
+use cargo_test_support::registry::{self, RegistryBuilder, Response, TestRegistry};
 use cargo_test_support::str;
 use cargo_test_support::t;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 const TOKEN: &str = "test-token";
 const TOKEN2: &str = "test-token2";
@@ -365,3 +366,205 @@ fn default_registry_configured() {
     check_token(None, None);
     check_token(Some("a-new-token"), Some("alternative"));
 }
+
+#[cargo_test]
+fn login_generate_keypair() {
+    let _registry = RegistryBuilder::new()
+        .credential_provider(&["cargo:paseto"])
+        .alternative()
+        .no_configure_token()
+        .build();
+    let credentials = credentials_toml();
+
+    cargo_process("login --generate-keypair --registry alternative")
+        .masquerade_as_nightly_cargo(&["asymmetric-token"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--generate-keypair` flag is unstable, pass `-Z asymmetric-token` to enable it
+See https://github.com/rust-lang/cargo/issues/10519 for more information about the `--generate-keypair` flag.
+
+"#]])
+        .run();
+
+    // A key given on stdin is ignored.
+    cargo_process("login -Z asymmetric-token --generate-keypair --registry alternative")
+        .masquerade_as_nightly_cargo(&["asymmetric-token"])
+        .with_stdin("k3.secret.fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+k3.public.[..]
+
+"#]])
+        .run();
+    let first = fs::read_to_string(&credentials).unwrap();
+    assert!(first.contains("secret-key = \"k3.secret."));
+    assert!(!first.contains("fNYVuMvBgOlljt9TDohnaYLblghqaHoQquVZwgR6X12cBFHZLFsaU3q7X3k1Zn36"));
+
+    // Generating a keypair again rotates it.
+    cargo_process("login -Z asymmetric-token --generate-keypair --registry alternative")
+        .masquerade_as_nightly_cargo(&["asymmetric-token"])
+        .with_stderr_data(str![[r#"
+k3.public.[..]
+[NOTE] replaced the keypair of `alternative`, whose public key was `k3.public.[..]`
+Register the new public key with the registry, then revoke the old one.
+
+"#]])
+        .run();
+    let second = fs::read_to_string(&credentials).unwrap();
+    assert!(second.contains("secret-key = \"k3.secret."));
+    assert_ne!(first, second);
+}
+
+#[cargo_test]
+fn login_generate_keypair_without_paseto() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .no_configure_token()
+        .build();
+
+    cargo_process("login -Z asymmetric-token --generate-keypair --registry alternative")
+        .masquerade_as_nightly_cargo(&["asymmetric-token"])
+        .env("CARGO_REGISTRY_GLOBAL_CREDENTIAL_PROVIDERS", "cargo:token")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[ERROR] cannot generate a keypair for `alternative`, as the `cargo:paseto` credential provider is not configured for it
+
+"#]])
+        .run();
+}
+
+/// Builds a registry whose token endpoint answers with `token_responses` in
+/// turn, after handing out a device code that expires in `expires_in` seconds.
+fn device_authorization_registry(
+    expires_in: u64,
+    token_responses: Vec<(u32, &'static str)>,
+) -> TestRegistry {
+    let token_responses = Mutex::new(token_responses.into_iter());
+    RegistryBuilder::new()
+        .http_api()
+        .http_index()
+        .device_authorization()
+        .no_configure_token()
+        .add_responder("/oauth/device", move |req, _server| {
+            let body = String::from_utf8(req.body.clone().unwrap()).unwrap();
+            assert_eq!(body, "client_id=cargo");
+            Response {
+                code: 200,
+                headers: vec![],
+                body: format!(
+                    r#"{{"device_code":"device-code","user_code":"ABCD-1234","verification_uri":"{}","expires_in":{expires_in},"interval":0}}"#,
+                    req.url.join("/device").unwrap()
+                )
+                .into_bytes(),
+            }
+        })
+        .add_responder("/oauth/token", move |req, _server| {
+            let body = String::from_utf8(req.body.clone().unwrap()).unwrap();
+            assert_eq!(
+                body,
+                "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code\
+                 &device_code=device-code&client_id=cargo"
+            );
+            let (code, body) = token_responses.lock().unwrap().next().unwrap();
+            Response {
+                code,
+                headers: vec![],
+                body: body.as_bytes().to_vec(),
+            }
+        })
+        .build()
+}
+
+#[cargo_test]
+fn login_with_device_authorization() {
+    let registry = device_authorization_registry(
+        60,
+        vec![
+            (400, r#"{"error":"authorization_pending"}"#),
+            (
+                200,
+                r#"{"access_token":"device-token","token_type":"Bearer"}"#,
+            ),
+        ],
+    );
+    cargo_process("login -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["device authorization"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[NOTE] to log in to crates-io, open `http://127.0.0.1:[..]/device` and enter the code `ABCD-1234`
+[LOGIN] token for `crates-io` saved
+
+"#]])
+        .run();
+    let credentials = fs::read_to_string(credentials_toml()).unwrap();
+    assert_eq!(credentials, "[registry]\ntoken = \"device-token\"\n");
+}
+
+#[cargo_test]
+fn login_with_device_authorization_never_expiring() {
+    let registry = device_authorization_registry(
+        u64::MAX,
+        vec![(
+            200,
+            r#"{"access_token":"device-token","token_type":"Bearer"}"#,
+        )],
+    );
+    cargo_process("login -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["device authorization"])
+        .replace_crates_io(registry.index_url())
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[NOTE] to log in to crates-io, open `http://127.0.0.1:[..]/device` and enter the code `ABCD-1234`
+[LOGIN] token for `crates-io` saved
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn login_registry_config_error() {
+    let registry = RegistryBuilder::new()
+        .http_api()
+        .http_index()
+        .no_configure_token()
+        .add_responder("/index/config.json", |req, server| {
+            server.internal_server_error(req)
+        })
+        .build();
+    cargo_process("login -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["device authorization"])
+        .replace_crates_io(registry.index_url())
+        .env("CARGO_NET_RETRY", "0")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[ERROR] download of config.json failed
+
+Caused by:
+  failed to get successful HTTP response from `http://127.0.0.1:[..]/index/config.json` (127.0.0.1), got 500
+  body:
+  internal server error
+
+"#]])
+        .run();
+    assert!(!credentials_toml().exists());
+}
+
+#[cargo_test]
+fn login_with_device_authorization_denied() {
+    let registry = device_authorization_registry(60, vec![(400, r#"{"error":"access_denied"}"#)]);
+    cargo_process("login -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["device authorization"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[NOTE] to log in to crates-io, open `http://127.0.0.1:[..]/device` and enter the code `ABCD-1234`
+[ERROR] the login was denied
+
+"#]])
+        .run();
+    assert!(!credentials_toml().exists());
+}
@@ -6,16 +6,21 @@
 extern crate cargo_test_macro;
 
 mod advanced_env;
+mod advisories;
 mod alt_registry;
 mod artifact_dep;
 mod artifact_dir;
+mod artifact_hashes;
 mod bad_config;
 mod bad_manifest_path;
 mod bench;
+mod bench_baseline;
 mod binary_name;
 mod build;
 mod build_plan;
+mod build_sandbox;
 mod build_script;
+mod build_script_cache;
 mod build_script_env;
 mod build_script_extra_link_arg;
 mod cache_lock;
@@ -24,6 +29,7 @@ mod cargo;
 mod cargo_add;
 mod cargo_alias_config;
 mod cargo_bench;
+mod cargo_bisect_dep;
 mod cargo_build;
 mod cargo_check;
 mod cargo_clean;
@@ -39,11 +45,13 @@ mod cargo_git_checkout;
 mod cargo_help;
 mod cargo_init;
 mod cargo_install;
+mod cargo_license_report;
 mod cargo_locate_project;
 mod cargo_login;
 mod cargo_logout;
 mod cargo_metadata;
 mod cargo_new;
+mod cargo_outdated;
 mod cargo_owner;
 mod cargo_package;
 mod cargo_pkgid;
@@ -51,6 +59,7 @@ mod cargo_publish;
 mod cargo_read_manifest;
 mod cargo_remove;
 mod cargo_report;
+mod cargo_resolve;
 mod cargo_run;
 mod cargo_rustc;
 mod cargo_rustdoc;
@@ -61,8 +70,10 @@ mod cargo_tree;
 mod cargo_uninstall;
 mod cargo_update;
 mod cargo_vendor;
+mod cargo_verify_lockfile;
 mod cargo_verify_project;
 mod cargo_version;
+mod cargo_workspace;
 mod cargo_yank;
 mod cfg;
 mod check;
@@ -84,9 +95,14 @@ mod diagnostics;
 mod direct_minimal_versions;
 mod directory;
 mod doc;
+mod doc_merged_index;
+mod doc_serve;
 mod docscrape;
 mod edition;
 mod error;
+mod external_executor;
+mod feature_presets;
+mod feature_unification;
 mod features;
 mod features2;
 mod features_namespaced;
@@ -98,16 +114,23 @@ mod future_incompat_report;
 mod generate_lockfile;
 mod git;
 mod git_auth;
+mod git_checkout_options;
 mod git_gc;
+mod git_ref_globs;
 mod git_shallow;
 mod glob_targets;
 mod global_cache_tracker;
+mod group_diagnostics;
 mod help;
 mod https;
+mod index_snapshot;
 mod inheritable_workspace_fields;
 mod install;
+mod install_prebuilt;
 mod install_upgrade;
 mod jobserver;
+mod link_inputs;
+mod links_libs;
 mod lints;
 mod lints_table;
 mod list_availables;
@@ -117,6 +140,7 @@ mod lockfile_compat;
 mod login;
 mod logout;
 mod lto;
+mod manifest_cache;
 mod member_discovery;
 mod member_errors;
 mod message_format;
@@ -125,10 +149,13 @@ mod metabuild;
 mod metadata;
 mod minimal_versions;
 mod multitarget;
+mod native_clippy;
+mod nested_workspaces;
 mod net_config;
 mod new;
 mod offline;
 mod old_cargos;
+mod only_deps;
 mod open_namespaces;
 mod owner;
 mod package;
@@ -137,7 +164,9 @@ mod patch;
 mod path;
 mod paths;
 mod pkgid;
+mod post_build;
 mod precise_pre_release;
+mod print_env;
 mod proc_macro;
 mod profile_config;
 mod profile_custom;
@@ -153,10 +182,13 @@ mod read_manifest;
 mod registry;
 mod registry_auth;
 mod registry_overlay;
+mod registry_remap;
 mod rename_deps;
 mod replace;
 mod required_features;
 mod run;
+mod run_interactive;
+mod runner_composition;
 mod rust_version;
 mod rustc;
 mod rustc_info_cache;
@@ -168,23 +200,34 @@ mod rustup;
 mod script;
 mod search;
 mod shell_quoting;
+mod source_policy;
 mod source_replacement;
+mod split_target_dirs;
 mod ssh;
 mod standard_lib;
+mod status_format;
+mod target_alias;
 mod test;
+mod test_events;
+mod test_manifest;
+mod test_partition;
 mod timings;
 mod tool_paths;
 mod tree;
 mod tree_graph_features;
 mod unit_graph;
 mod update;
+mod validate_config;
 mod vendor;
 mod verify_project;
 mod version;
 mod warn_on_failure;
+mod watch;
 mod weak_dep_features;
+mod workspace_patch;
 mod workspaces;
 mod yank;
+mod yanked_policy;
 
 #[cargo_test]
 fn aaa_trigger_cross_compile_disabled_check() {
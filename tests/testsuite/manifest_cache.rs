@@ -0,0 +1,157 @@
+//! Tests for reusing parsed manifests with `GlobalContext::enable_manifest_cache`.
+
+use cargo::core::{Shell, Workspace};
+use cargo::util::context::GlobalContext;
+
+use cargo_test_support::install::cargo_home;
+use cargo_test_support::{basic_lib_manifest, project};
+
+/// The address of the contents of a member's manifest, which is the same
+/// for every load that reused the parsed manifest.
+fn contents_ptr(ws: &Workspace<'_>, name: &str) -> *const u8 {
+    ws.members()
+        .find(|pkg| pkg.name() == name)
+        .unwrap()
+        .manifest()
+        .contents()
+        .as_ptr()
+}
+
+#[cargo_test]
+fn reuse_until_changed() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+
+                [workspace.package]
+                version = "1.0.0"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version.workspace = true
+                edition = "2015"
+            "#,
+        )
+        .file("a/src/lib.rs", "")
+        .file("b/Cargo.toml", &basic_lib_manifest("b"))
+        .file("b/src/lib.rs", "")
+        .build();
+
+    let mut gctx = GlobalContext::new(
+        Shell::from_write(Box::new(Vec::new())),
+        p.root(),
+        cargo_home(),
+    );
+    gctx.enable_manifest_cache();
+    let cache = gctx.manifest_cache().unwrap();
+    let manifest = p.root().join("Cargo.toml");
+
+    let ws = Workspace::new(&manifest, &gctx).unwrap();
+    let (a, b) = (contents_ptr(&ws, "a"), contents_ptr(&ws, "b"));
+    assert_eq!(cache.len(), 3);
+    assert!(cache.changed_manifests().is_empty());
+
+    // Nothing changed, so every manifest is reused.
+    let ws = Workspace::new(&manifest, &gctx).unwrap();
+    assert_eq!(contents_ptr(&ws, "a"), a);
+    assert_eq!(contents_ptr(&ws, "b"), b);
+
+    // `a` inherits from the root, so it's parsed again along with it.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [workspace]
+            members = ["a", "b"]
+
+            [workspace.package]
+            version = "2.0.0"
+        "#,
+    );
+    assert_eq!(
+        cache.changed_manifests(),
+        [p.root().join("Cargo.toml"), p.root().join("a/Cargo.toml")]
+    );
+    let ws = Workspace::new(&manifest, &gctx).unwrap();
+    let pkg_a = ws.members().find(|pkg| pkg.name() == "a").unwrap();
+    assert_eq!(pkg_a.version().to_string(), "2.0.0");
+    assert_ne!(contents_ptr(&ws, "a"), a);
+    assert_eq!(contents_ptr(&ws, "b"), b);
+    assert!(cache.changed_manifests().is_empty());
+
+    // New targets are discovered.
+    p.change_file("b/src/bin/extra.rs", "fn main() {}");
+    assert_eq!(cache.changed_manifests(), [p.root().join("b/Cargo.toml")]);
+    let ws = Workspace::new(&manifest, &gctx).unwrap();
+    let pkg_b = ws.members().find(|pkg| pkg.name() == "b").unwrap();
+    assert!(pkg_b.targets().iter().any(|t| t.name() == "extra"));
+}
+
+#[cargo_test]
+fn disabled_by_default() {
+    let p = project()
+        .file("Cargo.toml", &basic_lib_manifest("foo"))
+        .file("src/lib.rs", "")
+        .build();
+
+    let gctx = GlobalContext::new(
+        Shell::from_write(Box::new(Vec::new())),
+        p.root(),
+        cargo_home(),
+    );
+    Workspace::new(&p.root().join("Cargo.toml"), &gctx).unwrap();
+    assert!(gctx.manifest_cache().is_none());
+}
+
+#[cargo_test]
+fn lints_profile_change() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lints-profiles"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [lints.rust]
+                unsafe_code = "warn"
+
+                [lints.profile.ci.rust]
+                unsafe_code = "deny"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let mut gctx = GlobalContext::new(
+        Shell::from_write(Box::new(Vec::new())),
+        p.root(),
+        cargo_home(),
+    );
+    gctx.nightly_features_allowed = true;
+    gctx.enable_manifest_cache();
+    let manifest = p.root().join("Cargo.toml");
+    let lint_rustflags = |gctx: &GlobalContext| {
+        let ws = Workspace::new(&manifest, gctx).unwrap();
+        ws.current().unwrap().manifest().lint_rustflags().to_vec()
+    };
+
+    assert_eq!(lint_rustflags(&gctx), ["--warn=unsafe_code"]);
+    assert!(!gctx.manifest_cache().unwrap().is_empty());
+
+    // The lints of the profile are merged over the default ones.
+    gctx.set_lints_profile(Some("ci".to_string()));
+    assert_eq!(lint_rustflags(&gctx), ["--deny=unsafe_code"]);
+
+    gctx.set_lints_profile(None);
+    assert_eq!(lint_rustflags(&gctx), ["--warn=unsafe_code"]);
+}
@@ -1,5 +1,6 @@
 //! Tests for --message-format flag.
 
+use cargo_test_support::prelude::*;
 use cargo_test_support::{basic_lib_manifest, basic_manifest, project, str};
 
 #[cargo_test]
@@ -148,6 +149,259 @@ failures:
 test result: FAILED. 0 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in [ELAPSED]s
 
 
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn sarif() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let x = 1; }")
+        .file("src/main.rs", "fn main() { let x: u32 = \"\"; }")
+        .build();
+
+    p.cargo("check -Zunstable-options --message-format=sarif")
+        .masquerade_as_nightly_cargo(&["sarif"])
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "runs": [
+    {
+      "originalUriBaseIds": {
+        "%SRCROOT%": {
+          "uri": "[ROOTURL]/foo/"
+        }
+      },
+      "results": [
+        {
+          "level": "warning",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/lib.rs",
+                  "uriBaseId": "%SRCROOT%"
+                },
+                "region": {
+                  "endColumn": 19,
+                  "endLine": 1,
+                  "startColumn": 18,
+                  "startLine": 1
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "unused variable: `x`"
+          },
+          "ruleId": "unused_variables"
+        },
+        {
+          "level": "error",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/main.rs",
+                  "uriBaseId": "%SRCROOT%"
+                },
+                "region": {
+                  "endColumn": 28,
+                  "endLine": 1,
+                  "startColumn": 26,
+                  "startLine": 1
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "mismatched types"
+          },
+          "ruleId": "E0308"
+        }
+      ],
+      "tool": {
+        "driver": {
+          "informationUri": "https://www.rust-lang.org/",
+          "name": "rustc",
+          "rules": [
+            {
+              "helpUri": "https://doc.rust-lang.org/error_codes/E0308.html",
+              "id": "E0308"
+            },
+            {
+              "id": "unused_variables"
+            }
+          ]
+        }
+      }
+    }
+  ],
+  "version": "2.1.0"
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[WARNING] unused variable: `x`
+ --> src/lib.rs:1:18
+  |
+1 | pub fn f() { let x = 1; }
+...
+
+[WARNING] `foo` (lib) generated 1 warning (run `cargo fix --lib -p foo` to apply 1 suggestion)
+error[E0308]: mismatched types
+ --> src/main.rs:1:26
+  |
+1 | fn main() { let x: u32 = ""; }
+  |                    ---   ^^ expected `u32`, found `&str`
+  |                    |
+  |                    expected due to this
+
+For more information about this error, try `rustc --explain E0308`.
+[ERROR] could not compile `foo` (bin "foo") due to 1 previous error
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn sarif_replays_cached_diagnostics() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let x = 1; }")
+        .build();
+
+    p.cargo("check --lib --tests -Zunstable-options --message-format=sarif")
+        .masquerade_as_nightly_cargo(&["sarif"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "runs": [
+    {
+      "originalUriBaseIds": {
+        "%SRCROOT%": {
+          "uri": "[ROOTURL]/foo/"
+        }
+      },
+      "results": [
+        {
+          "level": "warning",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/lib.rs",
+                  "uriBaseId": "%SRCROOT%"
+                },
+                "region": {
+                  "endColumn": 19,
+                  "endLine": 1,
+                  "startColumn": 18,
+                  "startLine": 1
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "unused variable: `x`"
+          },
+          "ruleId": "unused_variables"
+        }
+      ],
+      "tool": {
+        "driver": {
+          "informationUri": "https://www.rust-lang.org/",
+          "name": "rustc",
+          "rules": [
+            {
+              "id": "unused_variables"
+            }
+          ]
+        }
+      }
+    }
+  ],
+  "version": "2.1.0"
+}
+"#]]
+            .json(),
+        )
+        .run();
+
+    // The warning of the fresh units is still reported, once.
+    p.cargo("check --lib --tests -Zunstable-options --message-format=sarif")
+        .masquerade_as_nightly_cargo(&["sarif"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "runs": [
+    {
+      "originalUriBaseIds": {
+        "%SRCROOT%": {
+          "uri": "[ROOTURL]/foo/"
+        }
+      },
+      "results": [
+        {
+          "level": "warning",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/lib.rs",
+                  "uriBaseId": "%SRCROOT%"
+                },
+                "region": {
+                  "endColumn": 19,
+                  "endLine": 1,
+                  "startColumn": 18,
+                  "startLine": 1
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "unused variable: `x`"
+          },
+          "ruleId": "unused_variables"
+        }
+      ],
+      "tool": {
+        "driver": {
+          "informationUri": "https://www.rust-lang.org/",
+          "name": "rustc",
+          "rules": [
+            {
+              "id": "unused_variables"
+            }
+          ]
+        }
+      }
+    }
+  ],
+  "version": "2.1.0"
+}
+"#]]
+            .json(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn sarif_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check --message-format=sarif")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--message-format=sarif` flag is unstable, pass `-Z unstable-options` to enable it
+
 "#]])
         .run();
 }
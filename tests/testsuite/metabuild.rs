@@ -476,11 +476,15 @@ fn metabuild_build_plan() {
         .with_json(
             r#"
             {
+                "version": 2,
                 "invocations": [
                     {
+                        "package_id": "[..]",
                         "package_name": "mb",
                         "package_version": "0.5.0",
+                        "target_name": "[..]",
                         "target_kind": ["lib"],
+                        "target_src_path": "[..]/mb/src/lib.rs",
                         "compile_mode": "build",
                         "kind": null,
                         "deps": [],
@@ -495,9 +499,12 @@ fn metabuild_build_plan() {
                         "cwd": "[..]"
                     },
                     {
+                        "package_id": "[..]",
                         "package_name": "mb-other",
                         "package_version": "0.0.1",
+                        "target_name": "[..]",
                         "target_kind": ["lib"],
+                        "target_src_path": "[..]/mb-other/src/lib.rs",
                         "compile_mode": "build",
                         "kind": null,
                         "deps": [],
@@ -512,9 +519,12 @@ fn metabuild_build_plan() {
                         "cwd": "[..]"
                     },
                     {
+                        "package_id": "[..]",
                         "package_name": "foo",
                         "package_version": "0.0.1",
+                        "target_name": "[..]",
                         "target_kind": ["custom-build"],
+                        "target_src_path": null,
                         "compile_mode": "build",
                         "kind": null,
                         "deps": [0, 1],
@@ -526,9 +536,12 @@ fn metabuild_build_plan() {
                         "cwd": "[..]"
                     },
                     {
+                        "package_id": "[..]",
                         "package_name": "foo",
                         "package_version": "0.0.1",
+                        "target_name": "[..]",
                         "target_kind": ["custom-build"],
+                        "target_src_path": null,
                         "compile_mode": "run-custom-build",
                         "kind": null,
                         "deps": [2],
@@ -540,9 +553,12 @@ fn metabuild_build_plan() {
                         "cwd": "[..]"
                     },
                     {
+                        "package_id": "[..]",
                         "package_name": "foo",
                         "package_version": "0.0.1",
+                        "target_name": "[..]",
                         "target_kind": ["lib"],
+                        "target_src_path": "[..]/foo/src/lib.rs",
                         "compile_mode": "build",
                         "kind": null,
                         "deps": [3],
@@ -558,6 +574,7 @@ fn metabuild_build_plan() {
                     }
                 ],
                 "inputs": [
+                    "[..]/foo/Cargo.lock",
                     "[..]/foo/Cargo.toml",
                     "[..]/foo/mb/Cargo.toml",
                     "[..]/foo/mb-other/Cargo.toml"
@@ -717,6 +734,7 @@ fn metabuild_json_artifact() {
               "fresh": false,
               "package_id": "path+file:///[..]/foo#0.0.1",
               "manifest_path": "[..]",
+              "platform": null,
               "profile": "{...}",
               "reason": "compiler-artifact",
               "target": {
@@ -4,8 +4,12 @@
 
 use cargo_test_support::install::cargo_home;
 use cargo_test_support::paths::CargoPathExt;
+use cargo_test_support::prelude::*;
 use cargo_test_support::registry::Package;
-use cargo_test_support::{basic_bin_manifest, basic_lib_manifest, main_file, project, rustc_host};
+use cargo_test_support::{
+    basic_bin_manifest, basic_lib_manifest, main_file, project, rustc_host, str,
+};
+use cargo_util_schemas::metadata::Metadata;
 use serde_json::json;
 
 #[cargo_test]
@@ -2083,12 +2087,12 @@ fn cargo_metadata_bad_version() {
         .file("src/foo.rs", &main_file(r#""i am foo""#, &[]))
         .build();
 
-    p.cargo("metadata --no-deps --format-version 2")
+    p.cargo("metadata --no-deps --format-version 3")
         .with_status(1)
         .with_stderr_contains(
             "\
-error: invalid value '2' for '--format-version <VERSION>'
-  [possible values: 1]
+error: invalid value '3' for '--format-version <VERSION>'
+  [possible values: 1, 2]
 ",
         )
         .run();
@@ -4654,3 +4658,313 @@ local-time = 1979-05-27
         )
         .run();
 }
+
+#[cargo_test]
+fn include_feature_graph() {
+    Package::new("optdep", "1.0.0").publish();
+    Package::new("bar", "1.0.0")
+        .feature("default", &["std"])
+        .feature("std", &[])
+        .feature("serde", &[])
+        .feature("unused", &[])
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = { version = "1.0", features = ["serde"] }
+                optdep = { version = "1.0", optional = true }
+
+                [features]
+                default = ["extra"]
+                extra = ["dep:optdep", "bar/std"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("metadata --format-version 1 --include-feature-graph")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--include-feature-graph` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    let metadata = p
+        .cargo("metadata --format-version 1 --include-feature-graph -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .run_json();
+    let foo = metadata["resolve"]["root"].as_str().unwrap();
+    let bar = "registry+https://github.com/rust-lang/crates.io-index#bar@1.0.0";
+    let optdep = "registry+https://github.com/rust-lang/crates.io-index#optdep@1.0.0";
+    let node = |pkg: &str, feature: Option<&str>| json!({"pkg": pkg, "feature": feature});
+    let edge = |from: Option<serde_json::Value>, to| json!({"from": from, "to": to});
+    assert_eq!(
+        metadata["feature_graph"],
+        json!({
+            "edges": [
+                edge(None, node(foo, Some("default"))),
+                edge(Some(node(bar, Some("default"))), node(bar, Some("std"))),
+                edge(Some(node(foo, None)), node(bar, Some("default"))),
+                edge(Some(node(foo, None)), node(bar, Some("serde"))),
+                edge(Some(node(foo, Some("default"))), node(foo, Some("extra"))),
+                edge(Some(node(foo, Some("extra"))), node(bar, Some("std"))),
+                edge(Some(node(foo, Some("extra"))), node(optdep, None)),
+            ]
+        })
+    );
+}
+
+#[cargo_test]
+fn include_feature_graph_conflicts_with_no_deps() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("metadata --no-deps --include-feature-graph")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--no-deps' cannot be used with '--include-feature-graph'
+
+Usage: cargo[EXE] metadata --no-deps
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn only_sections() {
+    Package::new("missing", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                missing = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    // The dependency can't be resolved, but nothing needs resolving.
+    p.cargo(
+        "metadata --format-version 1 --only workspace-members,workspace-root -Zunstable-options",
+    )
+    .masquerade_as_nightly_cargo(&["metadata --only"])
+    .with_stdout_data(
+        str![[r#"
+{
+  "version": 1,
+  "workspace_members": [
+    "path+[ROOTURL]/foo#0.1.0"
+  ],
+  "workspace_root": "[ROOT]/foo"
+}
+"#]]
+        .json(),
+    )
+    .with_stderr_data("")
+    .run();
+
+    let metadata = p
+        .cargo("metadata --format-version 1 --no-deps --only packages --only metadata -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["metadata --only"])
+        .run_json();
+    let keys: Vec<_> = metadata.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["metadata", "packages", "version"]);
+    assert_eq!(metadata["packages"][0]["name"], "foo");
+    assert_eq!(metadata["metadata"], json!(null));
+
+    p.cargo("metadata --format-version 1 --only resolve -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["metadata --only"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] failed to select a version for the requirement `missing = "^1.0"`
+candidate versions found which didn't match: 0.1.0
+location searched: `dummy-registry` index (which is replacing registry `crates-io`)
+required by package `foo v0.1.0 ([ROOT]/foo)`
+perhaps a crate was updated and forgotten to be re-vendored?
+
+"#]])
+        .run();
+
+    p.cargo("metadata --format-version 1 --only members")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid value 'members' for '--only <SECTIONS>'
+  [possible values: packages, workspace-members, workspace-default-members, resolve, target-directory, workspace-root, metadata]
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn no_feature_resolution() {
+    Package::new("baz", "1.0.0").publish();
+    Package::new("bar", "1.0.0").dep("baz", "1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let metadata = p
+        .cargo("metadata --format-version 1 --no-feature-resolution -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["metadata --no-feature-resolution"])
+        .run_json();
+    assert_eq!(metadata["resolve"], json!(null));
+    let names: Vec<_> = metadata["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|pkg| pkg["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["bar", "baz", "foo"]);
+
+    p.cargo(
+        "metadata --format-version 1 --no-feature-resolution --only resolve -Zunstable-options",
+    )
+    .masquerade_as_nightly_cargo(&["metadata --no-feature-resolution"])
+    .with_status(101)
+    .with_stderr_data(str![[r#"
+[ERROR] `--only resolve` can't be used with `--no-feature-resolution`
+
+"#]])
+    .run();
+
+    p.cargo("metadata --format-version 1 --no-feature-resolution --filter-platform x86_64-unknown-linux-gnu")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--no-feature-resolution' cannot be used with '--filter-platform <TRIPLE>'
+
+Usage: cargo metadata --format-version <VERSION> --no-feature-resolution
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn only_and_no_feature_resolution_are_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("metadata --format-version 1 --only packages")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--only` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+    p.cargo("metadata --format-version 1 --only packages")
+        .masquerade_as_nightly_cargo(&["metadata --only"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--only` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+    p.cargo("metadata --format-version 1 --no-feature-resolution")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--no-feature-resolution` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn format_version_2() {
+    Package::new("bar", "1.0.0").feature("std", &[]).publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                rust-version = "1.70"
+
+                [dependencies]
+                bar = { version = "1.0", features = ["std"] }
+
+                [lints.rust]
+                unsafe_code = "forbid"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let output = p
+        .cargo("metadata --format-version 2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["metadata --format-version 2"])
+        .run_json();
+    let metadata: Metadata = serde_json::from_value(output).unwrap();
+    assert_eq!(metadata.version, 2);
+    let foo = metadata.packages.iter().find(|p| p.name == "foo").unwrap();
+    assert_eq!(foo.rust_version.as_ref().unwrap().to_string(), "1.70");
+    assert_eq!(
+        serde_json::to_value(&foo.lints).unwrap(),
+        json!({"rust": {"unsafe_code": "forbid"}})
+    );
+    let bar = metadata.packages.iter().find(|p| p.name == "bar").unwrap();
+    assert_eq!(serde_json::to_value(&bar.lints).unwrap(), json!({}));
+    // The feature graph is output without `--include-feature-graph`.
+    let edges = metadata.feature_graph.unwrap().edges;
+    assert!(edges
+        .iter()
+        .any(|edge| edge.to.feature.as_deref() == Some("std")));
+    assert!(metadata.resolve.is_some());
+
+    // Format version 1 deserializes into the same types, without the fields
+    // added by version 2.
+    let output = p.cargo("metadata --format-version 1").run_json();
+    let metadata: Metadata = serde_json::from_value(output).unwrap();
+    assert_eq!(metadata.version, 1);
+    assert!(metadata.feature_graph.is_none());
+    assert!(metadata.packages.iter().all(|p| p.lints.is_none()));
+    assert_eq!(metadata.workspace_members.len(), 1);
+}
+
+#[cargo_test]
+fn format_version_2_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("metadata --format-version 2")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--format-version 2` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
@@ -43,3 +43,82 @@ fn minimal_version_cli() {
 
     assert!(!lock.contains("1.1.0"));
 }
+
+#[cargo_test]
+fn minimal_versions_flag() {
+    Package::new("indirect", "1.0.0").publish();
+    Package::new("indirect", "1.1.0").publish();
+    Package::new("dep", "1.0.0")
+        .dep("indirect", "1.0")
+        .publish();
+    Package::new("dep", "1.1.0")
+        .dep("indirect", "1.0")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                authors = []
+                version = "0.0.1"
+
+                [dependencies]
+                dep = "1.0"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("generate-lockfile --minimal-versions")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to earliest compatible versions
+[ADDING] dep v1.0.0 (latest: v1.1.0)
+[ADDING] indirect v1.0.0 (latest: v1.1.0)
+
+"#]])
+        .run();
+
+    let lock = p.read_lockfile();
+    assert!(!lock.contains("1.1.0"));
+
+    // Without the flag, a plain update moves back to the latest versions.
+    p.cargo("update")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[UPDATING] dep v1.0.0 -> v1.1.0
+[UPDATING] indirect v1.0.0 -> v1.1.0
+
+"#]])
+        .run();
+
+    p.cargo("update --minimal-versions")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to earliest compatible versions
+[DOWNGRADING] dep v1.1.0 -> v1.0.0 (latest: v1.1.0)
+[DOWNGRADING] indirect v1.1.0 -> v1.0.0 (latest: v1.1.0)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn minimal_versions_conflicts_with_direct() {
+    let p = project().file("src/main.rs", "fn main() {}").build();
+
+    p.cargo("generate-lockfile --minimal-versions --direct-minimal-versions")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--minimal-versions' cannot be used with '--direct-minimal-versions'
+
+Usage: cargo generate-lockfile --minimal-versions
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
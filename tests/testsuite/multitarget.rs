@@ -54,6 +54,53 @@ fn simple_build_with_config() {
     assert!(p.target_bin(t2, "foo").is_file());
 }
 
+#[cargo_test]
+fn json_reports_platform() {
+    let target = rustc_host();
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "1.0.0"))
+        .file("src/main.rs", "fn main() {}")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    // Build scripts are built for the host, without a platform.
+    let artifact = |kind: &str, platform: &str| {
+        format!(
+            r#"
+                {{
+                    "reason": "compiler-artifact",
+                    "package_id": "{{...}}",
+                    "manifest_path": "{{...}}",
+                    "target": {{
+                        "kind": ["{kind}"],
+                        "crate_types": ["bin"],
+                        "doc": "{{...}}",
+                        "doctest": false,
+                        "edition": "2015",
+                        "name": "{{...}}",
+                        "src_path": "{{...}}",
+                        "test": "{{...}}"
+                    }},
+                    "platform": {platform},
+                    "profile": "{{...}}",
+                    "features": [],
+                    "filenames": "{{...}}",
+                    "executable": "{{...}}",
+                    "fresh": false
+                }}
+            "#
+        )
+    };
+    p.cargo("build --message-format=json --target")
+        .arg(&target)
+        .with_json_contains_unordered(&format!(
+            "{}\n{}",
+            artifact("custom-build", "null"),
+            artifact("bin", &format!("\"{target}\"")),
+        ))
+        .run();
+}
+
 #[cargo_test]
 fn simple_test() {
     if !cross_compile::can_run_on_host() {
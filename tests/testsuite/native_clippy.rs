@@ -0,0 +1,147 @@
+//! Tests for `-Znative-clippy`.
+
+use cargo_test_support::{basic_manifest, project, str, tools};
+
+#[cargo_test]
+fn runs_clippy_driver() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("-Znative-clippy clippy -v -- -D warnings")
+        .masquerade_as_nightly_cargo(&["native-clippy"])
+        .env("CLIPPY_DRIVER", tools::wrapped_clippy_driver())
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `[..]clippy-driver[EXE] rustc --crate-name foo [..] -D warnings`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn separate_from_check() {
+    let p = project().file("src/lib.rs", "").build();
+    let clippy = || {
+        let mut clippy = p.cargo("-Znative-clippy clippy");
+        clippy
+            .masquerade_as_nightly_cargo(&["native-clippy"])
+            .env("CLIPPY_DRIVER", tools::wrapped_clippy_driver());
+        clippy
+    };
+
+    p.cargo("check").run();
+    clippy()
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    // Neither invalidates the other.
+    p.cargo("check -v")
+        .with_stderr_data(str![[r#"
+[FRESH] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    clippy()
+        .arg("-v")
+        .with_stderr_data(str![[r#"
+[FRESH] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // Changing the arguments of clippy lints again.
+    clippy()
+        .args(&["--", "-W", "clippy::pedantic"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn no_deps() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = { path = "../bar" }
+            "#,
+        )
+        .file("foo/src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("-Znative-clippy clippy -p foo --no-deps -v")
+        .masquerade_as_nightly_cargo(&["native-clippy"])
+        .env("CLIPPY_DRIVER", tools::wrapped_clippy_driver())
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] bar v0.1.0 ([ROOT]/foo/bar)
+[RUNNING] `rustc --crate-name bar [..]`
+[CHECKING] foo v0.1.0 ([ROOT]/foo/foo)
+[RUNNING] `[..]clippy-driver[EXE] rustc --crate-name foo [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn fix() {
+    let p = project()
+        .file("src/lib.rs", "pub fn foo() -> u32 { let mut x = 1; x }")
+        .build();
+
+    p.cargo("-Znative-clippy clippy --fix --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["native-clippy"])
+        .env("CLIPPY_DRIVER", tools::wrapped_clippy_driver())
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FIXED] src/lib.rs (1 fix)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 1;"));
+}
+
+#[cargo_test]
+fn fix_flags_require_fix() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("-Znative-clippy clippy --allow-dirty")
+        .masquerade_as_nightly_cargo(&["native-clippy"])
+        .env("CLIPPY_DRIVER", tools::wrapped_clippy_driver())
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the following required arguments were not provided:
+  --fix
+
+Usage: cargo clippy --fix --allow-dirty [-- [ARGS]...]
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
@@ -0,0 +1,183 @@
+//! Tests for `-Znested-workspaces`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{basic_manifest, project, str, Project};
+
+fn outer_with_inner(members: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [workspace]
+                    members = {members}
+                "#
+            ),
+        )
+        .file(
+            "crates/app/Cargo.toml",
+            r#"
+                [package]
+                name = "app"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                a = { path = "../inner/a" }
+            "#,
+        )
+        .file("crates/app/src/lib.rs", "extern crate a;")
+        .file(
+            "crates/inner/Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+            "#,
+        )
+        .file("crates/inner/a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("crates/inner/a/src/lib.rs", "")
+        .file("crates/inner/b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("crates/inner/b/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = outer_with_inner(r#"["crates/*"]"#);
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] multiple workspace roots found in the same workspace:
+  [ROOT]/foo/crates/inner
+  [ROOT]/foo
+
+"#]])
+        .run();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] multiple workspace roots found in the same workspace:
+  [ROOT]/foo/crates/inner
+  [ROOT]/foo
+
+[HELP] pass `-Znested-workspaces` to keep nested workspaces separate
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn nested_workspace_is_member_group() {
+    let p = outer_with_inner(r#"["crates/app", "crates/inner"]"#);
+
+    // The members of the nested workspace join the outer one as a group,
+    // resolved in its lockfile and built in its target directory.
+    p.cargo("check -Znested-workspaces")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 3 packages to latest compatible versions
+[CHECKING] a v0.1.0 ([ROOT]/foo/crates/inner/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/crates/inner/b)
+[CHECKING] app v0.1.0 ([ROOT]/foo/crates/app)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(p.root().join("Cargo.lock").is_file());
+    assert!(!p.root().join("crates/inner/Cargo.lock").exists());
+    assert!(!p.root().join("crates/inner/target").exists());
+
+    p.cargo("tree --workspace --depth 0 -Znested-workspaces")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stdout_data(
+            str![[r#"
+a v0.1.0 ([ROOT]/foo/crates/inner/a)
+
+app v0.1.0 ([ROOT]/foo/crates/app)
+
+b v0.1.0 ([ROOT]/foo/crates/inner/b)
+
+"#]]
+            .unordered(),
+        )
+        .run();
+
+    // The nested workspace still resolves and builds on its own.
+    p.cargo("check --workspace -Znested-workspaces")
+        .cwd("crates/inner")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] a v0.1.0 ([ROOT]/foo/crates/inner/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/crates/inner/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(p.root().join("crates/inner/Cargo.lock").is_file());
+    assert!(p.root().join("crates/inner/target").is_dir());
+
+    // From a nested member, the nearest workspace is used.
+    p.cargo("check -Znested-workspaces")
+        .cwd("crates/inner/b")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn nested_workspace_in_glob() {
+    let p = outer_with_inner(r#"["crates/*"]"#);
+
+    p.cargo("check -Znested-workspaces")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 3 packages to latest compatible versions
+[CHECKING] a v0.1.0 ([ROOT]/foo/crates/inner/a)
+[CHECKING] b v0.1.0 ([ROOT]/foo/crates/inner/b)
+[CHECKING] app v0.1.0 ([ROOT]/foo/crates/app)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn path_dependency_into_nested_workspace() {
+    let p = outer_with_inner(r#"["crates/app"]"#);
+
+    p.cargo("check --workspace -Znested-workspaces")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] a v0.1.0 ([ROOT]/foo/crates/inner/a)
+[CHECKING] app v0.1.0 ([ROOT]/foo/crates/app)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // `a` isn't a member of the outer workspace.
+    p.cargo("tree --workspace --depth 0 -Znested-workspaces")
+        .masquerade_as_nightly_cargo(&["nested-workspaces"])
+        .with_stdout_data(str![[r#"
+app v0.1.0 ([ROOT]/foo/crates/app)
+
+"#]])
+        .run();
+}
@@ -3,7 +3,12 @@
 #![allow(deprecated)]
 
 use cargo_test_support::cargo_process;
+use cargo_test_support::compare::assert_e2e;
+use cargo_test_support::git;
 use cargo_test_support::paths;
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+use cargo_test_support::str;
 use std::env;
 use std::fs::{self, File};
 
@@ -605,3 +610,315 @@ It is recommended to use a different name to avoid problems.
         )
         .run();
 }
+
+const TEMPLATE_MANIFEST: &str = r#"
+[template]
+path = "template"
+
+[template.variables.license]
+prompt = "License of the package"
+default = "MIT OR Apache-2.0"
+"#;
+
+const TEMPLATE_CARGO_TOML: &str = r#"[package]
+name = "{{ name }}"
+version = "0.1.0"
+edition = "{{edition}}"
+license = "{{ license }}"
+"#;
+
+const TEMPLATE_MAIN_RS: &str = r#"fn main() {
+    println!("{{x}} {}", {{crate_name}}::VERSION);
+}
+"#;
+
+#[cargo_test]
+fn template_from_path() {
+    project()
+        .at("tmpl")
+        .file("cargo-template.toml", TEMPLATE_MANIFEST)
+        .file("template/Cargo.toml", TEMPLATE_CARGO_TOML)
+        .file("template/src/main.rs", TEMPLATE_MAIN_RS)
+        .file("template/src/{{name}}.txt", "{{ missing }}")
+        .build();
+
+    cargo_process("new my-app --vcs none --template tmpl --define license=MIT -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_stderr_data(str![[r#"
+[CREATING] `my-app` package from template `tmpl`
+[NOTE] see more `Cargo.toml` keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+"#]])
+        .run();
+
+    let root = paths::root().join("my-app");
+    assert_e2e().eq(
+        fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+        str![[r#"
+[package]
+name = "my-app"
+version = "0.1.0"
+edition = "2021"
+license = "MIT"
+
+"#]],
+    );
+    assert_e2e().eq(
+        fs::read_to_string(root.join("src/main.rs")).unwrap(),
+        str![[r#"
+fn main() {
+    println!("{{x}} {}", my_app::VERSION);
+}
+
+"#]],
+    );
+    assert_e2e().eq(
+        fs::read_to_string(root.join("src/my-app.txt")).unwrap(),
+        str!["{{ missing }}"],
+    );
+    assert!(!root.join("cargo-template.toml").exists());
+}
+
+#[cargo_test]
+fn template_from_git() {
+    let repo = git::new("tmpl", |p| {
+        p.file("cargo-template.toml", TEMPLATE_MANIFEST)
+            .file("template/Cargo.toml", TEMPLATE_CARGO_TOML)
+            .file("template/src/main.rs", TEMPLATE_MAIN_RS)
+    });
+
+    cargo_process(&format!(
+        "new foo --vcs none --edition 2018 --template {} -Zunstable-options",
+        repo.url()
+    ))
+    .masquerade_as_nightly_cargo(&["new-template"])
+    .with_stderr_data(str![[r#"
+[CREATING] `foo` package from template `[ROOTURL]/tmpl`
+[UPDATING] git repository `[ROOTURL]/tmpl`
+[NOTE] see more `Cargo.toml` keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+"#]])
+    .run();
+
+    assert_e2e().eq(
+        fs::read_to_string(paths::root().join("foo/Cargo.toml")).unwrap(),
+        str![[r#"
+[package]
+name = "foo"
+version = "0.1.0"
+edition = "2018"
+license = "MIT OR Apache-2.0"
+
+"#]],
+    );
+}
+
+#[cargo_test]
+fn template_from_registry() {
+    Package::new("tmpl", "1.0.0")
+        .file("src/lib.rs", "")
+        .file("cargo-template.toml", TEMPLATE_MANIFEST)
+        .file("template/Cargo.toml.tmpl", TEMPLATE_CARGO_TOML)
+        .file("template/src/main.rs", TEMPLATE_MAIN_RS)
+        .publish();
+
+    cargo_process("new foo --vcs none --template tmpl@1 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_stderr_data(str![[r#"
+[CREATING] `foo` package from template `tmpl@1`
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] tmpl v1.0.0 (registry `dummy-registry`)
+[NOTE] see more `Cargo.toml` keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+"#]])
+        .run();
+
+    let root = paths::root().join("foo");
+    assert!(root.join("Cargo.toml").is_file());
+    assert!(!root.join("Cargo.toml.tmpl").exists());
+    assert!(root.join("src/main.rs").is_file());
+}
+
+#[cargo_test]
+fn template_without_value_for_variable() {
+    project()
+        .at("tmpl")
+        .file(
+            "cargo-template.toml",
+            r#"
+                [template.variables.author]
+                prompt = "Author"
+            "#,
+        )
+        .file("Cargo.toml", "[package]\nname = \"{{name}}\"\n")
+        .build();
+
+    cargo_process("new foo --vcs none --template tmpl -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_stdin("")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CREATING] `foo` package from template `tmpl`
+[ERROR] no value for the template variable `author`
+
+pass `--define author=<VALUE>` to set it
+
+"#]])
+        .run();
+
+    cargo_process("new foo --vcs none --template tmpl --define email=a -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CREATING] `foo` package from template `tmpl`
+[ERROR] template `tmpl` has no variable `email`
+
+"#]])
+        .run();
+
+    assert!(!paths::root().join("foo").exists());
+}
+
+#[cargo_test]
+fn template_path_outside_of_template() {
+    project()
+        .at("tmpl")
+        .file("cargo-template.toml", "[template]\npath = \"..\"\n")
+        .build();
+
+    cargo_process("new foo --vcs none --template tmpl -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CREATING] `foo` package from template `tmpl`
+[ERROR] `template.path` of template `tmpl` is outside of the template: `[ROOT]`
+
+"#]])
+        .run();
+
+    assert!(!paths::root().join("foo").exists());
+}
+
+#[cargo_test]
+fn template_file_outside_of_package() {
+    project()
+        .at("tmpl")
+        .file(
+            "cargo-template.toml",
+            r#"
+                [template.variables.dir]
+            "#,
+        )
+        .file("Cargo.toml", TEMPLATE_CARGO_TOML)
+        .file("{{dir}}/evil.txt", "")
+        .build();
+
+    cargo_process("new foo --vcs none --template tmpl --define dir=.. -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[CREATING] `foo` package from template `tmpl`
+[ERROR] Failed to create package `foo` at `[ROOT]/foo`
+
+Caused by:
+  template file `{{dir}}/evil.txt` would be written outside of the package: `../evil.txt`
+
+"#]])
+        .run();
+
+    assert!(!paths::root().join("evil.txt").exists());
+}
+
+#[cargo_test]
+fn template_requires_unstable_options() {
+    cargo_process("new foo --template tmpl")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--template` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    cargo_process("new foo --template tmpl")
+        .masquerade_as_nightly_cargo(&["new-template"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--template` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    cargo_process("new foo --lib --template tmpl")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--lib' cannot be used with '--template <TEMPLATE>'
+
+Usage: cargo[EXE] new --lib <PATH>
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn init_workspace_inherits_equal_values_only() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"  # first release
+                edition = "2021"
+                publish = false
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("init --workspace --vcs none --registry alternative -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["init-workspace"])
+        .run();
+
+    let manifest = p.read_file("crates/foo/Cargo.toml");
+    assert!(manifest.contains("version.workspace = true"), "{manifest}");
+    assert!(manifest.contains("edition.workspace = true"), "{manifest}");
+    assert!(manifest.contains("publish = false"), "{manifest}");
+}
+
+#[cargo_test]
+fn init_workspace_requires_unstable_options() {
+    cargo_process("init --workspace")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--workspace` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    cargo_process("init --workspace")
+        .masquerade_as_nightly_cargo(&["init-workspace"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--workspace` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    cargo_process("init --workspace --lib -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["init-workspace"])
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--workspace' cannot be used with '--lib'
+
+Usage: cargo[EXE] init --workspace -Z <FLAG> [PATH]
+
+For more information, try '--help'.
+
+"#]])
+        .run();
+}
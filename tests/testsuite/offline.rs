@@ -1,11 +1,14 @@
 //! Tests for --offline flag.
 
+use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::{
     basic_manifest, git, main_file, project,
-    registry::{Package, RegistryBuilder},
+    registry::{registry_path, Package, RegistryBuilder},
     str, Execs,
 };
 use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 #[allow(deprecated)]
 #[cargo_test]
@@ -355,7 +358,7 @@ Caused by:
     p.cargo("check --offline")
         .with_status(101)
         .with_stderr_data(str![[r#"
-[ERROR] failed to download `bar v0.1.0`
+[ERROR] failed to download `baz v1.0.0`
 
 Caused by:
   attempting to make an HTTP request, but --offline was specified
@@ -788,3 +791,263 @@ If you want to try to generate the lock file without accessing the network, remo
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn offline_conflict_lists_versions_not_downloaded() {
+    Package::new("bar", "1.2.0").publish();
+    let downloaded = project()
+        .at("downloaded")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "downloaded"
+            version = "0.0.1"
+            edition = "2015"
+
+            [dependencies]
+            bar = "=1.2.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    downloaded.cargo("fetch").run();
+
+    // Newer versions are in the cached index, but never downloaded.
+    Package::new("bar", "1.3.0").publish();
+    Package::new("bar", "1.4.0").publish();
+    let indexed = project()
+        .at("indexed")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "indexed"
+            version = "0.0.1"
+            edition = "2015"
+
+            [dependencies]
+            bar = "1.4"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    indexed.cargo("generate-lockfile").run();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+
+            [dependencies]
+            bar = "1.2"
+
+            [dev-dependencies]
+            bar = "1.3"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check --offline")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to select a version for `bar`.
+    ... required by package `foo v0.0.1 ([ROOT]/foo)`
+versions that meet the requirements `^1.3` are: 1.4.0, 1.3.0
+
+all possible versions conflict with previously selected packages.
+
+  previously selected package `bar v1.2.0`
+    ... which satisfies dependency `bar = "^1.2"` of package `foo v0.0.1 ([ROOT]/foo)`
+
+failed to select a version for `bar` which could resolve this conflict
+versions 1.3.0 through 1.4.0 of `bar` exist but are not downloaded; run `cargo fetch` without `--offline` to download them
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn fallback_to_cache_sparse_index() {
+    let fail = Arc::new(AtomicBool::new(false));
+    let fail2 = fail.clone();
+    let _registry = RegistryBuilder::new()
+        .http_index()
+        .add_responder("/index/3/b/bar", move |req, server| {
+            if fail2.load(Ordering::SeqCst) {
+                server.internal_server_error(req)
+            } else {
+                server.index(req)
+            }
+        })
+        .build();
+    Package::new("bar", "0.0.1").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+
+            [dependencies]
+            bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [net]
+            retry = 0
+            fallback-to-cache = true
+            "#,
+        )
+        .build();
+    p.cargo("fetch").run();
+
+    fail.store(true, Ordering::SeqCst);
+    p.cargo("update -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["net.fallback-to-cache"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] download of 3/b/bar failed: failed to get successful HTTP response from `http://127.0.0.1:[..]/index/3/b/bar` (127.0.0.1), got 500
+body:
+internal server error
+falling back to the cached index of `dummy-registry`
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    // It can be turned off for a single command.
+    p.cargo("update -Zunstable-options --config net.fallback-to-cache=false")
+        .masquerade_as_nightly_cargo(&["net.fallback-to-cache"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+
+Caused by:
+  failed to query replaced source registry `crates-io`
+
+Caused by:
+  download of 3/b/bar failed
+
+Caused by:
+  failed to get successful HTTP response from `http://127.0.0.1:[..]/index/3/b/bar` (127.0.0.1), got 500
+  body:
+  internal server error
+
+"#]])
+        .run();
+
+    // Without `-Zunstable-options` the config is ignored.
+    p.cargo("update")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] ignoring `net.fallback-to-cache` config, pass `-Zunstable-options` to enable it
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn fallback_to_cache_git_index() {
+    Package::new("bar", "0.0.1").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+
+            [dependencies]
+            bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [net]
+            retry = 0
+            fallback-to-cache = true
+            "#,
+        )
+        .build();
+    p.cargo("fetch").run();
+
+    registry_path().rm_rf();
+    p.cargo("update -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["net.fallback-to-cache"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] failed to fetch `[ROOTURL]/registry`: [..]
+falling back to the cached index of `dummy-registry`
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["net.fallback-to-cache"])
+        .run();
+}
+
+#[cargo_test]
+fn fallback_to_cache_git_dependency() {
+    let git_project = git::new("bar", |project| {
+        project
+            .file("Cargo.toml", &basic_manifest("bar", "0.0.1"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = {{ git = '{}' }}
+                "#,
+                git_project.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [net]
+            retry = 0
+            fallback-to-cache = true
+            "#,
+        )
+        .build();
+    p.cargo("fetch").run();
+
+    git_project.root().rm_rf();
+    p.cargo("update -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["net.fallback-to-cache"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/bar`
+[WARNING] failed to fetch into: [ROOT]/home/.cargo/git/db/bar-[HASH]: [..]
+falling back to the cached git repository `[ROOTURL]/bar`
+[LOCKING] 0 packages to latest compatible versions
+
+"#]])
+        .run();
+}
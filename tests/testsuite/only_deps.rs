@@ -0,0 +1,123 @@
+//! Tests for `--only-deps`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project, str};
+
+#[cargo_test]
+fn build_only_deps() {
+    Package::new("dep", "1.0.0").publish();
+    Package::new("build-dep", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "1.0"
+
+                [build-dependencies]
+                build-dep = "1.0"
+            "#,
+        )
+        .file("build.rs", "fn main() {}")
+        .file("src/lib.rs", "")
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zunstable-options --only-deps")
+        .masquerade_as_nightly_cargo(&["only-deps"])
+        .with_stderr_data(
+            str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] build-dep v1.0.0 (registry `dummy-registry`)
+[COMPILING] build-dep v1.0.0
+[COMPILING] dep v1.0.0
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(!p.bin("foo").is_file());
+    assert!(!p.root().join("target/debug/libdep.rlib").is_file());
+
+    p.cargo("build")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.bin("foo").is_file());
+}
+
+#[cargo_test]
+fn check_only_deps_of_selected_package() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar", "baz"]
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = { path = "../bar" }
+            "#,
+        )
+        .file("foo/src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zunstable-options --only-deps -p foo")
+        .masquerade_as_nightly_cargo(&["only-deps"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 3 packages to latest compatible versions
+[CHECKING] bar v0.1.0 ([ROOT]/foo/bar)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // A selected package isn't built even if another one depends on it.
+    p.cargo("check -Zunstable-options --only-deps -p foo -p bar")
+        .masquerade_as_nightly_cargo(&["only-deps"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn only_deps_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("build --only-deps")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--only-deps` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
@@ -3,8 +3,9 @@
 use std::fs;
 
 use cargo_test_support::paths::CargoPathExt;
+use cargo_test_support::prelude::*;
 use cargo_test_support::project;
-use cargo_test_support::registry::{self, api_path};
+use cargo_test_support::registry::{self, api_path, RegistryBuilder, Response};
 use cargo_test_support::str;
 
 fn setup(name: &str, content: Option<&str>) {
@@ -192,3 +193,164 @@ fn simple_remove_with_asymmetric() {
         .with_status(0)
         .run();
 }
+
+fn foo_project() -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build()
+}
+
+#[cargo_test]
+fn list_with_roles() {
+    let registry = registry::init();
+    let content = r#"{
+        "users": [
+            {
+                "id": 70,
+                "login": "github:rust-lang:core",
+                "name": "Core",
+                "kind": "team",
+                "role": "maintainer"
+            },
+            {
+                "id": 123,
+                "login": "octocat",
+                "kind": "user",
+                "role": "owner"
+            }
+        ]
+    }"#;
+    setup("foo", Some(content));
+    let p = foo_project();
+
+    p.cargo("owner -l")
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(str![[r#"
+github:rust-lang:core (Core) [maintainer]
+octocat [owner]
+
+"#]])
+        .run();
+
+    p.cargo("owner -l --message-format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(
+            str![[r#"
+{
+  "added": [],
+  "crate": "foo",
+  "message": null,
+  "owners": [
+    {
+      "email": null,
+      "kind": "team",
+      "login": "github:rust-lang:core",
+      "name": "Core",
+      "role": "maintainer"
+    },
+    {
+      "email": null,
+      "kind": "user",
+      "login": "octocat",
+      "name": null,
+      "role": "owner"
+    }
+  ],
+  "removed": [],
+  "role": null
+}
+"#]]
+            .json(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn message_format_json_requires_unstable() {
+    let registry = registry::init();
+    setup("foo", None);
+    let p = foo_project();
+
+    p.cargo("owner -l --message-format json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--message-format=json` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn add_with_role() {
+    let registry = RegistryBuilder::new()
+        .http_api()
+        .capabilities(&["owner-roles"])
+        .add_responder("/api/v1/crates/foo/owners", |req, _| {
+            let body = String::from_utf8(req.body.clone().unwrap()).unwrap();
+            assert_eq!(body, r#"{"users":["octocat"],"role":"maintainer"}"#);
+            Response {
+                code: 200,
+                headers: vec![],
+                body: br#"{"ok":true,"msg":"user octocat has been invited to be a maintainer of crate foo"}"#
+                    .to_vec(),
+            }
+        })
+        .build();
+    let p = foo_project();
+
+    p.cargo("owner -a octocat --role maintainer --message-format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(
+            str![[r#"
+{
+  "added": [
+    "octocat"
+  ],
+  "crate": "foo",
+  "message": "user octocat has been invited to be a maintainer of crate foo",
+  "owners": null,
+  "removed": [],
+  "role": "maintainer"
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[OWNER] user octocat has been invited to be a maintainer of crate foo
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn add_with_role_unsupported() {
+    let registry = RegistryBuilder::new().http_api().build();
+    let p = foo_project();
+
+    p.cargo("owner -a octocat --role maintainer -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[ERROR] the registry at http://127.0.0.1:[..]/ does not support owner roles, as its `config.json` doesn't list the `owner-roles` capability
+
+"#]])
+        .run();
+}
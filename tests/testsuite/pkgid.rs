@@ -5,6 +5,8 @@
 use cargo_test_support::basic_lib_manifest;
 use cargo_test_support::compare::assert_e2e;
 use cargo_test_support::git;
+use cargo_test_support::paths;
+use cargo_test_support::prelude::*;
 use cargo_test_support::project;
 use cargo_test_support::registry::Package;
 use cargo_test_support::str;
@@ -318,6 +320,7 @@ fn pkgid_json_message_metadata_consistency() {
   "package_id": "$PKGID",
   "manifest_path": "[..]",
   "target": "{...}",
+  "platform": null,
   "profile": "{...}",
   "features": [],
   "filenames": "{...}",
@@ -356,6 +359,7 @@ fn pkgid_json_message_metadata_consistency() {
   "package_id": "$PKGID",
   "manifest_path": "[..]",
   "target": "{...}",
+  "platform": null,
   "profile": "{...}",
   "features": [],
   "filenames": "{...}",
@@ -431,3 +435,134 @@ fn pkgid_json_message_metadata_consistency() {
         )
         .run()
 }
+
+fn batch_project() -> cargo_test_support::Project {
+    Package::new("baz", "1.0.0").publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["bar"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2018"
+
+                [dependencies]
+                baz = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_lib_manifest("bar"))
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn batch() {
+    let p = batch_project();
+    p.cargo("generate-lockfile").run();
+
+    p.cargo("pkgid foo baz -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["pkgid batch"])
+        .with_stdout_data(str![[r#"
+path+[ROOTURL]/foo#0.1.0
+registry+https://github.com/rust-lang/crates.io-index#baz@1.0.0
+
+"#]])
+        .run();
+
+    p.cargo("pkgid --all --format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["pkgid batch"])
+        .with_stdout_data(
+            str![[r#"
+{"id":"path+[ROOTURL]/foo/bar#0.5.0","name":"bar","source":null,"version":"0.5.0"}
+{"id":"registry+https://github.com/rust-lang/crates.io-index#baz@1.0.0","name":"baz","source":"registry+https://github.com/rust-lang/crates.io-index","version":"1.0.0"}
+{"id":"path+[ROOTURL]/foo#0.1.0","name":"foo","source":null,"version":"0.1.0"}
+
+"#]]
+            .json_lines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn reverse_lookup() {
+    let p = batch_project();
+    p.cargo("check --workspace").run();
+
+    let find = |dir: std::path::PathBuf, prefix: &str| {
+        std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .starts_with(prefix)
+            })
+            .unwrap()
+    };
+    let fingerprint = find(p.target_debug_dir().join(".fingerprint"), "baz-");
+    let rmeta = find(p.target_debug_dir().join("deps"), "libbar-");
+    let registry_src = find(paths::home().join(".cargo/registry/src"), "");
+    let baz_src = registry_src.join("baz-1.0.0/src/lib.rs");
+
+    p.cargo("pkgid -Zunstable-options --format json")
+        .arg("--for-path")
+        .arg(fingerprint.join("lib-baz"))
+        .arg("--for-path")
+        .arg(&rmeta)
+        .arg("--for-path")
+        .arg(&baz_src)
+        .arg("--for-path")
+        .arg("bar/src/lib.rs")
+        .masquerade_as_nightly_cargo(&["pkgid reverse lookup"])
+        .with_stdout_data(
+            str![[r#"
+{"id":"registry+https://github.com/rust-lang/crates.io-index#baz@1.0.0","name":"baz","path":"[ROOT]/foo/target/debug/.fingerprint/baz-[HASH]/lib-baz","source":"registry+https://github.com/rust-lang/crates.io-index","version":"1.0.0"}
+{"id":"path+[ROOTURL]/foo/bar#0.5.0","name":"bar","path":"[ROOT]/foo/target/debug/deps/libbar-[HASH].rmeta","source":null,"version":"0.5.0"}
+{"id":"registry+https://github.com/rust-lang/crates.io-index#baz@1.0.0","name":"baz","path":"[ROOT]/home/.cargo/registry/src/-[HASH]/baz-1.0.0/src/lib.rs","source":"registry+https://github.com/rust-lang/crates.io-index","version":"1.0.0"}
+{"id":"path+[ROOTURL]/foo/bar#0.5.0","name":"bar","path":"bar/src/lib.rs","source":null,"version":"0.5.0"}
+
+"#]]
+            .json_lines(),
+        )
+        .run();
+
+    p.cargo("pkgid -Zunstable-options --for-path")
+        .arg(paths::root())
+        .masquerade_as_nightly_cargo(&["pkgid reverse lookup"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `[ROOT]` isn't inside a package of the lock file, the target directory or the registry cache
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn batch_is_unstable() {
+    let p = batch_project();
+    p.cargo("generate-lockfile").run();
+
+    p.cargo("pkgid foo bar")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `<SPEC>...` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+    p.cargo("pkgid --for-path src/lib.rs")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--for-path` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
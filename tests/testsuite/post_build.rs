@@ -0,0 +1,231 @@
+//! Tests for post-build scripts.
+
+use cargo_test_support::{project, sleep_ms, str, Project};
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                post-build = "post_build.rs"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("post_build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `post-build-scripts` is required
+
+  The package requires the Cargo feature called `post-build-scripts`, but that feature is not stabilized in this version of Cargo ([..]).
+  Consider adding `cargo-features = ["post-build-scripts"]` to the top of Cargo.toml (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#post-build-scripts for more information about the status of this feature.
+
+"#]])
+        .run();
+}
+
+/// A package with two binaries and a post-build script recording the size of
+/// each of them next to it.
+fn signing_project() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["post-build-scripts"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                post-build = "post_build.rs"
+
+                [[bin]]
+                name = "foo"
+                path = "src/main.rs"
+
+                [[bin]]
+                name = "bar"
+                path = "src/bar.rs"
+            "#,
+        )
+        .file("src/main.rs", r#"fn main() { println!("foo"); }"#)
+        .file("src/bar.rs", r#"fn main() { println!("bar"); }"#)
+        .file("key.txt", "secret")
+        .file(
+            "post_build.rs",
+            r#"
+                use std::env;
+                use std::fs;
+
+                fn main() {
+                    println!("cargo::rerun-if-changed=key.txt");
+                    println!("cargo::rerun-if-env-changed=SIGN_SUFFIX");
+                    let key = fs::read_to_string("key.txt").unwrap();
+                    let suffix = env::var("SIGN_SUFFIX").unwrap_or_default();
+                    for name in ["foo", "bar"] {
+                        let bin = env::var(format!("CARGO_BIN_FILE_{}", name)).unwrap();
+                        let len = fs::metadata(&bin).unwrap().len();
+                        fs::write(format!("{}.sig", bin), format!("{}{}{}", key, len, suffix))
+                            .unwrap();
+                    }
+                    assert!(env::var("OUT_DIR").is_ok());
+                    println!("cargo::warning=signed binaries");
+                }
+            "#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn runs_after_binaries() {
+    let p = signing_project();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] signed binaries
+[WARNING] `foo` (post-build script) generated 1 warning
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    for name in ["foo", "bar"] {
+        let bin = p.bin(name);
+        let sig = p.read_file(&format!("target/debug/{name}.sig"));
+        let len = bin.metadata().unwrap().len();
+        assert_eq!(sig, format!("secret{len}"));
+    }
+}
+
+#[cargo_test]
+fn rerun_tracking() {
+    let p = signing_project();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .run();
+
+    p.cargo("build -v")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_stderr_data(str![[r#"
+[FRESH] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    sleep_ms(1000);
+    p.change_file("key.txt", "other");
+    p.cargo("build -v")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_stderr_data(str![[r#"
+[DIRTY] foo v0.1.0 ([ROOT]/foo): the file `key.txt` has changed ([..])
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `[ROOT]/foo/target/debug/build/foo-[HASH]/post-build-script-post_build`
+[WARNING] signed binaries
+[WARNING] `foo` (post-build script) generated 1 warning
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.read_file("target/debug/foo.sig").starts_with("other"));
+
+    p.cargo("build")
+        .env("SIGN_SUFFIX", "-v2")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] signed binaries
+[WARNING] `foo` (post-build script) generated 1 warning
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.read_file("target/debug/foo.sig").ends_with("-v2"));
+
+    // Rebuilding a binary runs the script again.
+    p.change_file("src/bar.rs", r#"fn main() { println!("bar, again"); }"#);
+    p.cargo("build")
+        .env("SIGN_SUFFIX", "-v2")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[WARNING] signed binaries
+[WARNING] `foo` (post-build script) generated 1 warning
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    let len = p.bin("bar").metadata().unwrap().len();
+    assert_eq!(
+        p.read_file("target/debug/bar.sig"),
+        format!("other{len}-v2")
+    );
+}
+
+#[cargo_test]
+fn not_run_without_binaries() {
+    let p = signing_project();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.1.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/debug/foo.sig").exists());
+}
+
+#[cargo_test]
+fn script_failure() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["post-build-scripts"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                post-build = "post_build.rs"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "post_build.rs",
+            r#"fn main() { eprintln!("no signing key"); std::process::exit(1); }"#,
+        )
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo(&["post-build-scripts"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[ERROR] failed to run post-build script for `foo v0.1.0 ([ROOT]/foo)`
+
+Caused by:
+  process didn't exit successfully: `[ROOT]/foo/target/debug/build/foo-[HASH]/post-build-script-post_build` ([EXIT_STATUS]: 1)
+  --- stderr
+  no signing key
+
+"#]])
+        .run();
+}
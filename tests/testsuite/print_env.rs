@@ -0,0 +1,194 @@
+//! Tests for `cargo build --print-env`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::{basic_manifest, project, str};
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("build --print-env")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--print-env` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn print_env_human() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("build.rs", "fn main() {}")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [env]
+                FROM_CONFIG = "yes"
+            "#,
+        )
+        .build();
+
+    p.cargo("build --print-env -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["print-env"])
+        .with_stdout_data(str![[r#"
+# rustc: bar v0.0.1, lib target `bar`
+...
+CARGO_CRATE_NAME=bar
+CARGO_MANIFEST_DIR=[ROOT]/foo/bar
+...
+CARGO_PKG_NAME=bar
+...
+FROM_CONFIG=yes
+...
+# rustc: foo v0.1.0, custom-build target `build-script-build`
+...
+CARGO_PKG_NAME=foo
+...
+FROM_CONFIG=yes
+...
+# build-script: foo v0.1.0, custom-build target `build-script-build`
+...
+HOST=[HOST_TARGET]
+...
+OUT_DIR=[ROOT]/foo/target/debug/build/foo-[HASH]/out
+...
+TARGET=[HOST_TARGET]
+
+# rustc: foo v0.1.0, bin target `foo`
+# partial: the build script hasn't run yet, the variables it sets are missing
+...
+CARGO_BIN_NAME=foo
+...
+CARGO_PRIMARY_PACKAGE=1
+...
+# runner: foo v0.1.0, bin target `foo`
+# partial: the build script hasn't run yet, the variables it sets are missing
+...
+OUT_DIR=[ROOT]/foo/target/debug/build/foo-[HASH]/out
+
+
+"#]])
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+
+"#]])
+        .run();
+
+    assert!(!p.bin("foo").is_file());
+}
+
+#[cargo_test]
+fn print_env_json() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build --print-env=json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["print-env"])
+        .with_stdout_data(
+            str![[r#"
+{"compile_mode":"build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo#0.0.1","partial":false,"process":"rustc","program":"rustc","target_kind":["custom-build"],"target_name":"build-script-build"}
+{"compile_mode":"run-custom-build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo#0.0.1","partial":false,"process":"build-script","program":"[ROOT]/foo/target/debug/build/foo-[HASH]/build-script-build[EXE]","target_kind":["custom-build"],"target_name":"build-script-build"}
+{"compile_mode":"build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo#0.0.1","partial":true,"process":"rustc","program":"rustc","target_kind":["bin"],"target_name":"foo"}
+{"compile_mode":"build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo#0.0.1","partial":true,"process":"runner","program":"[ROOT]/foo/target/debug/foo[EXE]","target_kind":["bin"],"target_name":"foo"}
+
+"#]]
+            .json_lines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn print_env_after_build_script_ran() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "build.rs",
+            r#"fn main() { println!("cargo::rustc-env=FROM_BUILD_SCRIPT=yes"); }"#,
+        )
+        .build();
+
+    p.cargo("build").run();
+
+    // The variables of the build script are known once it has run.
+    p.cargo("build --print-env -Zunstable-options --print-env-for foo:foo")
+        .masquerade_as_nightly_cargo(&["print-env"])
+        .with_stdout_data(str![[r#"
+# rustc: foo v0.0.1, bin target `foo`
+...
+FROM_BUILD_SCRIPT=yes
+...
+# runner: foo v0.0.1, bin target `foo`
+...
+FROM_BUILD_SCRIPT=yes
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn print_env_for() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("build.rs", "fn main() {}")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("build --print-env=json -Zunstable-options --print-env-for bar --print-env-for foo:foo")
+        .masquerade_as_nightly_cargo(&["print-env"])
+        .with_stdout_data(
+            str![[r#"
+{"compile_mode":"build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo/bar#0.0.1","partial":false,"process":"rustc","program":"rustc","target_kind":["lib"],"target_name":"bar"}
+{"compile_mode":"build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo#0.1.0","partial":true,"process":"rustc","program":"rustc","target_kind":["bin"],"target_name":"foo"}
+{"compile_mode":"build","env":"{...}","kind":null,"package_id":"path+[ROOTURL]/foo#0.1.0","partial":true,"process":"runner","program":"[ROOT]/foo/target/debug/foo[EXE]","target_kind":["bin"],"target_name":"foo"}
+
+"#]]
+            .json_lines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn conflicts_with_build_plan() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("build --print-env --build-plan -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["print-env"])
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--print-env[=<FMT>]' cannot be used with '--build-plan'
+...
+"#]])
+        .run();
+}
@@ -3,7 +3,7 @@
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::prelude::*;
 use cargo_test_support::registry::Package;
-use cargo_test_support::{basic_lib_manifest, paths, project, str};
+use cargo_test_support::{basic_lib_manifest, paths, project, rustc_host, str};
 use cargo_util_schemas::manifest::TomlDebugInfo;
 
 // TODO: this should be remove once -Zprofile-rustflags is stabilized
@@ -72,6 +72,176 @@ Caused by:
         .run();
 }
 
+#[cargo_test]
+fn target_profile_rustflags_precedence() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["profile-rustflags"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [profile.dev]
+                rustflags = ["--cfg", "from_profile"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{host}.profile.dev]
+                    rustflags = ["--cfg", "from_target_profile"]
+
+                    [target.'cfg(all())'.profile.dev]
+                    rustflags = ["--cfg", "from_cfg_profile"]
+
+                    [target.{host}.profile.release]
+                    rustflags = ["--cfg", "from_release"]
+                "#,
+                host = rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("check -v")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_data(str![[r#"
+[WARNING] unused key `profile` in [target] config table `cfg(all())`
+[NOTE] `profile` tables in [target] require `-Zprofile-rustflags`
+[WARNING] unused key `profile` in [target] config table `[HOST_TARGET]`
+[NOTE] `profile` tables in [target] require `-Zprofile-rustflags`
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..] --cfg from_profile [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // `RUSTFLAGS` does not replace the per-profile flags, and comes last.
+    p.cargo("check -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .env("RUSTFLAGS", "--cfg from_env")
+        .with_stderr_data(str![[r#"
+[DIRTY] foo v0.0.1 ([ROOT]/foo): the profile configuration changed
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..] --cfg from_profile --cfg from_target_profile --cfg from_cfg_profile [..] --cfg from_env`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn target_profile_rustflags_rebuild() {
+    let p = project()
+        .file("Cargo.toml", &basic_lib_manifest("foo"))
+        .file(
+            "src/lib.rs",
+            r#"
+                #![allow(unexpected_cfgs)]
+                #[cfg(all(first, not(debug_assertions)))]
+                compile_error!("dev flags used in release");
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{}.profile.dev]
+                    rustflags = ["--cfg", "first"]
+                "#,
+                rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("check -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.5.0 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..] --cfg first [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // Other profiles don't pick up the flags.
+    p.cargo("check -v --release -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.5.0 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.change_file(
+        ".cargo/config.toml",
+        &format!(
+            r#"
+                [target.{}.profile.dev]
+                rustflags = ["--cfg", "second"]
+            "#,
+            rustc_host()
+        ),
+    );
+
+    p.cargo("check -v -Zprofile-rustflags")
+        .masquerade_as_nightly_cargo(&["profile-rustflags"])
+        .with_stderr_data(str![[r#"
+[DIRTY] foo v0.5.0 ([ROOT]/foo): the profile configuration changed
+[CHECKING] foo v0.5.0 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..] --cfg second [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn links_override_named_profile() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                links = "profile"
+                build = "build.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{host}.profile]
+                    rustc-flags = "-L foo"
+                "#,
+                host = rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("check -v")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..] -L foo`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn profile_config_validate_warnings() {
     let p = project()
@@ -488,6 +658,111 @@ fn named_env_profile() {
         .run();
 }
 
+#[cargo_test]
+fn config_overrides_custom_manifest_profile() {
+    // A config profile is merged into the custom profile of the same name
+    // in `Cargo.toml`, and changing it rebuilds.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2015"
+
+            [profile.ci]
+            inherits = "release"
+            debug = 1
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [profile.ci]
+            opt-level = 1
+            "#,
+        )
+        .build();
+
+    p.cargo("build -v --profile=ci")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc [..]-C opt-level=1 [..]-C debuginfo=1 [..]`
+[FINISHED] `ci` profile [optimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+        [profile.ci]
+        opt-level = 2
+        "#,
+    );
+    p.cargo("build -v --profile=ci")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc [..]-C opt-level=2 [..]-C debuginfo=1 [..]`
+[FINISHED] `ci` profile [optimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // The environment takes precedence over config files.
+    p.cargo("build -v --profile=ci")
+        .env("CARGO_PROFILE_CI_OPT_LEVEL", "s")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc [..]-C opt-level=s [..]-C debuginfo=1 [..]`
+[FINISHED] `ci` profile [optimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn config_profile_inherits_custom_manifest_profile() {
+    // A profile only defined in config can inherit from a custom profile
+    // defined in `Cargo.toml`.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+            edition = "2015"
+
+            [profile.ci]
+            inherits = "release"
+            debug = 1
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+            [profile.ci-fast]
+            inherits = "ci"
+            codegen-units = 4
+            "#,
+        )
+        .build();
+
+    p.cargo("build -v --profile=ci-fast")
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.1.0 ([ROOT]/foo)
+[RUNNING] `rustc [..]-C opt-level=3 [..]-C codegen-units=4 -C debuginfo=1 [..]`
+[FINISHED] `ci-fast` profile [optimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.root().join("target/ci-fast").is_dir());
+}
+
 #[allow(deprecated)]
 #[cargo_test]
 fn test_with_dev_profile() {
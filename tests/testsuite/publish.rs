@@ -2,6 +2,7 @@
 
 use cargo_test_support::git::{self, repo};
 use cargo_test_support::paths;
+use cargo_test_support::prelude::*;
 use cargo_test_support::registry::{self, Package, RegistryBuilder, Response};
 use cargo_test_support::{basic_manifest, project, publish, str};
 use std::fs;
@@ -3212,6 +3213,154 @@ You may press ctrl-c to skip waiting; the crate should be available shortly.
         .run();
 }
 
+#[cargo_test]
+fn wait_timeout_flag() {
+    // `--wait-timeout` overrides `publish.timeout`.
+    let registry = registry::RegistryBuilder::new()
+        .http_api()
+        .delayed_index_update(20)
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "delay"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [publish]
+                timeout = 300
+            "#,
+        )
+        .build();
+
+    p.cargo("publish --no-verify --wait-timeout 2")
+        .masquerade_as_nightly_cargo(&["publish-timeout"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--wait-timeout` flag is unstable, pass `-Z publish-timeout` to enable it
+
+"#]])
+        .run();
+
+    p.cargo("publish --no-verify --wait-timeout 2 --message-format json -Zpublish-timeout -Zunstable-options")
+        .replace_crates_io(registry.index_url())
+        .masquerade_as_nightly_cargo(&["publish-timeout", "unstable-options"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "name": "delay",
+  "reason": "publish-timeout",
+  "source": "registry+https://github.com/rust-lang/crates.io-index",
+  "version": "0.0.1"
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] delay v0.0.1 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] delay v0.0.1 ([ROOT]/foo)
+[UPLOADED] delay v0.0.1 to registry `crates-io`
+[NOTE] waiting for `delay v0.0.1` to be available at registry `crates-io`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[WARNING] timed out waiting for `delay v0.0.1` to be available in registry `crates-io`
+[NOTE] the registry may have a backlog that is delaying making the crate available. The crate should be available soon.
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn wait_for_publish_verify_download() {
+    // The index is updated before the `.crate` file can be downloaded.
+    let downloads = Mutex::new(0);
+    let registry = registry::RegistryBuilder::new()
+        .http_index()
+        .http_api()
+        .add_responder("/dl/foo/0.0.1/download", move |req, server| {
+            let mut downloads = downloads.lock().unwrap();
+            *downloads += 1;
+            if *downloads == 1 {
+                server.not_found(req)
+            } else {
+                server.dl(req)
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [publish]
+                poll-interval = 1
+                verify-download = true
+            "#,
+        )
+        .build();
+
+    p.cargo("publish --no-verify --message-format json -Zpublish-timeout -Zunstable-options")
+        .replace_crates_io(registry.index_url())
+        .masquerade_as_nightly_cargo(&["publish-timeout", "unstable-options"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "name": "foo",
+  "reason": "publish-available",
+  "source": "registry+https://github.com/rust-lang/crates.io-index",
+  "verified": [
+    "index",
+    "download"
+  ],
+  "version": "0.0.1"
+}
+"#]]
+            .json(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] crates.io index
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.0.1 ([ROOT]/foo)
+[UPLOADED] foo v0.0.1 to registry `crates-io`
+[NOTE] waiting for `foo v0.0.1` to be available at registry `crates-io`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.0.1 at registry `crates-io`
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn wait_for_git_publish() {
     // Slow publish to an index with a git index.
@@ -3397,3 +3546,159 @@ You may press ctrl-c to skip waiting; the crate should be available shortly.
 
     validate_upload_foo();
 }
+
+#[cargo_test]
+fn publish_retries_rate_limit() {
+    let limited = Mutex::new(false);
+    let _reg = registry::RegistryBuilder::new()
+        .http_api()
+        .token(registry::Token::Plaintext("sekrit".to_string()))
+        .add_responder("/api/v1/crates/new", move |req, server| {
+            let mut limited = limited.lock().unwrap();
+            if *limited {
+                server.check_authorized_publish(req)
+            } else {
+                *limited = true;
+                Response {
+                    body: br#"{"errors": [{"detail": "too many crates published"}]}"#.to_vec(),
+                    code: 429,
+                    headers: vec!["Retry-After: 1".to_string()],
+                }
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("publish --no-verify --token sekrit --registry dummy-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["publish rate limit"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.0.1 ([ROOT]/foo)
+[WARNING] the registry rate limited publishing `foo v0.0.1`, retrying in 1s
+[UPLOADED] foo v0.0.1 to registry `dummy-registry`
+[NOTE] waiting for `foo v0.0.1` to be available at registry `dummy-registry`.
+You may press ctrl-c to skip waiting; the crate should be available shortly.
+[PUBLISHED] foo v0.0.1 at registry `dummy-registry`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn publish_rate_limit_timeout() {
+    let _reg = registry::RegistryBuilder::new()
+        .http_api()
+        .token(registry::Token::Plaintext("sekrit".to_string()))
+        .add_responder("/api/v1/crates/new", |_, _| Response {
+            body: br#"{"errors": [{"detail": "too many crates published"}]}"#.to_vec(),
+            code: 429,
+            headers: vec!["Retry-After: 3600".to_string()],
+        })
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("publish --no-verify --token sekrit --registry dummy-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["publish rate limit"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.0.1 ([ROOT]/foo)
+[NOTE] not retrying in 3600s, as that exceeds `publish.rate-limit-timeout`
+[ERROR] failed to publish to registry at http://127.0.0.1:[..]/
+
+Caused by:
+  the remote server responded with an error (status 429 Too Many Requests): too many crates published
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn publish_rate_limit_retry_after_overflow() {
+    let _reg = registry::RegistryBuilder::new()
+        .http_api()
+        .token(registry::Token::Plaintext("sekrit".to_string()))
+        .add_responder("/api/v1/crates/new", |_, _| {
+            // Take long enough that adding the delay to the elapsed time overflows.
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            Response {
+                body: br#"{"errors": [{"detail": "too many crates published"}]}"#.to_vec(),
+                code: 429,
+                headers: vec!["Retry-After: 18446744073709551615".to_string()],
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("publish --no-verify --token sekrit --registry dummy-registry -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["publish rate limit"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] foo v0.0.1 ([ROOT]/foo)
+[PACKAGED] 4 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+[UPLOADING] foo v0.0.1 ([ROOT]/foo)
+[NOTE] not retrying in 18446744073709551615s, as that exceeds `publish.rate-limit-timeout`
+[ERROR] failed to publish to registry at http://127.0.0.1:[..]/
+
+Caused by:
+  the remote server responded with an error (status 429 Too Many Requests): too many crates published
+
+"#]])
+        .run();
+}
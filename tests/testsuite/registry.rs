@@ -7,7 +7,7 @@ use cargo_test_support::prelude::*;
 use cargo_test_support::registry::{
     self, registry_path, Dependency, Package, RegistryBuilder, Response, TestRegistry,
 };
-use cargo_test_support::{basic_manifest, project, str};
+use cargo_test_support::{basic_manifest, project, str, Project};
 use cargo_test_support::{git, install::cargo_home, t};
 use cargo_util::paths::remove_dir_all;
 use snapbox::data::Inline;
@@ -3449,6 +3449,179 @@ internal server error
 "#]]).run();
 }
 
+/// Serves the `.crate` file requested by `req`, but breaks off the transfer
+/// halfway through.
+fn dl_truncated(req: &registry::Request, server: &registry::HttpServer) -> Response {
+    let mut response = server.dl(req);
+    let len = response.body.len();
+    response.headers.push(format!("Content-Length: {len}"));
+    response.body.truncate(len / 2);
+    response
+}
+
+#[cargo_test]
+fn dl_resume_after_dropped_connection() {
+    let ranges = Arc::new(Mutex::new(Vec::new()));
+    let ranges2 = ranges.clone();
+    let _server = RegistryBuilder::new()
+        .http_index()
+        .add_responder("/dl/bar/1.0.0/download", move |req, server| {
+            let mut ranges = ranges2.lock().unwrap();
+            ranges.push(req.range.clone());
+            if ranges.len() == 1 {
+                dl_truncated(req, server)
+            } else {
+                server.dl(req)
+            }
+        })
+        .build();
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("fetch").with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[WARNING] spurious network error (3 tries remaining): [18] Transferred a partial file (transfer closed with [..] bytes remaining to read)
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+
+"#]]).run();
+
+    // The second attempt only asked for what the first one missed.
+    let ranges = ranges.lock().unwrap();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0], None);
+    assert!(ranges[1].as_ref().unwrap().starts_with("bytes="));
+    assert!(p
+        .glob(paths::home().join(".cargo/registry/cache/*/*.partial"))
+        .next()
+        .is_none());
+}
+
+#[cargo_test]
+fn dl_resume_partial_file() {
+    let fail = Arc::new(Mutex::new(true));
+    let fail2 = fail.clone();
+    let _server = RegistryBuilder::new()
+        .http_index()
+        .add_responder("/dl/bar/1.0.0/download", move |req, server| {
+            if *fail2.lock().unwrap() {
+                dl_truncated(req, server)
+            } else {
+                assert!(req.range.is_some(), "download was not resumed");
+                server.dl(req)
+            }
+        })
+        .build();
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("fetch")
+        .env("CARGO_NET_RETRY", "0")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[ERROR] failed to download from `http://127.0.0.1:[..]/dl/bar/1.0.0/download`
+
+Caused by:
+  [18] Transferred a partial file (transfer closed with [..] bytes remaining to read)
+
+"#]])
+        .run();
+
+    // What was received so far is kept to resume from.
+    let partial: Vec<_> = p
+        .glob(paths::home().join(".cargo/registry/cache/*/bar-1.0.0.crate.partial"))
+        .map(|p| p.unwrap())
+        .collect();
+    assert_eq!(partial.len(), 1);
+    assert!(fs::metadata(&partial[0]).unwrap().len() > 0);
+
+    *fail.lock().unwrap() = false;
+    p.cargo("fetch")
+        .with_stderr_data(str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+
+"#]])
+        .run();
+    assert!(!partial[0].exists());
+}
+
+#[cargo_test]
+fn dl_resume_corrupt_partial_file() {
+    let _server = RegistryBuilder::new().http_index().build();
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+
+    // Whatever is left over is resumed from, but can't be trusted.
+    let cache = paths::home().join(".cargo/registry/cache");
+    fs::create_dir_all(&cache).unwrap();
+    let index = p
+        .glob(paths::home().join(".cargo/registry/index/*"))
+        .next()
+        .unwrap()
+        .unwrap();
+    let partial = cache
+        .join(index.file_name().unwrap())
+        .join("bar-1.0.0.crate.partial");
+    fs::create_dir_all(partial.parent().unwrap()).unwrap();
+    fs::write(&partial, "garbage").unwrap();
+
+    p.cargo("fetch").with_stderr_data(str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[WARNING] failed to download replaced source registry `crates-io`: failed to verify the checksum of `bar v1.0.0 (registry `dummy-registry`)`
+the download was resumed from an earlier attempt, downloading `bar v1.0.0` again from scratch
+[DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+
+"#]]).run();
+    assert!(!partial.exists());
+}
+
 /// Creates a random prefix to randomly spread out the package names
 /// to somewhat evenly distribute the different failures at different
 /// points.
@@ -4269,3 +4442,394 @@ required by package `foo v0.0.1 ([ROOT]/foo)`
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn sparse_delta() {
+    // The `ETag` and contents of the index file of `bar` to send deltas from.
+    let base: Arc<Mutex<Option<(String, Vec<u8>)>>> = Arc::default();
+    let deltas = Arc::new(Mutex::new(0));
+    let _registry = RegistryBuilder::new()
+        .http_index()
+        .add_responder("/index/3/b/bar", {
+            let base = base.clone();
+            let deltas = deltas.clone();
+            move |req, server| {
+                let base = base.lock().unwrap();
+                let Some((etag, old)) = &*base else {
+                    return server.index(req);
+                };
+                if req.a_im.as_deref() != Some("cargo-index-delta")
+                    || req.if_none_match.as_deref() != Some(&format!("\"{etag}\""))
+                {
+                    return server.index(req);
+                }
+                *deltas.lock().unwrap() += 1;
+                let mut response = server.index(req);
+                response.code = 226;
+                response.headers.push("IM: cargo-index-delta".to_string());
+                response.body = response.body[old.len()..].to_vec();
+                response
+            }
+        })
+        .build();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = ">= 0.0.1"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    Package::new("bar", "0.0.1").publish();
+    p.cargo("generate-lockfile -Zsparse-delta")
+        .masquerade_as_nightly_cargo(&["sparse-delta"])
+        .run();
+
+    let set_base = || {
+        let old = fs::read(registry_path().join("3/b/bar")).unwrap();
+        let etag = cargo_util::Sha256::new().update(&old).finish_hex();
+        *base.lock().unwrap() = Some((etag, old));
+    };
+    set_base();
+    Package::new("bar", "0.0.2").publish();
+
+    p.cargo("update -Zsparse-delta")
+        .masquerade_as_nightly_cargo(&["sparse-delta"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v0.0.1 -> v0.0.2
+
+"#]])
+        .run();
+    assert_eq!(*deltas.lock().unwrap(), 1);
+
+    // The delta was merged with the cached versions.
+    let cache = paths::home()
+        .join(".cargo/registry/index")
+        .read_dir()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path()
+        .join(".cache/3/b/bar");
+    let cache = String::from_utf8_lossy(&fs::read(cache).unwrap()).into_owned();
+    assert!(cache.contains(r#""vers":"0.0.1""#));
+    assert!(cache.contains(r#""vers":"0.0.2""#));
+
+    // Without `-Zsparse-delta`, no delta is requested.
+    set_base();
+    Package::new("bar", "0.0.3").publish();
+    p.cargo("update")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v0.0.2 -> v0.0.3
+
+"#]])
+        .run();
+    assert_eq!(*deltas.lock().unwrap(), 1);
+}
+
+#[cargo_test]
+fn sparse_mirror_index_fallback() {
+    let mirror = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .no_configure_registry()
+        .build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.alternative]
+                    index = "sparse+http://127.0.0.1:1/index/"
+                    mirrors = ["{}"]
+                "#,
+                mirror.index_url()
+            ),
+        )
+        .build();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["registry mirrors"])
+        .env("CARGO_NET_RETRY", "0")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[WARNING] failed to download from `http://127.0.0.1:1/index/config.json`: [7] Couldn't connect to server ([..])
+falling back to mirror `http://127.0.0.1:[..]/index/`
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[CHECKING] bar v0.0.1 (registry `alternative`)
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    // The lock file keeps the source of the registry, not of its mirror.
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains(r#"source = "sparse+http://127.0.0.1:1/index/""#));
+
+    // Without `-Zunstable-options` the mirrors are ignored.
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `registries.alternative.mirrors` config, pass `-Zunstable-options` to enable it
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn sparse_mirror_download_fallback() {
+    let _registry = RegistryBuilder::new()
+        .http_index()
+        .add_responder("/dl/bar/0.0.1/download", |req, server| {
+            server.internal_server_error(req)
+        })
+        .build();
+    let mirror = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .no_configure_registry()
+        .build();
+    Package::new("bar", "0.0.1").publish();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.dummy-registry]
+                    mirrors = ["{}"]
+                "#,
+                mirror.index_url()
+            ),
+        )
+        .build();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["registry mirrors"])
+        .env("CARGO_NET_RETRY", "0")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[WARNING] failed to download from `http://127.0.0.1:[..]/dl/bar/0.0.1/download`: failed to get successful HTTP response from `http://127.0.0.1:[..]/dl/bar/0.0.1/download` (127.0.0.1), got 500
+body:
+internal server error
+trying mirror `http://127.0.0.1:[..]/dl/bar/0.0.1/download`
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+[CHECKING] bar v0.0.1
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn sparse_mirror_config_fetched_on_fallback_only() {
+    let _registry = RegistryBuilder::new().http_index().build();
+    let config_fetched = Arc::new(Mutex::new(false));
+    let mirror = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .no_configure_registry()
+        .add_responder("/index/config.json", {
+            let config_fetched = config_fetched.clone();
+            move |req, server| {
+                *config_fetched.lock().unwrap() = true;
+                server.internal_server_error(req)
+            }
+        })
+        .build();
+    Package::new("bar", "0.0.1").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [registries.dummy-registry]
+                    mirrors = ["{}"]
+                "#,
+                mirror.index_url()
+            ),
+        )
+        .build();
+
+    p.cargo("fetch -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["registry mirrors"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `dummy-registry`)
+
+"#]])
+        .run();
+
+    // Nothing failed, so the mirror was never contacted.
+    assert!(!*config_fetched.lock().unwrap());
+}
+
+/// Project using `bar` from the `alternative` registry, with `config` as its
+/// `.cargo/config.toml`.
+fn proxied_project(config: &str) -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = { version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(".cargo/config.toml", config)
+        .build()
+}
+
+#[cargo_test]
+fn sparse_registry_proxy() {
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    // The proxy of the registry connects directly to it, instead of using the
+    // unreachable proxy of `http.proxy`.
+    let p = proxied_project(
+        r#"
+            [http]
+            proxy = "127.0.0.1:1"
+
+            [registries.alternative]
+            proxy = ""
+        "#,
+    );
+
+    p.cargo("check")
+        .env("CARGO_NET_RETRY", "0")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `registries.alternative.proxy` config, pass `-Zunstable-options` to enable it
+[UPDATING] `alternative` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+...
+"#]])
+        .run();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["registry proxy"])
+        .env("CARGO_NET_RETRY", "0")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[CHECKING] bar v0.0.1 (registry `alternative`)
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn http_no_proxy() {
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    let p = proxied_project(
+        r#"
+            [http]
+            proxy = "127.0.0.1:1"
+            no-proxy = ["example.com", "127.0.0.1"]
+        "#,
+    );
+
+    p.cargo("check")
+        .env("CARGO_NET_RETRY", "0")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `http.no-proxy` config, pass `-Zunstable-options` to enable it
+[UPDATING] `alternative` index
+[ERROR] failed to get `bar` as a dependency of package `foo v0.0.1 ([ROOT]/foo)`
+...
+"#]])
+        .run();
+
+    p.cargo("check -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["http no-proxy"])
+        .env("CARGO_NET_RETRY", "0")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[CHECKING] bar v0.0.1 (registry `alternative`)
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
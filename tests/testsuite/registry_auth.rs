@@ -1,9 +1,16 @@
 //! Tests for registry authentication.
 
+use std::fs;
+
 use cargo_test_support::compare::assert_e2e;
-use cargo_test_support::registry::{Package, RegistryBuilder, Token};
+use cargo_test_support::paths;
+use cargo_test_support::registry::{Package, RegistryBuilder, Response, Token};
 use cargo_test_support::str;
 use cargo_test_support::{project, Execs, Project};
+use pasetors::keys::{AsymmetricPublicKey, AsymmetricSecretKey};
+use pasetors::token::UntrustedToken;
+use pasetors::version3::{PublicToken, V3};
+use pasetors::Public;
 
 fn cargo(p: &Project, s: &str) -> Execs {
     let mut e = p.cargo(s);
@@ -88,6 +95,44 @@ fn simple() {
         .run();
 }
 
+#[cargo_test]
+fn resume_corrupt_partial_file() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .auth_required()
+        .http_index()
+        .build();
+
+    let p = make_project();
+    cargo(&p, "generate-lockfile").run();
+
+    // The download from scratch after the resumed one fails still needs the
+    // token of the registry.
+    let index = p
+        .glob(paths::home().join(".cargo/registry/index/*"))
+        .next()
+        .unwrap()
+        .unwrap();
+    let partial = paths::home()
+        .join(".cargo/registry/cache")
+        .join(index.file_name().unwrap())
+        .join("bar-0.0.1.crate.partial");
+    fs::create_dir_all(partial.parent().unwrap()).unwrap();
+    fs::write(&partial, "garbage").unwrap();
+
+    cargo(&p, "fetch")
+        .with_stderr_data(str![[r#"
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[WARNING] failed to verify the checksum of `bar v0.0.1 (registry `alternative`)`
+the download was resumed from an earlier attempt, downloading `bar v0.0.1 (registry `alternative`)` again from scratch
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+
+"#]])
+        .run();
+    assert!(!partial.exists());
+}
+
 #[cargo_test]
 fn simple_with_asymmetric() {
     let _registry = RegistryBuilder::new()
@@ -460,6 +505,33 @@ fn login() {
         .run();
 }
 
+#[cargo_test]
+fn login_unstable_options() {
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .no_configure_token()
+        .auth_required()
+        .http_index()
+        .build();
+
+    // The `config.json` of the registry can't be fetched before logging in,
+    // which is left to the credential provider.
+    let p = make_project();
+    cargo(&p, "login --registry alternative -Zunstable-options")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[UPDATING] `alternative` index
+please paste the token found on https://test-registry-login/me below
+[ERROR] credential provider `cargo:token` failed action `login`
+
+Caused by:
+  please provide a non-empty token
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn login_existing_token() {
     let _registry = RegistryBuilder::new()
@@ -570,3 +642,49 @@ fn token_not_logged() {
     assert_eq!(authorizations.len(), 7);
     assert!(!log.contains("a-unique_token"));
 }
+
+#[cargo_test]
+fn asymmetric_challenge() {
+    // The challenge of a rejected request is signed in the next token.
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .auth_required()
+        .http_index()
+        .token(Token::rfc_key())
+        .add_responder("/index/config.json", |req, server| {
+            let Some(authorization) = &req.authorization else {
+                return Response {
+                    code: 401,
+                    headers: vec![
+                        r#"WWW-Authenticate: Cargo login_url="https://test-registry-login/me", challenge="c4ll3ng3""#
+                            .to_string(),
+                    ],
+                    body: b"Unauthorized message from server.".to_vec(),
+                };
+            };
+            let Token::Keys(secret, _) = Token::rfc_key() else {
+                unreachable!()
+            };
+            let secret: AsymmetricSecretKey<V3> = secret.as_str().try_into().unwrap();
+            let public: AsymmetricPublicKey<V3> = (&secret).try_into().unwrap();
+            let token = UntrustedToken::<Public, V3>::try_from(authorization.as_str()).unwrap();
+            let token = PublicToken::verify(&public, &token, None, None).unwrap();
+            assert!(token.payload().contains(r#""challenge":"c4ll3ng3""#));
+            server.index(req)
+        })
+        .build();
+
+    let p = make_project();
+    cargo(&p, "build")
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.0.1 (registry `alternative`)
+[COMPILING] bar v0.0.1 (registry `alternative`)
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -0,0 +1,115 @@
+//! Tests for the `registry-remap` config table.
+
+use cargo_test_support::registry::{self, Package};
+use cargo_test_support::{project, str, Project};
+
+fn remap_project() -> Project {
+    registry::alt_init();
+    Package::new("bar", "1.0.0").publish();
+    Package::new("bar", "1.0.0").alternative(true).publish();
+    Package::new("bar", "1.2.0").alternative(true).publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn ignored_without_flag() {
+    let p = remap_project();
+
+    p.cargo("generate-lockfile")
+        .arg("--config")
+        .arg("registry-remap.crates-io='alternative'")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+
+"#]])
+        .run();
+    assert!(p
+        .read_lockfile()
+        .contains("source = \"registry+https://github.com/rust-lang/crates.io-index\""));
+}
+
+#[cargo_test]
+fn resolves_against_remapped_registry() {
+    let p = remap_project();
+
+    p.cargo("generate-lockfile").run();
+    Package::new("bar", "1.1.0").publish();
+
+    // The version locked from crates.io is kept, as the remapped registry has it.
+    p.cargo("check -Zregistry-remap")
+        .arg("--config")
+        .arg("registry-remap.crates-io='alternative'")
+        .masquerade_as_nightly_cargo(&["registry-remap"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `alternative` index
+[LOCKING] 1 package to latest compatible version
+[ADDING] bar v1.0.0 (registry `alternative`) (latest: v1.2.0)
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.0.0 (registry `alternative`)
+[CHECKING] bar v1.0.0 (registry `alternative`)
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    let lock = p.read_lockfile();
+    assert!(lock.contains("alternative-registry"), "{lock}");
+
+    p.cargo("update -Zregistry-remap")
+        .arg("--config")
+        .arg("registry-remap.crates-io='alternative'")
+        .masquerade_as_nightly_cargo(&["registry-remap"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `[ROOT]/alternative-registry` index
+[LOCKING] 1 package to latest compatible version
+[UPDATING] bar v1.0.0 (registry `[ROOT]/alternative-registry`) -> v1.2.0
+
+"#]])
+        .run();
+
+    // Flipping the config back resolves against crates.io again.
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 1 package to latest compatible version
+[ADDING] bar v1.1.0
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.1.0 (registry `dummy-registry`)
+[CHECKING] bar v1.1.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn remap_to_itself() {
+    let p = remap_project();
+
+    p.cargo("check -Zregistry-remap")
+        .arg("--config")
+        .arg("registry-remap.alternative='alternative'")
+        .masquerade_as_nightly_cargo(&["registry-remap"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `registry-remap.alternative` cannot remap a registry to itself
+
+"#]])
+        .run();
+}
@@ -5,6 +5,7 @@ use cargo_test_support::install::{
 };
 use cargo_test_support::is_nightly;
 use cargo_test_support::paths::CargoPathExt;
+use cargo_test_support::prelude::*;
 use cargo_test_support::project;
 use cargo_test_support::str;
 
@@ -235,7 +236,8 @@ fn build_example_multiple_required_features() {
         .with_status(101)
         .with_stderr_data(str![[r#"
 [ERROR] target `foo_1` in package `foo` requires the features: `b`, `c`
-Consider enabling them by passing, e.g., `--features="b c"`
+missing features: `c`
+Consider enabling them by passing, e.g., `--features="c"`
 
 "#]])
         .run();
@@ -1629,3 +1631,141 @@ Consider enabling some of the needed features by passing, e.g., `--features="fea
 
 "#]]).run();
 }
+
+#[cargo_test]
+fn missing_features_are_listed() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2021"
+
+                [features]
+                a = []
+                b = []
+
+                [[bin]]
+                name = "foo_1"
+                path = "src/foo_1.rs"
+                required-features = ["a", "b"]
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("src/foo_1.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build --bin foo_1 --features a")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] target `foo_1` in package `foo` requires the features: `a`, `b`
+missing features: `b`
+Consider enabling them by passing, e.g., `--features="b"`
+
+"#]])
+        .run();
+
+    p.cargo("build -v --features a")
+        .with_stderr_data(str![[r#"
+[NOTE] skipping target `foo_1` in package `foo`, it requires the missing features: `b` (enable them with `--features="b"`)
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn all_required_features() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo", "bar"]
+                resolver = "2"
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2021"
+
+                [features]
+                a = []
+                b = []
+                c = []
+
+                [[bin]]
+                name = "foo_1"
+                path = "src/foo_1.rs"
+                required-features = ["a"]
+
+                [[example]]
+                name = "ex"
+                required-features = ["c"]
+            "#,
+        )
+        .file("foo/src/main.rs", "fn main() {}")
+        .file("foo/src/foo_1.rs", "fn main() {}")
+        .file("foo/examples/ex.rs", "fn main() {}")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.0.1"
+                edition = "2021"
+
+                [features]
+                a = []
+
+                [[bin]]
+                name = "bar_1"
+                path = "src/bar_1.rs"
+                required-features = ["a"]
+            "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .file("bar/src/bar_1.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build -Zunstable-options --all-required-features -p foo -v")
+        .masquerade_as_nightly_cargo(&["all-required-features"])
+        .with_stderr_data(
+            str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] foo v0.0.1 ([ROOT]/foo/foo)
+[RUNNING] `rustc --crate-name foo_1 [..]--cfg 'feature="a"' [..]`
+[RUNNING] `rustc --crate-name foo [..]--cfg 'feature="a"' [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(p.bin("foo_1").is_file());
+    assert!(!p.bin("bar_1").is_file());
+
+    // Only the features of the selected targets are enabled.
+    p.cargo("build -Zunstable-options --all-required-features --example ex --workspace")
+        .masquerade_as_nightly_cargo(&["all-required-features"])
+        .run();
+    assert!(p.bin("examples/ex").is_file());
+    assert!(!p.bin("bar_1").is_file());
+
+    p.cargo("build --all-required-features")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--all-required-features` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
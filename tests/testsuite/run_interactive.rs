@@ -0,0 +1,137 @@
+//! Tests for how `cargo run` selects the target to run, and for
+//! `cargo run --interactive`.
+
+use cargo_test_support::{project, str, Project};
+
+fn servers_project() -> Project {
+    project()
+        .file("src/lib.rs", "")
+        .file(
+            "src/bin/server.rs",
+            r#"fn main() { println!("hello server"); }"#,
+        )
+        .file(
+            "src/bin/server-admin.rs",
+            r#"fn main() { println!("hello server-admin"); }"#,
+        )
+        .file(
+            "src/bin/client.rs",
+            r#"fn main() { println!("hello client"); }"#,
+        )
+        .build()
+}
+
+#[cargo_test]
+fn suggests_several_close_names() {
+    let p = servers_project();
+
+    p.cargo("run --bin serv")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] no bin target named `serv`
+
+	Did you mean one of `server`, `server-admin`?
+
+"#]])
+        .run();
+
+    p.cargo("run --bin admin")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] no bin target named `admin`
+
+	Did you mean `server-admin`?
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = servers_project();
+
+    p.cargo("run --interactive")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--interactive` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn choose_among_binaries() {
+    let p = servers_project();
+
+    p.cargo("run -Zunstable-options --interactive")
+        .masquerade_as_nightly_cargo(&["interactive"])
+        .with_stdin("2\n")
+        .with_stdout_data(str![[r#"
+hello server
+
+"#]])
+        .with_stderr_data(str![[r#"
+Which bin target do you want to run?
+  1) client
+  2) server
+  3) server-admin
+> [COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `target/debug/server[EXE]`
+
+"#]])
+        .run();
+
+    p.cargo("run -Zunstable-options --interactive")
+        .masquerade_as_nightly_cargo(&["interactive"])
+        .with_stdin("client\n")
+        .with_stdout_data(str![[r#"
+hello client
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn choose_among_close_names() {
+    let p = servers_project();
+
+    p.cargo("run -Zunstable-options --interactive --bin serv")
+        .masquerade_as_nightly_cargo(&["interactive"])
+        .with_stdin("2\n")
+        .with_stdout_data(str![[r#"
+hello server-admin
+
+"#]])
+        .with_stderr_data(str![[r#"
+[WARNING] no bin target named `serv`
+Which bin target do you want to run?
+  1) server
+  2) server-admin
+> [COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `target/debug/server-admin[EXE]`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_choice() {
+    let p = servers_project();
+
+    p.cargo("run -Zunstable-options --interactive")
+        .masquerade_as_nightly_cargo(&["interactive"])
+        .with_stdin("4\n")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+Which bin target do you want to run?
+  1) client
+  2) server
+  3) server-admin
+> [ERROR] `4` is not one of the listed bin targets
+
+"#]])
+        .run();
+}
@@ -0,0 +1,235 @@
+//! Tests for `-Zrunner-composition`.
+
+use cargo_test_support::{project, rustc_host, str, Project};
+
+fn runner_project(config: &str) -> Project {
+    project()
+        .file("src/main.rs", r#"fn main() { println!("hello"); }"#)
+        .file("tests/t.rs", "")
+        .file(".cargo/config.toml", config)
+        .build()
+}
+
+#[cargo_test]
+fn chain_named_runners() {
+    let p = runner_project(&format!(
+        r#"
+            [runners]
+            qemu = ["qemu-arm", "-L", "/sysroot"]
+            adapter = "tools/adapter --format=json"
+
+            [target.{}]
+            runner = ["@adapter", "@qemu", "--verbose"]
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run -Zrunner-composition -- --param")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/foo/tools/adapter --format=json qemu-arm -L /sysroot --verbose target/debug/foo[EXE] --param`
+[ERROR] could not execute process `[ROOT]/foo/tools/adapter --format=json qemu-arm -L /sysroot --verbose target/debug/foo[EXE] --param` (never executed)
+
+Caused by:
+  [NOT_FOUND]
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn chain_in_cfg_runner() {
+    let p = runner_project(
+        r#"
+            [runners]
+            inner = "inner-runner"
+            outer = ["outer-runner", "@inner"]
+
+            [target.'cfg(not(target_os = "none"))']
+            runner = "@outer -x"
+        "#,
+    );
+
+    p.cargo("test -Zrunner-composition --test t")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] tests/t.rs (target/debug/deps/t-[HASH][EXE])
+[ERROR] test failed, to rerun pass `--test t`
+
+Caused by:
+  could not execute process `outer-runner inner-runner -x [ROOT]/foo/target/debug/deps/t-[HASH][EXE]` (never executed)
+
+Caused by:
+  [NOT_FOUND]
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn references_are_literal_without_flag() {
+    let p = runner_project(&format!(
+        r#"
+            [runners]
+            qemu = "qemu-arm"
+
+            [target.{}]
+            runner = ["@qemu", "{{artifact}}"]
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `@qemu '{artifact}' target/debug/foo[EXE]`
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn escaped_reference() {
+    let p = runner_project(&format!(
+        r#"
+            [target.{}]
+            runner = ["nonexistent-runner", "@@file"]
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run -Zrunner-composition")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `nonexistent-runner '@file' target/debug/foo[EXE]`
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn artifact_placeholder() {
+    let p = runner_project(&format!(
+        r#"
+            [target.{}]
+            runner = ["nonexistent-runner", "--exe={{artifact}}", "--"]
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run -Zrunner-composition -- --param")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `nonexistent-runner --exe=target/debug/foo[EXE] -- --param`
+...
+"#]])
+        .run();
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn artifact_env_var() {
+    let p = runner_project(&format!(
+        r#"
+            [target.{}]
+            runner = ["sh", "-c", 'echo "artifact: $CARGO_RUNNER_ARTIFACT" && exec "$0"']
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run -Zrunner-composition")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_stdout_data(str![[r#"
+artifact: target/debug/foo
+hello
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn unknown_runner() {
+    let p = runner_project(&format!(
+        r#"
+            [target.{}]
+            runner = "@missing"
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run -Zrunner-composition")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] runner `@missing` in the runner defined in [ROOT]/foo/.cargo/config.toml was not found, expected a `runners.missing` config value
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn runner_cycle() {
+    let p = runner_project(&format!(
+        r#"
+            [runners]
+            a = "@b"
+            b = ["runner-b", "@a"]
+
+            [target.{}]
+            runner = "@a"
+        "#,
+        rustc_host()
+    ));
+
+    p.cargo("run -Zrunner-composition")
+        .masquerade_as_nightly_cargo(&["runner-composition"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] runner `@a` refers to itself: `@a` -> `@b` -> `@a`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn doctests_cannot_use_artifact_placeholder() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{}]
+                    runner = ["nonexistent-runner", "{{artifact}}"]
+                "#,
+                rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("test -Zrunner-composition -Zdoctest-xcompile --doc")
+        .masquerade_as_nightly_cargo(&["runner-composition", "doctest-xcompile"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[DOCTEST] foo
+[ERROR] the runner of doctests cannot use `{artifact}`, as rustdoc passes the doctest as its last argument
+
+"#]])
+        .run();
+}
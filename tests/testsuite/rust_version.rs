@@ -165,8 +165,8 @@ fn lint_dep_incompatible_with_rust_version() {
             "\
 [DOWNLOADING] crates ...
 [DOWNLOADED] too_new_parent v0.0.1 (registry `[..]`)
-[DOWNLOADED] too_new_child v0.0.1 (registry `[..]`)
 [DOWNLOADED] rustc_compatible v0.0.1 (registry `[..]`)
+[DOWNLOADED] too_new_child v0.0.1 (registry `[..]`)
 [ERROR] rustc [..] is not supported by the following packages:
   too_new_child@0.0.1 requires rustc 1.2345.0
   too_new_parent@0.0.1 requires rustc 1.2345.0
@@ -180,6 +180,101 @@ where `<compatible-ver>` is the latest version supporting rustc [..]
     p.cargo("check --ignore-rust-version").run();
 }
 
+#[cargo_test]
+fn lint_workspace_incompatible_with_rust_version() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["foo"]
+            [workspace.package]
+            rust-version = "1.9876.0"
+        "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+            authors = []
+        "#,
+        )
+        .file("foo/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[WARNING] rustc [..] is not supported by the workspace at `[ROOT]/foo/Cargo.toml`
+  `workspace.package.rust-version` requires rustc 1.9876.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo/foo)
+[FINISHED] [..]
+",
+        )
+        .run();
+    p.cargo("check --ignore-rust-version")
+        .with_stderr("[FINISHED] [..]")
+        .run();
+}
+
+#[cargo_test]
+fn lint_member_newer_than_workspace_rust_version() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["foo", "bar"]
+            [workspace.package]
+            rust-version = "1.60"
+        "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+            authors = []
+            rust-version = "1.70"
+        "#,
+        )
+        .file("foo/src/main.rs", "fn main() {}")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            [package]
+            name = "bar"
+            version = "0.0.1"
+            edition = "2015"
+            authors = []
+            rust-version.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check")
+        .with_stderr_unordered(
+            "\
+[LOCKING] 2 packages to latest compatible versions
+[WARNING] `workspace.package.rust-version` of `[ROOT]/foo/Cargo.toml` is rustc 1.60, but the following member requires a newer rustc:
+  foo@0.0.1 requires rustc 1.70
+Either raise `workspace.package.rust-version` or lower the `rust-version` of these members
+[CHECKING] foo v0.0.1 ([ROOT]/foo/foo)
+[CHECKING] bar v0.0.1 ([ROOT]/foo/bar)
+[FINISHED] [..]
+",
+        )
+        .run();
+    p.cargo("check -p bar").run();
+    p.cargo("check --ignore-rust-version").run();
+}
+
 #[cargo_test]
 fn resolve_with_rust_version() {
     Package::new("only-newer", "1.6.0")
@@ -1,6 +1,6 @@
 use cargo_test_support::basic_manifest;
 use cargo_test_support::prelude::*;
-use cargo_test_support::registry::Package;
+use cargo_test_support::registry::{Package, RegistryBuilder};
 use cargo_test_support::str;
 
 const ECHO_SCRIPT: &str = r#"#!/usr/bin/env cargo
@@ -920,6 +920,50 @@ fn cmd_check_with_embedded() {
         .run();
 }
 
+#[cargo_test]
+fn cmd_check_with_embedded_manifest() {
+    let script = r#"#!/usr/bin/env cargo
+---
+package.edition = "2021"
+---
+
+fn main() {
+    let unused = 1;
+}
+"#;
+    let p = cargo_test_support::project()
+        .file("script.rs", script)
+        .build();
+
+    // Diagnostics point at the script, not at the copy of it that is built.
+    p.cargo("-Zscript check --manifest-path script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[CHECKING] script v0.0.0 ([ROOT]/foo)
+[WARNING] unused variable: `unused`
+ --> script.rs:7:9
+  |
+7 |     let unused = 1;
+  |         ^^^^^^ [HELP] if this is intentional, prefix it with an underscore: `_unused`
+  |
+  = [NOTE] `#[warn(unused_variables)]` (part of `#[warn(unused)]`) on by default
+
+[WARNING] `script` (bin "script") generated 1 warning (run `cargo fix --bin "script"` to apply 1 suggestion)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.cargo("-Zscript fix --manifest-path script.rs --allow-no-vcs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `cargo fix` does not support single-file packages
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn cmd_check_with_missing_script_rs() {
     let p = cargo_test_support::project().build();
@@ -1124,6 +1168,86 @@ fn cmd_metadata_with_embedded() {
         .run();
 }
 
+#[cargo_test]
+fn cmd_metadata_with_embedded_manifest() {
+    let script = r#"#!/usr/bin/env cargo
+---
+package.edition = "2021"
+---
+
+fn main() {}
+"#;
+    let p = cargo_test_support::project()
+        .file("script.rs", script)
+        .build();
+
+    // Tools get the script, not the copy of it that is built.
+    p.cargo("-Zscript metadata --manifest-path script.rs --format-version=1 --no-deps")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "metadata": null,
+  "packages": [
+    {
+      "authors": [],
+      "categories": [],
+      "default_run": null,
+      "dependencies": [],
+      "description": null,
+      "documentation": null,
+      "edition": "2021",
+      "features": {},
+      "homepage": null,
+      "id": "path+[ROOTURL]/foo#script@0.0.0",
+      "keywords": [],
+      "license": null,
+      "license_file": null,
+      "links": null,
+      "manifest_path": "[ROOT]/foo/script.rs",
+      "metadata": null,
+      "name": "script",
+      "publish": [],
+      "readme": null,
+      "repository": null,
+      "rust_version": null,
+      "source": null,
+      "targets": [
+        {
+          "crate_types": [
+            "bin"
+          ],
+          "doc": true,
+          "doctest": false,
+          "edition": "2021",
+          "kind": [
+            "bin"
+          ],
+          "name": "script",
+          "src_path": "[ROOT]/foo/script.rs",
+          "test": true
+        }
+      ],
+      "version": "0.0.0"
+    }
+  ],
+  "resolve": null,
+  "target_directory": "[ROOT]/home/.cargo/target/[HASH]",
+  "version": 1,
+  "workspace_default_members": [
+    "path+[ROOTURL]/foo#script@0.0.0"
+  ],
+  "workspace_members": [
+    "path+[ROOTURL]/foo#script@0.0.0"
+  ],
+  "workspace_root": "[ROOT]/foo"
+}
+"#]]
+            .json(),
+        )
+        .run();
+}
+
 #[cargo_test]
 fn cmd_read_manifest_with_embedded() {
     let script = ECHO_SCRIPT;
@@ -1317,3 +1441,194 @@ fn cmd_publish_with_embedded() {
 "#]])
         .run();
 }
+
+/// The number of single-file packages with a build cached in
+/// `$CARGO_HOME/target`.
+fn cached_script_dirs() -> usize {
+    let cache = cargo_test_support::paths::home().join(".cargo/target");
+    std::fs::read_dir(cache)
+        .unwrap()
+        .map(|dir| std::fs::read_dir(dir.unwrap().path()).unwrap().count())
+        .sum()
+}
+
+#[cargo_test]
+fn cache_keyed_on_embedded_manifest() {
+    Package::new("dep", "1.0.0").publish();
+    let script = r#"#!/usr/bin/env cargo
+---
+[package]
+edition = "2021"
+
+[dependencies]
+dep = "1.0.0"
+---
+
+fn main() {}"#;
+    let p = cargo_test_support::project()
+        .file("script.rs", script)
+        .build();
+
+    p.cargo("-Zscript -v script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.0.0 (registry `dummy-registry`)
+[COMPILING] dep v1.0.0
+[COMPILING] script v0.0.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/home/.cargo/target/[HASH]/debug/script[EXE]`
+
+"#]])
+        .run();
+
+    // Changing the code reuses the lock file and the build of `dep`.
+    p.change_file(
+        "script.rs",
+        &script.replace("fn main() {}", "fn main() { }"),
+    );
+    p.cargo("-Zscript -v script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[COMPILING] script v0.0.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/home/.cargo/target/[HASH]/debug/script[EXE]`
+
+"#]])
+        .run();
+    assert_eq!(cached_script_dirs(), 1);
+
+    // Changing the manifest gets a new cache.
+    Package::new("dep", "1.0.1").publish();
+    p.change_file(
+        "script.rs",
+        &script.replace(r#"dep = "1.0.0""#, r#"dep = "1.0""#),
+    );
+    p.cargo("-Zscript -v script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.0.1 (registry `dummy-registry`)
+[COMPILING] dep v1.0.1
+[COMPILING] script v0.0.0 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/home/.cargo/target/[HASH]/debug/script[EXE]`
+
+"#]])
+        .run();
+    assert_eq!(cached_script_dirs(), 2);
+
+    // Changing it back reuses the first one.
+    p.change_file(
+        "script.rs",
+        &script.replace("fn main() {}", "fn main() { }"),
+    );
+    p.cargo("-Zscript -v script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/home/.cargo/target/[HASH]/debug/script[EXE]`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn clean_cache() {
+    let script = r#"#!/usr/bin/env cargo
+---
+package.edition = "2021"
+---
+
+fn main() {}"#;
+    let p = cargo_test_support::project()
+        .file("a.rs", script)
+        .file("b.rs", script)
+        .build();
+
+    for script in ["a.rs", "b.rs"] {
+        p.cargo(&format!("-Zscript -q {script}"))
+            .masquerade_as_nightly_cargo(&["script"])
+            .run();
+    }
+    p.change_file("a.rs", &script.replace("2021", "2018"));
+    p.cargo("-Zscript -q a.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .run();
+    std::fs::remove_file(p.root().join("b.rs")).unwrap();
+    // Not created for a script, so it's left alone.
+    let unrelated = cargo_test_support::paths::home().join(".cargo/target/xy/unrelated");
+    std::fs::create_dir_all(&unrelated).unwrap();
+    std::fs::write(unrelated.join("file"), "").unwrap();
+    assert_eq!(cached_script_dirs(), 4);
+
+    p.cargo("clean --clean-cache")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--clean-cache` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+See https://github.com/rust-lang/cargo/issues/12207 for more information about the `--clean-cache` flag.
+
+"#]])
+        .run();
+
+    // The first build of `a.rs` and the build of `b.rs` are out of date.
+    p.cargo("clean -Zscript --clean-cache")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[REMOVED] [FILE_NUM] files, [FILE_SIZE]B total
+
+"#]])
+        .run();
+    assert_eq!(cached_script_dirs(), 2);
+    assert!(unrelated.join("file").exists());
+
+    p.cargo("-Zscript -v a.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/home/.cargo/target/[HASH]/debug/a[EXE]`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn cache_reused_without_index() {
+    let registry = RegistryBuilder::new().http_index().build();
+    Package::new("dep", "1.0.0").publish();
+    let p = cargo_test_support::project()
+        .file(
+            "script.rs",
+            r#"#!/usr/bin/env cargo
+---
+[package]
+edition = "2021"
+
+[dependencies]
+dep = "1.0.0"
+---
+
+fn main() {}"#,
+        )
+        .build();
+
+    p.cargo("-Zscript -q script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .run();
+
+    // Running it again neither resolves nor updates the index.
+    drop(registry);
+    p.cargo("-Zscript -v script.rs")
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/home/.cargo/target/[HASH]/debug/script[EXE]`
+
+"#]])
+        .run();
+}
@@ -0,0 +1,246 @@
+//! Tests for the `[source-policy]` config.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, git, project, str, Project};
+
+/// A workspace whose member `foo` has a git dependency, a path dependency
+/// outside the workspace, and path dependencies on another member.
+fn workspace(policy: &str) -> Project {
+    let git = git::new("gitdep", |p| {
+        p.file("Cargo.toml", &basic_manifest("gitdep", "1.0.0"))
+            .file("src/lib.rs", "")
+    });
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [workspace]
+                    members = ["foo", "member"]
+
+                    [package]
+                    name = "root"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    member = {{ path = "member", version = "0.1.0" }}
+
+                    [patch.crates-io]
+                    patched = {{ git = "{}", package = "gitdep" }}
+                "#,
+                git.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "foo/Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+                    license = "MIT"
+                    description = "foo"
+
+                    [dependencies]
+                    gitdep = {{ git = "{}", version = "1.0.0" }}
+                    member = {{ path = "../member", version = "0.1.0" }}
+
+                    [target.'cfg(unix)'.build-dependencies]
+                    outside = {{ path = "../../outside", version = "0.1.0" }}
+
+                    [dev-dependencies]
+                    helper = {{ path = "../../outside", package = "outside" }}
+                "#,
+                git.url()
+            ),
+        )
+        .file("foo/src/lib.rs", "")
+        .file("member/Cargo.toml", &basic_manifest("member", "0.1.0"))
+        .file("member/src/lib.rs", "")
+        .file(".cargo/config.toml", policy)
+        .build()
+}
+
+fn outside() {
+    project()
+        .at("outside")
+        .file("Cargo.toml", &basic_manifest("outside", "0.1.0"))
+        .file("src/lib.rs", "")
+        .build();
+}
+
+#[cargo_test]
+fn forbid_in_profile() {
+    outside();
+    let p = workspace(
+        r#"
+            [source-policy]
+            profiles = ["release"]
+        "#,
+    );
+
+    p.cargo("check --release -Zsource-policy")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `source-policy` config forbids git and path dependencies in builds with the `release` profile:
+  `gitdep` is a git dependency, in `[dependencies]` of `[ROOT]/foo/foo/Cargo.toml`
+  `outside` is a path dependency on `[ROOT]/outside`, outside the workspace, in `[target.'cfg(unix)'.build-dependencies]` of `[ROOT]/foo/foo/Cargo.toml`
+  `patched` is a git dependency, in `[patch.crates-io]` of `[ROOT]/foo/Cargo.toml`
+
+[HELP] depend on versions from a registry instead
+
+"#]])
+        .run();
+
+    // Other profiles aren't checked.
+    p.cargo("check -Zsource-policy")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_stderr_data(str![[r#"
+[UPDATING] git repository `[ROOTURL]/gitdep`
+...
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn forbid_git_only() {
+    outside();
+    let p = workspace(
+        r#"
+            [source-policy]
+            forbid = ["git"]
+            profiles = ["dev"]
+        "#,
+    );
+
+    p.cargo("check -Zsource-policy")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+...
+[ERROR] the `source-policy` config forbids git dependencies in builds with the `dev` profile:
+  `gitdep` is a git dependency, in `[dependencies]` of `[ROOT]/foo/foo/Cargo.toml`
+  `patched` is a git dependency, in `[patch.crates-io]` of `[ROOT]/foo/Cargo.toml`
+
+[HELP] depend on versions from a registry instead
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn patch_from_config() {
+    Package::new("bar", "1.0.0").publish();
+    outside();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [source-policy]
+                profiles = ["release"]
+
+                [patch.crates-io]
+                bar = { path = "../outside" }
+            "#,
+        )
+        .build();
+
+    p.cargo("check --release -Zsource-policy")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+...
+[ERROR] the `source-policy` config forbids git and path dependencies in builds with the `release` profile:
+  `bar` is a path dependency on `[ROOT]/outside`, outside the workspace, in `[patch.crates-io]` of the `patch` config
+
+[HELP] depend on versions from a registry instead
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn forbid_on_package() {
+    outside();
+    let p = workspace(
+        r#"
+            [source-policy]
+            package = true
+        "#,
+    );
+
+    p.cargo("package -p foo --no-verify -Zsource-policy")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[ERROR] the `source-policy` config forbids git and path dependencies when packaging `foo v0.1.0 ([ROOT]/foo/foo)`:
+  `gitdep` is a git dependency, in `[dependencies]` of `[ROOT]/foo/foo/Cargo.toml`
+  `outside` is a path dependency on `[ROOT]/outside`, outside the workspace, in `[target.'cfg(unix)'.build-dependencies]` of `[ROOT]/foo/foo/Cargo.toml`
+
+[HELP] depend on versions from a registry instead
+
+"#]])
+        .run();
+
+    // Packaging another member only checks that member, and builds aren't
+    // checked at all.
+    p.cargo("package --no-verify -Zsource-policy")
+        .cwd("member")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_stderr_data(str![[r#"
+[WARNING] manifest has no description, license, license-file, documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[PACKAGING] member v0.1.0 ([ROOT]/foo/member)
+[PACKAGED] 3 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+
+"#]])
+        .run();
+    p.cargo("check --release -Zsource-policy")
+        .masquerade_as_nightly_cargo(&["source-policy"])
+        .with_stderr_data(str![[r#"
+...
+[FINISHED] `release` profile [optimized] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    outside();
+    let p = workspace(
+        r#"
+            [source-policy]
+            profiles = ["dev"]
+        "#,
+    );
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+...
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -0,0 +1,184 @@
+//! Tests for `workspace.split-target-dirs`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project, str, Project};
+
+fn split_workspace() -> Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+                split-target-dirs = true
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = split_workspace();
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] [ROOT]/foo/Cargo.toml: ignoring `workspace.split-target-dirs`, pass `-Zsplit-target-dirs` to enable
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] [..] v0.1.0 ([ROOT]/foo/[..])
+[CHECKING] [..] v0.1.0 ([ROOT]/foo/[..])
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.cargo("check").cwd("a").run();
+    assert!(!p.root().join("target/members").exists());
+}
+
+#[cargo_test]
+fn member_builds_into_own_dir() {
+    let p = split_workspace();
+
+    p.cargo("build -Zsplit-target-dirs")
+        .cwd("a")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] a v0.1.0 ([ROOT]/foo/a)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.root().join("target/members/a/debug/liba.rlib").is_file());
+    assert!(!p.root().join("target/debug/liba.rlib").exists());
+
+    // The directory follows the package being built, not the current one.
+    p.cargo("build -p b -Zsplit-target-dirs")
+        .cwd("a")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .with_stderr_data(str![[r#"
+[COMPILING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.root().join("target/members/b/debug/libb.rlib").is_file());
+
+    // From the workspace root, each member still uses its own directory.
+    p.cargo("build --workspace -Zsplit-target-dirs")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(!p.root().join("target/debug/liba.rlib").exists());
+    assert!(!p.root().join("target/debug/libb.rlib").exists());
+
+    p.cargo("clean -p a -Zsplit-target-dirs")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .run();
+    assert!(!p.root().join("target/members/a").exists());
+    assert!(p.root().join("target/members/b").is_dir());
+}
+
+#[cargo_test]
+fn dependencies_are_shared() {
+    Package::new("dep", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+                split-target-dirs = true
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "1.0"
+            "#,
+        )
+        .file("a/src/main.rs", "extern crate dep; fn main() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "1.0"
+            "#,
+        )
+        .file("b/src/main.rs", "extern crate dep; fn main() {}")
+        .build();
+
+    p.cargo("build -Zsplit-target-dirs")
+        .cwd("a")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 3 packages to latest compatible versions
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v1.0.0 (registry `dummy-registry`)
+[COMPILING] dep v1.0.0
+[COMPILING] a v0.1.0 ([ROOT]/foo/a)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(!p.bin("a").exists());
+    assert!(p
+        .root()
+        .join("target/members/a/debug")
+        .join(format!("a{}", std::env::consts::EXE_SUFFIX))
+        .is_file());
+
+    // `dep` is reused from the shared target directory.
+    p.cargo("build -Zsplit-target-dirs")
+        .cwd("b")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .with_stderr_data(str![[r#"
+[COMPILING] b v0.1.0 ([ROOT]/foo/b)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+
+    p.cargo("run -Zsplit-target-dirs")
+        .cwd("b")
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `[ROOT]/foo/target/members/b/debug/b[EXE]`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn configured_target_dir() {
+    let p = split_workspace();
+
+    // An explicit target directory is used as is.
+    p.cargo("build -Zsplit-target-dirs")
+        .cwd("a")
+        .env("CARGO_TARGET_DIR", p.root().join("custom"))
+        .masquerade_as_nightly_cargo(&["split-target-dirs"])
+        .run();
+    assert!(p.root().join("custom/debug/liba.rlib").is_file());
+    assert!(!p.root().join("custom/members").exists());
+}
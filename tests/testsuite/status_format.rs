@@ -0,0 +1,97 @@
+//! Tests for `--status-format`.
+
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str};
+
+#[cargo_test]
+fn json_status() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zunstable-options --status-format=json")
+        .masquerade_as_nightly_cargo(&["status-format"])
+        .with_stdout_data(str![[r#"
+{"reason":"build-status","status":"resolving"}
+{"downloaded":1,"package_id":"registry+https://github.com/rust-lang/crates.io-index#bar@0.1.0","reason":"build-status","status":"downloading","total":1}
+{"current":1,"fresh":false,"id":0,"mode":"check","package_id":"registry+https://github.com/rust-lang/crates.io-index#bar@0.1.0","reason":"build-status","status":"compiling","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"bar","src_path":"[ROOT]/home/.cargo/registry/src/-[HASH]/bar-0.1.0/src/lib.rs","test":true},"total":2}
+{"current":2,"fresh":false,"id":1,"mode":"check","package_id":"path+[ROOTURL]/foo#0.1.0","reason":"build-status","status":"compiling","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"foo","src_path":"[ROOT]/foo/src/lib.rs","test":true},"total":2}
+{"reason":"build-status","status":"finished","success":true}
+
+"#]].json_lines())
+        .run();
+
+    // Fresh units are reported too, without resolving or downloading
+    // anything new.
+    p.cargo("check -Zunstable-options --status-format=json")
+        .masquerade_as_nightly_cargo(&["status-format"])
+        .with_stdout_data(str![[r#"
+{"reason":"build-status","status":"resolving"}
+{"current":1,"fresh":true,"id":0,"mode":"check","package_id":"registry+https://github.com/rust-lang/crates.io-index#bar@0.1.0","reason":"build-status","status":"compiling","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"bar","src_path":"[ROOT]/home/.cargo/registry/src/-[HASH]/bar-0.1.0/src/lib.rs","test":true},"total":2}
+{"current":2,"fresh":true,"id":1,"mode":"check","package_id":"path+[ROOTURL]/foo#0.1.0","reason":"build-status","status":"compiling","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"foo","src_path":"[ROOT]/foo/src/lib.rs","test":true},"total":2}
+{"reason":"build-status","status":"finished","success":true}
+
+"#]].json_lines())
+        .run();
+}
+
+#[cargo_test]
+fn json_status_with_message_format() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check -Zunstable-options --status-format=json --message-format=json")
+        .masquerade_as_nightly_cargo(&["status-format"])
+        .with_stdout_data(str![[r#"
+{"reason":"build-status","status":"resolving"}
+{"current":1,"fresh":false,"id":0,"mode":"check","package_id":"path+[ROOTURL]/foo#0.0.1","reason":"build-status","status":"compiling","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"foo","src_path":"[ROOT]/foo/src/lib.rs","test":true},"total":1}
+{"executable":null,"features":[],"filenames":["[ROOT]/foo/target/debug/deps/libfoo-[HASH].rmeta"],"fresh":false,"manifest_path":"[ROOT]/foo/Cargo.toml","package_id":"path+[ROOTURL]/foo#0.0.1","platform":null,"profile":{"debug_assertions":true,"debuginfo":2,"opt_level":"0","overflow_checks":true,"test":false},"reason":"compiler-artifact","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"foo","src_path":"[ROOT]/foo/src/lib.rs","test":true}}
+{"reason":"build-finished","success":true}
+{"reason":"build-status","status":"finished","success":true}
+
+"#]].json_lines())
+        .run();
+}
+
+#[cargo_test]
+fn json_status_on_failure() {
+    let p = project().file("src/lib.rs", "invalid").build();
+
+    p.cargo("check -Zunstable-options --status-format=json")
+        .masquerade_as_nightly_cargo(&["status-format"])
+        .with_status(101)
+        .with_stdout_data(str![[r#"
+{"reason":"build-status","status":"resolving"}
+{"current":1,"fresh":false,"id":0,"mode":"check","package_id":"path+[ROOTURL]/foo#0.0.1","reason":"build-status","status":"compiling","target":{"crate_types":["lib"],"doc":true,"doctest":true,"edition":"2015","kind":["lib"],"name":"foo","src_path":"[ROOT]/foo/src/lib.rs","test":true},"total":1}
+{"reason":"build-status","status":"finished","success":false}
+
+"#]].json_lines())
+        .run();
+}
+
+#[cargo_test]
+fn status_format_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("check --status-format=json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--status-format` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
@@ -0,0 +1,142 @@
+//! Tests for `-Ztarget-alias`.
+
+use cargo_test_support::{project, rustc_host, str};
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target-alias.native]
+                    triple = "{}"
+                "#,
+                rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("check --target native")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `target-alias.native` config, pass `-Ztarget-alias` to enable it
+[ERROR] failed to run `rustc` to learn about target-specific information
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn expands_with_settings() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            r#"
+                #![allow(unexpected_cfgs)]
+
+                #[cfg(not(from_alias))]
+                compile_error!("rustflags of the alias were not used");
+
+                // `[env]` sets it to "env" instead.
+                const _: [(); 5] = [(); env!("FROM_ALIAS").len()];
+
+                fn main() {}
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target.{target}]
+                    runner = "ignored-runner"
+                    rustflags = ["--cfg", "from_target"]
+
+                    [env]
+                    FROM_ALIAS = "env"
+
+                    [target-alias.native]
+                    triple = "{target}"
+                    runner = "nonexistent-runner -r"
+                    rustflags = ["--cfg", "from_alias"]
+                    env = {{ FROM_ALIAS = "alias" }}
+                "#,
+                target = rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("run -v -Ztarget-alias --target native")
+        .masquerade_as_nightly_cargo(&["target-alias"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]--target [HOST_TARGET] [..]--cfg from_alias`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] `nonexistent-runner -r target/[HOST_TARGET]/debug/foo[EXE]`
+[ERROR] could not execute process `nonexistent-runner -r target/[HOST_TARGET]/debug/foo[EXE]` (never executed)
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn build_target_config() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [build]
+                    target = "native"
+
+                    [target-alias.native]
+                    triple = "{}"
+                    linker = "nonexistent-linker"
+                "#,
+                rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("check -v -Ztarget-alias")
+        .masquerade_as_nightly_cargo(&["target-alias"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[RUNNING] `rustc --crate-name foo [..]--target [HOST_TARGET] [..]-C linker=nonexistent-linker [..]`
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn aliases_for_the_same_target() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [target-alias.one]
+                    triple = "{target}"
+
+                    [target-alias.two]
+                    triple = "{target}"
+                "#,
+                target = rustc_host()
+            ),
+        )
+        .build();
+
+    p.cargo("check -Ztarget-alias --target one --target two")
+        .masquerade_as_nightly_cargo(&["target-alias"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] target aliases `one` and `two` both stand for `[HOST_TARGET]`, only one of them can be used at a time
+
+"#]])
+        .run();
+}
@@ -4048,6 +4048,7 @@ fn json_artifact_includes_test_flag() {
             r#"
                 {
                     "reason":"compiler-artifact",
+                    "platform": null,
                     "profile": {
                         "debug_assertions": true,
                         "debuginfo": 2,
@@ -4096,6 +4097,7 @@ fn json_artifact_includes_executable_for_library_tests() {
                     "fresh": false,
                     "package_id": "path+file:///[..]/foo#0.0.1",
                     "manifest_path": "[..]",
+                    "platform": null,
                     "profile": "{...}",
                     "reason": "compiler-artifact",
                     "target": {
@@ -4135,6 +4137,7 @@ fn json_artifact_includes_executable_for_integration_tests() {
                     "fresh": false,
                     "package_id": "path+file:///[..]/foo#0.0.1",
                     "manifest_path": "[..]",
+                    "platform": null,
                     "profile": "{...}",
                     "reason": "compiler-artifact",
                     "target": {
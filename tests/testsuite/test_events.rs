@@ -0,0 +1,182 @@
+//! Tests for `cargo test --no-capture-json`.
+
+#![allow(deprecated)]
+
+use cargo_test_support::{basic_manifest, project, str};
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("test --no-capture-json --message-format=json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--no-capture-json` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn requires_json_message_format() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("test -Zunstable-options --no-capture-json")
+        .masquerade_as_nightly_cargo(&["no-capture-json"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `--no-capture-json` requires `--message-format=json`
+
+"#]])
+        .run();
+}
+
+#[cargo_test(nightly, reason = "the JSON output of libtest is unstable")]
+fn reports_test_events() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+                /// ```
+                /// assert!(true);
+                /// ```
+                pub fn f() {}
+
+                #[test]
+                fn passes() {}
+
+                #[test]
+                fn fails() {
+                    println!("some output");
+                    panic!();
+                }
+
+                #[test]
+                #[ignore = "too slow"]
+                fn ignored() {}
+            "#,
+        )
+        .build();
+
+    p.cargo("test -Zunstable-options --no-capture-json --message-format=json --no-fail-fast")
+        .masquerade_as_nightly_cargo(&["no-capture-json"])
+        .with_status(101)
+        .with_json_contains_unordered(
+            r#"
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "suite",
+              "event": "started",
+              "test_count": 3
+            }
+
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "test",
+              "event": "ok",
+              "name": "passes"
+            }
+
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "test",
+              "event": "failed",
+              "name": "fails",
+              "stdout": "{...}"
+            }
+
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "test",
+              "event": "ignored",
+              "name": "ignored",
+              "message": "too slow"
+            }
+
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "suite",
+              "event": "failed",
+              "passed": 1,
+              "failed": 1,
+              "ignored": 1,
+              "measured": 0,
+              "filtered_out": 0,
+              "exec_time": "{...}"
+            }
+
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "test",
+              "event": "ok",
+              "name": "src/lib.rs - f (line 2)"
+            }
+            "#,
+        )
+        .with_stderr_data(str![[r#"
+...
+[ERROR] test failed, to rerun pass `--lib`
+...
+"#]])
+        .run();
+}
+
+#[cargo_test(nightly, reason = "the JSON output of libtest is unstable")]
+fn other_output_goes_to_stderr() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file(
+            "tests/t.rs",
+            r#"
+                #[test]
+                fn prints() {
+                    println!("not an event");
+                }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo(
+        "test -Zunstable-options --no-capture-json --message-format=json --test t -- --nocapture",
+    )
+    .masquerade_as_nightly_cargo(&["no-capture-json"])
+    .with_json_contains_unordered(
+        r#"
+            {
+              "reason": "test-event",
+              "package_id": "path+file:///[..]/foo#0.0.1",
+              "manifest_path": "[..]/foo/Cargo.toml",
+              "target": "{...}",
+              "type": "test",
+              "event": "ok",
+              "name": "prints"
+            }
+            "#,
+    )
+    .with_stderr_data(str![[r#"
+...
+not an event
+...
+"#]])
+    .run();
+}
@@ -0,0 +1,118 @@
+//! Tests for `cargo test --test-manifest`.
+
+use cargo_test_support::{basic_manifest, project, str, t};
+use cargo_util::ProcessBuilder;
+use cargo_util_schemas::test_manifest::{TestManifest, TEST_MANIFEST_VERSION};
+
+#[cargo_test]
+fn gated() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("test --test-manifest tests.json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--test-manifest` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn lists_test_executables() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2018"
+
+                [[test]]
+                name = "custom"
+                harness = false
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("tests/custom.rs", "fn main() {}")
+        .file(
+            "tests/t.rs",
+            r#"
+                #[test]
+                fn reads_env() {
+                    assert_eq!(env!("CARGO_MANIFEST_DIR"), std::env::var("CARGO_MANIFEST_DIR").unwrap());
+                    assert_eq!(std::env::var("FROM_CONFIG").unwrap(), "yes");
+                }
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [env]
+                FROM_CONFIG = "yes"
+            "#,
+        )
+        .build();
+
+    p.cargo("test -Zunstable-options --no-run --test-manifest target/tests.json")
+        .masquerade_as_nightly_cargo(&["test-manifest"])
+        .run();
+
+    let manifest = p.read_file("target/tests.json");
+    let manifest: TestManifest = t!(serde_json::from_str(&manifest));
+    assert_eq!(manifest.version, TEST_MANIFEST_VERSION);
+    let names: Vec<_> = manifest
+        .executables
+        .iter()
+        .map(|e| (e.target_name.as_str(), e.target_kind.clone(), e.harness))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("foo", vec!["lib".to_string()], true),
+            ("custom", vec!["test".to_string()], false),
+            ("t", vec!["test".to_string()], true),
+        ]
+    );
+
+    // The recorded command runs the tests without Cargo.
+    let t = &manifest.executables[2];
+    assert_eq!(t.package_id.name(), "foo");
+    assert_eq!(t.manifest_path, p.root().join("Cargo.toml"));
+    assert_eq!(t.kind, None);
+    assert_eq!(t.cwd.as_deref(), Some(p.root().as_path()));
+    assert_eq!(t.program, t.path.to_str().unwrap());
+    assert!(t.env.contains_key("CARGO_MANIFEST_DIR"));
+    let mut cmd = ProcessBuilder::new(&t.program);
+    cmd.args(&t.args).cwd(t.cwd.as_ref().unwrap());
+    for (var, value) in &t.env {
+        cmd.env(var, value);
+    }
+    let output = t!(cmd.exec_with_output());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("test reads_env ... ok"));
+}
+
+#[cargo_test]
+fn records_runner_and_args() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [target.'cfg(all())']
+                runner = "my-runner --flag"
+            "#,
+        )
+        .build();
+
+    p.cargo("test -Zunstable-options --no-run --lib --test-manifest tests.json -- --exact")
+        .masquerade_as_nightly_cargo(&["test-manifest"])
+        .run();
+
+    let manifest: TestManifest = t!(serde_json::from_str(&p.read_file("tests.json")));
+    let lib = &manifest.executables[0];
+    assert_eq!(lib.program, "my-runner");
+    assert_eq!(lib.args, ["--flag", lib.path.to_str().unwrap(), "--exact"]);
+}
@@ -0,0 +1,101 @@
+//! Tests for `cargo test --partition`.
+
+use cargo_test_support::{basic_lib_manifest, project, str, Project};
+
+fn sharded_project() -> Project {
+    project()
+        .file("Cargo.toml", &basic_lib_manifest("foo"))
+        .file(
+            "src/lib.rs",
+            r#"
+                /// ```
+                /// foo::f();
+                /// ```
+                pub fn f() {}
+            "#,
+        )
+        .file("tests/a.rs", "")
+        .file("tests/b.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = sharded_project();
+
+    p.cargo("test --partition 1/2")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--partition` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_partition() {
+    let p = sharded_project();
+
+    for partition in ["0/2", "3/2", "1", "a/b"] {
+        p.cargo(&format!("test -Zunstable-options --partition {partition}"))
+            .masquerade_as_nightly_cargo(&["partition"])
+            .with_status(101)
+            .with_stderr_data(format!(
+                "[ERROR] invalid value `{partition}` for `--partition`, \
+                 expected `M/N` where M is between 1 and N\n"
+            ))
+            .run();
+    }
+}
+
+#[cargo_test]
+fn shards_run_disjoint_executables() {
+    let p = sharded_project();
+
+    // The executables run in the order lib, a, b, and then the doctests.
+    p.cargo("test -Zunstable-options --partition 1/2")
+        .masquerade_as_nightly_cargo(&["partition"])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.5.0 ([ROOT]/foo)
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] unittests src/lib.rs (target/debug/deps/foo-[HASH][EXE])
+[RUNNING] tests/b.rs (target/debug/deps/b-[HASH][EXE])
+
+"#]])
+        .run();
+
+    p.cargo("test -Zunstable-options --partition 2/2")
+        .masquerade_as_nightly_cargo(&["partition"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] tests/a.rs (target/debug/deps/a-[HASH][EXE])
+[DOCTEST] foo
+
+"#]])
+        .run();
+
+    // A shard may be left with nothing to run.
+    p.cargo("test -Zunstable-options --partition 5/5")
+        .masquerade_as_nightly_cargo(&["partition"])
+        .with_stderr_data(str![[r#"
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn shards_with_test_filter() {
+    let p = sharded_project();
+
+    p.cargo("test -Zunstable-options --partition 2/3 --tests")
+        .masquerade_as_nightly_cargo(&["partition"])
+        .with_stderr_data(str![[r#"
+[COMPILING] foo v0.5.0 ([ROOT]/foo)
+[FINISHED] `test` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+[RUNNING] tests/a.rs (target/debug/deps/a-[HASH][EXE])
+
+"#]])
+        .run();
+}
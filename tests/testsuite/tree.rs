@@ -2,6 +2,7 @@
 
 use super::features2::switch_to_resolver_2;
 use cargo_test_support::cross_compile::{self, alternate};
+use cargo_test_support::prelude::*;
 use cargo_test_support::registry::{Dependency, Package};
 use cargo_test_support::str;
 use cargo_test_support::{basic_manifest, git, project, rustc_host, Project};
@@ -752,6 +753,106 @@ c v1.0.0
         .run();
 }
 
+#[cargo_test]
+fn why() {
+    let p = make_simple_proj();
+
+    p.cargo("tree --why c")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[ERROR] the `--why` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .with_status(101)
+        .run();
+
+    // `c` is both a direct dependency and reachable through `a -> b`, only
+    // the direct edge is shown.
+    p.cargo("tree --why c -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+c v1.0.0
+└── foo v0.1.0 ([ROOT]/foo)
+
+"#]])
+        .run();
+
+    // All shortest paths of the same length are shown.
+    p.cargo("tree --why b -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+b v1.0.0
+├── a v1.0.0
+│   └── foo v0.1.0 ([ROOT]/foo)
+├── bdep v1.0.0
+│   [build-dependencies]
+│   └── foo v0.1.0 ([ROOT]/foo)
+└── devdep v1.0.0
+    [dev-dependencies]
+    └── foo v0.1.0 ([ROOT]/foo)
+
+"#]])
+        .run();
+
+    p.cargo("tree --why b -e normal -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+b v1.0.0
+└── a v1.0.0
+    └── foo v0.1.0 ([ROOT]/foo)
+
+"#]])
+        .run();
+
+    p.cargo("tree --why c -i c")
+        .with_stderr_data(str![[r#"
+[ERROR] the argument '--why <SPEC>' cannot be used with '--invert [<SPEC>]'
+
+Usage: cargo[EXE] tree --why <SPEC>
+
+For more information, try '--help'.
+
+"#]])
+        .with_status(1)
+        .run();
+}
+
+#[cargo_test]
+fn why_features() {
+    Package::new("optdep", "1.0.0")
+        .feature("std", &[])
+        .publish();
+    Package::new("mid", "1.0.0")
+        .add_dep(Dependency::new("optdep", "1.0").optional(true))
+        .feature("std", &["optdep/std"])
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            mid = { version = "1.0", features = ["std"] }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("tree --why optdep -e features -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+optdep v1.0.0
+└── optdep feature "std"
+    └── mid feature "std"
+        └── foo v0.1.0 ([ROOT]/foo)
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn invert_with_build_dep() {
     // -i for a common dependency between normal and build deps.
@@ -1101,6 +1202,80 @@ dupe-dep v2.0.0
         .run();
 }
 
+#[cargo_test]
+fn duplicates_features() {
+    Package::new("common", "1.0.0")
+        .feature("default", &["std"])
+        .feature("std", &[])
+        .feature("alloc", &[])
+        .publish();
+    Package::new("other", "1.0.0").publish();
+    Package::new("nostd", "1.0.0")
+        .add_dep(Dependency::new("common", "1.0").default_features(false))
+        .dep("other", "1.0")
+        .publish();
+    Package::new("withstd", "1.0.0")
+        .dep("common", "1.0")
+        .dep("other", "1.0")
+        .publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            common = { version = "1.0", default-features = false, features = ["alloc"] }
+            nostd = "1.0"
+            withstd = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("tree --duplicates-features")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+[ERROR] the `--duplicates-features` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .with_status(101)
+        .run();
+
+    p.cargo("tree --duplicates-features -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+common v1.0.0 features: alloc, default, std
+├── foo v0.1.0 ([ROOT]/foo) requested: alloc
+├── nostd v1.0.0 requested: none
+└── withstd v1.0.0 requested: default
+
+"#]])
+        .run();
+
+    p.cargo("tree --duplicates-features -e normal --format {p} -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+common v1.0.0 features: alloc, default, std
+├── foo v0.1.0 ([ROOT]/foo) requested: alloc
+├── nostd v1.0.0 requested: none
+└── withstd v1.0.0 requested: default
+
+"#]])
+        .run();
+
+    p.cargo("tree --duplicates-features -p nostd -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[WARNING] no packages with differing feature requests found
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn charset() {
     let p = make_simple_proj();
@@ -1231,6 +1406,359 @@ foo v0.1.0 ([ROOT]/foo) [bar,default,dep,dep_that_is_awesome,foo,other-dep]
         .run();
 }
 
+#[cargo_test]
+fn format_rust_version_and_source_kind() {
+    Package::new("dep", "1.0.0").rust_version("1.60").publish();
+    let git = git::new("gitdep", |p| {
+        p.file("Cargo.toml", &basic_manifest("gitdep", "0.5.0"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+                    rust-version = "1.70"
+
+                    [dependencies]
+                    dep = "1.0"
+                    gitdep = {{ git = "{}" }}
+                    bar = {{ path = "bar" }}
+                "#,
+                git.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("tree -Zunstable-options --format")
+        .arg("{p} [{s}] {rv}")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+foo v0.1.0 ([ROOT]/foo) [path] 1.70
+├── bar v0.1.0 ([ROOT]/foo/bar) [path] 
+├── dep v1.0.0 [registry] 1.60
+└── gitdep v0.5.0 ([ROOTURL]/gitdep#[..]) [git] 
+
+"#]])
+        .run();
+
+    p.cargo("tree --format {rv}")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] tree format `{rv}` not valid
+
+Caused by:
+  the `{rv}` placeholder is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn json_output() {
+    Package::new("dep", "1.0.0")
+        .rust_version("1.60")
+        .feature("feat", &[])
+        .publish();
+    Package::new("unixdep", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+                license = "MIT"
+
+                [dependencies]
+                dep = { version = "1.0", features = ["feat"] }
+
+                [target.'cfg(unix)'.build-dependencies]
+                unixdep = "1.0"
+
+                [target.'cfg(all())'.dev-dependencies]
+                dep = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("tree -Zunstable-options --output-format json --target all")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "edges": [
+    {
+      "from": 0,
+      "kind": "normal",
+      "targets": [],
+      "to": 1
+    },
+    {
+      "from": 0,
+      "kind": "build",
+      "targets": [
+        "cfg(unix)"
+      ],
+      "to": 2
+    },
+    {
+      "from": 0,
+      "kind": "dev",
+      "targets": [
+        "cfg(all())"
+      ],
+      "to": 1
+    }
+  ],
+  "nodes": [
+    {
+      "features": [],
+      "id": "path+[ROOTURL]/foo#0.1.0",
+      "kind": "package",
+      "license": "MIT",
+      "name": "foo",
+      "proc_macro": false,
+      "rust_version": null,
+      "source": null,
+      "source_kind": "path",
+      "target": "host",
+      "version": "0.1.0"
+    },
+    {
+      "features": [
+        "feat"
+      ],
+      "id": "registry+https://github.com/rust-lang/crates.io-index#dep@1.0.0",
+      "kind": "package",
+      "license": null,
+      "name": "dep",
+      "proc_macro": false,
+      "rust_version": "1.60",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "source_kind": "registry",
+      "target": "host",
+      "version": "1.0.0"
+    },
+    {
+      "features": [],
+      "id": "registry+https://github.com/rust-lang/crates.io-index#unixdep@1.0.0",
+      "kind": "package",
+      "license": null,
+      "name": "unixdep",
+      "proc_macro": false,
+      "rust_version": null,
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "source_kind": "registry",
+      "target": "host",
+      "version": "1.0.0"
+    }
+  ],
+  "roots": [
+    0
+  ]
+}
+"#]]
+            .json(),
+        )
+        .run();
+
+    // Features are nodes of their own, between a package and its dependency.
+    p.cargo("tree -Zunstable-options --output-format json -e normal,features -i dep")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(
+            str![[r#"
+{
+  "edges": [
+    {
+      "from": 0,
+      "kind": "feature",
+      "targets": [],
+      "to": 1
+    },
+    {
+      "from": 0,
+      "kind": "feature",
+      "targets": [],
+      "to": 4
+    },
+    {
+      "from": 1,
+      "kind": "normal",
+      "targets": [],
+      "to": 2
+    },
+    {
+      "from": 2,
+      "kind": "feature",
+      "targets": [],
+      "to": 3
+    },
+    {
+      "from": 4,
+      "kind": "normal",
+      "targets": [],
+      "to": 2
+    }
+  ],
+  "nodes": [
+    {
+      "features": [
+        "feat"
+      ],
+      "id": "registry+https://github.com/rust-lang/crates.io-index#dep@1.0.0",
+      "kind": "package",
+      "license": null,
+      "name": "dep",
+      "proc_macro": false,
+      "rust_version": "1.60",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "source_kind": "registry",
+      "target": "host",
+      "version": "1.0.0"
+    },
+    {
+      "command_line": false,
+      "kind": "feature",
+      "name": "default",
+      "package": 0
+    },
+    {
+      "features": [],
+      "id": "path+[ROOTURL]/foo#0.1.0",
+      "kind": "package",
+      "license": "MIT",
+      "name": "foo",
+      "proc_macro": false,
+      "rust_version": null,
+      "source": null,
+      "source_kind": "path",
+      "target": "host",
+      "version": "0.1.0"
+    },
+    {
+      "command_line": true,
+      "kind": "feature",
+      "name": "default",
+      "package": 2
+    },
+    {
+      "command_line": false,
+      "kind": "feature",
+      "name": "feat",
+      "package": 0
+    }
+  ],
+  "roots": [
+    0
+  ]
+}
+"#]]
+            .json(),
+        )
+        .run();
+
+    p.cargo("tree --output-format json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--output-format json` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn graph_export() {
+    Package::new("dep", "1.0.0").publish();
+    Package::new("dep", "2.0.0").publish();
+    Package::new("other", "1.0.0").dep("dep", "2.0").publish();
+    Package::new("unixdep", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                dep = "1.0"
+
+                [build-dependencies]
+                other = "1.0"
+
+                [target.'cfg(all())'.dev-dependencies]
+                unixdep = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("tree -Zunstable-options --graph dot")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r##"
+digraph {
+    n0 [label="foo v0.1.0 ([ROOT]/foo)", shape=box];
+    n1 [label="dep v1.0.0", shape=box, class="duplicate", style=filled, fillcolor="#ffd7d7"];
+    n2 [label="other v1.0.0", shape=box];
+    n3 [label="dep v2.0.0", shape=box, class="duplicate", style=filled, fillcolor="#ffd7d7"];
+    n4 [label="unixdep v1.0.0", shape=box];
+    n0 -> n1;
+    n0 -> n2 [class="build", style=dashed];
+    n0 -> n4 [class="dev", style=dashed, label="cfg(all())"];
+    n2 -> n3;
+}
+
+"##]])
+        .run();
+
+    p.cargo("tree -Zunstable-options --graph mermaid -e normal,dev")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+flowchart TD
+    n0["foo v0.1.0 ([ROOT]/foo)"]
+    n1["dep v1.0.0"]
+    n2["unixdep v1.0.0"]
+    n0 --> n1
+    n0 -.->|"cfg(all())"| n2
+
+"#]])
+        .run();
+
+    p.cargo("tree -Zunstable-options --graph mermaid -i dep@2.0.0")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stdout_data(str![[r#"
+flowchart TD
+    n0["dep v2.0.0"]
+    n1["other v1.0.0"]
+    n2["foo v0.1.0 ([ROOT]/foo)"]
+    n0 --> n1
+    n1 -.-> n2
+
+"#]])
+        .run();
+
+    p.cargo("tree --graph dot")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--graph` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
+
 #[cargo_test]
 fn dev_dep_feature() {
     // New feature resolver with optional dep
@@ -1625,9 +2153,9 @@ fn ambiguous_name() {
 [LOCKING] 4 packages to latest compatible versions
 [ADDING] dep v1.0.0 (latest: v2.0.0)
 [DOWNLOADING] crates ...
-[DOWNLOADED] dep v2.0.0 (registry `dummy-registry`)
 [DOWNLOADED] dep v1.0.0 (registry `dummy-registry`)
 [DOWNLOADED] bar v1.0.0 (registry `dummy-registry`)
+[DOWNLOADED] dep v2.0.0 (registry `dummy-registry`)
 [ERROR] There are multiple `dep` packages in your project, and the specification `dep` is ambiguous.
 Please re-run this command with one of the following specifications:
   dep@1.0.0
@@ -2616,3 +2616,162 @@ fn update_breaking_mixed_pinning_renaming() {
             "#]],
     );
 }
+
+#[cargo_test]
+fn precise_requirement() {
+    Package::new("bar", "1.0.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+    Package::new("bar", "1.2.0").publish();
+    Package::new("bar", "1.3.0").publish();
+    Package::new("bar", "1.3.1").yanked(true).publish();
+    Package::new("bar", "1.4.0").publish();
+
+    p.cargo("update bar --precise >=1.2,<1.4")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid version format for precise version `>=1.2,<1.4`
+
+Caused by:
+  unexpected character '>' while parsing major version number
+
+"#]])
+        .run();
+
+    // The newest version matching, leaving out yanked ones.
+    p.cargo("update bar --precise >=1.2,<1.4 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise requirement"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[UPDATING] bar v1.0.0 -> v1.3.0
+
+"#]])
+        .run();
+
+    // What the dependents require is taken into account.
+    p.cargo("update bar --precise <2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise requirement"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[UPDATING] bar v1.3.0 -> v1.4.0
+
+"#]])
+        .run();
+
+    p.cargo("update bar --precise >=2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise requirement"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] no version of `bar` matches `>=2` and what its dependents require
+
+"#]])
+        .run();
+
+    p.cargo("update bar --precise 1.x.y -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise requirement"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] invalid version or version requirement for precise version `1.x.y`
+
+Caused by:
+  unexpected character after wildcard in version req
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn precise_multiple_packages() {
+    Package::new("bar", "1.0.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "1.0"
+                baz = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("generate-lockfile").run();
+    Package::new("bar", "1.1.0").publish();
+    Package::new("bar", "1.2.0").publish();
+    Package::new("baz", "0.1.1").publish();
+    Package::new("baz", "0.1.2").publish();
+
+    p.cargo("update -p bar --precise 1.1.0 -p baz --precise 0.1.1")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--precise <PRECISE>...` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    p.cargo("update -p bar --precise 1.1.0 -p baz -Zunstable-options --precise 0.1.1 --precise 0.1.2")
+        .masquerade_as_nightly_cargo(&["precise multiple"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `--precise` was passed 3 times to update 2 packages, pass it either once for all of them or once for each
+
+"#]])
+        .run();
+
+    // Nothing is updated if any of the packages can't be.
+    let lock = p.read_lockfile();
+    p.cargo("update -p bar --precise 1.1.0 -p baz --precise 0.1.9 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise multiple"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] no matching package named `baz` found
+location searched: registry `crates-io`
+required by package `foo v0.1.0 ([ROOT]/foo)`
+
+"#]])
+        .run();
+    assert_eq!(p.read_lockfile(), lock);
+
+    p.cargo("update -p bar --precise 1.1.0 -p baz --precise <0.1.2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise multiple"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[UPDATING] bar v1.0.0 -> v1.1.0
+[UPDATING] baz v0.1.0 -> v0.1.1
+
+"#]])
+        .run();
+
+    // A single `--precise` applies to every package.
+    p.cargo("update -p bar -p baz --precise >=0.1.2 -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["precise multiple"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[UPDATING] bar v1.1.0 -> v1.2.0
+[UPDATING] baz v0.1.1 -> v0.1.2
+
+"#]])
+        .run();
+}
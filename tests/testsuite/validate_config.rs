@@ -0,0 +1,148 @@
+//! Tests for `-Zvalidate-config`.
+
+use cargo_test_support::{paths, project, str};
+
+use super::config::write_config_at;
+
+#[cargo_test]
+fn unknown_keys() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+[build]
+jobs = 1
+jbos = 2
+
+[registries.my-registry]
+idnex = "sparse+https://example.com/index/"
+
+[buidl]
+rustflags = []
+
+[net]
+ssh.known-host = []
+"#,
+        )
+        .build();
+
+    p.cargo("check -Zvalidate-config")
+        .masquerade_as_nightly_cargo(&["validate-config"])
+        .with_stderr_data(str![[r#"
+[WARNING] unused config key `build.jbos` in `[ROOT]/foo/.cargo/config.toml:4:1`
+
+	Did you mean `jobs`?
+[WARNING] unused config key `registries.my-registry.idnex` in `[ROOT]/foo/.cargo/config.toml:7:1`
+
+	Did you mean `index`?
+[WARNING] unused config key `buidl` in `[ROOT]/foo/.cargo/config.toml:9:2`
+
+	Did you mean `build`?
+[WARNING] unused config key `net.ssh.known-host` in `[ROOT]/foo/.cargo/config.toml:13:5`
+
+	Did you mean `known-hosts`?
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn gated() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+[build]
+jbos = 2
+
+[buidl]
+rustflags = []
+"#,
+        )
+        .build();
+
+    // Only the keys of the tables Cargo deserializes are reported.
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] unused config key `build.jbos` in `[ROOT]/foo/.cargo/config.toml`
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn user_chosen_keys() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            ".cargo/config.toml",
+            r#"
+[alias]
+b-all = "build --workspace"
+
+[env]
+SOME_VAR = { value = "1", force = true }
+
+[target.x86_64-unknown-linux-gnu.foo]
+rustc-link-lib = ["foo"]
+
+[profile.dev.package.foo]
+opt-level = 1
+
+[profile.dev.package.bar]
+opt-levle = 1
+"#,
+        )
+        .build();
+
+    p.cargo("check -Zvalidate-config")
+        .masquerade_as_nightly_cargo(&["validate-config"])
+        .with_stderr_data(str![[r#"
+[WARNING] unused config key `profile.dev.package.bar.opt-levle` in `[ROOT]/foo/.cargo/config.toml:15:1`
+
+	Did you mean `opt-level`?
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn every_loaded_file() {
+    write_config_at(
+        paths::home().join(".cargo/config.toml"),
+        "
+        [unstable]
+        validate-config = true
+
+        [term]
+        colour = 'never'
+        ",
+    );
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("extra.toml", "[nett]\noffline = false\n")
+        .build();
+
+    p.cargo("check --config extra.toml")
+        .masquerade_as_nightly_cargo(&["validate-config"])
+        .with_stderr_data(str![[r#"
+[WARNING] unused config key `nett` in `[ROOT]/foo/extra.toml:1:2`
+
+	Did you mean `net`?
+[WARNING] unused config key `term.colour` in `[ROOT]/home/.cargo/config.toml:6:9`
+
+	Did you mean `color`?
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
@@ -1323,3 +1323,286 @@ fn vendor_crate_with_ws_inherit() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn summary_requires_unstable_options() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("vendor --respect-source-config --versioned-dirs --summary summary.json")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--summary` flag is unstable, and only available on the nightly channel of Cargo, but this is the `stable` channel
+See https://doc.rust-lang.org/book/appendix-07-nightly-rust.html for more information about Rust release channels.
+
+"#]])
+        .run();
+
+    p.cargo("vendor --respect-source-config -Zunstable-options --summary summary.json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--summary` flag requires `--versioned-dirs`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn versioned_dirs_summary() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+                baz = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    Package::new("bar", "0.1.0").publish();
+    Package::new("bar", "0.2.0").publish();
+    Package::new("baz", "0.1.0").publish();
+    Package::new("qux", "0.1.0").publish();
+
+    p.cargo("vendor --respect-source-config --versioned-dirs")
+        .run();
+
+    // Crates vendored before are not reported as added.
+    p.cargo(
+        "vendor --respect-source-config -Zunstable-options --versioned-dirs --summary summary.json",
+    )
+    .masquerade_as_nightly_cargo(&["unstable-options"])
+    .with_stderr_data(str![[r#"
+To use vendored sources, add this to your .cargo/config.toml for this project:
+
+
+"#]])
+    .run();
+    assert_e2e().eq(
+        p.read_file("summary.json"),
+        str![[r#"
+{
+  "added": {},
+  "removed": [],
+  "unchanged": [
+    "bar-0.1.0",
+    "baz-0.1.0"
+  ],
+  "updated": {},
+  "version": 1
+}
+"#]]
+        .json(),
+    );
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "0.2.0"
+            qux = "0.1.0"
+        "#,
+    );
+    p.cargo(
+        "vendor --respect-source-config -Zunstable-options --versioned-dirs --summary summary.json",
+    )
+    .masquerade_as_nightly_cargo(&["unstable-options"])
+    .with_stderr_data(
+        str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[UPDATING] bar v0.1.0 -> v0.2.0
+[ADDING] qux v0.1.0
+[DOWNLOADING] crates ...
+[DOWNLOADED] qux v0.1.0 (registry `dummy-registry`)
+[DOWNLOADED] bar v0.2.0 (registry `dummy-registry`)
+   Vendoring bar v0.2.0 ([ROOT]/home/.cargo/registry/src/-[HASH]/bar-0.2.0) to [ROOT]/foo/vendor/bar-0.2.0
+   Vendoring qux v0.1.0 ([ROOT]/home/.cargo/registry/src/-[HASH]/qux-0.1.0) to [ROOT]/foo/vendor/qux-0.1.0
+To use vendored sources, add this to your .cargo/config.toml for this project:
+
+
+"#]]
+        .unordered(),
+    )
+    .run();
+    assert!(!p.root().join("vendor/baz-0.1.0").exists());
+    assert_e2e().eq(
+        p.read_file("summary.json"),
+        str![[r#"
+{
+  "added": {
+    "bar-0.2.0": {
+      "name": "bar",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "version": "0.2.0"
+    },
+    "qux-0.1.0": {
+      "name": "qux",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "version": "0.1.0"
+    }
+  },
+  "removed": [
+    "bar-0.1.0",
+    "baz-0.1.0"
+  ],
+  "unchanged": [],
+  "updated": {},
+  "version": 1
+}
+"#]]
+        .json(),
+    );
+}
+
+#[cargo_test]
+fn versioned_dirs_patches_overlay() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "0.1.0"
+            "#,
+        )
+        .file("src/lib.rs", "pub fn foo() { bar::patched(); }")
+        .file("vendor/patches/bar-0.1.0/src/lib.rs", "pub fn patched() {}")
+        .build();
+
+    Package::new("bar", "0.1.0")
+        .file("src/lib.rs", "pub fn original() {}")
+        .publish();
+
+    // The overlay is unstable, but is kept in place.
+    p.cargo("vendor --respect-source-config --versioned-dirs")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `[ROOT]/foo/vendor/patches`, pass `-Zunstable-options` to apply it
+...
+"#]])
+        .run();
+    assert_eq!(
+        p.read_file("vendor/bar-0.1.0/src/lib.rs"),
+        "pub fn original() {}"
+    );
+    assert!(p
+        .root()
+        .join("vendor/patches/bar-0.1.0/src/lib.rs")
+        .exists());
+
+    p.cargo(
+        "vendor --respect-source-config -Zunstable-options --versioned-dirs --summary summary.json",
+    )
+    .masquerade_as_nightly_cargo(&["unstable-options"])
+    .run();
+    assert_eq!(
+        p.read_file("vendor/bar-0.1.0/src/lib.rs"),
+        "pub fn patched() {}"
+    );
+    assert!(p
+        .read_file("summary.json")
+        .contains("\"updated\": {\n    \"bar-0.1.0\""));
+
+    // Editing the overlay re-vendors the crate with the new contents.
+    p.change_file(
+        "vendor/patches/bar-0.1.0/src/lib.rs",
+        "pub fn patched() {} pub fn again() {}",
+    );
+    p.cargo("vendor --respect-source-config -Zunstable-options --versioned-dirs")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_stderr_data(str![[r#"
+   Vendoring bar v0.1.0 ([ROOT]/home/.cargo/registry/src/-[HASH]/bar-0.1.0) to [ROOT]/foo/vendor/bar-0.1.0
+To use vendored sources, add this to your .cargo/config.toml for this project:
+
+
+"#]])
+        .run();
+    assert_eq!(
+        p.read_file("vendor/bar-0.1.0/src/lib.rs"),
+        "pub fn patched() {} pub fn again() {}"
+    );
+
+    add_vendor_config(&p);
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn git_provenance() {
+    let (git_project, git_repo) = git::new_repo("git", |p| {
+        p.file("crates/a/Cargo.toml", &basic_lib_manifest("a"))
+            .file("crates/a/src/lib.rs", "")
+    });
+    let rev_1 = git_repo.head().unwrap().target().unwrap().to_string();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    a = {{ git = '{}' }}
+                "#,
+                git_project.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let output = p
+        .cargo("vendor --respect-source-config")
+        .exec_with_output()
+        .unwrap();
+    let output = String::from_utf8(output.stdout).unwrap();
+    p.change_file(".cargo/config.toml", &output);
+
+    let provenance = p
+        .read_file("vendor/a/.cargo-git-source.json")
+        .replace(&rev_1, "[REV1]");
+    assert_e2e().eq(
+        provenance,
+        str![[r#"
+{
+  "git": "[ROOTURL]/git",
+  "path_in_vcs": "crates/a",
+  "rev": "[REV1]"
+}
+"#]]
+        .json(),
+    );
+    assert!(p
+        .read_file("vendor/a/.cargo-checksum.json")
+        .contains(".cargo-git-source.json"));
+    p.cargo("check").run();
+
+    // Locking another commit than the vendored one is caught.
+    git_project.change_file("crates/a/src/lib.rs", "pub fn a() {}");
+    git::add(&git_repo);
+    let rev_2 = git::commit(&git_repo).to_string();
+    p.change_file("Cargo.lock", &p.read_lockfile().replace(&rev_1, &rev_2));
+    p.cargo("clean").run();
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `a v0.5.0` was vendored from commit [..] of `[ROOTURL]/git`, but is locked to commit [..]
+
+"#]])
+        .run();
+}
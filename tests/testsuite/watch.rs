@@ -0,0 +1,190 @@
+//! Tests for `--watch`.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStderr, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use cargo_test_support::{project, str, Project};
+
+/// Spawns `cargo <cmd> -Zunstable-options --watch` in `p`.
+fn spawn(p: &Project, cmd: &str) -> (Child, BufReader<ChildStderr>) {
+    let mut cargo = p
+        .cargo(&format!("{cmd} -Zunstable-options --watch"))
+        .masquerade_as_nightly_cargo(&["watch"])
+        .build_command();
+    let mut child = cargo
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stderr = BufReader::new(child.stderr.take().unwrap());
+    (child, stderr)
+}
+
+/// Reads the output of cargo until a line starting with `status`, and returns
+/// the lines read.
+fn read_until(stderr: &mut BufReader<ChildStderr>, status: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        assert_ne!(
+            stderr.read_line(&mut line).unwrap(),
+            0,
+            "cargo exited before printing `{status}`, got:\n{}",
+            lines.join("")
+        );
+        let found = line.trim_start().starts_with(status);
+        lines.push(line);
+        if found {
+            return lines;
+        }
+    }
+}
+
+/// Changes a file so that its mtime is newer even on filesystems with a coarse
+/// resolution.
+fn change_file(p: &Project, path: &str, contents: &str) {
+    thread::sleep(Duration::from_secs(1));
+    p.change_file(path, contents);
+}
+
+#[cargo_test]
+fn reruns_on_change() {
+    let p = project().file("src/lib.rs", "").build();
+    let (mut child, mut stderr) = spawn(&p, "check");
+
+    read_until(&mut stderr, "Watching");
+    change_file(&p, "src/lib.rs", "pub fn f() -> u32 { \"\" }");
+    let lines = read_until(&mut stderr, "Watching");
+    assert!(
+        lines[0].contains("Rerunning src/lib.rs changed"),
+        "{lines:?}"
+    );
+    assert!(
+        lines.iter().any(|l| l.contains("error[E0308]")),
+        "{lines:?}"
+    );
+
+    // The build failed, but fixing it reruns it.
+    change_file(&p, "src/lib.rs", "pub fn f() -> u32 { 0 }");
+    let lines = read_until(&mut stderr, "Watching");
+    assert!(
+        lines[0].contains("Rerunning src/lib.rs changed"),
+        "{lines:?}"
+    );
+    assert!(lines.iter().any(|l| l.contains("Finished")), "{lines:?}");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cargo_test]
+fn only_watches_inputs() {
+    let p = project()
+        .file("src/lib.rs", "mod m;")
+        .file("src/m.rs", "")
+        .file("notes.txt", "")
+        .build();
+    let (mut child, mut stderr) = spawn(&p, "build");
+
+    let lines = read_until(&mut stderr, "Watching");
+    assert!(
+        lines
+            .last()
+            .unwrap()
+            .contains("Watching 3 files for changes"),
+        "{lines:?}"
+    );
+    // `notes.txt` isn't part of the dep-info of the library.
+    change_file(&p, "notes.txt", "changed");
+    change_file(&p, "src/m.rs", "pub fn f() {}");
+    let lines = read_until(&mut stderr, "Watching");
+    assert!(lines[0].contains("Rerunning src/m.rs changed"), "{lines:?}");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cargo_test]
+fn watches_rerun_if_changed() {
+    let p = project()
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "build.rs",
+            r#"fn main() { println!("cargo::rerun-if-changed=data.txt"); }"#,
+        )
+        .file("data.txt", "")
+        .build();
+    let (mut child, mut stderr) = spawn(&p, "run");
+
+    read_until(&mut stderr, "Watching");
+    change_file(&p, "data.txt", "changed");
+    let lines = read_until(&mut stderr, "Watching");
+    assert!(lines[0].contains("Rerunning data.txt changed"), "{lines:?}");
+    assert!(lines.iter().any(|l| l.contains("Running")), "{lines:?}");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cargo_test]
+fn manifest_changes_are_picked_up() {
+    let p = project().file("src/lib.rs", "").build();
+    let (mut child, mut stderr) = spawn(&p, "test");
+
+    read_until(&mut stderr, "Watching");
+    change_file(
+        &p,
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.2.0"
+            edition = "2015"
+        "#,
+    );
+    let lines = read_until(&mut stderr, "Watching");
+    assert!(
+        lines[0].contains("Rerunning Cargo.toml changed"),
+        "{lines:?}"
+    );
+    assert!(
+        lines.iter().any(|l| l.contains("Compiling foo v0.2.0")),
+        "{lines:?}"
+    );
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cargo_test]
+fn fails_without_anything_to_watch() {
+    let p = project()
+        .file("Cargo.toml", "[package]\nversion = \"0.1.0\"")
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zunstable-options --watch")
+        .masquerade_as_nightly_cargo(&["watch"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] missing field `name`
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn watch_is_unstable() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo("build --watch")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--watch` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+}
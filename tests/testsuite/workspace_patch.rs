@@ -0,0 +1,203 @@
+//! Tests for `patch.workspace = true` and `replace.workspace = true`.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{basic_manifest, project, str, Project};
+
+fn workspace_with_member_patch(member_patch: &str) -> Project {
+    Package::new("bar", "0.1.0").publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo"]
+
+                [patch.crates-io]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+                    edition = "2015"
+
+                    [dependencies]
+                    bar = "0.1.0"
+
+                    {member_patch}
+                "#
+            ),
+        )
+        .file("foo/src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn requires_unstable_flag() {
+    let p = workspace_with_member_patch("[patch]\nworkspace = true");
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] [ROOT]/foo/foo/Cargo.toml: ignoring `patch.workspace`, pass `-Zworkspace-patch` to enable
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] bar v0.1.0 ([ROOT]/foo/bar)
+[CHECKING] foo v0.1.0 ([ROOT]/foo/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn member_inherits_root_patch() {
+    let p = workspace_with_member_patch("[patch]\nworkspace = true");
+
+    p.cargo("check -Zworkspace-patch")
+        .cwd("foo")
+        .masquerade_as_nightly_cargo(&["workspace-patch"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[LOCKING] 2 packages to latest compatible versions
+[CHECKING] bar v0.1.0 ([ROOT]/foo/bar)
+[CHECKING] foo v0.1.0 ([ROOT]/foo/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn member_patch_with_entries() {
+    let p = workspace_with_member_patch(
+        r#"
+            [patch]
+            workspace = true
+
+            [patch.crates-io]
+            bar = { path = "../bar" }
+        "#,
+    );
+
+    p.cargo("check -Zworkspace-patch")
+        .masquerade_as_nightly_cargo(&["workspace-patch"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `workspace = true` must be the only key of the table, remove the other entries as the ones of the workspace root apply
+  --> foo/Cargo.toml:11:13
+   |
+11 |               [patch]
+   |  _____________^
+12 | |             workspace = true
+   | |____________________________^
+   |
+[ERROR] failed to load manifest for workspace member `[ROOT]/foo/foo`
+referenced by workspace at `[ROOT]/foo/Cargo.toml`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn member_replace_with_entries() {
+    let p = workspace_with_member_patch(
+        r#"
+            [replace]
+            workspace = true
+            "bar:0.1.0" = { path = "../bar" }
+        "#,
+    );
+
+    p.cargo("check -Zworkspace-patch")
+        .masquerade_as_nightly_cargo(&["workspace-patch"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `workspace = true` must be the only key of the table, remove the other entries as the ones of the workspace root apply
+  --> foo/Cargo.toml:11:13
+   |
+11 |               [replace]
+   |  _____________^
+12 | |             workspace = true
+13 | |             "bar:0.1.0" = { path = "../bar" }
+   | |_____________________________________________^
+   |
+[ERROR] failed to load manifest for workspace member `[ROOT]/foo/foo`
+referenced by workspace at `[ROOT]/foo/Cargo.toml`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn workspace_table_is_a_patch_entry() {
+    Package::new("bar", "0.1.0").publish();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1.0"
+
+                [patch.workspace]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zworkspace-patch")
+        .masquerade_as_nightly_cargo(&["workspace-patch"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  [patch] entry `workspace` should be a URL or registry name
+
+Caused by:
+  invalid url `workspace`: relative URL without a base
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn not_allowed_in_root() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [patch]
+                workspace = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check -Zworkspace-patch")
+        .masquerade_as_nightly_cargo(&["workspace-patch"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `patch.workspace = true` can only be used in workspace members, but `[ROOT]/foo/Cargo.toml` is the workspace root
+
+"#]])
+        .run();
+}
@@ -1801,6 +1801,125 @@ fn excluded_default_members_not_crate_glob() {
     assert!(p.bin("foo").is_file());
 }
 
+#[cargo_test]
+fn default_members_exclusion_pattern() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["crates/*"]
+                default-members = ["crates/*", "!crates/experimental-*"]
+            "#,
+        )
+        .file("crates/bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("crates/bar/src/main.rs", "fn main() {}")
+        .file(
+            "crates/experimental-baz/Cargo.toml",
+            &basic_manifest("experimental-baz", "0.1.0"),
+        )
+        .file("crates/experimental-baz/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build").run();
+    assert!(p.bin("bar").is_file());
+    assert!(!p.bin("experimental-baz").is_file());
+
+    p.cargo("build --workspace").run();
+    assert!(p.bin("experimental-baz").is_file());
+}
+
+#[cargo_test]
+fn default_members_only_exclusion_patterns() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2015"
+
+                [workspace]
+                members = ["bar", "baz"]
+                default-members = ["!baz"]
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/main.rs", "fn main() {}")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build").run();
+    assert!(p.bin("foo").is_file());
+    assert!(p.bin("bar").is_file());
+    assert!(!p.bin("baz").is_file());
+}
+
+#[cargo_test]
+fn exclusion_pattern_in_members() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["crates/*", "!crates/baz"]
+            "#,
+        )
+        .file("crates/bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("crates/bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] `!crates/baz` in `workspace.members` is read as a path, exclusion patterns are only supported in `workspace.default-members`
+
+[HELP] use `workspace.exclude` to leave packages out of the workspace
+[ERROR] failed to load manifest for workspace member `[ROOT]/foo/!crates/baz`
+referenced by workspace at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  failed to read `[ROOT]/foo/!crates/baz/Cargo.toml`
+
+Caused by:
+  [NOT_FOUND]
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn default_members_directory_starting_with_bang() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["!bar", "baz"]
+                default-members = ["!bar"]
+            "#,
+        )
+        .file("!bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("!bar/src/main.rs", "fn main() {}")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("baz/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .with_stderr_data(str![[r#"
+[LOCKING] 2 packages to latest compatible versions
+[COMPILING] bar v0.1.0 ([ROOT]/foo/!bar)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+    assert!(p.bin("bar").is_file());
+    assert!(!p.bin("baz").is_file());
+}
+
 #[cargo_test]
 fn glob_syntax() {
     let p = project()
@@ -3,6 +3,7 @@
 use std::fs;
 
 use cargo_test_support::paths::CargoPathExt;
+use cargo_test_support::prelude::*;
 use cargo_test_support::project;
 use cargo_test_support::registry;
 use cargo_test_support::str;
@@ -212,3 +213,49 @@ fn inline_and_explicit_version() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn message_format_json() {
+    let registry = registry::init();
+    setup("foo", "0.0.1");
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("yank --version 0.0.1 --message-format json")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] the `--message-format=json` flag is unstable, pass `-Z unstable-options` to enable it
+
+"#]])
+        .run();
+
+    p.cargo("yank --version 0.0.1 --message-format json -Zunstable-options")
+        .masquerade_as_nightly_cargo(&["unstable-options"])
+        .replace_crates_io(registry.index_url())
+        .with_stdout_data(
+            str![[r#"
+{
+  "crate": "foo",
+  "version": "0.0.1",
+  "yanked": true
+}
+"#]]
+            .json(),
+        )
+        .run();
+}
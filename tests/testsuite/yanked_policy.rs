@@ -0,0 +1,159 @@
+//! Tests for the `resolver.yanked` config.
+
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str, Project};
+
+/// A package whose `Cargo.lock` pins `bar 0.1.0`, which is yanked.
+fn yanked_lock_project(policy: &str) -> Project {
+    let cksum = Package::new("bar", "0.1.0").yanked(true).publish();
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2015"
+
+                [dependencies]
+                bar = "0.1"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.lock",
+            &format!(
+                r#"
+                    # This file is automatically @generated by Cargo.
+                    # It is not intended for manual editing.
+                    version = 3
+
+                    [[package]]
+                    name = "bar"
+                    version = "0.1.0"
+                    source = "registry+https://github.com/rust-lang/crates.io-index"
+                    checksum = "{cksum}"
+
+                    [[package]]
+                    name = "foo"
+                    version = "0.0.1"
+                    dependencies = [
+                     "bar",
+                    ]
+                "#
+            ),
+        )
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [resolver]
+                    yanked = "{policy}"
+                "#
+            ),
+        )
+        .build()
+}
+
+#[cargo_test]
+fn gated() {
+    let p = yanked_lock_project("deny");
+
+    p.cargo("check")
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `resolver.yanked` config without `-Zyanked-policy`
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.0 (registry `dummy-registry`)
+[CHECKING] bar v0.1.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn allow() {
+    let p = yanked_lock_project("allow");
+
+    p.cargo("check -Zyanked-policy")
+        .masquerade_as_nightly_cargo(&["yanked-policy"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.0 (registry `dummy-registry`)
+[CHECKING] bar v0.1.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn warn() {
+    let p = yanked_lock_project("warn");
+
+    p.cargo("check -Zyanked-policy")
+        .masquerade_as_nightly_cargo(&["yanked-policy"])
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[WARNING] package `bar v0.1.0` in Cargo.lock is yanked in registry `crates-io`, consider running `cargo update -p bar`
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v0.1.0 (registry `dummy-registry`)
+[CHECKING] bar v0.1.0
+[CHECKING] foo v0.0.1 ([ROOT]/foo)
+[FINISHED] `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn deny() {
+    let p = yanked_lock_project("deny");
+
+    p.cargo("check -Zyanked-policy")
+        .masquerade_as_nightly_cargo(&["yanked-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] Cargo.lock contains yanked packages, which is denied by `resolver.yanked`:
+  bar v0.1.0 (registry `crates-io`)
+
+[HELP] run `cargo update` to select versions that are not yanked
+
+"#]])
+        .run();
+
+    // Any command resolving the packages to build enforces the policy.
+    p.cargo("tree -Zyanked-policy -i foo")
+        .masquerade_as_nightly_cargo(&["yanked-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] Cargo.lock contains yanked packages, which is denied by `resolver.yanked`:
+  bar v0.1.0 (registry `crates-io`)
+
+[HELP] run `cargo update` to select versions that are not yanked
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn invalid_value() {
+    let p = yanked_lock_project("forbid");
+
+    p.cargo("check -Zyanked-policy")
+        .masquerade_as_nightly_cargo(&["yanked-policy"])
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] error in [ROOT]/foo/.cargo/config.toml: could not load config key `resolver.yanked`
+
+Caused by:
+  unknown variant `forbid`, expected one of `warn`, `deny`, `allow`
+
+"#]])
+        .run();
+}